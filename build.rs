@@ -1,66 +1,83 @@
 // PANDEMONIUM BUILD SCRIPT
 // COMPILES src/bpf/main.bpf.c INTO BPF BYTECODE AND GENERATES RUST SKELETON
-// vmlinux.h: CACHED AT /tmp/pandemonium-vmlinux.h. ON CACHE MISS, GENERATED
-// FROM RUNNING KERNEL'S BTF VIA bpftool (ONLY NEEDED ONCE PER KERNEL).
+// vmlinux.h: CACHED AT ~/.cache/pandemonium/vmlinux.h WITH A KERNEL STAMP
+// (src/btfcache.rs). ON A MISS OR A KERNEL CHANGE, GENERATED FROM THE
+// RUNNING KERNEL'S BTF VIA bpftool (ONLY NEEDED ONCE PER KERNEL).
+// PANDEMONIUM_VMLINUX_H=<PATH> USES THAT HEADER INSTEAD (CROSS-BUILDS), AND
+// --features stub-bpf SKIPS vmlinux.h AND THE BPF COMPILE ENTIRELY.
+// EVERY FLAVOUR EMBEDS `git describe` AS GIT_HASH FOR THE REPORT HEADER.
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use libbpf_cargo::SkeletonBuilder;
 
+#[path = "src/btfcache.rs"]
+mod btfcache;
+
 const BPF_SRC: &str = "src/bpf/main.bpf.c";
 
 fn main() {
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
 
-    // vmlinux.h: USE CACHE, GENERATE VIA bpftool ON MISS (ONLY NEEDED ONCE)
+    // BEFORE ANY EARLY RETURN: EVERY BUILD FLAVOUR STAMPS ITS REPORTS
+    emit_git_hash();
+
+    println!(
+        "cargo:rerun-if-env-changed={}",
+        btfcache::VMLINUX_OVERRIDE_ENV
+    );
+    let override_path = env::var(btfcache::VMLINUX_OVERRIDE_ENV).ok();
+    let source = btfcache::HeaderSource::select(
+        env::var_os("CARGO_FEATURE_STUB_BPF").is_some(),
+        override_path.as_deref(),
+    );
+    // A REBOOT INTO ANOTHER KERNEL REWRITES THE BTF FILE: RE-CHECK THE STAMP
+    if source.needs_host_btf() {
+        println!("cargo:rerun-if-changed={}", btfcache::BTF_PATH);
+    }
+
+    // vmlinux.h: OVERRIDE OR STAMPED CACHE (bpftool ON A MISS)
     // LAYOUT: $OUT_DIR/include/vmlinux/vmlinux.h
     // SCX HEADERS USE ../vmlinux.h RELATIVE TO include/scx/,
     // SO WE NEED include/vmlinux/vmlinux.h AT THE SAME LEVEL AS include/scx/
+    let (raw, origin) = match &source {
+        // NO SKELETON TO GENERATE: main.rs COMPILES scheduler_stub.rs INSTEAD
+        btfcache::HeaderSource::Stub => return,
+        // CROSS-BUILD: A HEADER DUMPED FROM THE TARGET'S BTF, USED AS-IS
+        btfcache::HeaderSource::Override(path) => {
+            println!("cargo:rerun-if-changed={path}");
+            let raw = std::fs::read_to_string(path)
+                .unwrap_or_else(|e| panic!("{}={}: {}", btfcache::VMLINUX_OVERRIDE_ENV, path, e));
+            (raw, format!("{}={}", btfcache::VMLINUX_OVERRIDE_ENV, path))
+        }
+        btfcache::HeaderSource::HostCache => {
+            let release = std::fs::read_to_string(btfcache::OSRELEASE_PATH).unwrap_or_default();
+            (cached_vmlinux_h(), format!("kernel {}", release.trim()))
+        }
+    };
     let vmlinux_dir = out_dir.join("include").join("vmlinux");
     std::fs::create_dir_all(&vmlinux_dir).expect("failed to create vmlinux dir");
-
     let vmlinux_h = vmlinux_dir.join("vmlinux.h");
-    let cache_dir = PathBuf::from(env::var("HOME").expect("HOME not set"))
-        .join(".cache")
-        .join("pandemonium");
-    std::fs::create_dir_all(&cache_dir).expect("failed to create cache dir");
-    let cache_path = cache_dir.join("vmlinux.h");
+    let patched = patch_vmlinux_c23(&raw);
+    std::fs::write(&vmlinux_h, patched.as_bytes()).expect("failed to write vmlinux.h");
 
-    if cache_path.exists()
-        && cache_path
-            .metadata()
-            .map(|m| m.len() > 1000)
-            .unwrap_or(false)
-    {
-        let raw = std::fs::read_to_string(&cache_path).expect("cached vmlinux.h is not utf-8");
-        let patched = patch_vmlinux_c23(&raw);
-        std::fs::write(&vmlinux_h, patched.as_bytes()).expect("failed to write vmlinux.h");
-    } else {
-        let output = Command::new("bpftool")
-            .args([
-                "btf",
-                "dump",
-                "file",
-                "/sys/kernel/btf/vmlinux",
-                "format",
-                "c",
-            ])
-            .output()
-            .expect(
-                "bpftool not found -- install once: pacman -S bpf (only needed for first build)",
-            );
-        if !output.status.success() {
-            panic!(
-                "bpftool failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
+    let skel_out = out_dir.join("bpf.skel.rs");
+    println!(
+        "cargo:rerun-if-env-changed={}",
+        btfcache::ALLOW_MISSING_SCX_ENV
+    );
+    let missing = btfcache::missing_scx_types(&raw);
+    if !missing.is_empty() {
+        let msg = btfcache::missing_scx_message(&origin, &missing);
+        if env::var(btfcache::ALLOW_MISSING_SCX_ENV).as_deref() != Ok("1") {
+            panic!("{msg}");
         }
-        std::fs::write(&cache_path, &output.stdout).expect("failed to cache vmlinux.h");
-        let raw = String::from_utf8(output.stdout).expect("vmlinux.h is not utf-8");
-        let patched = patch_vmlinux_c23(&raw);
-        std::fs::write(&vmlinux_h, patched.as_bytes()).expect("failed to write vmlinux.h");
+        // ESCAPE HATCH: EMPTY SKELETON. THE LIB BUILDS; THE BINARY WON'T
+        println!("cargo:warning={msg}");
+        std::fs::write(&skel_out, "").expect("failed to write empty skeleton");
+        return;
     }
 
     // SYMLINK: $OUT_DIR/include/vmlinux.h -> vmlinux/vmlinux.h
@@ -71,7 +88,6 @@ fn main() {
         .expect("failed to symlink vmlinux.h");
 
     let gen_include = out_dir.join("include");
-    let skel_out = out_dir.join("bpf.skel.rs");
 
     SkeletonBuilder::new()
         .source(BPF_SRC)
@@ -92,6 +108,76 @@ fn main() {
     println!("cargo:rerun-if-changed=include/scx");
 }
 
+// GIT_HASH FOR THE REPORT HEADER (src/sysinfo.rs): `git describe` OF THE
+// SOURCE TREE. NO .git (RELEASE TARBALL) OR NO git BINARY: NOTHING IS
+// EMITTED AND THE HEADER SAYS "unknown" -- NEVER A BUILD FAILURE.
+fn emit_git_hash() {
+    let head = Path::new(".git/HEAD");
+    let Ok(head_text) = std::fs::read_to_string(head) else {
+        return;
+    };
+    // CHECKOUTS MOVE HEAD; COMMITS MOVE THE BRANCH REF IT POINTS AT
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Some(branch) = head_text.trim().strip_prefix("ref: ") {
+        let ref_path = Path::new(".git").join(branch);
+        if ref_path.is_file() {
+            println!("cargo:rerun-if-changed={}", ref_path.display());
+        }
+    }
+    let describe = Command::new("git")
+        .args(["describe", "--tags", "--always"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(describe) = describe {
+        println!("cargo:rustc-env=GIT_HASH={describe}");
+    }
+}
+
+// HOST KERNEL'S vmlinux.h FROM ~/.cache/pandemonium. THE STAMP NEXT TO IT
+// PINS THE KERNEL RELEASE + BTF HASH; ANY MISMATCH RE-RUNS bpftool.
+fn cached_vmlinux_h() -> String {
+    let cache_dir = PathBuf::from(env::var("HOME").expect("HOME not set"))
+        .join(".cache")
+        .join("pandemonium");
+    std::fs::create_dir_all(&cache_dir).expect("failed to create cache dir");
+    let cache_path = cache_dir.join("vmlinux.h");
+    let stamp_path = cache_dir.join("vmlinux.h.stamp");
+
+    let release = std::fs::read_to_string(btfcache::OSRELEASE_PATH).unwrap_or_default();
+    let btf = std::fs::read(btfcache::BTF_PATH).ok();
+    let now = btfcache::CacheStamp::new(&release, btf.as_deref());
+    let stamp = std::fs::read_to_string(&stamp_path).ok();
+    let verdict = btfcache::check_cache(
+        cache_path.metadata().ok().map(|m| m.len()),
+        stamp.as_deref(),
+        &now,
+    );
+    if verdict.is_fresh() {
+        return std::fs::read_to_string(&cache_path).expect("cached vmlinux.h is not utf-8");
+    }
+    if let Some(reason) = verdict.reason() {
+        println!("cargo:warning=regenerating vmlinux.h: {reason}");
+    }
+
+    let output = Command::new("bpftool")
+        .args(["btf", "dump", "file", btfcache::BTF_PATH, "format", "c"])
+        .output()
+        .expect("bpftool not found -- install once: pacman -S bpf (only needed for first build)");
+    if !output.status.success() {
+        panic!(
+            "bpftool failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    std::fs::write(&cache_path, &output.stdout).expect("failed to cache vmlinux.h");
+    std::fs::write(&stamp_path, now.render()).expect("failed to write vmlinux.h stamp");
+    String::from_utf8(output.stdout).expect("vmlinux.h is not utf-8")
+}
+
 // PATCH vmlinux.h FOR COMPATIBILITY.
 // C23: true/false/bool ARE KEYWORDS, BUT vmlinux.h DEFINES THEM AS
 // ENUM VALUES AND A TYPEDEF. RENAME THE CONFLICTS.
@@ -66,6 +66,23 @@ def get_git_info() -> dict:
     return info
 
 
+def get_system_header() -> list[str]:
+    """Version/kernel/sched_ext/CPU header and [CONFIG] block from `pandemonium info`.
+
+    Same lines the Rust reports open with. Without a built binary, falls
+    back to what Python can see on its own.
+    """
+    try:
+        r = subprocess.run([str(BINARY), "info"], capture_output=True,
+                           text=True, timeout=30)
+        if r.returncode == 0 and r.stdout.strip():
+            return r.stdout.strip().splitlines()
+    except (OSError, subprocess.TimeoutExpired):
+        pass
+    return [f"PANDEMONIUM v{get_version()} (git {get_git_info()['commit']})",
+            f"KERNEL:    {platform.release()}"]
+
+
 # LOGGING
 
 def _timestamp() -> str:
@@ -331,6 +348,18 @@ def compute_core_counts(max_cpus: int) -> list[int]:
     return sorted(points)
 
 
+# SAME RULE AS hotplug::sanitize_nr_cpus: 0 IS REJECTED (ValueError), A
+# COUNT ABOVE possible IS CLAMPED WITH A WARNING. RETURNS (EFFECTIVE, WARNING)
+def sanitize_nr_cpus(requested: int, possible: int) -> tuple[int, str | None]:
+    if requested < 1:
+        raise ValueError(f"--nr-cpus {requested}: MUST BE AT LEAST 1")
+    possible = max(possible, 1)
+    if requested > possible:
+        return possible, (f"--nr-cpus {requested} EXCEEDS THE {possible} "
+                          f"POSSIBLE CPUS: CLAMPED TO {possible}")
+    return requested, None
+
+
 # SCHEDULER DETECTION
 
 SCX_OPS = Path("/sys/kernel/sched_ext/root/ops")
@@ -0,0 +1,107 @@
+// PANDEMONIUM MAP ABI SELF-TEST
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// EVERY MAP RUST DECODES HAS A RUST MIRROR OF ITS KEY AND VALUE. THE
+// SKELETON IS CHECKED AGAINST THEM BEFORE EVERY ATTACH; `pandemonium
+// self-test` (AND run --verbose AT STARTUP) LOADS THE OBJECT WITHOUT
+// ATTACHING AND PRINTS ONE PASS/FAIL ROW PER MAP FROM THE KERNEL'S OWN
+// bpf_map_info. THE HISTOGRAMS AND cpu_busy_map HAVE NO STRUCT: ONE u64
+// PER CPU (A BUCKET COUNT, A BUSY FLAG).
+
+use std::mem::size_of;
+
+use crate::hotplug::CpuBitmap;
+use crate::procdb::TaskClassEntry;
+use crate::stats::{check_value_size, DsqDepth, PandemoniumStats};
+use crate::tuning::TuningKnobs;
+use crate::victims::SchedEvent;
+
+const ARRAY_KEY: usize = size_of::<u32>();
+const COMM_KEY: usize = 16; // char[16]: TASK_COMM_LEN
+
+// (MAP, KEY MIRROR, VALUE MIRROR): THE MAPS scheduler.rs DECODES
+pub const MIRRORS: [(&str, usize, usize); 11] = [
+    ("stats_map", ARRAY_KEY, size_of::<PandemoniumStats>()),
+    ("tuning_knobs_map", ARRAY_KEY, size_of::<TuningKnobs>()),
+    ("dsq_depth_map", ARRAY_KEY, size_of::<DsqDepth>()),
+    ("cpu_busy_map", ARRAY_KEY, size_of::<u64>()),
+    ("reserved_cpus_map", ARRAY_KEY, size_of::<CpuBitmap>()),
+    ("task_class_observe", COMM_KEY, size_of::<TaskClassEntry>()),
+    ("task_class_init", COMM_KEY, size_of::<TaskClassEntry>()),
+    ("wake_lat_hist", ARRAY_KEY, size_of::<u64>()),
+    ("sleep_hist", ARRAY_KEY, size_of::<u64>()),
+    ("lag_hist", ARRAY_KEY, size_of::<u64>()),
+    ("event_ring", ARRAY_KEY, size_of::<SchedEvent>()),
+];
+
+// ONE MAP: THE BPF SIDE'S SIZES NEXT TO THE RUST MIRROR'S
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapAbi {
+    pub map: &'static str,
+    pub key_size: u32,
+    pub key_mirror: usize,
+    pub value_size: u32,
+    pub value_mirror: usize,
+}
+
+impl MapAbi {
+    // None: NOT A MAP RUST DECODES
+    pub fn new(map: &str, key_size: u32, value_size: u32) -> Option<Self> {
+        let &(map, key_mirror, value_mirror) = MIRRORS.iter().find(|(m, _, _)| *m == map)?;
+        Some(Self {
+            map,
+            key_size,
+            key_mirror,
+            value_size,
+            value_mirror,
+        })
+    }
+
+    pub fn pass(&self) -> bool {
+        self.key_size as usize == self.key_mirror && self.value_size as usize == self.value_mirror
+    }
+
+    // THE ATTACH GATE: THE FIRST MISMATCH AS AN ERROR
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.key_size as usize != self.key_mirror {
+            anyhow::bail!(
+                "{}: BPF KEY IS {} BYTES, RUST MIRROR IS {} (intf.h OUT OF SYNC)",
+                self.map,
+                self.key_size,
+                self.key_mirror
+            );
+        }
+        check_value_size(self.map, self.value_size, self.value_mirror)
+    }
+
+    // "PASS  stats_map           key 4/4  value 1024/1024" (BPF/RUST)
+    pub fn line(&self) -> String {
+        format!(
+            "{}  {:<18}  key {}/{}  value {}/{}",
+            if self.pass() { "PASS" } else { "FAIL" },
+            self.map,
+            self.key_size,
+            self.key_mirror,
+            self.value_size,
+            self.value_mirror
+        )
+    }
+}
+
+pub fn failures(rows: &[MapAbi]) -> usize {
+    rows.iter().filter(|r| !r.pass()).count()
+}
+
+pub fn summary(rows: &[MapAbi]) -> String {
+    match failures(rows) {
+        0 => format!(
+            "SELF-TEST PASSED: {} MAPS MATCH THEIR RUST MIRRORS",
+            rows.len()
+        ),
+        n => format!(
+            "SELF-TEST FAILED: {} OF {} MAPS DIFFER FROM THEIR RUST MIRRORS (sizes BPF/RUST)",
+            n,
+            rows.len()
+        ),
+    }
+}
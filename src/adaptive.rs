@@ -5,161 +5,568 @@
 //   READS BPF PER-CPU HISTOGRAMS FOR P99 COMPUTATION.
 //   DETECTS WORKLOAD REGIME. SETS BASELINE KNOBS.
 //   TIGHTENS ON P99 SPIKES. RELAXES GRADUALLY AFTER P99 NORMALIZES.
+//   THE REFLEX ONLY HOLDS A SLICE REQUEST; THE LOOP IS THE SOLE MAP WRITER
+//   AND MERGES THE REQUEST INTO EACH WRITE (tuning::merge_reflex).
 //
 // BPF PRODUCES HISTOGRAMS, RUST READS AND REACTS. RUST WRITES KNOBS,
 // BPF READS THEM ON THE VERY NEXT SCHEDULING DECISION.
+//
+// CONTROL SOCKET COMMANDS (control.rs) ARRIVE OVER A CHANNEL AND ARE
+// DRAINED ONCE PER TICK HERE: THE LOOP STAYS THE ONLY WRITER. --dbus
+// FEEDS THE SAME CHANNEL AND GETS REGIME + P99 BACK EACH TICK.
+// `gamemode start/end` SESSIONS NEST HERE (gamemode.rs); A GAME THAT
+// EXITS WITHOUT AN END IS ENDED ON THE NEXT TICK.
+// SIGUSR1 SETS A FLAG; THE NEXT TICK WRITES A DIAGNOSTIC SNAPSHOT (diag.rs).
+// THE ONLINE CPU SET IS POLLED EACH TICK; A CHANGE RESCALES (hotplug.rs).
+// --verbose ADDS CUMULATIVE STATS, APPLIED LAG, KNOB CHANGES AND PROCDB
+// FLUSHES (diag.rs), AND THE WORST WAKEUP AND PREEMPTION VICTIMS EVERY
+// 30 TICKS (victims.rs). --lag-scale STARTS AS A HELD KNOB (ctl unset RELEASES IT).
+// A FULLY IDLE MACHINE STRETCHES THE TICK TO 5S (tuning::IdleStretch); THE
+// FIRST SIGN OF ACTIVITY SNAPS IT BACK WITHIN A SECOND.
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 
-use crate::procdb::ProcessDb;
-use crate::scheduler::{PandemoniumStats, Scheduler};
-use crate::tuning::{self, detect_regime, scaled_regime_knobs, Regime, TuningKnobs, HIST_BUCKETS};
+use pandemonium::blackbox::{BlackBox, Record};
+use pandemonium::event::{AdaptiveTally, TickCounters, HEAT_MINUTES, MAX_SNAPSHOTS};
+use pandemonium::exitcode::ExitInfo;
+use pandemonium::governor::{self, GovernorWatch};
+use pandemonium::hotplug::{self, CpuScaling, HotplugWatch};
+use pandemonium::hybrid::{self, HybridMonitor};
+use pandemonium::latplot;
+use pandemonium::probe::{ProbeWindow, SelfProbeSummary, SharedLatencyHist};
+use pandemonium::reserved;
+use pandemonium::residency::{self, IdleResidency};
+use pandemonium::rtsteal::RtWatch;
+use pandemonium::stats::PandemoniumStats;
+use pandemonium::sysinfo::{RunConfig, SystemInfo};
+use pandemonium::telemetry::{telemetry_tick, AdaptiveColumns, TelemetryExtras};
+use pandemonium::victims::{self, EventCursor, VictimTable};
+
+use crate::ctl::{self, CtlCommand, CtlRequest, KnobOverrides, LiveStatus};
+use crate::diag::{self, RegimeChange, RegimeHistory};
+use crate::gamemode::{self, GameEnd, GameSessions, GameStart, PriorState, GAME_REGIME, NO_PID};
+use crate::pinmap::{TickRetry, PIN_OPEN_ATTEMPTS};
+use crate::procdb::{Force, ProcDbLimits, ProcessDb, Seed, INIT_RETRY_TICKS};
+use crate::restart::RestartSummary;
+use crate::scheduler::Scheduler;
+use crate::syscalls::OpBudget;
+use crate::topology::CpuTopology;
+use crate::tuning::{
+    self, detect_regime, merge_reflex, scaled_regime_knobs, BacklogWatch, ClassifierState,
+    ClockSample, IdleStretch, LagHist, QueueDepthWatch, Reflex, Regime, SloCompliance, TuningKnobs,
+    HIST_BUCKETS,
+};
 
 // REGIME THRESHOLDS, PROFILES, AND KNOB COMPUTATION LIVE IN tuning.rs
 // (ZERO BPF DEPENDENCIES, TESTABLE OFFLINE)
 
-// TIGHTEN PARAMETERS
+// SLEEP PATTERN BUCKETS: CLASSIFY IO-WAIT VS IDLE WORKLOADS
+const SLEEP_BUCKETS: usize = 4;
 
-const MIN_SLICE_NS: u64 = 500_000; // 500US FLOOR
+// EVERY ADAPTIVE KNOB WRITE GOES THROUGH HERE SO CTL-HELD KNOBS STICK
+fn write_knobs(sched: &Scheduler, mut knobs: TuningKnobs, held: &KnobOverrides) -> Result<()> {
+    held.apply(&mut knobs);
+    sched.write_tuning_knobs(&knobs)
+}
 
-// GRADUATED RELAX: STEP TOWARD BASELINE AFTER P99 NORMALIZES
-const RELAX_STEP_NS: u64 = 500_000; // RELAX BY 500US PER TICK
-const RELAX_HOLD_TICKS: u32 = 2; // WAIT 2S OF GOOD P99 BEFORE STEPPING
+// NO_PID STAYS NO_PID; A THREAD ID RESOLVES TO ITS PROCESS
+fn game_tgid(pid: u32) -> Result<u32, String> {
+    if pid == NO_PID {
+        return Ok(NO_PID);
+    }
+    std::fs::read_to_string(format!("/proc/{}/status", pid))
+        .ok()
+        .and_then(|st| gamemode::parse_tgid(&st))
+        .ok_or_else(|| format!("NO SUCH PID {}", pid))
+}
 
-// SLEEP PATTERN BUCKETS: CLASSIFY IO-WAIT VS IDLE WORKLOADS
-const SLEEP_BUCKETS: usize = 4;
+// RESPONSE PAIRS + Some(NEW PIN STATE) ON THE FIRST START OR LAST END
+type GameReply = (Vec<(&'static str, String)>, Option<Option<Regime>>);
+
+// GAMEMODE START/END: SESSION BOOKKEEPING, THE TGID BOOST, THE HELD GAME
+// KNOBS. THE CALLER APPLIES THE NEW PIN STATE.
+fn game_command(
+    sched: &Scheduler,
+    start: bool,
+    pid: u32,
+    games: &mut GameSessions,
+    held: &mut KnobOverrides,
+    pinned: Option<Regime>,
+    nr_cpus: u64,
+) -> Result<GameReply, String> {
+    let (tgid, state, repin) = if start {
+        let tgid = game_tgid(pid)?;
+        if tgid != NO_PID {
+            sched
+                .write_game_tgid(tgid)
+                .map_err(|e| format!("GAME MAP WRITE FAILED: {}", e))?;
+        }
+        match games.start(tgid, PriorState::capture(pinned, held)) {
+            GameStart::First => {
+                let mixed = scaled_regime_knobs(GAME_REGIME, nr_cpus);
+                for (name, value) in gamemode::game_knobs(&mixed, &sched.slice_bounds) {
+                    held.set(name, value);
+                }
+                log_info!(
+                    "GAMEMODE: ON (TGID {}), REGIME PINNED TO {}, SLICES TIGHTENED",
+                    tgid,
+                    GAME_REGIME.label()
+                );
+                (tgid, "started", Some(Some(GAME_REGIME)))
+            }
+            GameStart::Nested { active } => {
+                log_info!("GAMEMODE: TGID {} JOINS ({} ACTIVE)", tgid, active);
+                (tgid, "nested", None)
+            }
+            GameStart::Duplicate { .. } => (tgid, "already", None),
+        }
+    } else {
+        // A GAME THAT ALREADY EXITED IS ENDED BY THE TGID IT STARTED WITH
+        let tgid = if games.contains(pid) {
+            pid
+        } else {
+            game_tgid(pid)?
+        };
+        let end = games.end(tgid);
+        if end == GameEnd::NotActive {
+            return Err(format!("NO GAMEMODE SESSION FOR PID {}", pid));
+        }
+        if tgid != NO_PID {
+            if let Err(e) = sched.clear_game_tgid(tgid) {
+                log_warn!("GAMEMODE: TGID {} STILL IN THE GAME MAP: {}", tgid, e);
+            }
+        }
+        match end {
+            GameEnd::Last(prior) => {
+                prior.restore(held);
+                log_info!("GAMEMODE: OFF (TGID {}), PRIOR PIN + KNOBS RESTORED", tgid);
+                (tgid, "restored", Some(prior.pinned))
+            }
+            _ => {
+                log_info!(
+                    "GAMEMODE: TGID {} ENDS ({} STILL ACTIVE)",
+                    tgid,
+                    games.tgids().len()
+                );
+                (tgid, "ended", None)
+            }
+        }
+    };
+    let kv = vec![
+        ("tgid", tgid.to_string()),
+        ("state", state.to_string()),
+        ("games", games.tgids().len().to_string()),
+    ];
+    Ok((kv, repin))
+}
+
+// LOOP STATE A DIAGNOSTIC SNAPSHOT NEEDS BEYOND THE BPF MAPS
+struct DiagState<'a> {
+    regime: Regime,
+    pinned: Option<Regime>,
+    paused: bool,
+    tightened: bool,
+    tighten_events: u64,
+    tick: u64,
+    p99_ns: u64,
+    history: &'a RegimeHistory,
+    held: &'a KnobOverrides,
+    config: &'a RunConfig,
+    victims: &'a VictimTable,
+}
+
+// SIGUSR1: FULL STATE DUMP FOR BUG REPORTS. RETURNS THE FILE PATH.
+fn write_diag(sched: &Scheduler, st: &DiagState, procdb: Option<&ProcessDb>) -> Result<String> {
+    let mut out = format!("PANDEMONIUM DIAGNOSTIC SNAPSHOT ({})\n\n", diag::stamp());
+
+    out.push_str("== SYSTEM\n");
+    // [CONFIG] AS LOGGED AT STARTUP: KNOBS THEN, == KNOBS BELOW IS NOW
+    for line in SystemInfo::collect()
+        .header_lines()
+        .into_iter()
+        .chain(st.config.config_lines())
+    {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push('\n');
+
+    out.push_str("== STATE\n");
+    out.push_str(&format!(
+        "regime={} pinned={} paused={} tightened={} tighten_events={} ticks={} last_p99={}us\n",
+        st.regime.label(),
+        st.pinned.map(|r| r.label()).unwrap_or("-"),
+        st.paused,
+        st.tightened,
+        st.tighten_events,
+        st.tick,
+        st.p99_ns / 1000
+    ));
+    let held = st.held.names();
+    out.push_str(&format!(
+        "held knobs: {}\n\n",
+        if held.is_empty() {
+            "-".to_string()
+        } else {
+            held.join(",")
+        }
+    ));
+
+    out.push_str("== KNOBS\n");
+    let knobs = sched.read_tuning_knobs();
+    for name in tuning::KNOB_NAMES {
+        out.push_str(&format!("{}={}\n", name, knobs.get(name).unwrap_or(0)));
+    }
+
+    out.push_str(&format!(
+        "\n== REGIME HISTORY ({} CHANGES, LAST {} KEPT)\n",
+        st.history.len(),
+        diag::REGIME_HISTORY_CAP
+    ));
+    if st.history.is_empty() {
+        out.push_str("(NONE)\n");
+    }
+    for line in st.history.lines() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out.push_str("\n== CUMULATIVE STATS\n");
+    for (name, v) in sched.read_stats().fields() {
+        out.push_str(&format!("{}={}\n", name, v));
+    }
+
+    out.push_str("\n== WAKEUP LATENCY HISTOGRAMS (CUMULATIVE)\n");
+    let wake_hist = sched.read_wake_lat_hist();
+    for (tier, counts) in wake_hist.iter().enumerate() {
+        out.push_str(&diag::format_hist(tier, counts));
+    }
+
+    out.push_str("\n== WAKEUP LATENCY CDF (CUMULATIVE, ALL TIERS)\n");
+    for line in latplot::cdf_lines(&latplot::pool(&wake_hist), latplot::CDF_WIDTH) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "\n== WAKEUP LATENCY HEATMAP (PER MINUTE, LAST {} KEPT)\n",
+        HEAT_MINUTES
+    ));
+    for line in sched.log.heat.lines() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out.push_str("\n== APPLIED LAG SCALE (CUMULATIVE)\n");
+    out.push_str(&diag::format_lag_hist(&sched.read_lag_hist()));
+
+    out.push_str("\n== PROCDB\n");
+    match procdb {
+        Some(db) => {
+            let (total, confident) = db.summary();
+            out.push_str(&format!(
+                "profiles={} confident={} tick={} ingested={}\n",
+                total, confident, db.tick, db.ingested
+            ));
+            for (comm, p) in diag::top_profiles(&db.profiles, diag::DIAG_TOP_PROFILES) {
+                out.push_str(&diag::format_profile(comm, p));
+                out.push('\n');
+            }
+        }
+        None => out.push_str("NOT ACTIVE (INIT FAILED, RETRYING)\n"),
+    }
+
+    out.push_str(&format!(
+        "\n== WAKEUP VICTIMS (> {}MS, TOP {})\n",
+        victims::SLOW_WAKE_NS / 1_000_000,
+        victims::TOP_VICTIMS
+    ));
+    if sched.lightweight {
+        out.push_str("NOT RECORDED (--lightweight)\n");
+    }
+    for line in st.victims.format_lines(victims::TOP_VICTIMS) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "\n== PREEMPTED BATCH TASKS (TOP {})\n",
+        victims::TOP_VICTIMS
+    ));
+    for line in st
+        .victims
+        .preempted_lines(victims::TOP_VICTIMS, st.tick + 1)
+    {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    // NO BPF RINGBUF: STATS ARE PER-CPU ARRAYS, NOTHING CAN BE DROPPED.
+    // THE LOSSY BUFFERS ARE THE PER-CPU event_ring (OVERWRITTEN BEFORE A
+    // TICK DRAINED IT) AND THE USERSPACE EVENT LOG.
+    out.push_str("\n== BUFFERS\n");
+    out.push_str(&format!(
+        "bpf ringbuf: none (per-cpu stat arrays, no drops possible)\n\
+         bpf event_ring: {} slots/cpu, lost={} pending={}\n\
+         event log: {}/{} snapshots, wrapped={}\n",
+        victims::EVENT_RING_SLOTS,
+        st.victims.lost,
+        st.victims.pending(),
+        sched.log.len(),
+        MAX_SNAPSHOTS,
+        sched.log.wrapped()
+    ));
+
+    out.push_str(&format!(
+        "\n== TELEMETRY (LAST {} SNAPSHOTS)\n",
+        diag::DIAG_TELEMETRY_TAIL
+    ));
+    out.push_str(&sched.log.tail_table(diag::DIAG_TELEMETRY_TAIL));
+
+    std::fs::create_dir_all(diag::DIAG_DIR)?;
+    let path = diag::diag_path(&diag::stamp());
+    std::fs::write(&path, out)?;
+    Ok(path)
+}
 
 // MONITOR LOOP
 
+// EVERYTHING THE MONITOR LOOP TAKES FROM THE RUN: FLAGS, THE SIGNAL FLAGS AND
+// THE CHANNELS THAT OUTLIVE RESTARTS. BUILT ONCE IN main.rs, BEFORE THE
+// FIRST ATTACH.
+#[derive(Clone, Copy)]
+pub struct MonitorConfig<'a> {
+    pub shutdown: &'static AtomicBool,
+    pub diag_requested: &'static AtomicBool,
+    pub verbose: bool,
+    pub telemetry_interval: u64,
+    pub nr_cpus: u64,
+    pub track_hotplug: bool,
+    pub scaling_cpuset: Option<&'a [u32]>,
+    pub ctl_rx: Option<&'a Receiver<CtlRequest>>,
+    pub live_tx: Option<&'a Sender<LiveStatus>>,
+    pub probe_hist: Option<&'a SharedLatencyHist>,
+    pub procdb_limits: ProcDbLimits,
+    pub procdb_seeds: &'a [Seed],
+    pub procdb_forces: &'a [Force],
+    pub lag_scale: Option<u64>,
+    pub adaptive_classifier: bool,
+    pub slo_ns: Option<u64>,
+}
+
 // 1-SECOND CONTROL LOOP. READS BPF HISTOGRAMS, COMPUTES P99,
 // DETECTS WORKLOAD REGIME, TIGHTENS/RELAXES KNOBS.
-// RUNS ON THE MAIN THREAD.
+// RUNS ON THE MAIN THREAD. run_config, restarts AND deadline CHANGE
+// BETWEEN ATTACHES; EVERYTHING ELSE IS cfg.
 pub fn monitor_loop(
     sched: &mut Scheduler,
-    shutdown: &'static AtomicBool,
-    verbose: bool,
-    nr_cpus: u64,
-) -> Result<bool> {
+    cfg: &MonitorConfig,
+    mut blackbox: Option<&mut BlackBox>,
+    run_config: &RunConfig,
+    restarts: &RestartSummary,
+    deadline: Option<Instant>,
+) -> Result<ExitInfo> {
+    let MonitorConfig {
+        shutdown,
+        diag_requested,
+        verbose,
+        telemetry_interval,
+        nr_cpus,
+        track_hotplug,
+        scaling_cpuset,
+        ctl_rx,
+        live_tx,
+        probe_hist,
+        procdb_limits,
+        procdb_seeds,
+        procdb_forces,
+        lag_scale,
+        adaptive_classifier,
+        slo_ns,
+    } = *cfg;
     let mut prev = PandemoniumStats::default();
+    let mut prev_lag = LagHist::default();
     let mut prev_hist = [[0u64; HIST_BUCKETS]; 3];
     let mut prev_sleep = [0u64; SLEEP_BUCKETS];
     let mut regime = Regime::Mixed;
-    let mut relax_counter: u32 = 0;
-    let mut tightened = false;
+    let mut reflex = Reflex {
+        bounds: sched.slice_bounds,
+        slo_ns,
+        ..Reflex::default()
+    };
+    let mut slo = SloCompliance::default();
     let mut pending_regime = regime;
     let mut regime_hold: u32 = 0;
     let mut light_ticks: u64 = 0;
     let mut mixed_ticks: u64 = 0;
     let mut heavy_ticks: u64 = 0;
     let mut stability_score: u32 = 0;
-    let mut spike_count: u32 = 0;
+    let mut stretch = IdleStretch::default();
     let mut tick_counter: u64 = 0;
-    let mut tighten_events: u64 = 0;
     let mut prev_tighten_events: u64 = 0;
-    let sojourn_floor_ns: u64 = (nr_cpus * 1_000_000).clamp(2_000_000, 6_000_000);
-    let sojourn_ceil_ns: u64 = sojourn_floor_ns * 2;
-    let mut sojourn_thresh_ns: u64 = sojourn_floor_ns;
+    // SCALE BY ONLINE CPUS (INSIDE THE CPUSET); AN EXPLICIT --nr-cpus
+    // OVERRIDE STAYS FIXED
+    let mut online_watch = if track_hotplug {
+        hotplug::read_online()
+            .map(|o| HotplugWatch::new(o).with_cpuset(scaling_cpuset.map(<[u32]>::to_vec)))
+    } else {
+        None
+    };
+    let mut scaling = CpuScaling::new(online_watch.as_ref().map_or_else(
+        || {
+            let possible: Vec<u32> = (0..nr_cpus as u32).collect();
+            hotplug::scaling_cpus(&possible, scaling_cpuset) as u64
+        },
+        HotplugWatch::scaling_cpus,
+    ));
+    let mut sojourn_thresh_ns: u64 = scaling.sojourn_floor_ns;
+    // EVERY REGIME STARTS FROM THE SAME THRESHOLD: THE ADAPTED ONE CARRIES OVER
+    let mut cls_high = tuning::DEFAULT_LAT_CRI_THRESH_HIGH;
+    let mut cls_state = ClassifierState::default();
+    let mut pinned: Option<Regime> = None;
+    let mut paused = false;
+    let mut history = RegimeHistory::default();
+    let mut held = KnobOverrides::default();
+    let mut games = GameSessions::default();
+    if let Some(n) = lag_scale {
+        held.set("lag_scale", n);
+    }
+    let mut qwatch = QueueDepthWatch::default();
+    let mut bwatch = BacklogWatch::default();
+    let mut probe_window = probe_hist.map(ProbeWindow::new);
+    let mut hybrid_mon = HybridMonitor::default();
+    let mut prev_knobs: Option<TuningKnobs> = None;
+    let mut prev_clock = ClockSample::now();
+    let mut residency = IdleResidency::default();
+    let mut rt_watch = RtWatch::new(Path::new("/proc"));
+    let gov_root = Path::new(governor::CPU_ROOT);
+    let mut gov_watch = GovernorWatch::new(governor::read(gov_root));
+    let clock_origin = std::time::Instant::now();
+    let mut last_predictions: HashMap<[u8; 16], u8> = HashMap::new();
+    let mut event_cursor = EventCursor::default();
+    let mut sched_events = Vec::new();
+    let mut victim_table = VictimTable::default();
 
-    let mut procdb = match ProcessDb::new() {
+    // PINS NOT OPENABLE YET: THE MONITOR LOOP TRIES AGAIN EVERY
+    // INIT_RETRY_TICKS INSTEAD OF RUNNING THE WHOLE RUN WITHOUT PROCDB
+    let mut procdb_retry = TickRetry::every(INIT_RETRY_TICKS);
+    let mut procdb = match ProcessDb::new(
+        sched.pins.dir(),
+        PIN_OPEN_ATTEMPTS,
+        procdb_limits,
+        procdb_seeds,
+        procdb_forces,
+    ) {
         Ok(db) => Some(db),
         Err(e) => {
-            log_warn!("PROCDB INIT FAILED: {}", e);
+            log_warn!(
+                "PROCDB INIT FAILED: {:#} (RETRYING EVERY {} TICKS)",
+                e,
+                INIT_RETRY_TICKS
+            );
+            procdb_retry.failed(tick_counter);
             None
         }
     };
 
     // APPLY INITIAL REGIME
-    sched.write_tuning_knobs(&scaled_regime_knobs(regime, nr_cpus))?;
+    write_knobs(sched, scaled_regime_knobs(regime, scaling.nr_cpus), &held)?;
 
     while !shutdown.load(Ordering::Relaxed) && !sched.exited() {
+        // --duration: END LIKE CTRL+C, SUMMARY AND ALL
+        if tuning::deadline_reached(deadline, Instant::now()) {
+            log_info!("DURATION ELAPSED");
+            shutdown.store(true, Ordering::Relaxed);
+            break;
+        }
         let tick_start = std::time::Instant::now();
-        std::thread::sleep(Duration::from_secs(1));
+        // THE TICK'S SLEEP, IN STEPS THAT SAMPLE THE IDLE BITMAP. STRETCHED
+        // WHILE FULLY IDLE (tuning::IdleStretch): EACH STEP ALSO READS THE
+        // DISPATCH COUNT, AND ACTIVITY, A ctl REQUEST, SIGUSR1 OR CTRL+C
+        // ENDS THE TICK AT THAT STEP
+        let was_stretched = stretch.stretched();
+        let (window, samples) =
+            stretch.window((Duration::from_secs(1), residency::SAMPLES_PER_TICK));
+        let mut step_dispatches = was_stretched.then(|| sched.read_stats().nr_dispatches);
+        let mut step_start = tick_start;
+        let mut early_ctl: Vec<CtlRequest> = Vec::new();
+        residency.sample_window_until(
+            window,
+            samples,
+            std::thread::sleep,
+            || clock_origin.elapsed().as_nanos() as u64,
+            || sched.read_idle_bitmap(),
+            || {
+                let Some(prev) = step_dispatches.as_mut() else {
+                    return false;
+                };
+                if shutdown.load(Ordering::Relaxed) || diag_requested.load(Ordering::Relaxed) {
+                    stretch.snap();
+                    return true;
+                }
+                if let Some(req) = ctl_rx.and_then(|rx| rx.try_recv().ok()) {
+                    early_ctl.push(req);
+                    stretch.snap();
+                    return true;
+                }
+                let cur = sched.read_stats().nr_dispatches;
+                let step_ns = step_start.elapsed().as_nanos() as u64;
+                step_start = std::time::Instant::now();
+                let dispatched = cur.wrapping_sub(std::mem::replace(prev, cur));
+                stretch.step(dispatched, step_ns)
+            },
+        );
         let elapsed_ns = tick_start.elapsed().as_nanos() as u64;
+        if verbose && was_stretched && !stretch.stretched() {
+            log_info!(
+                "IDLE STRETCH ENDED AFTER {}ms: BACK TO 1s TICKS",
+                elapsed_ns / 1_000_000
+            );
+        }
 
-        let stats = sched.read_stats();
+        // ONE stats_map READ PER TICK FEEDS THE TOTAL AND THE HYBRID MONITOR
+        let per_cpu = sched.read_stats_percpu();
+        let stats = PandemoniumStats::total_except(&per_cpu, &sched.reserved);
 
         // COMPUTE DELTAS
-        let delta_d = stats.nr_dispatches.wrapping_sub(prev.nr_dispatches);
-        let delta_idle = stats.nr_idle_hits.wrapping_sub(prev.nr_idle_hits);
-        let delta_shared = stats.nr_shared.wrapping_sub(prev.nr_shared);
-        let delta_preempt = stats.nr_preempt.wrapping_sub(prev.nr_preempt);
-        let delta_keep = stats.nr_keep_running.wrapping_sub(prev.nr_keep_running);
-        let delta_wake_sum = stats.wake_lat_sum.wrapping_sub(prev.wake_lat_sum);
-        let delta_wake_samples = stats.wake_lat_samples.wrapping_sub(prev.wake_lat_samples);
-        let delta_hard = stats.nr_hard_kicks.wrapping_sub(prev.nr_hard_kicks);
-        let delta_soft = stats.nr_soft_kicks.wrapping_sub(prev.nr_soft_kicks);
-        let delta_enq_wake = stats.nr_enq_wakeup.wrapping_sub(prev.nr_enq_wakeup);
-        let delta_enq_requeue = stats.nr_enq_requeue.wrapping_sub(prev.nr_enq_requeue);
-        let delta_rescue = stats.nr_overflow_rescue.wrapping_sub(prev.nr_overflow_rescue);
-        let wake_avg_us = if delta_wake_samples > 0 {
-            delta_wake_sum / delta_wake_samples / 1000
-        } else {
-            0
-        };
-
-        // PER-PATH LATENCY
-        let d_idle_sum = stats.wake_lat_idle_sum.wrapping_sub(prev.wake_lat_idle_sum);
-        let d_idle_cnt = stats.wake_lat_idle_cnt.wrapping_sub(prev.wake_lat_idle_cnt);
-        let d_kick_sum = stats.wake_lat_kick_sum.wrapping_sub(prev.wake_lat_kick_sum);
-        let d_kick_cnt = stats.wake_lat_kick_cnt.wrapping_sub(prev.wake_lat_kick_cnt);
-        let lat_idle_us = if d_idle_cnt > 0 {
-            d_idle_sum / d_idle_cnt / 1000
-        } else {
-            0
-        };
-        let lat_kick_us = if d_kick_cnt > 0 {
-            d_kick_sum / d_kick_cnt / 1000
-        } else {
-            0
-        };
-        let delta_reenq = stats.nr_reenqueue.wrapping_sub(prev.nr_reenqueue);
-
-        // L2 CACHE AFFINITY DELTAS
-        let dl2_hb = stats.nr_l2_hit_batch.wrapping_sub(prev.nr_l2_hit_batch);
-        let dl2_mb = stats.nr_l2_miss_batch.wrapping_sub(prev.nr_l2_miss_batch);
-        let dl2_hi = stats
-            .nr_l2_hit_interactive
-            .wrapping_sub(prev.nr_l2_hit_interactive);
-        let dl2_mi = stats
-            .nr_l2_miss_interactive
-            .wrapping_sub(prev.nr_l2_miss_interactive);
-        let dl2_hl = stats
-            .nr_l2_hit_lat_crit
-            .wrapping_sub(prev.nr_l2_hit_lat_crit);
-        let dl2_ml = stats
-            .nr_l2_miss_lat_crit
-            .wrapping_sub(prev.nr_l2_miss_lat_crit);
-        let l2_pct_b = if dl2_hb + dl2_mb > 0 {
-            dl2_hb * 100 / (dl2_hb + dl2_mb)
-        } else {
-            0
-        };
-        let l2_pct_i = if dl2_hi + dl2_mi > 0 {
-            dl2_hi * 100 / (dl2_hi + dl2_mi)
-        } else {
-            0
-        };
-        let l2_pct_l = if dl2_hl + dl2_ml > 0 {
-            dl2_hl * 100 / (dl2_hl + dl2_ml)
-        } else {
-            0
-        };
+        let delta = stats.delta(&prev);
+        if delta.reset {
+            log_warn!("STATS COUNTERS WENT BACKWARDS (BPF RESTART?): HOLDING DECISIONS THIS TICK");
+        }
 
-        let idle_pct = if delta_d > 0 {
-            delta_idle * 100 / delta_d
-        } else {
-            0
-        };
+        // SUSPEND/RESUME: THIS TICK'S DELTAS STRADDLE THE SUSPEND
+        let clock = ClockSample::now();
+        let resumed = tuning::suspend_gap_ns(prev_clock, clock);
+        prev_clock = clock;
+        if let Some(gap_ns) = resumed {
+            log_warn!(
+                "RESUME DETECTED: SUSPENDED ~{}s, DISCARDING THIS TICK'S DELTAS + HISTOGRAMS",
+                gap_ns / 1_000_000_000
+            );
+            reflex.reset_counters();
+            stability_score = 0;
+        }
+        // NO USABLE DATA THIS TICK: HOLD EVERYTHING AS IF PAUSED
+        let discard = delta.reset || resumed.is_some();
+        let delta_d = delta.dispatches;
+        let idle_pct = delta.idle_pct();
 
         // READ HISTOGRAMS (CUMULATIVE, COMPUTE DELTAS)
         let cur_hist = sched.read_wake_lat_hist();
+        // AFTER A STATS RESET THE HISTOGRAMS RESTARTED TOO, AND ACROSS A
+        // SUSPEND THEY MIX BOTH SIDES: TREAT AS EMPTY
         let mut delta_hist = [[0u64; HIST_BUCKETS]; 3];
-        for tier in 0..3 {
-            for b in 0..HIST_BUCKETS {
-                delta_hist[tier][b] = cur_hist[tier][b].wrapping_sub(prev_hist[tier][b]);
+        if !discard {
+            for tier in 0..3 {
+                for b in 0..HIST_BUCKETS {
+                    delta_hist[tier][b] = cur_hist[tier][b].wrapping_sub(prev_hist[tier][b]);
+                }
             }
         }
 
@@ -169,19 +576,28 @@ pub fn monitor_loop(
         let tp99_l_ns = tuning::compute_p99_from_histogram(&delta_hist[2]);
 
         // AGGREGATE P99
-        let mut agg = [0u64; HIST_BUCKETS];
-        for t in 0..3 {
-            for b in 0..HIST_BUCKETS {
-                agg[b] += delta_hist[t][b];
-            }
-        }
-        let p99_ns = tuning::compute_p99_from_histogram(&agg);
+        let p99_ns = tuning::pooled_p99_ns(&delta_hist);
+        // --slo-p99-us: THIS WINDOW MET THE TARGET? None: NOT COUNTED
+        let woke = delta_hist.iter().flatten().any(|&c| c > 0);
+        let slo_met = slo_ns
+            .filter(|_| woke)
+            .map(|target| slo.record(p99_ns, target));
+
+        // SLOW WAKEUPS + PREEMPTIONS: CACHED pids NOW, NEW ONES AT A
+        // CAPPED /proc RATE
+        sched_events.clear();
+        let overrun = sched.read_events(&mut event_cursor, &mut sched_events);
+        victim_table.note_lost(overrun);
+        victim_table.ingest(&sched_events);
+        victim_table.resolve(victims::RESOLVE_PER_TICK, victims::read_comm);
 
         // SLEEP HISTOGRAM
         let cur_sleep = sched.read_sleep_hist();
         let mut delta_sleep = [0u64; SLEEP_BUCKETS];
-        for i in 0..SLEEP_BUCKETS {
-            delta_sleep[i] = cur_sleep[i].wrapping_sub(prev_sleep[i]);
+        if !discard {
+            for i in 0..SLEEP_BUCKETS {
+                delta_sleep[i] = cur_sleep[i].wrapping_sub(prev_sleep[i]);
+            }
         }
         let sleep_total: u64 = delta_sleep.iter().sum();
         let io_pct = if sleep_total > 0 {
@@ -190,8 +606,325 @@ pub fn monitor_loop(
             0
         };
 
-        // DETECT REGIME (SCHMITT TRIGGER + 2-TICK HOLD)
-        let detected = detect_regime(regime, idle_pct);
+        // CPU HOTPLUG: RESCALE AND REBUILD TOPOLOGY WHEN THE ONLINE SET CHANGES
+        if let Some(watch) = online_watch.as_mut() {
+            if let Some(t) = hotplug::read_online().and_then(|o| watch.update(o)) {
+                log_info!("CPU HOTPLUG: {}", t.describe());
+                scaling = CpuScaling::new(watch.scaling_cpus());
+                if scaling_cpuset.is_some() {
+                    log_info!("SCALING CPUS: {} (CPUSET)", scaling.nr_cpus);
+                }
+                sojourn_thresh_ns = scaling.clamp_sojourn(sojourn_thresh_ns);
+                CpuTopology::apply(sched, nr_cpus as usize);
+                if !paused {
+                    write_knobs(sched, scaled_regime_knobs(regime, scaling.nr_cpus), &held)?;
+                    reflex.release();
+                }
+            }
+        }
+        if let Some(change) = gov_watch.observe(governor::read(gov_root)) {
+            log_warn!("{}", change.message());
+            sched.log.governor_change(&change);
+        }
+
+        // TIME-WEIGHTED IDLE RESIDENCY OF THE UNRESERVED CPUS ONLINE NOW
+        let online: Vec<u32> = online_watch
+            .as_ref()
+            .map_or_else(|| (0..nr_cpus as u32).collect(), |w| w.online().to_vec());
+        let resid = residency.take(&reserved::unreserved(&online, &sched.reserved));
+        let resid_pct = resid.as_ref().map(|r| r.idle_pct);
+        // CAPACITY RT/DEADLINE TASKS TOOK: COMES OFF THE REGIME'S IDLE SIGNAL
+        let rt_pct = rt_watch.tick(
+            clock_origin.elapsed().as_nanos() as u64,
+            online.len() as u64,
+        );
+
+        // CONTROL SOCKET: EXECUTE PENDING COMMANDS BEFORE THIS TICK'S DECISIONS.
+        // GAMES THAT EXITED WITHOUT AN END GO FIRST, AS IF THEY HAD SENT ONE.
+        if let Some(rx) = ctl_rx {
+            let reaped: Vec<CtlRequest> = games
+                .exited(|tgid| Path::new(&format!("/proc/{}", tgid)).exists())
+                .into_iter()
+                .map(|pid| CtlRequest {
+                    cmd: CtlCommand::GameEnd { pid },
+                    reply: mpsc::channel().0,
+                })
+                .collect();
+            for req in reaped
+                .into_iter()
+                .chain(early_ctl)
+                .chain(std::iter::from_fn(|| rx.try_recv().ok()))
+            {
+                let resp = match req.cmd {
+                    CtlCommand::GetKnobs => ctl::format_knobs(&sched.read_tuning_knobs()),
+                    CtlCommand::GetRegime => ctl::format_regime(
+                        regime,
+                        pinned.is_some(),
+                        paused,
+                        reflex.tightened,
+                        &held,
+                        &sched.slice_bounds,
+                        restarts,
+                    ),
+                    CtlCommand::SetKnob { name, value } => {
+                        held.set(name, value);
+                        log_info!("CTL: {} = {} (HELD)", name, value);
+                        match write_knobs(sched, sched.read_tuning_knobs(), &held) {
+                            Ok(()) => ctl::format_knobs(&sched.read_tuning_knobs()),
+                            Err(e) => ctl::err(&format!("KNOB WRITE FAILED: {}", e)),
+                        }
+                    }
+                    CtlCommand::UnsetKnob { name } => {
+                        if held.unset(name) {
+                            log_info!("CTL: {} RELEASED", name);
+                        }
+                        // THE ADAPTIVE VALUE (PROFILE + REFLEX REQUEST) RETURNS NOW
+                        let mut knobs = sched.read_tuning_knobs();
+                        let adaptive = merge_reflex(
+                            scaled_regime_knobs(regime, scaling.nr_cpus),
+                            reflex.request(),
+                        );
+                        if let Some(v) = adaptive.get(name) {
+                            knobs.set(name, v);
+                        }
+                        match write_knobs(sched, knobs, &held) {
+                            Ok(()) => ctl::format_knobs(&sched.read_tuning_knobs()),
+                            Err(e) => ctl::err(&format!("KNOB WRITE FAILED: {}", e)),
+                        }
+                    }
+                    CtlCommand::Pin(r) => {
+                        pinned = Some(r);
+                        log_info!("CTL: REGIME PINNED TO {}", r.label());
+                        if r != regime {
+                            history.push(RegimeChange {
+                                tick: tick_counter,
+                                from: regime,
+                                to: r,
+                                idle_pct,
+                                cause: "pin",
+                            });
+                            regime = r;
+                            reflex.release();
+                            write_knobs(sched, scaled_regime_knobs(regime, scaling.nr_cpus), &held)?;
+                        }
+                        pending_regime = regime;
+                        regime_hold = 0;
+                        ctl::format_regime(
+                            regime,
+                            true,
+                            paused,
+                            reflex.tightened,
+                            &held,
+                            &sched.slice_bounds,
+                            restarts,
+                        )
+                    }
+                    CtlCommand::Unpin => {
+                        if pinned.take().is_some() {
+                            log_info!("CTL: REGIME UNPINNED");
+                        }
+                        ctl::format_regime(
+                            regime,
+                            false,
+                            paused,
+                            reflex.tightened,
+                            &held,
+                            &sched.slice_bounds,
+                            restarts,
+                        )
+                    }
+                    // THE COMPOSITOR MAP IS KEYED BY comm: EVERY TASK OF THAT
+                    // NAME IS BOOSTED, FOR THE REST OF THIS RUN
+                    CtlCommand::Boost { pid } => {
+                        match std::fs::read_to_string(format!("/proc/{}/comm", pid)) {
+                            Ok(comm) => match sched.write_compositor(comm.trim()) {
+                                Ok(()) => {
+                                    log_info!("CTL: BOOST PID {} ({})", pid, comm.trim());
+                                    ctl::ok_kv(&[
+                                        ("pid", pid.to_string()),
+                                        ("comm", comm.trim().to_string()),
+                                    ])
+                                }
+                                Err(e) => ctl::err(&format!("COMPOSITOR MAP WRITE FAILED: {}", e)),
+                            },
+                            Err(_) => ctl::err(&format!("NO SUCH PID {}", pid)),
+                        }
+                    }
+                    CtlCommand::GameStart { pid } | CtlCommand::GameEnd { pid } => {
+                        let start = matches!(req.cmd, CtlCommand::GameStart { .. });
+                        match game_command(
+                            sched,
+                            start,
+                            pid,
+                            &mut games,
+                            &mut held,
+                            pinned,
+                            scaling.nr_cpus,
+                        ) {
+                            Err(e) => ctl::err(&e),
+                            Ok((mut kv, repin)) => {
+                                if let Some(pin) = repin {
+                                    pinned = pin;
+                                    if let Some(r) = pin.filter(|&r| r != regime) {
+                                        history.push(RegimeChange {
+                                            tick: tick_counter,
+                                            from: regime,
+                                            to: r,
+                                            idle_pct,
+                                            cause: "gamemode",
+                                        });
+                                        regime = r;
+                                        reflex.release();
+                                    }
+                                    pending_regime = regime;
+                                    regime_hold = 0;
+                                    // HELD OR RELEASED GAME KNOBS TAKE EFFECT NOW
+                                    let adaptive = merge_reflex(
+                                        scaled_regime_knobs(regime, scaling.nr_cpus),
+                                        reflex.request(),
+                                    );
+                                    write_knobs(sched, adaptive, &held)?;
+                                }
+                                kv.push(("regime", regime.label().to_string()));
+                                kv.push(("pinned", pinned.is_some().to_string()));
+                                ctl::ok_kv(&kv)
+                            }
+                        }
+                    }
+                    CtlCommand::Pause => {
+                        if !paused {
+                            paused = true;
+                            log_info!("CTL: ADAPTIVE LAYER PAUSED");
+                        }
+                        ctl::format_regime(
+                            regime,
+                            pinned.is_some(),
+                            true,
+                            reflex.tightened,
+                            &held,
+                            &sched.slice_bounds,
+                            restarts,
+                        )
+                    }
+                    CtlCommand::Resume => {
+                        if paused {
+                            paused = false;
+                            let baseline = scaled_regime_knobs(regime, scaling.nr_cpus);
+                            reflex.resync(&sched.read_tuning_knobs(), &baseline);
+                            pending_regime = regime;
+                            regime_hold = 0;
+                            log_info!("CTL: ADAPTIVE LAYER RESUMED (TIGHTENED={})", reflex.tightened);
+                        }
+                        ctl::format_regime(
+                            regime,
+                            pinned.is_some(),
+                            false,
+                            reflex.tightened,
+                            &held,
+                            &sched.slice_bounds,
+                            restarts,
+                        )
+                    }
+                    CtlCommand::Stats => ctl::ok_kv(&[
+                        ("nr_dispatches", stats.nr_dispatches.to_string()),
+                        ("nr_idle_hits", stats.nr_idle_hits.to_string()),
+                        ("nr_shared", stats.nr_shared.to_string()),
+                        ("nr_preempt", stats.nr_preempt.to_string()),
+                        ("nr_keep_running", stats.nr_keep_running.to_string()),
+                        ("nr_hard_kicks", stats.nr_hard_kicks.to_string()),
+                        ("nr_soft_kicks", stats.nr_soft_kicks.to_string()),
+                        ("nr_reenqueue", stats.nr_reenqueue.to_string()),
+                        ("nr_overflow_rescue", stats.nr_overflow_rescue.to_string()),
+                        ("nr_procdb_hits", stats.nr_procdb_hits.to_string()),
+                        ("p99_us", (p99_ns / 1000).to_string()),
+                        ("tighten_events", reflex.tighten_events.to_string()),
+                        ("ticks", tick_counter.to_string()),
+                    ]),
+                    CtlCommand::ProcdbPreempted => {
+                        let rates: Vec<(String, String)> = victim_table
+                            .preempted_rates(tick_counter + 1)
+                            .into_iter()
+                            .map(|(comm, rate)| (comm, format!("{:.2}", rate)))
+                            .collect();
+                        let kv: Vec<(&str, String)> =
+                            rates.iter().map(|(c, r)| (c.as_str(), r.clone())).collect();
+                        ctl::ok_kv(&kv)
+                    }
+                    CtlCommand::ProcdbExport => match procdb {
+                        Some(ref db) => match db.export_to(&ProcessDb::exchange_path()) {
+                            Ok(n) => ctl::ok_kv(&[("profiles", n.to_string())]),
+                            Err(e) => ctl::err(&format!("PROCDB EXPORT FAILED: {}", e)),
+                        },
+                        None => ctl::err("PROCDB IS NOT ACTIVE"),
+                    },
+                    CtlCommand::ProcdbImport { replace } => match procdb {
+                        Some(ref mut db) => {
+                            let path = ProcessDb::exchange_path();
+                            let result = db.import_from(&path, replace);
+                            let _ = std::fs::remove_file(&path);
+                            match result {
+                                Ok((added, merged)) => {
+                                    log_info!(
+                                        "PROCDB: IMPORTED {} NEW + {} MERGED PROFILES{}",
+                                        added,
+                                        merged,
+                                        if replace { " (REPLACED)" } else { "" }
+                                    );
+                                    ctl::ok_kv(&[
+                                        ("added", added.to_string()),
+                                        ("merged", merged.to_string()),
+                                        ("profiles", db.profiles.len().to_string()),
+                                    ])
+                                }
+                                Err(e) => ctl::err(&format!("PROCDB IMPORT FAILED: {}", e)),
+                            }
+                        }
+                        None => ctl::err("PROCDB IS NOT ACTIVE"),
+                    },
+                };
+                let _ = req.reply.send(resp);
+            }
+        }
+
+        // SHARED DSQ DEPTH: A GROWING BACKLOG COUNTS AS SATURATION BELOW;
+        // A DEEP BACKLOG NEXT TO IDLE CPUS IS A DISPATCH BUG
+        let qdepth = sched.read_dsq_depth();
+        let qverdict = qwatch.update(qdepth.total(), idle_pct, scaling.nr_cpus);
+        if qverdict.stuck_onset {
+            log_error!(
+                "SHARED DSQ BACKLOG WITH IDLE CPUS: {} QUEUED (I={} B={}) AT idle={}% -- IDLE CPUS ARE NOT PULLING WORK",
+                qdepth.total(),
+                qdepth.interactive,
+                qdepth.batch,
+                idle_pct
+            );
+        }
+        // ENQUEUES OUTRUNNING DISPATCHES: THE EARLY SIGN OF A DISPATCH STALL
+        let backlog = bwatch.update(delta.backlog_net(), delta.reset);
+        if backlog.warn_onset {
+            log_warn!(
+                "DISPATCH BACKLOG GROWING FOR {}+ TICKS: ~{} ENQUEUES NOT DISPATCHED -- DISPATCH MAY BE STALLING",
+                tuning::BACKLOG_WARN_TICKS,
+                backlog.backlog
+            );
+        }
+
+        // DETECT REGIME (SCHMITT TRIGGER + 2-TICK HOLD).
+        // PINNED OR PAUSED: DETECTION OFF (A PIN ALREADY SET `regime`).
+        // A RESET OR RESUME TICK HAS NO DATA: HOLD EVERYTHING AS IF PAUSED
+        let gate = tuning::tick_decisions(paused || discard, pinned.is_some())
+            .lightweight(sched.lightweight);
+        let detected = if gate.detect_regime {
+            detect_regime(
+                regime,
+                qverdict.regime_idle_pct(tuning::rt_adjusted_idle(
+                    tuning::regime_idle_signal(idle_pct, resid_pct),
+                    rt_pct,
+                )),
+            )
+        } else {
+            regime
+        };
 
         let mut regime_changed_this_tick = false;
         if detected != regime {
@@ -202,76 +935,33 @@ pub fn monitor_loop(
                 regime_hold = 1;
             }
             if regime_hold >= 2 {
+                history.push(RegimeChange {
+                    tick: tick_counter,
+                    from: regime,
+                    to: detected,
+                    idle_pct,
+                    cause: "detect",
+                });
                 regime = detected;
-                sched.write_tuning_knobs(&scaled_regime_knobs(regime, nr_cpus))?;
+                write_knobs(sched, scaled_regime_knobs(regime, scaling.nr_cpus), &held)?;
                 regime_changed_this_tick = true;
-                tightened = false;
-                relax_counter = 0;
-                spike_count = 0;
+                reflex.release();
             }
         } else {
             pending_regime = regime;
             regime_hold = 0;
         }
 
-        // TIGHTEN CHECK: P99 SPIKE DETECTION
-        // REQUIRE 2 CONSECUTIVE ABOVE-CEILING TICKS BEFORE TIGHTENING.
-        // ONLY TIGHTEN IN MIXED: LIGHT HAS NO CONTENTION (POINTLESS),
-        // HEAVY IS FULLY SATURATED (MORE PREEMPTION JUST ADDS OVERHEAD).
-        if !tightened && !regime_changed_this_tick {
-            let ceiling = regime.p99_ceiling();
-            if tuning::should_reflex_tighten(p99_ns, tp99_i_ns, ceiling) {
-                spike_count += 1;
-                if spike_count >= 2 && regime == Regime::Mixed {
-                    let current = sched.read_tuning_knobs();
-                    let new_slice = (current.slice_ns * 3 / 4).max(MIN_SLICE_NS);
-                    let knobs = TuningKnobs {
-                        slice_ns: new_slice,
-                        preempt_thresh_ns: new_slice,
-                        ..current
-                    };
-                    sched.write_tuning_knobs(&knobs)?;
-                    tightened = true;
-                    tighten_events += 1;
-                    spike_count = 0;
-                }
-            } else {
-                spike_count = 0;
-            }
-        }
-
-        // GRADUATED RELAX: STEP SLICE TOWARD BASELINE (BATCH UNTOUCHED)
-        if tightened && !regime_changed_this_tick {
-            let ceiling = regime.p99_ceiling();
-            let baseline = scaled_regime_knobs(regime, nr_cpus);
-            if p99_ns <= ceiling {
-                relax_counter += 1;
-                if relax_counter >= RELAX_HOLD_TICKS {
-                    let current = sched.read_tuning_knobs();
-                    if current.slice_ns < baseline.slice_ns {
-                        let new_slice = (current.slice_ns + RELAX_STEP_NS).min(baseline.slice_ns);
-                        let knobs = TuningKnobs {
-                            slice_ns: new_slice,
-                            preempt_thresh_ns: baseline.preempt_thresh_ns.min(new_slice),
-                            batch_slice_ns: current.batch_slice_ns,
-                            ..baseline
-                        };
-                        sched.write_tuning_knobs(&knobs)?;
-                        if new_slice >= baseline.slice_ns {
-                            tightened = false;
-                        }
-                    } else {
-                        tightened = false;
-                    }
-                    relax_counter = 0;
-                }
-            } else {
-                relax_counter = 0;
-            }
+        // REFLEX: TIGHTEN ON 2 CONSECUTIVE P99 SPIKES (MIXED ONLY), THEN
+        // GRADUATED RELAX TOWARD BASELINE (tuning::Reflex). ONLY UPDATES THE
+        // REQUEST; THE KNOB WRITE BELOW APPLIES IT THIS SAME TICK.
+        if !regime_changed_this_tick {
+            let baseline = scaled_regime_knobs(regime, scaling.nr_cpus);
+            reflex.tick(&gate, regime, p99_ns, tp99_i_ns, &baseline);
         }
 
         // SLEEP-INFORMED BATCH TUNING (EVERY TICK)
-        let baseline = scaled_regime_knobs(regime, nr_cpus);
+        let baseline = scaled_regime_knobs(regime, scaling.nr_cpus);
         let longrun_active = stats.longrun_mode_active > 0;
 
         // LONGRUN OVERRIDE: DURING SUSTAINED BATCH PRESSURE (>2S),
@@ -279,7 +969,8 @@ pub fn monitor_loop(
         // CPU-BOUND, NOT IO-BOUND) AND WEAKEN AFFINITY TO SPREAD BATCH
         // TASKS ACROSS MORE CPUS INSTEAD OF CONCENTRATING ON HOTSPOTS.
         // BURST IS OWNED ENTIRELY BY BPF (CUSUM + WAKEUP RATE IN TICK).
-        let final_batch = if longrun_active {
+        // LIGHTWEIGHT HAS NO SLEEP HISTOGRAM: io=0% WOULD READ AS IDLE-HEAVY
+        let final_batch = if longrun_active || sched.lightweight {
             baseline.batch_slice_ns
         } else {
             tuning::sleep_adjust_batch_ns(baseline.batch_slice_ns, io_pct)
@@ -296,89 +987,268 @@ pub fn monitor_loop(
         // NORMALIZED TO ACTUAL ELAPSED TIME (SLEEP OVERSHOOTS UNDER LOAD).
         const SOJOURN_MULTIPLIER: u64 = 4;         // 4X DISPATCH INTERVAL
 
-        if delta_d > 0 && elapsed_ns > 0 {
+        if !discard && delta_d > 0 && elapsed_ns > 0 {
             let dispatch_rate = delta_d * 1_000_000_000 / elapsed_ns;
             let interval_ns = if dispatch_rate > 0 { 1_000_000_000 / dispatch_rate } else { 0 };
-            let target = (interval_ns * SOJOURN_MULTIPLIER).clamp(sojourn_floor_ns, sojourn_ceil_ns);
+            let target = (interval_ns * SOJOURN_MULTIPLIER).clamp(scaling.sojourn_floor_ns, scaling.sojourn_ceil_ns);
             // EWMA: 7/8 OLD + 1/8 NEW (SMOOTH, NO JITTER)
             sojourn_thresh_ns = sojourn_thresh_ns - (sojourn_thresh_ns >> 3) + (target >> 3);
         }
 
-        {
-            let current = sched.read_tuning_knobs();
-            if current.batch_slice_ns != final_batch
-                || current.sojourn_thresh_ns != sojourn_thresh_ns
-                || current.affinity_mode != final_affinity
-            {
-                sched.write_tuning_knobs(&TuningKnobs {
-                    batch_slice_ns: final_batch,
-                    sojourn_thresh_ns,
-                    affinity_mode: final_affinity,
-                    ..current
-                })?;
-            }
+        // --adaptive-classifier: LAT_CRITICAL SHARE OF THIS TICK'S WAKEUPS
+        // (PER-TIER HISTOGRAM SAMPLE COUNTS) MOVES THE HIGH THRESHOLD
+        if adaptive_classifier && gate.batch_tune {
+            let wakeups = delta_hist.map(|h| h.iter().sum::<u64>());
+            (cls_high, cls_state) = tuning::adjust_classifier_thresholds(
+                cls_high,
+                baseline.lat_cri_thresh_low,
+                wakeups,
+                cls_state,
+            );
+        }
+
+        // THE MONITOR'S KNOB WRITE: REGIME BASELINE + BATCH TUNING + THE
+        // REFLEX REQUEST + CTL-HELD KNOBS, REBUILT FROM SCRATCH EVERY TICK.
+        // MOSTLY UNCHANGED, SO MOSTLY SKIPPED BY THE KNOB WRITER.
+        if gate.batch_tune {
+            let desired = TuningKnobs {
+                batch_slice_ns: final_batch,
+                sojourn_thresh_ns,
+                affinity_mode: final_affinity,
+                lat_cri_thresh_high: cls_high,
+                ..baseline
+            };
+            write_knobs(sched, merge_reflex(desired, reflex.request()), &held)?;
         }
 
         // STABILITY TRACKING
-        let tighten_delta = tighten_events.wrapping_sub(prev_tighten_events);
-        prev_tighten_events = tighten_events;
+        let tighten_delta = reflex.tighten_events.wrapping_sub(prev_tighten_events);
+        prev_tighten_events = reflex.tighten_events;
         stability_score = tuning::compute_stability_score(
             stability_score,
             regime_changed_this_tick,
             tighten_delta,
             p99_ns,
-            regime.p99_ceiling(),
+            reflex.ceiling(regime),
         );
+        let quiet = !discard && tuning::idle_quiet(regime, stability_score, delta_d, elapsed_ns);
+        if stretch.tick(quiet, elapsed_ns) && verbose && stretch.stretched() {
+            log_info!(
+                "IDLE: {}s QUIET, MONITOR TICK STRETCHED TO {}s",
+                tuning::IDLE_STRETCH_AFTER_NS / 1_000_000_000,
+                tuning::STRETCHED_TICK.as_secs()
+            );
+        }
+
+        // ONE OPEN PER DUE TICK: NO BACKOFF SLEEP INSIDE THE LOOP
+        if procdb.is_none() && procdb_retry.due(tick_counter) {
+            match ProcessDb::new(
+                sched.pins.dir(),
+                1,
+                procdb_limits,
+                procdb_seeds,
+                procdb_forces,
+            ) {
+                Ok(db) => {
+                    log_info!(
+                        "PROCDB: ACTIVE AFTER {} FAILED INIT ATTEMPTS",
+                        procdb_retry.failures()
+                    );
+                    procdb = Some(db);
+                }
+                Err(e) => {
+                    procdb_retry.failed(tick_counter);
+                    if verbose {
+                        println!(
+                            "[PROCDB-RETRY] failures={} {:#}",
+                            procdb_retry.failures(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
 
-        // PROCESS CLASSIFICATION DATABASE: INGEST, PREDICT, EVICT
-        let (db_total, db_confident) = if let Some(ref mut db) = procdb {
-            db.ingest();
-            db.flush_predictions();
-            db.tick();
-            db.summary()
+        // PROCESS CLASSIFICATION DATABASE: INGEST, PREDICT, EVICT. ITS MAP
+        // SYSCALLS ARE CAPPED PER TICK; THE REST CARRIES OVER
+        let mut procdb_ops = OpBudget::new(procdb_limits.ops_per_tick);
+        let (db_total, db_confident, db_evicted, db_flushed) = if let Some(ref mut db) = procdb {
+            db.ingest(&mut procdb_ops);
+            let flush = db.flush_within(&mut procdb_ops);
+            if verbose {
+                let flapping = diag::withheld_predictions(&mut last_predictions, &flush.withheld);
+                if !flapping.is_empty() {
+                    println!(
+                        "[PROCDB-FLAP] withheld={} {}",
+                        flapping.len(),
+                        flapping.join(" ")
+                    );
+                }
+                let changes = diag::prediction_changes(&mut last_predictions, &flush.written);
+                if !changes.is_empty() {
+                    println!(
+                        "[PROCDB-FLUSH] wrote={} changed={} {}",
+                        flush.written.len(),
+                        changes.len(),
+                        changes.join(" ")
+                    );
+                }
+            }
+            let evicted = db.tick();
+            if evicted > 0 {
+                last_predictions.retain(|comm, _| db.profiles.contains_key(comm));
+            }
+            let (total, confident) = db.summary();
+            (total, confident, evicted, flush.written.len())
         } else {
-            (0, 0)
+            (0, 0, 0, 0)
         };
+        // CUMULATIVE LIVENESS: STALLS WHEN NO BPF OBSERVATION ARRIVES
+        let db_ingested = procdb.as_ref().map_or(0, |db| db.ingested);
 
         let p99_us = p99_ns / 1000;
-        let tp99_b = tp99_b_ns / 1000;
-        let tp99_i = tp99_i_ns / 1000;
-        let tp99_l = tp99_l_ns / 1000;
         let knobs = sched.read_tuning_knobs();
+        let hybrid_sample = sched.hybrid_tick(&mut hybrid_mon, &per_cpu);
+        // MAP SYSCALLS SINCE THE LAST TICK: THE SCHEDULER'S PLUS PROCDB'S
+        let mut syscalls = sched.take_syscalls();
+        let procdb_syscalls = procdb.as_ref().map(|db| db.syscalls.take());
+        if let Some(c) = &procdb_syscalls {
+            syscalls.merge(c);
+        }
 
-        let sojourn_ms = stats.batch_sojourn_ns / 1_000_000;
-        let sojourn_thresh_ms = sojourn_thresh_ns / 1_000_000;
-        let delta_burst = stats.burst_mode_active.wrapping_sub(prev.burst_mode_active);
-        let burst_label = if delta_burst > 0 { " BURST" } else { "" };
-        let longrun_label = if stats.longrun_mode_active > 0 { " LONGRUN" } else { "" };
+        // GATED OFF (QUIET, HIBERNATING, OR BETWEEN --telemetry-interval
+        // LINES): THE SNAPSHOT BELOW STILL RECORDS, NOTHING IS FORMATTED
+        let print_line = verbose
+            && tuning::should_print_telemetry(tick_counter, stability_score, telemetry_interval);
+        if print_line {
+            // SAMPLES SINCE THE LAST PRINTED LINE, NOT JUST THIS TICK
+            let probe_p99_us = match (probe_hist, probe_window.as_mut()) {
+                (Some(h), Some(w)) => Some(w.p99_since_last(h)),
+                _ => None,
+            };
+            let extras = TelemetryExtras {
+                adaptive: Some(AdaptiveColumns {
+                    p99_us,
+                    tier_p99_us: [tp99_b_ns / 1000, tp99_i_ns / 1000, tp99_l_ns / 1000],
+                    procdb_total: db_total,
+                    procdb_confident: db_confident,
+                    procdb_evicted: db_evicted,
+                    procdb_flushed: db_flushed,
+                    procdb_ingested: db_ingested,
+                    io_pct,
+                    sojourn_thresh_ms: sojourn_thresh_ns / 1_000_000,
+                    regime: regime.label(),
+                    pinned: pinned.is_some(),
+                    paused,
+                }),
+                probe_p99_us,
+                slo: slo_ns.map(|_| slo_met),
+                residency_pct: resid_pct,
+                rt_pct,
+                slice_us: knobs.slice_ns / 1000,
+                batch_us: knobs.batch_slice_ns / 1000,
+                cls_high: knobs.lat_cri_thresh_high,
+                cls_low: knobs.lat_cri_thresh_low,
+                qdepth,
+                backlog: backlog.backlog,
+                backlog_warn: backlog.warn,
+                lightweight: sched.lightweight,
+                governor: Some(gov_watch.label().to_string()),
+            };
+            println!("{}", telemetry_tick(&delta, &stats, &extras).render());
+            if let Some((fast, slow)) = hybrid_sample {
+                println!("{}", hybrid::format_line(&fast, &slow));
+            }
+            let mut fields = syscalls.fields().to_vec();
+            if let (Some(c), Some(db)) = (&procdb_syscalls, &procdb) {
+                fields.extend([
+                    ("procdb", c.total()),
+                    ("capped", procdb_ops.capped() as u64),
+                    ("backlog", db.backlog() as u64),
+                ]);
+            }
+            println!("{}", diag::format_kv_line("SYSCALLS", &fields));
+        }
 
-        if verbose && tuning::should_print_telemetry(tick_counter, stability_score) {
+        // --verbose: CUMULATIVE COUNTERS + APPLIED LAG EVERY 10 TICKS, KNOBS ON EVERY CHANGE
+        let knobs_changed = prev_knobs != Some(knobs);
+        if verbose && diag::should_dump_stats(tick_counter) {
+            println!("{}", diag::format_kv_line("STATS", &stats.fields()));
+            if let Some(r) = &resid {
+                println!("{}", r.format_line());
+            }
+            let lag = sched.read_lag_hist();
             println!(
-                "d/s: {:<8} idle: {}% shared: {:<6} preempt: {:<4} keep: {:<4} kick: H={:<4} S={:<4} enq: W={:<4} R={:<4} wake: {}us p99: {}us [B:{} I:{} L:{}] lat_idle: {}us lat_kick: {}us procdb: {}/{} sleep: io={}% slice: {}us batch: {}us reenq: {} sjrn: {}ms/{}ms rescue: {} l2: B={}% I={}% L={}% [{}{}{}]",
-                delta_d, idle_pct, delta_shared, delta_preempt, delta_keep,
-                delta_hard, delta_soft, delta_enq_wake, delta_enq_requeue,
-                wake_avg_us, p99_us, tp99_b, tp99_i, tp99_l,
-                lat_idle_us, lat_kick_us,
-                db_total, db_confident,
-                io_pct, knobs.slice_ns / 1000, knobs.batch_slice_ns / 1000,
-                delta_reenq, sojourn_ms, sojourn_thresh_ms,
-                delta_rescue,
-                l2_pct_b, l2_pct_i, l2_pct_l, regime.label(), burst_label, longrun_label,
+                "{}",
+                diag::format_lag_summary(knobs.lag_scale, &lag.delta(&prev_lag))
             );
+            prev_lag = lag;
+        }
+        if verbose && victims::should_dump_victims(tick_counter) {
+            if !sched.lightweight {
+                for line in victim_table.format_lines(victims::TOP_VICTIMS) {
+                    println!("{}", line);
+                }
+            }
+            for line in victim_table.preempted_lines(victims::TOP_VICTIMS, tick_counter + 1) {
+                println!("{}", line);
+            }
+        }
+        if verbose && knobs_changed {
+            let fields: Vec<(&str, u64)> = tuning::KNOB_NAMES
+                .iter()
+                .copied()
+                .zip(knobs.values())
+                .collect();
+            println!("{}", diag::format_kv_line("KNOB-CHANGE", &fields));
         }
 
-        sched.log.snapshot(
-            delta_d,
-            delta_idle,
-            delta_shared,
-            delta_preempt,
-            delta_keep,
-            wake_avg_us,
-            delta_hard,
-            delta_soft,
-            lat_idle_us,
-            lat_kick_us,
-        );
+        if !discard {
+            sched.log.paths.record(&delta);
+            sched.log.wake_hist(&delta_hist);
+        }
+        sched.log.snapshot(TickCounters::from_delta(&delta));
+
+        // BLACK BOX: EVERY TICK, PLUS THE FULL KNOB SET WHEN IT CHANGES
+        if let (Some(bb), Some(snap)) = (blackbox.as_deref_mut(), sched.log.latest()) {
+            bb.record(Record::snapshot(
+                snap,
+                p99_us,
+                regime as u64,
+                knobs.slice_ns,
+            ));
+            if knobs_changed {
+                bb.record(Record::knobs(snap.ts_ns, knobs.values(), regime as u64));
+            }
+            bb.sync(false);
+        }
+        prev_knobs = Some(knobs);
+
+        // --dbus: REGIME + P99 PROPERTIES (THE SERVICE DROPS REPEATS)
+        if let Some(tx) = live_tx {
+            let _ = tx.send(LiveStatus { regime, p99_us });
+        }
+
+        // SIGUSR1: ON-DEMAND DIAGNOSTIC SNAPSHOT
+        if diag_requested.swap(false, Ordering::Relaxed) {
+            let st = DiagState {
+                regime,
+                pinned,
+                paused,
+                tightened: reflex.tightened,
+                tighten_events: reflex.tighten_events,
+                tick: tick_counter,
+                p99_ns,
+                history: &history,
+                held: &held,
+                config: run_config,
+                victims: &victim_table,
+            };
+            match write_diag(sched, &st, procdb.as_ref()) {
+                Ok(path) => println!("DIAG: {}", path),
+                Err(e) => log_warn!("DIAG DUMP FAILED: {}", e),
+            }
+        }
 
         match regime {
             Regime::Light => light_ticks += 1,
@@ -399,47 +1269,50 @@ pub fn monitor_loop(
             Ok(()) => {
                 let (total, confident) = db.summary();
                 log_info!(
-                    "PROCDB: SAVED {}/{} PROFILES TO {}",
+                    "PROCDB: SAVED {}/{} PROFILES TO {} ({} EVICTED THIS RUN, CAP {})",
                     confident,
                     total,
-                    path.display()
+                    path.display(),
+                    db.evictions,
+                    db.limits.max_profiles
                 );
             }
             Err(e) => log_warn!("PROCDB SAVE FAILED: {}", e),
         }
     }
 
+    // REGIME RESIDENCY AND REFLEX COUNTS FOR THE SHUTDOWN SUMMARY
+    sched.log.adaptive = Some(AdaptiveTally {
+        regime_ticks: [light_ticks, mixed_ticks, heavy_ticks],
+        tighten_events: reflex.tighten_events,
+        relax_steps: reflex.relax_steps,
+    });
+
     // KNOBS SUMMARY: CAPTURED BY TEST HARNESS FOR ARCHIVE
     let final_knobs = sched.read_tuning_knobs();
     let final_stats = sched.read_stats();
-    let l2_total_b = final_stats.nr_l2_hit_batch + final_stats.nr_l2_miss_batch;
-    let l2_total_i = final_stats.nr_l2_hit_interactive + final_stats.nr_l2_miss_interactive;
-    let l2_total_l = final_stats.nr_l2_hit_lat_crit + final_stats.nr_l2_miss_lat_crit;
-    let l2_cum_b = if l2_total_b > 0 {
-        final_stats.nr_l2_hit_batch * 100 / l2_total_b
-    } else {
-        0
-    };
-    let l2_cum_i = if l2_total_i > 0 {
-        final_stats.nr_l2_hit_interactive * 100 / l2_total_i
-    } else {
-        0
-    };
-    let l2_cum_l = if l2_total_l > 0 {
-        final_stats.nr_l2_hit_lat_crit * 100 / l2_total_l
-    } else {
-        0
-    };
+    let [l2_cum_b, l2_cum_i, l2_cum_l] = final_stats.l2_hit_pct();
+    let writes = sched.knob_write_counts();
     println!(
-        "[KNOBS] regime={} slice_ns={} batch_ns={} preempt_ns={} demotion_ns={} lag={} tightened={} tighten_events={} ticks=L:{}/M:{}/H:{} l2_hit=B:{}%/I:{}%/L:{}%",
-        regime.label(), final_knobs.slice_ns, final_knobs.batch_slice_ns,
+        "[KNOBS] regime={} slice_ns={} slice_min_ns={} slice_max_ns={} batch_ns={} preempt_ns={} demotion_ns={} lag={} tightened={} tighten_events={} ticks=L:{}/M:{}/H:{} l2_hit=B:{}%/I:{}%/L:{}% qdepth_max={} backlog_max={} knob_writes={} knob_skips={}",
+        regime.label(), final_knobs.slice_ns,
+        sched.slice_bounds.min_ns, sched.slice_bounds.max_ns, final_knobs.batch_slice_ns,
         final_knobs.preempt_thresh_ns, final_knobs.cpu_bound_thresh_ns,
-        final_knobs.lag_scale, tightened, tighten_events,
+        final_knobs.lag_scale, reflex.tightened, reflex.tighten_events,
         light_ticks, mixed_ticks, heavy_ticks,
-        l2_cum_b, l2_cum_i, l2_cum_l,
+        l2_cum_b, l2_cum_i, l2_cum_l, qwatch.max(), bwatch.max(),
+        writes.written, writes.skipped,
     );
+    if let Some(target) = slo_ns {
+        println!("{}", slo.line(target));
+    }
+    if let (Some(h), Some(w)) = (probe_hist, probe_window.as_ref()) {
+        let bpf_p99_us = tuning::pooled_p99_ns(&sched.read_wake_lat_hist()) / 1000;
+        let summary =
+            SelfProbeSummary::new(&w.since_start(h), final_stats.wake_avg_us(), bpf_p99_us);
+        println!("{}", summary.line());
+    }
 
     // READ UEI EXIT REASON
-    let should_restart = sched.read_exit_info();
-    Ok(should_restart)
+    Ok(sched.read_exit_info())
 }
@@ -0,0 +1,118 @@
+// PANDEMONIUM ATTACH PRE-CHECK
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// ONLY ONE sched_ext SCHEDULER CAN OWN THE ROOT. IF scx_lavd (OR A STALE
+// PANDEMONIUM) IS ATTACHED, attach_struct_ops() FAILS WITH A BARE ERRNO
+// AND A LIBBPF STACK TRACE. READ /sys/kernel/sched_ext/root/ops BEFORE
+// LOAD AND NAME THE OWNER INSTEAD. WITH --takeover, WAIT (BOUNDED) FOR
+// IT TO DETACH -- UNLESS A MANAGER LIKE scx_loader WOULD JUST RESTART IT.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+pub const SCX_OPS_PATH: &str = "/sys/kernel/sched_ext/root/ops";
+
+// DAEMONS THAT RESPAWN/SWITCH SCHEDULERS ON THEIR OWN (/proc/<pid>/comm)
+pub const KNOWN_MANAGERS: &[&str] = &["scx_loader"];
+
+pub const TAKEOVER_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+// EMPTY OPS FILE: NO SCHEDULER ATTACHED
+pub fn parse_ops(contents: &str) -> Option<String> {
+    let name = contents.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+// None ALSO WHEN sched_ext IS NOT BUILT IN: THE LOAD REPORTS THAT ITSELF
+pub fn active_scheduler() -> Option<String> {
+    std::fs::read_to_string(SCX_OPS_PATH)
+        .ok()
+        .and_then(|s| parse_ops(&s))
+}
+
+// FIRST KNOWN MANAGER FOUND UNDER A /proc-SHAPED DIRECTORY
+pub fn find_manager_in(proc_root: &Path) -> Option<&'static str> {
+    let entries = std::fs::read_dir(proc_root).ok()?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str() else {
+            continue;
+        };
+        if !pid.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(comm) = std::fs::read_to_string(entry.path().join("comm")) else {
+            continue;
+        };
+        let comm = comm.trim();
+        if let Some(m) = KNOWN_MANAGERS.iter().find(|m| **m == comm) {
+            return Some(m);
+        }
+    }
+    None
+}
+
+pub fn find_manager() -> Option<&'static str> {
+    find_manager_in(Path::new("/proc"))
+}
+
+pub fn busy_message(ops: &str) -> String {
+    format!(
+        "SCHED_EXT IS ALREADY IN USE BY '{}'. STOP IT FIRST, OR PASS --takeover TO WAIT FOR IT TO DETACH",
+        ops
+    )
+}
+
+pub fn managed_message(ops: &str, manager: &str) -> String {
+    format!(
+        "SCHED_EXT IS IN USE BY '{}' UNDER {}, WHICH WOULD REATTACH IT. RUN `scxctl stop` (OR `sudo systemctl stop {}`) FIRST",
+        ops, manager, manager
+    )
+}
+
+// POLL read_ops UNTIL IT REPORTS NO SCHEDULER OR timeout ELAPSES.
+// progress(NAME, ELAPSED) IS CALLED ON THE FIRST POLL, THEN EVERY FEW SECONDS.
+pub fn wait_for_detach(
+    timeout: Duration,
+    mut read_ops: impl FnMut() -> Option<String>,
+    mut progress: impl FnMut(&str, Duration),
+) -> Result<()> {
+    let start = Instant::now();
+    let mut last_report: Option<Instant> = None;
+    loop {
+        let Some(ops) = read_ops() else {
+            return Ok(());
+        };
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            bail!("'{}' DID NOT DETACH WITHIN {}S", ops, timeout.as_secs());
+        }
+        if last_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            progress(&ops, elapsed);
+            last_report = Some(Instant::now());
+        }
+        std::thread::sleep(POLL_INTERVAL.min(timeout - elapsed));
+    }
+}
+
+// FULL PRE-CHECK: Ok ONCE THE ROOT IS FREE
+pub fn ensure_free(takeover: bool, progress: impl FnMut(&str, Duration)) -> Result<()> {
+    let Some(ops) = active_scheduler() else {
+        return Ok(());
+    };
+    if let Some(manager) = find_manager() {
+        bail!("{}", managed_message(&ops, manager));
+    }
+    if !takeover {
+        bail!("{}", busy_message(&ops));
+    }
+    wait_for_detach(TAKEOVER_TIMEOUT, active_scheduler, progress)
+}
@@ -0,0 +1,274 @@
+// PANDEMONIUM BENCH RE-ANALYSIS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE BENCH REPORT KEEPS MEDIAN / P99 / WORST; THE PHASE'S RAW PROBE
+// SAMPLES SIT NEXT TO IT (benchout.rs). `pandemonium bench-analyze DIR`
+// READS THEM BACK: ANY PERCENTILES, AN ASCII CDF PER PHASE, AND A
+// MANN-WHITNEY U TEST BETWEEN TWO PHASES. RANK-BASED, SO THE LONG TAIL OF
+// A LATENCY DISTRIBUTION DOES NOT SWAMP IT THE WAY IT WOULD A t-TEST.
+// PERCENTILES USE probe::percentile, SO P50 / P99 MATCH THE REPORT'S.
+
+use std::path::Path;
+
+use crate::benchout::{phase_slug, read_samples, sample_files};
+use crate::probe::{parse_probe_output, percentile};
+
+pub const DEFAULT_PERCENTILES: [f64; 4] = [50.0, 90.0, 99.0, 99.9];
+pub const CDF_WIDTH: usize = 50;
+pub const SIGNIFICANCE: f64 = 0.05;
+
+// ONE PHASE'S SAMPLES, EVERY PROBE THREAD POOLED, SORTED
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phase {
+    pub name: String,
+    pub samples: Vec<f64>,
+}
+
+impl Phase {
+    // THE PROBE'S OWN OUTPUT: RAW OR hist LINES, ONE OR MORE THREADS.
+    // A NON-FINITE SAMPLE OR AN OVERSIZED hist COUNT IS AN ERROR.
+    pub fn from_text(name: &str, text: &str) -> anyhow::Result<Self> {
+        let mut samples: Vec<f64> = parse_probe_output(text)?.into_values().flatten().collect();
+        samples.sort_by(f64::total_cmp);
+        Ok(Self {
+            name: name.to_string(),
+            samples,
+        })
+    }
+
+    pub fn percentile(&self, p: f64) -> f64 {
+        percentile(&self.samples, p)
+    }
+}
+
+// EVERY PHASE WITH A SAMPLE FILE IN dir, BY NAME
+pub fn load(dir: &Path) -> anyhow::Result<Vec<Phase>> {
+    let files =
+        sample_files(dir).map_err(|e| anyhow::anyhow!("CANNOT READ {}: {}", dir.display(), e))?;
+    if files.is_empty() {
+        anyhow::bail!(
+            "NO PROBE SAMPLES IN {} (ONLY --mode contention AND io RECORD THEM)",
+            dir.display()
+        );
+    }
+    files
+        .into_iter()
+        .map(|(name, path)| {
+            let text = read_samples(&path)
+                .map_err(|e| anyhow::anyhow!("CANNOT READ {}: {}", path.display(), e))?;
+            Phase::from_text(&name, &text).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+        })
+        .collect()
+}
+
+// A PHASE BY ITS SLUG OR ITS REPORT NAME ("EEVDF (DEFAULT)" FINDS eevdf)
+pub fn find<'a>(phases: &'a [Phase], name: &str) -> anyhow::Result<&'a Phase> {
+    let slug = phase_slug(name);
+    phases
+        .iter()
+        .find(|p| p.name == name || p.name == slug)
+        .ok_or_else(|| {
+            let known: Vec<&str> = phases.iter().map(|p| p.name.as_str()).collect();
+            anyhow::anyhow!("NO PHASE '{}' (HAVE: {})", name, known.join(", "))
+        })
+}
+
+pub fn check_percentiles(ps: &[f64]) -> anyhow::Result<()> {
+    if ps.is_empty() {
+        anyhow::bail!("--percentiles: NEED AT LEAST ONE");
+    }
+    if let Some(p) = ps.iter().find(|p| !(**p > 0.0 && **p <= 100.0)) {
+        anyhow::bail!("--percentiles: {} IS NOT IN (0, 100]", p);
+    }
+    Ok(())
+}
+
+// "P99.9" FOR A HEADER
+fn percentile_label(p: f64) -> String {
+    format!("P{}", p)
+}
+
+// ONE ROW PER PHASE: SAMPLES, THEN EACH PERCENTILE IN us
+pub fn percentile_table(phases: &[Phase], ps: &[f64]) -> Vec<String> {
+    let mut header = format!("{:<16} {:>9}", "PHASE", "SAMPLES");
+    for &p in ps {
+        header.push_str(&format!(" {:>9}", percentile_label(p)));
+    }
+    let mut lines = vec![header];
+    for phase in phases {
+        let mut row = format!("{:<16} {:>9}", phase.name, phase.samples.len());
+        for &p in ps {
+            row.push_str(&format!(" {:>7.0}us", phase.percentile(p)));
+        }
+        lines.push(row);
+    }
+    lines
+}
+
+// CUMULATIVE SHARE AT EACH POWER-OF-TWO us EDGE, FROM 1us TO THE FIRST
+// EDGE AT OR ABOVE THE WORST SAMPLE. A BIMODAL PHASE SHOWS AS TWO STEEP
+// STRETCHES WITH A FLAT ONE BETWEEN.
+pub fn cdf(phase: &Phase, width: usize) -> Vec<String> {
+    let n = phase.samples.len();
+    if n == 0 {
+        return vec![format!("{}: NO SAMPLES", phase.name)];
+    }
+    let worst = phase.samples[n - 1];
+    let mut lines = vec![format!("{} ({} samples)", phase.name, n)];
+    let mut edge = 1.0f64;
+    loop {
+        let below = phase.samples.partition_point(|&v| v <= edge);
+        let frac = below as f64 / n as f64;
+        let bar = (frac * width as f64).round() as usize;
+        lines.push(format!(
+            "  <= {:>8.0}us |{:<width$}| {:>5.1}%",
+            edge,
+            "#".repeat(bar),
+            frac * 100.0,
+            width = width
+        ));
+        if edge >= worst {
+            break;
+        }
+        edge *= 2.0;
+    }
+    lines
+}
+
+// MANN-WHITNEY U, NORMAL APPROXIMATION WITH TIE CORRECTION. u IS a's
+// STATISTIC; p IS TWO-SIDED. a_over_b = u / (n_a * n_b): THE CHANCE A
+// RANDOM a SAMPLE EXCEEDS A RANDOM b SAMPLE (TIES HALF), 0.5 = NO SHIFT.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MannWhitney {
+    pub u: f64,
+    pub z: f64,
+    pub p: f64,
+    pub a_over_b: f64,
+}
+
+impl MannWhitney {
+    pub fn significant(&self) -> bool {
+        self.p < SIGNIFICANCE
+    }
+}
+
+// None WHEN EITHER SIDE IS EMPTY
+pub fn mann_whitney(a: &[f64], b: &[f64]) -> Option<MannWhitney> {
+    let (na, nb) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    // (VALUE, FROM a), RANKED TOGETHER; TIES SHARE THEIR AVERAGE RANK
+    let mut all: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&v| (v, true))
+        .chain(b.iter().map(|&v| (v, false)))
+        .collect();
+    all.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let n = all.len() as f64;
+    let mut rank_sum_a = 0.0;
+    let mut ties = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        // RANKS i+1 ..= j
+        let rank = (i + 1 + j) as f64 / 2.0;
+        rank_sum_a += rank * all[i..j].iter().filter(|s| s.1).count() as f64;
+        let t = (j - i) as f64;
+        ties += t * t * t - t;
+        i = j;
+    }
+    let u = rank_sum_a - na * (na + 1.0) / 2.0;
+    let mean = na * nb / 2.0;
+    let var = na * nb / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+    let z = if var > 0.0 {
+        (u - mean) / var.sqrt()
+    } else {
+        0.0
+    };
+    Some(MannWhitney {
+        u,
+        z,
+        p: erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0),
+        a_over_b: u / (na * nb),
+    })
+}
+
+// COMPLEMENTARY ERROR FUNCTION, CHEBYSHEV FIT (FRACTIONAL ERROR < 1.2e-7)
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+pub fn format_p(p: f64) -> String {
+    if p < 1e-4 {
+        "p<0.0001".to_string()
+    } else {
+        format!("p={:.4}", p)
+    }
+}
+
+// "eevdf VS pandemonium: U=... z=-3.21 p=0.0013 P(eevdf>pandemonium)=0.58 SIGNIFICANT (p < 0.05)"
+pub fn compare_line(a: &Phase, b: &Phase) -> String {
+    let Some(t) = mann_whitney(&a.samples, &b.samples) else {
+        return format!("{} VS {}: NO SAMPLES TO COMPARE", a.name, b.name);
+    };
+    format!(
+        "{} VS {}: U={:.0} z={:.2} {} P({}>{})={:.3} {}",
+        a.name,
+        b.name,
+        t.u,
+        t.z,
+        format_p(t.p),
+        a.name,
+        b.name,
+        t.a_over_b,
+        if t.significant() {
+            format!("SIGNIFICANT (p < {})", SIGNIFICANCE)
+        } else {
+            "NOT SIGNIFICANT".to_string()
+        }
+    )
+}
+
+// THE WHOLE bench-analyze OUTPUT: TABLE, CDFs, THEN THE COMPARISONS
+pub fn report(phases: &[Phase], ps: &[f64], pairs: &[(&Phase, &Phase)]) -> Vec<String> {
+    let mut lines = percentile_table(phases, ps);
+    for phase in phases {
+        lines.push(String::new());
+        lines.extend(cdf(phase, CDF_WIDTH));
+    }
+    if !pairs.is_empty() {
+        lines.push(String::new());
+        lines.extend(pairs.iter().map(|(a, b)| compare_line(a, b)));
+    }
+    lines
+}
+
+// WITHOUT --compare: EVERY PAIR, IN NAME ORDER
+pub fn all_pairs(phases: &[Phase]) -> Vec<(&Phase, &Phase)> {
+    let mut pairs = Vec::new();
+    for (i, a) in phases.iter().enumerate() {
+        for b in &phases[i + 1..] {
+            pairs.push((a, b));
+        }
+    }
+    pairs
+}
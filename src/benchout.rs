@@ -0,0 +1,292 @@
+// PANDEMONIUM BENCH OUTPUT DIRECTORY
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// EVERY `pandemonium bench` WRITES INTO ONE DIRECTORY:
+//   build.log              --build: cargo build --release OUTPUT
+//   sched-<phase>.log      SCHEDULER stdout + stderr FOR A PANDEMONIUM PHASE
+//   probe-<phase>.txt.gz   RAW PROBE SAMPLES, GZIPPED (contention, io);
+//                          report.json NAMES EACH PHASE'S FILE, bench-analyze READS THEM
+//   report.txt             THE REPORT AS PRINTED
+//   report.json            THE SAME NUMBERS, MACHINE-READABLE
+// DEFAULT: /tmp/pandemonium/bench-<STAMP>. bench-latest NEXT TO IT POINTS AT
+// THE NEWEST RUN; --keep-logs N KEEPS THE NEWEST N bench-* DIRECTORIES.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const BENCH_DIR_PREFIX: &str = "bench-";
+pub const LATEST_LINK: &str = "bench-latest";
+pub const DEFAULT_KEEP_LOGS: u64 = 20;
+
+const SAMPLES_PREFIX: &str = "probe-";
+const SAMPLES_SUFFIX: &str = ".txt.gz";
+// BEFORE THE SAMPLES WERE COMPRESSED: STILL READ
+const PLAIN_SUFFIX: &str = ".txt";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchLayout {
+    dir: PathBuf,
+}
+
+impl BenchLayout {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    // <log_dir>/bench-<stamp>: STAMPS ARE YYYYMMDD-HHMMSS, SO NAMES SORT BY AGE
+    pub fn stamped(log_dir: &Path, stamp: &str) -> Self {
+        Self::new(log_dir.join(format!("{}{}", BENCH_DIR_PREFIX, stamp)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn build_log(&self) -> PathBuf {
+        self.dir.join("build.log")
+    }
+
+    pub fn report_txt(&self) -> PathBuf {
+        self.dir.join("report.txt")
+    }
+
+    pub fn report_json(&self) -> PathBuf {
+        self.dir.join("report.json")
+    }
+
+    pub fn sched_log(&self, phase: &str) -> PathBuf {
+        self.dir.join(format!("sched-{}.log", phase_slug(phase)))
+    }
+
+    pub fn probe_samples(&self, phase: &str) -> PathBuf {
+        self.dir.join(samples_file(phase))
+    }
+}
+
+// THE NAME report.json CARRIES: RELATIVE, SO A COPIED DIRECTORY STILL RESOLVES
+pub fn samples_file(phase: &str) -> String {
+    format!("{}{}{}", SAMPLES_PREFIX, phase_slug(phase), SAMPLES_SUFFIX)
+}
+
+// THE PROBE'S STDOUT AS-IS, GZIPPED: A CONTENTION PHASE IS ~1MB OF TEXT
+pub fn write_samples(path: &Path, text: &str) -> std::io::Result<()> {
+    let file = std::fs::File::create(path)?;
+    let mut gz = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    gz.write_all(text.as_bytes())?;
+    gz.finish()?;
+    Ok(())
+}
+
+// .gz DECOMPRESSED, ANYTHING ELSE (AN OLDER probe-<phase>.txt) AS-IS
+pub fn read_samples(path: &Path) -> std::io::Result<String> {
+    let file = std::fs::File::open(path)?;
+    let mut text = String::new();
+    if path.extension().is_some_and(|e| e == "gz") {
+        flate2::read::GzDecoder::new(file).read_to_string(&mut text)?;
+    } else {
+        std::io::BufReader::new(file).read_to_string(&mut text)?;
+    }
+    Ok(text)
+}
+
+// (PHASE SLUG, FILE) FOR EVERY SAMPLE FILE IN dir, BY NAME. A PHASE WITH
+// BOTH FORMS IS READ FROM THE .gz.
+pub fn sample_files(dir: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(rest) = name.strip_prefix(SAMPLES_PREFIX) else {
+            continue;
+        };
+        let (phase, gz) = match rest.strip_suffix(SAMPLES_SUFFIX) {
+            Some(phase) => (phase, true),
+            None => match rest.strip_suffix(PLAIN_SUFFIX) {
+                Some(phase) => (phase, false),
+                None => continue,
+            },
+        };
+        match files.iter_mut().find(|(p, _)| p == phase) {
+            Some(slot) if gz => slot.1 = path,
+            Some(_) => {}
+            None => files.push((phase.to_string(), path)),
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// FILE-NAME FORM OF A REPORT PHASE NAME: "EEVDF (DEFAULT)" -> "eevdf"
+pub fn phase_slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|w| !w.is_empty())
+        .unwrap_or("phase")
+        .to_ascii_lowercase()
+}
+
+// REPOINT <log_dir>/bench-latest AT target (REPLACES AN OLD LINK)
+pub fn point_latest(log_dir: &Path, target: &Path) -> std::io::Result<()> {
+    let link = log_dir.join(LATEST_LINK);
+    match std::fs::remove_file(&link) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    std::os::unix::fs::symlink(target, &link)
+}
+
+// bench-* DIRECTORIES UNDER log_dir, OLDEST FIRST (THE LINK IS NOT ONE)
+pub fn bench_dirs(log_dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = rd
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(BENCH_DIR_PREFIX) && n != LATEST_LINK)
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+// REMOVE ALL BUT THE NEWEST keep bench-* DIRECTORIES; RETURNS WHAT WENT
+pub fn prune_bench_dirs(log_dir: &Path, keep: usize) -> Vec<PathBuf> {
+    let dirs = bench_dirs(log_dir);
+    let excess = dirs.len().saturating_sub(keep);
+    dirs.into_iter()
+        .take(excess)
+        .filter(|d| std::fs::remove_dir_all(d).is_ok())
+        .collect()
+}
+
+// MINIMAL JSON VALUE FOR report.json (NO serde IN THIS CRATE). OBJECT KEYS
+// KEEP INSERTION ORDER SO THE FILE READS LIKE THE TEXT REPORT.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn str(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+
+    pub fn obj(fields: Vec<(&str, Json)>) -> Self {
+        Self::Obj(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    // TWO-SPACE INDENT, TRAILING NEWLINE
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    // ONE LINE, NO PADDING, TRAILING NEWLINE (LINE-ORIENTED READERS: WAYBAR)
+    pub fn render_line(&self) -> String {
+        let mut out = String::new();
+        self.write_line(&mut out);
+        out.push('\n');
+        out
+    }
+
+    fn write_line(&self, out: &mut String) {
+        match self {
+            Self::Arr(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write_line(out);
+                }
+                out.push(']');
+            }
+            Self::Obj(fields) => {
+                out.push('{');
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write_escaped(out, k);
+                    out.push_str(": ");
+                    v.write_line(out);
+                }
+                out.push('}');
+            }
+            scalar => scalar.write(out, 0),
+        }
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        let pad = |d: usize| "  ".repeat(d);
+        match self {
+            Self::Null => out.push_str("null"),
+            Self::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            // NaN / inf HAVE NO JSON SPELLING
+            Self::Num(v) if !v.is_finite() => out.push_str("null"),
+            Self::Num(v) if v.fract() == 0.0 && v.abs() < 1e15 => {
+                out.push_str(&format!("{}", *v as i64))
+            }
+            Self::Num(v) => out.push_str(&format!("{}", v)),
+            Self::Str(s) => write_escaped(out, s),
+            Self::Arr(items) if items.is_empty() => out.push_str("[]"),
+            Self::Arr(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&pad(depth + 1));
+                    item.write(out, depth + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&pad(depth));
+                out.push(']');
+            }
+            Self::Obj(fields) if fields.is_empty() => out.push_str("{}"),
+            Self::Obj(fields) => {
+                out.push_str("{\n");
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    out.push_str(&pad(depth + 1));
+                    write_escaped(out, k);
+                    out.push_str(": ");
+                    v.write(out, depth + 1);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&pad(depth));
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_escaped(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,338 @@
+// PANDEMONIUM BLACK BOX RECORDER
+// CRASH-SAFE RING FILE OF THE LAST ~500 MONITOR TICKS + KNOB CHANGES
+//
+// WHEN THE KERNEL ABORTS THE BPF SCHEDULER, THE INTERESTING DATA IS THE
+// SECONDS BEFORE THE ABORT. THE EventLog ONLY LIVES IN PROCESS MEMORY;
+// THIS FILE IS MAP_SHARED, SO EVERY STORE IS IN THE PAGE CACHE THE MOMENT
+// IT IS MADE AND SURVIVES SIGKILL. msync() PUSHES IT TOWARD DISK EACH TICK.
+//
+// LAYOUT (LITTLE-ENDIAN):
+//   HEADER (64B): MAGIC "PDBB", VERSION, SLOTS, SLOT_SIZE, CLEAN, PID,
+//                 START_S (REALTIME), NEXT_SEQ
+//   SLOTS (128B EACH): SEQ, TS_NS, KIND, CRC, 13 x u64 PAYLOAD
+// A SLOT IS VALID ONLY IF SEQ != 0 AND ITS CRC MATCHES: A WRITE TORN BY
+// SIGKILL OR POWER LOSS IS DETECTED AND SKIPPED, NEVER MISDECODED.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::event::Snapshot;
+
+pub const BLACKBOX_PATH: &str = "/var/lib/pandemonium/blackbox.bin";
+pub const BLACKBOX_UNCLEAN_PATH: &str = "/var/lib/pandemonium/blackbox-unclean.bin";
+
+const MAGIC: &[u8; 4] = b"PDBB";
+const VERSION: u32 = 1;
+pub const HEADER_SIZE: usize = 64;
+pub const SLOT_SIZE: usize = 128;
+pub const SLOTS: usize = 512;
+pub const FILE_SIZE: usize = HEADER_SIZE + SLOTS * SLOT_SIZE;
+pub const PAYLOAD_WORDS: usize = 13;
+
+// HEADER FIELD OFFSETS
+const H_CLEAN: usize = 16;
+const H_NEXT_SEQ: usize = 32;
+
+pub const KIND_SNAPSHOT: u32 = 1;
+pub const KIND_KNOBS: u32 = 2;
+
+// REGIME CODE FOR TICKS RECORDED IN --no-adaptive MODE
+pub const REGIME_BPF_ONLY: u64 = 255;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub seq: u64,
+    pub ts_ns: u64,
+    pub kind: u32,
+    pub data: [u64; PAYLOAD_WORDS],
+}
+
+impl Record {
+    // SNAPSHOT PAYLOAD: THE 10 TickCounters FIELDS, P99, REGIME, SLICE
+    pub fn snapshot(s: &Snapshot, p99_us: u64, regime: u64, slice_ns: u64) -> Self {
+        let t = &s.tick;
+        Self {
+            seq: 0,
+            ts_ns: s.ts_ns,
+            kind: KIND_SNAPSHOT,
+            data: [
+                t.dispatches,
+                t.idle_hits,
+                t.shared,
+                t.preempt,
+                t.keep_run,
+                t.wake_avg_us,
+                t.hard_kicks,
+                t.soft_kicks,
+                t.lat_idle_us,
+                t.lat_kick_us,
+                p99_us,
+                regime,
+                slice_ns,
+            ],
+        }
+    }
+
+    // KNOBS PAYLOAD: ALL 10 TUNING KNOBS (STRUCT ORDER), REGIME
+    pub fn knobs(ts_ns: u64, knobs: [u64; 10], regime: u64) -> Self {
+        let mut data = [0u64; PAYLOAD_WORDS];
+        data[..10].copy_from_slice(&knobs);
+        data[10] = regime;
+        Self {
+            seq: 0,
+            ts_ns,
+            kind: KIND_KNOBS,
+            data,
+        }
+    }
+}
+
+// FNV-1a 32: CHEAP, NO DEPENDENCY, PLENTY TO CATCH A TORN 128-BYTE WRITE
+fn fnv1a(chunks: &[&[u8]]) -> u32 {
+    let mut h: u32 = 0x811c9dc5;
+    for c in chunks {
+        for &b in *c {
+            h ^= b as u32;
+            h = h.wrapping_mul(0x01000193);
+        }
+    }
+    h
+}
+
+fn rd_u32(b: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes(b[off..off + 4].try_into().unwrap())
+}
+
+fn rd_u64(b: &[u8], off: usize) -> u64 {
+    u64::from_le_bytes(b[off..off + 8].try_into().unwrap())
+}
+
+pub fn encode_slot(r: &Record) -> [u8; SLOT_SIZE] {
+    let mut out = [0u8; SLOT_SIZE];
+    out[0..8].copy_from_slice(&r.seq.to_le_bytes());
+    out[8..16].copy_from_slice(&r.ts_ns.to_le_bytes());
+    out[16..20].copy_from_slice(&r.kind.to_le_bytes());
+    for (i, w) in r.data.iter().enumerate() {
+        let off = 24 + i * 8;
+        out[off..off + 8].copy_from_slice(&w.to_le_bytes());
+    }
+    let crc = fnv1a(&[&out[0..20], &out[24..]]);
+    out[20..24].copy_from_slice(&crc.to_le_bytes());
+    out
+}
+
+// None: EMPTY SLOT OR FAILED CRC
+pub fn decode_slot(b: &[u8]) -> Option<Record> {
+    if b.len() < SLOT_SIZE {
+        return None;
+    }
+    let seq = rd_u64(b, 0);
+    if seq == 0 || rd_u32(b, 20) != fnv1a(&[&b[0..20], &b[24..SLOT_SIZE]]) {
+        return None;
+    }
+    let mut data = [0u64; PAYLOAD_WORDS];
+    for (i, w) in data.iter_mut().enumerate() {
+        *w = rd_u64(b, 24 + i * 8);
+    }
+    Some(Record {
+        seq,
+        ts_ns: rd_u64(b, 8),
+        kind: rd_u32(b, 16),
+        data,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub clean: bool,
+    pub pid: u32,
+    pub start_s: u64,
+    pub next_seq: u64,
+}
+
+pub fn encode_header(h: &Header) -> [u8; HEADER_SIZE] {
+    let mut out = [0u8; HEADER_SIZE];
+    out[0..4].copy_from_slice(MAGIC);
+    out[4..8].copy_from_slice(&VERSION.to_le_bytes());
+    out[8..12].copy_from_slice(&(SLOTS as u32).to_le_bytes());
+    out[12..16].copy_from_slice(&(SLOT_SIZE as u32).to_le_bytes());
+    out[H_CLEAN..H_CLEAN + 4].copy_from_slice(&(h.clean as u32).to_le_bytes());
+    out[20..24].copy_from_slice(&h.pid.to_le_bytes());
+    out[24..32].copy_from_slice(&h.start_s.to_le_bytes());
+    out[H_NEXT_SEQ..H_NEXT_SEQ + 8].copy_from_slice(&h.next_seq.to_le_bytes());
+    out
+}
+
+pub fn decode_header(b: &[u8]) -> Result<Header> {
+    if b.len() < HEADER_SIZE {
+        bail!("BLACKBOX TRUNCATED ({} BYTES)", b.len());
+    }
+    if &b[0..4] != MAGIC {
+        bail!("NOT A BLACKBOX FILE (BAD MAGIC)");
+    }
+    let version = rd_u32(b, 4);
+    if version != VERSION {
+        bail!("BLACKBOX VERSION {} (EXPECTED {})", version, VERSION);
+    }
+    if rd_u32(b, 8) as usize != SLOTS || rd_u32(b, 12) as usize != SLOT_SIZE {
+        bail!("BLACKBOX GEOMETRY MISMATCH");
+    }
+    Ok(Header {
+        clean: rd_u32(b, H_CLEAN) != 0,
+        pid: rd_u32(b, 20),
+        start_s: rd_u64(b, 24),
+        next_seq: rd_u64(b, H_NEXT_SEQ),
+    })
+}
+
+pub struct Dump {
+    pub header: Header,
+    pub records: Vec<Record>, // CHRONOLOGICAL (BY SEQ)
+    pub torn: usize,          // NON-EMPTY SLOTS THAT FAILED CRC
+}
+
+// DECODE A WHOLE FILE. A SHORT FILE KEEPS EVERY COMPLETE SLOT IT HAS.
+pub fn decode(b: &[u8]) -> Result<Dump> {
+    let header = decode_header(b)?;
+    let mut records = Vec::new();
+    let mut torn = 0;
+    for chunk in b[HEADER_SIZE..].chunks(SLOT_SIZE).take(SLOTS) {
+        match decode_slot(chunk) {
+            Some(r) => records.push(r),
+            None if chunk.iter().any(|&x| x != 0) => torn += 1,
+            None => {}
+        }
+    }
+    records.sort_by_key(|r| r.seq);
+    Ok(Dump {
+        header,
+        records,
+        torn,
+    })
+}
+
+// ON STARTUP: IF THE LAST RUN NEVER MARKED ITSELF CLEAN, MOVE ITS FILE
+// ASIDE BEFORE THE NEW RUN OVERWRITES IT. RETURNS THE OLD HEADER IF MOVED.
+pub fn preserve_if_unclean(path: &Path, dest: &Path) -> Result<Option<Header>> {
+    let bytes = match std::fs::read(path) {
+        Ok(b) => b,
+        Err(_) => return Ok(None),
+    };
+    let Ok(header) = decode_header(&bytes) else {
+        return Ok(None);
+    };
+    if header.clean || header.next_seq <= 1 {
+        return Ok(None);
+    }
+    std::fs::rename(path, dest)?;
+    Ok(Some(header))
+}
+
+// MMAP'D WRITER
+
+pub struct BlackBox {
+    ptr: *mut u8,
+    next_seq: u64,
+}
+
+impl BlackBox {
+    // CREATE/TRUNCATE, MAP, WRITE A FRESH "UNCLEAN" HEADER
+    pub fn create(path: &Path) -> Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(FILE_SIZE as u64)?;
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                FILE_SIZE,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            bail!("BLACKBOX MMAP FAILED: {}", std::io::Error::last_os_error());
+        }
+
+        let bb = Self {
+            ptr: ptr as *mut u8,
+            next_seq: 1,
+        };
+        let start_s = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        bb.write_bytes(
+            0,
+            &encode_header(&Header {
+                clean: false,
+                pid: std::process::id(),
+                start_s,
+                next_seq: 1,
+            }),
+        );
+        bb.sync(false);
+        Ok(bb)
+    }
+
+    fn write_bytes(&self, off: usize, bytes: &[u8]) {
+        assert!(off + bytes.len() <= FILE_SIZE);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.add(off), bytes.len());
+        }
+    }
+
+    // SLOT FIRST, THEN NEXT_SEQ: A CRASH BETWEEN THE TWO LOSES NOTHING
+    pub fn record(&mut self, mut r: Record) {
+        r.seq = self.next_seq;
+        let slot = ((r.seq - 1) as usize) % SLOTS;
+        self.write_bytes(HEADER_SIZE + slot * SLOT_SIZE, &encode_slot(&r));
+        self.next_seq += 1;
+        self.write_bytes(H_NEXT_SEQ, &self.next_seq.to_le_bytes());
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.write_bytes(H_CLEAN, &1u32.to_le_bytes());
+        self.sync(true);
+    }
+
+    // MS_ASYNC PER TICK (NEVER STALLS THE MONITOR), MS_SYNC AT SHUTDOWN
+    pub fn sync(&self, blocking: bool) {
+        let flags = if blocking {
+            libc::MS_SYNC
+        } else {
+            libc::MS_ASYNC
+        };
+        unsafe {
+            libc::msync(self.ptr as *mut libc::c_void, FILE_SIZE, flags);
+        }
+    }
+
+    // COPY THE LIVE RING (E.G. RIGHT AFTER A BPF ABORT, BEFORE THE
+    // RESTARTED SCHEDULER OVERWRITES IT)
+    pub fn save_copy(&self, dest: &Path) -> Result<()> {
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr, FILE_SIZE) };
+        std::fs::write(dest, bytes)?;
+        Ok(())
+    }
+}
+
+impl Drop for BlackBox {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, FILE_SIZE);
+        }
+    }
+}
@@ -11,6 +11,9 @@
 // KERNEL PROCESS FLAGS (NOT IN vmlinux.h -- THESE ARE #define MACROS)
 #define PF_KTHREAD 0x00200000
 
+// run --self-probe THREAD NAME (RUST: pandemonium::probe::SELF_PROBE_COMM)
+#define SELF_PROBE_COMM "pnd-selfprobe"
+
 // TUNING KNOBS -- RUST ADAPTIVE LOOP WRITES THESE, BPF READS THEM
 // SINGLE-ELEMENT BPF_MAP_TYPE_ARRAY, UPDATED EVERY 50-1000MS
 struct tuning_knobs {
@@ -64,13 +67,56 @@ struct pandemonium_stats {
 	u64 longrun_mode_active;
 	// OVERFLOW SOJOURN RESCUE: TASKS DISPATCHED BY STEP 0 OVERFLOW AMPLIFICATION
 	u64 nr_overflow_rescue;
+	// TIER CHURN: RECLASSIFICATIONS IN runnable(), BY THE TIER MOVED INTO
+	u64 nr_tier_to_batch;
+	u64 nr_tier_to_interactive;
+	u64 nr_tier_to_lat_crit;
+};
+
+// SHARED DSQ DEPTH (BPF_MAP_TYPE_ARRAY, ONE ENTRY)
+// SAMPLED BY tick() WITH scx_bpf_dsq_nr_queued(), SUMMED ACROSS NODES.
+// A GAUGE: RUST READS THE LATEST SAMPLE ONCE PER SECOND.
+struct dsq_depth {
+	u64 interactive;        // TASKS QUEUED ON PER-NODE INTERACTIVE OVERFLOW DSQs
+	u64 batch;              // TASKS QUEUED ON PER-NODE BATCH OVERFLOW DSQs
+};
+
+// CPU BITMAP (BPF_MAP_TYPE_ARRAY, ONE ENTRY): BIT cpu % 64 OF
+// words[cpu / 64]. RUST MIRROR: hotplug::CpuBitmap. reserved_cpus_map
+// (--reserved-cpus, WRITTEN BY RUST BEFORE ATTACH) USES IT. IDLE STATE IS
+// NOT A BITMAP: cpu_busy_map IS ONE u64 PER CPU, AND RUST BUILDS ITS
+// OWN CpuBitmap FROM IT FOR IDLE RESIDENCY (residency.rs)
+#define CPU_BITMAP_WORDS (MAX_CPUS / 64)
+struct cpu_bitmap {
+	u64 words[CPU_BITMAP_WORDS];
+};
+
+// SCHEDULER EVENTS (BPF_MAP_TYPE_PERCPU_ARRAY, EVENT_RING_SLOTS + 1 ENTRIES)
+// A PER-CPU RING: SLOT seq % EVENT_RING_SLOTS. KEY EVENT_RING_SLOTS HOLDS
+// THE CPU'S LAST seq (ITS OTHER FIELDS UNUSED). RUST DRAINS NEW seqS EVERY
+// TICK AND ATTRIBUTES pid -> comm ITSELF (victims.rs).
+//   EVENT_SLOW_WAKE  running(): pid WAITED lat_ns > SLOW_WAKE_NS TO RUN
+//   EVENT_PREEMPT    tick(): pid (BATCH) KICKED OFF ITS CPU FOR A tier TASK
+#define EVENT_RING_SLOTS 32
+#define SLOW_WAKE_NS     2000000ULL   // 2MS
+#define EVENT_SLOW_WAKE  0
+#define EVENT_PREEMPT    1
+struct sched_event {
+	u64 seq;            // PER-CPU, FROM 1; 0 = SLOT NEVER WRITTEN
+	u64 lat_ns;         // SLOW_WAKE: WAKEUP-TO-RUN LATENCY; PREEMPT: 0
+	u32 pid;            // THE THREAD THAT WAITED / WAS PREEMPTED
+	u16 kind;           // EVENT_*
+	u16 tier;           // PREEMPT: THE TIER IT MADE ROOM FOR
 };
 
 // PROCESS CLASSIFICATION: BPF OBSERVES, RUST LEARNS, BPF APPLIES
 // SHARED BETWEEN BPF MAPS (task_class_observe, task_class_init) AND RUST (procdb.rs)
 struct task_class_entry {
 	u8  tier;
-	u8  _pad[7];
+	u8  policy;         // p->policy AT OBSERVATION (SCHED_FIFO/RR = PRIORITY PRIOR)
+	u8  pinned;         // INIT MAP ONLY: --force-*-comm, tier NEVER RECLASSIFIED
+	u8  _pad;
+	u32 uclamp_min;     // p->uclamp_req[UCLAMP_MIN] (0-1024, 0 WITHOUT CONFIG_UCLAMP_TASK)
 	u64 avg_runtime;
 	u64 runtime_dev;    // EWMA |RUNTIME - AVG_RUNTIME|
 	u64 wakeup_freq;    // WAKEUP FREQUENCY (EWMA)
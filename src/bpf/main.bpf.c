@@ -28,6 +28,37 @@ char _license[] SEC("license") = "GPL";
 
 const volatile u64 nr_cpu_ids = 1;
 
+// SET WHEN THE KERNEL EXPORTS scx_bpf_task_set_slice/_dsq_vtime.
+// NEWER KERNELS LOG "writing directly to p->scx.slice is deprecated" FOR
+// EVERY DIRECT FIELD WRITE; OLDER KERNELS DON'T HAVE THE KFUNCS AT ALL.
+// RUST DETECTS VIA BTF BEFORE LOAD. FALSE = LEGACY DIRECT WRITES.
+const volatile bool has_set_slice_kfunc = false;
+
+// HYBRID CPUS (P/E-CORE, big.LITTLE): RUST CLASSIFIES BY cpu_capacity AND
+// FILLS fast_cpus. FALSE ON UNIFORM MACHINES OR WITH --no-hybrid.
+const volatile bool hybrid_enabled = false;
+
+// SLICE GUARDRAILS (--slice-min-us / --slice-max-us). task_slice() CLAMPS
+// EVERY SLICE INTO [slice_min_ns, slice_max_ns]; RUST USES THE SAME PAIR
+// AS THE REFLEX FLOOR AND RELAX CEILING. DEFAULTS MATCH tuning.rs.
+const volatile u64 slice_min_ns = 100000;
+const volatile u64 slice_max_ns = 25000000;
+
+// LIGHTWEIGHT MODE (--lightweight): NO PER-WAKEUP ACCOUNTING. running()
+// TAKES NO WAKEUP LATENCY SAMPLE (wake_lat_* STATS, wake_lat_hist,
+// sleep_hist, EVENT_SLOW_WAKE), quiescent() TAKES NO TIMESTAMP, stopping()
+// NEVER PUBLISHES TO task_class_observe. DISPATCH, CLASSIFICATION, THE
+// COARSE COUNTERS AND EVENT_PREEMPT ARE UNCHANGED. FOR SERVERS AT VERY
+// HIGH WAKEUP RATES.
+const volatile bool lightweight = false;
+
+// RESERVED CPUS (--reserved-cpus): HOW MANY CPUS reserved_cpus_map HOLDS.
+// 0 SKIPS EVERY RESERVATION CHECK. RESERVED CPUS ARE LEFT TO WHAT IS
+// PINNED THERE (DAW, vCPU THREADS): IDLE SELECTION, KICKS AND WORK
+// STEALING AVOID THEM, AND THEIR dispatch() ONLY DRAINS THEIR OWN
+// PER-CPU DSQ, WHERE TASKS CONFINED TO THEM ARE QUEUED.
+const volatile u32 nr_reserved = 0;
+
 // BEHAVIORAL CONSTANTS
 
 // TEST: CUMULATIVE BURST COUNTER FOR RUST TELEMETRY VISIBILITY.
@@ -56,12 +87,35 @@ const volatile u64 nr_cpu_ids = 1;
 #define MAX_CSW_RATE         512
 #define LAG_CAP_NS           (40ULL * 1000000ULL)
 
-#define SLICE_MIN_NS 100000     // 100US FLOOR
 #define STARVATION_RESCUE_NS (500ULL * 1000000ULL) // 500MS HARD LIMIT
+#define WAKE_LAT_MAX_NS (10000ULL * 1000000ULL)     // 10S: SUSPEND ARTIFACT, NOT LATENCY
 // OVERFLOW SOJOURN RESCUE: COMPUTED IN init() FROM nr_cpu_ids
 // 2C: 4MS, 4C: 8MS, 5C+: 10MS. SEE pandemonium_init().
 
 
+// SLICE / VTIME SETTERS
+// RODATA FLAG IS CONSTANT AT LOAD: THE VERIFIER PRUNES THE DEAD BRANCH.
+// bpf_ksym_exists() GUARDS THE __weak REFERENCE ON KERNELS WITHOUT IT.
+
+bool scx_bpf_task_set_slice(struct task_struct *p, u64 slice) __ksym __weak;
+bool scx_bpf_task_set_dsq_vtime(struct task_struct *p, u64 vtime) __ksym __weak;
+
+static __always_inline void set_task_slice(struct task_struct *p, u64 slice)
+{
+	if (has_set_slice_kfunc && bpf_ksym_exists(scx_bpf_task_set_slice))
+		scx_bpf_task_set_slice(p, slice);
+	else
+		p->scx.slice = slice;
+}
+
+static __always_inline void set_task_vtime(struct task_struct *p, u64 vtime)
+{
+	if (has_set_slice_kfunc && bpf_ksym_exists(scx_bpf_task_set_dsq_vtime))
+		scx_bpf_task_set_dsq_vtime(p, vtime);
+	else
+		p->scx.dsq_vtime = vtime;
+}
+
 // GLOBALS
 
 static u32 nr_nodes;
@@ -70,7 +124,9 @@ static u64 vtime_now;
 // TICK-BASED INTERACTIVE PREEMPTION SIGNAL
 // SET BY enqueue() WHEN NON-BATCH TASK HITS OVERFLOW DSQ.
 // CLEARED BY tick() AFTER PREEMPTING A BATCH TASK.
+// waiting_tier: THE LAST SUCH TASK'S TIER, FOR THE EVENT_PREEMPT RECORD
 static bool interactive_waiting;
+static u32 waiting_tier;
 
 // SOJOURN TRACKERS: RECORD WHEN OVERFLOW DSQs TRANSITION FROM EMPTY.
 // DISPATCH STEP 0 CHECKS THESE TO RESCUE OVERFLOW TASKS AGING PAST
@@ -120,6 +176,11 @@ static u64 wake_rate_count;
 #define LONGRUN_THRESH_NS (2000ULL * 1000000ULL)
 static bool longrun_mode;
 
+// SHARED DSQ DEPTH SAMPLE: LAST TIME A tick() REWROTE dsq_depth_map.
+// ONE CPU PER INTERVAL WINS THE CAS; RUST READS THE MAP ONCE A SECOND.
+#define DSQ_DEPTH_INTERVAL_NS (10ULL * 1000000ULL)
+static u64 dsq_depth_ns;
+
 // USER EXIT
 
 UEI_DEFINE(uei);
@@ -140,6 +201,35 @@ struct {
 	__type(value, struct pandemonium_stats);
 } stats_map SEC(".maps");
 
+// SHARED DSQ DEPTH: WRITTEN BY tick(), READ BY RUST EVERY SECOND
+struct {
+	__uint(type, BPF_MAP_TYPE_ARRAY);
+	__uint(max_entries, 1);
+	__type(key, u32);
+	__type(value, struct dsq_depth);
+} dsq_depth_map SEC(".maps");
+
+// CPU BUSY FLAG: 0 WHILE THE CPU SITS IN THE IDLE LOOP, 1 OTHERWISE.
+// PER-CPU: update_idle() AND tick() ONLY EVER STORE TO THEIR OWN CPU'S
+// SLOT, SO THE HOTTEST CALLBACK SHARES NO CACHE LINE (A SHARED BITMAP
+// WORD WOULD BE AN ATOMIC RMW ON ONE LINE ACROSS 64 CPUS). ZERO AT LOAD:
+// EVERY CPU STARTS IDLE, AS THE KERNEL'S OWN IDLE MASK DOES ON ENABLE.
+// PINNED; RUST SAMPLES IT AT 10HZ AND BUILDS THE IDLE BITMAP ITSELF.
+struct {
+	__uint(type, BPF_MAP_TYPE_PERCPU_ARRAY);
+	__uint(max_entries, 1);
+	__type(key, u32);
+	__type(value, u64);
+} cpu_busy_map SEC(".maps");
+
+// RESERVED CPUS: WRITTEN BY RUST BEFORE ATTACH, PINNED FOR status.
+struct {
+	__uint(type, BPF_MAP_TYPE_ARRAY);
+	__uint(max_entries, 1);
+	__type(key, u32);
+	__type(value, struct cpu_bitmap);
+} reserved_cpus_map SEC(".maps");
+
 // CACHE DOMAIN MAP: l2_domain[cpu] = group_id
 // POPULATED BY RUST AT STARTUP FROM SYSFS TOPOLOGY
 struct {
@@ -175,6 +265,15 @@ struct {
 	__type(value, u8);
 } compositor_map SEC(".maps");
 
+// GAME MAP: `pandemonium gamemode start <pid>` ADDS THE GAME'S TGID, THE
+// MATCHING end REMOVES IT. KEY: TGID, VALUE: UNUSED (EXISTENCE = GAME)
+struct {
+	__uint(type, BPF_MAP_TYPE_HASH);
+	__uint(max_entries, 64);
+	__type(key, u32);
+	__type(value, u8);
+} game_tgid_map SEC(".maps");
+
 // L2 SIBLINGS MAP: FLAT ARRAY FOR L2-AWARE CPU PLACEMENT
 // l2_siblings[group_id * MAX_L2_SIBLINGS + slot] = cpu_id
 // SENTINEL: (u32)-1 MARKS END OF GROUP
@@ -188,6 +287,17 @@ struct {
 	__type(value, u32);
 } l2_siblings SEC(".maps");
 
+// FAST CPUS MAP: FLAT LIST OF HIGH-CAPACITY CPU IDS (HYBRID ONLY)
+// SENTINEL: (u32)-1 MARKS END. POPULATED BY RUST AT STARTUP.
+#define MAX_FAST_SCAN 64
+
+struct {
+	__uint(type, BPF_MAP_TYPE_ARRAY);
+	__uint(max_entries, MAX_CPUS);
+	__type(key, u32);
+	__type(value, u32);
+} fast_cpus SEC(".maps");
+
 // WAKEUP LATENCY HISTOGRAM: 3 TIERS x 12 BUCKETS = 36 ENTRIES PER CPU
 // BPF INCREMENTS IN running(); RUST READS ONCE PER SECOND IN MONITOR LOOP
 struct {
@@ -206,6 +316,28 @@ struct {
 	__type(value, u64);
 } sleep_hist SEC(".maps");
 
+// APPLIED LAG HISTOGRAM: 7 LOG2 BUCKETS OF THE EFFECTIVE lag_scale
+// (1, 2-3, ..., 32-63, 64) + KEY 7 = VTIME FLOOR CLAMPS (THE ONLY TIME
+// THE SCALE CHANGES A DEADLINE). BPF INCREMENTS IN task_deadline();
+// RUST READS IN THE MONITOR LOOP (tuning::LagHist)
+#define LAG_HIST_CLAMP 7
+struct {
+	__uint(type, BPF_MAP_TYPE_PERCPU_ARRAY);
+	__uint(max_entries, 8);
+	__type(key, u32);
+	__type(value, u64);
+} lag_hist SEC(".maps");
+
+// SCHEDULER EVENTS: PER-CPU RING OF struct sched_event (intf.h). BPF
+// WRITES IN running() AND tick(); RUST DRAINS ONCE PER SECOND IN THE
+// MONITOR LOOP
+struct {
+	__uint(type, BPF_MAP_TYPE_PERCPU_ARRAY);
+	__uint(max_entries, EVENT_RING_SLOTS + 1);
+	__type(key, u32);
+	__type(value, struct sched_event);
+} event_ring SEC(".maps");
+
 // PER-TASK CONTEXT
 
 struct task_ctx {
@@ -224,7 +356,8 @@ struct task_ctx {
 	u32 ewma_age;
 	s32 last_cpu;        // LAST CPU THIS TASK RAN ON (FOR CACHE AFFINITY)
 	u8  dispatch_path;   // 0=IDLE, 1=HARD_KICK, 2=SOFT_KICK
-	u8  _pad[3];
+	u8  tier_pinned;     // PROCDB FORCED TIER: runnable() NEVER RECLASSIFIES
+	u8  _pad[2];
 };
 
 struct {
@@ -236,6 +369,27 @@ struct {
 
 // HELPERS
 
+static __always_inline bool reserved_cpu(s32 cpu)
+{
+	u32 zero = 0;
+	struct cpu_bitmap *rb;
+
+	if (!nr_reserved || cpu < 0 || cpu >= MAX_CPUS)
+		return false;
+	rb = bpf_map_lookup_elem(&reserved_cpus_map, &zero);
+	return rb && (rb->words[(cpu >> 6) & (CPU_BITMAP_WORDS - 1)] &
+		      (1ULL << (cpu & 63)));
+}
+
+// STRICTLY AFFINED TO RESERVED CPUS: ALLOWED ON NO MORE CPUS THAN ARE
+// RESERVED, AND ON ONE OF THEM NOW (PER-CPU KTHREADS, taskset'D RT
+// HELPERS). THE ONLY TASKS A RESERVED CPU RUNS.
+static __always_inline bool task_confined(const struct task_struct *p)
+{
+	return nr_reserved && p->nr_cpus_allowed <= nr_reserved &&
+	       reserved_cpu(scx_bpf_task_cpu(p));
+}
+
 static __always_inline struct pandemonium_stats *get_stats(void)
 {
 	u32 zero = 0;
@@ -314,7 +468,25 @@ static __always_inline s32 find_idle_l2_sibling(const struct task_ctx *tctx)
 	return -1;
 }
 
-// HISTOGRAM BUCKETING: MATCHES HIST_EDGES_NS AND SLEEP_EDGES_NS IN RUST
+// HYBRID PLACEMENT: CLAIM AN IDLE FAST CPU THE TASK MAY RUN ON.
+// BOUNDED LOOP (MAX_FAST_SCAN), VERIFIER-SAFE. RETURNS -1 IF NONE IDLE.
+static __always_inline s32 find_idle_fast_cpu(const struct task_struct *p)
+{
+	for (int i = 0; i < MAX_FAST_SCAN; i++) {
+		u32 key = i;
+		u32 *val = bpf_map_lookup_elem(&fast_cpus, &key);
+		if (!val || *val == (u32)-1)
+			break;
+		s32 cpu = (s32)*val;
+		if (!bpf_cpumask_test_cpu(cpu, p->cpus_ptr))
+			continue;
+		if (scx_bpf_test_and_clear_cpu_idle(cpu))
+			return cpu;
+	}
+	return -1;
+}
+
+// HISTOGRAM BUCKETING: MATCHES HIST_EDGES_NS, SLEEP_EDGES_NS AND lag_bucket() IN RUST
 
 static __always_inline u32 lat_bucket(u64 lat_ns)
 {
@@ -340,6 +512,44 @@ static __always_inline u32 sleep_bucket(u64 sleep_ns)
 	return 3;
 }
 
+static __always_inline u32 lag_bucket(u64 scale)
+{
+	if (scale >= 64) return 6;
+	if (scale >= 32) return 5;
+	if (scale >= 16) return 4;
+	if (scale >= 8) return 3;
+	if (scale >= 4) return 2;
+	if (scale >= 2) return 1;
+	return 0;
+}
+
+static __always_inline void count_lag(u32 key)
+{
+	u64 *val = bpf_map_lookup_elem(&lag_hist, &key);
+	if (val)
+		*val += 1;
+}
+
+// PER-CPU MAP: NO OTHER CPU WRITES THESE SLOTS, NO ATOMICS NEEDED
+static __always_inline void record_event(u16 kind, u32 pid, u64 lat_ns, u16 tier)
+{
+	u32 cursor_key = EVENT_RING_SLOTS;
+	struct sched_event *cursor = bpf_map_lookup_elem(&event_ring, &cursor_key);
+	if (!cursor)
+		return;
+	u64 seq = cursor->seq + 1;
+	u32 slot = seq % EVENT_RING_SLOTS;
+	struct sched_event *e = bpf_map_lookup_elem(&event_ring, &slot);
+	if (!e)
+		return;
+	e->lat_ns = lat_ns;
+	e->pid = pid;
+	e->kind = kind;
+	e->tier = tier;
+	e->seq = seq;
+	cursor->seq = seq;
+}
+
 // EWMA
 
 static __always_inline u64 calc_avg(u64 old_val, u64 new_val, u32 age)
@@ -397,6 +607,22 @@ static __always_inline bool is_compositor(const struct task_struct *p)
 	return bpf_map_lookup_elem(&compositor_map, key) != NULL;
 }
 
+// GAME DETECTION: ANY THREAD OF A TGID IN game_tgid_map
+static __always_inline bool is_game(const struct task_struct *p)
+{
+	u32 tgid = p->tgid;
+	return bpf_map_lookup_elem(&game_tgid_map, &tgid) != NULL;
+}
+
+// PROCDB PRIORITY PRIOR: REQUESTED uclamp_min (0 ON KERNELS WITHOUT
+// CONFIG_UCLAMP_TASK; CO-RE DROPS THE READ WHEN THE FIELD IS ABSENT)
+static __always_inline u32 task_uclamp_min(const struct task_struct *p)
+{
+	if (!bpf_core_field_exists(p->uclamp_req))
+		return 0;
+	return BPF_CORE_READ_BITFIELD(&p->uclamp_req[UCLAMP_MIN], value);
+}
+
 // TRACE: FAST 4-BYTE COMM CHECK FOR SCHEDULER PROCESS TRACING
 // CATCHES "pandemonium" WITH ZERO MAP OVERHEAD. DISABLE VIA TRACE_SCHED=0.
 static __always_inline bool is_sched_task(const struct task_struct *p)
@@ -405,6 +631,19 @@ static __always_inline bool is_sched_task(const struct task_struct *p)
 	       p->comm[2] == 'n' && p->comm[3] == 'd';
 }
 
+// SELF-PROBE: THE run --self-probe THREAD MEASURES THE PATH AN ORDINARY
+// TASK GETS. NO COMPOSITOR BOOST, NO PROCDB PUBLISH (A LEARNED PRIOR WOULD
+// BOOST IT NEXT RUN). EXACT MATCH INCLUDING THE NUL.
+static __always_inline bool is_self_probe(const struct task_struct *p)
+{
+	const char name[] = SELF_PROBE_COMM;
+	unsigned int i;
+	for (i = 0; i < sizeof(name); i++)
+		if (p->comm[i] != name[i])
+			return false;
+	return true;
+}
+
 // EFFECTIVE WEIGHT: TIER-BASED MULTIPLIER ON NICE WEIGHT
 static __always_inline u64 effective_weight(const struct task_struct *p,
 					     const struct task_ctx *tctx)
@@ -447,10 +686,14 @@ static __always_inline u64 task_deadline(struct task_struct *p,
 	else if (nr_queued > 4 && lag_scale > 2)
 		lag_scale >>= 1;
 
+	count_lag(lag_bucket(lag_scale));
+
 	// CLAMP VTIME TO PREVENT UNBOUNDED BOOST AFTER LONG SLEEP
 	u64 vtime_floor = vtime_now - LAG_CAP_NS * lag_scale;
-	if (time_before(p->scx.dsq_vtime, vtime_floor))
-		p->scx.dsq_vtime = vtime_floor;
+	if (time_before(p->scx.dsq_vtime, vtime_floor)) {
+		set_task_vtime(p, vtime_floor);
+		count_lag(LAG_HIST_CLAMP);
+	}
 
 	// TIER-BASED AWAKE CAP
 	u64 awake_cap;
@@ -467,10 +710,20 @@ static __always_inline u64 task_deadline(struct task_struct *p,
 	return p->scx.dsq_vtime + tctx->awake_vtime;
 }
 
+static __always_inline u64 clamp_slice(u64 slice)
+{
+	if (slice < slice_min_ns)
+		return slice_min_ns;
+	if (slice > slice_max_ns)
+		return slice_max_ns;
+	return slice;
+}
+
 // PER-TIER DYNAMIC SLICING
 // LAT_CRITICAL: 1.5X AVG_RUNTIME (TIGHT -- FAST PREEMPTION)
 // INTERACTIVE:  2X AVG_RUNTIME (RESPONSIVE)
 // BATCH:        KNOB BASE SLICE (CONTROLLED BY ADAPTIVE LAYER)
+// ALL TIERS END IN THE RODATA GUARDRAILS
 static __always_inline u64 task_slice(const struct task_ctx *tctx,
 				      const struct tuning_knobs *knobs)
 {
@@ -482,33 +735,25 @@ static __always_inline u64 task_slice(const struct task_ctx *tctx,
 		base = tctx->avg_runtime + (tctx->avg_runtime >> 1);
 		if (base > base_slice)
 			base = base_slice;
-		if (base < SLICE_MIN_NS)
-			base = SLICE_MIN_NS;
-		return base;
+		return clamp_slice(base);
 	}
 
 	if (tctx->tier == TIER_INTERACTIVE) {
 		base = tctx->avg_runtime << 1;
 		if (base > base_slice)
 			base = base_slice;
-		if (base < SLICE_MIN_NS)
-			base = SLICE_MIN_NS;
-		return base;
+		return clamp_slice(base);
 	}
 
 	// BATCH: DEDICATED CEILING FROM RUST ADAPTIVE LAYER.
 	// WEIGHT-SCALED: HIGHER BEHAVIORAL WEIGHT = LONGER SLICE.
-	u64 batch_ceil = knobs ? knobs->batch_slice_ns : 20000000;
-	if (batch_ceil < SLICE_MIN_NS)
-		batch_ceil = SLICE_MIN_NS;
+	u64 batch_ceil = clamp_slice(knobs ? knobs->batch_slice_ns : 20000000);
 
 	base = batch_ceil * tctx->cached_weight >> 7;
 	if (base > batch_ceil)
 		base = batch_ceil;
-	if (base < SLICE_MIN_NS)
-		base = SLICE_MIN_NS;
 
-	return base;
+	return clamp_slice(base);
 }
 
 // SCHEDULING CALLBACKS
@@ -522,10 +767,24 @@ s32 BPF_STRUCT_OPS(pandemonium_select_cpu, struct task_struct *p,
 		   s32 prev_cpu, u64 wake_flags)
 {
 	bool is_idle = false;
-	s32 cpu = scx_bpf_select_cpu_dfl(p, prev_cpu, wake_flags, &is_idle);
+	struct task_ctx *tctx = lookup_task_ctx(p);
+	s32 cpu = -1;
+
+	// HYBRID: LAT_CRITICAL WAKEUPS TRY AN IDLE FAST CPU BEFORE THE DEFAULT
+	// PICK, WHICH WOULD HAPPILY CHOOSE AN IDLE E-CORE
+	if (hybrid_enabled && tctx && tctx->tier == TIER_LAT_CRITICAL)
+		cpu = find_idle_fast_cpu(p);
+	if (cpu >= 0)
+		is_idle = true;
+	else
+		cpu = scx_bpf_select_cpu_dfl(p, prev_cpu, wake_flags, &is_idle);
+
+	// RESERVED CPU: NO FAST PATH UNLESS THE TASK IS CONFINED THERE.
+	// enqueue() PLACES IT ON AN UNRESERVED CPU INSTEAD.
+	if (is_idle && nr_reserved && reserved_cpu(cpu) && !task_confined(p))
+		is_idle = false;
 
 	if (is_idle) {
-		struct task_ctx *tctx = lookup_task_ctx(p);
 		struct tuning_knobs *knobs = get_knobs();
 		u64 sl = tctx ? task_slice(tctx, knobs) : 1000000;
 
@@ -595,6 +854,24 @@ void BPF_STRUCT_OPS(pandemonium_enqueue, struct task_struct *p,
 	// CLASSIFY: WAKEUP VS RE-ENQUEUE
 	bool is_wakeup = tctx && tctx->awake_vtime == 0;
 
+	// CONFINED TO A RESERVED CPU: ITS OWN PER-CPU DSQ, THE ONLY ONE A
+	// RESERVED CPU DRAINS. EVERY OTHER TASK AVOIDS RESERVED CPUS BELOW.
+	bool avoid_reserved = false;
+	if (nr_reserved) {
+		if (task_confined(p)) {
+			s32 rcpu = scx_bpf_task_cpu(p);
+			dl = tctx ? task_deadline(p, tctx, (u64)rcpu, knobs)
+				  : vtime_now;
+			scx_bpf_dsq_insert_vtime(p, (u64)rcpu, sl, dl, enq_flags);
+			scx_bpf_kick_cpu(rcpu, SCX_KICK_IDLE);
+			struct pandemonium_stats *s = get_stats();
+			if (s)
+				s->nr_dispatches += 1;
+			return;
+		}
+		avoid_reserved = true;
+	}
+
 	// TIER 1: IDLE CPU -> NODE DSQ + KICK
 	// L2 PLACEMENT: TRY IDLE SIBLING IN SAME L2 DOMAIN FIRST.
 	// LAT_CRITICAL AND KERNEL THREADS SKIP AFFINITY -- FASTEST CPU WINS.
@@ -607,6 +884,9 @@ void BPF_STRUCT_OPS(pandemonium_enqueue, struct task_struct *p,
 	}
 	if (cpu < 0)
 		cpu = __COMPAT_scx_bpf_pick_idle_cpu_node(p->cpus_ptr, node, 0);
+	// A RESERVED CPU WOULD NOT DRAIN node_dsq: FALL TO TIER 2
+	if (avoid_reserved && reserved_cpu(cpu))
+		cpu = -1;
 	if (cpu >= 0 && (u64)cpu < nr_cpu_ids) {
 		dl = tctx ? task_deadline(p, tctx, node_dsq, knobs)
 			  : vtime_now;
@@ -649,6 +929,9 @@ void BPF_STRUCT_OPS(pandemonium_enqueue, struct task_struct *p,
 	    (tctx->tier == TIER_LAT_CRITICAL || is_wakeup)) {
 		cpu = __COMPAT_scx_bpf_pick_any_cpu_node(
 			p->cpus_ptr, node, 0);
+		// A RESERVED CPU WOULD NOT DRAIN node_dsq: FALL TO TIER 3
+		if (avoid_reserved && reserved_cpu(cpu))
+			cpu = -1;
 		if (cpu >= 0 && (u64)cpu < nr_cpu_ids &&
 		    __COMPAT_scx_bpf_cpu_curr(cpu)) {
 			dl = task_deadline(p, tctx, node_dsq, knobs);
@@ -723,11 +1006,16 @@ void BPF_STRUCT_OPS(pandemonium_enqueue, struct task_struct *p,
 
 	// ARM TICK SAFETY NET: SIGNAL THAT INTERACTIVE TASKS ARE WAITING IN OVERFLOW.
 	// tick() CHECKS THIS FLAG TO PREEMPT BATCH TASKS VIA preempt_thresh_ns.
-	if (tctx && tctx->tier != TIER_BATCH)
+	if (tctx && tctx->tier != TIER_BATCH) {
+		waiting_tier = tctx->tier;
 		interactive_waiting = true;
+	}
 
+	// LAST RAN ON A RESERVED CPU (BEFORE AN AFFINITY CHANGE): NO KICK,
+	// AN UNRESERVED CPU DRAINS THE DSQ AT ITS NEXT dispatch()
 	u64 kick_flags = is_wakeup ? SCX_KICK_PREEMPT : 0;
-	scx_bpf_kick_cpu(scx_bpf_task_cpu(p), kick_flags);
+	if (!(avoid_reserved && reserved_cpu(scx_bpf_task_cpu(p))))
+		scx_bpf_kick_cpu(scx_bpf_task_cpu(p), kick_flags);
 
 	if (tctx)
 		tctx->dispatch_path = is_wakeup ? 1 : 2;
@@ -805,6 +1093,19 @@ void BPF_STRUCT_OPS(pandemonium_dispatch, s32 cpu, struct task_struct *prev)
 	struct pandemonium_stats *s;
 	u64 now = bpf_ktime_get_ns();
 
+	// RESERVED CPU: ITS OWN PER-CPU DSQ (CONFINED TASKS) AND NOTHING
+	// ELSE. NO STEALING, NO SHARED DSQs, NO RESCUE DUTY. WITH NOTHING
+	// QUEUED THE KERNEL KEEPS prev RUNNING (NO SCX_OPS_ENQ_LAST).
+	if (nr_reserved && reserved_cpu(cpu)) {
+		if ((u64)cpu < nr_cpu_ids &&
+		    scx_bpf_dsq_move_to_local((u64)cpu)) {
+			s = get_stats();
+			if (s)
+				s->nr_dispatches += 1;
+		}
+		return;
+	}
+
 	// STEP 0: OWN PER-CPU DSQ -- HIGHEST PRIORITY, CACHE-HOT
 	if ((u64)cpu < nr_cpu_ids &&
 	    scx_bpf_dsq_move_to_local((u64)cpu)) {
@@ -1059,8 +1360,8 @@ skip_interactive_rescue:;
 	if (prev && !(prev->flags & PF_EXITING) &&
 	    (prev->scx.flags & SCX_TASK_QUEUED)) {
 		struct task_ctx *tctx = lookup_task_ctx(prev);
-		prev->scx.slice = tctx ? task_slice(tctx, knobs) :
-				  (knobs ? knobs->slice_ns : 1000000);
+		set_task_slice(prev, tctx ? task_slice(tctx, knobs) :
+				     (knobs ? knobs->slice_ns : 1000000));
 		s = get_stats();
 		if (s) {
 			s->nr_keep_running += 1;
@@ -1120,8 +1421,9 @@ void BPF_STRUCT_OPS(pandemonium_runnable, struct task_struct *p,
 	struct tuning_knobs *knobs = get_knobs();
 	u32 new_tier = classify_tier(tctx->lat_cri, knobs);
 
-	// COMPOSITOR BOOST: ALWAYS LAT_CRITICAL
-	if (new_tier != TIER_LAT_CRITICAL && is_compositor(p))
+	// COMPOSITOR + GAMEMODE BOOST: ALWAYS LAT_CRITICAL
+	if (new_tier != TIER_LAT_CRITICAL && (is_compositor(p) || is_game(p)) &&
+	    !is_self_probe(p))
 		new_tier = TIER_LAT_CRITICAL;
 
 	// KWORKER FLOOR: WORKQUEUE WORKERS HANDLE I/O COMPLETIONS, TIMER
@@ -1131,6 +1433,25 @@ void BPF_STRUCT_OPS(pandemonium_runnable, struct task_struct *p,
 	if (new_tier == TIER_BATCH && (p->flags & PF_WQ_WORKER))
 		new_tier = TIER_INTERACTIVE;
 
+	// FORCED BY THE USER (--force-*-comm): THE PIN OUTRANKS EVERY RULE
+	// ABOVE. THE EWMAs KEEP RUNNING SO procdb STILL SEES THE BEHAVIOR.
+	if (tctx->tier_pinned)
+		new_tier = tctx->tier;
+
+	// TIER CHURN: A TASK BOUNCING BETWEEN TIERS EVERY FEW HUNDRED MS
+	// SHOWS UP HERE FIRST (RUST REPORTS THE PER-TICK DELTA)
+	if (new_tier != tctx->tier) {
+		struct pandemonium_stats *s = get_stats();
+		if (s) {
+			if (new_tier == TIER_BATCH)
+				s->nr_tier_to_batch += 1;
+			else if (new_tier == TIER_INTERACTIVE)
+				s->nr_tier_to_interactive += 1;
+			else
+				s->nr_tier_to_lat_crit += 1;
+		}
+	}
+
 	tctx->tier = new_tier;
 }
 
@@ -1153,7 +1474,7 @@ void BPF_STRUCT_OPS(pandemonium_running, struct task_struct *p)
 	struct task_ctx *tctx = lookup_task_ctx(p);
 	if (!tctx) {
 		struct tuning_knobs *knobs = get_knobs();
-		p->scx.slice = knobs ? knobs->slice_ns : 1000000;
+		set_task_slice(p, knobs ? knobs->slice_ns : 1000000);
 		return;
 	}
 
@@ -1162,7 +1483,11 @@ void BPF_STRUCT_OPS(pandemonium_running, struct task_struct *p)
 
 	// WAKEUP-TO-RUN LATENCY
 	// ONLY RECORD ONCE PER WAKEUP: CLEAR last_woke_at AFTER RECORDING.
-	if (tctx->last_woke_at && now > tctx->last_woke_at) {
+	// LIGHTWEIGHT CLEARS IT TOO: runnable() READS IT FOR WAKEUP FREQUENCY,
+	// SO CLASSIFICATION SEES THE SAME VALUE IN BOTH MODES.
+	if (lightweight) {
+		tctx->last_woke_at = 0;
+	} else if (tctx->last_woke_at && now > tctx->last_woke_at) {
 		u64 wake_lat = now - tctx->last_woke_at;
 		u8 path = tctx->dispatch_path;
 
@@ -1176,30 +1501,38 @@ void BPF_STRUCT_OPS(pandemonium_running, struct task_struct *p)
 
 		tctx->last_woke_at = 0;
 
-		struct pandemonium_stats *s = get_stats();
-		if (s) {
-			s->wake_lat_samples += 1;
-			s->wake_lat_sum += wake_lat;
-			if (wake_lat > s->wake_lat_max)
-				s->wake_lat_max = wake_lat;
-
-			if (path == 0) {
-				s->wake_lat_idle_sum += wake_lat;
-				s->wake_lat_idle_cnt += 1;
-			} else if (path == 1) {
-				s->wake_lat_kick_sum += wake_lat;
-				s->wake_lat_kick_cnt += 1;
+		// A WAKEUP "WAITING" 10S+ STRADDLED A SUSPEND OR A STALE
+		// TIMESTAMP: DROP IT BEFORE IT POISONS THE AVERAGE AND P99
+		if (wake_lat <= WAKE_LAT_MAX_NS) {
+			struct pandemonium_stats *s = get_stats();
+			if (s) {
+				s->wake_lat_samples += 1;
+				s->wake_lat_sum += wake_lat;
+				if (wake_lat > s->wake_lat_max)
+					s->wake_lat_max = wake_lat;
+
+				if (path == 0) {
+					s->wake_lat_idle_sum += wake_lat;
+					s->wake_lat_idle_cnt += 1;
+				} else if (path == 1) {
+					s->wake_lat_kick_sum += wake_lat;
+					s->wake_lat_kick_cnt += 1;
+				}
 			}
-		}
 
-		// HISTOGRAM: BPF-SIDE LATENCY BUCKETING (NO RING BUFFER)
-		u32 tier_idx = (u32)tctx->tier;
-		if (tier_idx > 2) tier_idx = 2;
-		u32 bucket = lat_bucket(wake_lat);
-		u32 hist_key = tier_idx * 12 + bucket;
-		u64 *hist_val = bpf_map_lookup_elem(&wake_lat_hist, &hist_key);
-		if (hist_val)
-			*hist_val += 1;
+			// HISTOGRAM: BPF-SIDE LATENCY BUCKETING (NO RING BUFFER)
+			u32 tier_idx = (u32)tctx->tier;
+			if (tier_idx > 2) tier_idx = 2;
+			u32 bucket = lat_bucket(wake_lat);
+			u32 hist_key = tier_idx * 12 + bucket;
+			u64 *hist_val = bpf_map_lookup_elem(&wake_lat_hist, &hist_key);
+			if (hist_val)
+				*hist_val += 1;
+
+			// WHO WAITED: RUST'S VICTIM TABLE
+			if (wake_lat > SLOW_WAKE_NS)
+				record_event(EVENT_SLOW_WAKE, p->pid, wake_lat, 0);
+		}
 
 		if (sleep_dur > 0) {
 			u32 sbucket = sleep_bucket(sleep_dur);
@@ -1210,7 +1543,7 @@ void BPF_STRUCT_OPS(pandemonium_running, struct task_struct *p)
 	}
 
 	struct tuning_knobs *knobs = get_knobs();
-	p->scx.slice = task_slice(tctx, knobs);
+	set_task_slice(p, task_slice(tctx, knobs));
 }
 
 // STOPPING: TASK YIELDS CPU -- CHARGE VTIME WITH TIER-BASED WEIGHT
@@ -1238,10 +1571,14 @@ void BPF_STRUCT_OPS(pandemonium_stopping, struct task_struct *p,
 	// PROCDB: PUBLISH TASK CLASSIFICATION FOR USERSPACE
 	// INITIAL AT EWMA MATURITY, THEN EVERY 64 SCHEDULING EVENTS
 	// RE-PUBLISHING KEEPS PROCDB FRESH FOR LONG-LIVED TASKS
-	if (tctx->ewma_age == EWMA_AGE_MATURE ||
-	    (tctx->ewma_age > EWMA_AGE_MATURE && tctx->ewma_age % 64 == 0)) {
+	if (!lightweight &&
+	    (tctx->ewma_age == EWMA_AGE_MATURE ||
+	     (tctx->ewma_age > EWMA_AGE_MATURE && tctx->ewma_age % 64 == 0)) &&
+	    !is_self_probe(p)) {
 		struct task_class_entry obs = {};
 		obs.tier = (u8)tctx->tier;
+		obs.policy = (u8)p->policy;
+		obs.uclamp_min = task_uclamp_min(p);
 		obs.avg_runtime = tctx->avg_runtime;
 		obs.runtime_dev = tctx->runtime_dev;
 		obs.wakeup_freq = tctx->wakeup_freq;
@@ -1257,7 +1594,7 @@ void BPF_STRUCT_OPS(pandemonium_stopping, struct task_struct *p,
 	else
 		delta_vtime = slice;
 
-	p->scx.dsq_vtime += delta_vtime;
+	set_task_vtime(p, p->scx.dsq_vtime + delta_vtime);
 	tctx->awake_vtime += delta_vtime;
 }
 
@@ -1300,6 +1637,40 @@ void BPF_STRUCT_OPS(pandemonium_tick, struct task_struct *p)
 		s->longrun_mode_active = longrun_mode ? 1 : 0;
 	}
 
+	// BUSY FLAG: A CPU RUNNING tick() IS NOT IDLE. CATCHES CPUS THAT WERE
+	// BUSY AT ATTACH AND HAVE NOT PASSED THROUGH THE IDLE LOOP SINCE. OWN
+	// SLOT ONLY; READ FIRST SO THE COMMON CASE DIRTIES NOTHING
+	{
+		u32 zero = 0;
+		u64 *busy = bpf_map_lookup_elem(&cpu_busy_map, &zero);
+		if (busy && !*busy)
+			*busy = 1;
+	}
+
+	// QUEUE DEPTH: SAMPLE THE SHARED DSQs. AN UNBOUNDED BACKLOG HERE IS
+	// THE RUNNABLE-TASK-STALL FAILURE MODE; RUST WATCHES THE TREND.
+	// RATE-LIMITED RATHER THAN PINNED TO CPU 0: ANY TICKING CPU REFRESHES
+	// IT, SO A TICKLESS IDLE CPU 0 CANNOT FREEZE A STALE BACKLOG.
+	{
+		u32 zero = 0;
+		u64 now = bpf_ktime_get_ns();
+		u64 last = dsq_depth_ns;
+		struct dsq_depth *qd = NULL;
+
+		if (now - last >= DSQ_DEPTH_INTERVAL_NS &&
+		    __sync_bool_compare_and_swap(&dsq_depth_ns, last, now))
+			qd = bpf_map_lookup_elem(&dsq_depth_map, &zero);
+		if (qd) {
+			u64 qi = 0, qb = 0;
+			for (u32 i = 0; i < nr_nodes && i < MAX_NODES; i++) {
+				qi += scx_bpf_dsq_nr_queued(nr_cpu_ids + i);
+				qb += scx_bpf_dsq_nr_queued(nr_cpu_ids + nr_nodes + i);
+			}
+			qd->interactive = qi;
+			qd->batch = qb;
+		}
+	}
+
 	u64 bens = batch_enqueue_ns;
 	if (bens > 0) {
 		u64 now = bpf_ktime_get_ns();
@@ -1323,6 +1694,7 @@ void BPF_STRUCT_OPS(pandemonium_tick, struct task_struct *p)
 			struct task_ctx *tctx = lookup_task_ctx(p);
 			if (tctx && tctx->tier == TIER_BATCH) {
 				scx_bpf_kick_cpu(scx_bpf_task_cpu(p), SCX_KICK_PREEMPT);
+				record_event(EVENT_PREEMPT, p->pid, 0, TIER_BATCH);
 				return;
 			}
 		}
@@ -1385,6 +1757,7 @@ void BPF_STRUCT_OPS(pandemonium_tick, struct task_struct *p)
 	if (tctx->tier == TIER_BATCH && tctx->avg_runtime >= thresh) {
 		scx_bpf_kick_cpu(scx_bpf_task_cpu(p), SCX_KICK_PREEMPT);
 		interactive_waiting = false;
+		record_event(EVENT_PREEMPT, p->pid, 0, waiting_tier);
 		if (!s)
 			s = get_stats();
 		if (s)
@@ -1395,7 +1768,7 @@ void BPF_STRUCT_OPS(pandemonium_tick, struct task_struct *p)
 // ENABLE: NEW TASK ENTERS SCHED_EXT
 void BPF_STRUCT_OPS(pandemonium_enable, struct task_struct *p)
 {
-	p->scx.dsq_vtime = vtime_now;
+	set_task_vtime(p, vtime_now);
 
 	struct task_ctx *tctx = ensure_task_ctx(p);
 	if (tctx) {
@@ -1411,6 +1784,7 @@ void BPF_STRUCT_OPS(pandemonium_enable, struct task_struct *p)
 		tctx->tier = TIER_INTERACTIVE;
 		tctx->ewma_age = 0;
 		tctx->dispatch_path = 0;
+		tctx->tier_pinned = 0;
 
 		// PROCDB: APPLY LEARNED CLASSIFICATION FROM PRIOR RUNS
 		char key[16];
@@ -1419,6 +1793,7 @@ void BPF_STRUCT_OPS(pandemonium_enable, struct task_struct *p)
 		    bpf_map_lookup_elem(&task_class_init, key);
 		if (init_entry) {
 			tctx->tier = (u32)init_entry->tier;
+			tctx->tier_pinned = init_entry->pinned;
 			tctx->avg_runtime = init_entry->avg_runtime;
 			tctx->runtime_dev = init_entry->runtime_dev;
 			tctx->wakeup_freq = init_entry->wakeup_freq;
@@ -1535,6 +1910,9 @@ void BPF_STRUCT_OPS(pandemonium_exit, struct scx_exit_info *ei)
 void BPF_STRUCT_OPS(pandemonium_quiescent, struct task_struct *p,
 		    u64 deq_flags)
 {
+	if (lightweight)
+		return;
+
 	struct task_ctx *tctx = lookup_task_ctx(p);
 	if (tctx)
 		tctx->sleep_start_ns = bpf_ktime_get_ns();
@@ -1556,6 +1934,18 @@ void BPF_STRUCT_OPS(pandemonium_cpu_release, s32 cpu,
 	}
 }
 
+// UPDATE IDLE: KEEP THE BUSY FLAG FOR RUST'S RESIDENCY SAMPLING. THE
+// KERNEL CALLS IT ON cpu ITSELF, SO A PLAIN STORE TO THIS CPU'S SLOT.
+// SCX_OPS_KEEP_BUILTIN_IDLE: DEFINING THIS CALLBACK WOULD OTHERWISE TURN
+// OFF THE BUILT-IN IDLE TRACKING select_cpu() AND dispatch() RELY ON
+void BPF_STRUCT_OPS(pandemonium_update_idle, s32 cpu, bool idle)
+{
+	u32 zero = 0;
+	u64 *busy = bpf_map_lookup_elem(&cpu_busy_map, &zero);
+	if (busy)
+		*busy = idle ? 0 : 1;
+}
+
 // CPU HOTPLUG CALLBACKS
 void BPF_STRUCT_OPS(pandemonium_cpu_online, s32 cpu) {}
 void BPF_STRUCT_OPS(pandemonium_cpu_offline, s32 cpu) {}
@@ -1570,10 +1960,12 @@ SCX_OPS_DEFINE(pandemonium_ops,
 	       .tick         = (void *)pandemonium_tick,
 	       .enable       = (void *)pandemonium_enable,
 	       .quiescent    = (void *)pandemonium_quiescent,
+	       .update_idle  = (void *)pandemonium_update_idle,
 	       .cpu_release  = (void *)pandemonium_cpu_release,
 	       .cpu_online   = (void *)pandemonium_cpu_online,
 	       .cpu_offline  = (void *)pandemonium_cpu_offline,
 	       .init         = (void *)pandemonium_init,
 	       .exit         = (void *)pandemonium_exit,
-	       .flags        = SCX_OPS_BUILTIN_IDLE_PER_NODE,
+	       .flags        = SCX_OPS_BUILTIN_IDLE_PER_NODE |
+			       SCX_OPS_KEEP_BUILTIN_IDLE,
 	       .name         = "pandemonium");
\ No newline at end of file
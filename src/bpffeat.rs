@@ -0,0 +1,213 @@
+// PANDEMONIUM BPF TOOLCHAIN + KERNEL FEATURE REPORT (`pandemonium check`)
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// AN OLD clang OR libbpf PRODUCES BPF THE VERIFIER REJECTS, AND THE
+// REJECTION READS LIKE A PANDEMONIUM BUG. `check` (preflight.rs) PRINTS
+// MINIMUM REQUIRED VS DETECTED FOR
+//   - THE TOOLCHAIN: clang (RUN), libbpf + libbpf-rs (LINKED IN, FIXED AT
+//     BUILD TIME), bpftool (RUN; FIRST BUILD ONLY)
+//   - KERNEL BTF (/sys/kernel/btf/vmlinux: CO-RE RELOCATIONS AND THE
+//     KFUNC SCAN BOTH READ IT)
+//   - BPF FEATURES, EACH DECIDED BY A TINY PROBE LOAD (cli/check.rs). THE
+//     LOADS NEED ROOT: WITHOUT IT THEY ARE SKIPPED WITH A NOTE.
+//
+// FEATURES main.bpf.c DOES NOT USE ARE STILL PROBED (A FUTURE BUILD MAY)
+// BUT ONLY WARN WHEN MISSING.
+
+use std::fmt;
+
+use crate::compat::Compat;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    // "18.1.8", "v7.4.0", "14.0.0-1ubuntu1", "1.4": MISSING PARTS ARE 0,
+    // ANYTHING AFTER THE NUMBERS IS IGNORED
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let end = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let mut parts = s[..end].split('.').map(|p| p.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().and_then(Result::ok).unwrap_or(0);
+        let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// build.rs COMPILES main.bpf.c WITH -std=gnu23: clang 18 FIRST SPELLS IT
+pub const MIN_CLANG: Version = Version::new(18, 0, 0);
+// libbpf-rs 0.26 BINDS THE libbpf 1.x API
+pub const MIN_LIBBPF: Version = Version::new(1, 0, 0);
+// Cargo.toml PINS libbpf-rs = "=0.26.1"
+pub const LIBBPF_RS_VERSION: &str = "0.26.1";
+
+// `clang --version`: "clang version 18.1.8", "Ubuntu clang version
+// 14.0.0-1ubuntu1.1", "Apple clang version 15.0.0 (clang-1500.3.9.4)"
+pub fn parse_clang_version(output: &str) -> Option<Version> {
+    output.lines().find_map(|line| {
+        let rest = &line[line.find("clang version ")? + "clang version ".len()..];
+        Version::parse(rest.split_whitespace().next()?)
+    })
+}
+
+// `bpftool version`: "bpftool v7.4.0" ON THE FIRST LINE, THEN "using
+// libbpf v1.4" (OLD BUILDS: "/usr/sbin/bpftool v5.15.0")
+pub fn parse_bpftool_version(output: &str) -> Option<Version> {
+    let first = output.lines().next()?;
+    let mut words = first.split_whitespace();
+    if !words.next()?.ends_with("bpftool") {
+        return None;
+    }
+    Version::parse(words.next()?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    StructOps,
+    TaskStorage,
+    Ringbuf,
+    Timer,
+    Iterators,
+}
+
+pub struct FeatureReq {
+    pub feature: Feature,
+    pub name: &'static str,
+    pub min_kernel: &'static str,
+    pub used: bool, // main.bpf.c DEPENDS ON IT
+}
+
+pub const FEATURES: &[FeatureReq] = &[
+    FeatureReq {
+        feature: Feature::StructOps,
+        name: "struct_ops programs",
+        min_kernel: "5.6",
+        used: true,
+    },
+    FeatureReq {
+        feature: Feature::TaskStorage,
+        name: "task-local storage",
+        min_kernel: "5.11",
+        used: true,
+    },
+    FeatureReq {
+        feature: Feature::Ringbuf,
+        name: "ringbuf map",
+        min_kernel: "5.8",
+        used: false,
+    },
+    FeatureReq {
+        feature: Feature::Timer,
+        name: "bpf_timer",
+        min_kernel: "5.15",
+        used: false,
+    },
+    FeatureReq {
+        feature: Feature::Iterators,
+        name: "task iterators",
+        min_kernel: "5.8",
+        used: false,
+    },
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Probe {
+    Supported,
+    Unsupported,
+    Failed(i32), // THE PROBE ITSELF ERRORED (-errno): UNKNOWN, NOT MISSING
+    Skipped,     // NOT ROOT: NO PROGRAM LOADS
+}
+
+impl Probe {
+    // libbpf_probe_bpf_*: 1 SUPPORTED, 0 NOT, <0 -errno
+    pub fn from_libbpf(ret: i32) -> Self {
+        match ret {
+            1 => Self::Supported,
+            0 => Self::Unsupported,
+            e => Self::Failed(e),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub item: String,
+    pub minimum: String,
+    pub detected: String,
+    pub status: Compat,
+}
+
+// A TOOL BELOW ITS MINIMUM (OR MISSING) FAILS WHEN required, ELSE WARNS.
+// NO MINIMUM: ANY VERSION WILL DO.
+pub fn tool_row(
+    item: &str,
+    minimum: Option<Version>,
+    detected: Option<Version>,
+    required: bool,
+) -> Row {
+    let short = if required { Compat::Fail } else { Compat::Warn };
+    let status = match (detected, minimum) {
+        (None, _) => short,
+        (Some(v), Some(min)) if v < min => short,
+        _ => Compat::Pass,
+    };
+    Row {
+        item: item.to_string(),
+        minimum: minimum.map_or("any".to_string(), |m| m.to_string()),
+        detected: detected.map_or("missing".to_string(), |v| v.to_string()),
+        status,
+    }
+}
+
+// kb: SIZE OF /sys/kernel/btf/vmlinux, None WHEN ABSENT OR UNPARSABLE
+pub fn btf_row(kb: Option<u64>) -> Row {
+    Row {
+        item: "kernel BTF".to_string(),
+        minimum: "present".to_string(),
+        detected: kb.map_or("missing".to_string(), |kb| format!("{} KB", kb)),
+        status: if kb.is_some() {
+            Compat::Pass
+        } else {
+            Compat::Fail
+        },
+    }
+}
+
+// A MISSING FEATURE THE PROGRAM USES FAILS; ONE IT DOES NOT, OR A PROBE
+// THAT COULD NOT RUN, WARNS
+pub fn feature_row(req: &FeatureReq, probe: &Probe) -> Row {
+    let (detected, status) = match probe {
+        Probe::Supported => ("yes".to_string(), Compat::Pass),
+        Probe::Unsupported if req.used => ("no".to_string(), Compat::Fail),
+        Probe::Unsupported => ("no (unused)".to_string(), Compat::Warn),
+        Probe::Failed(e) => (format!("probe error {}", e), Compat::Warn),
+        Probe::Skipped => ("skipped (not root)".to_string(), Compat::Warn),
+    };
+    Row {
+        item: req.name.to_string(),
+        minimum: format!("kernel {}", req.min_kernel),
+        detected,
+        status,
+    }
+}
@@ -0,0 +1,180 @@
+// PANDEMONIUM vmlinux.h CACHE STAMP
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN build.rs (VIA #[path]) AND LIB CRATE (tests)
+//
+// build.rs CACHES THE bpftool-GENERATED vmlinux.h ACROSS BUILDS. A HEADER
+// FROM THE PREVIOUS KERNEL STILL COMPILES, BUT AGAINST OLD STRUCT LAYOUTS:
+// THE RESULT IS VERIFIER FAILURES OR DEPRECATED-API WARNINGS AT LOAD, NOT
+// A BUILD ERROR. A STAMP FILE NEXT TO THE CACHE RECORDS WHICH KERNEL (AND
+// WHICH BTF BLOB) THE HEADER CAME FROM; ANY MISMATCH REGENERATES IT.
+//
+// STAMP FORMAT, ONE key=value PER LINE:
+//   release=<uname -r>
+//   btf_fnv1a=<16 HEX DIGITS>   (ABSENT WHEN THE BTF FILE WAS UNREADABLE)
+//
+// HEADER SOURCE, FIRST MATCH WINS:
+//   --features stub-bpf       NO HEADER AND NO BPF COMPILE (scheduler_stub.rs)
+//   PANDEMONIUM_VMLINUX_H     THAT FILE, AS-IS
+//   (DEFAULT)                 THE STAMPED CACHE, bpftool ON A MISS
+// ONLY THE DEFAULT NEEDS /sys/kernel/btf/vmlinux OR bpftool.
+//
+// SENTINEL SCAN: ON A KERNEL WITHOUT CONFIG_SCHED_CLASS_EXT THE HEADER HAS
+// NO sched_ext TYPES AND CLANG FAILS WITH HUNDREDS OF ERRORS. build.rs
+// CHECKS FOR A FEW DEFINITIONS FIRST AND STOPS WITH ONE MESSAGE INSTEAD.
+
+pub const BTF_PATH: &str = "/sys/kernel/btf/vmlinux";
+pub const OSRELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+pub const VMLINUX_OVERRIDE_ENV: &str = "PANDEMONIUM_VMLINUX_H";
+pub const ALLOW_MISSING_SCX_ENV: &str = "PANDEMONIUM_ALLOW_MISSING_SCX";
+
+// DEFINED BY EVERY sched_ext KERNEL main.bpf.c CAN LOAD ON
+pub const SCX_SENTINEL_TYPES: [&str; 3] = ["sched_ext_ops", "scx_dispatch_q", "bpf_iter_scx_dsq"];
+
+// A REAL vmlinux.h IS MEGABYTES; ANYTHING THIS SMALL IS A FAILED DUMP
+pub const MIN_HEADER_BYTES: u64 = 1000;
+
+// FNV-1A 64: NO DEPENDENCY, AND ONE BIT OF BTF CHANGE MOVES THE HASH
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheStamp {
+    pub release: String,
+    pub btf_hash: Option<u64>,
+}
+
+impl CacheStamp {
+    pub fn new(release: &str, btf: Option<&[u8]>) -> Self {
+        Self {
+            release: release.trim().to_string(),
+            btf_hash: btf.map(fnv1a64),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!("release={}\n", self.release);
+        if let Some(h) = self.btf_hash {
+            out.push_str(&format!("btf_fnv1a={:016x}\n", h));
+        }
+        out
+    }
+
+    // None WITHOUT A release LINE; UNKNOWN KEYS ARE SKIPPED
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut release = None;
+        let mut btf_hash = None;
+        for line in text.lines() {
+            match line.trim().split_once('=') {
+                Some(("release", v)) => release = Some(v.to_string()),
+                Some(("btf_fnv1a", v)) => btf_hash = u64::from_str_radix(v, 16).ok(),
+                _ => {}
+            }
+        }
+        Some(Self {
+            release: release?,
+            btf_hash,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderSource {
+    Stub,
+    Override(String),
+    HostCache,
+}
+
+impl HeaderSource {
+    // stub: build.rs's CARGO_FEATURE_STUB_BPF; override_path: PANDEMONIUM_VMLINUX_H
+    pub fn select(stub: bool, override_path: Option<&str>) -> Self {
+        match override_path {
+            _ if stub => Self::Stub,
+            Some(path) if !path.is_empty() => Self::Override(path.to_string()),
+            _ => Self::HostCache,
+        }
+    }
+
+    // RUNS bpftool / READS THE HOST'S BTF
+    pub fn needs_host_btf(&self) -> bool {
+        *self == Self::HostCache
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheVerdict {
+    Fresh,
+    Missing,   // NO HEADER, OR TOO SMALL TO BE ONE
+    Unstamped, // HEADER FROM A BUILD THAT PREDATES STAMPS
+    KernelChanged { was: String, now: String },
+    BtfChanged, // SAME RELEASE STRING, DIFFERENT BTF (REBUILT KERNEL)
+}
+
+impl CacheVerdict {
+    pub fn is_fresh(&self) -> bool {
+        *self == Self::Fresh
+    }
+
+    // WHY THE HEADER IS REGENERATED; None WHEN THERE WAS NOTHING TO REPLACE
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            Self::Fresh | Self::Missing => None,
+            Self::Unstamped => Some("cached vmlinux.h has no kernel stamp".to_string()),
+            Self::KernelChanged { was, now } => {
+                Some(format!("kernel changed from {} to {}", was, now))
+            }
+            Self::BtfChanged => Some("kernel BTF changed under the same release".to_string()),
+        }
+    }
+}
+
+// THE BTF HASH IS COMPARED ONLY WHEN BOTH SIDES HAVE ONE: AN UNREADABLE
+// BTF FILE FALLS BACK TO THE RELEASE STRING ALONE
+pub fn check_cache(
+    header_bytes: Option<u64>,
+    stamp: Option<&str>,
+    now: &CacheStamp,
+) -> CacheVerdict {
+    if header_bytes.is_none_or(|n| n <= MIN_HEADER_BYTES) {
+        return CacheVerdict::Missing;
+    }
+    let Some(was) = stamp.and_then(CacheStamp::parse) else {
+        return CacheVerdict::Unstamped;
+    };
+    if was.release != now.release {
+        return CacheVerdict::KernelChanged {
+            was: was.release,
+            now: now.release.clone(),
+        };
+    }
+    match (was.btf_hash, now.btf_hash) {
+        (Some(a), Some(b)) if a != b => CacheVerdict::BtfChanged,
+        _ => CacheVerdict::Fresh,
+    }
+}
+
+// SENTINELS WITH NO `struct <name> {` DEFINITION. bpftool EMITS EACH
+// DEFINITION AT COLUMN 0; A FORWARD DECLARATION (`struct x;`) DOESN'T COUNT
+pub fn missing_scx_types(header: &str) -> Vec<&'static str> {
+    SCX_SENTINEL_TYPES
+        .into_iter()
+        .filter(|name| {
+            let def = format!("struct {} {{", name);
+            !header.lines().any(|l| l.starts_with(&def))
+        })
+        .collect()
+}
+
+// SOURCE NAMES WHERE THE HEADER CAME FROM: "kernel <release>" OR THE OVERRIDE
+pub fn missing_scx_message(source: &str, missing: &[&str]) -> String {
+    format!(
+        "vmlinux.h from {} has no sched_ext types (missing: {}). \
+         PANDEMONIUM needs a kernel built with CONFIG_SCHED_CLASS_EXT=y (6.12+). \
+         Set {}=1 to skip the BPF skeleton (docs, lib tests only).",
+        source,
+        missing.join(", "),
+        ALLOW_MISSING_SCX_ENV
+    )
+}
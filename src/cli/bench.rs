@@ -1,14 +1,31 @@
 use std::fs::{self, File};
+use std::io::Read;
 use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use anyhow::{bail, Result};
 use clap::ValueEnum;
 
+use pandemonium::benchanalyze::{self, all_pairs, check_percentiles, find};
+use pandemonium::benchout::{
+    point_latest, prune_bench_dirs, samples_file, write_samples, BenchLayout, Json,
+};
+use pandemonium::iobench::{parse_io_report, total_iops, IoKind, IoProfile, IO_FILE_BYTES};
+use pandemonium::probe::{parse_audio_output, summarize_probe};
+use pandemonium::runusage::{
+    format_usage_row, mean_usage, usage_header, usage_summary_lines, RunUsage,
+};
+
 use super::child_guard::ChildGuard;
-use super::report::{format_delta, format_latency_delta, mean_stdev, percentile, save_report};
-use super::{binary_path, is_scx_active, self_exe, wait_for_activation, LOG_DIR, TARGET_DIR};
+use super::probe::ProbeProfile;
+use super::report::{
+    format_delta, format_latency_delta, format_rate_delta, format_xrun_delta, mean_stdev,
+    write_report,
+};
+use super::run::chrono_stamp;
+use super::{binary_path, require_scx_free, self_exe, wait_for_activation, LOG_DIR, TARGET_DIR};
 
 #[derive(Clone, ValueEnum)]
 pub enum BenchMode {
@@ -21,110 +38,117 @@ pub enum BenchMode {
     Mixed,
     /// A/B with user-provided command
     Cmd,
+    /// IO-wait workers (O_DIRECT reads + fsync writers) + interactive probe
+    Io,
 }
 
-// BUILD RELEASE BINARY AND RUN BENCH, SAVING LOGS
-pub fn run_bench_run(
-    mode: BenchMode,
-    cmd: Option<&str>,
-    iterations: usize,
-    clean_cmd: Option<&str>,
-    sched_args: &[String],
-) -> Result<()> {
-    fs::create_dir_all(LOG_DIR)?;
+// SCRATCH FILE FOR --mode io. /tmp IS OFTEN TMPFS, WHICH REJECTS O_DIRECT
+const IO_SCRATCH_DIR: &str = "/var/tmp/pandemonium";
+
+// HOW LONG EACH PHASE RUNS THE IO WORKERS
+const IO_PHASE_SECS: u64 = 30;
+
+// WHERE A BENCH WRITES AND WHAT HAPPENS BEFORE IT (SEE benchout.rs)
+pub struct BenchOutput {
+    pub build: bool,              // cargo build --release FIRST
+    pub out_dir: Option<PathBuf>, // NONE: LOG_DIR/bench-<STAMP>
+    pub keep_logs: usize,         // bench-* DIRECTORIES KEPT UNDER LOG_DIR
+}
 
-    let build_out = File::create(format!("{}/build.out", LOG_DIR))?;
-    let build_err = File::create(format!("{}/build.err", LOG_DIR))?;
+// cargo build --release INTO TARGET_DIR, OUTPUT IN THE BENCH DIRECTORY
+fn build_release(out: &BenchLayout) -> Result<()> {
+    log_info!("Building release binary...");
+    let log = File::create(out.build_log())?;
     let status = Command::new("cargo")
         .args(["build", "--release"])
         .env("CARGO_TARGET_DIR", TARGET_DIR)
-        .stdout(Stdio::from(build_out))
-        .stderr(Stdio::from(build_err))
+        .stdout(Stdio::from(log.try_clone()?))
+        .stderr(Stdio::from(log))
         .status()?;
     if !status.success() {
-        bail!("BUILD FAILED. SEE {}/build.err", LOG_DIR);
-    }
-
-    let extra_args: Vec<String> = sched_args.to_vec();
-
-    let bench_out = File::create(format!("{}/bench.out", LOG_DIR))?;
-    let bench_err = File::create(format!("{}/bench.err", LOG_DIR))?;
-    let mut bench_cmd = Command::new(binary_path());
-    let mode_name = mode
-        .to_possible_value()
-        .ok_or_else(|| anyhow::anyhow!("INVALID BENCH MODE"))?
-        .get_name()
-        .to_string();
-    bench_cmd
-        .arg("bench")
-        .arg("--mode")
-        .arg(mode_name)
-        .arg("--iterations")
-        .arg(iterations.to_string());
-    if let Some(c) = cmd {
-        bench_cmd.arg("--cmd").arg(c);
+        bail!("BUILD FAILED. SEE {}", out.build_log().display());
     }
-    if let Some(cc) = clean_cmd {
-        bench_cmd.arg("--clean-cmd").arg(cc);
-    }
-    if !extra_args.is_empty() {
-        bench_cmd.arg("--").args(extra_args);
-    }
-
-    let status = bench_cmd
-        .stdout(Stdio::from(bench_out))
-        .stderr(Stdio::from(bench_err))
-        .status()?;
-    if !status.success() {
-        bail!("BENCH FAILED. SEE {}/bench.err", LOG_DIR);
-    }
-
-    log_info!("Build logs: {}/build.out {}/build.err", LOG_DIR, LOG_DIR);
-    log_info!("Bench logs: {}/bench.out {}/bench.err", LOG_DIR, LOG_DIR);
     Ok(())
 }
 
-fn timed_run(cmd: &str) -> Option<f64> {
+// sh -c cmd, REAPED WITH wait4() SO THE ITERATION'S rusage (CPU TIME,
+// MAX RSS, CONTEXT SWITCHES OF THE WHOLE TREE) COMES BACK WITH ITS WALL TIME
+fn timed_run(cmd: &str) -> Option<RunUsage> {
     log_info!("Running: {}", cmd);
     let start = Instant::now();
-    let result = Command::new("sh")
+    let mut child = match Command::new("sh")
         .args(["-c", cmd])
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
-        .output();
-    let elapsed = start.elapsed().as_secs_f64();
-    match result {
-        Ok(r) if r.status.success() => {
-            log_info!("Completed in {:.2}s", elapsed);
-            Some(elapsed)
-        }
-        Ok(r) => {
-            let stderr = String::from_utf8_lossy(&r.stderr);
-            log_error!(
-                "Command failed (exit {}): {}",
-                r.status.code().unwrap_or(-1),
-                &stderr[..stderr.len().min(500)]
-            );
-            None
-        }
+        .spawn()
+    {
+        Ok(c) => c,
         Err(e) => {
             log_error!("Command failed: {}", e);
-            None
+            return None;
         }
+    };
+    // DRAIN stderr WHILE WAITING: A FULL PIPE WOULD STALL THE COMMAND
+    let mut stderr_pipe = child.stderr.take();
+    let drain = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(p) = stderr_pipe.as_mut() {
+            let _ = p.read_to_end(&mut buf);
+        }
+        buf
+    });
+
+    let mut status = 0;
+    let mut ru: libc::rusage = unsafe { std::mem::zeroed() };
+    let rc = loop {
+        let rc = unsafe { libc::wait4(child.id() as i32, &mut status, 0, &mut ru) };
+        if rc >= 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            break rc;
+        }
+    };
+    let elapsed = start.elapsed().as_secs_f64();
+    let stderr = drain.join().unwrap_or_default();
+    if rc < 0 {
+        log_error!("Command failed: wait4: {}", std::io::Error::last_os_error());
+        return None;
     }
-}
 
-fn start_scheduler(sched_args: &[String]) -> Result<ChildGuard> {
-    let bin = binary_path();
-    let mut args = Vec::new();
-    args.extend(sched_args.iter().cloned());
+    let exited = libc::WIFEXITED(status);
+    if exited && libc::WEXITSTATUS(status) == 0 {
+        let usage = RunUsage::from_rusage(elapsed, &ru);
+        log_info!(
+            "Completed in {:.2}s (user {:.2}s, sys {:.2}s, {} involuntary switches)",
+            usage.wall_s,
+            usage.user_s,
+            usage.sys_s,
+            usage.ivcsw
+        );
+        Some(usage)
+    } else {
+        let stderr = String::from_utf8_lossy(&stderr);
+        log_error!(
+            "Command failed (exit {}): {}",
+            if exited {
+                libc::WEXITSTATUS(status)
+            } else {
+                -1
+            },
+            &stderr[..stderr.len().min(500)]
+        );
+        None
+    }
+}
 
-    let child = Command::new("sudo")
-        .arg(&bin)
-        .args(&args)
+// stdout + stderr GO TO log: A PIPE NOBODY READS FILLS UP AND STALLS IT
+fn start_scheduler(sched_args: &[String], log: &Path) -> Result<ChildGuard> {
+    let argv: Vec<String> = std::iter::once(binary_path())
+        .chain(sched_args.iter().cloned())
+        .collect();
+    let log = File::create(log)?;
+    let child = super::privileged_command(&[], &argv)?
         .process_group(0)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stdout(Stdio::from(log.try_clone()?))
+        .stderr(Stdio::from(log))
         .spawn()?;
     Ok(ChildGuard::new(child))
 }
@@ -133,45 +157,135 @@ fn stop_scheduler(guard: &mut ChildGuard) {
     guard.stop();
 }
 
-fn ensure_scheduler_started(sched_args: &[String]) -> Result<ChildGuard> {
-    let guard = start_scheduler(sched_args)?;
+fn ensure_scheduler_started(sched_args: &[String], log: &Path) -> Result<ChildGuard> {
+    let guard = start_scheduler(sched_args, log)?;
     if !wait_for_activation(10) {
-        bail!("PANDEMONIUM DID NOT ACTIVATE WITHIN 10S");
+        bail!(
+            "PANDEMONIUM DID NOT ACTIVATE WITHIN 10S (SEE {})",
+            log.display()
+        );
     }
     log_info!("PANDEMONIUM is active");
     std::thread::sleep(Duration::from_secs(2));
     Ok(guard)
 }
 
+// START PROBE WITH DEATH PIPE + PROCESS GROUP. RETURNS THE GUARD AND THE
+// DEATH PIPE'S WRITE END, WHICH THE CALLER CLOSES AFTER COLLECTING OUTPUT.
+fn start_probe(probe_exe: &str, probe: ProbeProfile) -> Result<(ChildGuard, i32)> {
+    let (death_read, death_write) =
+        super::death_pipe::create_death_pipe().map_err(|e| anyhow::anyhow!("DEATH PIPE: {}", e))?;
+    let death_write_copy = death_write;
+    let probe_proc = unsafe {
+        Command::new(probe_exe)
+            .arg("probe")
+            .arg("--death-pipe-fd")
+            .arg(death_read.to_string())
+            .args(probe.args())
+            .process_group(0)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .pre_exec(move || {
+                libc::close(death_write_copy);
+                libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM as libc::c_ulong);
+                Ok(())
+            })
+            .spawn()?
+    };
+    super::death_pipe::close_fd(death_read);
+    Ok((ChildGuard::new(probe_proc), death_write))
+}
+
+// SIGTERM A PROBE OR IO WORKER GROUP AND COLLECT WHAT IT PRINTS AT EXIT
+fn collect_output(guard: ChildGuard) -> Result<String> {
+    unsafe {
+        libc::killpg(guard.id() as i32, libc::SIGTERM);
+    }
+    let output = guard.into_child().wait_with_output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// ONE PIPELINE FOR EVERY MODE: OUTPUT DIRECTORY, OPTIONAL BUILD, A/B, REPORT
+#[allow(clippy::too_many_arguments)]
 pub fn run_bench(
     mode: BenchMode,
     cmd: Option<&str>,
     iterations: usize,
     clean_cmd: Option<&str>,
+    probe: ProbeProfile,
+    io: IoProfile,
     sched_args: &[String],
+    output: &BenchOutput,
 ) -> Result<()> {
+    if matches!(mode, BenchMode::Cmd) && cmd.is_none() {
+        bail!("--cmd required for --mode cmd");
+    }
+
+    let out = match &output.out_dir {
+        Some(dir) => BenchLayout::new(dir.clone()),
+        None => BenchLayout::stamped(Path::new(LOG_DIR), &chrono_stamp()),
+    };
+    fs::create_dir_all(LOG_DIR)?;
+    fs::create_dir_all(out.dir())?;
+    // ABSOLUTE, SO bench-latest RESOLVES FOR A RELATIVE --out-dir TOO
+    let out = BenchLayout::new(fs::canonicalize(out.dir())?);
+    // ONLY AUTO-NAMED bench-* DIRECTORIES ROTATE; AN --out-dir ELSEWHERE IS LEFT ALONE
+    let _ = point_latest(Path::new(LOG_DIR), out.dir());
+    for old in prune_bench_dirs(Path::new(LOG_DIR), output.keep_logs) {
+        log_info!("Pruned {}", old.display());
+    }
+    log_info!("Output: {}", out.dir().display());
+
+    if output.build {
+        build_release(&out)?;
+    }
+
     match mode {
         BenchMode::SelfBuild => bench_general(
+            "self",
             &format!("CARGO_TARGET_DIR={} cargo build --release", TARGET_DIR),
             iterations,
             Some(&format!("cargo clean --target-dir {}", TARGET_DIR)),
             sched_args,
+            &out,
         ),
-        BenchMode::Cmd => {
-            let cmd = cmd.ok_or_else(|| anyhow::anyhow!("--cmd required for --mode cmd"))?;
-            bench_general(cmd, iterations, clean_cmd, sched_args)
-        }
-        BenchMode::Mixed => bench_mixed(sched_args),
-        BenchMode::Contention => bench_contention(sched_args),
+        BenchMode::Cmd => bench_general(
+            "cmd",
+            cmd.unwrap_or_default(),
+            iterations,
+            clean_cmd,
+            sched_args,
+            &out,
+        ),
+        BenchMode::Mixed => bench_mixed(sched_args, &out),
+        BenchMode::Contention => bench_contention(probe, sched_args, &out),
+        BenchMode::Io => bench_io(probe, io, sched_args, &out),
     }
 }
 
+fn usage_json(u: &RunUsage) -> Json {
+    Json::obj(vec![
+        ("wall_s", Json::Num(u.wall_s)),
+        ("user_s", Json::Num(u.user_s)),
+        ("sys_s", Json::Num(u.sys_s)),
+        ("max_rss_kb", Json::Num(u.max_rss_kb as f64)),
+        ("vcsw", Json::Num(u.vcsw as f64)),
+        ("ivcsw", Json::Num(u.ivcsw as f64)),
+    ])
+}
+
+fn sched_args_json(sched_args: &[String]) -> Json {
+    Json::Arr(sched_args.iter().map(|a| Json::str(a)).collect())
+}
+
 // A/B BENCHMARK: EEVDF VS PANDEMONIUM (GENERIC)
 fn bench_general(
+    mode: &str,
     cmd: &str,
     iterations: usize,
     clean_cmd: Option<&str>,
     sched_args: &[String],
+    out: &BenchLayout,
 ) -> Result<()> {
     let sep = "=".repeat(60);
     log_info!("PANDEMONIUM A/B benchmark");
@@ -181,38 +295,36 @@ fn bench_general(
         log_info!("Clean cmd: {}", cc);
     }
 
-    if is_scx_active() {
-        bail!("SCHED_EXT IS ALREADY ACTIVE. STOP IT BEFORE BENCHMARKING.");
-    }
+    require_scx_free()?;
 
     // PHASE 1: EEVDF BASELINE
     log_info!("Phase 1: EEVDF baseline");
-    let mut eevdf_times = Vec::new();
+    let mut eevdf_runs = Vec::new();
     for i in 0..iterations {
         log_info!("Iteration {}/{}", i + 1, iterations);
         if let Some(cc) = clean_cmd {
             let _ = Command::new("sh").args(["-c", cc]).output();
         }
         match timed_run(cmd) {
-            Some(t) => eevdf_times.push(t),
+            Some(u) => eevdf_runs.push(u),
             None => bail!("ABORTING BENCHMARK: COMMAND FAILED"),
         }
     }
 
     // PHASE 2: START PANDEMONIUM
     log_info!("Phase 2: starting PANDEMONIUM");
-    let mut pand_proc = ensure_scheduler_started(sched_args)?;
+    let mut pand_proc = ensure_scheduler_started(sched_args, &out.sched_log("PANDEMONIUM"))?;
 
     // PHASE 3: PANDEMONIUM BENCHMARK
     log_info!("Phase 3: PANDEMONIUM benchmark");
-    let mut pand_times = Vec::new();
+    let mut pand_runs = Vec::new();
     for i in 0..iterations {
         log_info!("Iteration {}/{}", i + 1, iterations);
         if let Some(cc) = clean_cmd {
             let _ = Command::new("sh").args(["-c", cc]).output();
         }
         match timed_run(cmd) {
-            Some(t) => pand_times.push(t),
+            Some(u) => pand_runs.push(u),
             None => {
                 stop_scheduler(&mut pand_proc);
                 bail!("ABORTING BENCHMARK: COMMAND FAILED");
@@ -226,8 +338,9 @@ fn bench_general(
     log_info!("PANDEMONIUM stopped");
 
     // RESULTS
-    let (eevdf_mean, eevdf_std) = mean_stdev(&eevdf_times);
-    let (pand_mean, pand_std) = mean_stdev(&pand_times);
+    let wall = |runs: &[RunUsage]| runs.iter().map(|r| r.wall_s).collect::<Vec<_>>();
+    let (eevdf_mean, eevdf_std) = mean_stdev(&wall(&eevdf_runs));
+    let (pand_mean, pand_std) = mean_stdev(&wall(&pand_runs));
     let delta_pct = if eevdf_mean > 0.0 {
         ((pand_mean - eevdf_mean) / eevdf_mean) * 100.0
     } else {
@@ -245,38 +358,43 @@ fn bench_general(
         "EEVDF:       {:.2}s +/- {:.2}s",
         eevdf_mean, eevdf_std
     ));
-    report.push(format!(
-        "  RUNS: {}",
-        eevdf_times
-            .iter()
-            .map(|t| format!("{:.2}s", t))
-            .collect::<Vec<_>>()
-            .join(", ")
-    ));
+    report.push(usage_header());
+    for (i, u) in eevdf_runs.iter().enumerate() {
+        report.push(format_usage_row(&(i + 1).to_string(), u));
+    }
     report.push(format!(
         "PANDEMONIUM: {:.2}s +/- {:.2}s",
         pand_mean, pand_std
     ));
-    report.push(format!(
-        "  RUNS: {}",
-        pand_times
-            .iter()
-            .map(|t| format!("{:.2}s", t))
-            .collect::<Vec<_>>()
-            .join(", ")
+    report.push(usage_header());
+    for (i, u) in pand_runs.iter().enumerate() {
+        report.push(format_usage_row(&(i + 1).to_string(), u));
+    }
+    report.push(String::new());
+    report.extend(usage_summary_lines(
+        &mean_usage(&eevdf_runs),
+        &mean_usage(&pand_runs),
     ));
     report.push(String::new());
     report.push(format_delta(delta_pct, "BUILD"));
     report.push(sep.clone());
 
-    let report_text = report.join("\n") + "\n";
-    for line in &report {
-        println!("{}", line);
-    }
-
-    let path = save_report(&report_text, "benchmark")?;
-    println!("\nSAVED TO {}", path);
-    Ok(())
+    let phase = |runs: &[RunUsage]| {
+        Json::obj(vec![
+            ("runs", Json::Arr(runs.iter().map(usage_json).collect())),
+            ("mean", usage_json(&mean_usage(runs))),
+        ])
+    };
+    let json = Json::obj(vec![
+        ("mode", Json::str(mode)),
+        ("command", Json::str(cmd)),
+        ("iterations", Json::Num(iterations as f64)),
+        ("sched_args", sched_args_json(sched_args)),
+        ("eevdf", phase(&eevdf_runs)),
+        ("pandemonium", phase(&pand_runs)),
+        ("wall_delta_pct", Json::Num(delta_pct)),
+    ]);
+    write_report(out, &report, &json)
 }
 
 // PW-TOP SNAPSHOT: CAPTURE PIPEWIRE XRUN COUNTS
@@ -336,7 +454,7 @@ fn pw_get_xruns() -> i64 {
 }
 
 // MIXED BENCHMARK: COMPILE + AUDIO
-fn bench_mixed(sched_args: &[String]) -> Result<()> {
+fn bench_mixed(sched_args: &[String], out: &BenchLayout) -> Result<()> {
     let sep = "=".repeat(60);
     log_info!("PANDEMONIUM mixed workload benchmark");
 
@@ -350,9 +468,7 @@ fn bench_mixed(sched_args: &[String]) -> Result<()> {
         log_info!("  {} (xruns: {})", name, err);
     }
 
-    if is_scx_active() {
-        bail!("SCHED_EXT IS ALREADY ACTIVE. STOP IT BEFORE BENCHMARKING.");
-    }
+    require_scx_free()?;
 
     let build_cmd = format!("CARGO_TARGET_DIR={} cargo build --release", TARGET_DIR);
     let clean_cmd = format!("cargo clean --target-dir {}", TARGET_DIR);
@@ -364,14 +480,16 @@ fn bench_mixed(sched_args: &[String]) -> Result<()> {
     let _ = Command::new("sh").args(["-c", &clean_cmd]).output();
     let xruns_before = pw_get_xruns();
     log_info!("Xruns before: {}", xruns_before);
-    let eevdf_time = timed_run(&build_cmd).ok_or_else(|| anyhow::anyhow!("BUILD FAILED"))?;
+    let eevdf_time = timed_run(&build_cmd)
+        .ok_or_else(|| anyhow::anyhow!("BUILD FAILED"))?
+        .wall_s;
     let xruns_after = pw_get_xruns();
     let eevdf_xruns = xruns_after - xruns_before;
     log_info!("Xruns after: {} (delta: {})", xruns_after, eevdf_xruns);
 
     // PHASE 2: START PANDEMONIUM
     log_info!("Phase 2: starting PANDEMONIUM");
-    let mut pand_proc = ensure_scheduler_started(&sched_args)?;
+    let mut pand_proc = ensure_scheduler_started(&sched_args, &out.sched_log("PANDEMONIUM"))?;
 
     // PHASE 3: PANDEMONIUM
     log_info!("Phase 3: PANDEMONIUM");
@@ -379,7 +497,7 @@ fn bench_mixed(sched_args: &[String]) -> Result<()> {
     let xruns_before = pw_get_xruns();
     log_info!("Xruns before: {}", xruns_before);
     let pand_time = match timed_run(&build_cmd) {
-        Some(t) => t,
+        Some(u) => u.wall_s,
         None => {
             stop_scheduler(&mut pand_proc);
             bail!("BUILD FAILED");
@@ -443,25 +561,34 @@ fn bench_mixed(sched_args: &[String]) -> Result<()> {
     }
     report.push(sep.clone());
 
-    let report_text = report.join("\n") + "\n";
-    for line in &report {
-        println!("{}", line);
-    }
-
-    let path = save_report(&report_text, "mixed")?;
-    println!("\nSAVED TO {}", path);
-    Ok(())
+    let phase = |build_s: f64, xruns: i64| {
+        Json::obj(vec![
+            ("build_s", Json::Num(build_s)),
+            ("audio_xruns", Json::Num(xruns as f64)),
+        ])
+    };
+    let json = Json::obj(vec![
+        ("mode", Json::str("mixed")),
+        ("sched_args", sched_args_json(&sched_args)),
+        ("eevdf", phase(eevdf_time, eevdf_xruns)),
+        ("pandemonium", phase(pand_time, pand_xruns)),
+        ("build_delta_pct", Json::Num(delta_pct)),
+        ("xrun_delta", Json::Num(xrun_delta as f64)),
+    ]);
+    write_report(out, &report, &json)
 }
 
 // CONTENTION BENCHMARK: COMPILE + INTERACTIVE PROBE
-fn bench_contention(sched_args: &[String]) -> Result<()> {
+fn bench_contention(probe: ProbeProfile, sched_args: &[String], out: &BenchLayout) -> Result<()> {
+    probe.validate()?;
     let sep = "=".repeat(60);
     log_info!("PANDEMONIUM contention benchmark");
-    log_info!("Workload: cargo build --release + interactive probe (10ms sleep/wake)");
+    log_info!(
+        "Workload: cargo build --release + interactive probe ({})",
+        probe.describe()
+    );
 
-    if is_scx_active() {
-        bail!("SCHED_EXT IS ALREADY ACTIVE. STOP IT BEFORE BENCHMARKING.");
-    }
+    require_scx_free()?;
 
     let build_cmd = format!("CARGO_TARGET_DIR={} cargo build --release", TARGET_DIR);
     let clean_cmd = format!("cargo clean --target-dir {}", TARGET_DIR);
@@ -481,6 +608,8 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
         median: f64,
         p99: f64,
         worst: f64,
+        worst_thread_p99: f64,
+        xruns_per_min: f64,
     }
 
     let phases: Vec<(&str, bool)> = vec![("EEVDF (DEFAULT)", false), ("PANDEMONIUM", true)];
@@ -491,7 +620,10 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
         log_info!("Phase: {}", phase_name);
 
         let mut pand_proc = if *use_scheduler {
-            Some(ensure_scheduler_started(&sched_args)?)
+            Some(ensure_scheduler_started(
+                &sched_args,
+                &out.sched_log(phase_name),
+            )?)
         } else {
             None
         };
@@ -499,27 +631,7 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
         // CLEAN BUILD
         let _ = Command::new("sh").args(["-c", &clean_cmd]).output();
 
-        // START PROBE WITH DEATH PIPE + PROCESS GROUP
-        let (death_read, death_write) = super::death_pipe::create_death_pipe()
-            .map_err(|e| anyhow::anyhow!("DEATH PIPE: {}", e))?;
-        let death_write_copy = death_write;
-        let probe_proc = unsafe {
-            Command::new(&probe_exe)
-                .arg("probe")
-                .arg("--death-pipe-fd")
-                .arg(death_read.to_string())
-                .process_group(0)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::null())
-                .pre_exec(move || {
-                    libc::close(death_write_copy);
-                    libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM as libc::c_ulong);
-                    Ok(())
-                })
-                .spawn()?
-        };
-        super::death_pipe::close_fd(death_read);
-        let probe_guard = ChildGuard::new(probe_proc);
+        let (probe_guard, death_write) = start_probe(&probe_exe, probe)?;
 
         // RUN BUILD
         log_info!("Building...");
@@ -548,13 +660,9 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
         std::thread::sleep(Duration::from_secs(1));
 
         // STOP PROBE AND COLLECT OUTPUT
-        unsafe {
-            libc::killpg(probe_guard.id() as i32, libc::SIGTERM);
-        }
-        let probe_child = probe_guard.into_child();
-        let probe_output = probe_child.wait_with_output()?;
+        let probe_stdout = collect_output(probe_guard)?;
         super::death_pipe::close_fd(death_write);
-        let probe_stdout = String::from_utf8_lossy(&probe_output.stdout);
+        write_samples(&out.probe_samples(phase_name), &probe_stdout)?;
 
         // STOP SCHEDULER IF RUNNING
         if let Some(ref mut p) = pand_proc {
@@ -562,23 +670,42 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
             log_info!("PANDEMONIUM stopped");
         }
 
-        // PARSE PROBE OUTPUT
-        let mut overshoots: Vec<f64> = probe_stdout
-            .lines()
-            .filter_map(|line| line.trim().parse::<f64>().ok())
-            .collect();
-        overshoots.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-        let n = overshoots.len();
-        let med = percentile(&overshoots, 50.0);
-        let p99 = percentile(&overshoots, 99.0);
-        let worst = overshoots.last().copied().unwrap_or(0.0);
+        // PARSE PROBE OUTPUT (POOLED + PER-THREAD)
+        let summary = summarize_probe(&probe_stdout)?;
+        let n = summary.pooled.samples;
+        let med = summary.pooled.median;
+        let p99 = summary.pooled.p99;
+        let worst = summary.pooled.worst;
+        let (worst_tid, worst_thread_p99) = summary.worst_thread_p99().unwrap_or((0, 0.0));
+        let audio = parse_audio_output(&probe_stdout).unwrap_or_default();
 
         log_info!("Build time: {:.2}s", build_time);
         log_info!("Probe samples: {}", n);
         log_info!("Median overshoot: {:.0}us", med);
         log_info!("P99 overshoot: {:.0}us", p99);
         log_info!("Worst overshoot: {:.0}us", worst);
+        if summary.per_thread.len() > 1 {
+            for (tid, t) in &summary.per_thread {
+                log_info!(
+                    "  thread {}: {} samples, median {:.0}us, p99 {:.0}us",
+                    tid,
+                    t.samples,
+                    t.median,
+                    t.p99
+                );
+            }
+            log_info!(
+                "Worst-thread P99: {:.0}us (thread {})",
+                worst_thread_p99,
+                worst_tid
+            );
+        }
+        log_info!(
+            "Audio xruns: {} of {} cycles ({:.1}/min)",
+            audio.xruns,
+            audio.cycles,
+            audio.xruns_per_min()
+        );
 
         results.push(PhaseResult {
             name: phase_name.to_string(),
@@ -587,6 +714,8 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
             median: med,
             p99,
             worst,
+            worst_thread_p99,
+            xruns_per_min: audio.xruns_per_min(),
         });
     }
 
@@ -606,40 +735,334 @@ fn bench_contention(sched_args: &[String]) -> Result<()> {
     report.push(sep.clone());
     report.push("CONTENTION BENCHMARK RESULTS".to_string());
     report.push(sep.clone());
-    report
-        .push("WORKLOAD: CARGO BUILD --RELEASE + INTERACTIVE PROBE (10MS SLEEP/WAKE)".to_string());
+    report.push(format!(
+        "WORKLOAD: CARGO BUILD --RELEASE + INTERACTIVE PROBE ({})",
+        probe.describe().to_uppercase()
+    ));
     report.push(String::new());
     report.push(format!(
-        "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8}",
-        "SCHEDULER", "BUILD", "SAMPLES", "MEDIAN", "P99", "WORST"
+        "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8} {:>9}",
+        "SCHEDULER", "BUILD", "SAMPLES", "MEDIAN", "P99", "WORST", "XRUNS/MIN"
     ));
     report.push(format!(
-        "{} {} {} {} {} {}",
+        "{} {} {} {} {} {} {}",
         "-".repeat(24),
         "-".repeat(8),
         "-".repeat(8),
         "-".repeat(8),
         "-".repeat(8),
         "-".repeat(8),
+        "-".repeat(9),
     ));
     for r in &results {
         report.push(format!(
-            "{:<24} {:>7.2}s {:>8} {:>7.0}us {:>7.0}us {:>7.0}us",
-            r.name, r.build_time, r.samples, r.median, r.p99, r.worst,
+            "{:<24} {:>7.2}s {:>8} {:>7.0}us {:>7.0}us {:>7.0}us {:>9.1}",
+            r.name, r.build_time, r.samples, r.median, r.p99, r.worst, r.xruns_per_min,
         ));
     }
     report.push(String::new());
     report.push(format_delta(build_delta, "BUILD"));
     report.push(format_latency_delta(med_delta, "MEDIAN"));
     report.push(format_latency_delta(p99_delta, "P99"));
+    if probe.threads > 1 {
+        report.push(format_latency_delta(
+            pand.worst_thread_p99 - eevdf.worst_thread_p99,
+            "WORST-THREAD P99",
+        ));
+    }
+    report.push(format_xrun_delta(pand.xruns_per_min - eevdf.xruns_per_min));
     report.push(sep.clone());
 
-    let report_text = report.join("\n") + "\n";
-    for line in &report {
-        println!("{}", line);
+    let phase = |r: &PhaseResult| {
+        Json::obj(vec![
+            ("samples_file", Json::str(&samples_file(&r.name))),
+            ("build_s", Json::Num(r.build_time)),
+            ("samples", Json::Num(r.samples as f64)),
+            ("median_us", Json::Num(r.median)),
+            ("p99_us", Json::Num(r.p99)),
+            ("worst_us", Json::Num(r.worst)),
+            ("worst_thread_p99_us", Json::Num(r.worst_thread_p99)),
+            ("xruns_per_min", Json::Num(r.xruns_per_min)),
+        ])
+    };
+    let json = Json::obj(vec![
+        ("mode", Json::str("contention")),
+        ("probe", Json::str(&probe.describe())),
+        ("sched_args", sched_args_json(&sched_args)),
+        ("eevdf", phase(eevdf)),
+        ("pandemonium", phase(pand)),
+        ("build_delta_pct", Json::Num(build_delta)),
+        ("median_delta_us", Json::Num(med_delta)),
+        ("p99_delta_us", Json::Num(p99_delta)),
+    ]);
+    write_report(out, &report, &json)
+}
+
+// SHARED SCRATCH FILE FOR THE IO WORKERS, REMOVED ON DROP
+struct IoScratch {
+    path: String,
+}
+
+impl IoScratch {
+    // FILLED WITH REAL DATA: DIRECT READS OF A SPARSE HOLE NEVER TOUCH THE DEVICE
+    fn create() -> Result<Self> {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        fs::create_dir_all(IO_SCRATCH_DIR)?;
+        let scratch = Self {
+            path: format!("{}/io-scratch-{}", IO_SCRATCH_DIR, std::process::id()),
+        };
+        let mut f = File::create(&scratch.path)?;
+        let chunk = vec![0xa5u8; 1 << 20];
+        for _ in 0..IO_FILE_BYTES / chunk.len() as u64 {
+            f.write_all(&chunk)?;
+        }
+        f.sync_all()?;
+
+        if let Err(e) = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(&scratch.path)
+        {
+            bail!("O_DIRECT UNSUPPORTED ON {} ({})", IO_SCRATCH_DIR, e);
+        }
+        Ok(scratch)
     }
+}
 
-    let path = save_report(&report_text, "contention")?;
-    println!("\nSAVED TO {}", path);
+impl Drop for IoScratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn start_io_worker(scratch: &IoScratch, kind: IoKind, io: IoProfile) -> Result<ChildGuard> {
+    let child = Command::new(self_exe())
+        .arg("io-worker")
+        .arg("--file")
+        .arg(&scratch.path)
+        .arg("--kind")
+        .arg(kind.name())
+        .arg("--io-size")
+        .arg(io.size.to_string())
+        .process_group(0)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(ChildGuard::new(child))
+}
+
+// IO BENCHMARK: DIRECT READS + FSYNC WRITERS + INTERACTIVE PROBE
+fn bench_io(
+    probe: ProbeProfile,
+    io: IoProfile,
+    sched_args: &[String],
+    out: &BenchLayout,
+) -> Result<()> {
+    probe.validate()?;
+    io.validate().map_err(anyhow::Error::msg)?;
+    let sep = "=".repeat(60);
+    log_info!("PANDEMONIUM io benchmark");
+    log_info!(
+        "Workload: {} + interactive probe ({}), {}s per phase",
+        io.describe(),
+        probe.describe(),
+        IO_PHASE_SECS
+    );
+
+    require_scx_free()?;
+
+    log_info!(
+        "Writing {}MB scratch file in {}",
+        IO_FILE_BYTES >> 20,
+        IO_SCRATCH_DIR
+    );
+    let scratch = IoScratch::create()?;
+    let probe_exe = self_exe().to_string_lossy().into_owned();
+    let sched_args = sched_args.to_vec();
+
+    struct PhaseResult {
+        name: String,
+        samples: usize,
+        median: f64,
+        p99: f64,
+        worst: f64,
+        read_iops: f64,
+        fsync_iops: f64,
+        xruns_per_min: f64,
+    }
+
+    let phases: Vec<(&str, bool)> = vec![("EEVDF (DEFAULT)", false), ("PANDEMONIUM", true)];
+
+    let mut results = Vec::new();
+
+    for (phase_name, use_scheduler) in &phases {
+        log_info!("Phase: {}", phase_name);
+
+        let mut pand_proc = if *use_scheduler {
+            Some(ensure_scheduler_started(
+                &sched_args,
+                &out.sched_log(phase_name),
+            )?)
+        } else {
+            None
+        };
+
+        let (probe_guard, death_write) = start_probe(&probe_exe, probe)?;
+        let mut workers = Vec::new();
+        for kind in IoKind::ALL {
+            for _ in 0..io.depth {
+                workers.push(start_io_worker(&scratch, kind, io)?);
+            }
+        }
+
+        log_info!("Running IO workers for {}s...", IO_PHASE_SECS);
+        std::thread::sleep(Duration::from_secs(IO_PHASE_SECS));
+
+        // WORKERS FIRST: EACH REPORTS OPS OVER ITS OWN ELAPSED TIME
+        let mut reports = Vec::new();
+        for w in workers {
+            let out = collect_output(w)?;
+            reports.extend(out.lines().filter_map(parse_io_report));
+        }
+        let probe_stdout = collect_output(probe_guard)?;
+        super::death_pipe::close_fd(death_write);
+        write_samples(&out.probe_samples(phase_name), &probe_stdout)?;
+
+        if let Some(ref mut p) = pand_proc {
+            stop_scheduler(p);
+            log_info!("PANDEMONIUM stopped");
+        }
+
+        if reports.len() < 2 * io.depth {
+            log_warn!("{} OF {} IO WORKERS REPORTED", reports.len(), 2 * io.depth);
+        }
+
+        let summary = summarize_probe(&probe_stdout)?;
+        let read_iops = total_iops(&reports, IoKind::Read);
+        let fsync_iops = total_iops(&reports, IoKind::Fsync);
+        let audio = parse_audio_output(&probe_stdout).unwrap_or_default();
+        log_info!("Probe samples: {}", summary.pooled.samples);
+        log_info!("Median overshoot: {:.0}us", summary.pooled.median);
+        log_info!("P99 overshoot: {:.0}us", summary.pooled.p99);
+        log_info!("Read IOPS: {:.0}", read_iops);
+        log_info!("Fsync IOPS: {:.0}", fsync_iops);
+        log_info!(
+            "Audio xruns: {} of {} cycles ({:.1}/min)",
+            audio.xruns,
+            audio.cycles,
+            audio.xruns_per_min()
+        );
+
+        results.push(PhaseResult {
+            name: phase_name.to_string(),
+            samples: summary.pooled.samples,
+            median: summary.pooled.median,
+            p99: summary.pooled.p99,
+            worst: summary.pooled.worst,
+            read_iops,
+            fsync_iops,
+            xruns_per_min: audio.xruns_per_min(),
+        });
+    }
+    drop(scratch);
+
+    // REPORT
+    let eevdf = &results[0];
+    let pand = &results[1];
+    let rate_delta = |a: f64, b: f64| if a > 0.0 { ((b - a) / a) * 100.0 } else { 0.0 };
+
+    let mut report = Vec::new();
+    report.push(sep.clone());
+    report.push("IO BENCHMARK RESULTS".to_string());
+    report.push(sep.clone());
+    report.push(format!(
+        "WORKLOAD: {} + INTERACTIVE PROBE ({}), {}S PER PHASE",
+        io.describe().to_uppercase(),
+        probe.describe().to_uppercase(),
+        IO_PHASE_SECS
+    ));
+    report.push(String::new());
+    report.push(format!(
+        "{:<24} {:>8} {:>8} {:>8} {:>8} {:>10} {:>10} {:>9}",
+        "SCHEDULER", "SAMPLES", "MEDIAN", "P99", "WORST", "READ IOPS", "FSYNC IOPS", "XRUNS/MIN"
+    ));
+    report.push(format!(
+        "{} {} {} {} {} {} {} {}",
+        "-".repeat(24),
+        "-".repeat(8),
+        "-".repeat(8),
+        "-".repeat(8),
+        "-".repeat(8),
+        "-".repeat(10),
+        "-".repeat(10),
+        "-".repeat(9),
+    ));
+    for r in &results {
+        report.push(format!(
+            "{:<24} {:>8} {:>7.0}us {:>7.0}us {:>7.0}us {:>10.0} {:>10.0} {:>9.1}",
+            r.name, r.samples, r.median, r.p99, r.worst, r.read_iops, r.fsync_iops, r.xruns_per_min,
+        ));
+    }
+    report.push(String::new());
+    report.push(format_latency_delta(pand.median - eevdf.median, "MEDIAN"));
+    report.push(format_latency_delta(pand.p99 - eevdf.p99, "P99"));
+    report.push(format_rate_delta(
+        rate_delta(eevdf.read_iops, pand.read_iops),
+        "READ IOPS",
+    ));
+    report.push(format_rate_delta(
+        rate_delta(eevdf.fsync_iops, pand.fsync_iops),
+        "FSYNC IOPS",
+    ));
+    report.push(format_xrun_delta(pand.xruns_per_min - eevdf.xruns_per_min));
+    report.push(sep.clone());
+
+    let phase = |r: &PhaseResult| {
+        Json::obj(vec![
+            ("samples_file", Json::str(&samples_file(&r.name))),
+            ("samples", Json::Num(r.samples as f64)),
+            ("median_us", Json::Num(r.median)),
+            ("p99_us", Json::Num(r.p99)),
+            ("worst_us", Json::Num(r.worst)),
+            ("read_iops", Json::Num(r.read_iops)),
+            ("fsync_iops", Json::Num(r.fsync_iops)),
+            ("xruns_per_min", Json::Num(r.xruns_per_min)),
+        ])
+    };
+    let json = Json::obj(vec![
+        ("mode", Json::str("io")),
+        ("workload", Json::str(&io.describe())),
+        ("probe", Json::str(&probe.describe())),
+        ("phase_secs", Json::Num(IO_PHASE_SECS as f64)),
+        ("sched_args", sched_args_json(&sched_args)),
+        ("eevdf", phase(eevdf)),
+        ("pandemonium", phase(pand)),
+        (
+            "read_iops_delta_pct",
+            Json::Num(rate_delta(eevdf.read_iops, pand.read_iops)),
+        ),
+        (
+            "fsync_iops_delta_pct",
+            Json::Num(rate_delta(eevdf.fsync_iops, pand.fsync_iops)),
+        ),
+    ]);
+    write_report(out, &report, &json)
+}
+
+// `pandemonium bench-analyze DIR`: THE RAW PROBE SAMPLES OF A FINISHED BENCH,
+// RE-READ (benchanalyze.rs). compare: TWO PHASES, ELSE EVERY PAIR.
+pub fn run_bench_analyze(dir: &Path, percentiles: &[f64], compare: &[String]) -> Result<()> {
+    check_percentiles(percentiles)?;
+    let phases = benchanalyze::load(dir)?;
+    let pairs = match compare {
+        [a, b] => vec![(find(&phases, a)?, find(&phases, b)?)],
+        _ => all_pairs(&phases),
+    };
+    log_info!("PANDEMONIUM bench analysis: {}", dir.display());
+    for line in benchanalyze::report(&phases, percentiles, &pairs) {
+        println!("{}", line);
+    }
     Ok(())
 }
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use pandemonium::blackbox::{
+    self, Record, BLACKBOX_PATH, BLACKBOX_UNCLEAN_PATH, KIND_KNOBS, KIND_SNAPSHOT, REGIME_BPF_ONLY,
+    SLOTS,
+};
+
+use crate::tuning::KNOB_NAMES;
+
+fn regime_label(code: u64) -> &'static str {
+    match code {
+        0 => "LIGHT",
+        1 => "MIXED",
+        2 => "HEAVY",
+        REGIME_BPF_ONLY => "BPF",
+        _ => "?",
+    }
+}
+
+fn print_record(r: &Record, newest_ts: u64) {
+    let age_s = newest_ts.saturating_sub(r.ts_ns) as f64 / 1_000_000_000.0;
+    let d = &r.data;
+    match r.kind {
+        KIND_SNAPSHOT => println!(
+            "{:<8.1} {:<10} {:<8} {:<8} {:<7} {:<8} {:<7} {:<7} {:<7} {:<8} {:<8} {:<7} {:<6} {}",
+            -age_s,
+            d[0],
+            d[1],
+            d[2],
+            d[3],
+            d[4],
+            d[5],
+            d[6],
+            d[7],
+            d[8],
+            d[9],
+            d[10],
+            regime_label(d[11]),
+            d[12] / 1000
+        ),
+        KIND_KNOBS => {
+            let knobs: Vec<String> = KNOB_NAMES
+                .iter()
+                .zip(d.iter())
+                .map(|(n, v)| format!("{}={}", n, v))
+                .collect();
+            println!(
+                "{:<8.1} KNOBS [{}] {}",
+                -age_s,
+                regime_label(d[10]),
+                knobs.join(" ")
+            );
+        }
+        k => println!("{:<8.1} UNKNOWN RECORD KIND {}", -age_s, k),
+    }
+}
+
+pub fn run_blackbox(file: Option<&str>, last: usize) -> Result<()> {
+    // DEFAULT: THE PRESERVED UNCLEAN RUN IF THERE IS ONE, ELSE THE LIVE RING
+    let path = match file {
+        Some(f) => f.to_string(),
+        None if Path::new(BLACKBOX_UNCLEAN_PATH).exists() => BLACKBOX_UNCLEAN_PATH.to_string(),
+        None => BLACKBOX_PATH.to_string(),
+    };
+    let bytes = match std::fs::read(&path) {
+        Ok(b) => b,
+        Err(e) => bail!("CANNOT READ {}: {}", path, e),
+    };
+    let dump = blackbox::decode(&bytes)?;
+
+    log_info!("Blackbox: {}", path);
+    log_info!(
+        "  PID {}  started {} (unix)  shutdown: {}",
+        dump.header.pid,
+        dump.header.start_s,
+        if dump.header.clean {
+            "clean"
+        } else {
+            "UNCLEAN"
+        }
+    );
+    log_info!(
+        "  {} records (ring holds {}), {} total written, {} torn",
+        dump.records.len(),
+        SLOTS,
+        dump.header.next_seq.saturating_sub(1),
+        dump.torn
+    );
+    if dump.torn > 0 {
+        log_warn!("TORN SLOTS SKIPPED (WRITE INTERRUPTED BY CRASH OR POWER LOSS)");
+    }
+
+    let skip = dump.records.len().saturating_sub(last);
+    let records = &dump.records[skip..];
+    let Some(newest) = records.last() else {
+        log_info!("No records");
+        return Ok(());
+    };
+
+    println!(
+        "\n{:<8} {:<10} {:<8} {:<8} {:<7} {:<8} {:<7} {:<7} {:<7} {:<8} {:<8} {:<7} {:<6} {}",
+        "T_S",
+        "DISPATCH",
+        "IDLE",
+        "SHARED",
+        "PREEMPT",
+        "KEEP",
+        "WAKE_US",
+        "KICK_H",
+        "KICK_S",
+        "LAT_IDLE",
+        "LAT_KICK",
+        "P99_US",
+        "REGIME",
+        "SLICE_US"
+    );
+    for r in records {
+        print_record(r, newest.ts_ns);
+    }
+    Ok(())
+}
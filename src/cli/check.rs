@@ -1,129 +1,178 @@
-use std::io::Read;
-use std::path::Path;
-use std::process::Command;
+use std::time::Duration;
 
 use anyhow::Result;
 
-fn check_tool(name: &str) -> bool {
-    Command::new("which")
-        .arg(name)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
+use crate::scheduler;
+use pandemonium::abi;
+use pandemonium::bpffeat::{Feature, Probe, Version};
+use pandemonium::client::Client;
+use pandemonium::compat;
+use pandemonium::container::{self, ContainerSignals, PinDir};
+use pandemonium::ctl;
+use pandemonium::preflight::{self, BpfProbes, Mode, PreflightReport, Status};
+use pandemonium::scxstate::{self, ScxRuntime};
 
-fn check_kernel_version() -> bool {
-    let release = std::fs::read_to_string("/proc/sys/kernel/osrelease")
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    let parts: Vec<&str> = release.split('.').collect();
-    if parts.len() >= 2 {
-        if let (Ok(major), Ok(minor)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-            if major > 6 || (major == 6 && minor >= 12) {
-                log_info!("Kernel {} (>= 6.12)", release);
-                return true;
+// ONE TINY PROBE LOAD PER FEATURE (libbpf_probe_bpf_*). ITERATORS HAVE NO
+// PROG TYPE OF THEIR OWN: A TRACING LOAD PLUS THE bpf_iter_task TARGET IN BTF
+fn probe_feature(feature: Feature, btf: Option<&[u8]>) -> Probe {
+    use libbpf_rs::libbpf_sys as sys;
+    let null = std::ptr::null();
+    let ret = unsafe {
+        match feature {
+            Feature::StructOps => {
+                sys::libbpf_probe_bpf_prog_type(sys::BPF_PROG_TYPE_STRUCT_OPS, null)
+            }
+            Feature::TaskStorage => {
+                sys::libbpf_probe_bpf_map_type(sys::BPF_MAP_TYPE_TASK_STORAGE, null)
             }
-            log_error!(
-                "Kernel {}.{} is too old. PANDEMONIUM requires 6.12+.",
-                major,
-                minor
-            );
-            log_error!("sched_ext (CONFIG_SCHED_CLASS_EXT) was merged in Linux 6.12.");
-            return false;
+            Feature::Ringbuf => sys::libbpf_probe_bpf_map_type(sys::BPF_MAP_TYPE_RINGBUF, null),
+            Feature::Timer => sys::libbpf_probe_bpf_helper(
+                sys::BPF_PROG_TYPE_SOCKET_FILTER,
+                sys::BPF_FUNC_timer_init,
+                null,
+            ),
+            Feature::Iterators => sys::libbpf_probe_bpf_prog_type(sys::BPF_PROG_TYPE_TRACING, null),
+        }
+    };
+    let probe = Probe::from_libbpf(ret);
+    if feature == Feature::Iterators && probe == Probe::Supported {
+        let has_iter = btf
+            .and_then(|data| compat::btf_strings(data).ok())
+            .is_some_and(|strtab| compat::has_symbol(strtab, "bpf_iter_task"));
+        if !has_iter {
+            return Probe::Unsupported;
         }
     }
-    log_warn!("Cannot parse kernel version from '{}'", release);
-    false
+    probe
 }
 
-fn check_vmlinux_cache() -> bool {
-    let cache = Path::new("/tmp/pandemonium-vmlinux.h");
-    if cache.exists() && cache.metadata().map(|m| m.len() > 1000).unwrap_or(false) {
-        let size = cache.metadata().map(|m| m.len() / 1024).unwrap_or(0);
-        log_info!("vmlinux.h cached ({size} KB)");
-        return true;
+// THE LINKED libbpf AND ITS PROBES, FOR preflight::run
+pub fn bpf_probes() -> BpfProbes {
+    let libbpf = unsafe {
+        Version::new(
+            libbpf_rs::libbpf_sys::libbpf_major_version(),
+            libbpf_rs::libbpf_sys::libbpf_minor_version(),
+            0,
+        )
+    };
+    BpfProbes {
+        libbpf,
+        probe: probe_feature,
     }
-    log_warn!("vmlinux.h not cached (will be downloaded on first build)");
-    true
 }
 
-fn check_kernel_config() -> bool {
-    let file = match std::fs::File::open("/proc/config.gz") {
-        Ok(f) => f,
-        Err(_) => {
-            log_warn!("/proc/config.gz not found (skipped)");
-            return true;
+pub fn preflight(mode: Mode, pins: &PinDir) -> PreflightReport {
+    preflight::run(mode, pins, &bpf_probes())
+}
+
+// `pandemonium check [--json]`: THE FULL PREFLIGHT REPORT. EXITS 1 ON ANY
+// FAIL, AFTER PRINTING EVERYTHING.
+pub fn run_check(pins: &PinDir, json: bool) -> Result<()> {
+    let report = preflight(Mode::Check, pins);
+    if json {
+        print!("{}", report.json().render());
+    } else {
+        log_info!("PANDEMONIUM dependency check");
+        for (status, line) in report.render_lines() {
+            match status {
+                Status::Ok => log_info!("{}", line),
+                Status::Warn => log_warn!("{}", line),
+                Status::Fail => log_error!("{}", line),
+            }
         }
-    };
-    let mut decoder = flate2::read::GzDecoder::new(file);
-    let mut config = String::new();
-    if decoder.read_to_string(&mut config).is_err() {
-        log_warn!("/proc/config.gz unreadable (skipped)");
-        return true;
     }
-    let found = config.contains("CONFIG_SCHED_CLASS_EXT=y");
-    if found {
-        log_info!("CONFIG_SCHED_CLASS_EXT=y found");
-    } else {
-        log_error!("CONFIG_SCHED_CLASS_EXT not found -- sched_ext may not be available");
+    if !report.ok() {
+        std::process::exit(1);
     }
-    found
+    Ok(())
 }
 
-pub fn run_check() -> Result<()> {
-    log_info!("PANDEMONIUM dependency check");
+// ONE-SECOND SAMPLE OF THE PINNED MAPS: NONZERO d/s PROVES BPF IS DISPATCHING
+fn runtime_health(pins: &PinDir) -> Result<String> {
+    let client = Client::new(pins.clone(), ctl::SOCKET_PATH);
+    let before = client.stats()?;
+    std::thread::sleep(Duration::from_secs(1));
+    let after = client.stats()?;
+    Ok(scxstate::health_line(
+        &after.delta(&before),
+        &client.knobs()?,
+    ))
+}
 
-    let mut ok = true;
-    let tools = ["cargo", "rustc", "clang", "sudo"];
-    for tool in &tools {
-        if check_tool(tool) {
-            log_info!("  {:<24}OK", tool);
-        } else {
-            log_error!("  {:<24}MISSING", tool);
-            ok = false;
-        }
+// `pandemonium check --runtime`: WHAT THE KERNEL SAYS IS ATTACHED, AND
+// WHETHER IT IS US. EXITS 1 UNLESS PANDEMONIUM OWNS THE sched_ext ROOT.
+pub fn run_runtime_check(pins: &PinDir) -> Result<()> {
+    let Some(rt) = ScxRuntime::read() else {
+        log_error!(
+            "sched_ext not available ({} missing): {}",
+            scxstate::SCX_SYSFS_ROOT,
+            container::sched_ext_hint(ContainerSignals::read().runtime().as_deref())
+        );
+        std::process::exit(1);
+    };
+
+    log_info!("sched_ext runtime ({}):", scxstate::SCX_SYSFS_ROOT);
+    for line in rt.summary_lines() {
+        log_info!("  {}", line);
     }
 
-    log_info!("Kernel version:");
-    if !check_kernel_version() {
-        ok = false;
+    if !rt.is_pandemonium() {
+        match &rt.ops {
+            Some(ops) => log_warn!("PANDEMONIUM is not attached ('{}' owns sched_ext)", ops),
+            None => log_warn!("PANDEMONIUM is not attached (no scheduler active)"),
+        }
+        std::process::exit(1);
     }
 
-    log_info!("Kernel config:");
-    if !check_kernel_config() {
-        ok = false;
+    // MAPS ARE PINNED RIGHT AFTER ATTACH: THE PIN'S MTIME IS THE ATTACH TIME
+    match std::fs::metadata(pins.stats())
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+    {
+        Some(up) => log_info!("  uptime:      {}", scxstate::format_uptime(up)),
+        None => log_warn!(
+            "  uptime:      unknown ({} not pinned)",
+            pins.stats().display()
+        ),
     }
 
-    log_info!("Build cache:");
-    check_vmlinux_cache();
+    match runtime_health(pins) {
+        Ok(line) => log_info!("  health: {}", line),
+        Err(e) => log_warn!("  health: unavailable ({:#})", e),
+    }
+    Ok(())
+}
 
-    let scx_path = Path::new("/sys/kernel/sched_ext/root/ops");
-    if scx_path.exists() {
-        let active = std::fs::read_to_string(scx_path).unwrap_or_default();
-        let active = active.trim();
-        if active.is_empty() {
-            log_info!("sched_ext available (no scheduler active)");
+// ONE PASS/FAIL ROW PER DECODED MAP FROM A LOADED, NEVER-ATTACHED SKELETON
+// (abi.rs). RETURNS THE FAILED ROWS.
+pub fn report_self_test() -> Result<usize> {
+    let rows = scheduler::self_test()?;
+    for row in &rows {
+        if row.pass() {
+            log_info!("  {}", row.line());
         } else {
-            log_info!("sched_ext active ({})", active);
+            log_error!("  {}", row.line());
         }
+    }
+    let failed = abi::failures(&rows);
+    if failed == 0 {
+        log_info!("{}", abi::summary(&rows));
     } else {
-        log_error!("sched_ext not available (sysfs path missing)");
-        ok = false;
+        log_error!("{}", abi::summary(&rows));
     }
+    Ok(failed)
+}
 
-    if ok {
-        log_info!("All checks passed");
-    } else {
-        log_error!("Some checks failed");
-        if !check_tool("cargo") || !check_tool("rustc") {
-            log_info!("  Install Rust: https://rustup.rs");
-        }
-        if !check_tool("clang") {
-            log_info!("  Install clang: pacman -S clang");
+// `pandemonium self-test`: EXITS 1 ON ANY MISMATCH OR A LOAD THAT FAILS
+pub fn run_self_test() -> Result<()> {
+    log_info!("PANDEMONIUM map ABI self-test");
+    match report_self_test() {
+        Ok(0) => Ok(()),
+        Ok(_) => std::process::exit(1),
+        Err(e) => {
+            log_error!("SELF-TEST COULD NOT LOAD THE BPF OBJECT: {:#}", e);
+            std::process::exit(1);
         }
-        std::process::exit(1);
     }
-
-    Ok(())
 }
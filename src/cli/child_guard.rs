@@ -26,6 +26,14 @@ impl ChildGuard {
         self.child.as_ref().map(|c| c.id()).unwrap_or(0)
     }
 
+    /// Non-blocking: true once the child has exited (or was consumed).
+    pub fn exited(&mut self) -> bool {
+        match self.child.as_mut() {
+            Some(c) => !matches!(c.try_wait(), Ok(None)),
+            None => true,
+        }
+    }
+
     /// Three-phase shutdown: SIGINT → wait 500ms → SIGKILL.
     /// Targets the entire process group via killpg.
     pub fn stop(&mut self) {
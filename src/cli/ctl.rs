@@ -0,0 +1,16 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+
+// ONE REQUEST LINE, PARSED RESPONSE. "ERR ..." REPLIES BECOME Err
+pub fn request(socket: &str, line: &str) -> Result<BTreeMap<String, String>> {
+    pandemonium::client::request(Path::new(socket), line)
+}
+
+pub fn run_ctl(socket: &str, words: &[String]) -> Result<()> {
+    for (k, v) in &request(socket, &words.join(" "))? {
+        println!("{} = {}", k, v);
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+
+use crate::cli::ctl::request;
+use crate::ctl::SOCKET_PATH;
+use crate::gamemode::gamemode_snippet;
+
+// THIN CLIENT: THE SCHEDULER TRACKS THE SESSIONS (gamemode.rs), SO TWO
+// OVERLAPPING GAMES NEST NO MATTER WHO CALLS start/end
+pub fn run_gamemode(socket: &str, start: bool, pid: Option<u32>) -> Result<()> {
+    let verb = if start { "start" } else { "end" };
+    let line = match pid {
+        Some(p) => format!("gamemode {} {}", verb, p),
+        None => format!("gamemode {}", verb),
+    };
+    for (k, v) in &request(socket, &line)? {
+        println!("{} = {}", k, v);
+    }
+    Ok(())
+}
+
+// PRINTED, NOT WRITTEN: gamemode.ini IS THE USER'S FILE
+// (pandemonium install --gamemode >> ~/.config/gamemode.ini)
+pub fn run_install_gamemode(socket: &str) -> Result<()> {
+    let exe = crate::cli::self_exe();
+    let socket = (socket != SOCKET_PATH).then_some(socket);
+    print!("{}", gamemode_snippet(&exe.to_string_lossy(), socket));
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// IO WORKER FOR `bench --mode io`
+// ONE SYNCHRONOUS IO STREAM AGAINST THE SHARED SCRATCH FILE. RUNS UNTIL
+// SIGINT/SIGTERM, THEN PRINTS ITS REPORT LINE (pandemonium::iobench).
+
+use std::fs::OpenOptions;
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+
+use pandemonium::iobench::{IoKind, IoProfile, IoReport, IO_ALIGN};
+
+static RUNNING: AtomicBool = AtomicBool::new(true);
+
+// PAGE-ALIGNED BUFFER: O_DIRECT REJECTS UNALIGNED USER MEMORY
+struct AlignedBuf {
+    ptr: *mut u8,
+    layout: std::alloc::Layout,
+}
+
+impl AlignedBuf {
+    fn new(len: usize) -> Self {
+        let layout = std::alloc::Layout::from_size_align(len, IO_ALIGN as usize).unwrap();
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            std::alloc::handle_alloc_error(layout);
+        }
+        Self { ptr, layout }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        unsafe { std::alloc::dealloc(self.ptr, self.layout) }
+    }
+}
+
+pub fn run_io_worker(file: &str, kind: &str, size: u64) -> Result<()> {
+    let profile = IoProfile { size, depth: 1 };
+    profile.validate().map_err(anyhow::Error::msg)?;
+    let kind = match IoKind::parse(kind) {
+        Some(k) => k,
+        None => bail!("UNKNOWN IO KIND {:?} (read, fsync)", kind),
+    };
+
+    ctrlc::set_handler(move || {
+        RUNNING.store(false, Ordering::Relaxed);
+    })
+    .ok();
+
+    let f = match kind {
+        IoKind::Read => OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(file)?,
+        IoKind::Fsync => OpenOptions::new().write(true).open(file)?,
+    };
+
+    let mut buf = AlignedBuf::new(profile.size as usize);
+    let slots = profile.slots();
+    // XORSHIFT SEEDED PER PROCESS: WORKERS SPREAD OVER DIFFERENT OFFSETS
+    let mut x = std::process::id() as u64 | 1;
+    let mut ops = 0u64;
+    let start = Instant::now();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        let offset = (x % slots) * profile.size;
+        let ok = match kind {
+            IoKind::Read => f.read_exact_at(buf.as_mut_slice(), offset).is_ok(),
+            IoKind::Fsync => {
+                f.write_all_at(buf.as_mut_slice(), offset).is_ok() && f.sync_data().is_ok()
+            }
+        };
+        // INTERRUPTED MID-OP: STOP COUNTING, DON'T FAIL THE REPORT
+        if !ok {
+            break;
+        }
+        ops += 1;
+    }
+
+    let report = IoReport {
+        kind,
+        ops,
+        elapsed_us: start.elapsed().as_micros() as u64,
+    };
+    println!("{}", report.line());
+    Ok(())
+}
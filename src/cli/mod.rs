@@ -1,12 +1,20 @@
 pub mod bench;
+pub mod blackbox;
 pub mod check;
 pub mod child_guard;
+pub mod ctl;
 pub mod death_pipe;
+pub mod gamemode;
+pub mod io_worker;
 pub mod probe;
+pub mod procdb;
 pub mod report;
 pub mod run;
+pub mod soak;
+pub mod status;
 pub mod stress;
 pub mod test_gate;
+pub mod topology;
 pub const TARGET_DIR: &str = "/tmp/pandemonium-build";
 pub const LOG_DIR: &str = "/tmp/pandemonium";
 
@@ -18,10 +26,31 @@ pub fn self_exe() -> std::path::PathBuf {
     std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from(binary_path()))
 }
 
+// argv AS ROOT: DIRECTLY WHEN ALREADY ROOT, ELSE VIA $PANDEMONIUM_SUDO OR
+// THE FIRST OF sudo/doas/run0 IN $PATH (src/privilege.rs)
+pub fn privileged_command(
+    env: &[(&str, &str)],
+    argv: &[String],
+) -> anyhow::Result<std::process::Command> {
+    let esc = pandemonium::privilege::Escalation::detect().map_err(anyhow::Error::msg)?;
+    log_info!("Running: {}", esc.wrap(env, argv).join(" "));
+    Ok(esc.command(env, argv))
+}
+
+// PER-CPU VALUES OF THE RUNNING SCHEDULER'S PINNED stats_map
+pub fn read_pinned_stats(
+    pins: &pandemonium::container::PinDir,
+) -> anyhow::Result<Vec<pandemonium::stats::PandemoniumStats>> {
+    pandemonium::client::Client::new(pins.clone(), pandemonium::ctl::SOCKET_PATH).stats_percpu()
+}
+
 pub fn is_scx_active() -> bool {
-    std::fs::read_to_string("/sys/kernel/sched_ext/root/ops")
-        .map(|s| !s.trim().is_empty())
-        .unwrap_or(false)
+    pandemonium::attach::active_scheduler().is_some()
+}
+
+// HARNESSES NEVER TAKE OVER: NAME THE ATTACHED SCHEDULER AND STOP
+pub fn require_scx_free() -> anyhow::Result<()> {
+    pandemonium::attach::ensure_free(false, |_, _| {})
 }
 
 pub fn wait_for_activation(timeout_secs: u64) -> bool {
@@ -1,15 +1,148 @@
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+
+use pandemonium::probe::{
+    audio_deadline_ns, next_audio_cycle, AudioStats, LatencyHist, SharedLatencyHist, AUDIO_WORK_US,
+    SELF_PROBE_COMM, SELF_PROBE_PERIOD_US,
+};
 
 static RUNNING: AtomicBool = AtomicBool::new(true);
 
 // PRE-ALLOCATED SAMPLE BUFFER -- NO I/O DURING MEASUREMENT
 const MAX_SAMPLES: usize = 16384;
 
+pub const DEFAULT_PERIOD_US: u64 = 10_000;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ProbeOutput {
+    /// One line per sample (overshoot_us, or "tid overshoot_us")
+    Raw,
+    /// Accumulate into fixed buckets, dump "hist tid lo_us count" at exit
+    Hist,
+}
+
+/// Probe wakeup pattern. Each iteration spins `spin_us` (emulated work),
+/// then sleeps for the rest of `period_us` and measures the overshoot.
+/// `threads` independent loops run concurrently (audio + input + compositor).
+/// `audio` adds an emulated 48kHz/64-frame audio callback that counts xruns.
+///   10000/0:   DEFAULT INTERACTIVE PROBE (10MS SLEEP/WAKE)
+///   1000/200:  AUDIO CALLBACK (1MS PERIOD, 200US DSP)
+///   16667/4000: 60FPS FRAME LOOP
+#[derive(Clone, Copy)]
+pub struct ProbeProfile {
+    pub period_us: u64,
+    pub spin_us: u64,
+    pub threads: usize,
+    pub audio: bool,
+}
+
+impl Default for ProbeProfile {
+    fn default() -> Self {
+        Self {
+            period_us: DEFAULT_PERIOD_US,
+            spin_us: 0,
+            threads: 1,
+            audio: false,
+        }
+    }
+}
+
+impl ProbeProfile {
+    pub fn validate(&self) -> Result<()> {
+        if self.period_us == 0 {
+            bail!("PROBE PERIOD MUST BE > 0");
+        }
+        if self.threads == 0 {
+            bail!("PROBE THREADS MUST BE > 0");
+        }
+        if self.spin_us >= self.period_us {
+            bail!(
+                "PROBE SPIN ({}us) MUST BE SHORTER THAN PERIOD ({}us)",
+                self.spin_us,
+                self.period_us
+            );
+        }
+        Ok(())
+    }
+
+    pub fn sleep_ns(&self) -> i64 {
+        ((self.period_us - self.spin_us) * 1000) as i64
+    }
+
+    // CONFIG HEADER. PARSERS SKIP IT: NOT A BARE INTEGER.
+    pub fn header(&self) -> String {
+        format!(
+            "# probe period_us={} spin_us={} threads={} audio={}",
+            self.period_us, self.spin_us, self.threads, self.audio as u8
+        )
+    }
+
+    // FORWARDED TO A CHILD `pandemonium probe`
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--period-us".to_string(),
+            self.period_us.to_string(),
+            "--spin-us".to_string(),
+            self.spin_us.to_string(),
+            "--threads".to_string(),
+            self.threads.to_string(),
+        ];
+        if self.audio {
+            args.push("--audio".to_string());
+        }
+        args
+    }
+
+    pub fn describe(&self) -> String {
+        let base = if self.spin_us == 0 {
+            format!("{}us sleep/wake", self.period_us)
+        } else {
+            format!("{}us period, {}us spin", self.period_us, self.spin_us)
+        };
+        let base = if self.threads > 1 {
+            format!("{}, {} threads", base, self.threads)
+        } else {
+            base
+        };
+        if self.audio {
+            format!("{}, audio callback", base)
+        } else {
+            base
+        }
+    }
+}
+
+fn clock_ns(clock: libc::clockid_t) -> i64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe {
+        libc::clock_gettime(clock, &mut ts);
+    }
+    ts.tv_sec * 1_000_000_000 + ts.tv_nsec
+}
+
+fn now_ns() -> i64 {
+    clock_ns(libc::CLOCK_MONOTONIC)
+}
+
 /// Interactive wakeup probe.
 /// When PANDEMONIUM is running, BPF records latencies to ring buffer.
 /// For EEVDF baseline, we measure in userspace.
 /// Either way: ZERO I/O during measurement, bulk output at end.
-pub fn run_probe(death_pipe_fd: Option<i32>) {
+pub fn run_probe(
+    death_pipe_fd: Option<i32>,
+    profile: ProbeProfile,
+    output: ProbeOutput,
+    header: bool,
+) -> Result<()> {
+    profile.validate()?;
+
     ctrlc::set_handler(move || {
         RUNNING.store(false, Ordering::Relaxed);
     })
@@ -19,39 +152,196 @@ pub fn run_probe(death_pipe_fd: Option<i32>) {
         super::death_pipe::spawn_death_watcher(fd, &RUNNING);
     }
 
-    let mut samples: Vec<i64> = Vec::with_capacity(MAX_SAMPLES);
+    // ONE MEASUREMENT LOOP PER THREAD, UNPINNED: THE SCHEDULER PLACES THEM
+    let handles: Vec<_> = (0..profile.threads)
+        .map(|_| std::thread::spawn(move || measure(profile, output)))
+        .collect();
+    let audio = profile.audio.then(|| std::thread::spawn(audio_callback));
+    let streams: Vec<Stream> = handles.into_iter().filter_map(|h| h.join().ok()).collect();
+    let audio = audio.and_then(|h| h.join().ok());
 
-    let target_ns: i64 = 10_000_000; // 10MS SLEEP TARGET
-    let req = libc::timespec {
-        tv_sec: 0,
-        tv_nsec: target_ns,
-    };
+    // BULK OUTPUT AT END -- USE write() DIRECTLY TO MINIMIZE OVERHEAD
+    use std::io::Write;
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    if header {
+        let _ = writeln!(handle, "{}", profile.header());
+    }
+    for (tid, stream) in streams.iter().enumerate() {
+        match stream {
+            // SINGLE THREAD: BARE overshoot_us (UNCHANGED FORMAT). MULTI: "tid overshoot_us"
+            Stream::Raw(samples) => {
+                for s in samples {
+                    if profile.threads > 1 {
+                        let _ = writeln!(handle, "{} {}", tid, s);
+                    } else {
+                        let _ = writeln!(handle, "{}", s);
+                    }
+                }
+            }
+            Stream::Hist(hist) => {
+                for line in hist.lines(tid as u32) {
+                    let _ = writeln!(handle, "{}", line);
+                }
+            }
+        }
+    }
+    if let Some(stats) = audio {
+        let _ = writeln!(handle, "{}", stats.line());
+    }
+    Ok(())
+}
+
+enum Stream {
+    Raw(Vec<i64>),
+    Hist(Box<LatencyHist>),
+}
+
+impl Stream {
+    fn new(output: ProbeOutput) -> Self {
+        match output {
+            ProbeOutput::Raw => Self::Raw(Vec::with_capacity(MAX_SAMPLES)),
+            ProbeOutput::Hist => Self::Hist(Box::default()),
+        }
+    }
+
+    // RAW STOPS AT THE PRE-ALLOCATED CAP; HIST NEVER FILLS
+    fn full(&self) -> bool {
+        match self {
+            Self::Raw(v) => v.len() >= MAX_SAMPLES,
+            Self::Hist(_) => false,
+        }
+    }
+
+    fn push(&mut self, overshoot_us: i64) {
+        match self {
+            Self::Raw(v) => v.push(overshoot_us),
+            Self::Hist(h) => h.record(overshoot_us as u64),
+        }
+    }
+}
+
+fn sleep_req(target_ns: i64) -> libc::timespec {
+    libc::timespec {
+        tv_sec: target_ns / 1_000_000_000,
+        tv_nsec: target_ns % 1_000_000_000,
+    }
+}
+
+// ONE SAMPLE: SLEEP target_ns, RETURN HOW LATE THE WAKEUP CAME BACK
+fn sleep_overshoot_us(req: &libc::timespec, target_ns: i64) -> i64 {
+    let t0 = now_ns();
+    unsafe {
+        libc::nanosleep(req, std::ptr::null_mut());
+    }
+    let elapsed_ns = now_ns() - t0;
+    (elapsed_ns - target_ns).max(0) / 1000
+}
+
+fn measure(profile: ProbeProfile, output: ProbeOutput) -> Stream {
+    let mut stream = Stream::new(output);
+
+    let target_ns = profile.sleep_ns();
+    let spin_ns = (profile.spin_us * 1000) as i64;
+    let req = sleep_req(target_ns);
 
-    // HOT LOOP: MEASURE + BUFFER. ZERO I/O.
-    while RUNNING.load(Ordering::Relaxed) && samples.len() < MAX_SAMPLES {
-        let mut t0 = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
+    // HOT LOOP: SPIN + MEASURE + BUFFER. ZERO I/O.
+    while RUNNING.load(Ordering::Relaxed) && !stream.full() {
+        if spin_ns > 0 {
+            let spin_end = now_ns() + spin_ns;
+            while now_ns() < spin_end {
+                std::hint::spin_loop();
+            }
+        }
+        stream.push(sleep_overshoot_us(&req, target_ns));
+    }
+    stream
+}
+
+// `run --self-probe`: THE PROBE LOOP AS A THREAD OF THE SCHEDULER ITSELF,
+// ONE SAMPLE EVERY SELF_PROBE_PERIOD_US INTO A HISTOGRAM THE MONITOR READS.
+// THE THREAD NAME IS SELF_PROBE_COMM: BPF KEYS ITS BOOST EXCLUSION ON IT.
+// DROPPING THE HANDLE STOPS AND JOINS THE THREAD.
+pub struct SelfProbe {
+    pub hist: Arc<SharedLatencyHist>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SelfProbe {
+    pub fn spawn() -> Result<Self> {
+        let hist = Arc::new(SharedLatencyHist::default());
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_hist, thread_stop) = (Arc::clone(&hist), Arc::clone(&stop));
+        let handle = std::thread::Builder::new()
+            .name(SELF_PROBE_COMM.to_string())
+            .spawn(move || {
+                let target_ns = (SELF_PROBE_PERIOD_US * 1000) as i64;
+                let req = sleep_req(target_ns);
+                while !thread_stop.load(Ordering::Relaxed) {
+                    thread_hist.record(sleep_overshoot_us(&req, target_ns) as u64);
+                }
+            })?;
+        Ok(Self {
+            hist,
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for SelfProbe {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+// EMULATED AUDIO CALLBACK. WAKES ON THE ABSOLUTE 48KHZ/64-FRAME GRID, BURNS
+// AUDIO_WORK_US OF THREAD CPU TIME (PREEMPTION STRETCHES IT, AS IT WOULD
+// STRETCH REAL DSP), AND COUNTS AN XRUN WHEN IT FINISHES PAST THE NEXT
+// GRID POINT. ZERO I/O: THE COUNTS ARE PRINTED AT EXIT.
+fn audio_callback() -> AudioStats {
+    let work_ns = (AUDIO_WORK_US * 1000) as i64;
+    let mut stats = AudioStats::default();
+    let start = now_ns();
+    let mut cycle = 1;
+
+    while RUNNING.load(Ordering::Relaxed) {
+        let wake = audio_deadline_ns(start, cycle);
+        let ts = libc::timespec {
+            tv_sec: wake / 1_000_000_000,
+            tv_nsec: wake % 1_000_000_000,
         };
-        let mut t1 = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
+        let rc = unsafe {
+            libc::clock_nanosleep(
+                libc::CLOCK_MONOTONIC,
+                libc::TIMER_ABSTIME,
+                &ts,
+                std::ptr::null_mut(),
+            )
         };
-        unsafe {
-            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut t0);
-            libc::nanosleep(&req, std::ptr::null_mut());
-            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut t1);
+        // SIGNAL BEFORE THE GRID POINT: RE-CHECK RUNNING, SAME DEADLINE
+        if rc == libc::EINTR {
+            continue;
         }
-        let elapsed_ns = (t1.tv_sec - t0.tv_sec) * 1_000_000_000 + (t1.tv_nsec - t0.tv_nsec);
-        let overshoot_us = (elapsed_ns - target_ns).max(0) / 1000;
-        samples.push(overshoot_us);
-    }
 
-    // BULK OUTPUT AT END -- USE write() DIRECTLY TO MINIMIZE OVERHEAD
-    use std::io::Write;
-    let stdout = std::io::stdout();
-    let mut handle = stdout.lock();
-    for s in &samples {
-        let _ = writeln!(handle, "{}", s);
+        let cpu_end = clock_ns(libc::CLOCK_THREAD_CPUTIME_ID) + work_ns;
+        while clock_ns(libc::CLOCK_THREAD_CPUTIME_ID) < cpu_end {
+            std::hint::spin_loop();
+        }
+        let done = now_ns();
+        stats.cycles += 1;
+
+        if done > audio_deadline_ns(start, cycle + 1) {
+            stats.xruns += 1;
+            cycle = next_audio_cycle(start, done);
+        } else {
+            cycle += 1;
+        }
     }
+    stats.elapsed_us = ((now_ns() - start) / 1000) as u64;
+    stats
 }
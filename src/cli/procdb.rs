@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use pandemonium::container::PinDir;
+use pandemonium::scxstate::PANDEMONIUM_OPS;
+
+use crate::cli::ctl::request;
+use crate::procdb::{decode_export, list_rows, ProcessDb, TaskClassEntry, MIN_CONFIDENCE};
+
+// A RUNNING SCHEDULER OWNS THE CACHE AND REWRITES IT ON EXIT, SO EDITING
+// THE FILE UNDER IT WOULD BE LOST: GO THROUGH ITS CONTROL SOCKET INSTEAD
+fn pandemonium_attached() -> bool {
+    pandemonium::attach::active_scheduler().as_deref() == Some(PANDEMONIUM_OPS)
+}
+
+pub fn run_export(socket: &str, file: &Path) -> Result<()> {
+    let (n, source) = if pandemonium_attached() {
+        let kv = request(socket, "procdb export")?;
+        let exchange = ProcessDb::exchange_path();
+        std::fs::copy(&exchange, file)?;
+        let _ = std::fs::remove_file(&exchange);
+        let n = kv.get("profiles").cloned().unwrap_or_default();
+        (n, "RUNNING SCHEDULER".to_string())
+    } else {
+        let cache = ProcessDb::default_path();
+        let db = ProcessDb::detached(ProcessDb::load_from_disk(&cache)?);
+        (db.export_to(file)?.to_string(), cache.display().to_string())
+    };
+    println!(
+        "EXPORTED {} PROFILES FROM {} TO {}",
+        n,
+        source,
+        file.display()
+    );
+    Ok(())
+}
+
+pub fn run_import(socket: &str, file: &Path, replace: bool) -> Result<()> {
+    // VALIDATE BEFORE TOUCHING ANYTHING: A CORRUPT FILE CHANGES NOTHING
+    let data = std::fs::read(file)?;
+    let incoming = decode_export(&data)?;
+    if incoming.is_empty() && !replace {
+        bail!("{} HOLDS NO PROFILES", file.display());
+    }
+
+    if pandemonium_attached() {
+        let exchange = ProcessDb::exchange_path();
+        if let Some(parent) = exchange.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&exchange, &data)?;
+        let line = if replace {
+            "procdb import replace"
+        } else {
+            "procdb import"
+        };
+        let kv = match request(socket, line) {
+            Ok(kv) => kv,
+            Err(e) => {
+                let _ = std::fs::remove_file(&exchange);
+                return Err(e);
+            }
+        };
+        let get = |k: &str| kv.get(k).cloned().unwrap_or_default();
+        println!(
+            "RUNNING SCHEDULER: {} NEW, {} MERGED, {} PROFILES TOTAL",
+            get("added"),
+            get("merged"),
+            get("profiles")
+        );
+        return Ok(());
+    }
+
+    // NOTHING RUNNING: EDIT THE CACHE. save() KEEPS ONLY BEHAVIORALLY
+    // CONFIDENT PROFILES, THE SAME RULE AS A SCHEDULER SHUTDOWN.
+    let cache = ProcessDb::default_path();
+    let existing = if replace {
+        Default::default()
+    } else {
+        ProcessDb::load_from_disk(&cache)?
+    };
+    let mut db = ProcessDb::detached(existing);
+    let (added, merged) = db.import(incoming, replace);
+    db.save(&cache)?;
+    let persisted = db
+        .profiles
+        .values()
+        .filter(|p| p.behavioral_confidence() >= MIN_CONFIDENCE)
+        .count();
+    println!(
+        "{}: {} NEW, {} MERGED; {}/{} PROFILES CONFIDENT ENOUGH TO PERSIST",
+        cache.display(),
+        added,
+        merged,
+        persisted,
+        db.profiles.len()
+    );
+    Ok(())
+}
+
+// RUNNING: THE PINNED INIT MAP, EXACTLY WHAT NEW TASKS START WITH (FORCED
+// comms FLAGGED), PLUS PREEMPTED/S OVER THE RUN FROM THE CONTROL SOCKET
+// (UNREACHABLE: THE COLUMN READS "-"). NOTHING RUNNING: THE CACHE, WHICH
+// NEVER HOLDS A PIN.
+pub fn run_list(pins: &PinDir, socket: &str) -> Result<()> {
+    let mut preempted = None;
+    let (entries, source) = if pandemonium_attached() {
+        preempted = request(socket, "procdb preempted").ok().map(|kv| {
+            kv.into_iter()
+                .filter_map(|(comm, rate)| Some((comm, rate.parse::<f64>().ok()?)))
+                .collect::<HashMap<String, f64>>()
+        });
+        (
+            ProcessDb::read_init(pins.dir())?,
+            "RUNNING SCHEDULER".to_string(),
+        )
+    } else {
+        let cache = ProcessDb::default_path();
+        let entries = ProcessDb::load_from_disk(&cache)?
+            .into_iter()
+            .map(|(comm, p)| {
+                let entry = TaskClassEntry {
+                    tier: p.predicted_tier(),
+                    policy: 0,
+                    pinned: 0,
+                    _pad: 0,
+                    uclamp_min: 0,
+                    avg_runtime: p.avg_runtime_ns,
+                    runtime_dev: p.runtime_dev_ns,
+                    wakeup_freq: p.wakeup_freq,
+                    csw_rate: p.csw_rate,
+                };
+                (comm, entry)
+            })
+            .collect();
+        (entries, cache.display().to_string())
+    };
+    let forced = entries.iter().filter(|(_, e)| e.pinned != 0).count();
+    println!(
+        "{} PREDICTIONS FROM {} ({} FORCED)",
+        entries.len(),
+        source,
+        forced
+    );
+    for row in list_rows(&entries, preempted.as_ref()) {
+        println!("{}", row);
+    }
+    Ok(())
+}
@@ -1,24 +1,35 @@
 use anyhow::Result;
 
-use super::LOG_DIR;
+use pandemonium::benchout::{BenchLayout, Json};
+use pandemonium::sysinfo::{RunConfig, SystemInfo};
 
-fn chrono_stamp() -> String {
-    let output = std::process::Command::new("date")
-        .arg("+%Y%m%d-%H%M%S")
-        .output()
-        .ok();
-    match output {
-        Some(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_string(),
-        _ => "unknown".to_string(),
+// PRINT THE REPORT, THEN report.txt + report.json INTO THE BENCH DIRECTORY.
+// BOTH OPEN WITH THE SYSTEM HEADER (VERSION, KERNEL, SCHED_EXT, CPU) AND
+// THE [CONFIG] BLOCK (THE SCHEDULER LOGS CARRY EACH PHASE'S OWN).
+pub fn write_report(out: &BenchLayout, report: &[String], json: &Json) -> Result<()> {
+    let system = SystemInfo::collect();
+    let config = RunConfig::collect(&system, None, None, None);
+    let mut lines = system.header_lines();
+    lines.extend(config.config_lines());
+    lines.extend(report.iter().cloned());
+    for line in &lines {
+        println!("{}", line);
     }
-}
-
-pub fn save_report(content: &str, prefix: &str) -> Result<String> {
-    std::fs::create_dir_all(LOG_DIR)?;
-    let stamp = chrono_stamp();
-    let path = format!("{}/{}-{}.log", LOG_DIR, prefix, stamp);
-    std::fs::write(&path, content)?;
-    Ok(path)
+    std::fs::write(out.report_txt(), lines.join("\n") + "\n")?;
+    let json = match json {
+        Json::Obj(fields) => {
+            let mut stamped = vec![
+                ("system".to_string(), system.json()),
+                ("config".to_string(), config.json()),
+            ];
+            stamped.extend(fields.iter().cloned());
+            Json::Obj(stamped)
+        }
+        other => other.clone(),
+    };
+    std::fs::write(out.report_json(), json.render())?;
+    println!("\nSAVED TO {}", out.dir().display());
+    Ok(())
 }
 
 pub fn mean_stdev(values: &[f64]) -> (f64, f64) {
@@ -34,15 +45,6 @@ pub fn mean_stdev(values: &[f64]) -> (f64, f64) {
     (m, variance.sqrt())
 }
 
-pub fn percentile(sorted_vals: &[f64], p: f64) -> f64 {
-    if sorted_vals.is_empty() {
-        return 0.0;
-    }
-    let idx = (sorted_vals.len() as f64 * p / 100.0) as usize;
-    let idx = idx.min(sorted_vals.len() - 1);
-    sorted_vals[idx]
-}
-
 pub fn format_delta(delta_pct: f64, label: &str) -> String {
     if delta_pct < 0.0 {
         format!(
@@ -78,3 +80,39 @@ pub fn format_latency_delta(delta_us: f64, label: &str) -> String {
         format!("{} LATENCY DELTA: 0us (SAME)", label)
     }
 }
+
+// THROUGHPUT: HIGHER IS BETTER (format_delta IS FOR ELAPSED TIME)
+pub fn format_rate_delta(delta_pct: f64, label: &str) -> String {
+    if delta_pct > 0.0 {
+        format!(
+            "{} DELTA: {:+.1}% (PANDEMONIUM DOES {:.1}% MORE)",
+            label, delta_pct, delta_pct
+        )
+    } else if delta_pct < 0.0 {
+        format!(
+            "{} DELTA: {:+.1}% (PANDEMONIUM DOES {:.1}% LESS)",
+            label,
+            delta_pct,
+            delta_pct.abs()
+        )
+    } else {
+        format!("{} DELTA: 0.0% (NO DIFFERENCE)", label)
+    }
+}
+
+// EMULATED AUDIO CALLBACK: FEWER XRUNS PER MINUTE IS BETTER
+pub fn format_xrun_delta(delta_per_min: f64) -> String {
+    if delta_per_min < 0.0 {
+        format!(
+            "XRUNS/MIN DELTA: {:+.1} (PANDEMONIUM HAS FEWER AUDIO DROPOUTS)",
+            delta_per_min
+        )
+    } else if delta_per_min > 0.0 {
+        format!(
+            "XRUNS/MIN DELTA: {:+.1} (PANDEMONIUM HAS MORE AUDIO DROPOUTS)",
+            delta_per_min
+        )
+    } else {
+        "XRUNS/MIN DELTA: 0.0 (SAME AUDIO QUALITY)".to_string()
+    }
+}
@@ -1,11 +1,19 @@
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::process::CommandExt;
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicI32, Ordering};
 use std::time::Duration;
 
 use anyhow::{bail, Result};
 
+use pandemonium::exitcode::ExitStatus;
+use pandemonium::kmsg::{
+    format_relative, hint_for, is_scx_related, parse_record, KmsgRecord, KMSG_PATH,
+    TROUBLESHOOTING_URL,
+};
+
 use super::{binary_path, LOG_DIR, TARGET_DIR};
 
 fn build_scheduler() -> Result<()> {
@@ -31,7 +39,7 @@ fn build_scheduler() -> Result<()> {
     Ok(())
 }
 
-fn capture_dmesg_cursor() -> Option<String> {
+pub fn capture_dmesg_cursor() -> Option<String> {
     let output = Command::new("journalctl")
         .args(["-k", "--no-pager", "-n", "1", "--show-cursor"])
         .output()
@@ -48,7 +56,7 @@ fn capture_dmesg_cursor() -> Option<String> {
     None
 }
 
-fn capture_dmesg_after(cursor: Option<&str>) -> String {
+pub fn capture_dmesg_after(cursor: Option<&str>) -> String {
     let mut cmd = Command::new("journalctl");
     cmd.args(["-k", "--no-pager"]);
     if let Some(c) = cursor {
@@ -72,52 +80,101 @@ fn capture_dmesg_after(cursor: Option<&str>) -> String {
     relevant.join("\n")
 }
 
+// PER-INVOCATION LOG DIRECTORIES KEPT UNDER LOG_DIR (OLDEST PRUNED FIRST)
+const MAX_RUN_DIRS: usize = 20;
+
+// PGID OF THE RUNNING SCHEDULER, FOR CTRL+C FORWARDING
+static SCHED_PGID: AtomicI32 = AtomicI32::new(0);
+
+struct RunLogs {
+    dir: String,
+    stdout: String,
+    stderr: String,
+    dmesg: String,
+    report: String,
+}
+
 fn save_logs(
-    scheduler_output: &str,
+    stamp: &str,
+    stdout: &str,
+    stderr: &str,
     dmesg: &str,
     returncode: i32,
-) -> Result<(String, String, String)> {
-    std::fs::create_dir_all(LOG_DIR)?;
-
-    let stamp = chrono_stamp();
+) -> Result<RunLogs> {
+    let dir = format!("{}/run-{}", LOG_DIR, stamp);
+    std::fs::create_dir_all(&dir)?;
+
+    let logs = RunLogs {
+        stdout: format!("{}/stdout.log", dir),
+        stderr: format!("{}/stderr.log", dir),
+        dmesg: format!("{}/dmesg.log", dir),
+        report: format!("{}/report.log", dir),
+        dir,
+    };
 
-    let sched_path = format!("{}/run-{}.log", LOG_DIR, stamp);
-    std::fs::write(&sched_path, scheduler_output)?;
+    let dmesg_text = if dmesg.is_empty() {
+        "(NO RELEVANT KERNEL MESSAGES)"
+    } else {
+        dmesg
+    };
 
-    let dmesg_path = format!("{}/dmesg-{}.log", LOG_DIR, stamp);
+    std::fs::write(&logs.stdout, stdout)?;
+    std::fs::write(&logs.stderr, stderr)?;
     std::fs::write(
-        &dmesg_path,
-        if dmesg.is_empty() {
-            "(NO RELEVANT KERNEL MESSAGES)\n"
-        } else {
-            dmesg
-        },
+        &logs.dmesg,
+        format!(
+            "-- BEGIN run-{stamp} --\n{dmesg_text}\n-- END run-{stamp} (EXIT CODE {returncode}) --\n"
+        ),
     )?;
 
-    let report_path = format!("{}/report-{}.log", LOG_DIR, stamp);
     let report = format!(
         "PANDEMONIUM RUN -- {stamp}\n\
          EXIT CODE: {returncode}\n\n\
          SCHEDULER OUTPUT\n\
-         {scheduler_output}\n\n\
+         {stdout}\n\n\
+         SCHEDULER STDERR\n\
+         {stderr}\n\n\
          KERNEL LOG (DMESG)\n\
          {dmesg_text}\n",
-        dmesg_text = if dmesg.is_empty() {
-            "(NO RELEVANT KERNEL MESSAGES)"
-        } else {
-            dmesg
-        },
     );
-    std::fs::write(&report_path, &report)?;
+    std::fs::write(&logs.report, &report)?;
 
-    let latest = format!("{}/latest.log", LOG_DIR);
+    // latest -> RUN DIR, latest.log -> ITS REPORT
+    let latest = format!("{}/latest", LOG_DIR);
     let _ = std::fs::remove_file(&latest);
-    let _ = std::os::unix::fs::symlink(&report_path, &latest);
+    let _ = std::os::unix::fs::symlink(&logs.dir, &latest);
+    let latest_log = format!("{}/latest.log", LOG_DIR);
+    let _ = std::fs::remove_file(&latest_log);
+    let _ = std::os::unix::fs::symlink(&logs.report, &latest_log);
 
-    Ok((sched_path, dmesg_path, report_path))
+    prune_run_dirs();
+    Ok(logs)
+}
+
+// ROTATION: run-YYYYMMDD-HHMMSS SORTS CHRONOLOGICALLY BY NAME
+fn prune_run_dirs() {
+    let mut dirs: Vec<_> = match std::fs::read_dir(LOG_DIR) {
+        Ok(rd) => rd
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("run-"))
+                    .unwrap_or(false)
+            })
+            .collect(),
+        Err(_) => return,
+    };
+    dirs.sort();
+    let excess = dirs.len().saturating_sub(MAX_RUN_DIRS);
+    for d in &dirs[..excess] {
+        let _ = std::fs::remove_dir_all(d);
+    }
 }
 
-fn chrono_stamp() -> String {
+pub fn chrono_stamp() -> String {
     let output = Command::new("date").arg("+%Y%m%d-%H%M%S").output().ok();
     match output {
         Some(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_string(),
@@ -125,7 +182,7 @@ fn chrono_stamp() -> String {
     }
 }
 
-pub fn run_start(observe: bool, sched_args: &[String]) -> Result<()> {
+pub fn run_start(observe: bool, duration: Option<u64>, sched_args: &[String]) -> Result<()> {
     // BUILD FIRST
     build_scheduler()?;
 
@@ -139,20 +196,47 @@ pub fn run_start(observe: bool, sched_args: &[String]) -> Result<()> {
         cmd_args.push("--verbose".to_string());
         cmd_args.push("--dump-log".to_string());
     }
+    // THE SCHEDULER ENDS ITS OWN RUN: SUMMARY PRINTED, EXIT 0
+    if let Some(secs) = duration {
+        log_info!("Duration: {}s", secs);
+        cmd_args.push("--duration".to_string());
+        cmd_args.push(secs.to_string());
+    }
     cmd_args.extend(sched_args.iter().cloned());
 
-    let full_cmd = format!("sudo {} {}", bin, cmd_args.join(" "));
-    log_info!("Running: {}", full_cmd);
+    let argv: Vec<String> = std::iter::once(bin).chain(cmd_args).collect();
+    let mut cmd = super::privileged_command(&[], &argv)?;
 
+    let stamp = chrono_stamp();
     let cursor = capture_dmesg_cursor();
 
-    let mut child = Command::new("sudo")
-        .arg(&bin)
-        .args(&cmd_args)
+    let mut child = cmd
         .process_group(0)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()?;
+    SCHED_PGID.store(child.id() as i32, Ordering::Relaxed);
+
+    // THE SCHEDULER IS IN ITS OWN PROCESS GROUP: FORWARD CTRL+C
+    ctrlc::set_handler(|| {
+        let pgid = SCHED_PGID.load(Ordering::Relaxed);
+        if pgid > 0 {
+            unsafe {
+                libc::killpg(pgid, libc::SIGINT);
+            }
+        }
+    })?;
+
+    // DRAIN STDERR ON ITS OWN THREAD (A FULL PIPE WOULD STALL THE SCHEDULER)
+    let stderr = child.stderr.take().unwrap();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut lines = Vec::new();
+        for l in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+            eprintln!("{}", l);
+            lines.push(l);
+        }
+        lines.join("\n")
+    });
 
     let stdout = child.stdout.take().unwrap();
     let reader = BufReader::new(stdout);
@@ -169,8 +253,10 @@ pub fn run_start(observe: bool, sched_args: &[String]) -> Result<()> {
     }
 
     let status = child.wait()?;
+    SCHED_PGID.store(0, Ordering::Relaxed);
 
     let scheduler_output = output_lines.join("\n");
+    let scheduler_stderr = stderr_reader.join().unwrap_or_default();
     let returncode = status.code().unwrap_or(-1);
 
     log_info!("PANDEMONIUM exited with code {}", returncode);
@@ -180,7 +266,13 @@ pub fn run_start(observe: bool, sched_args: &[String]) -> Result<()> {
     let dmesg = capture_dmesg_after(cursor.as_deref());
 
     // SAVE LOGS
-    let (sched_path, dmesg_path, report_path) = save_logs(&scheduler_output, &dmesg, returncode)?;
+    let logs = save_logs(
+        &stamp,
+        &scheduler_output,
+        &scheduler_stderr,
+        &dmesg,
+        returncode,
+    )?;
 
     // PRINT DMESG
     if dmesg.is_empty() {
@@ -192,25 +284,127 @@ pub fn run_start(observe: bool, sched_args: &[String]) -> Result<()> {
         }
     }
 
-    match returncode {
-        0 => log_info!("Status: clean exit"),
-        130 => log_info!("Status: user interrupted (CTRL+C)"),
+    match (returncode, ExitStatus::from_code(returncode)) {
+        (0, _) => log_info!("Status: clean exit"),
+        (130, _) => log_info!("Status: user interrupted (CTRL+C)"),
+        (_, Some(status)) => log_warn!("Status: exit code {} ({})", returncode, status.label()),
         _ => log_warn!("Status: exit code {}", returncode),
     }
 
-    log_info!("Logs saved to {}/", LOG_DIR);
-    log_info!("  scheduler: {}", sched_path);
-    log_info!("  dmesg:     {}", dmesg_path);
-    log_info!("  combined:  {}", report_path);
-    log_info!("  latest:    {}/latest.log", LOG_DIR);
+    log_info!("Logs saved to {}/", logs.dir);
+    log_info!("  stdout:    {}", logs.stdout);
+    log_info!("  stderr:    {}", logs.stderr);
+    log_info!("  dmesg:     {}", logs.dmesg);
+    log_info!("  combined:  {}", logs.report);
+    log_info!("  latest:    {}/latest", LOG_DIR);
+
+    // PROPAGATE THE SCHEDULER'S EXIT STATUS SO SCRIPTS CAN DETECT FAILED RUNS
+    if returncode != 0 {
+        std::process::exit(returncode);
+    }
 
     Ok(())
 }
 
-pub fn run_dmesg() -> Result<()> {
-    let output = Command::new("journalctl")
-        .args(["-k", "--no-pager", "-n", "50"])
-        .output()?;
+// SNAPSHOT WITHOUT --since-boot: LAST N KERNEL RECORDS (PRE-FILTER)
+const DMESG_TAIL: usize = 50;
+
+pub fn run_dmesg(follow: bool, since_boot: bool, errors_only: bool) -> Result<()> {
+    let mut opts = std::fs::OpenOptions::new();
+    opts.read(true);
+    if !follow {
+        opts.custom_flags(libc::O_NONBLOCK);
+    }
+    let mut kmsg = match opts.open(KMSG_PATH) {
+        Ok(f) => f,
+        Err(e) if follow => bail!("CANNOT OPEN {}: {} (RUN AS ROOT)", KMSG_PATH, e),
+        Err(e) => {
+            log_warn!(
+                "{} unavailable ({}), falling back to journalctl",
+                KMSG_PATH,
+                e
+            );
+            return run_dmesg_journal(errors_only);
+        }
+    };
+
+    // FOLLOW WITHOUT --since-boot: ONLY RECORDS ARRIVING FROM NOW ON
+    if follow && !since_boot {
+        kmsg.seek(SeekFrom::End(0))?;
+    }
+
+    let mut printer = KmsgPrinter {
+        errors_only,
+        base_us: None,
+        found: false,
+    };
+    let mut backlog: Vec<KmsgRecord> = Vec::new();
+    let mut buf = vec![0u8; 8192];
+    loop {
+        let n = match kmsg.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            // EPIPE: RING BUFFER OVERWROTE RECORDS WE HADN'T READ YET
+            Err(e) if e.raw_os_error() == Some(libc::EPIPE) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let Some(rec) = parse_record(&String::from_utf8_lossy(&buf[..n])) else {
+            continue;
+        };
+        if follow {
+            printer.print(&rec);
+        } else {
+            backlog.push(rec);
+        }
+    }
+
+    if !follow {
+        let skip = if since_boot {
+            0
+        } else {
+            backlog.len().saturating_sub(DMESG_TAIL)
+        };
+        for rec in &backlog[skip..] {
+            printer.print(rec);
+        }
+    }
+
+    if !printer.found {
+        log_info!("No recent sched_ext/PANDEMONIUM kernel messages");
+    }
+
+    Ok(())
+}
+
+struct KmsgPrinter {
+    errors_only: bool,
+    base_us: Option<u64>,
+    found: bool,
+}
+
+impl KmsgPrinter {
+    fn print(&mut self, rec: &KmsgRecord) {
+        if !is_scx_related(&rec.msg) || (self.errors_only && !rec.is_error()) {
+            return;
+        }
+        let base = *self.base_us.get_or_insert(rec.ts_us);
+        println!("{} {}", format_relative(rec.ts_us, base), rec.msg);
+        if let Some(hint) = hint_for(&rec.msg) {
+            log_warn!("HINT: {} ({})", hint, TROUBLESHOOTING_URL);
+        }
+        self.found = true;
+    }
+}
+
+// FALLBACK WHEN /dev/kmsg IS RESTRICTED (dmesg_restrict=1, NON-ROOT)
+fn run_dmesg_journal(errors_only: bool) -> Result<()> {
+    let mut args = vec!["-k", "--no-pager", "-n", "50"];
+    if errors_only {
+        args.extend(["-p", "err"]);
+    }
+    let output = Command::new("journalctl").args(&args).output()?;
 
     if !output.status.success() {
         bail!("journalctl failed");
@@ -222,9 +416,11 @@ pub fn run_dmesg() -> Result<()> {
         if line.is_empty() || line.starts_with("-- ") {
             continue;
         }
-        let low = line.to_lowercase();
-        if low.contains("sched_ext") || low.contains("scx") || low.contains("pandemonium") {
+        if is_scx_related(line) {
             println!("{}", line);
+            if let Some(hint) = hint_for(line) {
+                log_warn!("HINT: {} ({})", hint, TROUBLESHOOTING_URL);
+            }
             found = true;
         }
     }
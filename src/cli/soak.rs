@@ -0,0 +1,322 @@
+// PANDEMONIUM SOAK RUN (`pandemonium test --soak HOURS`)
+// EXECUTES THE ROTATION AND CRITERIA TABLES IN pandemonium::soak. ON THE
+// FIRST FAILED MINUTE: BUNDLE dmesg, THE BLACK BOX AND THE TELEMETRY TAIL
+// INTO /tmp/pandemonium/soak-<stamp>/, STOP EVERYTHING, EXIT NONZERO.
+// A RUN THAT ENDS LEAVING PINS OR pandemonium PROCESSES BEHIND
+// (pandemonium::leak AGAINST A SNAPSHOT FROM BEFORE THE START) FAILS TOO.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+use pandemonium::blackbox::BLACKBOX_PATH;
+use pandemonium::container::DEFAULT_PIN_DIR;
+use pandemonium::leak::{leaks, Leak, Snapshot};
+use pandemonium::scxstate::ScxRuntime;
+use pandemonium::soak::{evaluate, parse_p99_us, phase_at, spin_cpus, Phase, SoakSample};
+
+use super::child_guard::ChildGuard;
+use super::run::{capture_dmesg_after, capture_dmesg_cursor, chrono_stamp};
+use super::{require_scx_free, self_exe, wait_for_activation, LOG_DIR};
+
+// SCHEDULER OUTPUT KEPT FOR THE FAILURE BUNDLE (~10 MINUTES AT ONE LINE/S)
+const TELEMETRY_TAIL: usize = 600;
+
+// SCRATCH TARGET DIR FOR THE COMPILE INGREDIENT, WIPED BEFORE EVERY BUILD
+const SOAK_BUILD_DIR: &str = "/tmp/pandemonium-soak-build";
+
+// A BUILD THAT FAILS FASTER THAN THIS (NO TOOLCHAIN, NO NETWORK) BACKS OFF
+// INSTEAD OF TURNING THE COMPILE INGREDIENT INTO A PROCESS STORM
+const COMPILE_MIN_RUN: Duration = Duration::from_secs(10);
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// SLEEP UP TO dur, WAKING EARLY WHEN stop IS SET
+fn sleep_unless(stop: &AtomicBool, dur: Duration) {
+    let deadline = Instant::now() + dur;
+    while !stop.load(Ordering::Relaxed) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn compile_loop(stop: Arc<AtomicBool>) {
+    let project_root = env!("CARGO_MANIFEST_DIR");
+    while !stop.load(Ordering::Relaxed) {
+        let _ = std::fs::remove_dir_all(SOAK_BUILD_DIR);
+        let started = Instant::now();
+        let child = Command::new("cargo")
+            .args(["build", "--release"])
+            .env("CARGO_TARGET_DIR", SOAK_BUILD_DIR)
+            .current_dir(project_root)
+            .process_group(0)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut guard = match child {
+            Ok(c) => ChildGuard::new(c),
+            Err(e) => {
+                log_warn!("SOAK: CANNOT SPAWN cargo ({}): COMPILE INGREDIENT IDLES", e);
+                return;
+            }
+        };
+        while !guard.exited() && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        drop(guard);
+        if started.elapsed() < COMPILE_MIN_RUN {
+            sleep_unless(&stop, COMPILE_MIN_RUN);
+        }
+    }
+}
+
+fn storm_loop(stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        let _ = Command::new("true")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+// ONE PHASE'S INGREDIENTS; stop() TEARS ALL OF THEM DOWN
+struct Workload {
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+    spinners: Vec<ChildGuard>,
+}
+
+impl Workload {
+    fn start(phase: &Phase, nr_cpus: u32) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut threads = Vec::new();
+        if phase.compile {
+            let s = stop.clone();
+            threads.push(std::thread::spawn(move || compile_loop(s)));
+        }
+        for _ in 0..phase.storm_threads {
+            let s = stop.clone();
+            threads.push(std::thread::spawn(move || storm_loop(s)));
+        }
+        let spinners = spin_cpus(phase.spin_pct, nr_cpus)
+            .into_iter()
+            .filter_map(|cpu| {
+                Command::new(self_exe())
+                    .args(["stress-worker", "--cpu", &cpu.to_string()])
+                    .process_group(0)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map(ChildGuard::new)
+                    .ok()
+            })
+            .collect();
+        Self {
+            stop,
+            threads,
+            spinners,
+        }
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for t in self.threads {
+            let _ = t.join();
+        }
+        drop(self.spinners);
+    }
+}
+
+// FORWARD EVERY LINE OF ONE SCHEDULER PIPE (STDOUT OR STDERR) TO THE LOOP
+fn forward_lines(pipe: impl Read + Send + 'static, tx: Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(|l| l.ok()) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn drain(rx: &Receiver<String>, tail: &mut VecDeque<String>, sample: &mut SoakSample) {
+    for line in rx.try_iter() {
+        if let Some(p99) = parse_p99_us(&line) {
+            sample.p99_us.push(p99);
+        }
+        if tail.len() == TELEMETRY_TAIL {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+}
+
+fn write_bundle(
+    stamp: &str,
+    cursor: Option<&str>,
+    sample: &SoakSample,
+    phase: &Phase,
+    failures: &[String],
+    tail: &VecDeque<String>,
+) -> Result<String> {
+    let dir = format!("{}/soak-{}", LOG_DIR, stamp);
+    std::fs::create_dir_all(&dir)?;
+
+    let mut report = format!(
+        "PANDEMONIUM SOAK FAILURE -- {}\nMINUTE: {}\nPHASE: {}\n",
+        stamp, sample.minute, phase.name
+    );
+    for f in failures {
+        report.push_str(&format!("FAILED {}\n", f));
+    }
+    report.push_str(&format!("P99 SAMPLES (us): {:?}\n", sample.p99_us));
+    std::fs::write(format!("{}/failure.txt", dir), report)?;
+
+    // THE sched_ext EXIT REASON (WATCHDOG STALLS INCLUDED) LANDS IN dmesg
+    let dmesg = capture_dmesg_after(cursor);
+    std::fs::write(
+        format!("{}/dmesg.log", dir),
+        if dmesg.is_empty() {
+            "(NO RELEVANT KERNEL MESSAGES)\n".to_string()
+        } else {
+            dmesg + "\n"
+        },
+    )?;
+
+    let mut telemetry: String = tail.iter().map(|l| format!("{}\n", l)).collect();
+    if telemetry.is_empty() {
+        telemetry = "(NO SCHEDULER OUTPUT)\n".to_string();
+    }
+    std::fs::write(format!("{}/telemetry.log", dir), telemetry)?;
+
+    // COPIED BEFORE THE SCHEDULER IS STOPPED; DECODE WITH
+    // `pandemonium blackbox --file <dir>/blackbox.bin`
+    if let Err(e) = std::fs::copy(BLACKBOX_PATH, format!("{}/blackbox.bin", dir)) {
+        log_warn!("SOAK: NO BLACK BOX COPY ({}: {})", BLACKBOX_PATH, e);
+    }
+    Ok(dir)
+}
+
+pub fn run_soak(hours: f64) -> Result<()> {
+    if !(hours > 0.0 && hours.is_finite()) {
+        bail!("--soak HOURS MUST BE A POSITIVE NUMBER");
+    }
+    if unsafe { libc::geteuid() } != 0 {
+        bail!("SOAK NEEDS ROOT: sudo pandemonium test --soak {}", hours);
+    }
+    require_scx_free()?;
+
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::Relaxed))?;
+
+    let total_minutes = (hours * 60.0).ceil() as u64;
+    let nr_cpus = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1);
+    let stamp = chrono_stamp();
+    let cursor = capture_dmesg_cursor();
+
+    let leak_snapshot = || {
+        Snapshot::take(
+            std::path::Path::new(DEFAULT_PIN_DIR),
+            std::path::Path::new("/proc"),
+            std::process::id(),
+        )
+    };
+    let baseline = leak_snapshot();
+
+    log_info!("PANDEMONIUM soak: {} minutes", total_minutes);
+    let mut child = Command::new(self_exe())
+        .arg("--verbose")
+        .process_group(0)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (tx, rx) = mpsc::channel();
+    forward_lines(child.stdout.take().unwrap(), tx.clone());
+    forward_lines(child.stderr.take().unwrap(), tx);
+    let mut sched = ChildGuard::new(child);
+    if !wait_for_activation(10) {
+        bail!("PANDEMONIUM DID NOT ACTIVATE WITHIN 10S");
+    }
+
+    let mut tail = VecDeque::with_capacity(TELEMETRY_TAIL);
+    let mut workload: Option<(&Phase, Workload)> = None;
+    let mut minute = 0;
+    while minute < total_minutes && !INTERRUPTED.load(Ordering::Relaxed) {
+        let phase = phase_at(minute);
+        if workload.as_ref().map(|(p, _)| p.name) != Some(phase.name) {
+            if let Some((_, w)) = workload.take() {
+                w.stop();
+            }
+            log_info!("SOAK {}m: PHASE {}", minute, phase.name);
+            workload = Some((phase, Workload::start(phase, nr_cpus)));
+        }
+
+        let mut sample = SoakSample {
+            minute,
+            ..Default::default()
+        };
+        sleep_unless(&INTERRUPTED, Duration::from_secs(60));
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            break;
+        }
+        drain(&rx, &mut tail, &mut sample);
+        sample.scheduler_alive = !sched.exited();
+        sample.ops = ScxRuntime::read().and_then(|rt| rt.ops);
+
+        let failures = evaluate(&sample);
+        if !failures.is_empty() {
+            for f in &failures {
+                log_error!("SOAK FAILED AT MINUTE {} ({}): {}", minute, phase.name, f);
+            }
+            let dir = write_bundle(&stamp, cursor.as_deref(), &sample, phase, &failures, &tail)?;
+            if let Some((_, w)) = workload.take() {
+                w.stop();
+            }
+            sched.stop();
+            log_error!("SOAK BUNDLE: {}/", dir);
+            std::process::exit(1);
+        }
+        log_info!(
+            "SOAK {}/{}m [{}] OK (median p99 {}us)",
+            minute + 1,
+            total_minutes,
+            phase.name,
+            sample.median_p99_us().unwrap_or(0)
+        );
+        minute += 1;
+    }
+
+    if let Some((_, w)) = workload.take() {
+        w.stop();
+    }
+    sched.stop();
+    // THE PIPE FORWARDERS END WITH THE SCHEDULER'S PIPES, ON THEIR OWN
+    // TIME: THIS PROCESS'S THREAD COUNT IS NOT A LEAK SIGNAL HERE
+    let found: Vec<Leak> = leaks(&baseline, &leak_snapshot())
+        .into_iter()
+        .filter(|l| !matches!(l, Leak::Threads { .. }))
+        .collect();
+    for l in &found {
+        log_error!("SOAK LEAK: {}", l.message());
+    }
+    if !found.is_empty() {
+        bail!("SOAK LEFT {} LEAK(S) BEHIND", found.len());
+    }
+    if minute < total_minutes {
+        log_warn!(
+            "SOAK INTERRUPTED AFTER {} OF {} MINUTES",
+            minute,
+            total_minutes
+        );
+    } else {
+        log_info!("SOAK PASSED: {} MINUTES", total_minutes);
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+
+use pandemonium::client::Client;
+use pandemonium::container::PinDir;
+use pandemonium::scxstate::PANDEMONIUM_OPS;
+use pandemonium::statusbar::{self, BarState, BarStatus, Template};
+
+// ONE LINE FOR A STATUS BAR (WAYBAR custom MODULE, i3blocks, polybar).
+// EXITS 0 WHENEVER IT PRINTED A LINE: A STOPPED SCHEDULER IS "inactive",
+// NOT AN ERROR THE BAR WOULD RENDER AS A BROKEN MODULE. ONLY A BAD --format
+// FAILS.
+pub fn run_status(pins: &PinDir, socket: &str, waybar: bool, format: Option<&str>) -> Result<()> {
+    let template = match format {
+        Some(f) => statusbar::parse_format(f),
+        None if waybar => Template::parse(statusbar::WAYBAR_TEMPLATE),
+        None => Template::parse(statusbar::DEFAULT_TEMPLATE),
+    }
+    .map_err(anyhow::Error::msg)?;
+
+    let state = read_state(&Client::new(pins.clone(), socket));
+    if waybar {
+        print!("{}", statusbar::waybar_json(&state, &template));
+    } else {
+        println!("{}", template.render(&state));
+    }
+    Ok(())
+}
+
+fn read_state(client: &Client) -> BarState {
+    // PINS OUTLIVE A SIGKILLED SCHEDULER: ASK sched_ext WHO IS ATTACHED FIRST
+    match pandemonium::attach::active_scheduler() {
+        None => return BarState::Inactive("NO sched_ext SCHEDULER ATTACHED".to_string()),
+        Some(ops) if ops != PANDEMONIUM_OPS => {
+            return BarState::Inactive(format!("{} IS ATTACHED", ops))
+        }
+        Some(_) => {}
+    }
+    let stats = match client.stats() {
+        Ok(s) => s,
+        Err(e) => return BarState::Inactive(format!("{:#}", e)),
+    };
+    // NO SOCKET (--no-adaptive): NO REGIME. NO wake_lat_hist PIN (OLDER
+    // BUILD): NO P99. NEITHER MAKES THE SCHEDULER INACTIVE.
+    let regime = client.regime().ok();
+    let hist = client.histogram().unwrap_or_default();
+    // NO reserved_cpus PIN (OLDER BUILD): NOTHING RESERVED
+    let reserved = client.reserved_cpus().unwrap_or_default();
+    BarState::Running(BarStatus {
+        regime: regime.as_ref().map(|r| r.regime),
+        pinned: regime.as_ref().is_some_and(|r| r.pinned),
+        paused: regime.as_ref().is_some_and(|r| r.paused),
+        tightened: regime.as_ref().is_some_and(|r| r.tightened),
+        p99_ns: hist.p99_ns(),
+        wake_avg_ns: stats.wake_lat_sum / stats.wake_lat_samples.max(1),
+        samples: hist.samples(),
+        dispatches: stats.nr_dispatches,
+        reserved,
+        restarts: regime.as_ref().map_or(0, |r| r.restarts),
+        last_restart: regime.and_then(|r| r.last_restart),
+    })
+}
@@ -4,6 +4,9 @@ use anyhow::{bail, Result};
 
 use super::TARGET_DIR;
 
+// PASSED THROUGH TO THE ROOT cargo WHEN SET (WHAT sudo -E USED TO KEEP)
+const TOOLCHAIN_ENV: [&str; 3] = ["CARGO_HOME", "RUSTUP_HOME", "RUSTUP_TOOLCHAIN"];
+
 pub fn run_test_gate() -> Result<()> {
     let project_root = env!("CARGO_MANIFEST_DIR");
 
@@ -23,21 +26,28 @@ pub fn run_test_gate() -> Result<()> {
 
     // LAYERS 2-5: INTEGRATION TESTS (REQUIRES ROOT)
     log_info!("Layers 2-5: integration (requires root)");
-    let l2 = Command::new("sudo")
-        .args([
-            "-E",
-            &format!("CARGO_TARGET_DIR={}", TARGET_DIR),
-            "cargo",
-            "test",
-            "--test",
-            "gate",
-            "--release",
-            "--",
-            "--ignored",
-            "--test-threads=1",
-            "full_gate",
-        ])
-        .env("CARGO_TARGET_DIR", TARGET_DIR)
+    // THE ESCALATION TOOL MAY RESET THE ENVIRONMENT: HAND cargo ITS TARGET
+    // DIR AND THE CALLER'S TOOLCHAIN LOCATION EXPLICITLY
+    let toolchain: Vec<(&str, String)> = TOOLCHAIN_ENV
+        .iter()
+        .filter_map(|k| std::env::var(k).ok().map(|v| (*k, v)))
+        .collect();
+    let mut env = vec![("CARGO_TARGET_DIR", TARGET_DIR)];
+    env.extend(toolchain.iter().map(|(k, v)| (*k, v.as_str())));
+    let argv: Vec<String> = [
+        "cargo",
+        "test",
+        "--test",
+        "gate",
+        "--release",
+        "--",
+        "--ignored",
+        "--test-threads=1",
+        "full_gate",
+    ]
+    .map(String::from)
+    .to_vec();
+    let l2 = super::privileged_command(&env, &argv)?
         .current_dir(project_root)
         .status()?;
 
@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+use pandemonium::container::PinDir;
+use pandemonium::l2topo::L2Topology;
+
+// RECOMPUTE THE L2 GROUPS EXACTLY AS STARTUP DOES AND PRINT WHAT BPF SEES.
+// --live: ADD PER-GROUP L2 HIT RATES FROM THE RUNNING SCHEDULER'S PINNED
+// PER-CPU stats_map (CUMULATIVE SINCE ATTACH).
+pub fn run_topology(live: bool, pins: &PinDir) -> Result<()> {
+    let nr_cpus = libbpf_rs::num_possible_cpus()?;
+    let topo = L2Topology::detect(nr_cpus);
+
+    for line in topo.summary_lines() {
+        log_info!("{}", line);
+    }
+    println!();
+    for line in topo.neighbor_table() {
+        println!("{}", line);
+    }
+
+    if !live {
+        return Ok(());
+    }
+
+    let per_cpu = super::read_pinned_stats(pins)?;
+
+    println!();
+    log_info!("L2 hit rate per group (live, since attach):");
+    for line in topo.group_l2_table(&per_cpu) {
+        println!("{}", line);
+    }
+    Ok(())
+}
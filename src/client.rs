@@ -0,0 +1,469 @@
+// PANDEMONIUM CLIENT API
+// READ A RUNNING SCHEDULER FROM ANOTHER PROCESS: TRAY APPS, BAR MODULES
+//
+// THE LIBRARY ENTRY POINT FOR EMBEDDERS. WHERE EACH ANSWER COMES FROM:
+//   stats(), stats_percpu()   PINNED stats_map (SUMMED / PER CPU)
+//   knobs()                   PINNED tuning_knobs
+//   histogram()               PINNED wake_lat_hist
+//   reserved_cpus()           PINNED reserved_cpus (--reserved-cpus)
+//   regime()                  CONTROL SOCKET `get regime`
+//   idle_cpus()               /proc/stat OVER A WINDOW (sched_ext'S IDLE
+//                             CPUMASK IS KERNEL-INTERNAL)
+// NO ROOT NEEDED WHEN THE SCHEDULER RUNS WITH --pin-group GROUP AND THE
+// CALLER IS IN GROUP: THE PIN DIRECTORY, ITS PINS AND THE CONTROL SOCKET
+// BECOME root:GROUP. ON 6.12+ A PINNED-MAP LOOKUP ONLY NEEDS FILE
+// PERMISSIONS, BUT THE bpffs MOUNT ITSELF MUST STILL BE o+x.
+//
+// MAPS ARE READ THROUGH MapSource: PinnedMaps OPENS THE bpffs PINS, DirMaps
+// READS PLAIN FILES LAID OUT THE SAME WAY (TESTS, OFFLINE DUMPS). A TRAY
+// APP STARTED WITH THE SCHEDULER WAITS FOR THE PINS THROUGH
+// PinnedMaps::with_retry (pinmap.rs).
+//
+// SEMVER (5.x):
+//   Client, MapSource, PinnedMaps, DirMaps, request(): METHODS AND
+//   FUNCTIONS ARE ONLY ADDED IN A MINOR RELEASE, NEVER CHANGED OR REMOVED.
+//   RegimeStatus, WakeLatHist: #[non_exhaustive], FIELDS ARE ONLY ADDED.
+//   PandemoniumStats, TuningKnobs MIRROR intf.h: A MINOR RELEASE MAY ADD A
+//   FIELD (CONSTRUCT THEM WITH ..Default::default()). A CLIENT BUILT
+//   AGAINST ANOTHER LAYOUT THAN THE RUNNING SCHEDULER GETS AN ERROR FROM
+//   check_value_size, NEVER MISDECODED NUMBERS.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use libbpf_rs::MapCore;
+
+use crate::container::{PinDir, KNOBS_PIN, RESERVED_CPUS_PIN, STATS_PIN, WAKE_HIST_PIN};
+use crate::ctl;
+use crate::hotplug::{bitmap_cpus, CpuBitmap};
+use crate::hybrid::parse_proc_stat;
+use crate::pinmap::{open_pinned_with_retry, PIN_OPEN_DELAY};
+use crate::stats::{check_value_size, PandemoniumStats};
+use crate::tuning::{compute_p99_from_histogram, pooled_p99_ns, Regime, TuningKnobs, HIST_BUCKETS};
+
+// SERVER REPLIES WITHIN ~1 MONITOR TICK; ITS OWN TIMEOUT IS 3S
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(5);
+
+const PROC_STAT_PATH: &str = "/proc/stat";
+
+/// A CPU counts as idle when at least this share of its time over the
+/// sampling window was idle or iowait.
+pub const IDLE_CPU_PCT: u64 = 90;
+
+/// Wakeup-latency tiers, in `wake_lat_hist` key order.
+pub const WAKE_TIERS: [&str; 3] = ["batch", "interactive", "lat_critical"];
+
+/// Where map values come from.
+///
+/// Every map PANDEMONIUM pins is an array keyed `0..nr_keys`. Implementations
+/// return exactly `nr_keys` values of exactly `value_size` bytes, or an error.
+pub trait MapSource {
+    /// Values of keys `0..nr_keys` of an array map.
+    fn read_array(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<u8>>>;
+
+    /// Values of keys `0..nr_keys` of a per-CPU array map, indexed `[key][cpu]`.
+    fn read_percpu(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<Vec<u8>>>>;
+}
+
+/// The running scheduler's maps, pinned on bpffs under `--pin-dir`.
+///
+/// Each read opens its map once. A caller started alongside the scheduler
+/// can use [`PinnedMaps::with_retry`] to wait for the pins to appear.
+#[derive(Clone, Debug, Default)]
+pub struct PinnedMaps {
+    pins: PinDir,
+    attempts: u32,
+    delay: Duration,
+}
+
+impl PinnedMaps {
+    pub fn new(pins: PinDir) -> Self {
+        Self {
+            pins,
+            attempts: 1,
+            delay: PIN_OPEN_DELAY,
+        }
+    }
+
+    /// Try each open up to `attempts` times, waiting `delay`, then twice
+    /// as long, and so on between tries.
+    pub fn with_retry(mut self, attempts: u32, delay: Duration) -> Self {
+        self.attempts = attempts;
+        self.delay = delay;
+        self
+    }
+
+    fn open(&self, map: &str, value_size: usize) -> Result<libbpf_rs::MapHandle> {
+        let path = self.pins.map(map);
+        let handle = match open_pinned_with_retry(&path, self.attempts, self.delay) {
+            Ok(h) => h,
+            Err(e) => bail!(
+                "{:#} (IS PANDEMONIUM RUNNING? ROOT OR --pin-group MEMBERSHIP REQUIRED; SAME --pin-dir?)",
+                e
+            ),
+        };
+        check_value_size(&path.display().to_string(), handle.value_size(), value_size)?;
+        Ok(handle)
+    }
+}
+
+impl MapSource for PinnedMaps {
+    fn read_array(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<u8>>> {
+        let handle = self.open(map, value_size)?;
+        (0..nr_keys)
+            .map(|key| {
+                handle
+                    .lookup(&key.to_ne_bytes(), libbpf_rs::MapFlags::ANY)?
+                    .with_context(|| format!("{}: NO ENTRY AT KEY {}", map, key))
+            })
+            .collect()
+    }
+
+    fn read_percpu(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<Vec<u8>>>> {
+        let handle = self.open(map, value_size)?;
+        (0..nr_keys)
+            .map(|key| {
+                handle
+                    .lookup_percpu(&key.to_ne_bytes(), libbpf_rs::MapFlags::ANY)?
+                    .with_context(|| format!("{}: NO ENTRY AT KEY {}", map, key))
+            })
+            .collect()
+    }
+}
+
+/// Plain files laid out like a pin directory.
+///
+/// An array map is one file named after the map holding the values of keys
+/// `0..nr_keys` back to back. A per-CPU map is one such file per CPU,
+/// `<map>.cpu0`, `<map>.cpu1`, ... up to the first gap.
+#[derive(Clone, Debug)]
+pub struct DirMaps {
+    dir: PathBuf,
+}
+
+impl DirMaps {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn read_values(path: &Path, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<u8>>> {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("CANNOT READ {}", path.display()))?;
+        if bytes.len() != nr_keys as usize * value_size {
+            bail!(
+                "{}: {} BYTES, EXPECTED {} KEYS x {} BYTES",
+                path.display(),
+                bytes.len(),
+                nr_keys,
+                value_size
+            );
+        }
+        Ok(bytes.chunks(value_size).map(<[u8]>::to_vec).collect())
+    }
+}
+
+impl MapSource for DirMaps {
+    fn read_array(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<u8>>> {
+        Self::read_values(&self.dir.join(map), nr_keys, value_size)
+    }
+
+    fn read_percpu(&self, map: &str, nr_keys: u32, value_size: usize) -> Result<Vec<Vec<Vec<u8>>>> {
+        let mut by_cpu = Vec::new();
+        loop {
+            let path = self.dir.join(format!("{}.cpu{}", map, by_cpu.len()));
+            if !path.exists() {
+                break;
+            }
+            by_cpu.push(Self::read_values(&path, nr_keys, value_size)?);
+        }
+        if by_cpu.is_empty() {
+            bail!("{}: NO {}.cpu0", self.dir.display(), map);
+        }
+        Ok((0..nr_keys as usize)
+            .map(|key| by_cpu.iter().map(|cpu| cpu[key].clone()).collect())
+            .collect())
+    }
+}
+
+/// The adaptive layer's state, as answered by `get regime`.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegimeStatus {
+    pub regime: Regime,
+    /// Regime detection is off (`ctl pin regime`).
+    pub pinned: bool,
+    /// Adaptive knob writes are frozen (`ctl pause`).
+    pub paused: bool,
+    /// The P99 reflex has tightened the slice.
+    pub tightened: bool,
+    /// Knobs held by `ctl set`, released by `ctl unset`.
+    pub held: Vec<String>,
+    pub slice_min_ns: u64,
+    pub slice_max_ns: u64,
+    /// Kernel-requested restarts this run (0 from builds without the field).
+    pub restarts: u32,
+    /// The latest restart's uei reason.
+    pub last_restart: Option<String>,
+}
+
+impl RegimeStatus {
+    /// Decode a parsed `get regime` response.
+    pub fn from_response(kv: &BTreeMap<String, String>) -> Result<Self, String> {
+        let field = |k: &str| {
+            kv.get(k)
+                .map(String::as_str)
+                .ok_or_else(|| format!("get regime: NO {} IN RESPONSE", k))
+        };
+        let flag = |k: &str| {
+            field(k)?
+                .parse::<bool>()
+                .map_err(|_| format!("get regime: BAD {}", k))
+        };
+        let ns = |k: &str| {
+            field(k)?
+                .parse::<u64>()
+                .map_err(|_| format!("get regime: BAD {}", k))
+        };
+        let held = field("held")?;
+        Ok(Self {
+            regime: Regime::from_label(field("regime")?)
+                .ok_or_else(|| "get regime: UNKNOWN REGIME".to_string())?,
+            pinned: flag("pinned")?,
+            paused: flag("paused")?,
+            tightened: flag("tightened")?,
+            held: if held == "-" {
+                Vec::new()
+            } else {
+                held.split(',').map(str::to_string).collect()
+            },
+            slice_min_ns: ns("slice_min_ns")?,
+            slice_max_ns: ns("slice_max_ns")?,
+            // OPTIONAL: A SCHEDULER FROM BEFORE RESTART TRACKING LEAVES THEM OUT
+            restarts: match kv.get("restarts") {
+                Some(n) => n
+                    .parse()
+                    .map_err(|_| "get regime: BAD restarts".to_string())?,
+                None => 0,
+            },
+            last_restart: kv
+                .get("last_restart")
+                .filter(|r| r.as_str() != "-")
+                .cloned(),
+        })
+    }
+}
+
+/// Cumulative wakeup-latency histogram since the scheduler loaded.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WakeLatHist {
+    /// Counts indexed `[tier][bucket]`: tiers as in [`WAKE_TIERS`], bucket
+    /// upper edges in [`crate::tuning::HIST_EDGES_NS`].
+    pub counts: [[u64; HIST_BUCKETS]; 3],
+}
+
+impl WakeLatHist {
+    pub fn from_counts(counts: [[u64; HIST_BUCKETS]; 3]) -> Self {
+        Self { counts }
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// P99 over all tiers pooled, as the adaptive layer computes it.
+    pub fn p99_ns(&self) -> u64 {
+        pooled_p99_ns(&self.counts)
+    }
+
+    pub fn tier_p99_ns(&self, tier: usize) -> Option<u64> {
+        self.counts.get(tier).map(compute_p99_from_histogram)
+    }
+}
+
+/// Online CPUs that were at least `min_idle_pct`% idle between two
+/// `/proc/stat` snapshots.
+pub fn idle_cpus_between(before: &str, after: &str, min_idle_pct: u64) -> Vec<u32> {
+    let before = parse_proc_stat(before);
+    parse_proc_stat(after)
+        .into_iter()
+        .filter_map(|(cpu, idle, total)| {
+            let &(_, pi, pt) = before.iter().find(|b| b.0 == cpu)?;
+            let dt = total.saturating_sub(pt);
+            let di = idle.saturating_sub(pi);
+            (dt > 0 && di * 100 >= dt * min_idle_pct).then_some(cpu as u32)
+        })
+        .collect()
+}
+
+/// One control-socket request (protocol in [`crate::ctl`]). `ERR` replies
+/// become `Err`.
+pub fn request(socket: &Path, line: &str) -> Result<BTreeMap<String, String>> {
+    if let Err(e) = ctl::parse_command(line) {
+        bail!("{}", e);
+    }
+
+    let mut stream = match UnixStream::connect(socket) {
+        Ok(s) => s,
+        Err(e) => bail!(
+            "CANNOT CONNECT TO {}: {} (IS PANDEMONIUM RUNNING? ROOT OR --pin-group MEMBERSHIP REQUIRED)",
+            socket.display(),
+            e
+        ),
+    };
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+    stream.write_all(format!("{}\n", line).as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut resp = String::new();
+    stream.read_to_string(&mut resp)?;
+
+    ctl::parse_response(&resp).map_err(anyhow::Error::msg)
+}
+
+/// A read-only view of a running PANDEMONIUM.
+///
+/// Nothing is opened up front: every call reads the current state, so one
+/// client survives scheduler restarts.
+///
+/// ```no_run
+/// use pandemonium::client::Client;
+///
+/// let client = Client::default(); // /sys/fs/bpf/pandemonium, /run/pandemonium.sock
+/// let stats = client.stats()?;
+/// println!("dispatches: {}", stats.nr_dispatches);
+/// println!("regime: {}", client.regime()?.regime.label());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Against a fake pin directory:
+///
+/// ```
+/// use pandemonium::client::{Client, DirMaps};
+/// use pandemonium::tuning::TuningKnobs;
+///
+/// let dir = std::env::temp_dir().join(format!("pandemonium-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir)?;
+/// let knobs = TuningKnobs::default();
+/// let bytes: Vec<u8> = knobs.values().iter().flat_map(|v| v.to_ne_bytes()).collect();
+/// std::fs::write(dir.join("tuning_knobs"), bytes)?;
+///
+/// let client = Client::with_maps(DirMaps::new(&dir), "/nonexistent.sock");
+/// assert_eq!(client.knobs()?, knobs);
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Client<M: MapSource = PinnedMaps> {
+    maps: M,
+    socket: PathBuf,
+}
+
+impl Default for Client<PinnedMaps> {
+    fn default() -> Self {
+        Self::new(PinDir::default(), ctl::SOCKET_PATH)
+    }
+}
+
+impl Client<PinnedMaps> {
+    /// The scheduler started with `--pin-dir` and `--ctl-socket`.
+    pub fn new(pins: PinDir, socket: impl Into<PathBuf>) -> Self {
+        Self::with_maps(PinnedMaps::new(pins), socket)
+    }
+}
+
+impl<M: MapSource> Client<M> {
+    pub fn with_maps(maps: M, socket: impl Into<PathBuf>) -> Self {
+        Self {
+            maps,
+            socket: socket.into(),
+        }
+    }
+
+    /// Cumulative BPF counters, summed over CPUs.
+    pub fn stats(&self) -> Result<PandemoniumStats> {
+        Ok(PandemoniumStats::total(&self.stats_percpu()?))
+    }
+
+    /// One entry per possible CPU, indexed by CPU id.
+    pub fn stats_percpu(&self) -> Result<Vec<PandemoniumStats>> {
+        let keys = self
+            .maps
+            .read_percpu(STATS_PIN, 1, std::mem::size_of::<PandemoniumStats>())?;
+        Ok(keys
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(|v| PandemoniumStats::from_bytes(v).unwrap_or_default())
+            .collect())
+    }
+
+    /// The knobs BPF is scheduling with right now.
+    pub fn knobs(&self) -> Result<TuningKnobs> {
+        let keys = self
+            .maps
+            .read_array(KNOBS_PIN, 1, std::mem::size_of::<TuningKnobs>())?;
+        keys.first()
+            .and_then(|v| TuningKnobs::from_bytes(v))
+            .with_context(|| format!("{}: EMPTY", KNOBS_PIN))
+    }
+
+    /// Regime and override state from the control socket (adaptive mode only).
+    pub fn regime(&self) -> Result<RegimeStatus> {
+        let kv = request(&self.socket, "get regime")?;
+        RegimeStatus::from_response(&kv).map_err(anyhow::Error::msg)
+    }
+
+    /// Online CPUs at least [`IDLE_CPU_PCT`]% idle over `window`. Blocks for
+    /// `window`.
+    pub fn idle_cpus(&self, window: Duration) -> Result<Vec<u32>> {
+        let before = std::fs::read_to_string(PROC_STAT_PATH)?;
+        std::thread::sleep(window);
+        let after = std::fs::read_to_string(PROC_STAT_PATH)?;
+        Ok(idle_cpus_between(&before, &after, IDLE_CPU_PCT))
+    }
+
+    /// CPUs the scheduler keeps dispatch off (`--reserved-cpus`), ascending.
+    /// Empty when none are reserved.
+    pub fn reserved_cpus(&self) -> Result<Vec<u32>> {
+        let keys = self
+            .maps
+            .read_array(RESERVED_CPUS_PIN, 1, std::mem::size_of::<CpuBitmap>())?;
+        let bytes = keys
+            .first()
+            .with_context(|| format!("{}: EMPTY", RESERVED_CPUS_PIN))?;
+        let words: Vec<u64> = bytes
+            .chunks_exact(8)
+            .filter_map(|w| <[u8; 8]>::try_from(w).ok())
+            .map(u64::from_ne_bytes)
+            .collect();
+        Ok(bitmap_cpus(&words))
+    }
+
+    /// Cumulative wakeup-latency histogram, summed over CPUs.
+    pub fn histogram(&self) -> Result<WakeLatHist> {
+        let keys = self.maps.read_percpu(
+            WAKE_HIST_PIN,
+            (WAKE_TIERS.len() * HIST_BUCKETS) as u32,
+            std::mem::size_of::<u64>(),
+        )?;
+        let sum = |key: usize| -> u64 {
+            keys.get(key)
+                .into_iter()
+                .flatten()
+                .filter_map(|v| <[u8; 8]>::try_from(v.as_slice()).ok())
+                .map(u64::from_ne_bytes)
+                .sum()
+        };
+        Ok(WakeLatHist::from_counts(std::array::from_fn(|tier| {
+            std::array::from_fn(|bucket| sum(tier * HIST_BUCKETS + bucket))
+        })))
+    }
+}
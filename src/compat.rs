@@ -0,0 +1,249 @@
+// PANDEMONIUM SCHED_EXT API COMPATIBILITY
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE KERNEL sched_ext API MOVES UNDER US (scx_bpf_dispatch ->
+// scx_bpf_dsq_insert, scx_bpf_consume -> scx_bpf_dsq_move_to_local,
+// DIRECT p->scx.slice WRITES -> scx_bpf_task_set_slice). BEFORE LOADING
+// THE BPF PROGRAM, SCAN THE RUNNING KERNEL'S BTF STRING TABLE FOR EVERY
+// KFUNC main.bpf.c DEPENDS ON AND REPORT PASS/WARN/FAIL PER SYMBOL.
+//
+// BTF LAYOUT (include/uapi/linux/btf.h):
+//   struct btf_header { u16 magic; u8 version; u8 flags; u32 hdr_len;
+//                       u32 type_off; u32 type_len; u32 str_off; u32 str_len; }
+//   OFFSETS ARE RELATIVE TO THE END OF THE HEADER. THE STRING SECTION IS A
+//   SEQUENCE OF NUL-TERMINATED NAMES -- EVERY KFUNC HAS ITS NAME THERE.
+
+use anyhow::{bail, Result};
+
+pub const BTF_VMLINUX_PATH: &str = "/sys/kernel/btf/vmlinux";
+
+const BTF_MAGIC: u16 = 0xeb9f;
+const BTF_HEADER_MIN: usize = 24;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Compat {
+    Pass, // CURRENT NAME PRESENT
+    Warn, // ONLY LEGACY NAME PRESENT, OR OPTIONAL SYMBOL MISSING
+    Fail, // REQUIRED SYMBOL MISSING UNDER EVERY KNOWN NAME
+}
+
+impl Compat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+// ONE KFUNC THE BPF PROGRAM CALLS (DIRECTLY OR VIA compat.bpf.h)
+pub struct KfuncReq {
+    pub name: &'static str,
+    pub legacy: Option<&'static str>,
+    pub required: bool,
+}
+
+// EVERY scx_bpf_* CALL IN main.bpf.c. OPTIONAL ENTRIES ARE __weak OR
+// NEWER-KERNEL ALTERNATIVES THE PROGRAM CAN LIVE WITHOUT.
+pub const KFUNCS: &[KfuncReq] = &[
+    KfuncReq {
+        name: "scx_bpf_dsq_insert_vtime",
+        legacy: Some("scx_bpf_dispatch_vtime"),
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_dsq_move_to_local",
+        legacy: Some("scx_bpf_consume"),
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_create_dsq",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_dsq_nr_queued",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_kick_cpu",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_select_cpu_dfl",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_test_and_clear_cpu_idle",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_reenqueue_local",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_task_cpu",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_cpu_curr",
+        legacy: Some("scx_bpf_cpu_rq"),
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_pick_idle_cpu_node",
+        legacy: None,
+        required: false,
+    },
+    KfuncReq {
+        name: "scx_bpf_pick_any_cpu_node",
+        legacy: None,
+        required: false,
+    },
+    KfuncReq {
+        name: "scx_bpf_nr_node_ids",
+        legacy: None,
+        required: false,
+    },
+    KfuncReq {
+        name: "scx_bpf_cpu_node",
+        legacy: None,
+        required: false,
+    },
+    KfuncReq {
+        name: "scx_bpf_task_set_slice",
+        legacy: None,
+        required: false,
+    },
+    KfuncReq {
+        name: "scx_bpf_task_set_dsq_vtime",
+        legacy: None,
+        required: false,
+    },
+];
+
+pub struct KfuncStatus {
+    pub name: &'static str,
+    pub found_as: Option<&'static str>,
+    pub status: Compat,
+}
+
+pub struct CompatReport {
+    pub kernel: String,
+    pub entries: Vec<KfuncStatus>,
+}
+
+impl CompatReport {
+    pub fn missing_required(&self) -> Vec<&'static str> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == Compat::Fail)
+            .map(|e| e.name)
+            .collect()
+    }
+
+    pub fn worst(&self) -> Compat {
+        if self.entries.iter().any(|e| e.status == Compat::Fail) {
+            Compat::Fail
+        } else if self.entries.iter().any(|e| e.status == Compat::Warn) {
+            Compat::Warn
+        } else {
+            Compat::Pass
+        }
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.name == name && e.found_as.is_some())
+    }
+
+    // BOTH SETTERS OR NEITHER: BPF SWITCHES SLICE AND VTIME WRITES TOGETHER
+    pub fn has_task_set_kfuncs(&self) -> bool {
+        self.has("scx_bpf_task_set_slice") && self.has("scx_bpf_task_set_dsq_vtime")
+    }
+}
+
+// EXTRACT THE STRING SECTION FROM A RAW BTF BLOB. HANDLES BOTH BYTE ORDERS.
+pub fn btf_strings(data: &[u8]) -> Result<&[u8]> {
+    if data.len() < BTF_HEADER_MIN {
+        bail!("BTF TOO SHORT ({} BYTES)", data.len());
+    }
+    let le = match u16::from_le_bytes([data[0], data[1]]) {
+        BTF_MAGIC => true,
+        m if m.swap_bytes() == BTF_MAGIC => false,
+        m => bail!("BAD BTF MAGIC {:#06x}", m),
+    };
+    let rd = |off: usize| -> u32 {
+        let b = [data[off], data[off + 1], data[off + 2], data[off + 3]];
+        if le {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        }
+    };
+    let hdr_len = rd(4) as usize;
+    let str_off = rd(16) as usize;
+    let str_len = rd(20) as usize;
+    let start = hdr_len.saturating_add(str_off);
+    let end = start.saturating_add(str_len);
+    if hdr_len < BTF_HEADER_MIN || end > data.len() {
+        bail!(
+            "BTF STRING SECTION OUT OF BOUNDS ({}..{} OF {})",
+            start,
+            end,
+            data.len()
+        );
+    }
+    Ok(&data[start..end])
+}
+
+// EXACT NAME MATCH AGAINST THE NUL-SEPARATED STRING TABLE
+pub fn has_symbol(strtab: &[u8], name: &str) -> bool {
+    strtab.split(|&b| b == 0).any(|s| s == name.as_bytes())
+}
+
+pub fn evaluate(strtab: &[u8], reqs: &[KfuncReq]) -> Vec<KfuncStatus> {
+    reqs.iter()
+        .map(|r| {
+            let (found_as, status) = if has_symbol(strtab, r.name) {
+                (Some(r.name), Compat::Pass)
+            } else if let Some(legacy) = r.legacy.filter(|l| has_symbol(strtab, l)) {
+                (Some(legacy), Compat::Warn)
+            } else if r.required {
+                (None, Compat::Fail)
+            } else {
+                (None, Compat::Warn)
+            };
+            KfuncStatus {
+                name: r.name,
+                found_as,
+                status,
+            }
+        })
+        .collect()
+}
+
+pub fn kernel_release() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+// READ THE RUNNING KERNEL'S BTF AND EVALUATE EVERY KFUNC WE DEPEND ON
+pub fn probe_kernel() -> Result<CompatReport> {
+    let data = std::fs::read(BTF_VMLINUX_PATH)?;
+    let strtab = btf_strings(&data)?;
+    Ok(CompatReport {
+        kernel: kernel_release(),
+        entries: evaluate(strtab, KFUNCS),
+    })
+}
@@ -0,0 +1,107 @@
+// PANDEMONIUM CONFLICTING TUNING DAEMONS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// DAEMONS THAT RENICE, CHANGE ioprio OR SCHEDULING POLICY PER PROCESS
+// FIGHT THE TIERING: A nice CHANGE MOVES A TASK'S WEIGHT UNDER THE
+// CLASSIFIER, AND A BENCHMARK THEN MEASURES BOTH POLICIES AT ONCE. AT
+// STARTUP AND IN `check` A /proc SCAN MATCHES EVERY comm AGAINST
+// CONFLICTS AND WARNS, NAMING EACH DAEMON AND WHAT IT DOES.
+// --ignore-conflicts SILENCES THE STARTUP WARNING.
+//
+// ONE ROW PER DAEMON. comm IS WHAT /proc/<pid>/comm SHOWS: THE EXECUTABLE
+// (OR SCRIPT) NAME, CUT TO 15 BYTES BY THE KERNEL.
+
+use std::path::Path;
+
+pub struct Conflict {
+    pub comm: &'static str,
+    pub name: &'static str,
+    pub note: &'static str,
+}
+
+pub const CONFLICTS: &[Conflict] = &[
+    Conflict {
+        comm: "ananicy-cpp",
+        name: "ananicy-cpp",
+        note: "renices and sets ioprio/sched policy per comm rule, overriding tier weights",
+    },
+    Conflict {
+        comm: "ananicy",
+        name: "ananicy",
+        note: "renices and sets ioprio/sched policy per comm rule, overriding tier weights",
+    },
+    Conflict {
+        comm: "system76-schedu",
+        name: "system76-scheduler",
+        note: "renices foreground/background processes and boosts the focused window's",
+    },
+    Conflict {
+        comm: "gamemoded",
+        name: "gamemode",
+        note: "renices games (renice=) and raises their ioprio (ioprio=, on by default); \
+               set renice=0 and ioprio=off in gamemode.ini",
+    },
+];
+
+// THE KERNEL CUTS comm TO TASK_COMM_LEN - 1
+pub const COMM_LEN: usize = 15;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Found {
+    pub pid: u32,
+    pub name: &'static str,
+    pub note: &'static str,
+}
+
+impl Found {
+    pub fn message(&self) -> String {
+        format!(
+            "CONFLICTING DAEMON: {} (PID {}) {}. ITS EFFECT MIXES WITH PANDEMONIUM'S TIERING; \
+             --ignore-conflicts SILENCES THIS",
+            self.name, self.pid, self.note
+        )
+    }
+}
+
+// A comm AS READ FROM /proc (TRAILING NEWLINE) AGAINST THE TABLE
+pub fn match_comm(comm: &str) -> Option<&'static Conflict> {
+    let comm = comm.trim_end_matches('\n');
+    CONFLICTS.iter().find(|c| c.comm == comm)
+}
+
+// (pid, comm) PAIRS -> ONE Found PER DAEMON (ITS LOWEST PID), TABLE ORDER
+pub fn detect<'a>(procs: impl IntoIterator<Item = (u32, &'a str)>) -> Vec<Found> {
+    let mut found: Vec<Found> = Vec::new();
+    for (pid, comm) in procs {
+        let Some(c) = match_comm(comm) else {
+            continue;
+        };
+        match found.iter_mut().find(|f| f.name == c.name) {
+            Some(f) => f.pid = f.pid.min(pid),
+            None => found.push(Found {
+                pid,
+                name: c.name,
+                note: c.note,
+            }),
+        }
+    }
+    found.sort_by_key(|f| CONFLICTS.iter().position(|c| c.name == f.name));
+    found
+}
+
+// EVERY NUMERIC DIRECTORY UNDER proc_root WITH A READABLE comm. A PROCESS
+// THAT EXITS MID-SCAN IS SKIPPED, NOT AN ERROR.
+pub fn scan(proc_root: &Path) -> Vec<Found> {
+    let Ok(dir) = std::fs::read_dir(proc_root) else {
+        return Vec::new();
+    };
+    let procs: Vec<(u32, String)> = dir
+        .flatten()
+        .filter_map(|e| {
+            let pid = e.file_name().to_str()?.parse::<u32>().ok()?;
+            let comm = std::fs::read_to_string(e.path().join("comm")).ok()?;
+            Some((pid, comm))
+        })
+        .collect();
+    detect(procs.iter().map(|(pid, comm)| (*pid, comm.as_str())))
+}
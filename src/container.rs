@@ -0,0 +1,355 @@
+// PANDEMONIUM CONTAINER AWARENESS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// A PRIVILEGED CONTAINER CAN LOAD A sched_ext SCHEDULER, BUT THE FAILURES
+// ON THE WAY ARE OPAQUE: NO sudo, A /sys/fs/bpf THAT IS A PLAIN DIRECTORY
+// (PINS FAIL SILENTLY), A PRIVATE /sys WITHOUT /sys/kernel/sched_ext.
+// THIS MODULE DETECTS THE CONTAINER AND CHECKS WHAT THE PIN DIRECTORY IS
+// MOUNTED ON (privilege.rs RUNS CHILDREN WITHOUT sudo AS ROOT). EVERY
+// HELPER TAKES ITS INPUTS AS TEXT SO THE TESTS NEED NO CONTAINER.
+
+use std::path::{Component, Path, PathBuf};
+
+pub const DOCKERENV_PATH: &str = "/.dockerenv";
+pub const CONTAINERENV_PATH: &str = "/run/.containerenv"; // PODMAN
+pub const PID1_ENVIRON_PATH: &str = "/proc/1/environ";
+pub const SELF_CGROUP_PATH: &str = "/proc/self/cgroup";
+pub const SELF_MOUNTS_PATH: &str = "/proc/self/mounts";
+
+pub const BPFFS_ROOT: &str = "/sys/fs/bpf";
+pub const DEFAULT_PIN_DIR: &str = "/sys/fs/bpf/pandemonium";
+
+// CGROUP PATH FRAGMENTS LEFT BY CONTAINER RUNTIMES (CGROUP V1, OR V2
+// WITHOUT A CGROUP NAMESPACE)
+const CGROUP_MARKERS: [(&str, &str); 5] = [
+    ("/docker", "docker"),
+    ("kubepods", "kubernetes"),
+    ("libpod", "podman"),
+    ("lxc.payload", "lxc"),
+    ("/lxc/", "lxc"),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerSignals {
+    pub dockerenv: bool,
+    pub containerenv: bool,
+    pub pid1_environ: Option<String>, // NUL-SEPARATED; UNREADABLE WITHOUT ROOT
+    pub cgroup: String,
+}
+
+impl ContainerSignals {
+    pub fn read() -> Self {
+        Self {
+            dockerenv: Path::new(DOCKERENV_PATH).exists(),
+            containerenv: Path::new(CONTAINERENV_PATH).exists(),
+            pid1_environ: std::fs::read(PID1_ENVIRON_PATH)
+                .ok()
+                .map(|b| String::from_utf8_lossy(&b).into_owned()),
+            cgroup: std::fs::read_to_string(SELF_CGROUP_PATH).unwrap_or_default(),
+        }
+    }
+
+    // RUNTIME NAME, OR None ON THE HOST. MARKER FILES FIRST, THEN THE
+    // container= VARIABLE nspawn/podman/lxc GIVE PID 1, THEN CGROUP PATHS.
+    // LAST: A PURE CGROUP-V2 VIEW ROOTED AT "/" IS A CGROUP NAMESPACE (A
+    // systemd HOST NEVER RUNS USER PROCESSES IN THE ROOT CGROUP).
+    pub fn runtime(&self) -> Option<String> {
+        if self.dockerenv {
+            return Some("docker".to_string());
+        }
+        if self.containerenv {
+            return Some("podman".to_string());
+        }
+        if let Some(env) = &self.pid1_environ {
+            let named = env
+                .split('\0')
+                .find_map(|kv| kv.strip_prefix("container="))
+                .filter(|v| !v.is_empty());
+            if let Some(name) = named {
+                return Some(name.to_string());
+            }
+        }
+        for line in self.cgroup.lines() {
+            let path = line.splitn(3, ':').nth(2).unwrap_or("");
+            if let Some((_, name)) = CGROUP_MARKERS.iter().find(|(m, _)| path.contains(m)) {
+                return Some(name.to_string());
+            }
+        }
+        let lines: Vec<&str> = self.cgroup.lines().filter(|l| !l.is_empty()).collect();
+        if lines == ["0::/"] {
+            return Some("cgroup-namespace".to_string());
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountEntry {
+    pub point: PathBuf,
+    pub fstype: String,
+}
+
+// /proc/self/mounts: "<source> <point> <fstype> <options> 0 0", SPACES IN
+// THE MOUNT POINT OCTAL-ESCAPED (\040)
+pub fn parse_mounts(text: &str) -> Vec<MountEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            let point = fields.next()?;
+            let fstype = fields.next()?;
+            Some(MountEntry {
+                point: PathBuf::from(unescape_octal(point)),
+                fstype: fstype.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn unescape_octal(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|d| d.iter().all(|b| (b'0'..=b'7').contains(b)));
+        match (bytes[i], octal) {
+            (b'\\', Some(d)) => {
+                out.push((d[0] - b'0') * 64 + (d[1] - b'0') * 8 + (d[2] - b'0'));
+                i += 4;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// THE MOUNT path LIVES ON: LONGEST COMPONENT-WISE PREFIX, LATER ENTRIES
+// WIN TIES (A MOUNT STACKED OVER ANOTHER AT THE SAME POINT HIDES IT)
+pub fn mount_for<'a>(mounts: &'a [MountEntry], path: &Path) -> Option<&'a MountEntry> {
+    let depth = |p: &Path| {
+        p.components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .count()
+    };
+    mounts.iter().filter(|m| path.starts_with(&m.point)).fold(
+        None,
+        |best: Option<&MountEntry>, m| match best {
+            Some(b) if depth(&b.point) > depth(&m.point) => Some(b),
+            _ => Some(m),
+        },
+    )
+}
+
+// PINS ONLY WORK ON bpffs. create_dir_all() SUCCEEDS ON THE OVERLAY OR
+// TMPFS A CONTAINER SHOWS AT /sys/fs/bpf, SO THE DIRECTORY EXISTING PROVES
+// NOTHING. THE PIN DIRECTORY ITSELF NEED NOT EXIST YET.
+pub fn on_bpffs(mounts: &[MountEntry], pin_dir: &Path) -> bool {
+    mount_for(mounts, pin_dir).is_some_and(|m| m.fstype == "bpf")
+}
+
+pub fn bpffs_hint(pin_dir: &Path, runtime: Option<&str>) -> String {
+    let mut hint = format!(
+        "{} IS NOT ON A BPF FILESYSTEM: mount -t bpf bpf {}",
+        pin_dir.display(),
+        BPFFS_ROOT
+    );
+    if let Some(rt) = runtime {
+        hint.push_str(&format!(
+            " (IN {}: START THE CONTAINER WITH -v {}:{} OR PASS --pin-dir ON A BPFFS MOUNT)",
+            rt, BPFFS_ROOT, BPFFS_ROOT
+        ));
+    }
+    hint
+}
+
+// /sys/kernel/sched_ext MISSING: NO sched_ext IN THE KERNEL, OR (IN A
+// CONTAINER) A /sys THAT ISN'T THE HOST'S
+pub fn sched_ext_hint(runtime: Option<&str>) -> String {
+    match runtime {
+        Some(rt) => format!(
+            "IN {}: /sys MAY NOT BE THE HOST'S. RUN THE CONTAINER --privileged (OR BIND-MOUNT /sys)",
+            rt
+        ),
+        None => "KERNEL NEEDS CONFIG_SCHED_CLASS_EXT=y (6.12+)".to_string(),
+    }
+}
+
+// WHERE THE SCHEDULER PINS ITS MAPS (--pin-dir) AND WHERE OUT-OF-PROCESS
+// READERS (check --runtime, topology --live, client.rs) FIND THEM. ONE FILE
+// PER MAP, NAMED AFTER THE MAP (tuning_knobs_map PINS AS tuning_knobs).
+// --pin-group HANDS THE DIRECTORY AND ITS PINS TO A GROUP (SEE share()).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinDir {
+    dir: PathBuf,
+    group: Option<u32>,
+}
+
+pub const KNOBS_PIN: &str = "tuning_knobs";
+pub const STATS_PIN: &str = "stats_map";
+pub const WAKE_HIST_PIN: &str = "wake_lat_hist";
+pub const CPU_BUSY_PIN: &str = "cpu_busy";
+pub const RESERVED_CPUS_PIN: &str = "reserved_cpus";
+
+// --pin-group: DIRECTORY root:GROUP rwxr-x---, EVERY PIN rw-rw---- (THE
+// CONTROL SOCKET'S ctl::SOCKET_MODE: THE GROUP IS TRUSTED TO SET KNOBS)
+pub const SHARED_DIR_MODE: u32 = 0o750;
+pub const SHARED_PIN_MODE: u32 = 0o660;
+
+impl Default for PinDir {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(DEFAULT_PIN_DIR),
+            group: None,
+        }
+    }
+}
+
+impl PinDir {
+    // RELATIVE PATHS WOULD RESOLVE AGAINST EACH READER'S cwd
+    pub fn new(dir: &Path) -> Result<Self, String> {
+        if !dir.is_absolute() {
+            return Err(format!(
+                "--pin-dir {} MUST BE AN ABSOLUTE PATH",
+                dir.display()
+            ));
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            group: None,
+        })
+    }
+
+    pub fn with_group(self, gid: u32) -> Self {
+        Self {
+            group: Some(gid),
+            ..self
+        }
+    }
+
+    pub fn group(&self) -> Option<u32> {
+        self.group
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn map(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    pub fn knobs(&self) -> PathBuf {
+        self.map(KNOBS_PIN)
+    }
+
+    pub fn stats(&self) -> PathBuf {
+        self.map(STATS_PIN)
+    }
+
+    // AFTER PINNING: chown THE DIRECTORY AND EVERY PIN IN IT TO THE GROUP
+    // AND OPEN THEM TO IT. NO GROUP: NOTHING TO DO.
+    pub fn share(&self) -> std::io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let Some(gid) = self.group else {
+            return Ok(());
+        };
+        std::os::unix::fs::chown(&self.dir, None, Some(gid))?;
+        std::fs::set_permissions(&self.dir, std::fs::Permissions::from_mode(SHARED_DIR_MODE))?;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            std::os::unix::fs::chown(&path, None, Some(gid))?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(SHARED_PIN_MODE))?;
+        }
+        Ok(())
+    }
+
+    // THE FIRST ANCESTOR A NON-ROOT GROUP MEMBER CANNOT ENTER (NO o+x).
+    // DISTROS MOUNT /sys/fs/bpf 0700: THE GROUP NEVER REACHES THE PINS.
+    pub fn blocked_ancestor(&self) -> Option<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        self.dir.ancestors().skip(1).find_map(|a| {
+            let mode = std::fs::metadata(a).ok()?.permissions().mode();
+            (mode & 0o001 == 0).then(|| a.to_path_buf())
+        })
+    }
+}
+
+// ALL-OR-NOTHING PINNING FOR Scheduler::init(): EVERY PIN MADE THROUGH
+// THE GUARD, AND THE DIRECTORY IF THE GUARD CREATED IT, IS REMOVED AGAIN
+// WHEN IT DROPS WITHOUT commit(). A FAILED PIN NEVER LEAVES CLIENTS A
+// DIRECTORY MIXING THIS RUN'S MAPS WITH MISSING ONES.
+pub struct PinGuard {
+    dir: PathBuf,
+    created_dir: bool,
+    pinned: Vec<PathBuf>,
+    committed: bool,
+}
+
+impl PinGuard {
+    pub fn create(pins: &PinDir) -> std::io::Result<Self> {
+        let created_dir = !pins.dir().is_dir();
+        std::fs::create_dir_all(pins.dir())?;
+        Ok(Self {
+            dir: pins.dir().to_path_buf(),
+            created_dir,
+            pinned: Vec::new(),
+            committed: false,
+        })
+    }
+
+    // REPLACE WHATEVER A PREVIOUS RUN LEFT AT path WITH pin(path)
+    pub fn pin<E>(
+        &mut self,
+        path: PathBuf,
+        pin: impl FnOnce(&Path) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let _ = std::fs::remove_file(&path);
+        pin(&path)?;
+        self.pinned.push(path);
+        Ok(())
+    }
+
+    pub fn pinned(&self) -> &[PathBuf] {
+        &self.pinned
+    }
+
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for PinGuard {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        for path in self.pinned.iter().rev() {
+            let _ = std::fs::remove_file(path);
+        }
+        // ONLY THE LEAF, AND ONLY IF EMPTY: A PARENT OR A STRANGER'S FILE STAYS
+        if self.created_dir {
+            let _ = std::fs::remove_dir(&self.dir);
+        }
+    }
+}
+
+// --pin-group NAME OR NUMERIC GID
+pub fn resolve_group(group: &str) -> Result<u32, String> {
+    if let Ok(gid) = group.parse::<u32>() {
+        return Ok(gid);
+    }
+    let name = std::ffi::CString::new(group).map_err(|_| format!("BAD GROUP NAME {:?}", group))?;
+    // getgrnam: STATIC BUFFER, COPIED OUT BEFORE ANY OTHER CALL
+    let gr = unsafe { libc::getgrnam(name.as_ptr()) };
+    if gr.is_null() {
+        return Err(format!("NO SUCH GROUP {}", group));
+    }
+    Ok(unsafe { (*gr).gr_gid })
+}
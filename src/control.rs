@@ -0,0 +1,96 @@
+// PANDEMONIUM CONTROL SOCKET SERVER
+// UNIX DOMAIN SOCKET, ONE SMALL ACCEPT THREAD.
+//
+// THE THREAD ONLY PARSES. EVERY COMMAND IS FORWARDED OVER A CHANNEL AND
+// EXECUTED BY THE MONITOR LOOP AT ITS NEXT TICK, SO SCHEDULER STATE
+// (KNOBS, REGIME, TIGHTEN STATE) HAS A SINGLE OWNER AND NO LOCKS.
+// PROTOCOL: ctl.rs
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+use crate::ctl::{self, CtlRequest};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct CtlServer {
+    path: String,
+    #[cfg_attr(not(feature = "dbus"), allow(dead_code))]
+    tx: Sender<CtlRequest>,
+    pub rx: Receiver<CtlRequest>,
+}
+
+impl CtlServer {
+    // group: --pin-group, THE SOCKET JOINS THE PINS IN THAT GROUP
+    pub fn bind(path: &str, group: Option<u32>) -> Result<Self> {
+        if Path::new(path).exists() {
+            if UnixStream::connect(path).is_ok() {
+                bail!("CONTROL SOCKET {} IS IN USE BY ANOTHER INSTANCE", path);
+            }
+            // STALE SOCKET FROM A CRASHED RUN
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(ctl::SOCKET_MODE))?;
+        if let Some(gid) = group {
+            std::os::unix::fs::chown(path, None, Some(gid))?;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let server_tx = tx.clone();
+        std::thread::Builder::new()
+            .name("pandemonium-ctl".to_string())
+            .spawn(move || serve(listener, server_tx))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            tx,
+            rx,
+        })
+    }
+
+    // ANOTHER FRONT END (--dbus) FEEDING THE SAME MONITOR-LOOP CHANNEL
+    #[cfg_attr(not(feature = "dbus"), allow(dead_code))]
+    pub fn sender(&self) -> Sender<CtlRequest> {
+        self.tx.clone()
+    }
+}
+
+impl Drop for CtlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn serve(listener: UnixListener, tx: Sender<CtlRequest>) {
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        let resp = handle(&stream, &tx);
+        let _ = stream.write_all(resp.as_bytes());
+    }
+}
+
+fn handle(stream: &UnixStream, tx: &Sender<CtlRequest>) -> String {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let mut line = String::new();
+    if BufReader::new(stream.take(ctl::MAX_LINE as u64))
+        .read_line(&mut line)
+        .is_err()
+    {
+        return ctl::err("READ FAILED");
+    }
+
+    match ctl::parse_command(&line) {
+        Ok(cmd) => ctl::dispatch(tx, cmd),
+        Err(e) => ctl::err(&e),
+    }
+}
@@ -0,0 +1,106 @@
+// PANDEMONIUM CPU LISTS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE KERNEL'S cpulist FORMAT: "0-3,8,10-11". ONE STRICT PARSER FOR SYSFS
+// (ONLINE SET, CACHE shared_cpu_list, CGROUP CPUSETS) AND USER INPUT
+// (--reserved-cpus), ONE FORMATTER FOR EVERY BANNER AND REPORT.
+//
+// STRICT: A TOKEN THAT IS NOT N OR A-B FAILS THE WHOLE LIST. A LIST THAT
+// PARSES "MOSTLY" WOULD NAME THE WRONG CPUS WITHOUT A WORD.
+
+// KERNEL CONFIG_NR_CPUS TOPS OUT AT 8192. A BOUND, SO "0-4000000000" IS AN
+// ERROR AND NOT A FOUR-BILLION-ENTRY VEC.
+pub const MAX_CPU: u32 = 8191;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CpuListError {
+    Empty,                             // NO CPU AT ALL
+    EmptyToken,                        // "2,,3" OR A TRAILING COMMA
+    BadToken(String),                  // NOT N OR A-B
+    Reversed { start: u32, end: u32 }, // "5-3"
+    TooLarge(u64),                     // PAST MAX_CPU
+}
+
+impl CpuListError {
+    pub fn message(&self) -> String {
+        match self {
+            Self::Empty => "NO CPUS GIVEN".to_string(),
+            Self::EmptyToken => "EMPTY ENTRY (DOUBLED OR TRAILING COMMA)".to_string(),
+            Self::BadToken(t) => format!("{:?} IS NOT A CPU OR A RANGE (E.G. 2,3 OR 8-11)", t),
+            Self::Reversed { start, end } => {
+                format!(
+                    "RANGE {}-{} RUNS BACKWARDS (WRITE {}-{})",
+                    start, end, end, start
+                )
+            }
+            Self::TooLarge(cpu) => format!("CPU {} PAST THE LAST POSSIBLE CPU {}", cpu, MAX_CPU),
+        }
+    }
+}
+
+fn parse_cpu(token: &str, whole: &str) -> Result<u32, CpuListError> {
+    // str::parse TAKES A LEADING '+': NOT A CPU NUMBER
+    if token.is_empty() || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(CpuListError::BadToken(whole.to_string()));
+    }
+    let cpu: u64 = token
+        .parse()
+        .map_err(|_| CpuListError::BadToken(whole.to_string()))?;
+    if cpu > MAX_CPU as u64 {
+        return Err(CpuListError::TooLarge(cpu));
+    }
+    Ok(cpu as u32)
+}
+
+// "2,3" / "8-11" / "0,4-5": SORTED, DEDUPED. WHITESPACE AROUND ENTRIES AND
+// THE DASH IS ALLOWED (A SYSFS FILE ENDS IN A NEWLINE).
+pub fn parse(s: &str) -> Result<Vec<u32>, CpuListError> {
+    if s.trim().is_empty() {
+        return Err(CpuListError::Empty);
+    }
+    let mut cpus = Vec::new();
+    for token in s.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(CpuListError::EmptyToken);
+        }
+        match token.split_once('-') {
+            Some((a, b)) => {
+                let start = parse_cpu(a.trim(), token)?;
+                let end = parse_cpu(b.trim(), token)?;
+                if start > end {
+                    return Err(CpuListError::Reversed { start, end });
+                }
+                cpus.extend(start..=end);
+            }
+            None => cpus.push(parse_cpu(token, token)?),
+        }
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+// INVERSE OF parse: SORTED RUNS, "0-3,8,10-11"; "" FOR NO CPUS
+pub fn format(cpus: &[u32]) -> String {
+    let mut sorted = cpus.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < sorted.len() {
+        let start = sorted[i];
+        let mut end = start;
+        while i + 1 < sorted.len() && sorted[i + 1] == end + 1 {
+            i += 1;
+            end = sorted[i];
+        }
+        parts.push(if start == end {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, end)
+        });
+        i += 1;
+    }
+    parts.join(",")
+}
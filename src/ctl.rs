@@ -0,0 +1,317 @@
+// PANDEMONIUM CONTROL PROTOCOL
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (SOCKET SERVER, `pandemonium ctl`) AND LIB CRATE (tests)
+//
+// LINE-BASED: ONE REQUEST LINE PER CONNECTION, ONE RESPONSE.
+// RESPONSES START WITH "OK" OR "ERR <REASON>". BODIES ARE key=value LINES.
+//   get knobs            ALL TUNING KNOBS AS CURRENTLY IN THE BPF MAP
+//   get regime           REGIME, PIN/PAUSE STATE, TIGHTEN STATE, HELD KNOBS,
+//                        SLICE GUARDRAILS (FIXED AT LOAD), KERNEL RESTARTS
+//                        SO FAR + THE LATEST ONE'S REASON
+//   set <knob> <value>   WRITE ONE KNOB AND HOLD IT ACROSS REGIME CHANGES
+//   unset <knob>         RELEASE A HELD KNOB BACK TO THE ADAPTIVE LAYER
+//   pin regime <name>    FORCE LIGHT/MIXED/HEAVY, REGIME DETECTION OFF
+//   unpin                REGIME DETECTION BACK ON
+//   profile <name>       POWER PROFILE: power-saver PINS HEAVY, performance
+//                        PINS MIXED, balanced UNPINS
+//   boost <pid>          THE PID'S comm JOINS THE COMPOSITORS (LAT_CRITICAL)
+//   gamemode start [pid] GAME STATE ON (NESTED PER GAME, gamemode.rs);
+//   gamemode end [pid]   THE LAST END RESTORES WHAT THE FIRST START REPLACED
+//   pause                FREEZE ADAPTIVE KNOB WRITES (TELEMETRY CONTINUES)
+//   resume               UNFREEZE, RE-SYNC TIGHTEN STATE FROM THE MAP
+//   stats                CUMULATIVE BPF COUNTERS
+//   procdb export        WRITE LIVE PROFILES TO THE PROCDB EXCHANGE FILE
+//   procdb import [replace]
+//                        MERGE (OR REPLACE WITH) THE EXCHANGE FILE'S PROFILES
+// THE SOCKET (control.rs) AND D-BUS (dbus.rs) BOTH PARSE HERE AND FORWARD
+// THROUGH dispatch(): ONE COMMAND PATH INTO THE MONITOR LOOP.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use crate::gamemode::NO_PID;
+use crate::restart::RestartSummary;
+use crate::tuning::{Regime, SliceBounds, TuningKnobs, AFFINITY_STRONG, KNOB_NAMES};
+
+pub const SOCKET_PATH: &str = "/run/pandemonium.sock";
+pub const SOCKET_MODE: u32 = 0o660; // ROOT + OWNING GROUP ONLY
+pub const MAX_LINE: usize = 256;
+
+// 1S. ANYTHING LONGER STARVES INTERACTIVE TASKS FOR A FULL FRAME OR MORE.
+const MAX_KNOB_NS: u64 = 1_000_000_000;
+
+// MONITOR TICKS ONCE PER SECOND: ALLOW A LATE TICK BEFORE GIVING UP
+const REPLY_TIMEOUT: Duration = Duration::from_secs(3);
+
+// power-profiles-daemon NAMES. A PROFILE IS A REGIME PIN: HEAVY'S WIDE
+// SLICES PREEMPT AND WAKE LEAST, MIXED HAS THE TIGHTEST INTERACTIVE CONTROL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    PowerSaver,
+    Balanced,
+    Performance,
+}
+
+impl Profile {
+    pub const ALL: [Self; 3] = [Self::PowerSaver, Self::Balanced, Self::Performance];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PowerSaver => "power-saver",
+            Self::Balanced => "balanced",
+            Self::Performance => "performance",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == s)
+    }
+
+    // None: UNPIN, REGIME DETECTION DECIDES
+    pub fn regime(self) -> Option<Regime> {
+        match self {
+            Self::PowerSaver => Some(Regime::Heavy),
+            Self::Balanced => None,
+            Self::Performance => Some(Regime::Mixed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CtlCommand {
+    GetKnobs,
+    GetRegime,
+    SetKnob { name: &'static str, value: u64 },
+    UnsetKnob { name: &'static str },
+    Pin(Regime),
+    Unpin,
+    Boost { pid: u32 },
+    GameStart { pid: u32 }, // gamemode::NO_PID WHEN NONE WAS GIVEN
+    GameEnd { pid: u32 },
+    Pause,
+    Resume,
+    Stats,
+    ProcdbExport,
+    ProcdbImport { replace: bool },
+    ProcdbPreempted, // comm=PREEMPTIONS/S FOR `procdb list`
+}
+
+fn knob_name(name: &str) -> Result<&'static str, String> {
+    KNOB_NAMES
+        .iter()
+        .find(|n| **n == name)
+        .copied()
+        .ok_or_else(|| format!("UNKNOWN KNOB {} (KNOBS: {})", name, KNOB_NAMES.join(" ")))
+}
+
+fn parse_pid(s: &str) -> Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(pid) if pid > 0 => Ok(pid),
+        _ => Err(format!("BAD PID {}", s)),
+    }
+}
+
+pub fn parse_command(line: &str) -> Result<CtlCommand, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["get", "knobs"] => Ok(CtlCommand::GetKnobs),
+        ["get", "regime"] => Ok(CtlCommand::GetRegime),
+        ["set", name, value] => {
+            let name = knob_name(name)?;
+            let value: u64 = value
+                .parse()
+                .map_err(|_| format!("BAD VALUE {} (EXPECTED UNSIGNED INTEGER)", value))?;
+            validate_knob(name, value)?;
+            Ok(CtlCommand::SetKnob { name, value })
+        }
+        ["unset", name] => Ok(CtlCommand::UnsetKnob {
+            name: knob_name(name)?,
+        }),
+        ["pin", "regime", r] => Regime::from_label(r)
+            .map(CtlCommand::Pin)
+            .ok_or_else(|| format!("UNKNOWN REGIME {} (LIGHT, MIXED, HEAVY)", r)),
+        ["unpin"] => Ok(CtlCommand::Unpin),
+        // A PROFILE IS A PIN OR AN UNPIN: THE LOOP NEVER SEES IT
+        ["profile", p] => Profile::from_name(p)
+            .map(|p| p.regime().map_or(CtlCommand::Unpin, CtlCommand::Pin))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Profile::ALL.iter().map(|p| p.name()).collect();
+                format!("UNKNOWN PROFILE {} ({})", p, names.join(", "))
+            }),
+        ["boost", pid] => Ok(CtlCommand::Boost {
+            pid: parse_pid(pid)?,
+        }),
+        ["gamemode", "start"] => Ok(CtlCommand::GameStart { pid: NO_PID }),
+        ["gamemode", "start", pid] => Ok(CtlCommand::GameStart {
+            pid: parse_pid(pid)?,
+        }),
+        ["gamemode", "end"] => Ok(CtlCommand::GameEnd { pid: NO_PID }),
+        ["gamemode", "end", pid] => Ok(CtlCommand::GameEnd {
+            pid: parse_pid(pid)?,
+        }),
+        ["pause"] => Ok(CtlCommand::Pause),
+        ["resume"] => Ok(CtlCommand::Resume),
+        ["stats"] => Ok(CtlCommand::Stats),
+        ["procdb", "export"] => Ok(CtlCommand::ProcdbExport),
+        ["procdb", "import"] => Ok(CtlCommand::ProcdbImport { replace: false }),
+        ["procdb", "import", "replace"] => Ok(CtlCommand::ProcdbImport { replace: true }),
+        ["procdb", "preempted"] => Ok(CtlCommand::ProcdbPreempted),
+        [] => Err("EMPTY COMMAND".to_string()),
+        _ => Err(format!("UNKNOWN COMMAND: {}", line.trim())),
+    }
+}
+
+// REJECT VALUES THE BPF SIDE WOULD MISBEHAVE ON
+pub fn validate_knob(name: &str, value: u64) -> Result<(), String> {
+    if name.ends_with("_ns") && (value == 0 || value > MAX_KNOB_NS) {
+        return Err(format!("{} OUT OF RANGE (1..={})", name, MAX_KNOB_NS));
+    }
+    if name == "affinity_mode" && value > AFFINITY_STRONG {
+        return Err(format!(
+            "affinity_mode OUT OF RANGE (0..={})",
+            AFFINITY_STRONG
+        ));
+    }
+    Ok(())
+}
+
+// A PARSED COMMAND ON ITS WAY TO THE MONITOR LOOP, WITH WHERE TO ANSWER
+pub struct CtlRequest {
+    pub cmd: CtlCommand,
+    pub reply: Sender<String>,
+}
+
+// WHAT THE MONITOR LOOP REPORTS BACK EACH TICK (D-BUS PROPERTIES)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiveStatus {
+    pub regime: Regime,
+    pub p99_us: u64,
+}
+
+// ONE COMMAND THROUGH THE MONITOR LOOP; ITS RESPONSE, OR AN ERR LINE WHEN
+// THE LOOP IS GONE OR DOES NOT ANSWER WITHIN A LATE TICK
+pub fn dispatch(tx: &Sender<CtlRequest>, cmd: CtlCommand) -> String {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    if tx
+        .send(CtlRequest {
+            cmd,
+            reply: reply_tx,
+        })
+        .is_err()
+    {
+        return err("SCHEDULER IS SHUTTING DOWN");
+    }
+    reply_rx
+        .recv_timeout(REPLY_TIMEOUT)
+        .unwrap_or_else(|_| err("NO REPLY FROM MONITOR LOOP"))
+}
+
+// KNOBS SET OVER THE SOCKET. RE-APPLIED AFTER EVERY ADAPTIVE WRITE SO
+// REGIME CHANGES AND TIGHTEN/RELAX DON'T SILENTLY UNDO THEM.
+#[derive(Default, Debug)]
+pub struct KnobOverrides {
+    entries: Vec<(&'static str, u64)>,
+}
+
+impl KnobOverrides {
+    pub fn set(&mut self, name: &'static str, value: u64) {
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some(e) => e.1 = value,
+            None => self.entries.push((name, value)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.entries.iter().find(|(n, _)| *n == name).map(|e| e.1)
+    }
+
+    pub fn unset(&mut self, name: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|(n, _)| *n != name);
+        self.entries.len() != before
+    }
+
+    pub fn apply(&self, knobs: &mut TuningKnobs) {
+        for &(name, value) in &self.entries {
+            knobs.set(name, value);
+        }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.entries.iter().map(|(n, _)| *n).collect()
+    }
+}
+
+// RESPONSES
+
+pub fn err(msg: &str) -> String {
+    format!("ERR {}\n", msg)
+}
+
+pub fn ok_kv(pairs: &[(&str, String)]) -> String {
+    let mut out = String::from("OK\n");
+    for (k, v) in pairs {
+        out.push_str(&format!("{}={}\n", k, v));
+    }
+    out
+}
+
+pub fn format_knobs(knobs: &TuningKnobs) -> String {
+    let pairs: Vec<(&str, String)> = KNOB_NAMES
+        .iter()
+        .map(|n| (*n, knobs.get(n).unwrap_or(0).to_string()))
+        .collect();
+    ok_kv(&pairs)
+}
+
+pub fn format_regime(
+    regime: Regime,
+    pinned: bool,
+    paused: bool,
+    tightened: bool,
+    overrides: &KnobOverrides,
+    bounds: &SliceBounds,
+    restarts: &RestartSummary,
+) -> String {
+    let held = overrides.names();
+    ok_kv(&[
+        ("regime", regime.label().to_string()),
+        ("pinned", pinned.to_string()),
+        ("paused", paused.to_string()),
+        ("tightened", tightened.to_string()),
+        (
+            "held",
+            if held.is_empty() {
+                "-".to_string()
+            } else {
+                held.join(",")
+            },
+        ),
+        ("slice_min_ns", bounds.min_ns.to_string()),
+        ("slice_max_ns", bounds.max_ns.to_string()),
+        ("restarts", restarts.count.to_string()),
+        (
+            "last_restart",
+            restarts
+                .last_reason
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+    ])
+}
+
+// CLIENT SIDE: "OK\nk=v\n..." -> MAP, "ERR ..." -> Err(REASON)
+// BINARY: ONLY dbus.rs; THE CLI GOES THROUGH pandemonium::client
+#[cfg_attr(not(feature = "dbus"), allow(dead_code))]
+pub fn parse_response(resp: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut lines = resp.lines();
+    match lines.next() {
+        Some("OK") => Ok(lines
+            .filter_map(|l| l.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()),
+        Some(l) if l.starts_with("ERR") => Err(l.trim_start_matches("ERR").trim().to_string()),
+        _ => Err("MALFORMED RESPONSE".to_string()),
+    }
+}
@@ -0,0 +1,128 @@
+// PANDEMONIUM D-BUS INTERFACE (--dbus, CARGO FEATURE dbus)
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (--dbus) AND LIB CRATE (tests)
+//
+// org.pandemonium.Scheduler1 AT /org/pandemonium/Scheduler1. EVERY METHOD
+// IS A CONTROL-SOCKET LINE, PARSED BY ctl::parse_command AND SENT DOWN THE
+// SAME CHANNEL AS THE SOCKET, SO THE TWO FRONT ENDS CANNOT DRIFT:
+//   SetProfile(s)   profile <name>      power-saver / balanced / performance
+//   PinRegime(s)    pin regime <name>   light / mixed / heavy
+//   Boost(u)        boost <pid>
+// A REJECTED LINE IS org.freedesktop.DBus.Error.InvalidArgs, AN ERR FROM THE
+// MONITOR LOOP IS org.freedesktop.DBus.Error.Failed.
+//
+// PROPERTIES Regime (s) AND P99Us (t) FOLLOW THE MONITOR LOOP: ONE
+// PropertiesChanged PER TICK THAT MOVED EITHER. EMPTY / 0 UNTIL THE FIRST TICK.
+//
+// THE SYSTEM BUS NEEDS dbus/org.pandemonium.Scheduler1.conf IN
+// /etc/dbus-1/system.d/ BEFORE ROOT MAY OWN THE NAME.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::fdo;
+use zbus::zvariant::Value;
+
+use crate::ctl::{self, CtlRequest, LiveStatus};
+
+pub const BUS_NAME: &str = "org.pandemonium.Scheduler1";
+pub const OBJECT_PATH: &str = "/org/pandemonium/Scheduler1";
+pub const INTERFACE: &str = "org.pandemonium.Scheduler1";
+
+struct Scheduler1 {
+    tx: Sender<CtlRequest>,
+    status: Option<LiveStatus>,
+}
+
+impl Scheduler1 {
+    fn run(&self, line: &str) -> fdo::Result<()> {
+        let cmd = ctl::parse_command(line).map_err(fdo::Error::InvalidArgs)?;
+        ctl::parse_response(&ctl::dispatch(&self.tx, cmd))
+            .map(|_| ())
+            .map_err(fdo::Error::Failed)
+    }
+}
+
+#[zbus::interface(name = "org.pandemonium.Scheduler1")]
+impl Scheduler1 {
+    fn set_profile(&self, profile: &str) -> fdo::Result<()> {
+        self.run(&format!("profile {}", profile))
+    }
+
+    fn pin_regime(&self, regime: &str) -> fdo::Result<()> {
+        self.run(&format!("pin regime {}", regime))
+    }
+
+    fn boost(&self, pid: u32) -> fdo::Result<()> {
+        self.run(&format!("boost {}", pid))
+    }
+
+    #[zbus(property)]
+    fn regime(&self) -> String {
+        self.status
+            .map_or(String::new(), |s| s.regime.label().to_string())
+    }
+
+    #[zbus(property)]
+    fn p99_us(&self) -> u64 {
+        self.status.map_or(0, |s| s.p99_us)
+    }
+}
+
+// CLAIM THE NAME, EXPORT THE OBJECT, START THE PUBLISHER THREAD. THE
+// RETURNED SENDER IS FOR THE MONITOR LOOP; DROPPING IT ENDS THE THREAD,
+// WHICH CLOSES THE CONNECTION AND RELEASES THE NAME.
+pub fn serve(session: bool, ctl_tx: Sender<CtlRequest>) -> zbus::Result<Sender<LiveStatus>> {
+    let iface = Scheduler1 {
+        tx: ctl_tx,
+        status: None,
+    };
+    let builder = if session {
+        Builder::session()?
+    } else {
+        Builder::system()?
+    };
+    let conn = builder
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, iface)?
+        .build()?;
+
+    let (live_tx, live_rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("pandemonium-dbus".into())
+        .spawn(move || publish(conn, live_rx))
+        .map_err(|e| zbus::Error::Failure(format!("SPAWN D-BUS THREAD: {}", e)))?;
+    Ok(live_tx)
+}
+
+fn publish(conn: Connection, rx: Receiver<LiveStatus>) {
+    let Ok(iface) = conn.object_server().interface::<_, Scheduler1>(OBJECT_PATH) else {
+        return;
+    };
+    let mut last: Option<LiveStatus> = None;
+    for status in rx {
+        if last == Some(status) {
+            continue;
+        }
+        iface.get_mut().status = Some(status);
+        let mut changed: HashMap<&str, Value> = HashMap::new();
+        if last.map(|l| l.regime) != Some(status.regime) {
+            changed.insert("Regime", Value::from(status.regime.label()));
+        }
+        if last.map(|l| l.p99_us) != Some(status.p99_us) {
+            changed.insert("P99Us", Value::from(status.p99_us));
+        }
+        last = Some(status);
+        // A FAILED EMIT (BUS GONE) IS NOT WORTH STOPPING THE SCHEDULER FOR
+        let invalidated: Vec<&str> = Vec::new();
+        let _ = conn.emit_signal(
+            None::<&str>,
+            OBJECT_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            &(INTERFACE, changed, invalidated),
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// PANDEMONIUM DIAGNOSTIC SNAPSHOT HELPERS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (SIGUSR1 DUMP IN adaptive.rs) AND LIB CRATE (tests)
+// ALSO FORMATS THE --verbose SECTIONS OF THE MONITOR LOOP.
+//
+// `kill -USR1 $(pidof pandemonium)` WRITES /tmp/pandemonium/diag-<ts>.txt:
+// KNOBS, REGIME HISTORY, CUMULATIVE STATS, P99 + APPLIED LAG HISTOGRAMS, PROCDB TOP 20,
+// EVENT LOG STATE, LAST 120 TELEMETRY SNAPSHOTS. EVERYTHING A BUG REPORT
+// NEEDS IN ONE FILE.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::procdb::TaskProfile;
+use crate::tuning::{
+    compute_p99_from_histogram, lag_bucket_label, LagHist, Regime, HIST_BUCKETS, HIST_EDGES_NS,
+};
+
+pub const DIAG_DIR: &str = "/tmp/pandemonium";
+pub const DIAG_TELEMETRY_TAIL: usize = 120;
+pub const DIAG_TOP_PROFILES: usize = 20;
+pub const REGIME_HISTORY_CAP: usize = 64;
+pub const VERBOSE_STATS_TICKS: u64 = 10;
+
+const TIER_NAMES: [&str; 3] = ["BATCH", "INTERACTIVE", "LAT_CRITICAL"];
+
+// REGIME HISTORY: BOUNDED, OLDEST DROPPED
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegimeChange {
+    pub tick: u64,
+    pub from: Regime,
+    pub to: Regime,
+    pub idle_pct: u64,
+    pub cause: &'static str, // "detect", "pin" OR "gamemode"
+}
+
+#[derive(Default)]
+pub struct RegimeHistory {
+    entries: VecDeque<RegimeChange>,
+}
+
+impl RegimeHistory {
+    pub fn push(&mut self, change: RegimeChange) {
+        if self.entries.len() == REGIME_HISTORY_CAP {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(change);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|c| {
+                format!(
+                    "tick {:<8} {:<5} -> {:<5} idle={}% ({})",
+                    c.tick,
+                    c.from.label(),
+                    c.to.label(),
+                    c.idle_pct,
+                    c.cause
+                )
+            })
+            .collect()
+    }
+}
+
+// CUMULATIVE WAKEUP HISTOGRAM, ONE TIER: "<= EDGE: COUNT" + P99
+pub fn format_hist(tier: usize, counts: &[u64; HIST_BUCKETS]) -> String {
+    let total: u64 = counts.iter().sum();
+    let mut out = format!(
+        "{} (samples={} p99={}us)\n",
+        TIER_NAMES.get(tier).copied().unwrap_or("?"),
+        total,
+        compute_p99_from_histogram(counts) / 1000
+    );
+    for (i, &c) in counts.iter().enumerate() {
+        let edge = if HIST_EDGES_NS[i] == u64::MAX {
+            "+inf".to_string()
+        } else {
+            format!("{}us", HIST_EDGES_NS[i] / 1000)
+        };
+        out.push_str(&format!("  <= {:<8} {}\n", edge, c));
+    }
+    out
+}
+
+// CUMULATIVE APPLIED LAG: "x<SCALE>: COUNT" + FLOOR CLAMP SHARE
+pub fn format_lag_hist(h: &LagHist) -> String {
+    let mut out = format!(
+        "deadlines={} floor_clamps={} ({}%)\n",
+        h.total(),
+        h.clamped,
+        h.clamp_pct()
+    );
+    for (b, &c) in h.counts.iter().enumerate() {
+        out.push_str(&format!("  x{:<7} {}\n", lag_bucket_label(b), c));
+    }
+    out
+}
+
+// MOST-OBSERVED PROFILES FIRST, comm AS TIE-BREAK (DETERMINISTIC OUTPUT)
+pub fn top_profiles(
+    profiles: &HashMap<[u8; 16], TaskProfile>,
+    n: usize,
+) -> Vec<(&[u8; 16], &TaskProfile)> {
+    let mut v: Vec<_> = profiles.iter().collect();
+    v.sort_by(|a, b| b.1.observations.cmp(&a.1.observations).then(a.0.cmp(b.0)));
+    v.truncate(n);
+    v
+}
+
+pub fn comm_str(comm: &[u8; 16]) -> String {
+    let end = comm.iter().position(|&b| b == 0).unwrap_or(comm.len());
+    String::from_utf8_lossy(&comm[..end]).into_owned()
+}
+
+pub fn format_profile(comm: &[u8; 16], p: &TaskProfile) -> String {
+    format!(
+        "{:<16} tier={:<12} obs={:<6} conf={:.2} avg_rt={}us dev={}us wake={} csw={} flap={}%{}",
+        comm_str(comm),
+        TIER_NAMES
+            .get(p.dominant_tier() as usize)
+            .copied()
+            .unwrap_or("?"),
+        p.observations,
+        p.behavioral_confidence(),
+        p.avg_runtime_ns / 1000,
+        p.runtime_dev_ns / 1000,
+        p.wakeup_freq,
+        p.csw_rate,
+        p.flap.rate_pct(),
+        if p.flap.withheld { " WITHHELD" } else { "" }
+    )
+}
+
+// LOCAL TIME, SORTABLE: YYYYMMDD-HHMMSS
+pub fn stamp() -> String {
+    unsafe {
+        let mut t: libc::time_t = 0;
+        libc::time(&mut t);
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&t, &mut tm);
+        format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday,
+            tm.tm_hour,
+            tm.tm_min,
+            tm.tm_sec
+        )
+    }
+}
+
+pub fn diag_path(stamp: &str) -> String {
+    format!("{}/diag-{}.txt", DIAG_DIR, stamp)
+}
+
+// VERBOSE SECTIONS
+
+// EVERY 10TH TICK (TICK 9, 19, ...): NOTHING AT STARTUP, FIRST DUMP AFTER 10S
+pub fn should_dump_stats(tick: u64) -> bool {
+    (tick + 1) % VERBOSE_STATS_TICKS == 0
+}
+
+// "[TAG] a=1 b=2 ..." ON ONE GREPPABLE LINE
+pub fn format_kv_line(tag: &str, fields: &[(&str, u64)]) -> String {
+    let mut out = format!("[{}]", tag);
+    for (name, v) in fields {
+        out.push_str(&format!(" {}={}", name, v));
+    }
+    out
+}
+
+// --verbose EVERY 10 TICKS: THE KNOB NEXT TO WHAT task_deadline() APPLIED
+// OVER THOSE 10 TICKS. p50 FAR BELOW THE KNOB = QUEUE PRESSURE IS HALVING
+// IT; clamp=0% = THE FLOOR NEVER BITES AND THE KNOB IS A NO-OP.
+pub fn format_lag_summary(knob: u64, h: &LagHist) -> String {
+    format!(
+        "[LAG] knob={} deadlines={} p50=x{} p90=x{} max=x{} clamp={}%",
+        knob,
+        h.total(),
+        h.percentile(50),
+        h.percentile(90),
+        h.max(),
+        h.clamp_pct()
+    )
+}
+
+// PREDICTIONS NEW OR RE-TIERED SINCE THE LAST FLUSH, AS "comm=TIER".
+// FLUSHES ALSO REWRITE A PROFILE WHOSE RUNTIME MOVED; ONLY TIERS ARE NEWS.
+// written IS A DELTA, SO last ACCUMULATES; THE CALLER FORGETS EVICTED
+// COMMS SO A RETURN IS REPORTED AGAIN.
+pub fn prediction_changes(
+    last: &mut HashMap<[u8; 16], u8>,
+    written: &[([u8; 16], u8)],
+) -> Vec<String> {
+    let mut changes: Vec<String> = written
+        .iter()
+        .filter(|(comm, tier)| last.get(comm) != Some(tier))
+        .map(|(comm, tier)| {
+            format!(
+                "{}={}",
+                comm_str(comm),
+                TIER_NAMES.get(*tier as usize).copied().unwrap_or("?")
+            )
+        })
+        .collect();
+    changes.sort();
+    last.extend(written.iter().copied());
+    changes
+}
+
+// PREDICTIONS PULLED FOR FLAPPING, AS "comm=FLAP%". FORGOTTEN IN last SO
+// prediction_changes() REPORTS THEIR RETURN.
+pub fn withheld_predictions(
+    last: &mut HashMap<[u8; 16], u8>,
+    withheld: &[([u8; 16], u32)],
+) -> Vec<String> {
+    let mut labels: Vec<String> = withheld
+        .iter()
+        .map(|(comm, rate)| format!("{}={}%", comm_str(comm), rate))
+        .collect();
+    labels.sort();
+    for (comm, _) in withheld {
+        last.remove(comm);
+    }
+    labels
+}
@@ -2,12 +2,121 @@
 // RECORDS STATS SNAPSHOTS DURING SCHEDULER EXECUTION
 // PRE-ALLOCATED RING BUFFER. NO HEAP ALLOCATION DURING MONITORING.
 // WRAPS AROUND AT CAPACITY -- OLDEST ENTRIES OVERWRITTEN.
+// THE WAKEUP PATH BUDGET BESIDE IT COVERS THE WHOLE RUN AND NEVER WRAPS.
+// GOVERNOR CHANGES ARE MARKED BESIDE THE RING: RARE, SO A SMALL LIST
+// RESERVED UP FRONT; PAST MAX_GOVERNOR_MARKS ONLY THE COUNT GROWS.
+// WAKEUP HISTOGRAM DELTAS FOLD INTO PER-MINUTE ROWS FOR THE HEATMAP: THE
+// LAST HEAT_MINUTES OF THEM, ALSO RESERVED UP FRONT.
+
+use std::collections::VecDeque;
+
+use crate::governor::GovernorChange;
+use crate::latplot::{cdf_lines, heatmap_lines, pool, CDF_WIDTH};
+use crate::stats::StatsDelta;
+use crate::tuning::{compute_p99_from_histogram, pooled_p99_ns, HIST_BUCKETS, HIST_EDGES_NS};
 
 pub const MAX_SNAPSHOTS: usize = 8192;
+pub const MAX_GOVERNOR_MARKS: usize = 64;
+pub const HEAT_MINUTES: usize = 60;
+const MINUTE_NS: u64 = 60_000_000_000;
 
-#[derive(Clone, Copy)]
-pub struct Snapshot {
-    pub ts_ns: u64,
+// WAKEUP PATHS, AS TAGGED BY BPF dispatch_path: IDLE FAST PATH, HARD-KICKED
+// ENQUEUE, AND THE REST (SOFT KICK). BPF KEEPS ONLY A SUM AND COUNT PER PATH,
+// SO EACH PATH'S HISTOGRAM HOLDS 1-SECOND AVERAGES WEIGHTED BY THAT SECOND'S
+// WAKEUPS: p99 IS THE AVERAGE 99% OF THE PATH'S WAKEUPS SAW OR BETTER, worst
+// THE WORST 1-SECOND AVERAGE. NEITHER IS A SINGLE WAKEUP.
+pub const WAKE_PATHS: [&str; 3] = ["idle", "hard_kick", "soft_kick"];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct PathTotals {
+    pub count: u64,
+    pub sum_ns: u64,
+    pub worst_ns: u64,
+    pub hist: [u64; HIST_BUCKETS],
+}
+
+impl PathTotals {
+    fn add(&mut self, count: u64, sum_ns: u64) {
+        if count == 0 {
+            return;
+        }
+        let avg_ns = sum_ns / count;
+        let bucket = HIST_EDGES_NS
+            .iter()
+            .position(|&e| avg_ns <= e)
+            .unwrap_or(HIST_BUCKETS - 1);
+        self.count += count;
+        self.sum_ns += sum_ns;
+        self.worst_ns = self.worst_ns.max(avg_ns);
+        self.hist[bucket] += count;
+    }
+
+    pub fn avg_us(&self) -> u64 {
+        self.sum_ns.checked_div(self.count).unwrap_or(0) / 1000
+    }
+
+    pub fn p99_us(&self) -> u64 {
+        compute_p99_from_histogram(&self.hist) / 1000
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct WakePathBudget {
+    pub paths: [PathTotals; 3], // WAKE_PATHS ORDER
+}
+
+impl WakePathBudget {
+    // ONE TICK. A RESET TICK CARRIES NOTHING.
+    pub fn record(&mut self, d: &StatsDelta) {
+        if d.reset {
+            return;
+        }
+        let soft_cnt = d
+            .wake_lat_samples
+            .saturating_sub(d.wake_lat_idle_cnt + d.wake_lat_kick_cnt);
+        let soft_sum = d
+            .wake_lat_sum
+            .saturating_sub(d.wake_lat_idle_sum + d.wake_lat_kick_sum);
+        self.paths[0].add(d.wake_lat_idle_cnt, d.wake_lat_idle_sum);
+        self.paths[1].add(d.wake_lat_kick_cnt, d.wake_lat_kick_sum);
+        self.paths[2].add(soft_cnt, soft_sum);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.paths.iter().map(|p| p.count).sum()
+    }
+
+    // HEADER + ONE ROW PER PATH; EMPTY BEFORE THE FIRST WAKEUP
+    pub fn table(&self) -> Vec<String> {
+        let total = self.total();
+        if total == 0 {
+            return Vec::new();
+        }
+        let mut out = vec![format!(
+            "  {:<12} {:>12} {:>7} {:>8} {:>8} {:>9}",
+            "WAKE PATH", "COUNT", "SHARE", "AVG_US", "P99_US", "WORST_US"
+        )];
+        for (name, p) in WAKE_PATHS.iter().zip(&self.paths) {
+            let share = p.count as f64 / total as f64 * 100.0;
+            out.push(format!(
+                "  {:<12} {:>12} {:>6.1}% {:>8} {:>8} {:>9}",
+                name,
+                p.count,
+                share,
+                p.avg_us(),
+                p.p99_us(),
+                p.worst_ns / 1000
+            ));
+        }
+        out
+    }
+}
+
+// ONE TICK OF TELEMETRY. BOTH MONITOR LOOPS BUILD IT FROM THE SAME
+// StatsDelta, SO EVERY FIELD MEANS ONE THING IN EVERY RING, DUMP AND
+// BLACK BOX RECORD.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TickCounters {
     pub dispatches: u64,
     pub idle_hits: u64,
     pub shared: u64,
@@ -20,70 +129,358 @@ pub struct Snapshot {
     pub lat_kick_us: u64,
 }
 
+impl TickCounters {
+    pub fn from_delta(d: &StatsDelta) -> Self {
+        Self {
+            dispatches: d.dispatches,
+            idle_hits: d.idle_hits,
+            shared: d.shared,
+            preempt: d.preempt,
+            keep_run: d.keep_running,
+            wake_avg_us: d.wake_avg_us(),
+            hard_kicks: d.hard_kicks,
+            soft_kicks: d.soft_kicks,
+            lat_idle_us: d.lat_idle_us(),
+            lat_kick_us: d.lat_kick_us(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Snapshot {
+    pub ts_ns: u64,
+    pub tick: TickCounters,
+}
+
+// dump() AND tail_table() COLUMNS AFTER THE TIME COLUMN, TickCounters ORDER
+const COLUMNS: [(&str, usize); 10] = [
+    ("DISPATCH/S", 12),
+    ("IDLE/S", 10),
+    ("SHARED/S", 10),
+    ("PREEMPT", 10),
+    ("KEEP_RUN", 10),
+    ("WAKE_US", 10),
+    ("KICK_H", 8),
+    ("KICK_S", 8),
+    ("LAT_IDLE", 10),
+    ("LAT_KICK", 10),
+];
+
+fn header_row(time: &str) -> String {
+    let mut out = format!("{:<10}", time);
+    for (name, width) in COLUMNS {
+        out.push_str(&format!(" {:<width$}", name, width = width));
+    }
+    out.trim_end().to_string()
+}
+
+fn snapshot_row(time_s: f64, s: &Snapshot) -> String {
+    let t = &s.tick;
+    let values = [
+        t.dispatches,
+        t.idle_hits,
+        t.shared,
+        t.preempt,
+        t.keep_run,
+        t.wake_avg_us,
+        t.hard_kicks,
+        t.soft_kicks,
+        t.lat_idle_us,
+        t.lat_kick_us,
+    ];
+    let mut out = format!("{:<10.1}", time_s);
+    for ((_, width), v) in COLUMNS.iter().zip(values) {
+        out.push_str(&format!(" {:<width$}", v, width = *width));
+    }
+    out.trim_end().to_string()
+}
+
+// WHOLE-RUN TOTALS OVER THE SNAPSHOTS STILL IN THE RING
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct RunSummary {
+    pub totals: TickCounters, // SUMS; wake_avg_us AND lat_* ARE NOT SUMMED
+    pub peak_dispatches: u64,
+    pub elapsed_s: f64,
+    pub samples: usize,
+}
+
+impl RunSummary {
+    pub fn avg_dispatch_per_s(&self) -> Option<f64> {
+        (self.elapsed_s > 0.0).then(|| self.totals.dispatches as f64 / self.elapsed_s)
+    }
+
+    pub fn idle_hit_pct(&self) -> f64 {
+        if self.totals.dispatches == 0 {
+            return 0.0;
+        }
+        self.totals.idle_hits as f64 / self.totals.dispatches as f64 * 100.0
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let t = &self.totals;
+        let mut out = vec![
+            "PANDEMONIUM SUMMARY".to_string(),
+            format!("  TOTAL DISPATCHES:  {}", t.dispatches),
+            format!("  TOTAL IDLE HITS:   {}", t.idle_hits),
+            format!("  TOTAL SHARED:      {}", t.shared),
+            format!("  TOTAL PREEMPT:     {}", t.preempt),
+            format!("  TOTAL KEEP_RUN:    {}", t.keep_run),
+            format!("  PEAK DISPATCH/S:   {}", self.peak_dispatches),
+        ];
+        if let Some(avg) = self.avg_dispatch_per_s() {
+            out.push(format!("  AVG DISPATCH/S:    {:.0}", avg));
+            out.push(format!("  IDLE HIT RATE:     {:.1}%", self.idle_hit_pct()));
+        }
+        out.push(format!("  ELAPSED:           {:.1}s", self.elapsed_s));
+        out.push(format!("  SAMPLES:           {}", self.samples));
+        out
+    }
+}
+
+// THE ADAPTIVE LOOP'S OWN WHOLE-RUN COUNTS, HANDED OVER WHEN IT EXITS
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct AdaptiveTally {
+    pub regime_ticks: [u64; 3], // LIGHT, MIXED, HEAVY
+    pub tighten_events: u64,
+    pub relax_steps: u64,
+}
+
+impl AdaptiveTally {
+    // SHARE OF TICKS IN EACH REGIME, LIGHT/MIXED/HEAVY
+    pub fn regime_pct(&self) -> [f64; 3] {
+        let total: u64 = self.regime_ticks.iter().sum();
+        self.regime_ticks.map(|t| {
+            if total == 0 {
+                0.0
+            } else {
+                t as f64 / total as f64 * 100.0
+            }
+        })
+    }
+}
+
+// WHOLE-RUN NUMBERS THE RING CANNOT HOLD, READ ONCE AT SHUTDOWN: BPF'S
+// CUMULATIVE COUNTERS AND HISTOGRAMS NEVER WRAP
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RunTotals {
+    pub hard_kicks: u64,
+    pub soft_kicks: u64,
+    pub wake_hist: [[u64; HIST_BUCKETS]; 3], // PER TIER, POOLED FOR THE P99
+    pub lag_deadlines: u64,                  // LagHist::total
+    pub lag_clamps: u64,                     // LagHist::clamped: THE VTIME FLOOR MOVED THE TASK
+    pub lightweight: bool,                   // NO WAKEUP SAMPLES: NO P99
+}
+
+impl RunTotals {
+    pub fn lines(&self, adaptive: Option<&AdaptiveTally>) -> Vec<String> {
+        let sampled = self.wake_hist.iter().flatten().any(|&c| c > 0);
+        let p99 = if self.lightweight || !sampled {
+            "-".to_string()
+        } else {
+            format!("{}us", pooled_p99_ns(&self.wake_hist) / 1000)
+        };
+        let mut out = vec![
+            format!("  WAKE P99 (RUN):    {}", p99),
+            format!(
+                "  TOTAL KICKS:       H={} S={}",
+                self.hard_kicks, self.soft_kicks
+            ),
+            format!(
+                "  LAG CLAMPS:        {} ({}% OF DEADLINES)",
+                self.lag_clamps,
+                (self.lag_clamps * 100)
+                    .checked_div(self.lag_deadlines)
+                    .unwrap_or(0)
+            ),
+        ];
+        if let Some(a) = adaptive {
+            let [l, m, h] = a.regime_pct();
+            out.push(format!(
+                "  REFLEX:            TIGHTEN={} RELAX_STEPS={}",
+                a.tighten_events, a.relax_steps
+            ));
+            out.push(format!(
+                "  REGIME TIME:       LIGHT={:.1}% MIXED={:.1}% HEAVY={:.1}%",
+                l, m, h
+            ));
+        }
+        out
+    }
+
+    // THE RUN'S WAKEUP LATENCY CDF, ALL TIERS POOLED. EMPTY WHERE THE P99
+    // LINE SAYS "-"
+    pub fn cdf_lines(&self) -> Vec<String> {
+        let pooled = pool(&self.wake_hist);
+        if self.lightweight || pooled.iter().all(|&c| c == 0) {
+            return Vec::new();
+        }
+        let mut out = vec!["  WAKE LATENCY CDF (RUN, ALL TIERS):".to_string()];
+        out.extend(cdf_lines(&pooled, CDF_WIDTH));
+        out
+    }
+}
+
+// PER-MINUTE WAKEUP HISTOGRAMS, TIERS POOLED. MINUTES COUNT FROM THE FIRST
+// TICK; PAST HEAT_MINUTES THE OLDEST MINUTE DROPS.
+#[derive(Clone, Debug)]
+pub struct WakeHeat {
+    start_ns: Option<u64>,
+    rows: VecDeque<(u64, [u64; HIST_BUCKETS])>,
+    dropped: u64,
+}
+
+impl Default for WakeHeat {
+    fn default() -> Self {
+        Self {
+            start_ns: None,
+            rows: VecDeque::with_capacity(HEAT_MINUTES),
+            dropped: 0,
+        }
+    }
+}
+
+impl WakeHeat {
+    // ONE TICK'S HISTOGRAM DELTA, ADDED TO ITS MINUTE'S ROW
+    pub fn add(&mut self, ts_ns: u64, delta: &[[u64; HIST_BUCKETS]; 3]) {
+        let start = *self.start_ns.get_or_insert(ts_ns);
+        let minute = ts_ns.saturating_sub(start) / MINUTE_NS;
+        let pooled = pool(delta);
+        match self.rows.back_mut() {
+            Some((m, counts)) if *m == minute => {
+                for (c, d) in counts.iter_mut().zip(pooled) {
+                    *c += d;
+                }
+            }
+            _ => {
+                if self.rows.len() == HEAT_MINUTES {
+                    self.rows.pop_front();
+                    self.dropped += 1;
+                }
+                self.rows.push_back((minute, pooled));
+            }
+        }
+    }
+
+    pub fn rows(&self) -> Vec<(u64, [u64; HIST_BUCKETS])> {
+        self.rows.iter().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = heatmap_lines(&self.rows());
+        if self.dropped > 0 {
+            out.push(format!(
+                "  ({} EARLIER MINUTES DROPPED, LAST {} KEPT)",
+                self.dropped, HEAT_MINUTES
+            ));
+        }
+        out
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernorMark {
+    pub ts_ns: u64,
+    pub from: String,
+    pub to: String,
+}
+
 pub struct EventLog {
     snapshots: Vec<Snapshot>,
     head: usize,
     len: usize,
+    pub paths: WakePathBudget,
+    // FED BY monitor_loop; EMPTY IN BPF-ONLY MODE (NO PER-TICK HISTOGRAMS)
+    pub heat: WakeHeat,
+    // SET BY monitor_loop ON EXIT; None IN BPF-ONLY MODE
+    pub adaptive: Option<AdaptiveTally>,
+    governor_marks: Vec<GovernorMark>,
+    governor_changes: u64,
 }
 
 impl EventLog {
     pub fn new() -> Self {
         Self {
-            snapshots: vec![
-                Snapshot {
-                    ts_ns: 0,
-                    dispatches: 0,
-                    idle_hits: 0,
-                    shared: 0,
-                    preempt: 0,
-                    keep_run: 0,
-                    wake_avg_us: 0,
-                    hard_kicks: 0,
-                    soft_kicks: 0,
-                    lat_idle_us: 0,
-                    lat_kick_us: 0
-                };
-                MAX_SNAPSHOTS
-            ],
+            snapshots: vec![Snapshot::default(); MAX_SNAPSHOTS],
             head: 0,
             len: 0,
+            paths: WakePathBudget::default(),
+            heat: WakeHeat::default(),
+            adaptive: None,
+            governor_marks: Vec::with_capacity(MAX_GOVERNOR_MARKS),
+            governor_changes: 0,
         }
     }
 
     // RECORD ONE STATS SNAPSHOT. CALLED ONCE PER SECOND FROM THE MONITOR LOOP.
     // OVERWRITES OLDEST ENTRY WHEN FULL.
-    pub fn snapshot(
-        &mut self,
-        dispatches: u64,
-        idle_hits: u64,
-        shared: u64,
-        preempt: u64,
-        keep_run: u64,
-        wake_avg_us: u64,
-        hard_kicks: u64,
-        soft_kicks: u64,
-        lat_idle_us: u64,
-        lat_kick_us: u64,
-    ) {
-        self.snapshots[self.head] = Snapshot {
-            ts_ns: now_ns(),
-            dispatches,
-            idle_hits,
-            shared,
-            preempt,
-            keep_run,
-            wake_avg_us,
-            hard_kicks,
-            soft_kicks,
-            lat_idle_us,
-            lat_kick_us,
-        };
+    pub fn snapshot(&mut self, tick: TickCounters) {
+        self.record(now_ns(), tick);
+    }
+
+    // snapshot() AT A GIVEN MONOTONIC TIME
+    // ONE TICK'S WAKEUP HISTOGRAM DELTA, FOR THE HEATMAP
+    pub fn wake_hist(&mut self, delta: &[[u64; HIST_BUCKETS]; 3]) {
+        self.heat.add(now_ns(), delta);
+    }
+
+    pub fn record(&mut self, ts_ns: u64, tick: TickCounters) {
+        self.snapshots[self.head] = Snapshot { ts_ns, tick };
         self.head = (self.head + 1) % MAX_SNAPSHOTS;
         if self.len < MAX_SNAPSHOTS {
             self.len += 1;
         }
     }
 
+    // TAG THE RUN WITH A GOVERNOR CHANGE SEEN THIS TICK
+    pub fn governor_change(&mut self, change: &GovernorChange) {
+        self.mark_governor(now_ns(), change);
+    }
+
+    // governor_change() AT A GIVEN MONOTONIC TIME
+    pub fn mark_governor(&mut self, ts_ns: u64, change: &GovernorChange) {
+        self.governor_changes += 1;
+        if self.governor_marks.len() < MAX_GOVERNOR_MARKS {
+            self.governor_marks.push(GovernorMark {
+                ts_ns,
+                from: change.from.clone(),
+                to: change.to.clone(),
+            });
+        }
+    }
+
+    pub fn governor_marks(&self) -> &[GovernorMark] {
+        &self.governor_marks
+    }
+
+    pub fn governor_changes(&self) -> u64 {
+        self.governor_changes
+    }
+
+    // ONE LINE PER KEPT MARK, TIME RELATIVE TO base_ts (THE DUMP'S TIME 0)
+    pub fn governor_lines(&self, base_ts: u64) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .governor_marks
+            .iter()
+            .map(|m| {
+                format!(
+                    "GOVERNOR @ {:.1}s: {} -> {}",
+                    m.ts_ns.saturating_sub(base_ts) as f64 / 1_000_000_000.0,
+                    m.from,
+                    m.to
+                )
+            })
+            .collect();
+        let dropped = self.governor_changes - self.governor_marks.len() as u64;
+        if dropped > 0 {
+            out.push(format!("(+{} LATER GOVERNOR CHANGES NOT KEPT)", dropped));
+        }
+        out
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -106,61 +503,60 @@ impl EventLog {
         (0..self.len).map(move |i| &self.snapshots[(start + i) % MAX_SNAPSHOTS])
     }
 
+    // MOST RECENT SNAPSHOT
+    pub fn latest(&self) -> Option<&Snapshot> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(&self.snapshots[(self.head + MAX_SNAPSHOTS - 1) % MAX_SNAPSHOTS])
+    }
+
+    // TRUE ONCE THE OLDEST SNAPSHOTS HAVE BEEN OVERWRITTEN
+    pub fn wrapped(&self) -> bool {
+        self.len == MAX_SNAPSHOTS
+    }
+
+    // LAST n SNAPSHOTS AS A TABLE, TIME RELATIVE TO THE NEWEST (DIAG DUMPS)
+    pub fn tail_table(&self, n: usize) -> String {
+        let skip = self.len.saturating_sub(n);
+        let tail: Vec<&Snapshot> = self.iter_chronological().skip(skip).collect();
+        let newest_ts = tail.last().map(|s| s.ts_ns).unwrap_or(0);
+
+        let mut out = header_row("AGE_S");
+        out.push('\n');
+        for s in tail {
+            let age_s = (newest_ts - s.ts_ns) as f64 / 1_000_000_000.0;
+            out.push_str(&snapshot_row(age_s, s));
+            out.push('\n');
+        }
+        out
+    }
+
     // DUMP THE TIME SERIES AFTER EXECUTION
     pub fn dump(&self) {
-        if self.len == 0 {
+        let Some(base_ts) = self.iter_chronological().next().map(|s| s.ts_ns) else {
             return;
-        }
+        };
 
-        let mut iter = self.iter_chronological();
-        let first = iter.next().unwrap();
-        let base_ts = first.ts_ns;
-
-        println!(
-            "\n{:<10} {:<12} {:<10} {:<10} {:<10} {:<10} {:<10} {:<8} {:<8} {:<10} {:<10}",
-            "TIME_S",
-            "DISPATCH/S",
-            "IDLE/S",
-            "SHARED/S",
-            "PREEMPT",
-            "KEEP_RUN",
-            "WAKE_US",
-            "KICK_H",
-            "KICK_S",
-            "LAT_IDLE",
-            "LAT_KICK"
-        );
-        println!(
-            "{:<10.1} {:<12} {:<10} {:<10} {:<10} {:<10} {:<10} {:<8} {:<8} {:<10} {:<10}",
-            0.0,
-            first.dispatches,
-            first.idle_hits,
-            first.shared,
-            first.preempt,
-            first.keep_run,
-            first.wake_avg_us,
-            first.hard_kicks,
-            first.soft_kicks,
-            first.lat_idle_us,
-            first.lat_kick_us
-        );
-
-        for s in iter {
+        println!("\n{}", header_row("TIME_S"));
+        for s in self.iter_chronological() {
             let elapsed_s = (s.ts_ns - base_ts) as f64 / 1_000_000_000.0;
-            println!(
-                "{:<10.1} {:<12} {:<10} {:<10} {:<10} {:<10} {:<10} {:<8} {:<8} {:<10} {:<10}",
-                elapsed_s,
-                s.dispatches,
-                s.idle_hits,
-                s.shared,
-                s.preempt,
-                s.keep_run,
-                s.wake_avg_us,
-                s.hard_kicks,
-                s.soft_kicks,
-                s.lat_idle_us,
-                s.lat_kick_us
-            );
+            println!("{}", snapshot_row(elapsed_s, s));
+        }
+
+        let governor = self.governor_lines(base_ts);
+        if !governor.is_empty() {
+            println!();
+            for line in governor {
+                println!("{}", line);
+            }
+        }
+
+        if !self.heat.is_empty() {
+            println!("\nWAKE LATENCY HEATMAP (PER MINUTE, ALL TIERS):");
+            for line in self.heat.lines() {
+                println!("{}", line);
+            }
         }
 
         if self.len == MAX_SNAPSHOTS {
@@ -172,43 +568,68 @@ impl EventLog {
         println!("TOTAL SNAPSHOTS: {}", self.len);
     }
 
-    // SUMMARY STATISTICS
-    pub fn summary(&self) {
+    // None UNDER TWO SNAPSHOTS: NO INTERVAL TO AVERAGE OVER
+    pub fn run_summary(&self) -> Option<RunSummary> {
         if self.len < 2 {
-            return;
+            return None;
         }
+        let mut sum = RunSummary {
+            samples: self.len,
+            ..RunSummary::default()
+        };
+        let (mut first_ts, mut last_ts) = (None, 0);
+        for s in self.iter_chronological() {
+            let (t, total) = (&s.tick, &mut sum.totals);
+            total.dispatches += t.dispatches;
+            total.idle_hits += t.idle_hits;
+            total.shared += t.shared;
+            total.preempt += t.preempt;
+            total.keep_run += t.keep_run;
+            total.hard_kicks += t.hard_kicks;
+            total.soft_kicks += t.soft_kicks;
+            sum.peak_dispatches = sum.peak_dispatches.max(t.dispatches);
+            first_ts.get_or_insert(s.ts_ns);
+            last_ts = s.ts_ns;
+        }
+        sum.elapsed_s = last_ts.saturating_sub(first_ts.unwrap_or(0)) as f64 / 1_000_000_000.0;
+        Some(sum)
+    }
 
-        let snapshots: Vec<&Snapshot> = self.iter_chronological().collect();
-
-        let total_d: u64 = snapshots.iter().map(|s| s.dispatches).sum();
-        let total_idle: u64 = snapshots.iter().map(|s| s.idle_hits).sum();
-        let total_shared: u64 = snapshots.iter().map(|s| s.shared).sum();
-        let total_preempt: u64 = snapshots.iter().map(|s| s.preempt).sum();
-        let total_keep: u64 = snapshots.iter().map(|s| s.keep_run).sum();
-
-        let peak_d = snapshots.iter().map(|s| s.dispatches).max().unwrap_or(0);
-
-        let elapsed_ns = snapshots.last().unwrap().ts_ns - snapshots.first().unwrap().ts_ns;
-        let elapsed_s = elapsed_ns as f64 / 1_000_000_000.0;
+    // THE SHUTDOWN BLOCK: RING TOTALS, WHOLE-RUN BPF TOTALS, THE ADAPTIVE
+    // TALLY, GOVERNOR CHANGES, THE WAKE PATH TABLE. EMPTY UNDER TWO
+    // SNAPSHOTS.
+    pub fn summary_lines(&self, totals: &RunTotals) -> Vec<String> {
+        let Some(sum) = self.run_summary() else {
+            return Vec::new();
+        };
+        let mut out = sum.lines();
+        out.extend(totals.lines(self.adaptive.as_ref()));
+        if let Some(first) = self.governor_marks.first() {
+            out.push(format!(
+                "  GOVERNOR CHANGES:  {} (STARTED AS {})",
+                self.governor_changes, first.from
+            ));
+        }
+        out.extend(self.paths.table());
+        out
+    }
 
-        println!("\nPANDEMONIUM SUMMARY");
-        println!("  TOTAL DISPATCHES:  {}", total_d);
-        println!("  TOTAL IDLE HITS:   {}", total_idle);
-        println!("  TOTAL SHARED:      {}", total_shared);
-        println!("  TOTAL PREEMPT:     {}", total_preempt);
-        println!("  TOTAL KEEP_RUN:    {}", total_keep);
-        println!("  PEAK DISPATCH/S:   {}", peak_d);
-        if elapsed_s > 0.0 {
-            println!("  AVG DISPATCH/S:    {:.0}", total_d as f64 / elapsed_s);
-            let idle_pct = if total_d > 0 {
-                total_idle as f64 / total_d as f64 * 100.0
-            } else {
-                0.0
-            };
-            println!("  IDLE HIT RATE:     {:.1}%", idle_pct);
+    pub fn summary(&self, totals: &RunTotals) {
+        let lines = self.summary_lines(totals);
+        if lines.is_empty() {
+            return;
+        }
+        println!();
+        for line in lines {
+            println!("{}", line);
+        }
+        let cdf = totals.cdf_lines();
+        if !cdf.is_empty() {
+            println!();
+            for line in cdf {
+                println!("{}", line);
+            }
         }
-        println!("  ELAPSED:           {:.1}s", elapsed_s);
-        println!("  SAMPLES:           {}", self.len);
     }
 }
 
@@ -0,0 +1,250 @@
+// PANDEMONIUM EXIT CODES
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// WHAT THE SCHEDULER PROCESS EXITS WITH, FOR SERVICE MANAGERS AND SCRIPTS:
+//   0  CLEAN: CTRL+C / SIGTERM, --duration, OR THE KERNEL DETACHED US
+//      WITHOUT AN ERROR (SYSRQ-S, UNREGISTER)
+//   1  BAD COMMAND LINE, OR ANY FAILURE NOT LISTED BELOW
+//   2  THE KERNEL EJECTED THE BPF SCHEDULER (scx_bpf_error, A RUNNABLE-TASK
+//      STALL) AND DID NOT ASK FOR A RESTART
+//   3  BPF LOAD OR ATTACH FAILED
+//   4  PREFLIGHT FAILED: A HARD preflight.rs FAIL (KFUNCS, BTF, BPF
+//      FEATURES), ANOTHER SCHEDULER ATTACHED, A SECOND INSTANCE, A CPU SET
+//      THAT DOES NOT MATCH THE MACHINE
+//
+// A KERNEL-REQUESTED RESTART (SCX_ECODE_ACT_RESTART) IS NOT AN ENDING:
+// ONLY THE ATTACH THE RUN FINISHES ON DECIDES THE CODE.
+//
+// THE BPF SIDE RECORDS WHY IT EXITED IN A struct user_exit_info (uei IN
+// .data). ExitInfo DECODES IT ONCE; main.rs LOGS, RESTARTS AND PICKS THE
+// EXIT CODE FROM THAT.
+
+use std::fmt;
+
+// include/linux/sched/ext.h: enum scx_exit_kind
+pub const SCX_EXIT_NONE: i32 = 0;
+pub const SCX_EXIT_DONE: i32 = 1;
+pub const SCX_EXIT_UNREG: i32 = 64;
+pub const SCX_EXIT_UNREG_BPF: i32 = 65;
+pub const SCX_EXIT_UNREG_KERN: i32 = 66;
+pub const SCX_EXIT_SYSRQ: i32 = 67;
+pub const SCX_EXIT_ERROR: i32 = 1024;
+pub const SCX_EXIT_ERROR_BPF: i32 = 1025;
+pub const SCX_EXIT_ERROR_STALL: i32 = 1026;
+
+// enum scx_exit_code: BITS 0-31 USER, 32-47 REASON, 48-63 ACTION
+pub const SCX_ECODE_USR_MASK: u64 = 0xffff_ffff;
+pub const SCX_ECODE_RSN_HOTPLUG: u64 = 1 << 32;
+pub const SCX_ECODE_ACT_RESTART: u64 = 1 << 48;
+
+// user_exit_info_common.h: char reason[UEI_REASON_LEN], msg[UEI_MSG_LEN]
+pub const UEI_REASON_LEN: usize = 128;
+pub const UEI_MSG_LEN: usize = 1024;
+
+// A uei char ARRAY: UP TO THE FIRST NUL (NONE: THE WHOLE ARRAY), INVALID
+// UTF-8 REPLACED RATHER THAN DROPPING THE TEXT
+pub fn uei_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end])
+        .trim_end()
+        .to_string()
+}
+
+// THE WHOLE uei, DECODED
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExitInfo {
+    pub kind: i32,
+    pub exit_code: i64,
+    pub reason: String,
+    pub msg: String,
+    pub wants_restart: bool,
+}
+
+impl ExitInfo {
+    pub fn from_uei(kind: i32, exit_code: i64, reason: &[u8], msg: &[u8]) -> Self {
+        Self {
+            kind,
+            exit_code,
+            reason: uei_str(reason),
+            msg: uei_str(msg),
+            wants_restart: (exit_code as u64 & SCX_ECODE_ACT_RESTART) != 0,
+        }
+    }
+
+    pub fn outcome(&self) -> BpfExit {
+        BpfExit::from_uei(self.kind, self.exit_code)
+    }
+
+    pub fn status(&self) -> ExitStatus {
+        self.outcome().status()
+    }
+
+    // "code=0x1000100000003 (RESTART, HOTPLUG, USER 3)"
+    pub fn describe_code(&self) -> String {
+        let code = self.exit_code as u64;
+        let mut parts = Vec::new();
+        if self.wants_restart {
+            parts.push("RESTART".to_string());
+        }
+        if code & SCX_ECODE_RSN_HOTPLUG != 0 {
+            parts.push("HOTPLUG".to_string());
+        }
+        if code & SCX_ECODE_USR_MASK != 0 {
+            parts.push(format!("USER {}", code & SCX_ECODE_USR_MASK));
+        }
+        if parts.is_empty() {
+            format!("code={}", self.exit_code)
+        } else {
+            format!("code={:#x} ({})", code, parts.join(", "))
+        }
+    }
+
+    // NOTHING WHILE STILL ATTACHED (OR DETACHED BY US)
+    pub fn log_lines(&self) -> Vec<String> {
+        if self.kind == SCX_EXIT_NONE {
+            return Vec::new();
+        }
+        let mut lines = vec![format!(
+            "BPF exit: kind={} ({}) {}",
+            self.kind,
+            kind_name(self.kind),
+            self.describe_code()
+        )];
+        if !self.reason.is_empty() {
+            lines.push(format!("BPF exit reason: {}", self.reason));
+        }
+        if !self.msg.is_empty() {
+            lines.push(format!("BPF exit msg: {}", self.msg));
+        }
+        lines
+    }
+}
+
+// uei.kind + uei.exit_code, DECODED ONCE
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BpfExit {
+    Attached,                         // NO EXIT RECORDED: WE DETACHED IT
+    Restart { kind: i32, code: i64 }, // SCX_ECODE_ACT_RESTART SET
+    Unregistered { kind: i32 },       // DONE / UNREG* / SYSRQ: NOT A FAILURE
+    Error { kind: i32, code: i64 },   // ERROR / ERROR_BPF / ERROR_STALL
+}
+
+impl BpfExit {
+    pub fn from_uei(kind: i32, exit_code: i64) -> Self {
+        if (exit_code as u64 & SCX_ECODE_ACT_RESTART) != 0 {
+            return Self::Restart {
+                kind,
+                code: exit_code,
+            };
+        }
+        match kind {
+            SCX_EXIT_NONE => Self::Attached,
+            k if k >= SCX_EXIT_ERROR => Self::Error {
+                kind,
+                code: exit_code,
+            },
+            _ => Self::Unregistered { kind },
+        }
+    }
+
+    pub fn should_restart(&self) -> bool {
+        matches!(self, Self::Restart { .. })
+    }
+
+    // THE RUN ENDED ON THIS EXIT: WHAT THE PROCESS REPORTS
+    pub fn status(&self) -> ExitStatus {
+        match self {
+            Self::Error { .. } => ExitStatus::BpfError,
+            _ => ExitStatus::Clean,
+        }
+    }
+}
+
+// "ERROR_STALL" FOR THE BPF EXIT LOG LINE
+pub fn kind_name(kind: i32) -> &'static str {
+    match kind {
+        SCX_EXIT_NONE => "NONE",
+        SCX_EXIT_DONE => "DONE",
+        SCX_EXIT_UNREG => "UNREG",
+        SCX_EXIT_UNREG_BPF => "UNREG_BPF",
+        SCX_EXIT_UNREG_KERN => "UNREG_KERN",
+        SCX_EXIT_SYSRQ => "SYSRQ",
+        SCX_EXIT_ERROR => "ERROR",
+        SCX_EXIT_ERROR_BPF => "ERROR_BPF",
+        SCX_EXIT_ERROR_STALL => "ERROR_STALL",
+        _ => "UNKNOWN",
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitStatus {
+    Clean,
+    Failed,
+    BpfError,
+    AttachFailed,
+    PreflightFailed,
+}
+
+impl ExitStatus {
+    pub const ALL: [ExitStatus; 5] = [
+        Self::Clean,
+        Self::Failed,
+        Self::BpfError,
+        Self::AttachFailed,
+        Self::PreflightFailed,
+    ];
+
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Clean => 0,
+            Self::Failed => 1,
+            Self::BpfError => 2,
+            Self::AttachFailed => 3,
+            Self::PreflightFailed => 4,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.code() as i32 == code)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clean => "clean exit",
+            Self::Failed => "error",
+            Self::BpfError => "BPF scheduler error exit",
+            Self::AttachFailed => "BPF load/attach failed",
+            Self::PreflightFailed => "preflight failed",
+        }
+    }
+
+    // TAG AN ERROR SO main() EXITS WITH THIS CODE
+    pub fn wrap(self, error: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(StatusError {
+            status: self,
+            error,
+        })
+    }
+}
+
+// AN ERROR THAT KNOWS ITS EXIT CODE. DISPLAYS AS THE ERROR IT WRAPS.
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: ExitStatus,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.error)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+// UNTAGGED ERRORS ARE 1. CONTEXT ADDED AFTER wrap() KEEPS THE TAG.
+pub fn status_of(error: &anyhow::Error) -> ExitStatus {
+    error
+        .downcast_ref::<StatusError>()
+        .map_or(ExitStatus::Failed, |e| e.status)
+}
@@ -0,0 +1,158 @@
+// PANDEMONIUM GAMEMODE SESSIONS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (MONITOR LOOP) AND LIB CRATE (tests)
+//
+// `pandemonium gamemode start [PID]` / `end [PID]` ARRIVE AS CONTROL-SOCKET
+// COMMANDS. WHILE ANY GAME IS ACTIVE:
+//   REGIME              PINNED TO MIXED
+//   slice_ns            HELD AT THE REFLEX'S FIRST TIGHTEN STEP (3/4 OF THE
+//   preempt_thresh_ns   MIXED BASELINE, CLAMPED TO THE SLICE BOUNDS)
+//   EACH GAME'S TGID    IN THE BPF game_tgid_map: EVERY THREAD LAT_CRITICAL
+// THE FIRST START SAVES THE PIN AND HELD KNOBS IT REPLACES; THE LAST END
+// PUTS THEM BACK. OVERLAPPING GAMES NEST: ENDING ONE OF TWO KEEPS THE GAME
+// STATE FOR THE OTHER. A GAME THAT EXITS WITHOUT AN END IS REAPED AS IF IT
+// HAD SENT ONE.
+//
+// NO PID (NO_PID): A SESSION WITHOUT A TGID BOOST. GameMode'S [custom]
+// SCRIPTS ARE NOT TOLD WHICH GAME STARTED, SO THE GENERATED SNIPPET USES IT.
+
+use crate::ctl::KnobOverrides;
+use crate::tuning::{Regime, SliceBounds, TuningKnobs};
+
+pub const GAME_REGIME: Regime = Regime::Mixed;
+pub const NO_PID: u32 = 0;
+pub const GAME_KNOBS: [&str; 2] = ["slice_ns", "preempt_thresh_ns"];
+
+// WHAT THE FIRST START REPLACED
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriorState {
+    pub pinned: Option<Regime>,
+    pub held: Vec<(&'static str, Option<u64>)>, // GAME_KNOBS, None: NOT HELD
+}
+
+impl PriorState {
+    pub fn capture(pinned: Option<Regime>, held: &KnobOverrides) -> Self {
+        Self {
+            pinned,
+            held: GAME_KNOBS.iter().map(|&n| (n, held.get(n))).collect(),
+        }
+    }
+
+    // PUT THE HELD GAME KNOBS BACK; THE CALLER RESTORES self.pinned
+    pub fn restore(&self, held: &mut KnobOverrides) {
+        for &(name, value) in &self.held {
+            match value {
+                Some(v) => held.set(name, v),
+                None => {
+                    held.unset(name);
+                }
+            }
+        }
+    }
+}
+
+// mixed: THE SCALED MIXED PROFILE. PREEMPT NEVER EXCEEDS THE SLICE.
+pub fn game_knobs(mixed: &TuningKnobs, bounds: &SliceBounds) -> [(&'static str, u64); 2] {
+    let slice_ns = bounds.clamp(mixed.slice_ns * 3 / 4);
+    [
+        ("slice_ns", slice_ns),
+        ("preempt_thresh_ns", mixed.preempt_thresh_ns.min(slice_ns)),
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStart {
+    First,                       // APPLY THE GAME STATE
+    Nested { active: usize },    // ALREADY APPLIED: JUST THE BOOST
+    Duplicate { active: usize }, // SAME TGID TWICE: NO-OP
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEnd {
+    Last(PriorState),            // RESTORE THIS
+    Remaining { active: usize }, // OTHER GAMES KEEP THE GAME STATE
+    NotActive,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameSessions {
+    tgids: Vec<u32>, // START ORDER
+    prior: Option<PriorState>,
+}
+
+impl GameSessions {
+    pub fn tgids(&self) -> &[u32] {
+        &self.tgids
+    }
+
+    pub fn contains(&self, tgid: u32) -> bool {
+        self.tgids.contains(&tgid)
+    }
+
+    // prior IS KEPT ONLY BY THE FIRST START
+    pub fn start(&mut self, tgid: u32, prior: PriorState) -> GameStart {
+        if self.contains(tgid) {
+            return GameStart::Duplicate {
+                active: self.tgids.len(),
+            };
+        }
+        self.tgids.push(tgid);
+        if self.prior.is_none() {
+            self.prior = Some(prior);
+            return GameStart::First;
+        }
+        GameStart::Nested {
+            active: self.tgids.len(),
+        }
+    }
+
+    pub fn end(&mut self, tgid: u32) -> GameEnd {
+        let before = self.tgids.len();
+        self.tgids.retain(|&t| t != tgid);
+        if self.tgids.len() == before {
+            return GameEnd::NotActive;
+        }
+        if self.tgids.is_empty() {
+            if let Some(prior) = self.prior.take() {
+                return GameEnd::Last(prior);
+            }
+        }
+        GameEnd::Remaining {
+            active: self.tgids.len(),
+        }
+    }
+
+    // TRACKED GAMES WHOSE PROCESS IS GONE (NO_PID NEVER EXPIRES)
+    pub fn exited(&self, alive: impl Fn(u32) -> bool) -> Vec<u32> {
+        self.tgids
+            .iter()
+            .copied()
+            .filter(|&t| t != NO_PID && !alive(t))
+            .collect()
+    }
+}
+
+// /proc/<pid>/status "Tgid:" LINE: A THREAD ID NAMES ITS WHOLE PROCESS
+pub fn parse_tgid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("Tgid:"))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+// `pandemonium install --gamemode`: A [custom] SECTION FOR gamemode.ini.
+// GAMEMODED RUNS IT AS THE DESKTOP USER, WHO NEEDS THE SOCKET (--pin-group).
+pub fn gamemode_snippet(exe: &str, socket: Option<&str>) -> String {
+    let socket_arg = socket.map_or(String::new(), |s| format!(" --socket {}", s));
+    format!(
+        "; PANDEMONIUM: REGIME PINNED TO {regime} WITH TIGHTENED SLICES WHILE GAMEMODE IS ACTIVE\n\
+         ; APPEND TO ~/.config/gamemode.ini (OR /etc/gamemode.ini). THE SCHEDULER MUST RUN\n\
+         ; WITH --pin-group <A GROUP YOU ARE IN> SO THESE RUN WITHOUT ROOT.\n\
+         ; GAMEMODE DOES NOT PASS THE GAME'S PID: BOOST ITS THREADS SEPARATELY WITH\n\
+         ;   {exe} gamemode{socket_arg} start <PID> / end <PID>\n\
+         [custom]\n\
+         start={exe} gamemode{socket_arg} start\n\
+         end={exe} gamemode{socket_arg} end\n",
+        regime = GAME_REGIME.label(),
+    )
+}
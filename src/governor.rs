@@ -0,0 +1,159 @@
+// PANDEMONIUM CPUFREQ GOVERNOR WATCH
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE BANNER READS THE GOVERNOR ONCE; power-profiles-daemon (OR A USER)
+// CAN FLIP performance <-> powersave MID-RUN, AND WAKEUP LATENCY MOVES
+// WITH IT. BOTH MONITOR LOOPS RE-READ IT ONCE PER TICK: cpu0's GOVERNOR
+// PLUS EVERY cpufreq POLICY, SO A MACHINE WHOSE POLICIES DISAGREE SHOWS
+// AS MIXED INSTEAD OF WHATEVER cpu0 HAPPENS TO RUN. A CHANGE LOGS ONE
+// LINE AND MARKS THE EVENT LOG; THE TELEMETRY LINE CARRIES THE LABEL
+// (gov:) SO A BENCHMARK CAN SEGMENT TICKS BY GOVERNOR.
+//
+// A FEW SMALL sysfs READS PER TICK. NO cpufreq (VMS, SOME CONTAINERS):
+// THE LABEL IS `-` AND NOTHING EVER CHANGES.
+
+use std::path::Path;
+
+pub const CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+pub const NO_GOVERNOR: &str = "-";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GovernorState {
+    pub cpu0: Option<String>,
+    pub policies: Vec<(u32, String)>, // (policyN, GOVERNOR), BY N
+}
+
+impl GovernorState {
+    // RAW sysfs CONTENTS (TRAILING NEWLINES, EMPTY FILES) -> STATE
+    pub fn from_strings(cpu0: Option<&str>, policies: &[(u32, &str)]) -> Self {
+        let clean = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        let mut policies: Vec<(u32, String)> = policies
+            .iter()
+            .filter_map(|&(n, g)| Some((n, clean(g)?)))
+            .collect();
+        policies.sort_by_key(|&(n, _)| n);
+        Self {
+            cpu0: cpu0.and_then(clean),
+            policies,
+        }
+    }
+
+    // DISTINCT GOVERNORS, cpu0's FIRST, THEN POLICY ORDER
+    fn distinct(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = Vec::new();
+        let all = self
+            .cpu0
+            .iter()
+            .map(String::as_str)
+            .chain(self.policies.iter().map(|(_, g)| g.as_str()));
+        for g in all {
+            if !out.contains(&g) {
+                out.push(g);
+            }
+        }
+        out
+    }
+
+    pub fn mixed(&self) -> bool {
+        self.distinct().len() > 1
+    }
+
+    // ONE TOKEN FOR THE TELEMETRY COLUMN: schedutil, performance+powersave
+    // (MIXED, cpu0's FIRST), OR `-` WITHOUT cpufreq
+    pub fn label(&self) -> String {
+        let distinct = self.distinct();
+        if distinct.is_empty() {
+            return NO_GOVERNOR.to_string();
+        }
+        distinct.join("+")
+    }
+
+    // policy0=performance policy4=powersave, ONLY WHEN MIXED
+    pub fn detail(&self) -> Option<String> {
+        if !self.mixed() {
+            return None;
+        }
+        let parts: Vec<String> = self
+            .policies
+            .iter()
+            .map(|(n, g)| format!("policy{}={}", n, g))
+            .collect();
+        Some(parts.join(" "))
+    }
+}
+
+// <root>/cpu0/cpufreq/scaling_governor AND <root>/cpufreq/policy*/scaling_governor
+pub fn read(root: &Path) -> GovernorState {
+    let cpu0 = std::fs::read_to_string(root.join("cpu0/cpufreq/scaling_governor")).ok();
+    let policies: Vec<(u32, String)> = std::fs::read_dir(root.join("cpufreq"))
+        .map(|dir| {
+            dir.flatten()
+                .filter_map(|e| {
+                    let n = e
+                        .file_name()
+                        .to_str()?
+                        .strip_prefix("policy")?
+                        .parse()
+                        .ok()?;
+                    let g = std::fs::read_to_string(e.path().join("scaling_governor")).ok()?;
+                    Some((n, g))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let borrowed: Vec<(u32, &str)> = policies.iter().map(|(n, g)| (*n, g.as_str())).collect();
+    GovernorState::from_strings(cpu0.as_deref(), &borrowed)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernorChange {
+    pub from: String,
+    pub to: String,
+    pub detail: Option<String>, // THE NEW STATE'S PER-POLICY VALUES WHEN MIXED
+}
+
+impl GovernorChange {
+    pub fn message(&self) -> String {
+        let mut out = format!("CPU GOVERNOR CHANGED: {} -> {}", self.from, self.to);
+        if let Some(d) = &self.detail {
+            out.push_str(&format!(" ({})", d));
+        }
+        out.push_str(" -- LATENCY BEFORE AND AFTER THIS POINT IS NOT COMPARABLE");
+        out
+    }
+}
+
+// THE LAST STATE SEEN. ANY DIFFERENCE IS A CHANGE, INCLUDING ONE POLICY
+// OF A MIXED MACHINE MOVING WITHOUT THE LABEL CHANGING.
+pub struct GovernorWatch {
+    current: GovernorState,
+    label: String,
+}
+
+impl GovernorWatch {
+    pub fn new(initial: GovernorState) -> Self {
+        Self {
+            label: initial.label(),
+            current: initial,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn observe(&mut self, now: GovernorState) -> Option<GovernorChange> {
+        if now == self.current {
+            return None;
+        }
+        let to = now.label();
+        let change = GovernorChange {
+            from: std::mem::replace(&mut self.label, to.clone()),
+            to,
+            detail: now.detail(),
+        };
+        self.current = now;
+        Some(change)
+    }
+}
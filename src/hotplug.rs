@@ -0,0 +1,245 @@
+// PANDEMONIUM CPU HOTPLUG TRACKING
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// POWER DAEMONS AND THE SCALE HARNESS OFFLINE CPUS UNDER A RUNNING
+// SCHEDULER. THE MONITOR LOOP POLLS /sys/devices/system/cpu/online EACH
+// TICK; WHEN THE SET CHANGES, EVERY CPU-COUNT-DERIVED VALUE IS
+// RECOMPUTED FROM HERE AND THE KNOBS + TOPOLOGY MAPS ARE PUSHED AGAIN.
+//
+// BPF-SIDE nr_cpu_ids IS RODATA (DSQ IDS ARE DERIVED FROM IT) AND STAYS
+// AT THE POSSIBLE-CPU COUNT; ONLY USERSPACE SCALING FOLLOWS HOTPLUG.
+//
+// A CGROUP CPUSET (CONTAINERS, systemd AllowedCPUs=) NARROWS THE SCALING
+// COUNT THE SAME WAY: THE ONLINE CPUS INSIDE IT (--scaling-cpuset).
+
+use std::path::{Path, PathBuf};
+
+use crate::cpulist;
+
+pub const CPU_ONLINE_PATH: &str = "/sys/devices/system/cpu/online";
+
+// SYSFS TEXT: A LIST THAT DOES NOT PARSE (OR IS EMPTY) IS NO CPUS
+pub fn parse_cpu_list(s: &str) -> Vec<u32> {
+    cpulist::parse(s).unwrap_or_default()
+}
+
+// MATCHES CPU_BITMAP_WORDS IN intf.h (MAX_CPUS / 64)
+pub const CPU_BITMAP_WORDS: usize = 16;
+
+// MATCHES struct cpu_bitmap IN intf.h: BIT cpu % 64 OF WORD cpu / 64.
+// THE IDLE BITMAP (residency.rs) AND --reserved-cpus (reserved.rs).
+pub type CpuBitmap = [u64; CPU_BITMAP_WORDS];
+
+const _: () = assert!(std::mem::size_of::<CpuBitmap>() == 128);
+
+// PAST THE BITMAP: NOT SET
+pub fn bitmap_has(bitmap: &[u64], cpu: u32) -> bool {
+    let word = (cpu / 64) as usize;
+    word < bitmap.len() && bitmap[word] & (1u64 << (cpu % 64)) != 0
+}
+
+// CPUS PAST THE BITMAP ARE DROPPED
+pub fn cpu_bitmap(cpus: &[u32]) -> CpuBitmap {
+    let mut b = [0u64; CPU_BITMAP_WORDS];
+    for &cpu in cpus {
+        if let Some(w) = b.get_mut((cpu / 64) as usize) {
+            *w |= 1u64 << (cpu % 64);
+        }
+    }
+    b
+}
+
+// INVERSE OF cpu_bitmap: THE SET CPUS, ASCENDING
+pub fn bitmap_cpus(bitmap: &[u64]) -> Vec<u32> {
+    (0..(bitmap.len() * 64) as u32)
+        .filter(|&cpu| bitmap_has(bitmap, cpu))
+        .collect()
+}
+
+// None IF THE FILE IS UNREADABLE OR THE LIST IS EMPTY (NEVER SCALE TO ZERO)
+pub fn read_cpu_list(path: &Path) -> Option<Vec<u32>> {
+    let s = std::fs::read_to_string(path).ok()?;
+    let cpus = parse_cpu_list(&s);
+    if cpus.is_empty() {
+        None
+    } else {
+        Some(cpus)
+    }
+}
+
+pub fn read_online() -> Option<Vec<u32>> {
+    read_cpu_list(Path::new(CPU_ONLINE_PATH))
+}
+
+// CGROUP CPUSET OF THE ROOT WORKLOAD SCOPE: IN A CONTAINER THE NAMESPACED
+// ROOT IS THE CONTAINER'S OWN CGROUP. v2 FIRST, THEN THE v1 HIERARCHY.
+pub const CPUSET_PATHS: [&str; 2] = [
+    "/sys/fs/cgroup/cpuset.cpus.effective",
+    "/sys/fs/cgroup/cpuset/cpuset.effective_cpus",
+];
+
+// THE FIRST READABLE, NON-EMPTY DEFAULT CPUSET. A CPUSET COVERING EVERY
+// ONLINE CPU RESTRICTS NOTHING: None, SCALING STAYS ON THE ONLINE SET.
+pub fn detect_cpuset(online: &[u32]) -> Option<(PathBuf, Vec<u32>)> {
+    let (path, cpus) = CPUSET_PATHS.iter().find_map(|p| {
+        let path = PathBuf::from(p);
+        read_cpu_list(&path).map(|cpus| (path, cpus))
+    })?;
+    if online.iter().all(|c| cpus.contains(c)) {
+        None
+    } else {
+        Some((path, cpus))
+    }
+}
+
+// CPUS THE SCALING FORMULAS COUNT: ONLINE CPUS INSIDE THE CPUSET. A CPUSET
+// WITH NO CPU ONLINE (STALE, OR FROM ANOTHER MACHINE) IS IGNORED.
+pub fn scaling_cpus(online: &[u32], cpuset: Option<&[u32]>) -> usize {
+    let inside = match cpuset {
+        Some(set) => online.iter().filter(|c| set.contains(c)).count(),
+        None => online.len(),
+    };
+    if inside == 0 {
+        online.len()
+    } else {
+        inside
+    }
+}
+
+// --nr-cpus ABOVE THE POSSIBLE COUNT: CLAMPED, NOT TRUSTED
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NrCpusWarning {
+    pub requested: u64,
+    pub possible: u64,
+}
+
+impl NrCpusWarning {
+    pub fn message(&self) -> String {
+        format!(
+            "--nr-cpus {} EXCEEDS THE {} POSSIBLE CPUS: CLAMPED TO {}",
+            self.requested, self.possible, self.possible
+        )
+    }
+}
+
+// (EFFECTIVE, WARNING). 0 IS REJECTED BY THE CALLER (clap range(1..)):
+// IT WOULD DIVIDE BY ZERO IN BPF. HERE IT BECOMES 1 ALL THE SAME.
+pub fn sanitize_nr_cpus(requested: u64, possible: u64) -> (u64, Option<NrCpusWarning>) {
+    let possible = possible.max(1);
+    if requested > possible {
+        return (
+            possible,
+            Some(NrCpusWarning {
+                requested,
+                possible,
+            }),
+        );
+    }
+    (requested.max(1), None)
+}
+
+// EVERY USERSPACE VALUE DERIVED FROM THE CPU COUNT
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CpuScaling {
+    pub nr_cpus: u64,
+    pub sojourn_floor_ns: u64,
+    pub sojourn_ceil_ns: u64,
+}
+
+impl CpuScaling {
+    pub fn new(nr_cpus: u64) -> Self {
+        let nr_cpus = nr_cpus.max(1);
+        let sojourn_floor_ns = (nr_cpus * 1_000_000).clamp(2_000_000, 6_000_000);
+        Self {
+            nr_cpus,
+            sojourn_floor_ns,
+            sojourn_ceil_ns: sojourn_floor_ns * 2,
+        }
+    }
+
+    // KEEP THE EWMA'D SOJOURN THRESHOLD, PULLED INTO THE NEW RANGE
+    pub fn clamp_sojourn(&self, thresh_ns: u64) -> u64 {
+        thresh_ns.clamp(self.sojourn_floor_ns, self.sojourn_ceil_ns)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CpuTransition {
+    pub from: usize,
+    pub to: usize,
+    pub offlined: Vec<u32>,
+    pub onlined: Vec<u32>,
+}
+
+impl CpuTransition {
+    pub fn describe(&self) -> String {
+        let list = |v: &[u32]| {
+            v.iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut parts = Vec::new();
+        if !self.offlined.is_empty() {
+            parts.push(format!("OFFLINE [{}]", list(&self.offlined)));
+        }
+        if !self.onlined.is_empty() {
+            parts.push(format!("ONLINE [{}]", list(&self.onlined)));
+        }
+        format!("{} -> {} CPUS ({})", self.from, self.to, parts.join(" "))
+    }
+}
+
+// LAST-SEEN ONLINE SET; update() REPORTS ONLY REAL CHANGES. WITH A
+// CPUSET, SCALING FOLLOWS THE ONLINE CPUS INSIDE IT.
+pub struct HotplugWatch {
+    online: Vec<u32>,
+    cpuset: Option<Vec<u32>>,
+}
+
+impl HotplugWatch {
+    pub fn new(online: Vec<u32>) -> Self {
+        Self {
+            online,
+            cpuset: None,
+        }
+    }
+
+    pub fn with_cpuset(mut self, cpuset: Option<Vec<u32>>) -> Self {
+        self.cpuset = cpuset;
+        self
+    }
+
+    pub fn online(&self) -> &[u32] {
+        &self.online
+    }
+
+    pub fn scaling_cpus(&self) -> u64 {
+        scaling_cpus(&self.online, self.cpuset.as_deref()) as u64
+    }
+
+    pub fn update(&mut self, online: Vec<u32>) -> Option<CpuTransition> {
+        if online == self.online || online.is_empty() {
+            return None;
+        }
+        let offlined = self
+            .online
+            .iter()
+            .filter(|c| !online.contains(c))
+            .copied()
+            .collect();
+        let onlined = online
+            .iter()
+            .filter(|c| !self.online.contains(c))
+            .copied()
+            .collect();
+        let t = CpuTransition {
+            from: self.online.len(),
+            to: online.len(),
+            offlined,
+            onlined,
+        };
+        self.online = online;
+        Some(t)
+    }
+}
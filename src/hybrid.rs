@@ -0,0 +1,170 @@
+// PANDEMONIUM HYBRID CORE AWARENESS (P-CORE/E-CORE, big.LITTLE)
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// TREATING EVERY CPU AS EQUAL LETS THE IDLE PICK LAND LAT_CRITICAL TASKS ON
+// E-CORES. AT STARTUP, READ EACH CPU'S CAPACITY (cpu_capacity, OR
+// cpuinfo_max_freq WHERE THE KERNEL DOESN'T EXPORT IT), SPLIT INTO FAST AND
+// SLOW SETS, AND HAND BPF THE FAST LIST. select_cpu() TRIES AN IDLE FAST
+// CPU FIRST FOR LAT_CRITICAL WAKEUPS.
+//
+// THE MONITOR REPORTS IDLE % (/proc/stat) AND WAKE LATENCY (PER-CPU
+// stats_map: running() RECORDS ON THE CPU THE TASK LANDED ON) PER CLASS.
+
+use crate::stats::{pct, PandemoniumStats};
+
+pub const PROC_STAT_PATH: &str = "/proc/stat";
+
+// BPF SCANS THIS MANY fast_cpus SLOTS PER LAT_CRITICAL WAKEUP (main.bpf.c)
+pub const MAX_FAST_SCAN: usize = 64;
+
+pub fn capacity_path(cpu: usize) -> String {
+    format!("/sys/devices/system/cpu/cpu{}/cpu_capacity", cpu)
+}
+
+pub fn max_freq_path(cpu: usize) -> String {
+    format!(
+        "/sys/devices/system/cpu/cpu{}/cpufreq/cpuinfo_max_freq",
+        cpu
+    )
+}
+
+fn read_u64(path: &str) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// cpu_capacity WHERE THE KERNEL EXPORTS IT, ELSE cpuinfo_max_freq. NEVER
+// MIX THE TWO: CAPACITY (0-1024) AND KHZ ARE NOT COMPARABLE.
+pub fn read_capacities(nr_cpus: usize) -> Vec<Option<u64>> {
+    let caps: Vec<Option<u64>> = (0..nr_cpus).map(|c| read_u64(&capacity_path(c))).collect();
+    if caps.iter().any(|c| c.is_some()) {
+        return caps;
+    }
+    (0..nr_cpus).map(|c| read_u64(&max_freq_path(c))).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HybridTopology {
+    pub fast: Vec<bool>, // fast[cpu]
+}
+
+impl HybridTopology {
+    // None ON A UNIFORM MACHINE (FEWER THAN TWO DISTINCT KNOWN CAPACITIES).
+    // FAST = AT OR ABOVE THE MIDPOINT OF THE SMALLEST AND LARGEST CAPACITY,
+    // SO A MID TIER ON 3-CLUSTER ARM PARTS GOES WITH THE BIG CORES WHEN IT
+    // IS CLOSER TO THEM. UNKNOWN CPUS COUNT AS FAST: NEVER STEER AWAY FROM
+    // A CPU WE KNOW NOTHING ABOUT.
+    pub fn classify(caps: &[Option<u64>]) -> Option<Self> {
+        let known = caps.iter().flatten();
+        let min = *known.clone().min()?;
+        let max = *known.max()?;
+        if min == max {
+            return None;
+        }
+        let mid = min + (max - min) / 2;
+        let fast = caps.iter().map(|c| c.is_none_or(|v| v >= mid)).collect();
+        Some(Self { fast })
+    }
+
+    pub fn is_fast(&self, cpu: usize) -> bool {
+        self.fast.get(cpu).copied().unwrap_or(false)
+    }
+
+    // FLAT LIST WRITTEN TO THE fast_cpus MAP (SENTINEL u32::MAX AFTER)
+    pub fn fast_cpu_list(&self) -> Vec<u32> {
+        self.fast
+            .iter()
+            .enumerate()
+            .filter(|(_, &f)| f)
+            .map(|(c, _)| c as u32)
+            .collect()
+    }
+
+    pub fn counts(&self) -> (usize, usize) {
+        let nr_fast = self.fast.iter().filter(|&&f| f).count();
+        (nr_fast, self.fast.len() - nr_fast)
+    }
+}
+
+// PER-CPU (idle, total) JIFFIES FROM /proc/stat "cpuN" LINES.
+// IDLE = idle + iowait; TOTAL = user..steal (GUEST IS ALREADY IN user).
+pub fn parse_proc_stat(text: &str) -> Vec<(usize, u64, u64)> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let mut it = line.split_whitespace();
+        let Some(cpu) = it
+            .next()
+            .and_then(|t| t.strip_prefix("cpu"))
+            .and_then(|n| n.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let v: Vec<u64> = it.take(8).filter_map(|x| x.parse().ok()).collect();
+        if v.len() < 5 {
+            continue;
+        }
+        out.push((cpu, v[3] + v[4], v.iter().sum()));
+    }
+    out
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct ClassSample {
+    pub idle_pct: u64,
+    pub wake_us: u64,
+}
+
+// TICK-TO-TICK DELTAS PER CLASS. FIRST TICK ONLY SETS THE BASELINE.
+#[derive(Default)]
+pub struct HybridMonitor {
+    prev_jiffies: Vec<(usize, u64, u64)>,
+    prev_stats: Vec<PandemoniumStats>,
+}
+
+impl HybridMonitor {
+    // RETURNS (FAST, SLOW)
+    pub fn tick(
+        &mut self,
+        topo: &HybridTopology,
+        proc_stat: &str,
+        per_cpu: &[PandemoniumStats],
+    ) -> Option<(ClassSample, ClassSample)> {
+        let jiffies = parse_proc_stat(proc_stat);
+        let primed = !self.prev_jiffies.is_empty();
+
+        // [FAST, SLOW] x (idle, total, lat_sum, lat_cnt)
+        let mut acc = [[0u64; 4]; 2];
+        if primed {
+            for &(cpu, idle, total) in &jiffies {
+                if let Some(&(_, pi, pt)) = self.prev_jiffies.iter().find(|p| p.0 == cpu) {
+                    let a = &mut acc[usize::from(!topo.is_fast(cpu))];
+                    a[0] += idle.saturating_sub(pi);
+                    a[1] += total.saturating_sub(pt);
+                }
+            }
+            for (cpu, (cur, prev)) in per_cpu.iter().zip(&self.prev_stats).enumerate() {
+                let a = &mut acc[usize::from(!topo.is_fast(cpu))];
+                a[2] += cur.wake_lat_sum.saturating_sub(prev.wake_lat_sum);
+                a[3] += cur.wake_lat_samples.saturating_sub(prev.wake_lat_samples);
+            }
+        }
+        self.prev_jiffies = jiffies;
+        self.prev_stats = per_cpu.to_vec();
+        if !primed {
+            return None;
+        }
+
+        let sample = |a: [u64; 4]| ClassSample {
+            idle_pct: pct(a[0], a[1]),
+            wake_us: a[2].checked_div(a[3]).unwrap_or(0) / 1000,
+        };
+        Some((sample(acc[0]), sample(acc[1])))
+    }
+}
+
+// VERBOSE TELEMETRY LINE, PRINTED UNDER THE d/s LINE
+pub fn format_line(fast: &ClassSample, slow: &ClassSample) -> String {
+    format!(
+        "[HYBRID] fast: idle={}% wake={}us slow: idle={}% wake={}us",
+        fast.idle_pct, fast.wake_us, slow.idle_pct, slow.wake_us
+    )
+}
@@ -0,0 +1,141 @@
+// PANDEMONIUM SINGLE-INSTANCE LOCK
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// A SECOND `pandemonium` FAILS AT ATTACH, BUT Scheduler::init() HAS
+// ALREADY REPLACED EVERY PIN UNDER THE PIN DIR BY THEN: THE RUNNING
+// DAEMON LOSES ITS idle_cpus, procdb AND KNOB PINS. run_scheduler() TAKES
+// THIS LOCK FIRST, BEFORE ANY PIN IS TOUCHED.
+//
+// flock(LOCK_EX | LOCK_NB) ON LOCK_PATH, WHICH ALSO HOLDS THE OWNER'S PID.
+// THE KERNEL DROPS THE LOCK WHEN ITS LAST FD CLOSES, SO A CRASHED DAEMON
+// NEVER LEAVES IT HELD AND A STALE PID IN THE FILE MEANS NOTHING. THE FD
+// IS CLOSE-ON-EXEC, SO ONLY A FORKED-NOT-EXECED CHILD CAN OUTLIVE ITS
+// PARENT WITH THE LOCK: --force REPLACES THE FILE (NEW INODE, NEW LOCK)
+// ONCE THE RECORDED PID IS VERIFIED DEAD.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+pub const LOCK_PATH: &str = "/run/pandemonium.pid";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LockError {
+    Held { pid: Option<u32> }, // ANOTHER INSTANCE HOLDS IT
+    StillAlive { pid: u32 },   // --force, BUT THE OWNER IS RUNNING
+    Io { op: &'static str, errno: i32 },
+}
+
+impl LockError {
+    pub fn message(&self, path: &Path) -> String {
+        match self {
+            LockError::Held { pid: Some(pid) } => format!(
+                "PANDEMONIUM ALREADY RUNNING AS PID {} ({}). --force TAKES OVER ONLY IF IT IS DEAD",
+                pid,
+                path.display()
+            ),
+            LockError::Held { pid: None } => format!(
+                "PANDEMONIUM ALREADY RUNNING ({} LOCKED, NO PID RECORDED)",
+                path.display()
+            ),
+            LockError::StillAlive { pid } => format!(
+                "--force: PID {} HOLDING {} IS STILL ALIVE; STOP IT FIRST",
+                pid,
+                path.display()
+            ),
+            LockError::Io { op, errno } => format!(
+                "INSTANCE LOCK {}: {} FAILED: {}",
+                path.display(),
+                op,
+                std::io::Error::from_raw_os_error(*errno)
+            ),
+        }
+    }
+}
+
+fn io_err(op: &'static str, e: std::io::Error) -> LockError {
+    LockError::Io {
+        op,
+        errno: e.raw_os_error().unwrap_or(libc::EIO),
+    }
+}
+
+// HELD UNTIL DROPPED (OR THE PROCESS EXITS)
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// FIRST DECIMAL NUMBER IN THE FILE; None FOR EMPTY OR GARBAGE
+pub fn parse_pid(contents: &str) -> Option<u32> {
+    contents.trim().parse().ok().filter(|&p| p > 0)
+}
+
+// kill(pid, 0): EPERM STILL MEANS THE PROCESS EXISTS
+pub fn pid_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+fn try_lock(path: &Path) -> Result<Option<File>, LockError> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| io_err("open", e))?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+    let e = std::io::Error::last_os_error();
+    if e.raw_os_error() == Some(libc::EWOULDBLOCK) {
+        return Ok(None);
+    }
+    Err(io_err("flock", e))
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    let mut s = String::new();
+    File::open(path).ok()?.read_to_string(&mut s).ok()?;
+    parse_pid(&s)
+}
+
+// TAKE THE LOCK AND RECORD pid IN IT
+pub fn acquire(path: &Path, pid: u32, force: bool) -> Result<InstanceLock, LockError> {
+    let mut file = match try_lock(path)? {
+        Some(f) => f,
+        None => {
+            let owner = read_pid(path);
+            if !force {
+                return Err(LockError::Held { pid: owner });
+            }
+            if let Some(p) = owner.filter(|&p| pid_alive(p)) {
+                return Err(LockError::StillAlive { pid: p });
+            }
+            // THE HOLDER IS AN ORPHANED CHILD FD: A NEW INODE IS A NEW LOCK
+            std::fs::remove_file(path).map_err(|e| io_err("remove", e))?;
+            try_lock(path)?.ok_or(LockError::Held { pid: owner })?
+        }
+    };
+    file.set_len(0).map_err(|e| io_err("truncate", e))?;
+    file.rewind().map_err(|e| io_err("seek", e))?;
+    writeln!(file, "{}", pid).map_err(|e| io_err("write", e))?;
+    Ok(InstanceLock {
+        _file: file,
+        path: path.to_path_buf(),
+    })
+}
@@ -0,0 +1,83 @@
+// PANDEMONIUM CTRL+C ESCALATION
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE FIRST CTRL+C ASKS FOR THE NORMAL SHUTDOWN: SUMMARY, --dump-log,
+// UNPIN, DETACH. WITH THE BPF SIDE WEDGED THAT PATH CAN HANG, AND A FLAG
+// THAT IS ALREADY SET CANNOT BE SET HARDER. SO THE HANDLER COUNTS: THE
+// SECOND CTRL+C OF A RUN FORCES THE EXIT. THE MAIN THREAD, IF IT GETS BACK
+// WITHIN FORCE_CLEANUP, SKIPS THE SUMMARY AND DUMP, DROPS THE SCHEDULER
+// (UNPIN, THEN THE LINK DETACHES) AND EXITS 130. IF IT DOES NOT, THE
+// HANDLER UNLINKS THE PINS ITSELF AND EXITS 130: THE KERNEL CLOSES THE
+// LINK FD ON EXIT, WHICH DETACHES THE STRUCT_OPS.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+pub const FORCE_EXIT_CODE: i32 = 130; // 128 + SIGINT
+pub const FORCE_CLEANUP: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Press {
+    Shutdown, // FIRST: THE NORMAL SHUTDOWN
+    Force,    // SECOND: FORCE THE EXIT
+    Repeat,   // THIRD AND LATER: THE FORCED EXIT IS UNDER WAY
+}
+
+// SIGINTS OF THE CURRENT RUN. A static: THE ctrlc HANDLER OUTLIVES EVERY
+// SCOPE THAT COULD OWN IT.
+pub struct Interrupts {
+    presses: AtomicU8,
+}
+
+impl Default for Interrupts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interrupts {
+    pub const fn new() -> Self {
+        Self {
+            presses: AtomicU8::new(0),
+        }
+    }
+
+    pub fn press(&self) -> Press {
+        let prev = self
+            .presses
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                Some(n.saturating_add(1))
+            })
+            .unwrap_or(u8::MAX);
+        match prev {
+            0 => Press::Shutdown,
+            1 => Press::Force,
+            _ => Press::Repeat,
+        }
+    }
+
+    pub fn forced(&self) -> bool {
+        self.presses.load(Ordering::SeqCst) >= 2
+    }
+
+    // A KERNEL-REQUESTED RESTART STARTS A NEW RUN
+    pub fn reset(&self) {
+        self.presses.store(0, Ordering::SeqCst);
+    }
+}
+
+// THE HANDLER'S CLEANUP, WITHOUT THE SKELETON: UNLINK EVERY PIN UNDER dir,
+// THEN dir. UNPINNING IS ONLY AN UNLINK ON bpffs. RETURNS THE PINS REMOVED.
+pub fn remove_pins(dir: &Path) -> usize {
+    let removed = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| std::fs::remove_file(e.path()).is_ok())
+                .count()
+        })
+        .unwrap_or(0);
+    let _ = std::fs::remove_dir(dir);
+    removed
+}
@@ -0,0 +1,138 @@
+// PANDEMONIUM IO BENCH WORKLOAD
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// `bench --mode io` RUNS IO-WAIT-HEAVY WORKERS ALONGSIDE THE PROBE: THE
+// SLEEP-BUCKET CLASSIFIER (IO-WAIT VS IDLE) HAS NO OTHER BENCHMARK.
+// EACH `pandemonium io-worker` IS ONE SYNCHRONOUS STREAM, SO --io-depth
+// WORKERS PER KIND KEEP --io-depth REQUESTS IN FLIGHT. AT EXIT A WORKER
+// PRINTS ONE REPORT LINE:
+//   "io <kind> ops=<N> elapsed_us=<US>"
+
+pub const IO_ALIGN: u64 = 4096; // O_DIRECT: OFFSET, LENGTH AND BUFFER ALIGNMENT
+pub const DEFAULT_IO_SIZE: u64 = 4096;
+pub const DEFAULT_IO_DEPTH: usize = 4;
+
+// SCRATCH FILE: LARGE ENOUGH THAT RANDOM DIRECT READS MISS ANY DEVICE CACHE
+pub const IO_FILE_BYTES: u64 = 256 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IoKind {
+    // O_DIRECT RANDOM READS: EVERY OP SLEEPS ON THE DEVICE
+    Read,
+    // pwrite + fdatasync: EVERY OP WAITS FOR A FLUSH
+    Fsync,
+}
+
+impl IoKind {
+    pub const ALL: [IoKind; 2] = [IoKind::Read, IoKind::Fsync];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IoKind::Read => "read",
+            IoKind::Fsync => "fsync",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoProfile {
+    pub size: u64,    // BYTES PER OP
+    pub depth: usize, // WORKERS PER KIND
+}
+
+impl Default for IoProfile {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_IO_SIZE,
+            depth: DEFAULT_IO_DEPTH,
+        }
+    }
+}
+
+impl IoProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.size == 0 || !self.size.is_multiple_of(IO_ALIGN) {
+            return Err(format!(
+                "--io-size {} MUST BE A NONZERO MULTIPLE OF {} (O_DIRECT ALIGNMENT)",
+                self.size, IO_ALIGN
+            ));
+        }
+        if self.size > IO_FILE_BYTES {
+            return Err(format!(
+                "--io-size {} EXCEEDS THE {} BYTE SCRATCH FILE",
+                self.size, IO_FILE_BYTES
+            ));
+        }
+        if self.depth == 0 {
+            return Err("--io-depth MUST BE > 0".to_string());
+        }
+        Ok(())
+    }
+
+    // OP-ALIGNED OFFSETS IN THE SCRATCH FILE
+    pub fn slots(&self) -> u64 {
+        IO_FILE_BYTES / self.size
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{}K direct reads + {}K fsync writes, depth {}",
+            self.size / 1024,
+            self.size / 1024,
+            self.depth
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoReport {
+    pub kind: IoKind,
+    pub ops: u64,
+    pub elapsed_us: u64,
+}
+
+impl IoReport {
+    pub fn line(&self) -> String {
+        format!(
+            "io {} ops={} elapsed_us={}",
+            self.kind.name(),
+            self.ops,
+            self.elapsed_us
+        )
+    }
+
+    pub fn iops(&self) -> f64 {
+        if self.elapsed_us == 0 {
+            return 0.0;
+        }
+        self.ops as f64 * 1_000_000.0 / self.elapsed_us as f64
+    }
+}
+
+pub fn parse_io_report(line: &str) -> Option<IoReport> {
+    let mut parts = line.split_whitespace();
+    if parts.next()? != "io" {
+        return None;
+    }
+    let kind = IoKind::parse(parts.next()?)?;
+    let ops = parts.next()?.strip_prefix("ops=")?.parse().ok()?;
+    let elapsed_us = parts.next()?.strip_prefix("elapsed_us=")?.parse().ok()?;
+    Some(IoReport {
+        kind,
+        ops,
+        elapsed_us,
+    })
+}
+
+// ACHIEVED IOPS OF ONE KIND: WORKERS RUN CONCURRENTLY, SO RATES ADD
+pub fn total_iops(reports: &[IoReport], kind: IoKind) -> f64 {
+    reports
+        .iter()
+        .filter(|r| r.kind == kind)
+        .map(IoReport::iops)
+        .sum()
+}
@@ -0,0 +1,70 @@
+// PANDEMONIUM KERNEL LOG PARSING
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// /dev/kmsg RECORD FORMAT (Documentation/ABI/testing/dev-kmsg):
+//   "<prefix>,<seq>,<ts_usec>,<flags>[,...];<message>\n"
+//   FOLLOWED BY OPTIONAL " KEY=VALUE" CONTINUATION LINES.
+//   PREFIX = (FACILITY << 3) | LEVEL. ONE record PER read().
+
+pub const KMSG_PATH: &str = "/dev/kmsg";
+
+// SYSLOG LEVELS (include/linux/kern_levels.h)
+pub const LEVEL_ERR: u8 = 3;
+
+pub const TROUBLESHOOTING_URL: &str = "https://github.com/wllclngn/PANDEMONIUM#troubleshooting";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KmsgRecord {
+    pub level: u8,
+    pub facility: u8,
+    pub seq: u64,
+    pub ts_us: u64,
+    pub msg: String,
+}
+
+impl KmsgRecord {
+    pub fn is_error(&self) -> bool {
+        self.level <= LEVEL_ERR
+    }
+}
+
+// PARSE ONE RAW RECORD. CONTINUATION LINES ARE DROPPED.
+pub fn parse_record(raw: &str) -> Option<KmsgRecord> {
+    let first = raw.lines().next()?;
+    let (header, msg) = first.split_once(';')?;
+    let mut fields = header.split(',');
+    let prefix: u32 = fields.next()?.trim().parse().ok()?;
+    let seq: u64 = fields.next()?.trim().parse().ok()?;
+    let ts_us: u64 = fields.next()?.trim().parse().ok()?;
+    Some(KmsgRecord {
+        level: (prefix & 7) as u8,
+        facility: (prefix >> 3) as u8,
+        seq,
+        ts_us,
+        msg: msg.to_string(),
+    })
+}
+
+// SAME MATCH THE JOURNALCTL SNAPSHOT HAS ALWAYS USED
+pub fn is_scx_related(msg: &str) -> bool {
+    let low = msg.to_lowercase();
+    low.contains("sched_ext") || low.contains("scx") || low.contains("pandemonium")
+}
+
+// KNOWN FAILURE SIGNATURES -> ONE-LINE HINT + README ANCHOR
+pub fn hint_for(msg: &str) -> Option<&'static str> {
+    let low = msg.to_lowercase();
+    if low.contains("deprecated") {
+        Some("KERNEL API DEPRECATION: RUN `pandemonium check` (SEE TROUBLESHOOTING: DEPRECATION WARNINGS)")
+    } else if low.contains("runnable task stall") {
+        Some("WATCHDOG STALL: A TASK WAITED > TIMEOUT IN A DSQ (SEE TROUBLESHOOTING: RUNNABLE TASK STALL)")
+    } else {
+        None
+    }
+}
+
+// "[+SSSS.UUUUUU]" RELATIVE TO A BASE TIMESTAMP
+pub fn format_relative(ts_us: u64, base_us: u64) -> String {
+    let d = ts_us.saturating_sub(base_us);
+    format!("[+{}.{:06}]", d / 1_000_000, d % 1_000_000)
+}
@@ -0,0 +1,179 @@
+// PANDEMONIUM L2 CACHE TOPOLOGY
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// GROUPS CPUS BY SHARED L2 (sysfs cache/index2/shared_cpu_list). STARTUP,
+// CPU HOTPLUG AND `pandemonium topology` ALL BUILD THE TABLE HERE, SO THE
+// REPORT SHOWS EXACTLY WHAT THE SCHEDULER WROTE INTO cache_domain AND
+// l2_siblings.
+//
+// PREFERRED NEIGHBORS OF A CPU ARE ITS GROUP'S FIRST MAX_L2_SIBLINGS
+// MEMBERS (THE SLOTS BPF CAN SEE), MINUS THE CPU ITSELF.
+
+use crate::cpulist;
+use crate::stats::{pct, PandemoniumStats};
+
+pub const MAX_L2_SIBLINGS: usize = 8; // l2_siblings SLOTS PER GROUP (main.bpf.c)
+
+pub fn shared_cpu_list_path(cpu: usize) -> String {
+    format!(
+        "/sys/devices/system/cpu/cpu{}/cache/index2/shared_cpu_list",
+        cpu
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L2Topology {
+    pub nr_cpus: usize,
+    pub l2_domain: Vec<u32>,      // l2_domain[cpu] = group_id
+    pub l2_groups: Vec<Vec<u32>>, // l2_groups[group_id] = [cpu, ...]
+}
+
+impl L2Topology {
+    // lists[cpu] = CONTENTS OF shared_cpu_list, None IF UNREADABLE
+    // (OFFLINE OR NO L2 INFO: THE CPU GETS ITS OWN ID, NO GROUP)
+    pub fn from_shared_lists(lists: &[Option<String>]) -> Self {
+        let nr_cpus = lists.len();
+        let mut l2_domain = vec![0u32; nr_cpus];
+        let mut seen_groups: Vec<Vec<u32>> = Vec::new();
+
+        for (cpu, list) in lists.iter().enumerate() {
+            // NO CACHE LIST, OR ONE THAT DOES NOT PARSE: ITS OWN DOMAIN
+            let Some(members) = list.as_deref().and_then(|c| cpulist::parse(c).ok()) else {
+                l2_domain[cpu] = cpu as u32;
+                continue;
+            };
+            let group_id = match seen_groups.iter().position(|g| *g == members) {
+                Some(id) => id as u32,
+                None => {
+                    seen_groups.push(members);
+                    (seen_groups.len() - 1) as u32
+                }
+            };
+            l2_domain[cpu] = group_id;
+        }
+
+        Self {
+            nr_cpus,
+            l2_domain,
+            l2_groups: seen_groups,
+        }
+    }
+
+    pub fn detect(nr_cpus: usize) -> Self {
+        let lists: Vec<Option<String>> = (0..nr_cpus)
+            .map(|cpu| std::fs::read_to_string(shared_cpu_list_path(cpu)).ok())
+            .collect();
+        Self::from_shared_lists(&lists)
+    }
+
+    // --reserved-cpus: DROP cpus FROM EVERY GROUP, SO NO SIBLING STEALS
+    // FOR THEM OR ONTO THEM. EACH GETS ITS OWN ID, LIKE AN UNREADABLE CPU.
+    pub fn without(mut self, cpus: &[u32]) -> Self {
+        for members in &mut self.l2_groups {
+            members.retain(|c| !cpus.contains(c));
+        }
+        for &cpu in cpus {
+            if let Some(d) = self.l2_domain.get_mut(cpu as usize) {
+                *d = cpu;
+            }
+        }
+        self
+    }
+
+    // GROUP MEMBERS OF `cpu` THAT BPF CAN PICK, EXCLUDING ITSELF
+    pub fn preferred_neighbors(&self, cpu: usize) -> Vec<u32> {
+        let Some(&gid) = self.l2_domain.get(cpu) else {
+            return Vec::new();
+        };
+        match self.l2_groups.get(gid as usize) {
+            Some(members) if members.contains(&(cpu as u32)) => members
+                .iter()
+                .take(MAX_L2_SIBLINGS)
+                .copied()
+                .filter(|&c| c != cpu as u32)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // GROUP LINES + SUMMARY, AS LOGGED AT STARTUP
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .l2_groups
+            .iter()
+            .enumerate()
+            .map(|(gid, members)| format!("L2 GROUP {}: [{}]", gid, join(members)))
+            .collect();
+        out.push(format!(
+            "L2 GROUPS: {} across {} CPUs",
+            self.l2_groups.len(),
+            self.nr_cpus
+        ));
+        out
+    }
+
+    // PER-CPU PREFERRED-NEIGHBOR TABLE
+    pub fn neighbor_table(&self) -> Vec<String> {
+        let mut out = vec![format!("{:<6} {:<6} NEIGHBORS", "CPU", "L2")];
+        for cpu in 0..self.nr_cpus {
+            let grouped = self
+                .l2_groups
+                .get(self.l2_domain[cpu] as usize)
+                .is_some_and(|m| m.contains(&(cpu as u32)));
+            let gid = if grouped {
+                self.l2_domain[cpu].to_string()
+            } else {
+                "-".to_string()
+            };
+            let neighbors = self.preferred_neighbors(cpu);
+            let shown = if neighbors.is_empty() {
+                "-".to_string()
+            } else {
+                join(&neighbors)
+            };
+            out.push(format!("{:<6} {:<6} {}", cpu, gid, shown));
+        }
+        out
+    }
+
+    // SUM PER-CPU L2 HIT/MISS (ALL TIERS) BY GROUP: [(hits, misses)]
+    // per_cpu[i] IS CPU i'S SLOT OF THE PERCPU stats_map
+    pub fn group_l2_counts(&self, per_cpu: &[PandemoniumStats]) -> Vec<(u64, u64)> {
+        let mut out = vec![(0u64, 0u64); self.l2_groups.len()];
+        for (gid, members) in self.l2_groups.iter().enumerate() {
+            for &cpu in members {
+                if let Some(s) = per_cpu.get(cpu as usize) {
+                    out[gid].0 +=
+                        s.nr_l2_hit_batch + s.nr_l2_hit_interactive + s.nr_l2_hit_lat_crit;
+                    out[gid].1 +=
+                        s.nr_l2_miss_batch + s.nr_l2_miss_interactive + s.nr_l2_miss_lat_crit;
+                }
+            }
+        }
+        out
+    }
+
+    pub fn group_l2_table(&self, per_cpu: &[PandemoniumStats]) -> Vec<String> {
+        let mut out = vec![format!(
+            "{:<6} {:<12} {:<12} HIT%",
+            "GROUP", "HITS", "MISSES"
+        )];
+        for (gid, (hit, miss)) in self.group_l2_counts(per_cpu).into_iter().enumerate() {
+            out.push(format!(
+                "{:<6} {:<12} {:<12} {}",
+                gid,
+                hit,
+                miss,
+                pct(hit, hit + miss)
+            ));
+        }
+        out
+    }
+}
+
+fn join(cpus: &[u32]) -> String {
+    cpus.iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,98 @@
+// PANDEMONIUM WAKEUP LATENCY PLOTS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// ASCII RENDERINGS OF THE WAKEUP LATENCY HISTOGRAM (HIST_EDGES_NS BUCKETS):
+// A CDF OF THE CUMULATIVE COUNTS FOR THE SHUTDOWN SUMMARY AND THE SIGUSR1
+// DUMP, AND A PER-MINUTE HEATMAP (TIME DOWN, BUCKET ACROSS) FROM THE TICK
+// DELTAS event.rs FOLDS INTO MINUTES. PLAIN ARRAYS IN, LINES OUT.
+
+use crate::tuning::{HIST_BUCKETS, HIST_EDGES_NS};
+
+pub const CDF_WIDTH: usize = 40;
+// HEATMAP INTENSITY, EMPTY TO BUSIEST. LOG SCALE: ONE WAKEUP IS STILL VISIBLE
+pub const HEAT_RAMP: &[u8] = b" .:-=+*#%@";
+const HEAT_CELL: usize = 5;
+
+// "10us", "1ms", "+inf"
+pub fn bucket_label(b: usize) -> String {
+    match HIST_EDGES_NS[b] {
+        u64::MAX => "+inf".to_string(),
+        ns if ns >= 1_000_000 => format!("{}ms", ns / 1_000_000),
+        ns => format!("{}us", ns / 1000),
+    }
+}
+
+// THE THREE TIERS SUMMED PER BUCKET
+pub fn pool(tiers: &[[u64; HIST_BUCKETS]; 3]) -> [u64; HIST_BUCKETS] {
+    std::array::from_fn(|b| tiers.iter().map(|t| t[b]).sum())
+}
+
+// ONE ROW PER BUCKET EDGE: THE SHARE OF WAKEUPS AT OR UNDER IT.
+// "  <=  100us |##########          |  50.0%"
+pub fn cdf_lines(counts: &[u64; HIST_BUCKETS], width: usize) -> Vec<String> {
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return vec!["  NO WAKEUP SAMPLES".to_string()];
+    }
+    let mut cumulative = 0u64;
+    counts
+        .iter()
+        .enumerate()
+        .map(|(b, &c)| {
+            cumulative += c;
+            let frac = cumulative as f64 / total as f64;
+            let bar = (frac * width as f64).round() as usize;
+            format!(
+                "  <= {:>5} |{:<width$}| {:>5.1}%",
+                bucket_label(b),
+                "#".repeat(bar),
+                frac * 100.0,
+                width = width
+            )
+        })
+        .collect()
+}
+
+// HEAT_RAMP INDEX FOR count AGAINST THE BUSIEST CELL: 0 ONLY FOR NO WAKEUPS,
+// THE TOP ONLY FOR THE BUSIEST
+pub fn heat_level(count: u64, max: u64) -> usize {
+    let top = HEAT_RAMP.len() - 1;
+    if count == 0 {
+        return 0;
+    }
+    if max <= 1 {
+        return top;
+    }
+    let scaled = (count as f64).ln() / (max as f64).ln() * (top - 1) as f64;
+    (1 + scaled.floor() as usize).min(top)
+}
+
+// (MINUTE, POOLED COUNTS) ROWS, OLDEST FIRST. A MINUTE WITH NO TICK HAS NO
+// ROW: A GAP IN THE MIN COLUMN IS A GAP IN THE RUN.
+pub fn heatmap_lines(rows: &[(u64, [u64; HIST_BUCKETS])]) -> Vec<String> {
+    let max = rows.iter().flat_map(|(_, c)| c).copied().max().unwrap_or(0);
+    if max == 0 {
+        return vec!["  NO WAKEUP SAMPLES".to_string()];
+    }
+    let mut header = format!("  {:>4}", "MIN");
+    for b in 0..HIST_BUCKETS {
+        header.push_str(&format!(" {:>width$}", bucket_label(b), width = HEAT_CELL));
+    }
+    let mut lines = vec![header];
+    for (minute, counts) in rows {
+        let mut row = format!("  {:>4}", minute);
+        for &c in counts {
+            let ch = HEAT_RAMP[heat_level(c, max)] as char;
+            row.push(' ');
+            row.push_str(&ch.to_string().repeat(HEAT_CELL));
+        }
+        lines.push(row.trim_end().to_string());
+    }
+    lines.push(format!(
+        "  SCALE: '{}' (LOG, '{}' = {} WAKEUPS)",
+        std::str::from_utf8(HEAT_RAMP).unwrap_or(""),
+        HEAT_RAMP[HEAT_RAMP.len() - 1] as char,
+        max
+    ));
+    lines
+}
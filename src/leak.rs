@@ -0,0 +1,105 @@
+// PANDEMONIUM LEAK SNAPSHOTS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// CLEANUP BUGS (STALE PINS, ORPHANED PROBES, LINGERING STRESS THREADS)
+// SHOW UP AS STATE THAT OUTLIVES A RUN. A Snapshot RECORDS WHAT A RUN CAN
+// LEAVE BEHIND: THE bpffs PIN DIR AND ITS ENTRIES, EVERY pandemonium
+// PROCESS, AND THE OBSERVER'S OWN THREAD COUNT. TAKEN BEFORE AND AFTER A
+// START/STOP CYCLE, leaks() NAMES WHAT DID NOT RETURN TO BASELINE. THE
+// GATE'S LEAK LAYER AND THE SOAK RUN'S EXIT CHECK SHARE IT.
+
+use std::path::Path;
+
+// comm OF THE SCHEDULER AND ITS SUBCOMMANDS (probe, test --soak, ...)
+pub const PROCESS_COMM: &str = "pandemonium";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub pin_dir: bool,                 // THE PIN DIR EXISTS
+    pub pins: Vec<String>,             // ITS ENTRIES, SORTED
+    pub processes: Vec<(u32, String)>, // (PID, comm) OF pandemonium PROCESSES, BY PID
+    pub threads: usize,                // THE OBSERVER'S /proc/<pid>/task ENTRIES
+}
+
+impl Snapshot {
+    // observer: THE PROCESS TAKING THE SNAPSHOT. ITS OWN PID IS NOT A
+    // pandemonium PROCESS TO REPORT (THE SOAK RUNNER IS ONE BY comm)
+    pub fn take(pin_dir: &Path, proc_root: &Path, observer: u32) -> Self {
+        let names = |dir: &Path| -> Option<Vec<String>> {
+            let mut out: Vec<String> = std::fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect();
+            out.sort();
+            Some(out)
+        };
+        let pins = names(pin_dir);
+        let mut processes: Vec<(u32, String)> = names(proc_root)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|name| {
+                let pid = name.parse::<u32>().ok().filter(|&p| p != observer)?;
+                let comm = std::fs::read_to_string(proc_root.join(name).join("comm")).ok()?;
+                let comm = comm.trim_end_matches('\n');
+                (comm == PROCESS_COMM).then(|| (pid, comm.to_string()))
+            })
+            .collect();
+        processes.sort();
+        let threads =
+            names(&proc_root.join(observer.to_string()).join("task")).map_or(0, |t| t.len());
+        Self {
+            pin_dir: pins.is_some(),
+            pins: pins.unwrap_or_default(),
+            processes,
+            threads,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Leak {
+    PinDir,
+    Pin(String),
+    Process(u32, String),
+    Threads { before: usize, after: usize },
+}
+
+impl Leak {
+    pub fn message(&self) -> String {
+        match self {
+            Leak::PinDir => "PIN DIR NOT REMOVED".to_string(),
+            Leak::Pin(name) => format!("PIN LEFT BEHIND: {}", name),
+            Leak::Process(pid, comm) => format!("PROCESS STILL RUNNING: {} (PID {})", comm, pid),
+            Leak::Threads { before, after } => {
+                format!("THREADS: {} BEFORE, {} AFTER", before, after)
+            }
+        }
+    }
+}
+
+// WHAT after HOLDS THAT before DID NOT. ANYTHING ALREADY THERE AT BASELINE
+// IS NOT THE CYCLE'S LEAK; FEWER THREADS THAN BEFORE IS NOT A LEAK EITHER.
+pub fn leaks(before: &Snapshot, after: &Snapshot) -> Vec<Leak> {
+    let mut out = Vec::new();
+    if after.pin_dir && !before.pin_dir {
+        out.push(Leak::PinDir);
+    }
+    for pin in &after.pins {
+        if !before.pins.contains(pin) {
+            out.push(Leak::Pin(pin.clone()));
+        }
+    }
+    for (pid, comm) in &after.processes {
+        if !before.processes.iter().any(|(p, _)| p == pid) {
+            out.push(Leak::Process(*pid, comm.clone()));
+        }
+    }
+    if after.threads > before.threads {
+        out.push(Leak::Threads {
+            before: before.threads,
+            after: after.threads,
+        });
+    }
+    out
+}
@@ -1,3 +1,49 @@
+pub mod abi;
+pub mod attach;
+pub mod benchanalyze;
+pub mod benchout;
+pub mod blackbox;
+pub mod bpffeat;
+pub mod btfcache;
+pub mod client;
+pub mod compat;
+pub mod conflicts;
+pub mod container;
+pub mod cpulist;
+pub mod ctl;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+pub mod diag;
 pub mod event;
+pub mod exitcode;
+pub mod gamemode;
+pub mod governor;
+pub mod hotplug;
+pub mod hybrid;
+pub mod instance;
+pub mod interrupt;
+pub mod iobench;
+pub mod kmsg;
+pub mod l2topo;
+pub mod latplot;
+pub mod leak;
+pub mod memlock;
+pub mod pinmap;
+pub mod preflight;
+pub mod privilege;
+pub mod probe;
+pub mod runusage;
 pub mod procdb;
+pub mod reserved;
+pub mod residency;
+pub mod restart;
+pub mod rtsteal;
+pub mod scxstate;
+pub mod soak;
+pub mod stats;
+pub mod statusbar;
+pub mod syscalls;
+pub mod sysinfo;
+pub mod telemetry;
 pub mod tuning;
+pub mod victims;
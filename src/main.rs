@@ -4,6 +4,7 @@
 // SCHEDULING DECISIONS HAPPEN IN BPF (ZERO KERNEL-USERSPACE ROUND TRIPS)
 // RUST USERSPACE HANDLES: ADAPTIVE CONTROL LOOP, MONITORING, BENCHMARKING
 
+#[cfg(not(feature = "stub-bpf"))]
 #[allow(non_upper_case_globals)]
 #[allow(non_camel_case_types)]
 #[allow(non_snake_case)]
@@ -14,21 +15,51 @@ mod bpf_skel;
 mod log;
 mod adaptive;
 mod cli;
+mod control;
+mod ctl;
+#[cfg(feature = "dbus")]
+mod dbus;
+mod diag;
+mod gamemode;
+mod pinmap;
 mod procdb;
+mod restart;
+#[cfg_attr(feature = "stub-bpf", path = "scheduler_stub.rs")]
 mod scheduler;
+mod syscalls;
 mod topology;
+// stub-bpf: KnobWriter AND THE LAG HISTOGRAM DECODE ONLY SERVE scheduler.rs
+#[cfg_attr(feature = "stub-bpf", allow(dead_code))]
 mod tuning;
 
 use std::mem::MaybeUninit;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
+use pandemonium::exitcode::{ExitInfo, ExitStatus};
+use pandemonium::interrupt::{self, Interrupts, Press, FORCE_CLEANUP, FORCE_EXIT_CODE};
+use pandemonium::{
+    blackbox, cpulist, event, exitcode, governor, hotplug, hybrid, memlock, probe, reserved,
+    residency, rtsteal, stats, sysinfo, telemetry,
+};
+use restart::{RestartDecision, RestartGuard, RestartPolicy};
 use scheduler::Scheduler;
 
 static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+static SIGINTS: Interrupts = Interrupts::new();
+#[cfg(feature = "gate-hooks")]
+static WEDGE_SHUTDOWN: AtomicBool = AtomicBool::new(false);
+static DIAG_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// ASYNC-SIGNAL-SAFE: ONLY FLIPS THE FLAG, THE MONITOR LOOP DOES THE WORK
+extern "C" fn on_sigusr1(_: libc::c_int) {
+    DIAG_REQUESTED.store(true, Ordering::Relaxed);
+}
 
 #[derive(Parser)]
 #[command(name = "pandemonium")]
@@ -41,47 +72,215 @@ struct Cli {
     #[arg(short, long)]
     verbose: bool,
 
+    /// With --verbose, print the telemetry line every SECS seconds instead of every second
+    #[arg(long, value_name = "SECS", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    telemetry_interval: u64,
+
     #[arg(long)]
     dump_log: bool,
 
-    /// Override CPU count for scaling formulas (default: auto-detect)
-    #[arg(long)]
+    /// Override CPU count for scaling formulas (default: auto-detect; clamped to possible CPUs)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
     nr_cpus: Option<u64>,
 
+    /// Cgroup cpuset file whose CPUs the scaling formulas count (default: the root cgroup's effective cpuset)
+    #[arg(long, value_name = "PATH", conflicts_with = "nr_cpus")]
+    scaling_cpuset: Option<PathBuf>,
+
+    /// Exit cleanly after SECS seconds of scheduling, printing the normal summary (exit status 0)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    duration: Option<u64>,
+
     /// Run BPF scheduler only, disable Rust adaptive control loop
     #[arg(long)]
     no_adaptive: bool,
 
+    /// No per-wakeup accounting in BPF (no P99, sleep histogram or procdb observations); for very high wakeup rates
+    #[arg(long, conflicts_with_all = ["slo_p99_us", "adaptive_classifier"])]
+    lightweight: bool,
+
+    /// Keep dispatch off these CPUs ("2,3" or "8-11"); only tasks affined to them run there
+    #[arg(long, value_name = "LIST")]
+    reserved_cpus: Option<String>,
+
     /// Additional compositor process names to boost to LAT_CRITICAL
     #[arg(long)]
     compositor: Vec<String>,
+
+    /// Control socket path for `pandemonium ctl` (adaptive mode only)
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    ctl_socket: String,
+
+    /// Export org.pandemonium.Scheduler1 on the system (default) or session bus (--features dbus)
+    #[arg(long, value_enum, value_name = "BUS", num_args = 0..=1, default_missing_value = "system")]
+    dbus: Option<DbusBus>,
+
+    /// Wait (bounded) for an already-attached sched_ext scheduler to detach
+    #[arg(long)]
+    takeover: bool,
+
+    /// Take the instance lock from a previous daemon, once its recorded pid is verified dead
+    #[arg(long)]
+    force: bool,
+
+    /// Exit on adaptive-layer failure instead of falling back to BPF-only mode
+    #[arg(long)]
+    strict: bool,
+
+    /// Treat all CPUs as equal on hybrid (P/E-core, big.LITTLE) machines
+    #[arg(long)]
+    no_hybrid: bool,
+
+    /// Process database capacity (profiles kept before scored eviction)
+    #[arg(long, default_value_t = procdb::MAX_PROFILES as u64, value_parser = clap::value_parser!(u64).range(1..))]
+    procdb_max: u64,
+
+    /// Forget procdb profiles not observed for this many seconds
+    #[arg(long, default_value_t = procdb::STALE_TICKS, value_parser = clap::value_parser!(u64).range(1..))]
+    procdb_stale_secs: u64,
+
+    /// Start procdb empty instead of from the built-in seed table
+    #[arg(long, conflicts_with = "seed_file")]
+    no_seed: bool,
+
+    /// Seed procdb from PATH ("comm tier avg_runtime_us" lines) instead of the built-in table
+    #[arg(long, value_name = "PATH")]
+    seed_file: Option<PathBuf>,
+
+    /// Pin comm NAME to BATCH: procdb never reclassifies or evicts it (repeatable)
+    #[arg(long, value_name = "NAME", conflicts_with = "no_adaptive")]
+    force_batch_comm: Vec<String>,
+
+    /// Pin comm NAME to LAT_CRITICAL: procdb never reclassifies or evicts it (repeatable)
+    #[arg(long, value_name = "NAME", conflicts_with = "no_adaptive")]
+    force_latcri_comm: Vec<String>,
+
+    /// Pin the comms listed in PATH ("comm tier" lines); --force-*-comm flags win
+    #[arg(long, value_name = "PATH", conflicts_with = "no_adaptive")]
+    force_file: Option<PathBuf>,
+
+    /// Hold the deadline lag multiplier at N in every regime (`ctl unset lag_scale` releases it)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(tuning::MIN_LAG_SCALE..=tuning::MAX_LAG_SCALE))]
+    lag_scale: Option<u64>,
+
+    /// Shortest slice BPF may grant; the reflex never tightens below it or 500us (microseconds)
+    #[arg(long, value_name = "US", default_value_t = tuning::MIN_SLICE_NS / 1000)]
+    slice_min_us: u64,
+
+    /// Longest slice BPF may grant; also the reflex relax ceiling (microseconds)
+    #[arg(long, value_name = "US", default_value_t = tuning::MAX_SLICE_NS / 1000)]
+    slice_max_us: u64,
+
+    /// Run a wakeup probe thread in-process (100 samples/s, never boosted); adds probe_p99 to telemetry
+    #[arg(long)]
+    self_probe: bool,
+
+    /// Adapt the LAT_CRITICAL classifier threshold to the tier share of wakeups (adaptive mode only)
+    #[arg(long, conflicts_with = "no_adaptive")]
+    adaptive_classifier: bool,
+
+    /// Hold this wakeup P99 in every regime (microseconds) and report compliance (adaptive mode only)
+    #[arg(long, value_name = "US", conflicts_with = "no_adaptive", value_parser = clap::value_parser!(u64).range(tuning::SLO_MIN_US..=tuning::SLO_MAX_US))]
+    slo_p99_us: Option<u64>,
+
+    /// Log the startup [CONFIG] block as one JSON object instead of key=value lines
+    #[arg(long)]
+    config_json: bool,
+
+    /// Do not warn about running tuning daemons that renice (ananicy-cpp, system76-scheduler, gamemode)
+    #[arg(long)]
+    ignore_conflicts: bool,
+
+    // GATE ONLY (--features gate-hooks): HANG THE SHUTDOWN PATH LIKE A
+    // WEDGED BPF READ, SO ONLY THE SECOND CTRL+C (interrupt.rs) CAN END IT
+    #[cfg(feature = "gate-hooks")]
+    #[arg(long, hide = true)]
+    wedge_shutdown: bool,
+
+    /// Directory to pin BPF maps in (must be on bpffs); check --runtime and topology --live read the same
+    #[arg(long, global = true, value_name = "DIR", default_value = pandemonium::container::DEFAULT_PIN_DIR)]
+    pin_dir: PathBuf,
+
+    /// Give GROUP (name or gid) access to the pinned maps and control socket, for non-root clients
+    #[arg(long, value_name = "GROUP")]
+    pin_group: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DbusBus {
+    System,
+    Session,
 }
 
 #[derive(Subcommand)]
 enum SubCmd {
     /// Check dependencies and kernel config
-    Check,
+    Check(CheckArgs),
+
+    /// Load the BPF object without attaching and check every decoded map's key/value size against its Rust mirror
+    SelfTest,
 
     /// Run interactive wakeup probe (stdout: overshoot_us per line)
     Probe(ProbeArgs),
 
-    /// Build, run with sudo, capture output + dmesg, save logs
+    /// Build, run as root (directly, or via sudo/doas/run0), capture output + dmesg, save logs
     Start(StartArgs),
 
     /// Show filtered kernel dmesg for sched_ext/pandemonium
-    Dmesg,
+    Dmesg(DmesgArgs),
+
+    /// Send a command to a running scheduler's control socket
+    Ctl(CtlArgs),
+
+    /// Export or import learned process classifications (fleet warm-up)
+    Procdb(ProcdbArgs),
+
+    /// Decode the crash-safe black box (last ~500 ticks before an abort)
+    Blackbox(BlackboxArgs),
+
+    /// Show L2 cache groups and per-CPU preferred neighbors as the scheduler sees them
+    Topology(TopologyArgs),
+
+    /// Print the version/kernel/sched_ext/CPU header and [CONFIG] block that open every report
+    Info(InfoArgs),
+
+    /// One status line for a status bar (Waybar JSON or a custom template); "inactive" when not running
+    Status(StatusArgs),
+
+    /// Game state on/off for Feral GameMode hooks: MIXED pin, tightened slices, TGID boost (nests)
+    Gamemode(GamemodeArgs),
+
+    /// Print integration snippets (binary + systemd service: ./pandemonium.py install)
+    Install(InstallArgs),
 
     /// A/B benchmark (EEVDF baseline vs PANDEMONIUM)
     Bench(BenchArgs),
 
-    /// Build release then run bench (logs to /tmp/pandemonium)
-    BenchRun(BenchRunArgs),
+    /// Deprecated alias for `bench --build` (removed in the next release)
+    BenchRun(BenchArgs),
+
+    /// Re-analyze a bench directory's raw probe samples: percentiles, ASCII CDFs, Mann-Whitney U between phases
+    BenchAnalyze(BenchAnalyzeArgs),
 
-    /// Run test gate (unit + integration)
-    Test,
+    /// Run test gate (unit + integration), or a long soak with --soak HOURS
+    Test(TestArgs),
 
     /// CPU-pinned stress worker for bench-scale (internal use)
     StressWorker(StressWorkerArgs),
+
+    /// Synchronous IO worker for bench --mode io (internal use)
+    IoWorker(IoWorkerArgs),
+}
+
+#[derive(Parser)]
+struct CheckArgs {
+    /// Report the attached sched_ext scheduler (sysfs) and, for PANDEMONIUM, a live health line
+    #[arg(long)]
+    runtime: bool,
+
+    /// Print the preflight report as one JSON object ({"ok", "status", "entries"}) instead of text
+    #[arg(long, conflicts_with = "runtime")]
+    json: bool,
 }
 
 #[derive(Parser)]
@@ -89,6 +288,170 @@ struct ProbeArgs {
     /// Death pipe FD for orphan detection (internal use)
     #[arg(long)]
     death_pipe_fd: Option<i32>,
+
+    /// Wakeup period in microseconds (1000 = audio callback, 16667 = 60fps frame)
+    #[arg(long, default_value_t = cli::probe::DEFAULT_PERIOD_US)]
+    period_us: u64,
+
+    /// Busy-work per period in microseconds, spun before each sleep
+    #[arg(long, default_value_t = 0)]
+    spin_us: u64,
+
+    /// Independent probe threads; output becomes "tid overshoot_us" when > 1
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
+
+    /// Sample output format (hist: one dump at exit, for short periods / many threads)
+    #[arg(long, value_enum, default_value = "raw")]
+    output: cli::probe::ProbeOutput,
+
+    /// Add an emulated audio callback (48kHz/64 frames, 300us work); prints an "xrun ..." line at exit
+    #[arg(long)]
+    audio: bool,
+
+    /// Print a "# probe ..." config header before the samples
+    #[arg(long)]
+    header: bool,
+}
+
+#[derive(Parser)]
+struct DmesgArgs {
+    /// Tail /dev/kmsg and print new sched_ext/pandemonium lines as they arrive
+    #[arg(long)]
+    follow: bool,
+
+    /// Include every record since boot (default: last 50, or new only with --follow)
+    #[arg(long)]
+    since_boot: bool,
+
+    /// Only show records at KERN_ERR or more severe
+    #[arg(long)]
+    errors_only: bool,
+}
+
+#[derive(Parser)]
+struct TestArgs {
+    /// Soak instead of the quick gate: run under a rotating workload for HOURS (root)
+    #[arg(long, value_name = "HOURS")]
+    soak: Option<f64>,
+}
+
+#[derive(Parser)]
+struct CtlArgs {
+    /// Control socket of the running scheduler
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    socket: String,
+
+    /// get knobs | get regime | set <knob> <value> | unset <knob> |
+    /// pin regime <light|mixed|heavy> | unpin | profile <name> | boost <pid> |
+    /// gamemode <start|end> [pid] | pause | resume | stats
+    #[arg(required = true, num_args = 1..)]
+    command: Vec<String>,
+}
+
+#[derive(Parser)]
+struct ProcdbArgs {
+    /// Control socket of the running scheduler (used when PANDEMONIUM is attached)
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    socket: String,
+
+    #[command(subcommand)]
+    action: ProcdbAction,
+}
+
+#[derive(Subcommand)]
+enum ProcdbAction {
+    /// Write all profiles (live if running, else the cache) to FILE
+    Export { file: PathBuf },
+
+    /// Merge FILE into the database (votes add, runtimes EWMA-merged)
+    Import {
+        file: PathBuf,
+
+        /// Replace the database with FILE instead of merging
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// List predictions (the live init map if running, else the cache); forced comms flagged
+    List,
+}
+
+#[derive(Parser)]
+struct BlackboxArgs {
+    /// Black box file (default: preserved unclean run if present, else the live ring)
+    #[arg(long)]
+    file: Option<String>,
+
+    /// Show only the newest N records
+    #[arg(long, default_value_t = blackbox::SLOTS)]
+    last: usize,
+}
+
+#[derive(Parser)]
+struct TopologyArgs {
+    /// Also show per-group L2 hit rates from the running scheduler
+    #[arg(long)]
+    live: bool,
+}
+
+#[derive(Parser)]
+struct InfoArgs {
+    /// One JSON object ({"system", "config"}) instead of the text block
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Parser)]
+struct StatusArgs {
+    /// Print Waybar's JSON ({"text", "alt", "class", "tooltip"}); class is the regime
+    #[arg(long)]
+    waybar: bool,
+
+    /// Text template, e.g. custom:"{regime} {p99_us}us" (fields: regime class p99 p99_us p99_ms
+    /// wake_us samples dispatches flags; {{ and }} for literal braces)
+    #[arg(long, value_name = "custom:TEMPLATE")]
+    format: Option<String>,
+
+    /// Control socket of the running scheduler (regime, pin and pause state)
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    socket: String,
+}
+
+#[derive(Parser)]
+struct GamemodeArgs {
+    /// Control socket of the running scheduler
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    socket: String,
+
+    #[command(subcommand)]
+    action: GamemodeAction,
+}
+
+#[derive(Subcommand)]
+enum GamemodeAction {
+    /// A game started: the first start saves the pin + held knobs it replaces
+    Start {
+        /// Game process (any thread): all its threads become LAT_CRITICAL
+        pid: Option<u32>,
+    },
+
+    /// A game ended: the last end restores what the first start replaced
+    End {
+        /// The PID given to `start` (omit if `start` had none)
+        pid: Option<u32>,
+    },
+}
+
+#[derive(Parser)]
+struct InstallArgs {
+    /// Print a gamemode.ini [custom] section calling `pandemonium gamemode start/end`
+    #[arg(long, required = true)]
+    gamemode: bool,
+
+    /// Control socket the snippet should use (omitted when it is the default)
+    #[arg(long, default_value = ctl::SOCKET_PATH)]
+    socket: String,
 }
 
 #[derive(Parser)]
@@ -98,12 +461,31 @@ struct StressWorkerArgs {
     cpu: u32,
 }
 
+#[derive(Parser)]
+struct IoWorkerArgs {
+    /// Scratch file to read or write
+    #[arg(long)]
+    file: String,
+
+    /// IO kind: read (O_DIRECT random reads) or fsync (pwrite + fdatasync)
+    #[arg(long)]
+    kind: String,
+
+    /// Bytes per IO op, a multiple of 4096
+    #[arg(long, default_value_t = pandemonium::iobench::DEFAULT_IO_SIZE)]
+    io_size: u64,
+}
+
 #[derive(Parser)]
 struct StartArgs {
     /// Run with --verbose --dump-log
     #[arg(long)]
     observe: bool,
 
+    /// Run for SECS seconds, then shut down cleanly (quick repro capture)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    duration: Option<u64>,
+
     /// Extra args forwarded to `pandemonium run`
     #[arg(last = true)]
     sched_args: Vec<String>,
@@ -127,135 +509,750 @@ struct BenchArgs {
     #[arg(long)]
     clean_cmd: Option<String>,
 
+    /// Probe wakeup period in microseconds (for --mode contention)
+    #[arg(long, default_value_t = cli::probe::DEFAULT_PERIOD_US)]
+    probe_period_us: u64,
+
+    /// Probe busy-work per period in microseconds (for --mode contention)
+    #[arg(long, default_value_t = 0)]
+    probe_spin_us: u64,
+
+    /// Concurrent probe threads (for --mode contention)
+    #[arg(long, default_value_t = 1)]
+    probe_threads: usize,
+
+    /// Bytes per IO op, a multiple of 4096 (for --mode io)
+    #[arg(long, default_value_t = pandemonium::iobench::DEFAULT_IO_SIZE)]
+    io_size: u64,
+
+    /// IO workers per kind, direct read and fsync write (for --mode io)
+    #[arg(long, default_value_t = pandemonium::iobench::DEFAULT_IO_DEPTH)]
+    io_depth: usize,
+
+    /// Build the release binary first (cargo build --release, logged to build.log)
+    #[arg(long)]
+    build: bool,
+
+    /// Directory for reports, probe samples and scheduler logs
+    /// (default: /tmp/pandemonium/bench-<timestamp>)
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    /// Newest bench-* directories kept under /tmp/pandemonium (older ones are removed)
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = pandemonium::benchout::DEFAULT_KEEP_LOGS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    keep_logs: u64,
+
     /// Extra args forwarded to `pandemonium run`
     #[arg(last = true)]
     sched_args: Vec<String>,
 }
 
 #[derive(Parser)]
-struct BenchRunArgs {
-    /// Benchmark mode
-    #[arg(long, value_enum)]
-    mode: cli::bench::BenchMode,
+struct BenchAnalyzeArgs {
+    /// Bench output directory (e.g. /tmp/pandemonium/bench-latest)
+    dir: PathBuf,
 
-    /// Command to benchmark (for --mode cmd)
-    #[arg(long)]
-    cmd: Option<String>,
+    /// Percentiles to report, comma-separated
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_values_t = pandemonium::benchanalyze::DEFAULT_PERCENTILES
+    )]
+    percentiles: Vec<f64>,
 
-    /// Number of iterations per phase
-    #[arg(long, default_value_t = 3)]
-    iterations: usize,
+    /// The two phases to test (default: every pair), e.g. --compare eevdf pandemonium
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    compare: Vec<String>,
+}
 
-    /// Clean command between iterations (for --mode cmd)
-    #[arg(long)]
-    clean_cmd: Option<String>,
+// THE RUN PATH'S FLAGS, RESOLVED FROM Cli: LISTS PARSED, FILES READ, BOUNDS
+// CHECKED. SHARED BY run_scheduler() AND bpf_only_loop().
+struct RunOptions {
+    verbose: bool,
+    telemetry_interval: u64,
+    dump_log: bool,
+    duration: Option<Duration>,
+    nr_cpus: Option<u64>,
+    scaling_cpuset: Option<PathBuf>,
+    no_adaptive: bool,
+    lightweight: bool,
+    reserved_cpus: Vec<u32>,
+    extra_compositors: Vec<String>,
+    ctl_socket: String,
+    takeover: bool,
+    force: bool,
+    strict: bool,
+    no_hybrid: bool,
+    procdb_limits: procdb::ProcDbLimits,
+    procdb_seeds: Vec<procdb::Seed>,
+    procdb_forces: Vec<procdb::Force>,
+    lag_scale: Option<u64>,
+    slice_bounds: tuning::SliceBounds,
+    self_probe: bool,
+    adaptive_classifier: bool,
+    slo_ns: Option<u64>,
+    pins: pandemonium::container::PinDir,
+    dbus_bus: Option<DbusBus>,
+    config_json: bool,
+    ignore_conflicts: bool,
+}
 
-    /// Extra args forwarded to `pandemonium run`
-    #[arg(last = true)]
-    sched_args: Vec<String>,
+impl RunOptions {
+    fn from_cli(cli: &Cli) -> Result<Self> {
+        // SYNTAX NOW; THE ONLINE-SET CHECK WAITS FOR run_scheduler()
+        let reserved_cpus = match &cli.reserved_cpus {
+            Some(list) => {
+                reserved::parse_reserved_cpus(list).map_err(|e| anyhow::anyhow!(e.message()))?
+            }
+            None => Vec::new(),
+        };
+        if cli.dbus.is_some() && !cfg!(feature = "dbus") {
+            anyhow::bail!(
+                "--dbus: BUILT WITHOUT THE dbus FEATURE (cargo build --release --features dbus)"
+            );
+        }
+        let mut pins =
+            pandemonium::container::PinDir::new(&cli.pin_dir).map_err(anyhow::Error::msg)?;
+        if let Some(group) = &cli.pin_group {
+            let gid = pandemonium::container::resolve_group(group).map_err(anyhow::Error::msg)?;
+            pins = pins.with_group(gid);
+        }
+        // RODATA IS WRITTEN ONCE AT LOAD: BAD BOUNDS FAIL NOW, NOT AFTER ATTACH
+        let slice_bounds = tuning::SliceBounds::from_us(cli.slice_min_us, cli.slice_max_us)
+            .map_err(anyhow::Error::msg)?;
+        // SEED TABLE IS RESOLVED BEFORE ATTACH: A BAD --seed-file FAILS NOW
+        let procdb_seeds = if cli.no_seed {
+            Vec::new()
+        } else if let Some(path) = &cli.seed_file {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("CANNOT READ SEED FILE {}: {}", path.display(), e))?;
+            procdb::parse_seed_file(&text)?
+        } else {
+            procdb::builtin_seeds()
+        };
+        // FORCED comms: FILE FIRST, FLAGS AFTER, SO A FLAG OVERRIDES THE FILE
+        let mut procdb_forces = match &cli.force_file {
+            Some(path) => {
+                let text = std::fs::read_to_string(path).map_err(|e| {
+                    anyhow::anyhow!("CANNOT READ FORCE FILE {}: {}", path.display(), e)
+                })?;
+                procdb::parse_force_file(&text)?
+            }
+            None => Vec::new(),
+        };
+        for (names, tier) in [(&cli.force_batch_comm, 0), (&cli.force_latcri_comm, 2)] {
+            for name in names {
+                procdb_forces.push(procdb::forced_comm(name, tier)?);
+            }
+        }
+        Ok(Self {
+            verbose: cli.verbose,
+            telemetry_interval: cli.telemetry_interval,
+            dump_log: cli.dump_log,
+            duration: cli.duration.map(Duration::from_secs),
+            nr_cpus: cli.nr_cpus,
+            scaling_cpuset: cli.scaling_cpuset.clone(),
+            no_adaptive: cli.no_adaptive,
+            lightweight: cli.lightweight,
+            reserved_cpus,
+            extra_compositors: cli.compositor.clone(),
+            ctl_socket: cli.ctl_socket.clone(),
+            takeover: cli.takeover,
+            force: cli.force,
+            strict: cli.strict,
+            no_hybrid: cli.no_hybrid,
+            procdb_limits: procdb::ProcDbLimits {
+                max_profiles: cli.procdb_max as usize,
+                stale_ticks: cli.procdb_stale_secs,
+                ops_per_tick: procdb::OPS_PER_TICK,
+            },
+            procdb_seeds,
+            procdb_forces,
+            lag_scale: cli.lag_scale,
+            slice_bounds,
+            self_probe: cli.self_probe,
+            adaptive_classifier: cli.adaptive_classifier,
+            slo_ns: cli.slo_p99_us.map(|us| us * 1000),
+            pins,
+            dbus_bus: cli.dbus,
+            config_json: cli.config_json,
+            ignore_conflicts: cli.ignore_conflicts,
+        })
+    }
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    // CLAP EXITS 2 ON A USAGE ERROR; HERE 2 MEANS A BPF ERROR EXIT
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        let status = if e.use_stderr() {
+            ExitStatus::Failed
+        } else {
+            ExitStatus::Clean
+        };
+        std::process::exit(status.code().into())
+    });
 
-    let verbose = cli.verbose;
-    let dump_log = cli.dump_log;
-    let nr_cpus = cli.nr_cpus;
-    let no_adaptive = cli.no_adaptive;
-    let extra_compositors = cli.compositor;
+    #[cfg(feature = "gate-hooks")]
+    WEDGE_SHUTDOWN.store(cli.wedge_shutdown, Ordering::Relaxed);
+    // --pin-dir IS THE ONLY RUN FLAG THE READERS SHARE. THE REST (SEED AND
+    // FORCE FILES, --pin-group) IS PARSED IN THE RUN ARM, SO A BAD RUN FILE
+    // NEVER FAILS ctl, bench-analyze OR self-test.
+    let pins = || pandemonium::container::PinDir::new(&cli.pin_dir).map_err(anyhow::Error::msg);
 
     match cli.command {
-        None => run_scheduler(verbose, dump_log, nr_cpus, no_adaptive, &extra_compositors),
-        Some(SubCmd::Check) => cli::check::run_check(),
-        Some(SubCmd::Probe(args)) => {
-            cli::probe::run_probe(args.death_pipe_fd);
+        None => {
+            let opts = RunOptions::from_cli(&cli)?;
+            let status = match run_scheduler(&opts) {
+                Ok(status) => status,
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    exitcode::status_of(&e)
+                }
+            };
+            // SERVICE MANAGERS AND SCRIPTS READ THE OUTCOME HERE (exitcode.rs)
+            if status != ExitStatus::Clean {
+                std::process::exit(status.code().into());
+            }
             Ok(())
         }
-        Some(SubCmd::Start(args)) => cli::run::run_start(args.observe, &args.sched_args),
-        Some(SubCmd::Dmesg) => cli::run::run_dmesg(),
-        Some(SubCmd::Bench(args)) => cli::bench::run_bench(
-            args.mode,
-            args.cmd.as_deref(),
-            args.iterations,
-            args.clean_cmd.as_deref(),
-            &args.sched_args,
-        ),
-        Some(SubCmd::BenchRun(args)) => cli::bench::run_bench_run(
-            args.mode,
-            args.cmd.as_deref(),
-            args.iterations,
-            args.clean_cmd.as_deref(),
-            &args.sched_args,
+        Some(SubCmd::Check(args)) if args.runtime => cli::check::run_runtime_check(&pins()?),
+        Some(SubCmd::Check(args)) => cli::check::run_check(&pins()?, args.json),
+        Some(SubCmd::SelfTest) => cli::check::run_self_test(),
+        Some(SubCmd::Probe(args)) => cli::probe::run_probe(
+            args.death_pipe_fd,
+            cli::probe::ProbeProfile {
+                period_us: args.period_us,
+                spin_us: args.spin_us,
+                threads: args.threads,
+                audio: args.audio,
+            },
+            args.output,
+            args.header,
         ),
-        Some(SubCmd::Test) => cli::test_gate::run_test_gate(),
+        Some(SubCmd::Start(args)) => {
+            cli::run::run_start(args.observe, args.duration, &args.sched_args)
+        }
+        Some(SubCmd::Dmesg(args)) => {
+            cli::run::run_dmesg(args.follow, args.since_boot, args.errors_only)
+        }
+        Some(SubCmd::Ctl(args)) => cli::ctl::run_ctl(&args.socket, &args.command),
+        Some(SubCmd::Procdb(args)) => match args.action {
+            ProcdbAction::Export { file } => cli::procdb::run_export(&args.socket, &file),
+            ProcdbAction::Import { file, replace } => {
+                cli::procdb::run_import(&args.socket, &file, replace)
+            }
+            ProcdbAction::List => cli::procdb::run_list(&pins()?, &args.socket),
+        },
+        Some(SubCmd::Blackbox(args)) => {
+            cli::blackbox::run_blackbox(args.file.as_deref(), args.last)
+        }
+        Some(SubCmd::Topology(args)) => cli::topology::run_topology(args.live, &pins()?),
+        Some(SubCmd::Status(args)) => {
+            cli::status::run_status(&pins()?, &args.socket, args.waybar, args.format.as_deref())
+        }
+        Some(SubCmd::Gamemode(args)) => match args.action {
+            GamemodeAction::Start { pid } => cli::gamemode::run_gamemode(&args.socket, true, pid),
+            GamemodeAction::End { pid } => cli::gamemode::run_gamemode(&args.socket, false, pid),
+        },
+        Some(SubCmd::Install(args)) => cli::gamemode::run_install_gamemode(&args.socket),
+        Some(SubCmd::Info(args)) => {
+            let system = sysinfo::SystemInfo::collect();
+            let config = sysinfo::RunConfig::collect(&system, None, None, None);
+            if args.json {
+                let json = pandemonium::benchout::Json::obj(vec![
+                    ("system", system.json()),
+                    ("config", config.json()),
+                ]);
+                print!("{}", json.render());
+            } else {
+                let mut lines = system.header_lines();
+                lines.extend(config.config_lines());
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            Ok(())
+        }
+        Some(SubCmd::Bench(args)) => run_bench_args(args, false),
+        Some(SubCmd::BenchRun(args)) => {
+            log_warn!("bench-run IS DEPRECATED: USE `pandemonium bench --build`");
+            run_bench_args(args, true)
+        }
+        Some(SubCmd::BenchAnalyze(args)) => {
+            cli::bench::run_bench_analyze(&args.dir, &args.percentiles, &args.compare)
+        }
+        Some(SubCmd::Test(args)) => match args.soak {
+            Some(hours) => cli::soak::run_soak(hours),
+            None => cli::test_gate::run_test_gate(),
+        },
         Some(SubCmd::StressWorker(args)) => {
             cli::stress::run_stress_worker(args.cpu);
             Ok(())
         }
+        Some(SubCmd::IoWorker(args)) => {
+            cli::io_worker::run_io_worker(&args.file, &args.kind, args.io_size)
+        }
     }
 }
 
+// --dbus: org.pandemonium.Scheduler1 FEEDING THE CONTROL SOCKET'S CHANNEL.
+// RETURNS WHERE THE MONITOR LOOP SENDS REGIME + P99 FOR THE PROPERTIES.
+#[cfg(feature = "dbus")]
+fn start_dbus(
+    bus: DbusBus,
+    ctl_server: Option<&control::CtlServer>,
+) -> Option<Sender<ctl::LiveStatus>> {
+    let Some(server) = ctl_server else {
+        log_warn!(
+            "--dbus: NO COMMAND CHANNEL (--no-adaptive OR NO CONTROL SOCKET), D-BUS DISABLED"
+        );
+        return None;
+    };
+    let session = bus == DbusBus::Session;
+    match dbus::serve(session, server.sender()) {
+        Ok(live) => {
+            log_info!(
+                "D-BUS: {} ON THE {} BUS",
+                dbus::BUS_NAME,
+                if session { "SESSION" } else { "SYSTEM" }
+            );
+            Some(live)
+        }
+        Err(e) => {
+            log_warn!("D-BUS DISABLED: {}", e);
+            None
+        }
+    }
+}
+
+// UNREACHABLE: main() REFUSES --dbus WITHOUT THE FEATURE
+#[cfg(not(feature = "dbus"))]
+fn start_dbus(
+    _bus: DbusBus,
+    _ctl_server: Option<&control::CtlServer>,
+) -> Option<Sender<ctl::LiveStatus>> {
+    None
+}
+
 // DEFAULT COMPOSITORS: BOOSTED TO LAT_CRITICAL VIA BPF MAP LOOKUP
 const DEFAULT_COMPOSITORS: &[&str] = &[
     "kwin", "gnome-shell", "mutter", "sway", "Hyprland",
     "picom", "weston", "labwc", "wayfire", "niri", "pandemonium",
 ];
 
-fn run_scheduler(
-    verbose: bool,
-    dump_log: bool,
-    nr_cpus: Option<u64>,
-    no_adaptive: bool,
-    extra_compositors: &[String],
-) -> Result<()> {
-    ctrlc::set_handler(move || {
-        SHUTDOWN.store(true, Ordering::Relaxed);
+// bench AND ITS DEPRECATED bench-run ALIAS: ONE PIPELINE, bench-run ALWAYS BUILDS
+fn run_bench_args(args: BenchArgs, build: bool) -> Result<()> {
+    cli::bench::run_bench(
+        args.mode,
+        args.cmd.as_deref(),
+        args.iterations,
+        args.clean_cmd.as_deref(),
+        cli::probe::ProbeProfile {
+            period_us: args.probe_period_us,
+            spin_us: args.probe_spin_us,
+            threads: args.probe_threads,
+            // XRUNS/MIN IS A STANDARD COLUMN OF EVERY PROBE-BASED BENCH
+            audio: true,
+        },
+        pandemonium::iobench::IoProfile {
+            size: args.io_size,
+            depth: args.io_depth,
+        },
+        &args.sched_args,
+        &cli::bench::BenchOutput {
+            build: build || args.build,
+            out_dir: args.out_dir,
+            keep_logs: args.keep_logs as usize,
+        },
+    )
+}
+
+fn run_scheduler(opts: &RunOptions) -> Result<ExitStatus> {
+    // THE ctrlc HANDLER RUNS ON ITS OWN THREAD: IT MAY WAIT AND EXIT
+    let pin_dir = opts.pins.dir().to_path_buf();
+    ctrlc::set_handler(move || match SIGINTS.press() {
+        Press::Shutdown => SHUTDOWN.store(true, Ordering::Relaxed),
+        Press::Force => {
+            SHUTDOWN.store(true, Ordering::Relaxed);
+            log_warn!(
+                "SECOND CTRL+C: FORCING EXIT (NO SUMMARY, {}s TO DETACH)",
+                FORCE_CLEANUP.as_secs()
+            );
+            std::thread::sleep(FORCE_CLEANUP);
+            // STILL HERE: THE MAIN THREAD IS WEDGED. EXIT CLOSES THE LINK FD
+            let removed = interrupt::remove_pins(&pin_dir);
+            log_warn!(
+                "FORCING EXIT: SHUTDOWN PATH HUNG, {} PIN(S) REMOVED, EXITING {}",
+                removed,
+                FORCE_EXIT_CODE
+            );
+            std::process::exit(FORCE_EXIT_CODE);
+        }
+        Press::Repeat => {}
     })?;
+    unsafe {
+        libc::signal(libc::SIGUSR1, on_sigusr1 as *const () as libc::sighandler_t);
+    }
 
-    let nr_cpus_display =
-        nr_cpus.unwrap_or_else(|| libbpf_rs::num_possible_cpus().unwrap_or(1) as u64);
-    let governor = std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor")
-        .unwrap_or_default()
-        .trim()
-        .to_string();
+    // --nr-cpus FEEDS nr_cpu_ids (RODATA) AND EVERY SCALING FORMULA: NEVER
+    // PAST THE POSSIBLE COUNT
+    let possible_cpus = libbpf_rs::num_possible_cpus().unwrap_or(1) as u64;
+    let requested_cpus = opts.nr_cpus;
+    let nr_cpus = requested_cpus.map(|requested| {
+        let (effective, warning) = pandemonium::hotplug::sanitize_nr_cpus(requested, possible_cpus);
+        if let Some(w) = warning {
+            log_warn!("{}", w.message());
+        }
+        effective
+    });
+    let nr_cpus_display = nr_cpus.unwrap_or(possible_cpus);
+    let governor = governor::read(Path::new(governor::CPU_ROOT)).label();
 
-    log_info!("PANDEMONIUM v{}", env!("CARGO_PKG_VERSION"));
+    let system = sysinfo::SystemInfo::collect();
+    for line in system.header_lines() {
+        log_info!("{}", line);
+    }
+    // EFFECTIVE FIRST; AN OVERRIDE ALSO SHOWS WHAT WAS ASKED FOR
+    let requested_label = match requested_cpus {
+        Some(r) => format!("REQUESTED {} OF {} POSSIBLE, ", r, possible_cpus),
+        None => String::new(),
+    };
     log_info!(
-        "CPUS: {} (governor: {})",
+        "CPUS: {} ({}governor: {})",
         nr_cpus_display,
-        if governor.is_empty() {
+        requested_label,
+        if governor == governor::NO_GOVERNOR {
             "unknown"
         } else {
             &governor
         }
     );
-    log_info!("VERBOSE: {}", verbose);
+    // CGROUP CPUSET: SCALE FOR THE CPUS THE WORKLOAD CAN RUN ON. DSQ
+    // COVERAGE (nr_cpu_ids) STAYS AT THE POSSIBLE COUNT. --nr-cpus WINS.
+    // POSSIBLE CPUS STAND IN WHEN sysfs HAS NO ONLINE LIST.
+    let online = hotplug::read_online().unwrap_or_else(|| (0..possible_cpus as u32).collect());
+    let cpuset = match &opts.scaling_cpuset {
+        Some(path) => {
+            let cpus = hotplug::read_cpu_list(path).ok_or_else(|| {
+                ExitStatus::PreflightFailed.wrap(anyhow::anyhow!(
+                    "--scaling-cpuset: NO CPU LIST IN {}",
+                    path.display()
+                ))
+            })?;
+            Some((path.to_path_buf(), cpus))
+        }
+        None if nr_cpus.is_none() => hotplug::detect_cpuset(&online),
+        None => None,
+    };
+    let scaling_cpus = cpuset
+        .as_ref()
+        .map(|(_, cpus)| hotplug::scaling_cpus(&online, Some(cpus)) as u64);
+    if let (Some((path, cpus)), Some(n)) = (&cpuset, scaling_cpus) {
+        log_info!(
+            "SCALING CPUS: {} OF {} (CPUSET {} FROM {})",
+            n,
+            nr_cpus_display,
+            cpulist::format(cpus),
+            path.display()
+        );
+    }
+    log_info!("VERBOSE: {}", opts.verbose);
+    if opts.lightweight {
+        log_info!(
+            "LIGHTWEIGHT: NO WAKEUP SAMPLING (NO P99, SLEEP HISTOGRAM OR PROCDB OBSERVATIONS)"
+        );
+    }
+    if !opts.reserved_cpus.is_empty() {
+        reserved::validate(&opts.reserved_cpus, &online)
+            .map_err(|e| ExitStatus::PreflightFailed.wrap(anyhow::anyhow!(e.message())))?;
+        log_info!(
+            "RESERVED CPUS: {} (NO DISPATCH EXCEPT TASKS AFFINED THERE)",
+            reserved::describe(&opts.reserved_cpus)
+        );
+    }
+    if let Some(rt) = pandemonium::container::ContainerSignals::read().runtime() {
+        log_info!("CONTAINER: {} (PIN DIR {})", rt, opts.pins.dir().display());
+    }
+
+    // ONE DAEMON AT A TIME: A SECOND ONE WOULD REPLACE THE FIRST'S PINS AND
+    // CONTROL SOCKET BEFORE FAILING AT ATTACH. HELD UNTIL EXIT.
+    let lock_path = Path::new(pandemonium::instance::LOCK_PATH);
+    let _instance = pandemonium::instance::acquire(lock_path, std::process::id(), opts.force)
+        .map_err(|e| ExitStatus::PreflightFailed.wrap(anyhow::anyhow!(e.message(lock_path))))?;
+
+    // REFUSE TO LOAD ON A KERNEL MISSING SOMETHING THE BPF PROGRAM NEEDS
+    // (KFUNCS, BTF, struct_ops, TASK STORAGE, sched_ext ITSELF). THE VERIFIER
+    // ERROR IS UNREADABLE; NAME THE REQUIREMENT AND ITS REMEDY INSTEAD.
+    let preflight = cli::check::preflight(pandemonium::preflight::Mode::Start, &opts.pins);
+    if let Some(msg) = preflight.blocking_message() {
+        return Err(ExitStatus::PreflightFailed.wrap(anyhow::anyhow!(msg)));
+    }
+    log_info!(
+        "PREFLIGHT: {} CHECKS, {} WARNINGS (`pandemonium check` LISTS THEM)",
+        preflight.entries.len(),
+        preflight.warnings().len()
+    );
+    let has_set_slice_kfunc = preflight.task_set_kfuncs;
+
+    // RENICING DAEMONS: WARN ONLY, THE USER DECIDES WHOSE POLICY WINS
+    if !opts.ignore_conflicts {
+        for found in pandemonium::conflicts::scan(Path::new("/proc")) {
+            log_warn!("{}", found.message());
+        }
+    }
+    log_info!(
+        "SLICE/VTIME WRITES: {}",
+        if has_set_slice_kfunc {
+            "scx_bpf_task_set_* KFUNCS"
+        } else {
+            "DIRECT p->scx FIELDS (LEGACY)"
+        }
+    );
 
-    let mut is_restart = false;
-    loop {
+    // HYBRID CORES: CLASSIFY ONCE, BEFORE LOAD (hybrid_enabled IS RODATA)
+    let hybrid_topo = if opts.no_hybrid {
+        None
+    } else {
+        let possible = libbpf_rs::num_possible_cpus().unwrap_or(1);
+        hybrid::HybridTopology::classify(&hybrid::read_capacities(possible))
+    };
+    match &hybrid_topo {
+        Some(h) => {
+            let (nr_fast, nr_slow) = h.counts();
+            log_info!(
+                "HYBRID CPUS: {} FAST [{}], {} SLOW (LAT_CRITICAL PREFERS FAST)",
+                nr_fast,
+                cpulist::format(&h.fast_cpu_list()),
+                nr_slow
+            );
+        }
+        None if opts.no_hybrid => log_info!("HYBRID CPUS: DISABLED (--no-hybrid)"),
+        None => {}
+    }
+
+    // ANOTHER SCHED_EXT SCHEDULER ATTACHED? NAME IT INSTEAD OF FAILING IN
+    // attach_struct_ops() WITH A BARE ERRNO
+    pandemonium::attach::ensure_free(opts.takeover, |ops, elapsed| {
+        log_info!(
+            "WAITING FOR '{}' TO DETACH ({}s/{}s)",
+            ops,
+            elapsed.as_secs(),
+            pandemonium::attach::TAKEOVER_TIMEOUT.as_secs()
+        );
+    })
+    .map_err(|e| ExitStatus::PreflightFailed.wrap(e))?;
+
+    // CONTROL SOCKET OUTLIVES RESTARTS; THE MONITOR LOOP IS ITS ONLY CONSUMER
+    let ctl_server = if opts.no_adaptive {
+        None
+    } else {
+        match control::CtlServer::bind(&opts.ctl_socket, opts.pins.group()) {
+            Ok(s) => {
+                log_info!("CONTROL SOCKET: {}", opts.ctl_socket);
+                Some(s)
+            }
+            Err(e) => {
+                log_warn!("CONTROL SOCKET DISABLED: {}", e);
+                None
+            }
+        }
+    };
+    // DROPPING THE SENDER STOPS THE D-BUS THREAD AND RELEASES THE NAME
+    let dbus_live = opts
+        .dbus_bus
+        .and_then(|bus| start_dbus(bus, ctl_server.as_ref()));
+
+    // BLACK BOX: MOVE AN UNCLEAN PREVIOUS RUN ASIDE, THEN START A FRESH RING
+    let bb_path = Path::new(blackbox::BLACKBOX_PATH);
+    let bb_unclean = Path::new(blackbox::BLACKBOX_UNCLEAN_PATH);
+    match blackbox::preserve_if_unclean(bb_path, bb_unclean) {
+        Ok(Some(h)) => log_warn!(
+            "PREVIOUS RUN (PID {}) ENDED UNCLEANLY: BLACKBOX SAVED TO {}. RUN `pandemonium blackbox` TO DECODE",
+            h.pid,
+            bb_unclean.display()
+        ),
+        Ok(None) => {}
+        Err(e) => log_warn!("BLACKBOX PRESERVE FAILED: {}", e),
+    }
+    let mut blackbox = match blackbox::BlackBox::create(bb_path) {
+        Ok(bb) => Some(bb),
+        Err(e) => {
+            log_warn!("BLACKBOX DISABLED: {}", e);
+            None
+        }
+    };
+
+    // MEMLOCK PREFLIGHT: SIZE THE DECLARED MAPS, RAISE THE LIMIT BEFORE ANY
+    // MAP IS CREATED. PER-CPU MAPS SCALE WITH POSSIBLE CPUS, NOT --nr-cpus.
+    let map_bytes = memlock::total_bytes(memlock::MAPS, possible_cpus);
+    let memlock_limit = memlock::raise_limit();
+    if opts.verbose {
+        log_info!("BPF MAP FOOTPRINT:");
+        for line in memlock::format_table(memlock::MAPS, possible_cpus) {
+            log_info!("{}", line);
+        }
+    }
+    match memlock_limit {
+        None => log_info!("RLIMIT_MEMLOCK: unlimited (maps ~{} KB)", map_bytes / 1024),
+        Some(limit) if limit < map_bytes => log_warn!(
+            "RLIMIT_MEMLOCK {} KB < ESTIMATED MAP FOOTPRINT {} KB (COULD NOT RAISE)",
+            limit / 1024,
+            map_bytes / 1024
+        ),
+        Some(limit) => log_info!(
+            "RLIMIT_MEMLOCK: {} KB (maps ~{} KB)",
+            limit / 1024,
+            map_bytes / 1024
+        ),
+    }
+
+    // MAP ABI SELF-TEST: ITS OWN LOAD, BEFORE THE ONE THAT ATTACHES. A
+    // MISMATCH WOULD FAIL init() ANYWAY; THIS NAMES EVERY MAP THAT DIFFERS
+    if opts.verbose {
+        log_info!("MAP ABI SELF-TEST:");
+        let failed = cli::check::report_self_test()
+            .map_err(|e| ExitStatus::AttachFailed.wrap(e.context("MAP ABI SELF-TEST")))?;
+        if failed > 0 {
+            return Err(ExitStatus::AttachFailed.wrap(anyhow::anyhow!(
+                "MAP ABI SELF-TEST: {} MAP(S) DIFFER FROM THEIR RUST MIRRORS",
+                failed
+            )));
+        }
+    }
+
+    // SELF-PROBE OUTLIVES RESTARTS: EACH MONITOR LOOP BASELINES ITS OWN SNAPSHOT
+    let self_probe = if opts.self_probe {
+        let sp = cli::probe::SelfProbe::spawn()?;
+        log_info!(
+            "SELF-PROBE: {}us SLEEP/WAKE AS '{}' (NO BOOST)",
+            probe::SELF_PROBE_PERIOD_US,
+            probe::SELF_PROBE_COMM
+        );
+        Some(sp)
+    } else {
+        None
+    };
+    let probe_hist = self_probe.as_ref().map(|p| &*p.hist);
+    let monitor = adaptive::MonitorConfig {
+        shutdown: &SHUTDOWN,
+        diag_requested: &DIAG_REQUESTED,
+        verbose: opts.verbose,
+        telemetry_interval: opts.telemetry_interval,
+        nr_cpus: nr_cpus_display,
+        track_hotplug: nr_cpus.is_none(),
+        scaling_cpuset: cpuset.as_ref().map(|(_, cpus)| cpus.as_slice()),
+        ctl_rx: ctl_server.as_ref().map(|s| &s.rx),
+        live_tx: dbus_live.as_ref(),
+        probe_hist,
+        procdb_limits: opts.procdb_limits,
+        procdb_seeds: &opts.procdb_seeds,
+        procdb_forces: &opts.procdb_forces,
+        lag_scale: opts.lag_scale,
+        adaptive_classifier: opts.adaptive_classifier,
+        slo_ns: opts.slo_ns,
+    };
+
+    let mode = if opts.no_adaptive {
+        sysinfo::MODE_BPF_ONLY
+    } else {
+        sysinfo::MODE_ADAPTIVE
+    };
+    let mut run_config: Option<sysinfo::RunConfig> = None;
+    // --duration COUNTS FROM THE FIRST ATTACH AND SPANS RESTARTS
+    let mut deadline: Option<Instant> = None;
+    // restart.rs: THE WAIT BEFORE THE NEXT ATTACH, GROWING IN A STORM
+    let mut restarts = RestartGuard::new(RestartPolicy::default());
+    let restart_clock = Instant::now();
+    let mut restart_wait: Option<Duration> = None;
+    let mut clean_exit = false;
+    // THE LAST ATTACH DECIDES: A RESTARTED RUN THAT ENDS CLEANLY IS CLEAN
+    let status = loop {
         // ON RESTART, WAIT FOR KERNEL STRUCT_OPS CLEANUP.
         // DETACH IS ASYNCHRONOUS -- UNDER HEAVY LOAD (12C SATURATED),
         // THE KERNEL NEEDS TIME TO FULLY UNREGISTER THE OLD SCHEDULER.
-        if is_restart {
-            std::thread::sleep(Duration::from_secs(2));
+        // A STORM'S LONGER WAITS STILL END AT CTRL+C.
+        if let Some(wait) = restart_wait.take() {
+            let until = Instant::now() + wait;
+            while !SHUTDOWN.load(Ordering::Relaxed) {
+                let left = until.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                std::thread::sleep(left.min(Duration::from_millis(100)));
+            }
+            if SHUTDOWN.load(Ordering::Relaxed) {
+                clean_exit = true;
+                break ExitStatus::Clean;
+            }
         }
 
         let mut open_object = MaybeUninit::uninit();
-        let mut sched = Scheduler::init(&mut open_object, nr_cpus)?;
+        let mut sched = Scheduler::init(
+            &mut open_object,
+            nr_cpus,
+            has_set_slice_kfunc,
+            hybrid_topo.clone(),
+            opts.slice_bounds,
+            opts.lightweight,
+            opts.reserved_cpus.clone(),
+            opts.pins.clone(),
+        )
+        .map_err(|e| {
+            if !memlock::is_memlock_error(&format!("{:#}", e)) {
+                return e;
+            }
+            // THE NUMBERS GO IN THE BUG REPORT
+            log_error!("BPF LOAD FAILED; MAP FOOTPRINT:");
+            for line in memlock::format_table(memlock::MAPS, possible_cpus) {
+                log_error!("{}", line);
+            }
+            match memlock_limit {
+                Some(limit) => e.context(format!(
+                    "RLIMIT_MEMLOCK IS {} KB AND COULD NOT BE RAISED (RUN AS ROOT OR RAISE `ulimit -l`)",
+                    limit / 1024
+                )),
+                None => e,
+            }
+        })
+        .map_err(|e| ExitStatus::AttachFailed.wrap(e))?;
 
         // POPULATE CACHE TOPOLOGY MAP AT STARTUP
-        match topology::CpuTopology::detect(nr_cpus_display as usize) {
-            Ok(topo) => {
-                topo.log_summary();
-                if let Err(e) = topo.populate_bpf_map(&sched) {
-                    log_warn!("CACHE TOPOLOGY MAP WRITE FAILED: {}", e);
-                }
-                if let Err(e) = topo.populate_l2_siblings_map(&sched) {
-                    log_warn!("L2 SIBLINGS MAP WRITE FAILED: {}", e);
+        topology::CpuTopology::apply(&sched, nr_cpus_display as usize);
+
+        // [CONFIG] ONCE, AFTER THE FIRST ATTACH: BPF init() HAS JUST WRITTEN
+        // ITS DEFAULT KNOBS. THE SIGUSR1 DUMP REPEATS THIS BLOCK.
+        let run_config = run_config.get_or_insert_with(|| {
+            let config = sysinfo::RunConfig {
+                reserved_cpus: Some(sched.reserved.clone()),
+                ..sysinfo::RunConfig::collect(
+                    &system,
+                    nr_cpus.or(scaling_cpus),
+                    Some(sysinfo::RunMode {
+                        mode,
+                        lightweight: sched.lightweight,
+                    }),
+                    Some(sched.read_tuning_knobs().values()),
+                )
+            };
+            if opts.config_json {
+                log_info!(
+                    "{} {}",
+                    sysinfo::CONFIG_PREFIX,
+                    config.json().render_line().trim_end()
+                );
+            } else {
+                for line in config.config_lines() {
+                    log_info!("{}", line);
                 }
             }
-            Err(e) => log_warn!("CACHE TOPOLOGY DETECT FAILED: {}", e),
-        }
+            config
+        });
 
         // POPULATE COMPOSITOR MAP: DEFAULT + USER-SUPPLIED NAMES
         for name in DEFAULT_COMPOSITORS {
@@ -263,177 +1260,345 @@ fn run_scheduler(
                 log_warn!("COMPOSITOR MAP WRITE FAILED: {} ({})", name, e);
             }
         }
-        for name in extra_compositors {
+        for name in &opts.extra_compositors {
             if let Err(e) = sched.write_compositor(name) {
                 log_warn!("COMPOSITOR MAP WRITE FAILED: {} ({})", name, e);
             }
         }
 
-        let should_restart = if no_adaptive {
+        if let Some(d) = opts.duration {
+            if deadline.is_none() {
+                log_info!("DURATION: {}s, THEN A CLEAN SHUTDOWN", d.as_secs());
+            }
+            deadline.get_or_insert_with(|| Instant::now() + d);
+        }
+
+        let exit_info = if opts.no_adaptive {
             // BPF-ONLY MODE: SCHEDULER RUNS WITH DEFAULT KNOBS, NO RUST TUNING
-            // STILL PRINTS STATS SO BENCHMARKS GET TELEMETRY FOR BOTH PHASES
             log_info!("PANDEMONIUM IS ACTIVE (BPF ONLY, CTRL+C TO EXIT)");
-            let mut prev = scheduler::PandemoniumStats::default();
-            while !SHUTDOWN.load(Ordering::Relaxed) && !sched.exited() {
-                std::thread::sleep(Duration::from_secs(1));
-
-                let stats = sched.read_stats();
-
-                let delta_d = stats.nr_dispatches.wrapping_sub(prev.nr_dispatches);
-                let delta_idle = stats.nr_idle_hits.wrapping_sub(prev.nr_idle_hits);
-                let delta_shared = stats.nr_shared.wrapping_sub(prev.nr_shared);
-                let delta_preempt = stats.nr_preempt.wrapping_sub(prev.nr_preempt);
-                let delta_keep = stats.nr_keep_running.wrapping_sub(prev.nr_keep_running);
-                let delta_wake_sum = stats.wake_lat_sum.wrapping_sub(prev.wake_lat_sum);
-                let delta_wake_samples = stats.wake_lat_samples.wrapping_sub(prev.wake_lat_samples);
-                let delta_hard = stats.nr_hard_kicks.wrapping_sub(prev.nr_hard_kicks);
-                let delta_soft = stats.nr_soft_kicks.wrapping_sub(prev.nr_soft_kicks);
-                let delta_enq_wake = stats.nr_enq_wakeup.wrapping_sub(prev.nr_enq_wakeup);
-                let delta_enq_requeue = stats.nr_enq_requeue.wrapping_sub(prev.nr_enq_requeue);
-                let wake_avg_us = if delta_wake_samples > 0 {
-                    delta_wake_sum / delta_wake_samples / 1000
-                } else {
-                    0
-                };
-
-                let d_idle_sum = stats.wake_lat_idle_sum.wrapping_sub(prev.wake_lat_idle_sum);
-                let d_idle_cnt = stats.wake_lat_idle_cnt.wrapping_sub(prev.wake_lat_idle_cnt);
-                let d_kick_sum = stats.wake_lat_kick_sum.wrapping_sub(prev.wake_lat_kick_sum);
-                let d_kick_cnt = stats.wake_lat_kick_cnt.wrapping_sub(prev.wake_lat_kick_cnt);
-                let lat_idle_us = if d_idle_cnt > 0 {
-                    d_idle_sum / d_idle_cnt / 1000
-                } else {
-                    0
-                };
-                let lat_kick_us = if d_kick_cnt > 0 {
-                    d_kick_sum / d_kick_cnt / 1000
-                } else {
-                    0
-                };
-                let delta_procdb = stats.nr_procdb_hits.wrapping_sub(prev.nr_procdb_hits);
-                let delta_reenq = stats.nr_reenqueue.wrapping_sub(prev.nr_reenqueue);
-
-                // L2 CACHE AFFINITY DELTAS
-                let dl2_hb = stats.nr_l2_hit_batch.wrapping_sub(prev.nr_l2_hit_batch);
-                let dl2_mb = stats.nr_l2_miss_batch.wrapping_sub(prev.nr_l2_miss_batch);
-                let dl2_hi = stats
-                    .nr_l2_hit_interactive
-                    .wrapping_sub(prev.nr_l2_hit_interactive);
-                let dl2_mi = stats
-                    .nr_l2_miss_interactive
-                    .wrapping_sub(prev.nr_l2_miss_interactive);
-                let dl2_hl = stats
-                    .nr_l2_hit_lat_crit
-                    .wrapping_sub(prev.nr_l2_hit_lat_crit);
-                let dl2_ml = stats
-                    .nr_l2_miss_lat_crit
-                    .wrapping_sub(prev.nr_l2_miss_lat_crit);
-                let l2_pct_b = if dl2_hb + dl2_mb > 0 {
-                    dl2_hb * 100 / (dl2_hb + dl2_mb)
-                } else {
-                    0
-                };
-                let l2_pct_i = if dl2_hi + dl2_mi > 0 {
-                    dl2_hi * 100 / (dl2_hi + dl2_mi)
-                } else {
-                    0
-                };
-                let l2_pct_l = if dl2_hl + dl2_ml > 0 {
-                    dl2_hl * 100 / (dl2_hl + dl2_ml)
-                } else {
-                    0
-                };
-
-                let idle_pct = if delta_d > 0 {
-                    delta_idle * 100 / delta_d
-                } else {
-                    0
-                };
-
-                let sojourn_ms = stats.batch_sojourn_ns / 1_000_000;
-                let delta_burst = stats.burst_mode_active.wrapping_sub(prev.burst_mode_active);
-                let burst_label = if delta_burst > 0 { " BURST" } else { "" };
-                let longrun_label = if stats.longrun_mode_active > 0 { " LONGRUN" } else { "" };
-
-                if verbose {
-                    println!(
-                        "d/s: {:<8} idle: {}% shared: {:<6} preempt: {:<4} keep: {:<4} kick: H={:<4} S={:<4} enq: W={:<4} R={:<4} wake: {}us lat_idle: {}us lat_kick: {}us procdb: {} reenq: {} sjrn: {}ms l2: B={}% I={}% L={}% [BPF{}{}]",
-                        delta_d, idle_pct, delta_shared, delta_preempt, delta_keep,
-                        delta_hard, delta_soft, delta_enq_wake, delta_enq_requeue,
-                        wake_avg_us, lat_idle_us, lat_kick_us, delta_procdb,
-                        delta_reenq, sojourn_ms, l2_pct_b, l2_pct_i, l2_pct_l,
-                        burst_label, longrun_label,
-                    );
+            bpf_only_loop(
+                &mut sched,
+                opts,
+                nr_cpus_display,
+                blackbox.as_mut(),
+                probe_hist,
+                deadline,
+            )
+        } else {
+            // ADAPTIVE MODE: BPF + SINGLE-THREAD MONITOR LOOP
+            log_info!("PANDEMONIUM IS ACTIVE (CTRL+C TO EXIT)");
+            match adaptive::monitor_loop(
+                &mut sched,
+                &monitor,
+                blackbox.as_mut(),
+                run_config,
+                restarts.summary(),
+                deadline,
+            ) {
+                Ok(info) => info,
+                Err(e) if opts.strict => return Err(e),
+                Err(e) => {
+                    // THE BPF SCHEDULER IS STILL ATTACHED AND SCHEDULING:
+                    // LOSING THE TUNING LOOP MUST NOT LOSE THE SCHEDULER
+                    log_warn!("{}", "=".repeat(60));
+                    log_warn!("ADAPTIVE LAYER FAILED: {:#}", e);
+                    log_warn!("FALLING BACK TO BPF-ONLY MODE (--strict TO FAIL INSTEAD)");
+                    log_warn!("{}", "=".repeat(60));
+                    bpf_only_loop(
+                        &mut sched,
+                        opts,
+                        nr_cpus_display,
+                        blackbox.as_mut(),
+                        probe_hist,
+                        deadline,
+                    )
                 }
+            }
+        };
 
-                sched.log.snapshot(
-                    delta_d,
-                    delta_idle,
-                    delta_shared,
-                    delta_preempt,
-                    delta_keep,
-                    wake_avg_us,
-                    delta_hard,
-                    delta_soft,
-                    lat_idle_us,
-                    lat_kick_us,
-                );
-
-                prev = stats;
+        // --wedge-shutdown: NEVER RETURN; THE HANDLER'S FORCED EXIT ENDS IT
+        #[cfg(feature = "gate-hooks")]
+        if WEDGE_SHUTDOWN.load(Ordering::Relaxed) && SHUTDOWN.load(Ordering::Relaxed) {
+            log_warn!("--wedge-shutdown: HANGING THE SHUTDOWN PATH");
+            loop {
+                std::thread::sleep(Duration::from_secs(1));
             }
+        }
 
-            // KNOBS SUMMARY: CAPTURED BY TEST HARNESS FOR ARCHIVE
-            let knobs = sched.read_tuning_knobs();
-            let final_stats = sched.read_stats();
-            let l2_total_b = final_stats.nr_l2_hit_batch + final_stats.nr_l2_miss_batch;
-            let l2_total_i = final_stats.nr_l2_hit_interactive + final_stats.nr_l2_miss_interactive;
-            let l2_total_l = final_stats.nr_l2_hit_lat_crit + final_stats.nr_l2_miss_lat_crit;
-            let l2_cum_b = if l2_total_b > 0 {
-                final_stats.nr_l2_hit_batch * 100 / l2_total_b
-            } else {
-                0
-            };
-            let l2_cum_i = if l2_total_i > 0 {
-                final_stats.nr_l2_hit_interactive * 100 / l2_total_i
-            } else {
-                0
-            };
-            let l2_cum_l = if l2_total_l > 0 {
-                final_stats.nr_l2_hit_lat_crit * 100 / l2_total_l
-            } else {
-                0
-            };
-            println!(
-                "[KNOBS] regime=BPF slice_ns={} batch_ns={} preempt_ns={} demotion_ns={} lag={} l2_hit=B:{}%/I:{}%/L:{}%",
-                knobs.slice_ns, knobs.batch_slice_ns,
-                knobs.preempt_thresh_ns, knobs.cpu_bound_thresh_ns,
-                knobs.lag_scale, l2_cum_b, l2_cum_i, l2_cum_l,
+        // SECOND CTRL+C: NO SUMMARY, NO DUMP. THE DROP UNPINS, THEN THE LINK
+        // DETACHES; process::exit WOULD SKIP BOTH IF IT CAME FIRST
+        if SIGINTS.forced() {
+            drop(sched);
+            log_warn!(
+                "FORCING EXIT: SCHEDULER DETACHED, EXITING {}",
+                FORCE_EXIT_CODE
             );
+            std::process::exit(FORCE_EXIT_CODE);
+        }
 
-            sched.read_exit_info()
-        } else {
-            // ADAPTIVE MODE: BPF + SINGLE-THREAD MONITOR LOOP
-            log_info!("PANDEMONIUM IS ACTIVE (CTRL+C TO EXIT)");
-            adaptive::monitor_loop(&mut sched, &SHUTDOWN, verbose, nr_cpus_display)?
-        };
+        for line in exit_info.log_lines() {
+            log_warn!("{}", line);
+        }
+
+        // KERNEL-INITIATED EXIT: KEEP THIS RING BEFORE A RESTART OVERWRITES IT
+        if !SHUTDOWN.load(Ordering::Relaxed) {
+            if let Some(bb) = blackbox.as_ref() {
+                match bb.save_copy(bb_unclean) {
+                    Ok(()) => log_warn!(
+                        "BPF SCHEDULER EXITED: BLACKBOX SAVED TO {}",
+                        bb_unclean.display()
+                    ),
+                    Err(e) => log_warn!("BLACKBOX SAVE FAILED: {}", e),
+                }
+            }
+        }
 
         log_info!("PANDEMONIUM IS SHUTTING DOWN");
 
-        if dump_log {
+        if opts.dump_log {
             sched.log.dump();
         }
-        sched.log.summary();
+        let cumulative = sched.read_stats();
+        let lag = sched.read_lag_hist();
+        sched.log.summary(&event::RunTotals {
+            hard_kicks: cumulative.nr_hard_kicks,
+            soft_kicks: cumulative.nr_soft_kicks,
+            wake_hist: sched.read_wake_lat_hist(),
+            lag_deadlines: lag.total(),
+            lag_clamps: lag.clamped,
+            lightweight: sched.lightweight,
+        });
 
-        if !should_restart || SHUTDOWN.load(Ordering::Relaxed) {
-            break;
+        if !exit_info.wants_restart || SHUTDOWN.load(Ordering::Relaxed) {
+            clean_exit = SHUTDOWN.load(Ordering::Relaxed);
+            break exit_info.status();
+        }
+
+        let reason = if exit_info.reason.is_empty() {
+            exit_info.describe_code()
+        } else {
+            exit_info.reason.clone()
+        };
+        let decision = restarts.record(restart_clock.elapsed().as_nanos() as u64, &reason);
+        match decision {
+            RestartDecision::Wait(wait) => {
+                if restarts.storming() {
+                    log_warn!("{}", restarts.storm_message(decision));
+                }
+                restart_wait = Some(wait);
+            }
+            RestartDecision::GiveUp { .. } => {
+                return Err(ExitStatus::BpfError.wrap(anyhow::anyhow!(
+                    "{} -- LAST {}",
+                    restarts.storm_message(decision),
+                    exit_info.log_lines().join("; ")
+                )));
+            }
         }
 
         // RESET SHUTDOWN FOR RESTART
         SHUTDOWN.store(false, Ordering::Relaxed);
-        log_info!("RESTARTING PANDEMONIUM...");
-        is_restart = true;
+        SIGINTS.reset();
+        log_info!(
+            "RESTARTING PANDEMONIUM ({} THIS RUN)...",
+            restarts.summary().count
+        );
+    };
+
+    if clean_exit {
+        if let Some(bb) = blackbox.as_mut() {
+            bb.mark_clean();
+        }
+    }
+
+    if status == ExitStatus::Clean {
+        log_info!("Shutdown complete");
+    } else {
+        log_warn!(
+            "Shutdown complete: exit {} ({})",
+            status.code(),
+            status.label()
+        );
+    }
+    Ok(status)
+}
+
+// BPF-ONLY LOOP: SCHEDULER RUNS WITH DEFAULT KNOBS, NO RUST TUNING.
+// STILL PRINTS STATS SO BENCHMARKS GET TELEMETRY FOR BOTH PHASES.
+// ALSO THE FALLBACK WHEN THE ADAPTIVE LAYER FAILS (WITHOUT --strict).
+// nr_cpus IS THE RESOLVED COUNT, NOT opts.nr_cpus (THE --nr-cpus REQUEST)
+fn bpf_only_loop(
+    sched: &mut Scheduler,
+    opts: &RunOptions,
+    nr_cpus: u64,
+    mut blackbox: Option<&mut blackbox::BlackBox>,
+    probe_hist: Option<&probe::SharedLatencyHist>,
+    deadline: Option<Instant>,
+) -> ExitInfo {
+    // --lag-scale: THE ONE KNOB WRITE WITHOUT THE ADAPTIVE LAYER
+    if let Some(n) = opts.lag_scale {
+        let knobs = tuning::TuningKnobs {
+            lag_scale: n,
+            ..sched.read_tuning_knobs()
+        };
+        if let Err(e) = sched.write_tuning_knobs(&knobs) {
+            log_warn!("--lag-scale {} NOT APPLIED: {}", n, e);
+        }
+    }
+    let mut prev = stats::PandemoniumStats::default();
+    let mut prev_lag = tuning::LagHist::default();
+    let bpf_slice_ns = sched.read_tuning_knobs().slice_ns;
+    let mut online_watch = hotplug::read_online().map(hotplug::HotplugWatch::new);
+    let mut qwatch = tuning::QueueDepthWatch::default();
+    let mut bwatch = tuning::BacklogWatch::default();
+    let mut probe_window = probe_hist.map(probe::ProbeWindow::new);
+    let mut hybrid_mon = hybrid::HybridMonitor::default();
+    let mut residency = residency::IdleResidency::default();
+    let mut rt_watch = rtsteal::RtWatch::new(Path::new("/proc"));
+    let gov_root = Path::new(governor::CPU_ROOT);
+    let mut gov_watch = governor::GovernorWatch::new(governor::read(gov_root));
+    let clock_origin = std::time::Instant::now();
+    let mut tick: u64 = 0;
+    while !SHUTDOWN.load(Ordering::Relaxed) && !sched.exited() {
+        if tuning::deadline_reached(deadline, Instant::now()) {
+            log_info!("DURATION ELAPSED");
+            SHUTDOWN.store(true, Ordering::Relaxed);
+            break;
+        }
+        residency.sample_window(
+            Duration::from_secs(1),
+            residency::SAMPLES_PER_TICK,
+            std::thread::sleep,
+            || clock_origin.elapsed().as_nanos() as u64,
+            || sched.read_idle_bitmap(),
+        );
+        tick += 1;
+
+        // CPU HOTPLUG: NO KNOBS TO RESCALE, BUT THE L2 MAPS GO STALE
+        if let Some(watch) = online_watch.as_mut() {
+            if let Some(t) = hotplug::read_online().and_then(|o| watch.update(o)) {
+                log_info!("CPU HOTPLUG: {}", t.describe());
+                topology::CpuTopology::apply(sched, nr_cpus as usize);
+            }
+        }
+        if let Some(change) = gov_watch.observe(governor::read(gov_root)) {
+            log_warn!("{}", change.message());
+            sched.log.governor_change(&change);
+        }
+        // REPORTED ONLY: THERE IS NO REGIME TO FEED WITHOUT THE ADAPTIVE LAYER
+        let online: Vec<u32> = online_watch
+            .as_ref()
+            .map_or_else(|| (0..nr_cpus as u32).collect(), |w| w.online().to_vec());
+        let resid = residency.take(&reserved::unreserved(&online, &sched.reserved));
+        let rt_pct = rt_watch.tick(
+            clock_origin.elapsed().as_nanos() as u64,
+            online.len() as u64,
+        );
+
+        // ONE stats_map READ PER TICK FEEDS THE TOTAL AND THE HYBRID MONITOR
+        let per_cpu = sched.read_stats_percpu();
+        let stats = stats::PandemoniumStats::total_except(&per_cpu, &sched.reserved);
+
+        // COMPUTE DELTAS
+        let delta = stats.delta(&prev);
+        if delta.reset {
+            log_warn!("STATS COUNTERS WENT BACKWARDS (BPF RESTART?): ZEROING THIS TICK");
+        }
+        let idle_pct = delta.idle_pct();
+        let hybrid_sample = sched.hybrid_tick(&mut hybrid_mon, &per_cpu);
+        let qdepth = sched.read_dsq_depth();
+        if qwatch.update(qdepth.total(), idle_pct, nr_cpus).stuck_onset {
+            log_error!(
+                "SHARED DSQ BACKLOG WITH IDLE CPUS: {} QUEUED (I={} B={}) AT idle={}% -- IDLE CPUS ARE NOT PULLING WORK",
+                qdepth.total(),
+                qdepth.interactive,
+                qdepth.batch,
+                idle_pct
+            );
+        }
+        let backlog = bwatch.update(delta.backlog_net(), delta.reset);
+        if backlog.warn_onset {
+            log_warn!(
+                "DISPATCH BACKLOG GROWING FOR {}+ TICKS: ~{} ENQUEUES NOT DISPATCHED -- DISPATCH MAY BE STALLING",
+                tuning::BACKLOG_WARN_TICKS,
+                backlog.backlog
+            );
+        }
+
+        // NO STABILITY SCORE WITHOUT THE ADAPTIVE LAYER: NEVER HIBERNATES
+        if opts.verbose && tuning::should_print_telemetry(tick, 0, opts.telemetry_interval) {
+            let knobs = sched.read_tuning_knobs();
+            let extras = telemetry::TelemetryExtras {
+                adaptive: None,
+                probe_p99_us: match (probe_hist, probe_window.as_mut()) {
+                    (Some(h), Some(w)) => Some(w.p99_since_last(h)),
+                    _ => None,
+                },
+                slo: None,
+                residency_pct: resid.as_ref().map(|r| r.idle_pct),
+                rt_pct,
+                slice_us: knobs.slice_ns / 1000,
+                batch_us: knobs.batch_slice_ns / 1000,
+                cls_high: knobs.lat_cri_thresh_high,
+                cls_low: knobs.lat_cri_thresh_low,
+                qdepth,
+                backlog: backlog.backlog,
+                backlog_warn: backlog.warn,
+                lightweight: sched.lightweight,
+                governor: Some(gov_watch.label().to_string()),
+            };
+            println!(
+                "{}",
+                telemetry::telemetry_tick(&delta, &stats, &extras).render()
+            );
+            if let Some((fast, slow)) = hybrid_sample {
+                println!("{}", hybrid::format_line(&fast, &slow));
+            }
+        }
+        // APPLIED LAG: THE BPF DEFAULT KNOB NEXT TO WHAT DEADLINES GOT (tick IS 1-BASED HERE)
+        if opts.verbose && diag::should_dump_stats(tick - 1) {
+            let lag = sched.read_lag_hist();
+            let knob = sched.read_tuning_knobs().lag_scale;
+            println!("{}", diag::format_lag_summary(knob, &lag.delta(&prev_lag)));
+            prev_lag = lag;
+            if let Some(r) = &resid {
+                println!("{}", r.format_line());
+            }
+        }
+
+        sched.log.paths.record(&delta);
+        sched.log.snapshot(event::TickCounters::from_delta(&delta));
+        if let (Some(bb), Some(snap)) = (blackbox.as_deref_mut(), sched.log.latest()) {
+            bb.record(blackbox::Record::snapshot(
+                snap,
+                0,
+                blackbox::REGIME_BPF_ONLY,
+                bpf_slice_ns,
+            ));
+            bb.sync(false);
+        }
+
+        prev = stats;
+    }
+
+    // KNOBS SUMMARY: CAPTURED BY TEST HARNESS FOR ARCHIVE
+    let knobs = sched.read_tuning_knobs();
+    let final_stats = sched.read_stats();
+    let [l2_cum_b, l2_cum_i, l2_cum_l] = final_stats.l2_hit_pct();
+    let writes = sched.knob_write_counts();
+    println!(
+        "[KNOBS] regime=BPF slice_ns={} slice_min_ns={} slice_max_ns={} batch_ns={} preempt_ns={} demotion_ns={} lag={} l2_hit=B:{}%/I:{}%/L:{}% qdepth_max={} backlog_max={} knob_writes={} knob_skips={}",
+        knobs.slice_ns, sched.slice_bounds.min_ns, sched.slice_bounds.max_ns,
+        knobs.batch_slice_ns, knobs.preempt_thresh_ns, knobs.cpu_bound_thresh_ns,
+        knobs.lag_scale, l2_cum_b, l2_cum_i, l2_cum_l, qwatch.max(), bwatch.max(),
+        writes.written, writes.skipped,
+    );
+    if let (Some(h), Some(w)) = (probe_hist, probe_window.as_ref()) {
+        let bpf_p99_us = tuning::pooled_p99_ns(&sched.read_wake_lat_hist()) / 1000;
+        let summary =
+            probe::SelfProbeSummary::new(&w.since_start(h), final_stats.wake_avg_us(), bpf_p99_us);
+        println!("{}", summary.line());
     }
 
-    log_info!("Shutdown complete");
-    Ok(())
+    sched.read_exit_info()
 }
@@ -0,0 +1,294 @@
+// PANDEMONIUM MEMLOCK PREFLIGHT
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// ON KERNELS OR CGROUPS WITHOUT MEMCG ACCOUNTING FOR BPF, EVERY MAP IS
+// CHARGED AGAINST RLIMIT_MEMLOCK AND A LOW DISTRO DEFAULT (8MB, 64KB IN
+// SOME CONTAINERS) TURNS open_skel.load() INTO A BARE EPERM/ENOMEM.
+// ESTIMATE THE FOOTPRINT OF THE MAPS main.bpf.c DECLARES, RAISE THE LIMIT
+// BEFORE LOAD, AND KEEP THE NUMBERS AROUND FOR THE ERROR PATH.
+//
+// THE ESTIMATE MIRRORS KERNEL ALLOCATION SHAPES (8-BYTE-ALIGNED VALUES,
+// PER-CPU COPIES, PREALLOCATED HASH ELEMENTS + BUCKETS, PAGE ROUNDING).
+// IT EXCLUDES .bss/.data/.rodata AND PROGRAM TEXT: TREAT IT AS A FLOOR.
+
+pub const CPU_POSSIBLE_PATH: &str = "/sys/devices/system/cpu/possible";
+
+const PAGE_SIZE: u64 = 4096;
+const HTAB_ELEM_OVERHEAD: u64 = 48; // struct htab_elem HEADER
+const HTAB_BUCKET_SIZE: u64 = 16; // struct bucket
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MapKind {
+    Array,
+    PercpuArray,
+    Hash,
+    LruHash,
+    TaskStorage, // BPF_F_NO_PREALLOC: GROWS PER TASK, NOTHING UP FRONT
+}
+
+impl MapKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Array => "array",
+            Self::PercpuArray => "percpu_array",
+            Self::Hash => "hash",
+            Self::LruHash => "lru_hash",
+            Self::TaskStorage => "task_storage",
+        }
+    }
+}
+
+pub struct MapSpec {
+    pub name: &'static str,
+    pub kind: MapKind,
+    pub key_size: u64,
+    pub value_size: u64,
+    pub max_entries: u64,
+}
+
+// EVERY SEC(".maps") IN main.bpf.c (SIZES FROM intf.h / scheduler.rs ABI ASSERTS)
+pub const MAPS: &[MapSpec] = &[
+    MapSpec {
+        name: "tuning_knobs_map",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 80,
+        max_entries: 1,
+    },
+    MapSpec {
+        name: "stats_map",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 248,
+        max_entries: 1,
+    },
+    MapSpec {
+        name: "dsq_depth_map",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 16,
+        max_entries: 1,
+    },
+    MapSpec {
+        name: "cpu_busy_map",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 8,
+        max_entries: 1,
+    },
+    MapSpec {
+        name: "reserved_cpus_map",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 128,
+        max_entries: 1,
+    },
+    MapSpec {
+        name: "cache_domain",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 4,
+        max_entries: 1024,
+    },
+    MapSpec {
+        name: "task_class_observe",
+        kind: MapKind::LruHash,
+        key_size: 16,
+        value_size: 40,
+        max_entries: 512,
+    },
+    MapSpec {
+        name: "task_class_init",
+        kind: MapKind::Hash,
+        key_size: 16,
+        value_size: 40,
+        max_entries: 512,
+    },
+    MapSpec {
+        name: "compositor_map",
+        kind: MapKind::Hash,
+        key_size: 16,
+        value_size: 1,
+        max_entries: 32,
+    },
+    MapSpec {
+        name: "game_tgid_map",
+        kind: MapKind::Hash,
+        key_size: 4,
+        value_size: 1,
+        max_entries: 64,
+    },
+    MapSpec {
+        name: "l2_siblings",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 4,
+        max_entries: 512,
+    },
+    MapSpec {
+        name: "fast_cpus",
+        kind: MapKind::Array,
+        key_size: 4,
+        value_size: 4,
+        max_entries: 1024,
+    },
+    MapSpec {
+        name: "wake_lat_hist",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 8,
+        max_entries: 36,
+    },
+    MapSpec {
+        name: "sleep_hist",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 8,
+        max_entries: 4,
+    },
+    MapSpec {
+        name: "lag_hist",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 8,
+        max_entries: 8,
+    },
+    MapSpec {
+        name: "event_ring",
+        kind: MapKind::PercpuArray,
+        key_size: 4,
+        value_size: 24,
+        max_entries: 33,
+    },
+    MapSpec {
+        name: "task_ctx_stor",
+        kind: MapKind::TaskStorage,
+        key_size: 4,
+        value_size: 0,
+        max_entries: 0,
+    },
+];
+
+fn align8(v: u64) -> u64 {
+    (v + 7) & !7
+}
+
+fn page_round(v: u64) -> u64 {
+    v.div_ceil(PAGE_SIZE) * PAGE_SIZE
+}
+
+// LOCKED BYTES FOR ONE MAP WITH nr_cpus POSSIBLE CPUS
+pub fn map_bytes(m: &MapSpec, nr_cpus: u64) -> u64 {
+    let raw = match m.kind {
+        MapKind::Array => align8(m.value_size) * m.max_entries,
+        MapKind::PercpuArray => align8(m.value_size) * m.max_entries * nr_cpus.max(1),
+        MapKind::Hash | MapKind::LruHash => {
+            let elem = HTAB_ELEM_OVERHEAD + align8(m.key_size) + align8(m.value_size);
+            let buckets = m.max_entries.next_power_of_two() * HTAB_BUCKET_SIZE;
+            elem * m.max_entries + buckets
+        }
+        MapKind::TaskStorage => return 0,
+    };
+    page_round(raw)
+}
+
+pub fn total_bytes(maps: &[MapSpec], nr_cpus: u64) -> u64 {
+    maps.iter().map(|m| map_bytes(m, nr_cpus)).sum()
+}
+
+// ONE LINE PER MAP + TOTAL, READY FOR log_info!/log_error!
+pub fn format_table(maps: &[MapSpec], nr_cpus: u64) -> Vec<String> {
+    let mut out = vec![format!(
+        "  {:<20}{:<14}{:>8}{:>12}",
+        "MAP", "TYPE", "ENTRIES", "LOCKED_KB"
+    )];
+    for m in maps {
+        let kb = match m.kind {
+            MapKind::TaskStorage => "on demand".to_string(),
+            _ => (map_bytes(m, nr_cpus) / 1024).to_string(),
+        };
+        out.push(format!(
+            "  {:<20}{:<14}{:>8}{:>12}",
+            m.name,
+            m.kind.label(),
+            m.max_entries,
+            kb
+        ));
+    }
+    out.push(format!(
+        "  {:<42}{:>12}",
+        format!("TOTAL ({} possible CPUs)", nr_cpus),
+        total_bytes(maps, nr_cpus) / 1024
+    ));
+    out
+}
+
+pub fn possible_cpus() -> Option<u64> {
+    let s = std::fs::read_to_string(CPU_POSSIBLE_PATH).ok()?;
+    let n = crate::hotplug::parse_cpu_list(s.trim()).len() as u64;
+    if n == 0 {
+        None
+    } else {
+        Some(n)
+    }
+}
+
+// None: UNLIMITED
+pub fn limit_from_raw(raw: libc::rlim_t) -> Option<u64> {
+    if raw == libc::RLIM_INFINITY {
+        None
+    } else {
+        Some(raw)
+    }
+}
+
+// CURRENT SOFT LIMIT (None: UNLIMITED)
+pub fn current_limit() -> Option<u64> {
+    let mut cur = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut cur) } != 0 {
+        return None;
+    }
+    limit_from_raw(cur.rlim_cur)
+}
+
+// TRY UNLIMITED, THEN SOFT = HARD (ALLOWED WITHOUT CAP_SYS_RESOURCE).
+// RETURNS THE LIMIT NOW IN EFFECT.
+pub fn raise_limit() -> Option<u64> {
+    let unlimited = libc::rlimit {
+        rlim_cur: libc::RLIM_INFINITY,
+        rlim_max: libc::RLIM_INFINITY,
+    };
+    if unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &unlimited) } == 0 {
+        return None;
+    }
+    let mut cur = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    unsafe {
+        if libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut cur) == 0 && cur.rlim_cur < cur.rlim_max {
+            let hard = libc::rlimit {
+                rlim_cur: cur.rlim_max,
+                rlim_max: cur.rlim_max,
+            };
+            libc::setrlimit(libc::RLIMIT_MEMLOCK, &hard);
+        }
+    }
+    current_limit()
+}
+
+// TRUE WHEN limit CANNOT HOLD needed BYTES
+pub fn is_short(limit: Option<u64>, needed: u64) -> bool {
+    limit.is_some_and(|l| l < needed)
+}
+
+// MAP CREATION UNDER A LOW MEMLOCK LIMIT FAILS WITH EPERM OR ENOMEM
+pub fn is_memlock_error(msg: &str) -> bool {
+    msg.contains("EPERM")
+        || msg.contains("ENOMEM")
+        || msg.contains("Operation not permitted")
+        || msg.contains("Cannot allocate memory")
+}
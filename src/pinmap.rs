@@ -0,0 +1,92 @@
+// PANDEMONIUM PINNED MAP HANDLES
+// SHARED BETWEEN BINARY CRATE (procdb) AND LIB CRATE (client, tests)
+//
+// A PIN CAN BE OPENED BEFORE IT IS THERE: THE MONITOR LOOP STARTS RIGHT
+// AFTER Scheduler::init() PINS, A TRAY APP LAUNCHED WITH THE SCHEDULER
+// RACES IT, AND bpffs UNDER A CONTAINER RUNTIME CAN LAG. EVERY PINNED MAP
+// OPEN GOES THROUGH open_pinned_with_retry(): UP TO attempts TRIES, THE
+// WAIT DOUBLING FROM delay, AND AN ERROR NAMING THE PATH AND THE ATTEMPTS.
+// CALLERS ADD WHICH MAP IT WAS FOR.
+//
+// A CALLER THAT CAN RUN WITHOUT THE MAP (procdb) GIVES UP FOR NOW AND
+// TRIES AGAIN EVERY FEW TICKS: TickRetry KEEPS THAT CADENCE.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+// 25 + 50 + 100 + 200 = 375ms OF WAITING BEFORE THE LAST TRY
+pub const PIN_OPEN_ATTEMPTS: u32 = 5;
+pub const PIN_OPEN_DELAY: Duration = Duration::from_millis(25);
+
+// open() UP TO attempts TIMES (AT LEAST ONCE), sleep()ING delay, 2*delay, ...
+// BETWEEN TRIES. THE LAST ERROR COMES BACK.
+pub fn retry_with_backoff<T, E>(
+    attempts: u32,
+    delay: Duration,
+    mut sleep: impl FnMut(Duration),
+    mut open: impl FnMut() -> std::result::Result<T, E>,
+) -> std::result::Result<T, E> {
+    let mut wait = delay;
+    for _ in 1..attempts.max(1) {
+        match open() {
+            Ok(v) => return Ok(v),
+            Err(_) => {
+                sleep(wait);
+                wait = wait.saturating_mul(2);
+            }
+        }
+    }
+    open()
+}
+
+pub fn open_pinned_with_retry(
+    path: &Path,
+    attempts: u32,
+    delay: Duration,
+) -> Result<libbpf_rs::MapHandle> {
+    let attempts = attempts.max(1);
+    retry_with_backoff(attempts, delay, std::thread::sleep, || {
+        libbpf_rs::MapHandle::from_pinned_path(path)
+    })
+    .with_context(|| {
+        format!(
+            "OPENING PINNED MAP {} ({} ATTEMPT{})",
+            path.display(),
+            attempts,
+            if attempts == 1 { "" } else { "S" }
+        )
+    })
+}
+
+// DUE RIGHT AWAY, THEN interval TICKS AFTER EACH FAILURE
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickRetry {
+    interval: u64,
+    next: u64,
+    failures: u64,
+}
+
+impl TickRetry {
+    pub fn every(interval: u64) -> Self {
+        Self {
+            interval: interval.max(1),
+            next: 0,
+            failures: 0,
+        }
+    }
+
+    pub fn due(&self, tick: u64) -> bool {
+        tick >= self.next
+    }
+
+    pub fn failed(&mut self, tick: u64) {
+        self.failures += 1;
+        self.next = tick + self.interval;
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.failures
+    }
+}
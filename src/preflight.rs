@@ -0,0 +1,658 @@
+// PANDEMONIUM PREFLIGHT
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// EVERYTHING `pandemonium check` VERIFIES, AS DATA: run() RETURNS A
+// PreflightReport OF TYPED ENTRIES (SECTION, NAME, OK/WARN/FAIL, DETAIL,
+// REMEDY). CONSUMERS:
+//   - `check` RENDERS IT (render_lines)
+//   - `check --json` PRINTS IT FOR PROVISIONING SCRIPTS AND bench-scale
+//   - run_scheduler RUNS Mode::Start AND REFUSES TO LOAD THE SKELETON ON
+//     ANY BLOCKING ENTRY
+//
+// CLASSIFICATION:
+//   FAIL  A REQUIREMENT IS NOT MET. `check` EXITS 1.
+//   WARN  DEGRADED OR UNKNOWN: OPTIONAL KFUNC MISSING, PROBE SKIPPED
+//         WITHOUT ROOT, FILE UNREADABLE. NEVER FAILS ANYTHING.
+//   A FAIL ON A hard ENTRY ALSO BLOCKS STARTING THE SCHEDULER: MISSING
+//   REQUIRED KFUNCS, NO KERNEL BTF, libbpf TOO OLD, A USED BPF FEATURE
+//   UNSUPPORTED, NO sched_ext IN sysfs. THE REST (TOOLCHAIN, KERNEL
+//   VERSION STRING, /proc/config.gz) ONLY MATTERS TO `check`: A RUNNING
+//   KERNEL WITH sched_ext IN sysfs IS PROOF ENOUGH.
+//
+// libbpf ITSELF (VERSION, PROBE LOADS) IS LINKED INTO THE BINARY ONLY:
+// IT HANDS THEM IN AS BpfProbes.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use crate::benchout::Json;
+use crate::bpffeat::{self, Feature, Probe, Version};
+use crate::compat::{self, Compat, CompatReport};
+use crate::conflicts::{self, Found};
+use crate::container::{self, ContainerSignals, PinDir};
+use crate::memlock;
+use crate::privilege::Escalation;
+use crate::scxstate;
+
+pub const MIN_KERNEL: (u32, u32) = (6, 12);
+pub const VMLINUX_CACHE_PATH: &str = "/tmp/pandemonium-vmlinux.h";
+pub const KERNEL_CONFIG_PATH: &str = "/proc/config.gz";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+impl From<Compat> for Status {
+    fn from(c: Compat) -> Self {
+        match c {
+            Compat::Pass => Self::Ok,
+            Compat::Warn => Self::Warn,
+            Compat::Fail => Self::Fail,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub section: &'static str,
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    pub remedy: Option<String>,
+    pub hard: bool, // A FAIL HERE BLOCKS STARTING THE SCHEDULER
+}
+
+impl Entry {
+    pub fn new(section: &'static str, name: &str, status: Status, detail: &str) -> Self {
+        Self {
+            section,
+            name: name.to_string(),
+            status,
+            detail: detail.to_string(),
+            remedy: None,
+            hard: false,
+        }
+    }
+
+    pub fn remedy(mut self, remedy: &str) -> Self {
+        self.remedy = Some(remedy.to_string());
+        self
+    }
+
+    pub fn hard(mut self) -> Self {
+        self.hard = true;
+        self
+    }
+
+    pub fn blocks_start(&self) -> bool {
+        self.hard && self.status == Status::Fail
+    }
+
+    pub fn json(&self) -> Json {
+        Json::obj(vec![
+            ("section", Json::str(self.section)),
+            ("name", Json::str(&self.name)),
+            ("status", Json::str(self.status.label())),
+            ("detail", Json::str(&self.detail)),
+            (
+                "remedy",
+                self.remedy.as_deref().map_or(Json::Null, Json::str),
+            ),
+            ("hard", Json::Bool(self.hard)),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PreflightReport {
+    pub entries: Vec<Entry>,
+    // scx_bpf_task_set_* PRESENT: run_scheduler PICKS THE SLICE/VTIME PATH
+    pub task_set_kfuncs: bool,
+}
+
+impl PreflightReport {
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn worst(&self) -> Status {
+        self.entries
+            .iter()
+            .map(|e| e.status)
+            .max()
+            .unwrap_or(Status::Ok)
+    }
+
+    // `check` PASSES: NO FAIL ANYWHERE
+    pub fn ok(&self) -> bool {
+        self.worst() != Status::Fail
+    }
+
+    pub fn blocking(&self) -> Vec<&Entry> {
+        self.entries.iter().filter(|e| e.blocks_start()).collect()
+    }
+
+    pub fn warnings(&self) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == Status::Warn)
+            .collect()
+    }
+
+    // ONE ERROR FOR run_scheduler, None WHEN NOTHING BLOCKS
+    pub fn blocking_message(&self) -> Option<String> {
+        let blocking = self.blocking();
+        if blocking.is_empty() {
+            return None;
+        }
+        let parts: Vec<String> = blocking
+            .iter()
+            .map(|e| match &e.remedy {
+                Some(r) => format!("{}: {} ({})", e.name, e.detail, r),
+                None => format!("{}: {}", e.name, e.detail),
+            })
+            .collect();
+        Some(format!(
+            "PREFLIGHT FAILED: {}. RUN `pandemonium check` FOR THE FULL REPORT",
+            parts.join("; ")
+        ))
+    }
+
+    // CLI TEXT: A "Section:" HEADER WHEN THE SECTION CHANGES, ONE LINE PER
+    // ENTRY, THE REMEDY UNDER ANYTHING NOT OK. EACH LINE CARRIES THE STATUS
+    // THE CALLER LOGS IT AT.
+    pub fn render_lines(&self) -> Vec<(Status, String)> {
+        let mut out = Vec::new();
+        let mut section = "";
+        for e in &self.entries {
+            if e.section != section {
+                section = e.section;
+                out.push((Status::Ok, format!("{}:", section)));
+            }
+            out.push((
+                e.status,
+                format!("  {:<30}{:<6}{}", e.name, e.status.label(), e.detail),
+            ));
+            if let (Some(r), true) = (&e.remedy, e.status != Status::Ok) {
+                out.push((e.status, format!("  {:<30}-> {}", "", r)));
+            }
+        }
+        out.push(if self.ok() {
+            (Status::Ok, "All checks passed".to_string())
+        } else {
+            (Status::Fail, "Some checks failed".to_string())
+        });
+        out
+    }
+
+    pub fn json(&self) -> Json {
+        Json::obj(vec![
+            ("ok", Json::Bool(self.ok())),
+            ("status", Json::str(self.worst().label())),
+            (
+                "entries",
+                Json::Arr(self.entries.iter().map(Entry::json).collect()),
+            ),
+        ])
+    }
+}
+
+// WHAT IS GATHERED: Check IS EVERYTHING; Start SKIPS WHAT CANNOT BLOCK A
+// START (TOOLCHAIN, BUILD CACHE, KERNEL CONFIG, MEMLOCK, ENVIRONMENT,
+// CONFLICTING DAEMONS: run_scheduler WARNS ABOUT THOSE ITSELF)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Check,
+    Start,
+}
+
+// WHAT ONLY THE BINARY CAN ASK libbpf: ITS VERSION AND THE PROBE LOADS
+pub struct BpfProbes {
+    pub libbpf: Version,
+    pub probe: fn(Feature, Option<&[u8]>) -> Probe,
+}
+
+pub const SEC_TOOLS: &str = "Tools";
+pub const SEC_ENV: &str = "Environment";
+pub const SEC_KERNEL: &str = "Kernel version";
+pub const SEC_KFUNCS: &str = "sched_ext kfuncs";
+pub const SEC_BPF: &str = "BPF toolchain + features";
+pub const SEC_CONFIG: &str = "Kernel config";
+pub const SEC_MEMLOCK: &str = "BPF map memory";
+pub const SEC_CACHE: &str = "Build cache";
+pub const SEC_CONFLICTS: &str = "Conflicting daemons";
+pub const SEC_SCX: &str = "sched_ext";
+
+pub fn tool_entry(name: &str, found: bool) -> Entry {
+    if found {
+        return Entry::new(SEC_TOOLS, name, Status::Ok, "found");
+    }
+    let remedy = match name {
+        "cargo" | "rustc" => "install Rust: https://rustup.rs",
+        "clang" => "install clang: pacman -S clang",
+        _ => "install it and put it on $PATH",
+    };
+    Entry::new(SEC_TOOLS, name, Status::Fail, "missing").remedy(remedy)
+}
+
+// /proc/sys/kernel/osrelease: "6.12.1-arch1-1"
+pub fn kernel_entry(release: &str) -> Entry {
+    let mut parts = release.trim().split('.');
+    let major = parts.next().and_then(|p| p.parse::<u32>().ok());
+    let minor = parts.next().and_then(|p| {
+        let end = p.find(|c: char| !c.is_ascii_digit()).unwrap_or(p.len());
+        p[..end].parse::<u32>().ok()
+    });
+    let release = release.trim();
+    match (major, minor) {
+        (Some(major), Some(minor)) if (major, minor) >= MIN_KERNEL => Entry::new(
+            SEC_KERNEL,
+            "kernel",
+            Status::Ok,
+            &format!("{} (>= {}.{})", release, MIN_KERNEL.0, MIN_KERNEL.1),
+        ),
+        (Some(major), Some(minor)) => Entry::new(
+            SEC_KERNEL,
+            "kernel",
+            Status::Fail,
+            &format!(
+                "{}.{} is too old: PANDEMONIUM requires {}.{}+",
+                major, minor, MIN_KERNEL.0, MIN_KERNEL.1
+            ),
+        )
+        .remedy("boot 6.12+: sched_ext (CONFIG_SCHED_CLASS_EXT) was merged in Linux 6.12"),
+        _ => Entry::new(
+            SEC_KERNEL,
+            "kernel",
+            Status::Warn,
+            &format!("cannot parse version from '{}'", release),
+        ),
+    }
+}
+
+// ONE ENTRY PER KFUNC main.bpf.c CALLS, THEN THE SLICE/VTIME PATH
+pub fn kfunc_entries(report: &CompatReport) -> Vec<Entry> {
+    let mut out: Vec<Entry> = report
+        .entries
+        .iter()
+        .map(|e| {
+            let status = Status::from(e.status);
+            match e.found_as {
+                Some(name) if name != e.name => {
+                    Entry::new(SEC_KFUNCS, e.name, status, &format!("legacy {}", name))
+                }
+                Some(_) => Entry::new(SEC_KFUNCS, e.name, status, "present"),
+                None if status == Status::Fail => Entry::new(SEC_KFUNCS, e.name, status, "missing")
+                    .remedy("boot a kernel whose sched_ext provides it")
+                    .hard(),
+                None => Entry::new(SEC_KFUNCS, e.name, status, "optional, missing"),
+            }
+        })
+        .collect();
+    out.push(if report.has_task_set_kfuncs() {
+        Entry::new(
+            SEC_KFUNCS,
+            "slice/vtime path",
+            Status::Ok,
+            "scx_bpf_task_set_* kfuncs",
+        )
+    } else {
+        Entry::new(
+            SEC_KFUNCS,
+            "slice/vtime path",
+            Status::Warn,
+            "direct p->scx writes (legacy kernel)",
+        )
+    });
+    out
+}
+
+// bpffeat ROWS, WITH WHICH ONES BLOCK A START AND HOW TO FIX EACH
+pub fn bpf_entry(row: &bpffeat::Row) -> Entry {
+    let status = Status::from(row.status);
+    let mut e = Entry::new(
+        SEC_BPF,
+        &row.item,
+        status,
+        &format!("{} (minimum {})", row.detected, row.minimum),
+    );
+    let feature = bpffeat::FEATURES.iter().find(|f| f.name == row.item);
+    e.hard = match feature {
+        Some(f) => f.used,
+        None => row.item == "libbpf" || row.item == "kernel BTF",
+    };
+    if status == Status::Ok {
+        return e;
+    }
+    e.remedy = match (feature, row.item.as_str()) {
+        (Some(_), _) if row.detected.starts_with("skipped") => {
+            Some("rerun as root: the probes load BPF programs".to_string())
+        }
+        (Some(f), _) => Some(format!("boot kernel {}+", f.min_kernel)),
+        (None, "clang") => Some(format!(
+            "install clang {}+ (-std=gnu23)",
+            bpffeat::MIN_CLANG.major
+        )),
+        (None, "libbpf") => Some("rebuild against libbpf 1.x".to_string()),
+        (None, "kernel BTF") => Some("boot a kernel with CONFIG_DEBUG_INFO_BTF=y".to_string()),
+        (None, "bpftool") => {
+            Some("install bpftool (needed once, to generate vmlinux.h)".to_string())
+        }
+        _ => None,
+    };
+    e
+}
+
+// config: THE DECOMPRESSED /proc/config.gz, None WHEN ABSENT OR UNREADABLE
+pub fn config_entry(config: Option<&str>) -> Entry {
+    match config {
+        None => Entry::new(
+            SEC_CONFIG,
+            "CONFIG_SCHED_CLASS_EXT",
+            Status::Warn,
+            "/proc/config.gz not found or unreadable (skipped)",
+        ),
+        Some(c) if c.contains("CONFIG_SCHED_CLASS_EXT=y") => {
+            Entry::new(SEC_CONFIG, "CONFIG_SCHED_CLASS_EXT", Status::Ok, "=y")
+        }
+        Some(_) => Entry::new(
+            SEC_CONFIG,
+            "CONFIG_SCHED_CLASS_EXT",
+            Status::Fail,
+            "not set: sched_ext may not be available",
+        )
+        .remedy("rebuild the kernel with CONFIG_SCHED_CLASS_EXT=y"),
+    }
+}
+
+// ONE ENTRY PER MAP, THEN RLIMIT_MEMLOCK. limit None: UNLIMITED.
+// WARN ONLY: THE SCHEDULER RAISES THE LIMIT ITSELF WHEN RUN AS ROOT
+pub fn memlock_entries(maps: &[memlock::MapSpec], nr_cpus: u64, limit: Option<u64>) -> Vec<Entry> {
+    let mut out: Vec<Entry> = maps
+        .iter()
+        .map(|m| {
+            let kb = match m.kind {
+                memlock::MapKind::TaskStorage => "on demand".to_string(),
+                _ => format!("{} KB", memlock::map_bytes(m, nr_cpus) / 1024),
+            };
+            Entry::new(
+                SEC_MEMLOCK,
+                m.name,
+                Status::Ok,
+                &format!("{} x{}, {}", m.kind.label(), m.max_entries, kb),
+            )
+        })
+        .collect();
+    let needed = memlock::total_bytes(maps, nr_cpus);
+    let total = format!("{} KB for {} possible CPUs", needed / 1024, nr_cpus);
+    out.push(match limit {
+        None => Entry::new(
+            SEC_MEMLOCK,
+            "RLIMIT_MEMLOCK",
+            Status::Ok,
+            &format!("unlimited, {} needed", total),
+        ),
+        Some(l) if memlock::is_short(Some(l), needed) => Entry::new(
+            SEC_MEMLOCK,
+            "RLIMIT_MEMLOCK",
+            Status::Warn,
+            &format!("{} KB < {}", l / 1024, total),
+        )
+        .remedy("raised at startup if running as root"),
+        Some(l) => Entry::new(
+            SEC_MEMLOCK,
+            "RLIMIT_MEMLOCK",
+            Status::Ok,
+            &format!("{} KB, {} needed", l / 1024, total),
+        ),
+    });
+    out
+}
+
+// ONE WARN PER DAEMON FOUND RUNNING, OR ONE OK LINE
+pub fn conflict_entries(found: &[Found]) -> Vec<Entry> {
+    if found.is_empty() {
+        return vec![Entry::new(
+            SEC_CONFLICTS,
+            "tuning daemons",
+            Status::Ok,
+            "none running",
+        )];
+    }
+    found
+        .iter()
+        .map(|f| {
+            Entry::new(
+                SEC_CONFLICTS,
+                f.name,
+                Status::Warn,
+                &format!("pid {}: {}", f.pid, f.note),
+            )
+            .remedy("stop it while PANDEMONIUM runs, or start with --ignore-conflicts")
+        })
+        .collect()
+}
+
+// ops: root/ops CONTENTS, None WHEN THE sysfs PATH IS MISSING
+pub fn sched_ext_entry(ops: Option<&str>, runtime: Option<&str>) -> Entry {
+    match ops.map(str::trim) {
+        None => Entry::new(
+            SEC_SCX,
+            "sysfs",
+            Status::Fail,
+            "not available (sysfs path missing)",
+        )
+        .remedy(&container::sched_ext_hint(runtime))
+        .hard(),
+        Some("") => Entry::new(
+            SEC_SCX,
+            "sysfs",
+            Status::Ok,
+            "available (no scheduler active)",
+        ),
+        Some(active) => Entry::new(
+            SEC_SCX,
+            "sysfs",
+            Status::Ok,
+            &format!("available ({} active)", active),
+        ),
+    }
+}
+
+fn tool_found(name: &str) -> bool {
+    Command::new("which")
+        .arg(name)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+// `<tool> <arg>` STDOUT+STDERR, None WHEN IT WILL NOT RUN
+fn tool_output(tool: &str, arg: &str) -> Option<String> {
+    let out = Command::new(tool).arg(arg).output().ok()?;
+    let mut text = String::from_utf8_lossy(&out.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&out.stderr));
+    Some(text)
+}
+
+fn read_kernel_config() -> Option<String> {
+    let file = std::fs::File::open(KERNEL_CONFIG_PATH).ok()?;
+    let mut config = String::new();
+    flate2::read::GzDecoder::new(file)
+        .read_to_string(&mut config)
+        .ok()?;
+    Some(config)
+}
+
+// CONTAINER, PRIVILEGE AND PIN DIRECTORY. ONLY A NON-ROOT USER WITH NO
+// WAY TO ESCALATE FAILS: A NON-bpffs PIN DIRECTORY JUST DISABLES PINNING
+fn environment_entries(pins: &PinDir, runtime: Option<&str>) -> Vec<Entry> {
+    let mut out = vec![Entry::new(
+        SEC_ENV,
+        "container",
+        Status::Ok,
+        runtime.unwrap_or("none (host)"),
+    )];
+    out.push(match Escalation::detect() {
+        Ok(Escalation::Root) => Entry::new(
+            SEC_ENV,
+            "privileges",
+            Status::Ok,
+            "root (no escalation needed)",
+        ),
+        Ok(esc) => Entry::new(
+            SEC_ENV,
+            "privileges",
+            Status::Ok,
+            &format!("via {}", esc.describe()),
+        ),
+        Err(e) => Entry::new(SEC_ENV, "privileges", Status::Fail, &e),
+    });
+    let mounts = std::fs::read_to_string(container::SELF_MOUNTS_PATH).unwrap_or_default();
+    out.push(
+        if container::on_bpffs(&container::parse_mounts(&mounts), pins.dir()) {
+            Entry::new(
+                SEC_ENV,
+                "pin dir",
+                Status::Ok,
+                &format!("{} (bpffs)", pins.dir().display()),
+            )
+        } else {
+            Entry::new(
+                SEC_ENV,
+                "pin dir",
+                Status::Warn,
+                &container::bpffs_hint(pins.dir(), runtime),
+            )
+        },
+    );
+    out
+}
+
+fn bpf_entries(mode: Mode, bpf: &BpfProbes, btf: Option<&[u8]>) -> Vec<Entry> {
+    let mut rows = Vec::new();
+    if mode == Mode::Check {
+        let clang = tool_output("clang", "--version")
+            .as_deref()
+            .and_then(bpffeat::parse_clang_version);
+        rows.push(bpffeat::tool_row(
+            "clang",
+            Some(bpffeat::MIN_CLANG),
+            clang,
+            true,
+        ));
+    }
+    rows.push(bpffeat::tool_row(
+        "libbpf",
+        Some(bpffeat::MIN_LIBBPF),
+        Some(bpf.libbpf),
+        true,
+    ));
+    rows.push(bpffeat::tool_row(
+        "libbpf-rs",
+        None,
+        Version::parse(bpffeat::LIBBPF_RS_VERSION),
+        true,
+    ));
+    if mode == Mode::Check {
+        let bpftool = tool_output("bpftool", "version")
+            .as_deref()
+            .and_then(bpffeat::parse_bpftool_version);
+        rows.push(bpffeat::tool_row("bpftool", None, bpftool, false));
+    }
+    rows.push(bpffeat::btf_row(btf.map(|b| b.len() as u64 / 1024)));
+    let root = unsafe { libc::geteuid() } == 0;
+    for req in bpffeat::FEATURES {
+        let probe = if root {
+            (bpf.probe)(req.feature, btf)
+        } else {
+            Probe::Skipped
+        };
+        rows.push(bpffeat::feature_row(req, &probe));
+    }
+    rows.iter().map(bpf_entry).collect()
+}
+
+pub fn run(mode: Mode, pins: &PinDir, bpf: &BpfProbes) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    let runtime = ContainerSignals::read().runtime();
+
+    if mode == Mode::Check {
+        for tool in ["cargo", "rustc", "clang"] {
+            report.push(tool_entry(tool, tool_found(tool)));
+        }
+        for e in environment_entries(pins, runtime.as_deref()) {
+            report.push(e);
+        }
+        report.push(kernel_entry(&compat::kernel_release()));
+    }
+
+    match compat::probe_kernel() {
+        Ok(kfuncs) => {
+            report.task_set_kfuncs = kfuncs.has_task_set_kfuncs();
+            for e in kfunc_entries(&kfuncs) {
+                report.push(e);
+            }
+        }
+        Err(e) => report.push(Entry::new(
+            SEC_KFUNCS,
+            "kfunc probe",
+            Status::Warn,
+            &format!("{} unreadable ({}): skipped", compat::BTF_VMLINUX_PATH, e),
+        )),
+    }
+
+    let btf = std::fs::read(compat::BTF_VMLINUX_PATH).ok();
+    for e in bpf_entries(mode, bpf, btf.as_deref()) {
+        report.push(e);
+    }
+
+    if mode == Mode::Check {
+        report.push(config_entry(read_kernel_config().as_deref()));
+        let nr_cpus = memlock::possible_cpus().unwrap_or(1);
+        for e in memlock_entries(memlock::MAPS, nr_cpus, memlock::current_limit()) {
+            report.push(e);
+        }
+        for e in conflict_entries(&conflicts::scan(Path::new("/proc"))) {
+            report.push(e);
+        }
+        let cache = Path::new(VMLINUX_CACHE_PATH);
+        report.push(match cache.metadata().map(|m| m.len()) {
+            Ok(len) if len > 1000 => Entry::new(
+                SEC_CACHE,
+                "vmlinux.h",
+                Status::Ok,
+                &format!("cached ({} KB)", len / 1024),
+            ),
+            _ => Entry::new(
+                SEC_CACHE,
+                "vmlinux.h",
+                Status::Warn,
+                "not cached (will be downloaded on first build)",
+            ),
+        });
+    }
+
+    let ops_path = Path::new(scxstate::SCX_SYSFS_ROOT).join("root/ops");
+    let ops = if ops_path.exists() {
+        Some(std::fs::read_to_string(&ops_path).unwrap_or_default())
+    } else {
+        None
+    };
+    report.push(sched_ext_entry(ops.as_deref(), runtime.as_deref()));
+    report
+}
@@ -0,0 +1,112 @@
+// PANDEMONIUM PRIVILEGE ESCALATION
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// start, bench AND THE TEST GATE SPAWN THE SCHEDULER (OR cargo) AS ROOT.
+// ONE PLACE DECIDES HOW, SO NONE OF THEM HARD-CODES sudo:
+//   euid 0               RUN DIRECTLY (CONTAINERS, ROOT CI)
+//   $PANDEMONIUM_SUDO    THAT COMMAND, SPLIT ON WHITESPACE ("doas", "sudo -n")
+//   (DEFAULT)            FIRST OF sudo, doas, run0 FOUND IN $PATH
+// ENVIRONMENT FOR THE CHILD GOES THROUGH env(1) ON THE COMMAND LINE:
+// sudo -E, doas's keepenv AND run0 --setenv DON'T AGREE, env(1) WORKS
+// UNDER ALL THREE AND WITHOUT ANY.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+pub const SUDO_ENV: &str = "PANDEMONIUM_SUDO";
+
+// SEARCH ORDER WHEN PANDEMONIUM_SUDO IS UNSET
+pub const ESCALATORS: [&str; 3] = ["sudo", "doas", "run0"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Escalation {
+    Root,
+    Tool(Vec<String>), // PROGRAM + ITS OWN ARGS, PREPENDED TO EVERY argv
+}
+
+impl Escalation {
+    // path: $PATH; override_cmd: $PANDEMONIUM_SUDO (EMPTY MEANS UNSET)
+    pub fn resolve(
+        euid: u32,
+        override_cmd: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<Self, String> {
+        if euid == 0 {
+            return Ok(Self::Root);
+        }
+        let path = path.unwrap_or("");
+        if let Some(cmd) = override_cmd.filter(|c| !c.trim().is_empty()) {
+            let words: Vec<String> = cmd.split_whitespace().map(String::from).collect();
+            if find_program(&words[0], path).is_none() {
+                return Err(format!("{}={}: {} NOT FOUND", SUDO_ENV, cmd, words[0]));
+            }
+            return Ok(Self::Tool(words));
+        }
+        ESCALATORS
+            .iter()
+            .find(|name| find_program(name, path).is_some())
+            .map(|name| Self::Tool(vec![name.to_string()]))
+            .ok_or_else(|| {
+                format!(
+                    "NOT ROOT AND NONE OF {} IN PATH: RUN AS ROOT OR SET {}",
+                    ESCALATORS.join(", "),
+                    SUDO_ENV
+                )
+            })
+    }
+
+    // THIS PROCESS: geteuid(), $PANDEMONIUM_SUDO, $PATH
+    pub fn detect() -> Result<Self, String> {
+        Self::resolve(
+            unsafe { libc::geteuid() },
+            std::env::var(SUDO_ENV).ok().as_deref(),
+            std::env::var("PATH").ok().as_deref(),
+        )
+    }
+
+    // "root" OR THE ESCALATION COMMAND, FOR LOGS AND `check`
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Root => "root".to_string(),
+            Self::Tool(words) => words.join(" "),
+        }
+    }
+
+    // FULL argv: [TOOL...] [env K=V...] argv
+    pub fn wrap(&self, env: &[(&str, &str)], argv: &[String]) -> Vec<String> {
+        let mut out = match self {
+            Self::Root => Vec::new(),
+            Self::Tool(words) => words.clone(),
+        };
+        if !env.is_empty() {
+            out.push("env".to_string());
+            out.extend(env.iter().map(|(k, v)| format!("{}={}", k, v)));
+        }
+        out.extend(argv.iter().cloned());
+        out
+    }
+
+    pub fn command(&self, env: &[(&str, &str)], argv: &[String]) -> std::process::Command {
+        let full = self.wrap(env, argv);
+        let mut cmd = std::process::Command::new(&full[0]);
+        cmd.args(&full[1..]);
+        cmd
+    }
+}
+
+// AN ABSOLUTE OR RELATIVE PATH IS CHECKED AS-IS; A BARE NAME IS LOOKED UP
+// IN path. ONLY EXECUTABLE REGULAR FILES COUNT.
+pub fn find_program(name: &str, path: &str) -> Option<PathBuf> {
+    let executable = |p: &Path| {
+        p.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+    if name.contains('/') {
+        let p = PathBuf::from(name);
+        return executable(&p).then_some(p);
+    }
+    path.split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(name))
+        .find(|p| executable(p))
+}
@@ -0,0 +1,411 @@
+// PANDEMONIUM PROBE OUTPUT
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// `pandemonium probe` PRINTS ONE SAMPLE PER LINE (--output raw):
+//   ONE THREAD:   "<overshoot_us>"
+//   --threads N:  "<tid> <overshoot_us>"
+// OR ONE LINE PER NON-EMPTY BUCKET AT EXIT (--output hist):
+//   "hist <tid> <bucket_lo_us> <count>"
+// --audio ADDS ONE EMULATED AUDIO CALLBACK THREAD AND, AT EXIT:
+//   "xrun cycles=<N> xruns=<M> elapsed_us=<US>"
+// LINES STARTING WITH '#' ARE CONFIG HEADERS. ANYTHING ELSE IS SKIPPED.
+// A NaN OR INFINITE SAMPLE, OR MORE THAN MAX_PROBE_SAMPLES IN TOTAL, IS AN
+// ERROR: THE FIRST WOULD POISON EVERY PERCENTILE, THE SECOND IS A CORRUPT
+// hist COUNT ASKING FOR UNBOUNDED MEMORY.
+//
+// `run --self-probe` RUNS THE SAME LOOP INSIDE THE SCHEDULER PROCESS AND
+// RECORDS INTO A SharedLatencyHist INSTEAD OF PRINTING.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// HISTOGRAM BUCKETS: 1US TO 100US, 10US TO 1MS, 100US TO 10MS,
+// 1MS TO 100MS, THEN ONE OVERFLOW BUCKET. 371 BUCKETS, ~3KB PER THREAD.
+// RESOLUTION IS FINEST WHERE WAKEUP OVERSHOOT ACTUALLY LIVES.
+// (LO_US, HI_US, WIDTH_US)
+const HIST_TIERS: [(u64, u64, u64); 4] = [
+    (0, 100, 1),
+    (100, 1_000, 10),
+    (1_000, 10_000, 100),
+    (10_000, 100_000, 1_000),
+];
+const HIST_OVERFLOW_US: u64 = 100_000;
+pub const HIST_BUCKETS: usize = 100 + 90 * 3 + 1;
+
+// EXPANDED SAMPLES PER PARSE: 400MB OF f64. A 1MS PROBE ON 8 THREADS FOR
+// AN HOUR IS ~29M, SO ONLY A CORRUPT hist COUNT GETS HERE.
+pub const MAX_PROBE_SAMPLES: usize = 50_000_000;
+
+// BUCKET INDEX FOR A SAMPLE
+pub fn hist_bucket(us: u64) -> usize {
+    let mut base = 0;
+    for &(lo, hi, width) in &HIST_TIERS {
+        if us < hi {
+            return base + ((us - lo) / width) as usize;
+        }
+        base += ((hi - lo) / width) as usize;
+    }
+    HIST_BUCKETS - 1
+}
+
+// LOWER EDGE (US) OF A BUCKET. THE PARSER REPORTS THIS AS THE SAMPLE VALUE.
+pub fn hist_bucket_lo(idx: usize) -> u64 {
+    let mut base = 0;
+    for &(lo, hi, width) in &HIST_TIERS {
+        let n = ((hi - lo) / width) as usize;
+        if idx < base + n {
+            return lo + (idx - base) as u64 * width;
+        }
+        base += n;
+    }
+    HIST_OVERFLOW_US
+}
+
+// IN-PROCESS ACCUMULATOR FOR --output hist. FIXED SIZE, NO ALLOCATION
+// AFTER CONSTRUCTION, SO IT CAN RUN UNBOUNDED (UNLIKE THE RAW BUFFER).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatencyHist {
+    pub counts: [u64; HIST_BUCKETS],
+}
+
+impl Default for LatencyHist {
+    fn default() -> Self {
+        Self {
+            counts: [0; HIST_BUCKETS],
+        }
+    }
+}
+
+impl LatencyHist {
+    pub fn record(&mut self, us: u64) {
+        self.counts[hist_bucket(us)] += 1;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // "hist <tid> <lo> <count>" FOR EVERY NON-EMPTY BUCKET
+    pub fn lines(&self, tid: u32) -> Vec<String> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 0)
+            .map(|(i, c)| format!("hist {} {} {}", tid, hist_bucket_lo(i), c))
+            .collect()
+    }
+
+    // COUNTS ADDED SINCE prev, AN EARLIER SNAPSHOT OF THE SAME HISTOGRAM
+    pub fn since(&self, prev: &LatencyHist) -> LatencyHist {
+        LatencyHist {
+            counts: std::array::from_fn(|i| self.counts[i].wrapping_sub(prev.counts[i])),
+        }
+    }
+
+    // LOWER EDGE (US) OF THE BUCKET HOLDING THE p-TH PERCENTILE. 0 WHEN EMPTY
+    pub fn percentile_us(&self, p: u64) -> u64 {
+        let total = self.total();
+        if total == 0 {
+            return 0;
+        }
+        let threshold = (total * p).div_ceil(100).max(1);
+        let mut cumulative = 0;
+        for (i, &c) in self.counts.iter().enumerate() {
+            cumulative += c;
+            if cumulative >= threshold {
+                return hist_bucket_lo(i);
+            }
+        }
+        HIST_OVERFLOW_US
+    }
+}
+
+// SELF-PROBE: ONE PROBE THREAD INSIDE THE SCHEDULER, 100 SAMPLES/S. ITS
+// OVERSHOOT IS A LATENCY SIGNAL THAT DOES NOT COME FROM BPF ACCOUNTING.
+// BPF MATCHES THE THREAD NAME (SELF_PROBE_COMM IN intf.h) AND SKIPS THE
+// COMPOSITOR BOOST AND PROCDB FOR IT: A PRIVILEGED PATH WOULD MEASURE
+// ITSELF, NOT WHAT AN ORDINARY TASK GETS.
+pub const SELF_PROBE_COMM: &str = "pnd-selfprobe";
+pub const SELF_PROBE_PERIOD_US: u64 = 10_000;
+
+// LatencyHist WITH ATOMIC BUCKETS: THE PROBE THREAD RECORDS, THE MONITOR
+// SNAPSHOTS ONCE PER TICK. RELAXED IS ENOUGH: EVERY BUCKET IS AN
+// INDEPENDENT COUNTER, AND A SAMPLE MISSED BY ONE SNAPSHOT LANDS IN THE NEXT.
+pub struct SharedLatencyHist {
+    counts: [AtomicU64; HIST_BUCKETS],
+}
+
+impl Default for SharedLatencyHist {
+    fn default() -> Self {
+        Self {
+            counts: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl SharedLatencyHist {
+    pub fn record(&self, us: u64) {
+        self.counts[hist_bucket(us)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> LatencyHist {
+        LatencyHist {
+            counts: std::array::from_fn(|i| self.counts[i].load(Ordering::Relaxed)),
+        }
+    }
+}
+
+// A MONITOR LOOP'S VIEW OF THE SHARED HISTOGRAM. EACH TELEMETRY LINE TAKES
+// THE SAMPLES SINCE THE PREVIOUS LINE; THE SHUTDOWN SUMMARY TAKES EVERYTHING
+// SINCE THE LOOP STARTED (THE PROBE THREAD OUTLIVES SCHEDULER RESTARTS).
+pub struct ProbeWindow {
+    start: LatencyHist,
+    prev: LatencyHist,
+}
+
+impl ProbeWindow {
+    pub fn new(hist: &SharedLatencyHist) -> Self {
+        let start = hist.snapshot();
+        Self {
+            prev: start.clone(),
+            start,
+        }
+    }
+
+    // P99 (US) OVER THE SAMPLES SINCE THE LAST CALL
+    pub fn p99_since_last(&mut self, hist: &SharedLatencyHist) -> u64 {
+        let cur = hist.snapshot();
+        let p99 = cur.since(&self.prev).percentile_us(99);
+        self.prev = cur;
+        p99
+    }
+
+    pub fn since_start(&self, hist: &SharedLatencyHist) -> LatencyHist {
+        hist.snapshot().since(&self.start)
+    }
+}
+
+// SHUTDOWN COMPARISON: WHAT BPF SAYS WAKEUPS COST AGAINST WHAT THE PROBE
+// SAW. BPF TIMES ENQUEUE -> RUNNING; THE PROBE SEES TIMER EXPIRY -> BACK IN
+// USERSPACE. A GAP THAT KEEPS GROWING IS LATENCY THE BPF SIDE CANNOT SEE.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SelfProbeSummary {
+    pub samples: u64,
+    pub probe_p50_us: u64,
+    pub probe_p99_us: u64,
+    pub bpf_wake_avg_us: u64,
+    pub bpf_p99_us: u64, // UPPER EDGE OF THE BPF HISTOGRAM BUCKET
+}
+
+impl SelfProbeSummary {
+    pub fn new(probe: &LatencyHist, bpf_wake_avg_us: u64, bpf_p99_us: u64) -> Self {
+        Self {
+            samples: probe.total(),
+            probe_p50_us: probe.percentile_us(50),
+            probe_p99_us: probe.percentile_us(99),
+            bpf_wake_avg_us,
+            bpf_p99_us,
+        }
+    }
+
+    // PROBE P99 MINUS BPF P99. POSITIVE: THE PROBE SAW WORSE THAN BPF REPORTS
+    pub fn p99_gap_us(&self) -> i64 {
+        self.probe_p99_us as i64 - self.bpf_p99_us as i64
+    }
+
+    pub fn line(&self) -> String {
+        format!(
+            "[SELF-PROBE] samples={} probe_p50_us={} probe_p99_us={} bpf_wake_avg_us={} bpf_p99_us={} p99_gap_us={}",
+            self.samples,
+            self.probe_p50_us,
+            self.probe_p99_us,
+            self.bpf_wake_avg_us,
+            self.bpf_p99_us,
+            self.p99_gap_us()
+        )
+    }
+}
+
+pub fn percentile(sorted_vals: &[f64], p: f64) -> f64 {
+    if sorted_vals.is_empty() {
+        return 0.0;
+    }
+    let idx = (sorted_vals.len() as f64 * p / 100.0) as usize;
+    let idx = idx.min(sorted_vals.len() - 1);
+    sorted_vals[idx]
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub median: f64,
+    pub p99: f64,
+    pub worst: f64,
+}
+
+impl LatencySummary {
+    // SORTS IN PLACE
+    pub fn from_samples(vals: &mut [f64]) -> Self {
+        vals.sort_by(f64::total_cmp);
+        Self {
+            samples: vals.len(),
+            median: percentile(vals, 50.0),
+            p99: percentile(vals, 99.0),
+            worst: vals.last().copied().unwrap_or(0.0),
+        }
+    }
+}
+
+pub struct ProbeSummary {
+    pub pooled: LatencySummary,
+    pub per_thread: Vec<(u32, LatencySummary)>,
+}
+
+impl ProbeSummary {
+    // THE NUMBER THAT MATTERS ON A DESKTOP: THE UNLUCKIEST WAKEUP CHAIN
+    pub fn worst_thread_p99(&self) -> Option<(u32, f64)> {
+        self.per_thread
+            .iter()
+            .map(|(tid, s)| (*tid, s.p99))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+// GROUP SAMPLES BY THREAD ID. UNPREFIXED LINES BELONG TO THREAD 0.
+// RAW AND HIST LINES PARSE INTO THE SAME SHAPE.
+pub fn parse_probe_output(text: &str) -> anyhow::Result<BTreeMap<u32, Vec<f64>>> {
+    let mut out: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+    let mut total = 0usize;
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (tid, val, count) = match fields.as_slice() {
+            [v] => (Some(0), v.parse::<f64>().ok(), Some(1)),
+            [t, v] => (t.parse::<u32>().ok(), v.parse::<f64>().ok(), Some(1)),
+            // HISTOGRAM BUCKET: EXPAND TO <count> SAMPLES AT THE LOWER EDGE
+            ["hist", t, lo, c] => (
+                t.parse::<u32>().ok(),
+                lo.parse::<f64>().ok(),
+                c.parse::<usize>().ok(),
+            ),
+            _ => continue,
+        };
+        if let (Some(tid), Some(val), Some(count)) = (tid, val, count) {
+            if !val.is_finite() {
+                anyhow::bail!("PROBE LINE {}: NON-FINITE SAMPLE {}", n + 1, val);
+            }
+            total = total.saturating_add(count);
+            if total > MAX_PROBE_SAMPLES {
+                anyhow::bail!(
+                    "PROBE LINE {}: MORE THAN {} SAMPLES",
+                    n + 1,
+                    MAX_PROBE_SAMPLES
+                );
+            }
+            let v = out.entry(tid).or_default();
+            v.extend(std::iter::repeat_n(val, count));
+        }
+    }
+    Ok(out)
+}
+
+pub fn summarize_probe(text: &str) -> anyhow::Result<ProbeSummary> {
+    let mut threads = parse_probe_output(text)?;
+    let mut pooled: Vec<f64> = threads.values().flatten().copied().collect();
+    let per_thread = threads
+        .iter_mut()
+        .map(|(tid, vals)| (*tid, LatencySummary::from_samples(vals)))
+        .collect();
+    Ok(ProbeSummary {
+        pooled: LatencySummary::from_samples(&mut pooled),
+        per_thread,
+    })
+}
+
+// AUDIO CALLBACK EMULATION: PIPEWIRE AT 48KHZ / 64-FRAME QUANTUM. THE
+// THREAD WAKES ON AN ABSOLUTE GRID (TIMER_ABSTIME) EVERY 1.33MS AND MUST
+// FINISH AUDIO_WORK_US OF CPU BEFORE THE NEXT GRID POINT. A LATE FINISH IS
+// AN XRUN. ABSOLUTE DEADLINES: A LATE WAKEUP CAN'T PUSH LATER ONES BACK.
+pub const AUDIO_RATE_HZ: u64 = 48_000;
+pub const AUDIO_QUANTUM_FRAMES: u64 = 64;
+pub const AUDIO_WORK_US: u64 = 300;
+
+// GRID POINT <cycle>, EXACT: NO ROUNDING ERROR ACCUMULATES ACROSS CYCLES
+pub fn audio_deadline_ns(start_ns: i64, cycle: u64) -> i64 {
+    let offset =
+        cycle as u128 * AUDIO_QUANTUM_FRAMES as u128 * 1_000_000_000 / AUDIO_RATE_HZ as u128;
+    start_ns + offset as i64
+}
+
+// FIRST GRID POINT STRICTLY AFTER now_ns. AFTER AN XRUN THE CALLBACK
+// RESYNCS HERE: PERIODS SLEPT THROUGH ARE ONE XRUN, NOT A BURST OF THEM.
+pub fn next_audio_cycle(start_ns: i64, now_ns: i64) -> u64 {
+    if now_ns < start_ns {
+        return 0;
+    }
+    let elapsed = (now_ns - start_ns) as u128;
+    let cycle = elapsed * AUDIO_RATE_HZ as u128 / (AUDIO_QUANTUM_FRAMES as u128 * 1_000_000_000);
+    let mut cycle = cycle as u64;
+    while audio_deadline_ns(start_ns, cycle) <= now_ns {
+        cycle += 1;
+    }
+    cycle
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct AudioStats {
+    pub cycles: u64,
+    pub xruns: u64,
+    pub elapsed_us: u64,
+}
+
+impl AudioStats {
+    pub fn line(&self) -> String {
+        format!(
+            "xrun cycles={} xruns={} elapsed_us={}",
+            self.cycles, self.xruns, self.elapsed_us
+        )
+    }
+
+    pub fn xruns_per_min(&self) -> f64 {
+        if self.elapsed_us == 0 {
+            return 0.0;
+        }
+        self.xruns as f64 * 60_000_000.0 / self.elapsed_us as f64
+    }
+
+    // POOLS ITERATIONS: RATES STAY CORRECT BECAUSE ELAPSED TIME ADDS TOO
+    pub fn merge(&mut self, other: AudioStats) {
+        self.cycles += other.cycles;
+        self.xruns += other.xruns;
+        self.elapsed_us += other.elapsed_us;
+    }
+}
+
+pub fn parse_audio_line(line: &str) -> Option<AudioStats> {
+    let mut parts = line.split_whitespace();
+    if parts.next()? != "xrun" {
+        return None;
+    }
+    let cycles = parts.next()?.strip_prefix("cycles=")?.parse().ok()?;
+    let xruns = parts.next()?.strip_prefix("xruns=")?.parse().ok()?;
+    let elapsed_us = parts.next()?.strip_prefix("elapsed_us=")?.parse().ok()?;
+    Some(AudioStats {
+        cycles,
+        xruns,
+        elapsed_us,
+    })
+}
+
+// NONE WHEN THE PROBE RAN WITHOUT --audio
+pub fn parse_audio_output(text: &str) -> Option<AudioStats> {
+    text.lines()
+        .filter_map(parse_audio_line)
+        .reduce(|mut acc, s| {
+            acc.merge(s);
+            acc
+        })
+}
@@ -12,13 +12,50 @@
 // MERGES INTO A HASHMAP WITH EWMA DECAY, AND WRITES CONFIDENT
 // PREDICTIONS BACK TO A BPF HASH MAP. NEW TASKS WITH MATCHING comm
 // START WITH THE CORRECT TIER AND avg_runtime FROM enable().
+//
+// PRIORITY PRIOR: A TASK THAT ASKED FOR SCHED_FIFO/RR OR A HIGH uclamp_min
+// HAS TOLD US WHAT IT IS. ITS OBSERVATION COUNTS AS A WEIGHTED
+// LAT_CRITICAL VOTE, AND THE PROFILE IS FLUSHED WITHOUT WAITING FOR
+// MIN_OBSERVATIONS OR RUNTIME STABILITY.
+//
+// SEEDS: A COMPILED-IN TABLE (OR --seed-file) OF WELL-KNOWN comms GIVES A
+// FRESH INSTALL A PREDICTION BEFORE ITS FIRST OBSERVATION. PRECEDENCE,
+// STRONGEST FIRST:
+//   0. FORCED              SEE BELOW: OUTRANKS EVERYTHING, INCLUDING BPF
+//   1. LIVE OBSERVATIONS   EVERY ONE IS A FULL VOTE; THE FIRST REPLACES THE
+//                          SEEDED RUNTIMES OUTRIGHT
+//   2. PERSISTED CACHE     A comm ALREADY IN procdb.bin IS NEVER SEEDED
+//   3. --seed-file         REPLACES THE BUILT-IN TABLE ENTIRELY
+//   4. BUILT-IN TABLE      BUILTIN_SEEDS
+// A SEED IS SEED_VOTE_WEIGHT VOTES, SO TWO CONTRARY OBSERVATIONS TIE IT
+// (NO PREDICTION) AND THE THIRD FLIPS THE DOMINANT TIER.
+//
+// FLAPPING: A comm WHOSE CONSECUTIVE OBSERVATIONS KEEP DISAGREEING
+// (BATCH, INTERACTIVE, BATCH, ...) HAS NO TIER WORTH PREDICTING; ITS VOTES
+// ONLY SAY WHICH SIDE IT LANDED ON MORE OFTEN. ONCE FLAP_WITHHOLD_PCT OF
+// ITS RECENT OBSERVATION PAIRS DISAGREE, THE PREDICTION IS PULLED FROM THE
+// INIT MAP AND NEW TASKS START AT THE BPF DEFAULT AGAIN. IT RETURNS WHEN
+// THE RATE FALLS BELOW HALF THAT. RUNTIME ONLY: NOT SAVED OR EXPORTED.
+//
+// FORCED: --force-batch-comm / --force-latcri-comm / --force-file PIN A
+// comm'S TIER. ITS INIT MAP ENTRY CARRIES pinned = 1, SO runnable() NEVER
+// RECLASSIFIES ITS TASKS; INGEST ONLY MARKS IT SEEN, EVICTION AND FLAP
+// WITHHOLDING SKIP IT, AND AN IMPORT (EVEN replace) LEAVES IT ALONE. THE
+// PIN ITSELF IS NOT SAVED OR EXPORTED: IT COMES FROM THIS RUN'S FLAGS.
+//
+// EXPORT/IMPORT: FULL PROFILES (TIER VOTES, NOT THE CACHE'S COLLAPSED
+// DOMINANT TIER) IN A VERSIONED, CHECKSUMMED FILE FOR WARMING UP OTHER
+// MACHINES. A RUNNING SCHEDULER EXCHANGES THEM THROUGH exchange_path()
+// ON A CONTROL SOCKET COMMAND, SO IT NEVER SAVES OVER AN IMPORT.
 
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use libbpf_rs::MapCore;
+use anyhow::{bail, Context, Result};
+
+use crate::pinmap::{open_pinned_with_retry, PIN_OPEN_DELAY};
+use crate::syscalls::{walk_cost, Counted, MapOps, OpBudget, SyscallTally};
 
 fn _timestamp() -> String {
     unsafe {
@@ -37,13 +74,77 @@ macro_rules! procdb_warn {
     ($($arg:tt)*) => { println!("{} [WARN]   {}", _timestamp(), format!($($arg)*)) };
 }
 
-const OBSERVE_PIN: &str = "/sys/fs/bpf/pandemonium/task_class_observe";
-const INIT_PIN: &str = "/sys/fs/bpf/pandemonium/task_class_init";
+// PIN FILE NAMES UNDER THE SCHEDULER'S --pin-dir
+const OBSERVE_PIN: &str = "task_class_observe";
+// MONITOR TICKS BETWEEN ProcessDb::new() RETRIES WHILE THE PINS WON'T OPEN
+pub const INIT_RETRY_TICKS: u64 = 10;
+const INIT_PIN: &str = "task_class_init";
 
 pub const MIN_OBSERVATIONS: u32 = 3;
 pub const MIN_CONFIDENCE: f64 = 0.6;
-pub const MAX_PROFILES: usize = 512;
-pub const STALE_TICKS: u64 = 60;
+pub const MAX_PROFILES: usize = 512; // DEFAULT, --procdb-max
+pub const STALE_TICKS: u64 = 60; // DEFAULT, --procdb-stale-secs (ONE TICK PER SECOND)
+
+// MAP SYSCALLS PER TICK (syscalls.rs): DRAIN, WITHHOLD, EVICTION DELETES AND
+// FLUSH SHARE IT IN THAT ORDER. A DRAINED OBSERVATION COSTS OBSERVE_OPS
+// (get_next_key, lookup, delete). THE REST CARRY OVER: UNDRAINED
+// OBSERVATIONS STAY IN THE LRU MAP, UNFLUSHED PROFILES STAY DIRTY, EVICTION
+// DELETES STAY QUEUED. 256 DRAINS ~80 OBSERVATIONS A TICK WITH NOTHING ELSE
+// DUE; A make -j BURST CATCHES UP OVER THE NEXT FEW.
+pub const OPS_PER_TICK: u64 = 256;
+pub const OBSERVE_OPS: u64 = 3;
+
+// RETENTION SCORE WEIGHTS: CONFIDENCE DOMINATES, SO A LONG-CONFIDENT
+// PROFILE OUTLIVES A BURST OF FRESH ONE-SHOT comms
+pub const RETAIN_W_CONFIDENCE: f64 = 2.0;
+pub const RETAIN_W_OBSERVATIONS: f64 = 1.0;
+pub const RETAIN_W_RECENCY: f64 = 1.0;
+// OBSERVATION TERM SATURATES HERE (LOG SCALE)
+pub const RETAIN_OBS_SATURATION: u32 = 64;
+
+pub const SCHED_FIFO: u8 = 1;
+pub const SCHED_RR: u8 = 2;
+// HALF OF SCHED_CAPACITY_SCALE (1024): THE TASK ASKED FOR AT LEAST HALF A BIG CORE
+pub const UCLAMP_LAT_CRIT_MIN: u32 = 512;
+// ONE PRIORITY OBSERVATION OUTVOTES THIS MANY BEHAVIORAL ONES
+pub const PRIORITY_VOTE_WEIGHT: u32 = 4;
+// A FLUSHED PREDICTION IS REWRITTEN ONLY WHEN ITS TIER CHANGES OR
+// avg_runtime MOVES MORE THAN THIS FAR FROM THE VALUE LAST WRITTEN
+pub const FLUSH_RUNTIME_DELTA_PCT: u64 = 10;
+// A SEED IS WORTH THIS MANY OBSERVATIONS: LOW, SO REAL BEHAVIOR WINS FAST
+pub const SEED_VOTE_WEIGHT: u32 = 2;
+// FLAP RATE: DISAGREEING PAIRS OVER OBSERVATION PAIRS, NO VERDICT BEFORE
+// FLAP_MIN_PAIRS. BOTH COUNTS HALVE AT FLAP_WINDOW SO OLD HISTORY FADES.
+pub const FLAP_MIN_PAIRS: u32 = 6;
+pub const FLAP_WINDOW: u32 = 32;
+pub const FLAP_WITHHOLD_PCT: u32 = 50;
+
+pub const TIER_NAMES: [&str; 3] = ["batch", "interactive", "lat_critical"];
+
+// (comm, TIER, avg_runtime_ns). AUDIO AND DISPLAY PATHS ARE LAT_CRITICAL;
+// COMPILERS, LINKERS AND ENCODERS ARE BATCH.
+pub const BUILTIN_SEEDS: &[(&str, u8, u64)] = &[
+    ("pipewire", 2, 50_000),
+    ("pipewire-pulse", 2, 50_000),
+    ("wireplumber", 1, 100_000),
+    ("pulseaudio", 2, 50_000),
+    ("jackd", 2, 30_000),
+    ("Xwayland", 2, 200_000),
+    ("Xorg", 2, 200_000),
+    ("kwin_wayland", 2, 300_000),
+    ("kwin_x11", 2, 300_000),
+    ("gnome-shell", 2, 300_000),
+    ("cc1", 0, 5_000_000),
+    ("cc1plus", 0, 10_000_000),
+    ("rustc", 0, 10_000_000),
+    ("clang", 0, 10_000_000),
+    ("as", 0, 2_000_000),
+    ("ld", 0, 5_000_000),
+    ("ld.lld", 0, 5_000_000),
+    ("mold", 0, 5_000_000),
+    ("ffmpeg", 0, 8_000_000),
+    ("x264", 0, 8_000_000),
+];
 
 const PROCDB_MAGIC: &[u8; 4] = b"PDDB";
 const PROCDB_VERSION: u32 = 2;
@@ -51,12 +152,22 @@ const PROCDB_PATH: &str = ".cache/pandemonium/procdb.bin";
 const ENTRY_SIZE: usize = 64;
 const V1_ENTRY_SIZE: usize = 40;
 
+// EXPORT FILE: MAGIC + VERSION + COUNT, 72-BYTE ENTRIES, FNV-1a TRAILER
+// OVER EVERYTHING BEFORE IT
+const EXPORT_MAGIC: &[u8; 4] = b"PDEX";
+pub const EXPORT_VERSION: u32 = 1;
+const EXPORT_ENTRY_SIZE: usize = 72;
+const EXCHANGE_FILE: &str = "procdb-exchange.pdex";
+
 // MATCHES struct task_class_entry IN intf.h
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct TaskClassEntry {
     pub tier: u8,
-    pub _pad: [u8; 7],
+    pub policy: u8,
+    pub pinned: u8, // INIT MAP ONLY: FORCED TIER
+    pub _pad: u8,
+    pub uclamp_min: u32,
     pub avg_runtime: u64,
     pub runtime_dev: u64,
     pub wakeup_freq: u64,
@@ -66,7 +177,62 @@ pub struct TaskClassEntry {
 // COMPILE-TIME ABI SAFETY: MUST MATCH struct task_class_entry IN intf.h
 const _: () = assert!(std::mem::size_of::<TaskClassEntry>() == 40);
 
-#[derive(Default)]
+impl TaskClassEntry {
+    // RT POLICY OR HIGH uclamp_min: AN EXPLICIT LATENCY REQUEST
+    pub fn priority_hint(&self) -> bool {
+        self.policy == SCHED_FIFO
+            || self.policy == SCHED_RR
+            || self.uclamp_min >= UCLAMP_LAT_CRIT_MIN
+    }
+}
+
+// TIER FLAP BOOKKEEPING FOR ONE comm
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlapTracker {
+    pub last_tier: Option<u8>, // TIER OF THE PREVIOUS OBSERVATION
+    pub flaps: u32,            // PAIRS THAT DISAGREED
+    pub pairs: u32,            // PAIRS SEEN
+    pub withheld: bool,        // PREDICTION PULLED FOR FLAPPING
+}
+
+impl FlapTracker {
+    // RECORD ONE OBSERVED TIER; true IF IT DISAGREES WITH THE LAST ONE
+    pub fn observe(&mut self, tier: u8) -> bool {
+        let Some(last) = self.last_tier.replace(tier) else {
+            return false;
+        };
+        if self.pairs >= FLAP_WINDOW {
+            self.pairs /= 2;
+            self.flaps /= 2;
+        }
+        self.pairs += 1;
+        let flapped = last != tier;
+        if flapped {
+            self.flaps += 1;
+        }
+        flapped
+    }
+
+    // 0 UNTIL FLAP_MIN_PAIRS PAIRS
+    pub fn rate_pct(&self) -> u32 {
+        if self.pairs < FLAP_MIN_PAIRS {
+            return 0;
+        }
+        self.flaps * 100 / self.pairs
+    }
+
+    // HYSTERESIS: WITHHELD AT FLAP_WITHHOLD_PCT, RELEASED BELOW HALF OF IT
+    pub fn flapping(&self) -> bool {
+        let limit = if self.withheld {
+            FLAP_WITHHOLD_PCT / 2
+        } else {
+            FLAP_WITHHOLD_PCT
+        };
+        self.rate_pct() >= limit
+    }
+}
+
+#[derive(Default, Debug)]
 pub struct TaskProfile {
     pub tier_votes: [u32; 3], // COUNT PER TIER: [BATCH, INTERACTIVE, LAT_CRITICAL]
     pub avg_runtime_ns: u64,
@@ -74,7 +240,12 @@ pub struct TaskProfile {
     pub wakeup_freq: u64,
     pub csw_rate: u64,
     pub observations: u32,
+    pub priority_observations: u32, // OBSERVATIONS CARRYING A PRIORITY HINT
     pub last_seen_tick: u64,
+    pub seeded: bool, // STARTED FROM A SEED ENTRY (SEED_VOTE_WEIGHT VOTES)
+    pub flushed: Option<(u8, u64)>, // (TIER, avg_runtime_ns) IN THE BPF INIT MAP
+    pub flap: FlapTracker,
+    pub forced: Option<u8>, // --force-*-comm TIER: NEVER OVERRIDDEN OR EVICTED
 }
 
 impl TaskProfile {
@@ -96,6 +267,11 @@ impl TaskProfile {
             .unwrap_or(1) // INTERACTIVE DEFAULT
     }
 
+    // THE TIER WRITTEN TO THE INIT MAP: A FORCED TIER BEATS ANY VOTE
+    pub fn predicted_tier(&self) -> u8 {
+        self.forced.unwrap_or_else(|| self.dominant_tier())
+    }
+
     // MULTI-DIMENSIONAL CONFIDENCE: TIER AGREEMENT * BEHAVIORAL STABILITY
     // HIGH RUNTIME VARIANCE REDUCES CONFIDENCE EVEN WITH STRONG TIER AGREEMENT
     pub fn behavioral_confidence(&self) -> f64 {
@@ -111,13 +287,524 @@ impl TaskProfile {
         let stability = (1.0 - dev_ratio.min(1.0)).max(0.0);
         tier_conf * (0.5 + 0.5 * stability)
     }
+
+    // FAST-PATH CONFIDENCE FOR PRIORITY-HINTED PROFILES: TIER AGREEMENT
+    // ALONE, NO MIN_OBSERVATIONS, NO STABILITY TERM. ONLY A LAT_CRITICAL
+    // MAJORITY QUALIFIES: THE HINT VOUCHES FOR NOTHING ELSE.
+    pub fn priority_confidence(&self) -> f64 {
+        if self.priority_observations == 0 || self.dominant_tier() != 2 {
+            return 0.0;
+        }
+        self.confidence()
+    }
+
+    // SEED FAST PATH: TIER AGREEMENT ALONE UNTIL MIN_OBSERVATIONS REAL
+    // OBSERVATIONS EXIST, THEN THE BEHAVIORAL PATH DECIDES
+    pub fn seed_confidence(&self) -> f64 {
+        if !self.seeded || self.observations >= MIN_OBSERVATIONS {
+            return 0.0;
+        }
+        self.confidence()
+    }
+
+    // SEEDED, NEVER OBSERVED
+    pub fn seed_only(&self) -> bool {
+        self.seeded && self.observations == 0
+    }
+
+    // WRITTEN TO THE BPF INIT MAP BY flush_predictions()
+    pub fn predictable(&self) -> bool {
+        self.forced.is_some()
+            || self.behavioral_confidence() >= MIN_CONFIDENCE
+            || self.priority_confidence() >= MIN_CONFIDENCE
+            || self.seed_confidence() >= MIN_CONFIDENCE
+    }
+
+    // PREDICTABLE, NOT WITHHELD, AND NEVER FLUSHED, RE-TIERED, OR
+    // avg_runtime MORE THAN FLUSH_RUNTIME_DELTA_PCT AWAY FROM THE FLUSHED
+    // VALUE. SMALL EWMA DRIFT ACCUMULATES AGAINST THE FLUSHED VALUE, NOT
+    // LAST TICK'S.
+    pub fn dirty(&self) -> bool {
+        if let Some(tier) = self.forced {
+            return self.flushed.map(|(t, _)| t) != Some(tier);
+        }
+        if !self.predictable() || self.flap.withheld {
+            return false;
+        }
+        let Some((tier, runtime)) = self.flushed else {
+            return true;
+        };
+        tier != self.dominant_tier()
+            || self.avg_runtime_ns.abs_diff(runtime) * 100 > runtime * FLUSH_RUNTIME_DELTA_PCT
+    }
+
+    // MERGE AN IMPORTED PROFILE INTO THIS ONE. VOTES AND OBSERVATION
+    // COUNTS ADD. RUNTIMES USE THE SAME 7/8 OLD + 1/8 NEW EWMA AS A LIVE
+    // OBSERVATION: WHAT THIS MACHINE MEASURED OUTRANKS A FLEET EXPORT.
+    // A PROFILE WITH NO LOCAL OBSERVATIONS TAKES THE IMPORTED RUNTIMES.
+    pub fn merge_from(&mut self, other: &TaskProfile) {
+        for (v, o) in self.tier_votes.iter_mut().zip(other.tier_votes) {
+            *v = v.saturating_add(o);
+        }
+        if self.observations == 0 {
+            self.avg_runtime_ns = other.avg_runtime_ns;
+            self.runtime_dev_ns = other.runtime_dev_ns;
+            self.wakeup_freq = other.wakeup_freq;
+            self.csw_rate = other.csw_rate;
+        } else {
+            self.avg_runtime_ns = (self.avg_runtime_ns * 7 + other.avg_runtime_ns) / 8;
+            self.runtime_dev_ns = (self.runtime_dev_ns * 7 + other.runtime_dev_ns) / 8;
+            self.wakeup_freq = (self.wakeup_freq * 7 + other.wakeup_freq) / 8;
+            self.csw_rate = (self.csw_rate * 7 + other.csw_rate) / 8;
+        }
+        self.observations = self.observations.saturating_add(other.observations);
+        self.priority_observations = self
+            .priority_observations
+            .saturating_add(other.priority_observations);
+    }
+}
+
+// ONE --force-*-comm / --force-file ENTRY
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Force {
+    pub comm: [u8; 16],
+    pub tier: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Seed {
+    pub comm: [u8; 16],
+    pub tier: u8,
+    pub avg_runtime_ns: u64,
+}
+
+// BPF comm IS 15 BYTES + NUL
+pub fn comm_key(name: &str) -> [u8; 16] {
+    let mut comm = [0u8; 16];
+    let len = name.len().min(15);
+    comm[..len].copy_from_slice(&name.as_bytes()[..len]);
+    comm
+}
+
+pub fn builtin_seeds() -> Vec<Seed> {
+    BUILTIN_SEEDS
+        .iter()
+        .map(|&(name, tier, avg_runtime_ns)| Seed {
+            comm: comm_key(name),
+            tier,
+            avg_runtime_ns,
+        })
+        .collect()
+}
+
+// ONE MAP ENTRY: THE comm KEY (NUL-PADDED TO 16) AND ITS VALUE. None
+// UNLESS THE VALUE IS EXACTLY ONE TaskClassEntry.
+fn decode_entry(key: &[u8], val: &[u8]) -> Option<([u8; 16], TaskClassEntry)> {
+    if val.len() != std::mem::size_of::<TaskClassEntry>() {
+        return None;
+    }
+    let entry: TaskClassEntry =
+        unsafe { std::ptr::read_unaligned(val.as_ptr() as *const TaskClassEntry) };
+    let mut comm = [0u8; 16];
+    let copy_len = key.len().min(16);
+    comm[..copy_len].copy_from_slice(&key[..copy_len]);
+    Some((comm, entry))
+}
+
+// DRAIN AS MANY OBSERVATIONS AS budget COVERS (OBSERVE_OPS EACH). THE KEY
+// WALK GOES ONE PAST THAT TO LEARN WHETHER ANY ARE LEFT: IF SO THE BUDGET
+// IS MARKED capped AND THEY WAIT IN THE MAP FOR THE NEXT TICK.
+pub fn drain_observations(
+    observe: &impl MapOps,
+    budget: &mut OpBudget,
+) -> Vec<([u8; 16], TaskClassEntry)> {
+    if budget.left() == 0 {
+        budget.cap();
+        return Vec::new();
+    }
+    let max = ((budget.left() - 1) / OBSERVE_OPS) as usize;
+    let mut keys = observe.keys(max + 1);
+    budget.take(walk_cost(keys.len(), max + 1));
+    if keys.len() > max {
+        keys.truncate(max);
+        budget.cap();
+    }
+    let mut observations = Vec::with_capacity(keys.len());
+    for key in &keys {
+        budget.take(OBSERVE_OPS - 1);
+        if let Some(obs) = observe.lookup(key).and_then(|val| decode_entry(key, &val)) {
+            observations.push(obs);
+        }
+        observe.delete(key);
+    }
+    observations
+}
+
+// WRITE EVERY DIRTY PROFILE THROUGH write AND RECORD WHAT WAS FLUSHED.
+// A FAILED WRITE LEAVES THE PROFILE DIRTY FOR THE NEXT TICK.
+// RETURNS (COMM, TIER) FOR EVERY PREDICTION WRITTEN
+pub fn flush_dirty(
+    profiles: &mut HashMap<[u8; 16], TaskProfile>,
+    mut write: impl FnMut(&[u8; 16], &TaskClassEntry) -> bool,
+) -> Vec<([u8; 16], u8)> {
+    let mut written = Vec::new();
+    for (comm, profile) in profiles.iter_mut().filter(|(_, p)| p.dirty()) {
+        let entry = TaskClassEntry {
+            tier: profile.predicted_tier(),
+            policy: 0,
+            pinned: profile.forced.is_some() as u8,
+            _pad: 0,
+            uclamp_min: 0,
+            avg_runtime: profile.avg_runtime_ns,
+            runtime_dev: profile.runtime_dev_ns,
+            wakeup_freq: profile.wakeup_freq,
+            csw_rate: profile.csw_rate,
+        };
+        if write(comm, &entry) {
+            profile.flushed = Some((entry.tier, entry.avg_runtime));
+            written.push((*comm, entry.tier));
+        }
+    }
+    written
+}
+
+// WITHHOLD EVERY PROFILE THAT STARTED FLAPPING: remove ITS FLUSHED
+// PREDICTION (A FAILED REMOVE RETRIES NEXT TICK). A PROFILE THAT SETTLED
+// IS RELEASED AND, NO LONGER FLUSHED, WRITTEN AGAIN BY flush_dirty().
+// RETURNS (COMM, FLAP %) FOR EVERY PROFILE NEWLY WITHHELD
+pub fn withhold_flapping(
+    profiles: &mut HashMap<[u8; 16], TaskProfile>,
+    mut remove: impl FnMut(&[u8; 16]) -> bool,
+) -> Vec<([u8; 16], u32)> {
+    let mut withheld = Vec::new();
+    for (comm, profile) in profiles.iter_mut().filter(|(_, p)| p.forced.is_none()) {
+        let flapping = profile.flap.flapping();
+        if flapping == profile.flap.withheld {
+            continue;
+        }
+        if !flapping {
+            profile.flap.withheld = false;
+            continue;
+        }
+        if profile.flushed.is_some() && !remove(comm) {
+            continue;
+        }
+        profile.flushed = None;
+        profile.flap.withheld = true;
+        withheld.push((*comm, profile.flap.rate_pct()));
+    }
+    withheld
+}
+
+// --seed-file: ONE "comm tier avg_runtime_us" PER LINE, '#' COMMENTS.
+// TIER IS batch / interactive / lat_critical (ANY CASE).
+pub fn parse_seed_file(text: &str) -> Result<Vec<Seed>> {
+    let mut seeds = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, tier, runtime_us] = fields.as_slice() else {
+            bail!(
+                "SEED LINE {}: EXPECTED \"comm tier avg_runtime_us\", GOT {:?}",
+                i + 1,
+                raw
+            );
+        };
+        if name.len() > 15 {
+            bail!("SEED LINE {}: comm {:?} LONGER THAN 15 BYTES", i + 1, name);
+        }
+        let Some(tier) = TIER_NAMES.iter().position(|t| t.eq_ignore_ascii_case(tier)) else {
+            bail!(
+                "SEED LINE {}: UNKNOWN TIER {:?} ({})",
+                i + 1,
+                tier,
+                TIER_NAMES.join(", ")
+            );
+        };
+        let Ok(runtime_us) = runtime_us.parse::<u64>() else {
+            bail!("SEED LINE {}: BAD avg_runtime_us {:?}", i + 1, runtime_us);
+        };
+        seeds.push(Seed {
+            comm: comm_key(name),
+            tier: tier as u8,
+            avg_runtime_ns: runtime_us.saturating_mul(1000),
+        });
+    }
+    Ok(seeds)
+}
+
+// ONE --force-batch-comm / --force-latcri-comm NAME
+pub fn forced_comm(name: &str, tier: u8) -> Result<Force> {
+    if name.is_empty() || name.len() > 15 {
+        bail!("FORCED comm {:?} MUST BE 1-15 BYTES", name);
+    }
+    Ok(Force {
+        comm: comm_key(name),
+        tier,
+    })
+}
+
+// --force-file: ONE "comm tier" PER LINE, '#' COMMENTS. TIER AS IN
+// parse_seed_file; A comm LISTED TWICE KEEPS ITS LAST TIER.
+pub fn parse_force_file(text: &str) -> Result<Vec<Force>> {
+    let mut forces = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, tier] = fields.as_slice() else {
+            bail!(
+                "FORCE LINE {}: EXPECTED \"comm tier\", GOT {:?}",
+                i + 1,
+                raw
+            );
+        };
+        if name.len() > 15 {
+            bail!("FORCE LINE {}: comm {:?} LONGER THAN 15 BYTES", i + 1, name);
+        }
+        let Some(tier) = TIER_NAMES.iter().position(|t| t.eq_ignore_ascii_case(tier)) else {
+            bail!(
+                "FORCE LINE {}: UNKNOWN TIER {:?} ({})",
+                i + 1,
+                tier,
+                TIER_NAMES.join(", ")
+            );
+        };
+        forces.push(Force {
+            comm: comm_key(name),
+            tier: tier as u8,
+        });
+    }
+    Ok(forces)
+}
+
+// `pandemonium procdb list`: ONE ROW PER INIT MAP ENTRY, SORTED BY comm.
+// FORCED ENTRIES ARE FLAGGED. PREEMPTED/S COMES FROM THE RUNNING
+// SCHEDULER (None: NOT RUNNING, "-"); A comm IT NEVER PREEMPTED IS 0.
+pub fn list_rows(
+    entries: &[([u8; 16], TaskClassEntry)],
+    preempted: Option<&HashMap<String, f64>>,
+) -> Vec<String> {
+    let mut sorted: Vec<_> = entries.iter().collect();
+    sorted.sort_by_key(|(comm, _)| *comm);
+    let mut rows = vec![format!(
+        "{:<16} {:<12} {:>10} {:>11}  {}",
+        "COMM", "TIER", "AVG_US", "PREEMPTED/S", "FLAGS"
+    )];
+    for (comm, e) in sorted {
+        let name = crate::diag::comm_str(comm);
+        let tier = TIER_NAMES.get(e.tier as usize).unwrap_or(&"?");
+        let rate = match preempted {
+            Some(rates) => format!("{:.2}", rates.get(&name).copied().unwrap_or(0.0)),
+            None => "-".to_string(),
+        };
+        let flags = if e.pinned != 0 { "FORCED" } else { "" };
+        rows.push(
+            format!(
+                "{:<16} {:<12} {:>10} {:>11}  {}",
+                name,
+                tier,
+                e.avg_runtime / 1000,
+                rate,
+                flags
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+    rows
+}
+
+// SAME FNV-1a AS THE BLACK BOX SLOT CRC
+fn fnv1a(data: &[u8]) -> u32 {
+    let mut h: u32 = 0x811c9dc5;
+    for &b in data {
+        h ^= b as u32;
+        h = h.wrapping_mul(0x01000193);
+    }
+    h
+}
+
+// SORTED BY comm: THE SAME DATABASE ALWAYS EXPORTS THE SAME BYTES.
+// SEED-ONLY PROFILES STAY HOME: EVERY MACHINE HAS ITS OWN SEED TABLE.
+// SO DO UNOBSERVED FORCED ONES; AN OBSERVED ONE EXPORTS ITS VOTES ONLY.
+pub fn encode_export(profiles: &HashMap<[u8; 16], TaskProfile>) -> Vec<u8> {
+    let mut entries: Vec<_> = profiles
+        .iter()
+        .filter(|(_, p)| !p.seed_only() && (p.forced.is_none() || p.observations > 0))
+        .collect();
+    entries.sort_by_key(|(comm, _)| **comm);
+
+    let mut out = Vec::with_capacity(12 + entries.len() * EXPORT_ENTRY_SIZE + 4);
+    out.extend_from_slice(EXPORT_MAGIC);
+    out.extend_from_slice(&EXPORT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (comm, p) in entries {
+        out.extend_from_slice(comm.as_slice()); // 16 bytes
+        for v in p.tier_votes {
+            out.extend_from_slice(&v.to_le_bytes()); // 12 bytes
+        }
+        out.extend_from_slice(&p.priority_observations.to_le_bytes()); // 4 bytes
+        out.extend_from_slice(&p.avg_runtime_ns.to_le_bytes()); // 8 bytes
+        out.extend_from_slice(&p.runtime_dev_ns.to_le_bytes()); // 8 bytes
+        out.extend_from_slice(&p.wakeup_freq.to_le_bytes()); // 8 bytes
+        out.extend_from_slice(&p.csw_rate.to_le_bytes()); // 8 bytes
+        out.extend_from_slice(&p.observations.to_le_bytes()); // 4 bytes
+        out.extend_from_slice(&[0u8; 4]); // 4 bytes pad
+    }
+    let crc = fnv1a(&out);
+    out.extend_from_slice(&crc.to_le_bytes());
+    out
+}
+
+// UNLIKE load_from_disk, EVERY DEFECT IS AN ERROR: AN IMPORT THE USER
+// ASKED FOR MUST NOT SILENTLY MERGE NOTHING (OR GARBAGE)
+pub fn decode_export(data: &[u8]) -> Result<HashMap<[u8; 16], TaskProfile>> {
+    let rd_u32 = |off: usize| u32::from_le_bytes(data[off..off + 4].try_into().unwrap());
+    let rd_u64 = |off: usize| u64::from_le_bytes(data[off..off + 8].try_into().unwrap());
+
+    if data.len() < 16 {
+        bail!("PROCDB EXPORT TOO SHORT ({} BYTES)", data.len());
+    }
+    if &data[0..4] != EXPORT_MAGIC {
+        bail!("NOT A PROCDB EXPORT (MAGIC {:?})", &data[0..4]);
+    }
+    let version = rd_u32(4);
+    if version != EXPORT_VERSION {
+        bail!(
+            "UNSUPPORTED PROCDB EXPORT VERSION {} (EXPECTED {})",
+            version,
+            EXPORT_VERSION
+        );
+    }
+    let count = rd_u32(8) as usize;
+    let expected = count
+        .checked_mul(EXPORT_ENTRY_SIZE)
+        .and_then(|n| n.checked_add(16));
+    if expected != Some(data.len()) {
+        bail!(
+            "PROCDB EXPORT SIZE MISMATCH ({} ENTRIES, {} BYTES)",
+            count,
+            data.len()
+        );
+    }
+    let body = data.len() - 4;
+    let (stored, actual) = (rd_u32(body), fnv1a(&data[..body]));
+    if stored != actual {
+        bail!(
+            "PROCDB EXPORT CHECKSUM MISMATCH (STORED {:08x}, COMPUTED {:08x})",
+            stored,
+            actual
+        );
+    }
+
+    let mut profiles = HashMap::with_capacity(count);
+    for i in 0..count {
+        let off = 12 + i * EXPORT_ENTRY_SIZE;
+        let mut comm = [0u8; 16];
+        comm.copy_from_slice(&data[off..off + 16]);
+        let profile = TaskProfile {
+            tier_votes: [rd_u32(off + 16), rd_u32(off + 20), rd_u32(off + 24)],
+            priority_observations: rd_u32(off + 28),
+            avg_runtime_ns: rd_u64(off + 32),
+            runtime_dev_ns: rd_u64(off + 40),
+            wakeup_freq: rd_u64(off + 48),
+            csw_rate: rd_u64(off + 56),
+            observations: rd_u32(off + 64),
+            last_seen_tick: 0,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
+        };
+        if profiles.insert(comm, profile).is_some() {
+            bail!("PROCDB EXPORT HAS A DUPLICATE comm AT ENTRY {}", i);
+        }
+    }
+    Ok(profiles)
+}
+
+// WHAT ONE flush_predictions() DID
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct FlushReport {
+    pub written: Vec<([u8; 16], u8)>,   // (COMM, TIER) WRITTEN
+    pub withheld: Vec<([u8; 16], u32)>, // (COMM, FLAP %) NEWLY WITHHELD
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcDbLimits {
+    pub max_profiles: usize,
+    pub stale_ticks: u64,
+    pub ops_per_tick: u64,
+}
+
+impl Default for ProcDbLimits {
+    fn default() -> Self {
+        Self {
+            max_profiles: MAX_PROFILES,
+            stale_ticks: STALE_TICKS,
+            ops_per_tick: OPS_PER_TICK,
+        }
+    }
+}
+
+// HIGHER = KEEP. EACH TERM IS IN [0, 1] BEFORE WEIGHTING:
+//   CONFIDENCE   max(behavioral, priority fast path)
+//   OBSERVATIONS ln(1 + obs) / ln(1 + RETAIN_OBS_SATURATION), CAPPED
+//   RECENCY      1 - age / stale_ticks, CLAMPED
+pub fn retention_score(profile: &TaskProfile, tick: u64, stale_ticks: u64) -> f64 {
+    let confidence = profile
+        .behavioral_confidence()
+        .max(profile.priority_confidence());
+    let observations = ((1.0 + profile.observations as f64).ln()
+        / (1.0 + RETAIN_OBS_SATURATION as f64).ln())
+    .min(1.0);
+    let age = tick.saturating_sub(profile.last_seen_tick) as f64;
+    let recency = (1.0 - age / stale_ticks.max(1) as f64).clamp(0.0, 1.0);
+    RETAIN_W_CONFIDENCE * confidence
+        + RETAIN_W_OBSERVATIONS * observations
+        + RETAIN_W_RECENCY * recency
+}
+
+// THE LIVE MAPS. MapCore SPELLED OUT: ITS keys/lookup/update/delete
+// SHARE MapOps' NAMES.
+impl MapOps for libbpf_rs::MapHandle {
+    fn lookup(&self, key: &[u8]) -> Option<Vec<u8>> {
+        libbpf_rs::MapCore::lookup(self, key, libbpf_rs::MapFlags::ANY)
+            .ok()
+            .flatten()
+    }
+
+    fn update(&self, key: &[u8], value: &[u8]) -> bool {
+        libbpf_rs::MapCore::update(self, key, value, libbpf_rs::MapFlags::ANY).is_ok()
+    }
+
+    fn delete(&self, key: &[u8]) -> bool {
+        libbpf_rs::MapCore::delete(self, key).is_ok()
+    }
+
+    fn keys(&self, max: usize) -> Vec<Vec<u8>> {
+        libbpf_rs::MapCore::keys(self).take(max).collect()
+    }
 }
 
 pub struct ProcessDb {
-    pub observe: Option<libbpf_rs::MapHandle>,
-    pub init: Option<libbpf_rs::MapHandle>,
+    pub observe: Option<Box<dyn MapOps>>,
+    pub init: Option<Box<dyn MapOps>>,
     pub profiles: HashMap<[u8; 16], TaskProfile>,
     pub tick: u64,
+    pub limits: ProcDbLimits,
+    pub evictions: u64, // CUMULATIVE: STALE + OVER-CAP
+    pub ingested: u64,  // CUMULATIVE OBSERVATIONS DRAINED FROM BPF
+    // EVERY MAP CALL THROUGH observe AND init
+    pub syscalls: SyscallTally,
+    // INIT MAP DELETES OF EVICTED comms, NOT YET MADE (OVER BUDGET)
+    pending_deletes: Vec<[u8; 16]>,
 }
 
 impl ProcessDb {
@@ -126,9 +813,38 @@ impl ProcessDb {
         PathBuf::from(home).join(PROCDB_PATH)
     }
 
-    pub fn new() -> Result<Self> {
-        let observe = libbpf_rs::MapHandle::from_pinned_path(OBSERVE_PIN)?;
-        let init = libbpf_rs::MapHandle::from_pinned_path(INIT_PIN)?;
+    // HAND-OFF FILE BETWEEN `pandemonium procdb` AND A RUNNING SCHEDULER
+    pub fn exchange_path() -> PathBuf {
+        Self::default_path().with_file_name(EXCHANGE_FILE)
+    }
+
+    // NO BPF MAPS: `pandemonium procdb` EDITING THE CACHE WHILE NOTHING RUNS
+    pub fn detached(profiles: HashMap<[u8; 16], TaskProfile>) -> Self {
+        Self {
+            observe: None,
+            init: None,
+            profiles,
+            tick: 0,
+            limits: ProcDbLimits::default(),
+            evictions: 0,
+            ingested: 0,
+            syscalls: SyscallTally::default(),
+            pending_deletes: Vec::new(),
+        }
+    }
+
+    // attempts: PINNED MAP OPENS BEFORE GIVING UP (pinmap.rs)
+    pub fn new(
+        pin_dir: &Path,
+        attempts: u32,
+        limits: ProcDbLimits,
+        seeds: &[Seed],
+        forces: &[Force],
+    ) -> Result<Self> {
+        let observe = open_pinned_with_retry(&pin_dir.join(OBSERVE_PIN), attempts, PIN_OPEN_DELAY)
+            .context("PROCDB OBSERVE MAP")?;
+        let init = open_pinned_with_retry(&pin_dir.join(INIT_PIN), attempts, PIN_OPEN_DELAY)
+            .context("PROCDB INIT MAP")?;
 
         let db_path = Self::default_path();
         let profiles = match Self::load_from_disk(&db_path) {
@@ -148,139 +864,300 @@ impl ProcessDb {
             }
         };
 
-        let db = Self {
-            observe: Some(observe),
-            init: Some(init),
+        let mut db = Self {
+            observe: Some(Box::new(observe)),
+            init: Some(Box::new(init)),
             profiles,
             tick: 0,
+            limits,
+            evictions: 0,
+            ingested: 0,
+            syscalls: SyscallTally::default(),
+            pending_deletes: Vec::new(),
         };
 
+        let seeded = db.seed(seeds);
+        if seeded > 0 {
+            procdb_info!("PROCDB: SEEDED {} OF {} PROFILES", seeded, seeds.len());
+        }
+        if db.force(forces) > 0 {
+            procdb_info!("PROCDB: FORCED {} comms", forces.len());
+        }
         db.flush_predictions();
         Ok(db)
     }
 
-    // DRAIN OBSERVATIONS FROM BPF LRU MAP, MERGE INTO PROFILES
-    pub fn ingest(&mut self) {
+    // DRAIN OBSERVATIONS FROM BPF LRU MAP (AS MANY AS budget COVERS),
+    // MERGE INTO PROFILES
+    pub fn ingest(&mut self, budget: &mut OpBudget) {
         let observe = match &self.observe {
             Some(m) => m,
             None => return,
         };
-        let keys: Vec<Vec<u8>> = observe.keys().collect();
-        for key in &keys {
-            if let Ok(Some(val)) = observe.lookup(key, libbpf_rs::MapFlags::ANY) {
-                if val.len() >= std::mem::size_of::<TaskClassEntry>() {
-                    let entry: TaskClassEntry =
-                        unsafe { std::ptr::read_unaligned(val.as_ptr() as *const TaskClassEntry) };
-
-                    let mut comm = [0u8; 16];
-                    let copy_len = key.len().min(16);
-                    comm[..copy_len].copy_from_slice(&key[..copy_len]);
-
-                    let profile = self.profiles.entry(comm).or_insert(TaskProfile {
-                        ..Default::default()
-                    });
-
-                    let tier_idx = (entry.tier as usize).min(2);
-                    profile.tier_votes[tier_idx] += 1;
-                    if profile.observations == 0 {
-                        profile.avg_runtime_ns = entry.avg_runtime;
-                        profile.runtime_dev_ns = entry.runtime_dev;
-                        profile.wakeup_freq = entry.wakeup_freq;
-                        profile.csw_rate = entry.csw_rate;
-                    } else {
-                        // EWMA: 7/8 OLD + 1/8 NEW
-                        profile.avg_runtime_ns =
-                            (profile.avg_runtime_ns * 7 + entry.avg_runtime) / 8;
-                        profile.runtime_dev_ns =
-                            (profile.runtime_dev_ns * 7 + entry.runtime_dev) / 8;
-                        profile.wakeup_freq = (profile.wakeup_freq * 7 + entry.wakeup_freq) / 8;
-                        profile.csw_rate = (profile.csw_rate * 7 + entry.csw_rate) / 8;
-                    }
-                    profile.observations += 1;
-                    profile.last_seen_tick = self.tick;
-                }
+        let observations =
+            drain_observations(&Counted::new(observe.as_ref(), &self.syscalls), budget);
+        self.ingested += observations.len() as u64;
+        for (comm, entry) in &observations {
+            self.merge_observation(*comm, entry);
+        }
+    }
+
+    // ADD A SEED PROFILE FOR EVERY comm NOT ALREADY KNOWN (PERSISTED OR
+    // OBSERVED PROFILES ALWAYS WIN). RETURNS PROFILES ADDED.
+    pub fn seed(&mut self, seeds: &[Seed]) -> usize {
+        let mut added = 0;
+        for s in seeds {
+            if self.profiles.contains_key(&s.comm) {
+                continue;
             }
-            let _ = observe.delete(key);
+            let mut tier_votes = [0u32; 3];
+            tier_votes[(s.tier as usize).min(2)] = SEED_VOTE_WEIGHT;
+            self.profiles.insert(
+                s.comm,
+                TaskProfile {
+                    tier_votes,
+                    avg_runtime_ns: s.avg_runtime_ns,
+                    last_seen_tick: self.tick,
+                    seeded: true,
+                    ..Default::default()
+                },
+            );
+            added += 1;
         }
+        added
     }
 
-    // WRITE CONFIDENT PREDICTIONS TO BPF INIT MAP
-    pub fn flush_predictions(&self) {
-        let init = match &self.init {
-            Some(m) => m,
-            None => return,
+    // PIN EACH comm TO ITS FORCED TIER, CREATING THE PROFILE IF NEEDED.
+    // A KNOWN PROFILE KEEPS ITS VOTES (SAVED AS BEFORE) BUT NO LONGER
+    // FLAPS. RETURNS PROFILES PINNED.
+    pub fn force(&mut self, forces: &[Force]) -> usize {
+        for f in forces {
+            let profile = self.profiles.entry(f.comm).or_default();
+            profile.forced = Some(f.tier.min(2));
+            profile.flap = FlapTracker::default();
+            profile.last_seen_tick = self.tick;
+        }
+        forces.len()
+    }
+
+    // MERGE ONE BPF OBSERVATION INTO ITS comm PROFILE. A FORCED PROFILE
+    // IS ONLY MARKED SEEN: INGEST NEVER OVERRIDES A PIN.
+    pub fn merge_observation(&mut self, comm: [u8; 16], entry: &TaskClassEntry) {
+        let profile = self.profiles.entry(comm).or_insert(TaskProfile {
+            ..Default::default()
+        });
+        if profile.forced.is_some() {
+            profile.last_seen_tick = self.tick;
+            return;
+        }
+
+        let tier = if entry.priority_hint() {
+            profile.tier_votes[2] += PRIORITY_VOTE_WEIGHT;
+            profile.priority_observations += 1;
+            2
+        } else {
+            let tier_idx = (entry.tier as usize).min(2);
+            profile.tier_votes[tier_idx] += 1;
+            tier_idx as u8
+        };
+        profile.flap.observe(tier);
+        if profile.observations == 0 {
+            profile.avg_runtime_ns = entry.avg_runtime;
+            profile.runtime_dev_ns = entry.runtime_dev;
+            profile.wakeup_freq = entry.wakeup_freq;
+            profile.csw_rate = entry.csw_rate;
+        } else {
+            // EWMA: 7/8 OLD + 1/8 NEW
+            profile.avg_runtime_ns = (profile.avg_runtime_ns * 7 + entry.avg_runtime) / 8;
+            profile.runtime_dev_ns = (profile.runtime_dev_ns * 7 + entry.runtime_dev) / 8;
+            profile.wakeup_freq = (profile.wakeup_freq * 7 + entry.wakeup_freq) / 8;
+            profile.csw_rate = (profile.csw_rate * 7 + entry.csw_rate) / 8;
+        }
+        profile.observations += 1;
+        profile.last_seen_tick = self.tick;
+    }
+
+    // WRITE NEW OR MEANINGFULLY CHANGED PREDICTIONS TO THE BPF INIT MAP,
+    // AFTER PULLING THOSE OF FLAPPING comms. A STABLE PROFILE COSTS ONE
+    // bpf_map_update_elem, NOT ONE PER TICK. UNCAPPED: STARTUP AND IMPORTS.
+    pub fn flush_predictions(&mut self) -> FlushReport {
+        self.flush_within(&mut OpBudget::unlimited())
+    }
+
+    // flush_predictions() FOR A TICK: ONE SYSCALL PER WITHHOLD, QUEUED
+    // EVICTION DELETE OR WRITE WHILE budget LASTS. EVICTION DELETES GO
+    // BEFORE ANY WRITE, SO A comm EVICTED AND SEEN AGAIN NEVER LOSES ITS
+    // NEW PREDICTION TO ITS OLD DELETE.
+    pub fn flush_within(&mut self, budget: &mut OpBudget) -> FlushReport {
+        let Some(init) = &self.init else {
+            return FlushReport::default();
+        };
+        let init = Counted::new(init.as_ref(), &self.syscalls);
+        let withheld = withhold_flapping(&mut self.profiles, |comm| {
+            budget.take(1) && init.delete(comm.as_slice())
+        });
+        let mut done = 0;
+        while done < self.pending_deletes.len() && budget.take(1) {
+            init.delete(self.pending_deletes[done].as_slice());
+            done += 1;
+        }
+        self.pending_deletes.drain(..done);
+        let written = flush_dirty(&mut self.profiles, |comm, entry| {
+            let val = unsafe {
+                std::slice::from_raw_parts(
+                    entry as *const TaskClassEntry as *const u8,
+                    std::mem::size_of::<TaskClassEntry>(),
+                )
+            };
+            budget.take(1) && init.update(comm.as_slice(), val)
+        });
+        FlushReport { written, withheld }
+    }
+
+    // WORK CARRIED INTO THE NEXT TICK: UNFLUSHED PROFILES AND QUEUED
+    // EVICTION DELETES (UNDRAINED OBSERVATIONS ARE UNCOUNTED: COUNTING
+    // THEM IS A KEY WALK)
+    pub fn backlog(&self) -> usize {
+        let unflushed = match self.init {
+            Some(_) => self.profiles.values().filter(|p| p.dirty()).count(),
+            None => 0,
         };
-        for (comm, profile) in &self.profiles {
-            if profile.behavioral_confidence() >= MIN_CONFIDENCE {
-                let entry = TaskClassEntry {
-                    tier: profile.dominant_tier(),
-                    _pad: [0; 7],
-                    avg_runtime: profile.avg_runtime_ns,
-                    runtime_dev: profile.runtime_dev_ns,
-                    wakeup_freq: profile.wakeup_freq,
-                    csw_rate: profile.csw_rate,
-                };
-
-                let val = unsafe {
-                    std::slice::from_raw_parts(
-                        &entry as *const TaskClassEntry as *const u8,
-                        std::mem::size_of::<TaskClassEntry>(),
-                    )
-                };
-                let _ = init.update(comm.as_slice(), val, libbpf_rs::MapFlags::ANY);
+        unflushed + self.pending_deletes.len()
+    }
+
+    // MERGE (OR WITH replace, SWAP IN) IMPORTED PROFILES. IMPORTED AND
+    // MERGED PROFILES COUNT AS SEEN NOW, SO STALE EVICTION GIVES THEM A
+    // FULL stale_ticks WINDOW TO MEET A LIVE TASK. FORCED PROFILES SURVIVE
+    // replace AND KEEP THEIR PIN WHEN MERGED.
+    // RETURNS (ADDED, MERGED)
+    pub fn import(
+        &mut self,
+        incoming: HashMap<[u8; 16], TaskProfile>,
+        replace: bool,
+    ) -> (usize, usize) {
+        if replace {
+            if let Some(ref init) = self.init {
+                let init = Counted::new(init.as_ref(), &self.syscalls);
+                for (comm, _) in self.profiles.iter().filter(|(_, p)| p.forced.is_none()) {
+                    init.delete(comm.as_slice());
+                }
             }
+            self.profiles.retain(|_, p| p.forced.is_some());
+        }
+        let (mut added, mut merged) = (0, 0);
+        for (comm, mut profile) in incoming {
+            match self.profiles.get_mut(&comm) {
+                Some(existing) => {
+                    existing.merge_from(&profile);
+                    existing.last_seen_tick = self.tick;
+                    merged += 1;
+                }
+                None => {
+                    profile.last_seen_tick = self.tick;
+                    self.profiles.insert(comm, profile);
+                    added += 1;
+                }
+            }
+        }
+        (added, merged)
+    }
+
+    pub fn export_to(&self, path: &Path) -> Result<usize> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
         }
+        std::fs::write(path, encode_export(&self.profiles))?;
+        Ok(self.profiles.len())
+    }
+
+    // READ AND VALIDATE path, THEN import(); FLUSHES PREDICTIONS WHEN LIVE
+    pub fn import_from(&mut self, path: &Path, replace: bool) -> Result<(usize, usize)> {
+        let incoming = decode_export(&std::fs::read(path)?)?;
+        let counts = self.import(incoming, replace);
+        self.flush_predictions();
+        Ok(counts)
+    }
+
+    // THE INIT MAP DELETE WAITS FOR THE NEXT flush_within()'S BUDGET
+    fn evict(&mut self, comm: &[u8; 16]) {
+        self.profiles.remove(comm);
+        if self.init.is_some() {
+            self.pending_deletes.push(*comm);
+        }
+        self.evictions += 1;
     }
 
     // EVICT STALE PROFILES, CAP TOTAL ENTRIES
-    pub fn tick(&mut self) {
+    // RETURNS PROFILES EVICTED THIS TICK (EVICTIONS/SEC IN TELEMETRY)
+    pub fn tick(&mut self) -> usize {
         self.tick += 1;
+        let before = self.evictions;
 
-        // REMOVE PROFILES NOT SEEN IN 60 SECONDS
+        // REMOVE PROFILES NOT SEEN IN stale_ticks SECONDS. SEED-ONLY PROFILES
+        // ARE WAITING FOR THEIR FIRST TASK, NOT STALE; THEY SCORE LOWEST
+        // BELOW AND GO FIRST UNDER CAP PRESSURE. FORCED PROFILES ARE NEVER
+        // EVICTED AND NEVER CANDIDATES.
         let tick = self.tick;
+        let stale_ticks = self.limits.stale_ticks;
         let stale: Vec<[u8; 16]> = self
             .profiles
             .iter()
-            .filter(|(_, p)| tick - p.last_seen_tick > STALE_TICKS)
+            .filter(|(_, p)| {
+                p.forced.is_none() && !p.seed_only() && tick - p.last_seen_tick > stale_ticks
+            })
             .map(|(k, _)| *k)
             .collect();
         for comm in &stale {
-            self.profiles.remove(comm);
-            if let Some(ref init) = self.init {
-                let _ = init.delete(comm.as_slice());
-            }
+            self.evict(comm);
         }
 
-        // CAP ENTRIES: EVICT OLDEST FIRST, TIE-BREAK BY OBSERVATIONS THEN COMM
-        if self.profiles.len() > MAX_PROFILES {
-            let mut entries: Vec<([u8; 16], u64, u32)> = self
+        // CAP ENTRIES: EVICT LOWEST retention_score FIRST,
+        // TIE-BREAK BY LAST SEEN, OBSERVATIONS, THEN COMM
+        let max_profiles = self.limits.max_profiles;
+        if self.profiles.len() > max_profiles {
+            let mut entries: Vec<(f64, u64, u32, [u8; 16])> = self
                 .profiles
                 .iter()
-                .map(|(k, v)| (*k, v.last_seen_tick, v.observations))
+                .filter(|(_, v)| v.forced.is_none())
+                .map(|(k, v)| {
+                    let score = retention_score(v, tick, stale_ticks);
+                    (score, v.last_seen_tick, v.observations, *k)
+                })
                 .collect();
-            entries.sort_by(|a, b| (a.1, a.2, a.0).cmp(&(b.1, b.2, b.0)));
-            let to_remove = self.profiles.len() - MAX_PROFILES;
-            for (k, _, _) in entries.into_iter().take(to_remove) {
-                self.profiles.remove(&k);
-                if let Some(ref init) = self.init {
-                    let _ = init.delete(k.as_slice());
-                }
+            entries.sort_by(|a, b| {
+                a.0.total_cmp(&b.0)
+                    .then((a.1, a.2, a.3).cmp(&(b.1, b.2, b.3)))
+            });
+            let to_remove = self.profiles.len() - max_profiles;
+            for (_, _, _, k) in entries.into_iter().take(to_remove) {
+                self.evict(&k);
             }
         }
+        (self.evictions - before) as usize
+    }
+
+    // EVERY ENTRY IN A RUNNING SCHEDULER'S INIT MAP: WHAT NEW TASKS
+    // START WITH, FORCED ONES FLAGGED (pinned)
+    pub fn read_init(pin_dir: &Path) -> Result<Vec<([u8; 16], TaskClassEntry)>> {
+        let init = open_pinned_with_retry(&pin_dir.join(INIT_PIN), 1, PIN_OPEN_DELAY)
+            .context("PROCDB INIT MAP")?;
+        Ok(init
+            .keys(usize::MAX)
+            .iter()
+            .filter_map(|key| decode_entry(key, &init.lookup(key)?))
+            .collect())
     }
 
     // (TOTAL PROFILES, CONFIDENT PROFILES)
     pub fn summary(&self) -> (usize, usize) {
         let total = self.profiles.len();
-        let confident = self
-            .profiles
-            .values()
-            .filter(|p| p.behavioral_confidence() >= MIN_CONFIDENCE)
-            .count();
+        let confident = self.profiles.values().filter(|p| p.predictable()).count();
         (total, confident)
     }
 
     // SERIALIZE CONFIDENT PROFILES TO DISK (ATOMIC WRITE)
+    // PRIORITY FAST-PATH PROFILES PERSIST ONLY ONCE BEHAVIORALLY CONFIDENT:
+    // THE HINT IS NOT SAVED AND RETURNS WITH THE NEXT LIVE OBSERVATION
     pub fn save(&self, path: &Path) -> Result<()> {
         let entries: Vec<_> = self
             .profiles
@@ -411,7 +1288,12 @@ impl ProcessDb {
                     wakeup_freq,
                     csw_rate,
                     observations,
+                    priority_observations: 0,
                     last_seen_tick: 0,
+                    seeded: false,
+                    flushed: None,
+                    flap: FlapTracker::default(),
+                    forced: None,
                 },
             );
         }
@@ -0,0 +1,110 @@
+// PANDEMONIUM RESERVED CPUS (--reserved-cpus)
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// SOFT-OFFLINE: THE CPUS STAY ONLINE, BUT PANDEMONIUM LEAVES THEM TO
+// WHATEVER IS PINNED THERE (A DAW'S AUDIO THREAD, vCPU THREADS). BPF
+// NEVER PICKS THEM FOR AN IDLE CPU, NEVER KICKS OR STEALS ONTO THEM, AND
+// A RESERVED CPU'S dispatch() ONLY DRAINS ITS OWN PER-CPU DSQ: TASKS
+// STRICTLY AFFINED TO RESERVED CPUS STILL RUN THERE.
+//
+// RUST LEAVES THEM OUT OF idle_pct (stats::PandemoniumStats::total_except),
+// IDLE RESIDENCY, THE L2 SIBLING GROUPS AND THE HYBRID FAST-CPU LIST.
+//
+// THE LIST GOES THROUGH THE STRICT cpulist PARSER: A TYPO MUST FAIL AT
+// STARTUP, NOT RESERVE THE WRONG CPUS.
+
+use crate::cpulist::{self, CpuListError};
+use crate::hotplug::CPU_BITMAP_WORDS;
+
+// struct cpu_bitmap CAPACITY (MAX_CPUS IN intf.h)
+pub const MAX_RESERVED_CPU: u32 = (CPU_BITMAP_WORDS * 64) as u32 - 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReservedCpusError {
+    Empty,                             // NO CPU AT ALL
+    EmptyToken,                        // "2,,3" OR A TRAILING COMMA
+    BadToken(String),                  // NOT N OR A-B
+    Reversed { start: u32, end: u32 }, // "5-3"
+    TooLarge(u64),                     // PAST MAX_RESERVED_CPU
+    Offline(Vec<u32>),                 // NOT IN THE ONLINE SET
+    AllOnline,                         // NOTHING LEFT TO SCHEDULE ON
+}
+
+impl From<CpuListError> for ReservedCpusError {
+    fn from(e: CpuListError) -> Self {
+        match e {
+            CpuListError::Empty => Self::Empty,
+            CpuListError::EmptyToken => Self::EmptyToken,
+            CpuListError::BadToken(t) => Self::BadToken(t),
+            CpuListError::Reversed { start, end } => Self::Reversed { start, end },
+            CpuListError::TooLarge(cpu) => Self::TooLarge(cpu),
+        }
+    }
+}
+
+impl ReservedCpusError {
+    pub fn message(&self) -> String {
+        let what = match self {
+            Self::Empty => CpuListError::Empty.message(),
+            Self::EmptyToken => CpuListError::EmptyToken.message(),
+            Self::BadToken(t) => CpuListError::BadToken(t.clone()).message(),
+            Self::Reversed { start, end } => CpuListError::Reversed {
+                start: *start,
+                end: *end,
+            }
+            .message(),
+            Self::TooLarge(cpu) => {
+                format!(
+                    "CPU {} PAST THE LAST SUPPORTED CPU {}",
+                    cpu, MAX_RESERVED_CPU
+                )
+            }
+            Self::Offline(cpus) => format!("CPUS NOT ONLINE: {}", cpulist::format(cpus)),
+            Self::AllOnline => "EVERY ONLINE CPU WOULD BE RESERVED".to_string(),
+        };
+        format!("--reserved-cpus: {}", what)
+    }
+}
+
+// cpulist::parse, BOUNDED BY THE BITMAP BPF READS
+pub fn parse_reserved_cpus(s: &str) -> Result<Vec<u32>, ReservedCpusError> {
+    let cpus = cpulist::parse(s)?;
+    match cpus.last() {
+        Some(&max) if max > MAX_RESERVED_CPU => Err(ReservedCpusError::TooLarge(max as u64)),
+        _ => Ok(cpus),
+    }
+}
+
+// AGAINST THE ONLINE SET AT STARTUP: EVERY RESERVED CPU ONLINE, AT LEAST
+// ONE ONLINE CPU LEFT OVER
+pub fn validate(reserved: &[u32], online: &[u32]) -> Result<(), ReservedCpusError> {
+    let offline: Vec<u32> = reserved
+        .iter()
+        .filter(|c| !online.contains(c))
+        .copied()
+        .collect();
+    if !offline.is_empty() {
+        return Err(ReservedCpusError::Offline(offline));
+    }
+    if unreserved(online, reserved).is_empty() {
+        return Err(ReservedCpusError::AllOnline);
+    }
+    Ok(())
+}
+
+// cpus MINUS THE RESERVED ONES, ORDER KEPT
+pub fn unreserved(cpus: &[u32], reserved: &[u32]) -> Vec<u32> {
+    cpus.iter()
+        .filter(|c| !reserved.contains(c))
+        .copied()
+        .collect()
+}
+
+// BANNER, [CONFIG] AND status: "2-3,8" OR "-" FOR NONE
+pub fn describe(reserved: &[u32]) -> String {
+    if reserved.is_empty() {
+        "-".to_string()
+    } else {
+        cpulist::format(reserved)
+    }
+}
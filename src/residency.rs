@@ -0,0 +1,147 @@
+// PANDEMONIUM IDLE RESIDENCY
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// idle_pct COMES FROM DISPATCH COUNTERS (IDLE FAST-PATH HITS / DISPATCHES).
+// IT ONLY MOVES WHEN SOMETHING IS DISPATCHED, SO BUSY PERIODS DOMINATE IT:
+// A SECOND WITH HALF THE CPUS PARKED CAN STILL READ "40% IDLE".
+//
+// BPF KEEPS A PER-CPU BUSY FLAG (update_idle(), PINNED AS cpu_busy). THE
+// MONITOR LOOPS SAMPLE IT SAMPLES_PER_TICK TIMES A TICK AS AN IDLE BITMAP
+// (idle_bitmap()); EACH SAMPLE HOLDS UNTIL THE NEXT ONE (ZERO-ORDER
+// HOLD), SO A CPU'S RESIDENCY IS THE SHARE
+// OF ELAPSED TIME IT SPENT IDLE, NOT A SHARE OF EVENTS. THE LAST SAMPLE
+// CARRIES INTO THE NEXT TICK: NO GAP BETWEEN TICKS.
+
+use std::time::Duration;
+
+use crate::hotplug::{bitmap_has, CpuBitmap, CPU_BITMAP_WORDS};
+
+// 10HZ AT THE 1S TICK
+pub const SAMPLES_PER_TICK: u32 = 10;
+
+// cpu_busy_map's VALUES BY CPU ID -> THE CPUS AT 0 (IN THE IDLE LOOP).
+// CPUS PAST THE BITMAP ARE DROPPED
+pub fn idle_bitmap(busy: impl IntoIterator<Item = u64>) -> CpuBitmap {
+    let mut b = [0u64; CPU_BITMAP_WORDS];
+    for (cpu, flag) in busy.into_iter().enumerate().take(CPU_BITMAP_WORDS * 64) {
+        if flag == 0 {
+            b[cpu / 64] |= 1u64 << (cpu % 64);
+        }
+    }
+    b
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResidencyTick {
+    pub idle_pct: u64,            // MEAN OVER THE ONLINE CPUS
+    pub per_cpu: Vec<(u32, u64)>, // (CPU, IDLE %)
+    pub samples: u32,
+}
+
+impl ResidencyTick {
+    // --verbose EVERY 10 TICKS: "[RESID] idle=52% samples=10 cpu0=98 cpu1=3 ..."
+    pub fn format_line(&self) -> String {
+        let mut out = format!("[RESID] idle={}% samples={}", self.idle_pct, self.samples);
+        for (cpu, pct) in &self.per_cpu {
+            out.push_str(&format!(" cpu{}={}", cpu, pct));
+        }
+        out
+    }
+}
+
+#[derive(Default)]
+pub struct IdleResidency {
+    held: Option<(u64, CpuBitmap)>, // LAST SAMPLE: TIME (NS), BITMAP
+    idle_ns: Vec<u64>,              // BY CPU
+    total_ns: u64,
+    samples: u32,
+}
+
+impl IdleResidency {
+    // CREDIT [LAST SAMPLE, now_ns) TO THE CPUS IDLE IN THE LAST SAMPLE,
+    // THEN HOLD THIS ONE. A CLOCK THAT STEPS BACK CREDITS NOTHING.
+    pub fn sample(&mut self, now_ns: u64, bitmap: &CpuBitmap) {
+        if let Some((at, prev)) = self.held {
+            let dt = now_ns.saturating_sub(at);
+            self.total_ns += dt;
+            for cpu in 0..(CPU_BITMAP_WORDS * 64) as u32 {
+                if bitmap_has(&prev, cpu) {
+                    let i = cpu as usize;
+                    if self.idle_ns.len() <= i {
+                        self.idle_ns.resize(i + 1, 0);
+                    }
+                    self.idle_ns[i] += dt;
+                }
+            }
+        }
+        self.held = Some((now_ns, *bitmap));
+        self.samples += 1;
+    }
+
+    // SLEEP window IN samples STEPS, READING THE BITMAP AFTER EACH. A
+    // FAILED READ SKIPS THAT SAMPLE: THE PREVIOUS ONE HOLDS LONGER.
+    pub fn sample_window(
+        &mut self,
+        window: Duration,
+        samples: u32,
+        sleep: impl FnMut(Duration),
+        now_ns: impl FnMut() -> u64,
+        read: impl FnMut() -> Option<CpuBitmap>,
+    ) {
+        self.sample_window_until(window, samples, sleep, now_ns, read, || false);
+    }
+
+    // sample_window, ENDING EARLY AFTER THE FIRST STEP stop() SAYS TO (THE
+    // IDLE STRETCH SNAPPING BACK). RETURNS THE STEPS TAKEN.
+    pub fn sample_window_until(
+        &mut self,
+        window: Duration,
+        samples: u32,
+        mut sleep: impl FnMut(Duration),
+        mut now_ns: impl FnMut() -> u64,
+        mut read: impl FnMut() -> Option<CpuBitmap>,
+        mut stop: impl FnMut() -> bool,
+    ) -> u32 {
+        let samples = samples.max(1);
+        let step = window / samples;
+        for taken in 1..=samples {
+            sleep(step);
+            if let Some(bitmap) = read() {
+                self.sample(now_ns(), &bitmap);
+            }
+            if stop() {
+                return taken;
+            }
+        }
+        samples
+    }
+
+    // RESIDENCY OF THE online CPUS SINCE THE LAST take(), THEN START A
+    // NEW WINDOW FROM THE HELD SAMPLE. None: NO TIME COVERED YET.
+    pub fn take(&mut self, online: &[u32]) -> Option<ResidencyTick> {
+        let total = self.total_ns;
+        let samples = self.samples;
+        let idle_ns = std::mem::take(&mut self.idle_ns);
+        self.total_ns = 0;
+        self.samples = 0;
+        if total == 0 || online.is_empty() {
+            return None;
+        }
+        let per_cpu: Vec<(u32, u64)> = online
+            .iter()
+            .map(|&cpu| {
+                let ns = idle_ns.get(cpu as usize).copied().unwrap_or(0);
+                (cpu, ns.min(total) * 100 / total)
+            })
+            .collect();
+        let sum: u64 = online
+            .iter()
+            .map(|&cpu| idle_ns.get(cpu as usize).copied().unwrap_or(0).min(total))
+            .sum();
+        Some(ResidencyTick {
+            idle_pct: sum * 100 / (total * online.len() as u64),
+            per_cpu,
+            samples,
+        })
+    }
+}
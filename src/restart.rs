@@ -0,0 +1,132 @@
+// PANDEMONIUM RESTART STORM PROTECTION
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (run_scheduler, get regime) AND LIB CRATE (tests)
+//
+// SCX_ECODE_ACT_RESTART ASKS FOR A FRESH ATTACH, AND ONE RESTART IS ROUTINE
+// (A HOTPLUG). A PATHOLOGICAL CONDITION (A HOTPLUG STORM, ANOTHER SCHEDULER
+// RACING THE ATTACH) ASKS AGAIN AND AGAIN: RESTARTING AT ONCE, FOREVER,
+// SPINS THE LOOP AND FLOODS THE LOG. RestartGuard KEEPS THE RESTART TIMES OF
+// A SLIDING WINDOW. THE FIRST `free` IN IT WAIT ONLY FOR STRUCT_OPS CLEANUP,
+// EACH ONE PAST THAT DOUBLES THE WAIT UP TO max_delay, AND ONE PAST give_up
+// ENDS THE RUN. THE WINDOW SLIDES: A QUIET SPELL EARNS THE FREE ONES BACK.
+// THE DEFAULT WAITS ADD UP TO ~1.5 MIN, WELL INSIDE ITS 10 MIN WINDOW, SO A
+// STORM THAT PERSISTS THROUGH THE BACKOFF DOES REACH give_up.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestartPolicy {
+    pub window: Duration,
+    pub free: u32,            // RESTARTS PER WINDOW AT base_delay
+    pub give_up: u32,         // RESTARTS PER WINDOW BEFORE STOPPING
+    pub base_delay: Duration, // KERNEL STRUCT_OPS CLEANUP: DETACH IS ASYNCHRONOUS
+    pub max_delay: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(600),
+            free: 3,
+            give_up: 8,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RestartPolicy {
+    // THE WAIT BEFORE THE nth RESTART OF THE WINDOW (1-BASED), None: STOP.
+    // base_delay UP TO free, THEN base_delay x 2, x 4, ... CAPPED.
+    pub fn delay(&self, in_window: u32) -> Option<Duration> {
+        if in_window > self.give_up {
+            return None;
+        }
+        let doublings = in_window.saturating_sub(self.free).min(31);
+        Some(
+            self.base_delay
+                .saturating_mul(1 << doublings)
+                .min(self.max_delay.max(self.base_delay)),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartDecision {
+    Wait(Duration),
+    GiveUp { in_window: u32 },
+}
+
+// WHAT `get regime` AND `pandemonium status` SHOW
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestartSummary {
+    pub count: u32,                  // KERNEL-REQUESTED RESTARTS THIS RUN
+    pub last_reason: Option<String>, // THE uei REASON OF THE LATEST ONE
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RestartGuard {
+    policy: RestartPolicy,
+    times_ns: VecDeque<u64>, // RESTARTS INSIDE THE WINDOW, OLDEST FIRST
+    summary: RestartSummary,
+}
+
+impl RestartGuard {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    pub fn summary(&self) -> &RestartSummary {
+        &self.summary
+    }
+
+    // RESTARTS INSIDE THE WINDOW AS OF THE LAST record()
+    pub fn in_window(&self) -> u32 {
+        self.times_ns.len() as u32
+    }
+
+    // PAST THE FREE RESTARTS: THE WAITS ARE GROWING
+    pub fn storming(&self) -> bool {
+        self.in_window() > self.policy.free
+    }
+
+    // "RESTART STORM: 5 KERNEL RESTARTS IN 600s, WAITING 8s BEFORE THE NEXT ATTACH"
+    pub fn storm_message(&self, decision: RestartDecision) -> String {
+        let head = format!(
+            "RESTART STORM: {} KERNEL RESTARTS IN {}s",
+            self.in_window(),
+            self.policy.window.as_secs()
+        );
+        match decision {
+            RestartDecision::Wait(d) => {
+                format!("{}, WAITING {}s BEFORE THE NEXT ATTACH", head, d.as_secs())
+            }
+            RestartDecision::GiveUp { .. } => format!("{}, GIVING UP", head),
+        }
+    }
+
+    // THE KERNEL ASKED FOR A RESTART AT now_ns (MONOTONIC). A NEWLINE IN
+    // reason WOULD SPLIT THE key=value LINE IT ENDS UP IN.
+    pub fn record(&mut self, now_ns: u64, reason: &str) -> RestartDecision {
+        let window_ns = self.policy.window.as_nanos() as u64;
+        while self
+            .times_ns
+            .front()
+            .is_some_and(|&t| now_ns.saturating_sub(t) >= window_ns)
+        {
+            self.times_ns.pop_front();
+        }
+        self.times_ns.push_back(now_ns);
+        self.summary.count += 1;
+        self.summary.last_reason = Some(reason.replace('\n', " "));
+        let in_window = self.in_window();
+        match self.policy.delay(in_window) {
+            Some(d) => RestartDecision::Wait(d),
+            None => RestartDecision::GiveUp { in_window },
+        }
+    }
+}
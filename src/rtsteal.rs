@@ -0,0 +1,146 @@
+// PANDEMONIUM RT/DEADLINE CAPACITY STEAL
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// SCHED_FIFO/RR AND SCHED_DEADLINE TASKS RUN ABOVE sched_ext: BPF NEVER
+// SEES THEM, YET AN AUDIO THREAD'S CPU TIME IS CAPACITY THE TIERS CANNOT
+// HAVE. NEITHER /proc/stat NOR /proc/schedstat SPLITS CPU TIME BY CLASS,
+// SO THE WATCH FINDS THE RT/DL THREADS THEMSELVES: EVERY RESCAN_TICKS IT
+// WALKS /proc/*/task/*/stat FOR THE POLICY FIELD, AND EVERY TICK IT READS
+// ONLY THOSE THREADS' schedstat RUNTIME. THE RUNTIME ADDED OVER (ELAPSED x
+// CPUS) IS THE SHARE OF CAPACITY NON-scx CLASSES TOOK (rt: IN TELEMETRY).
+// A THREAD THAT TURNS RT BETWEEN RESCANS IS MISSED UNTIL THE NEXT ONE; ONE
+// THAT EXITS DROPS OUT. tuning::rt_adjusted_idle() APPLIES THE SHARE.
+
+use std::path::{Path, PathBuf};
+
+pub const RESCAN_TICKS: u64 = 10;
+
+pub const SCHED_FIFO: u32 = 1;
+pub const SCHED_RR: u32 = 2;
+pub const SCHED_DEADLINE: u32 = 6;
+
+pub fn is_rt_policy(policy: u32) -> bool {
+    matches!(policy, SCHED_FIFO | SCHED_RR | SCHED_DEADLINE)
+}
+
+// /proc/<pid>/task/<tid>/stat -> policy (FIELD 41). comm MAY HOLD SPACES
+// AND ')', SO FIELDS ARE COUNTED FROM THE LAST ')' (FIELD 3 ON)
+pub fn parse_policy(stat: &str) -> Option<u32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(41 - 3)?.parse().ok()
+}
+
+// FIRST FIELD OF /proc/<pid>/task/<tid>/schedstat: NS SPENT ON A CPU
+pub fn parse_runtime_ns(schedstat: &str) -> Option<u64> {
+    schedstat.split_whitespace().next()?.parse().ok()
+}
+
+// SHARE OF nr_cpus x elapsed_ns THE RT/DL THREADS RAN, 0..=100
+pub fn stolen_pct(run_ns: u64, elapsed_ns: u64, nr_cpus: u64) -> u64 {
+    let capacity = elapsed_ns as u128 * nr_cpus as u128;
+    if capacity == 0 {
+        return 0;
+    }
+    (run_ns as u128 * 100 / capacity).min(100) as u64
+}
+
+struct RtThread {
+    pid: u32,
+    tid: u32,
+    run_ns: Option<u64>, // None: FOUND THIS TICK, NO BASELINE YET
+}
+
+pub struct RtWatch {
+    proc_root: PathBuf,
+    threads: Vec<RtThread>,
+    ticks: u64,
+    last_ns: Option<u64>,
+}
+
+impl RtWatch {
+    pub fn new(proc_root: &Path) -> Self {
+        Self {
+            proc_root: proc_root.to_path_buf(),
+            threads: Vec::new(),
+            ticks: 0,
+            last_ns: None,
+        }
+    }
+
+    // RT/DL THREADS BEING FOLLOWED
+    pub fn threads(&self) -> usize {
+        self.threads.len()
+    }
+
+    // EVERY (pid, tid) UNDER proc_root WHOSE POLICY IS RT OR DEADLINE
+    fn scan(&self) -> Vec<(u32, u32)> {
+        let numeric = |e: &std::fs::DirEntry| e.file_name().to_str()?.parse::<u32>().ok();
+        let Ok(procs) = std::fs::read_dir(&self.proc_root) else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        for p in procs.flatten() {
+            let Some(pid) = numeric(&p) else {
+                continue;
+            };
+            let Ok(tasks) = std::fs::read_dir(p.path().join("task")) else {
+                continue;
+            };
+            for t in tasks.flatten() {
+                let Some(tid) = numeric(&t) else {
+                    continue;
+                };
+                let policy = std::fs::read_to_string(t.path().join("stat"))
+                    .ok()
+                    .and_then(|s| parse_policy(&s));
+                if policy.is_some_and(is_rt_policy) {
+                    out.push((pid, tid));
+                }
+            }
+        }
+        out
+    }
+
+    // ONE TICK: RESCAN WHEN DUE (THE FIRST TICK ALWAYS), THEN ADD UP THE
+    // RUNTIME EACH FOLLOWED THREAD GAINED. None ON THE FIRST TICK: NO
+    // INTERVAL YET. A THREAD FOUND BY A RESCAN COUNTS FROM THE NEXT TICK.
+    pub fn tick(&mut self, now_ns: u64, nr_cpus: u64) -> Option<u64> {
+        if self.ticks.is_multiple_of(RESCAN_TICKS) {
+            let old = std::mem::take(&mut self.threads);
+            self.threads = self
+                .scan()
+                .into_iter()
+                .map(|(pid, tid)| RtThread {
+                    pid,
+                    tid,
+                    run_ns: old
+                        .iter()
+                        .find(|t| (t.pid, t.tid) == (pid, tid))
+                        .and_then(|t| t.run_ns),
+                })
+                .collect();
+        }
+        self.ticks += 1;
+
+        let root = &self.proc_root;
+        let mut run_ns = 0u64;
+        self.threads.retain_mut(|t| {
+            let path = root.join(format!("{}/task/{}/schedstat", t.pid, t.tid));
+            let Some(cur) = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|s| parse_runtime_ns(&s))
+            else {
+                return false;
+            };
+            if let Some(prev) = t.run_ns {
+                run_ns += cur.saturating_sub(prev);
+            }
+            t.run_ns = Some(cur);
+            true
+        });
+
+        let elapsed = self.last_ns.map(|l| now_ns.saturating_sub(l));
+        self.last_ns = Some(now_ns);
+        elapsed.map(|e| stolen_pct(run_ns, e, nr_cpus))
+    }
+}
@@ -0,0 +1,124 @@
+// PANDEMONIUM PER-RUN RESOURCE USAGE
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// `bench --mode cmd` / `--mode self` REAP EACH ITERATION WITH wait4() AND
+// KEEP ITS rusage NEXT TO THE WALL TIME. WALL TIME ALONE CAN'T SAY WHETHER
+// A SCHEDULER TRADED THROUGHPUT FOR LATENCY; CPU SECONDS AND CONTEXT
+// SWITCHES CAN. INVOLUNTARY SWITCHES ARE PREEMPTIONS: THE MOST DIRECT
+// VIEW OF HOW DIFFERENTLY TWO SCHEDULERS SLICE THE SAME WORK.
+//
+// THE BENCHMARKED COMMAND RUNS UNDER sh -c, WHICH WAITS FOR EVERYTHING IT
+// STARTS: CPU TIME AND SWITCH COUNTS COVER THE WHOLE TREE. MAX RSS IS THE
+// LARGEST SINGLE PROCESS IN IT, NOT THE SUM.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunUsage {
+    pub wall_s: f64,
+    pub user_s: f64,
+    pub sys_s: f64,
+    pub max_rss_kb: u64,
+    pub vcsw: u64,  // VOLUNTARY: BLOCKED ON IO, LOCKS, PIPES
+    pub ivcsw: u64, // INVOLUNTARY: PREEMPTED
+}
+
+fn timeval_s(tv: &libc::timeval) -> f64 {
+    tv.tv_sec as f64 + tv.tv_usec as f64 / 1e6
+}
+
+impl RunUsage {
+    // LINUX REPORTS ru_maxrss IN KILOBYTES
+    pub fn from_rusage(wall_s: f64, ru: &libc::rusage) -> Self {
+        Self {
+            wall_s,
+            user_s: timeval_s(&ru.ru_utime),
+            sys_s: timeval_s(&ru.ru_stime),
+            max_rss_kb: ru.ru_maxrss.max(0) as u64,
+            vcsw: ru.ru_nvcsw.max(0) as u64,
+            ivcsw: ru.ru_nivcsw.max(0) as u64,
+        }
+    }
+}
+
+// FIELD-WISE MEAN (COUNTS ROUND TO NEAREST); ZERO FOR NO RUNS
+pub fn mean_usage(runs: &[RunUsage]) -> RunUsage {
+    if runs.is_empty() {
+        return RunUsage::default();
+    }
+    let n = runs.len() as f64;
+    let avg = |f: fn(&RunUsage) -> f64| runs.iter().map(f).sum::<f64>() / n;
+    RunUsage {
+        wall_s: avg(|r| r.wall_s),
+        user_s: avg(|r| r.user_s),
+        sys_s: avg(|r| r.sys_s),
+        max_rss_kb: avg(|r| r.max_rss_kb as f64).round() as u64,
+        vcsw: avg(|r| r.vcsw as f64).round() as u64,
+        ivcsw: avg(|r| r.ivcsw as f64).round() as u64,
+    }
+}
+
+fn format_rss(kb: u64) -> String {
+    format!("{:.1}MB", kb as f64 / 1024.0)
+}
+
+pub fn usage_header() -> String {
+    format!(
+        "  {:<5} {:>9} {:>9} {:>9} {:>10} {:>10} {:>10}",
+        "RUN", "WALL", "USER", "SYS", "MAX RSS", "VOL CSW", "INVOL CSW"
+    )
+}
+
+pub fn format_usage_row(label: &str, u: &RunUsage) -> String {
+    format!(
+        "  {:<5} {:>9} {:>9} {:>9} {:>10} {:>10} {:>10}",
+        label,
+        format!("{:.2}s", u.wall_s),
+        format!("{:.2}s", u.user_s),
+        format!("{:.2}s", u.sys_s),
+        format_rss(u.max_rss_kb),
+        u.vcsw,
+        u.ivcsw
+    )
+}
+
+// PERCENT CHANGE FROM base; 0 WHEN base IS 0 (NOTHING TO COMPARE AGAINST)
+pub fn pct_delta(base: f64, new: f64) -> f64 {
+    if base > 0.0 {
+        (new - base) / base * 100.0
+    } else {
+        0.0
+    }
+}
+
+// ONE ROW PER METRIC: EEVDF MEAN, PANDEMONIUM MEAN, % CHANGE
+pub fn usage_summary_lines(eevdf: &RunUsage, pand: &RunUsage) -> Vec<String> {
+    let secs: fn(f64) -> String = |v| format!("{:.2}s", v);
+    let count: fn(f64) -> String = |v| format!("{:.0}", v);
+    let rss: fn(f64) -> String = |v| format_rss(v as u64);
+    let metrics = [
+        ("WALL", eevdf.wall_s, pand.wall_s, secs),
+        ("USER CPU", eevdf.user_s, pand.user_s, secs),
+        ("SYS CPU", eevdf.sys_s, pand.sys_s, secs),
+        (
+            "MAX RSS",
+            eevdf.max_rss_kb as f64,
+            pand.max_rss_kb as f64,
+            rss,
+        ),
+        ("VOL CSW", eevdf.vcsw as f64, pand.vcsw as f64, count),
+        ("INVOL CSW", eevdf.ivcsw as f64, pand.ivcsw as f64, count),
+    ];
+    let mut lines = vec![format!(
+        "{:<24} {:>11} {:>12} {:>8}",
+        "PHASE SUMMARY (MEAN)", "EEVDF", "PANDEMONIUM", "DELTA"
+    )];
+    for (name, a, b, fmt) in metrics {
+        lines.push(format!(
+            "  {:<22} {:>11} {:>12} {:>+7.1}%",
+            name,
+            fmt(a),
+            fmt(b),
+            pct_delta(a, b)
+        ));
+    }
+    lines
+}
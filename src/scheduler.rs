@@ -2,227 +2,311 @@
 // WRAPS THE BPF SKELETON: OPEN, CONFIGURE, LOAD, ATTACH, SHUTDOWN
 // MONITORING AND ADAPTIVE CONTROL LIVE IN adaptive.rs
 
+use std::cell::RefCell;
 use std::mem::MaybeUninit;
+use std::os::fd::{AsFd, AsRawFd};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use libbpf_rs::skel::{OpenSkel, SkelBuilder};
-use libbpf_rs::MapCore;
+use libbpf_rs::{MapCore, MapHandle};
 
 use crate::bpf_skel::*;
-use crate::tuning::TuningKnobs;
+use crate::syscalls::{MapOp, SyscallCounts, SyscallTally};
+use crate::tuning::{
+    KnobWriteCounts, KnobWriter, LagHist, SliceBounds, TuningKnobs, LAG_HIST_KEYS,
+};
+use pandemonium::abi::MapAbi;
+use pandemonium::container::{self, ContainerSignals, PinDir, PinGuard};
 use pandemonium::event::EventLog;
-
-// SCX EXIT CODES (FROM KERNEL)
-const SCX_EXIT_NONE: i32 = 0;
-const SCX_ECODE_RST_MASK: u64 = 1 << 16;
+use pandemonium::exitcode::{ExitInfo, SCX_EXIT_NONE};
+use pandemonium::hotplug::{cpu_bitmap, CpuBitmap};
+use pandemonium::hybrid::{
+    ClassSample, HybridMonitor, HybridTopology, MAX_FAST_SCAN, PROC_STAT_PATH,
+};
+use pandemonium::{reserved, residency};
+use pandemonium::stats::{DsqDepth, PandemoniumStats, PerCpuBuf};
+use pandemonium::victims::{EventCursor, SchedEvent, EVENT_RING_SLOTS};
 
 // SCX DSQ FLAGS (STABLE KERNEL ABI -- sched_ext/sched.h)
 const SCX_DSQ_FLAG_BUILTIN: u64 = 1u64 << 63;
 const SCX_DSQ_FLAG_LOCAL_ON: u64 = 1u64 << 62;
 
-// MATCHES struct pandemonium_stats IN BPF (intf.h)
-#[repr(C)]
-#[derive(Default, Clone, Copy)]
-pub struct PandemoniumStats {
-    pub nr_dispatches: u64,
-    pub nr_idle_hits: u64,
-    pub nr_shared: u64,
-    pub nr_preempt: u64,
-    pub wake_lat_sum: u64,
-    pub wake_lat_max: u64,
-    pub wake_lat_samples: u64,
-    pub nr_keep_running: u64,
-    pub nr_hard_kicks: u64,
-    pub nr_soft_kicks: u64,
-    pub nr_enq_wakeup: u64,
-    pub nr_enq_requeue: u64,
-    pub wake_lat_idle_sum: u64,
-    pub wake_lat_idle_cnt: u64,
-    pub wake_lat_kick_sum: u64,
-    pub wake_lat_kick_cnt: u64,
-    pub nr_procdb_hits: u64,
-    pub nr_l2_hit_batch: u64,
-    pub nr_l2_miss_batch: u64,
-    pub nr_l2_hit_interactive: u64,
-    pub nr_l2_miss_interactive: u64,
-    pub nr_l2_hit_lat_crit: u64,
-    pub nr_l2_miss_lat_crit: u64,
-    pub nr_reenqueue: u64,
-    pub batch_sojourn_ns: u64,
-    pub burst_mode_active: u64,
-    pub longrun_mode_active: u64,
-    pub nr_overflow_rescue: u64,
+// PandemoniumStats AND TuningKnobs (WITH THEIR ABI ASSERTS) LIVE IN stats.rs
+// AND tuning.rs (zero BPF dependencies, testable offline)
+
+// PERCPU_ARRAY SIZES (max_entries IN main.bpf.c)
+const WAKE_HIST_KEYS: usize = 36;
+const SLEEP_HIST_KEYS: usize = 4;
+
+// FILL buf FROM ONE KEY OF A PERCPU MAP WITHOUT ALLOCATING
+fn lookup_percpu_into(map: &impl AsFd, key: u32, buf: &mut PerCpuBuf) -> bool {
+    let fd = map.as_fd().as_raw_fd();
+    let key_bytes = key.to_ne_bytes();
+    let dst = buf.key_mut(key as usize);
+    let ret = unsafe {
+        libbpf_rs::libbpf_sys::bpf_map_lookup_elem(
+            fd,
+            key_bytes.as_ptr() as *const _,
+            dst.as_mut_ptr() as *mut _,
+        )
+    };
+    ret == 0
 }
 
-// COMPILE-TIME ABI SAFETY: MUST MATCH STRUCT LAYOUTS IN intf.h
-const _: () = assert!(std::mem::size_of::<PandemoniumStats>() == 224);
-const _: () = assert!(std::mem::size_of::<TuningKnobs>() == 80);
-
-// TuningKnobs lives in tuning.rs (zero BPF dependencies, testable offline)
-
-const KNOBS_PIN: &str = "/sys/fs/bpf/pandemonium/tuning_knobs";
+// FILL buf WITH EVERY KEY OF A PERCPU_ARRAY: ONE bpf_map_lookup_batch
+// SYSCALL WHERE THE KERNEL SUPPORTS IT (5.6+), ELSE ONE LOOKUP PER KEY.
+// ARRAYS RETURN KEYS IN ORDER; ANYTHING SHORT OR OUT OF ORDER FALLS BACK.
+// RETURNS THE SYSCALLS MADE.
+fn lookup_all_percpu_into(map: &impl AsFd, buf: &mut PerCpuBuf) -> u64 {
+    let n = buf.nr_keys();
+    let mut keys = [0u32; WAKE_HIST_KEYS];
+    let mut calls = 0;
+    if n <= keys.len() {
+        calls += 1;
+        let mut out_batch = 0u32;
+        let mut count = n as u32;
+        let opts = libbpf_rs::libbpf_sys::bpf_map_batch_opts {
+            sz: std::mem::size_of::<libbpf_rs::libbpf_sys::bpf_map_batch_opts>() as _,
+            elem_flags: 0,
+            flags: 0,
+        };
+        let ret = unsafe {
+            libbpf_rs::libbpf_sys::bpf_map_lookup_batch(
+                map.as_fd().as_raw_fd(),
+                std::ptr::null_mut(),
+                &mut out_batch as *mut u32 as *mut _,
+                keys.as_mut_ptr() as *mut _,
+                buf.as_mut_bytes().as_mut_ptr() as *mut _,
+                &mut count,
+                &opts,
+            )
+        };
+        // -ENOENT: THE BATCH REACHED THE END OF THE MAP, count IS STILL VALID
+        if (ret == 0 || ret == -libc::ENOENT)
+            && count as usize == n
+            && keys[..n].iter().enumerate().all(|(i, &k)| k as usize == i)
+        {
+            return calls;
+        }
+    }
+    buf.clear();
+    for key in 0..n as u32 {
+        lookup_percpu_into(map, key, buf);
+    }
+    calls + n as u64
+}
 
 pub struct Scheduler<'a> {
     skel: MainSkel<'a>,
     _link: libbpf_rs::Link,
     pub log: EventLog,
+    pub hybrid: Option<HybridTopology>,
+    // --pin-dir: WHERE THE MAPS ARE PINNED (procdb REOPENS TWO OF THEM)
+    pub pins: PinDir,
+    // FIXED AT LOAD (RODATA): EVERY KNOB WRITE IS CLAMPED TO THE SAME RANGE
+    pub slice_bounds: SliceBounds,
+    // FIXED AT LOAD (RODATA): NO WAKEUP SAMPLES, NO HISTOGRAMS, NO PROCDB
+    // OBSERVATIONS. THE MONITOR LOOPS READ IT TO KNOW WHAT THEY WON'T GET.
+    pub lightweight: bool,
+    // FIXED AT LOAD (--reserved-cpus, SORTED): LEFT OUT OF THE STATS TOTAL,
+    // IDLE RESIDENCY AND THE L2 GROUPS
+    pub reserved: Vec<u32>,
+    // EVERY tuning_knobs WRITE: SKIPS NO-OPS, DETECTS LOST UPDATES
+    knob_writer: KnobWriter,
+    // REUSED EVERY TICK; THE MONITOR THREAD IS THE ONLY READER
+    stats_buf: RefCell<PerCpuBuf>,
+    wake_hist_buf: RefCell<PerCpuBuf>,
+    sleep_hist_buf: RefCell<PerCpuBuf>,
+    lag_hist_buf: RefCell<PerCpuBuf>,
+    event_buf: RefCell<PerCpuBuf>,
+    busy_buf: RefCell<PerCpuBuf>,
+    // EVERY MAP SYSCALL MADE THROUGH THIS Scheduler (syscalls.rs)
+    syscalls: SyscallTally,
 }
 
 impl<'a> Scheduler<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         open_object: &'a mut MaybeUninit<libbpf_rs::OpenObject>,
         nr_cpus_override: Option<u64>,
+        has_set_slice_kfunc: bool,
+        hybrid: Option<HybridTopology>,
+        slice_bounds: SliceBounds,
+        lightweight: bool,
+        reserved: Vec<u32>,
+        pins: PinDir,
     ) -> Result<Self> {
-        // OPEN
-        let builder = MainSkelBuilder::default();
-        let mut open_skel = builder.open(open_object)?;
-
-        // CONFIGURE RODATA (BEFORE LOAD)
-        let rodata = open_skel.maps.rodata_data.as_mut().unwrap();
-
-        let possible = libbpf_rs::num_possible_cpus()? as u64;
-        rodata.nr_cpu_ids = nr_cpus_override.unwrap_or(possible);
-
-        // POPULATE SCX ENUM VALUES
-        rodata.__SCX_DSQ_FLAG_BUILTIN = SCX_DSQ_FLAG_BUILTIN;
-        rodata.__SCX_DSQ_FLAG_LOCAL_ON = SCX_DSQ_FLAG_LOCAL_ON;
-        rodata.__SCX_DSQ_INVALID = SCX_DSQ_FLAG_BUILTIN;
-        rodata.__SCX_DSQ_GLOBAL = SCX_DSQ_FLAG_BUILTIN | 1;
-        rodata.__SCX_DSQ_LOCAL = SCX_DSQ_FLAG_BUILTIN | SCX_DSQ_FLAG_LOCAL_ON;
-        rodata.__SCX_DSQ_LOCAL_ON = SCX_DSQ_FLAG_BUILTIN | SCX_DSQ_FLAG_LOCAL_ON | 1;
-        rodata.__SCX_DSQ_LOCAL_CPU_MASK = 0xFFFFFFFF;
-
-        // POPULATE SCX_KICK_* ENUM VALUES
-        rodata.__SCX_KICK_IDLE = 1;
-        rodata.__SCX_KICK_PREEMPT = 2;
-        rodata.__SCX_KICK_WAIT = 4;
+        // EVERY STEP NAMES ITSELF IN THE ERROR: A USER REPORT SAYS WHICH
+        // STAGE FAILED, NOT JUST AN ERRNO. NOTHING BEFORE THE PIN STEP
+        // TOUCHES THE FILESYSTEM; THE PIN STEP ROLLS ITSELF BACK.
+        let mut skel = load_skel(
+            open_object,
+            nr_cpus_override,
+            has_set_slice_kfunc,
+            hybrid.is_some(),
+            slice_bounds,
+            lightweight,
+            reserved.len() as u32,
+        )?;
+
+        // REFUSE TO ATTACH WITH A STALE RUST MIRROR OF ANY MAP WE DECODE
+        check_map_abi(&skel).context("CHECKING MAP ABI")?;
+
+        // FAST CPU LIST BEFORE ATTACH: hybrid_enabled IS ALREADY SET IN RODATA.
+        // A RESERVED FAST CPU IS NOT ONE LAT_CRITICAL CAN HAVE
+        if let Some(h) = &hybrid {
+            write_fast_cpus(&skel, &reserved::unreserved(&h.fast_cpu_list(), &reserved))
+                .context("WRITING HYBRID FAST CPU LIST")?;
+        }
 
-        // LOAD (VALIDATES BPF WITH KERNEL)
-        let mut skel = open_skel.load()?;
+        // RESERVED SET BEFORE ATTACH: THE FIRST select_cpu() ALREADY CHECKS IT
+        if !reserved.is_empty() {
+            let words: Vec<u8> = cpu_bitmap(&reserved)
+                .iter()
+                .flat_map(|w| w.to_ne_bytes())
+                .collect();
+            skel.maps
+                .reserved_cpus_map
+                .update(&0u32.to_ne_bytes(), &words, libbpf_rs::MapFlags::ANY)
+                .context("WRITING RESERVED CPU SET")?;
+        }
 
         // ATTACH STRUCT_OPS
-        let link = skel.maps.pandemonium_ops.attach_struct_ops()?;
-
-        // PIN MAPS FOR USERSPACE ACCESS (NON-FATAL: bpffs may not be mounted)
-        let pin_dir = "/sys/fs/bpf/pandemonium";
-        let bpffs_ok = std::fs::create_dir_all(pin_dir).is_ok();
-        if bpffs_ok {
-            std::fs::remove_file(KNOBS_PIN).ok();
-            skel.maps.tuning_knobs_map.pin(KNOBS_PIN).ok();
-
-            let cache_pin = "/sys/fs/bpf/pandemonium/cache_domain";
-            std::fs::remove_file(cache_pin).ok();
-            skel.maps.cache_domain.pin(cache_pin).ok();
-
-            let observe_pin = "/sys/fs/bpf/pandemonium/task_class_observe";
-            std::fs::remove_file(observe_pin).ok();
-            skel.maps.task_class_observe.pin(observe_pin).ok();
-
-            let init_pin = "/sys/fs/bpf/pandemonium/task_class_init";
-            std::fs::remove_file(init_pin).ok();
-            skel.maps.task_class_init.pin(init_pin).ok();
-
-            let compositor_pin = "/sys/fs/bpf/pandemonium/compositor_map";
-            std::fs::remove_file(compositor_pin).ok();
-            skel.maps.compositor_map.pin(compositor_pin).ok();
-        } else {
-            log_warn!("BPFFS NOT AVAILABLE: map pinning skipped (scheduler still functional)");
+        let link = skel
+            .maps
+            .pandemonium_ops
+            .attach_struct_ops()
+            .context("ATTACHING STRUCT_OPS")?;
+
+        // PIN MAPS FOR USERSPACE ACCESS (NON-FATAL: bpffs may not be mounted).
+        // mkdir SUCCEEDS ON A CONTAINER'S PLAIN /sys/fs/bpf, SO CHECK THE MOUNT
+        let mounts = std::fs::read_to_string(container::SELF_MOUNTS_PATH).unwrap_or_default();
+        let bpffs_ok = container::on_bpffs(&container::parse_mounts(&mounts), pins.dir());
+        let pinned = bpffs_ok
+            && match pin_maps(&mut skel, &pins) {
+                Ok(()) => true,
+                Err(e) => {
+                    log_warn!(
+                        "MAP PINNING ROLLED BACK (scheduler still functional): {:#}",
+                        e
+                    );
+                    false
+                }
+            };
+        if pinned {
+            // --pin-group: NON-ROOT CLIENTS IN THE GROUP READ THE PINS
+            if let Err(e) = pins.share() {
+                log_warn!("--pin-group: CANNOT SHARE {}: {}", pins.dir().display(), e);
+            } else if pins.group().is_some() {
+                if let Some(blocked) = pins.blocked_ancestor() {
+                    log_warn!(
+                        "--pin-group: {} IS NOT o+x, NON-ROOT CLIENTS CANNOT REACH {} (chmod o+x {})",
+                        blocked.display(),
+                        pins.dir().display(),
+                        blocked.display()
+                    );
+                }
+            }
+        } else if !bpffs_ok {
+            let runtime = ContainerSignals::read().runtime();
+            log_warn!(
+                "MAP PINNING SKIPPED (scheduler still functional): {}",
+                container::bpffs_hint(pins.dir(), runtime.as_deref())
+            );
         }
 
         Ok(Self {
             skel,
             _link: link,
             log: EventLog::new(),
+            hybrid,
+            pins,
+            slice_bounds,
+            lightweight,
+            reserved,
+            knob_writer: KnobWriter::default(),
+            stats_buf: RefCell::new(PerCpuBuf::new(
+                1,
+                possible as usize,
+                std::mem::size_of::<PandemoniumStats>(),
+            )),
+            wake_hist_buf: RefCell::new(PerCpuBuf::new(WAKE_HIST_KEYS, possible as usize, 8)),
+            sleep_hist_buf: RefCell::new(PerCpuBuf::new(SLEEP_HIST_KEYS, possible as usize, 8)),
+            lag_hist_buf: RefCell::new(PerCpuBuf::new(LAG_HIST_KEYS, possible as usize, 8)),
+            event_buf: RefCell::new(PerCpuBuf::new(
+                EVENT_RING_SLOTS + 1,
+                possible as usize,
+                std::mem::size_of::<SchedEvent>(),
+            )),
+            busy_buf: RefCell::new(PerCpuBuf::new(1, possible as usize, 8)),
+            syscalls: SyscallTally::default(),
         })
     }
 
+    // MAP SYSCALLS SINCE THE LAST CALL: THE MONITOR TAKES THEM ONCE A TICK
+    pub fn take_syscalls(&self) -> SyscallCounts {
+        self.syscalls.take()
+    }
+
     // SUM PER-CPU STATS INTO A SINGLE TOTAL
     pub fn read_stats(&self) -> PandemoniumStats {
-        let key = 0u32.to_ne_bytes();
-        let mut total = PandemoniumStats::default();
-
-        let percpu_vals = match self
-            .skel
-            .maps
-            .stats_map
-            .lookup_percpu(&key, libbpf_rs::MapFlags::ANY)
-        {
-            Ok(Some(v)) => v,
-            _ => return total,
-        };
+        PandemoniumStats::total_except(&self.read_stats_percpu(), &self.reserved)
+    }
 
-        for cpu_val in &percpu_vals {
-            if cpu_val.len() >= std::mem::size_of::<PandemoniumStats>() {
-                let stats: PandemoniumStats = unsafe {
-                    std::ptr::read_unaligned(cpu_val.as_ptr() as *const PandemoniumStats)
-                };
-                total.nr_dispatches += stats.nr_dispatches;
-                total.nr_idle_hits += stats.nr_idle_hits;
-                total.nr_shared += stats.nr_shared;
-                total.nr_preempt += stats.nr_preempt;
-                total.wake_lat_sum += stats.wake_lat_sum;
-                if stats.wake_lat_max > total.wake_lat_max {
-                    total.wake_lat_max = stats.wake_lat_max;
-                }
-                total.wake_lat_samples += stats.wake_lat_samples;
-                total.nr_keep_running += stats.nr_keep_running;
-                total.nr_hard_kicks += stats.nr_hard_kicks;
-                total.nr_soft_kicks += stats.nr_soft_kicks;
-                total.nr_enq_wakeup += stats.nr_enq_wakeup;
-                total.nr_enq_requeue += stats.nr_enq_requeue;
-                total.wake_lat_idle_sum += stats.wake_lat_idle_sum;
-                total.wake_lat_idle_cnt += stats.wake_lat_idle_cnt;
-                total.wake_lat_kick_sum += stats.wake_lat_kick_sum;
-                total.wake_lat_kick_cnt += stats.wake_lat_kick_cnt;
-                total.nr_procdb_hits += stats.nr_procdb_hits;
-                total.nr_l2_hit_batch += stats.nr_l2_hit_batch;
-                total.nr_l2_miss_batch += stats.nr_l2_miss_batch;
-                total.nr_l2_hit_interactive += stats.nr_l2_hit_interactive;
-                total.nr_l2_miss_interactive += stats.nr_l2_miss_interactive;
-                total.nr_l2_hit_lat_crit += stats.nr_l2_hit_lat_crit;
-                total.nr_l2_miss_lat_crit += stats.nr_l2_miss_lat_crit;
-                total.nr_reenqueue += stats.nr_reenqueue;
-                if stats.batch_sojourn_ns > total.batch_sojourn_ns {
-                    total.batch_sojourn_ns = stats.batch_sojourn_ns;
-                }
-                total.burst_mode_active += stats.burst_mode_active;
-                if stats.longrun_mode_active > total.longrun_mode_active {
-                    total.longrun_mode_active = stats.longrun_mode_active;
-                }
-            }
+    // ONE ENTRY PER POSSIBLE CPU, INDEXED BY CPU ID. CALLERS THAT ALSO
+    // NEED THE TOTAL TAKE PandemoniumStats::total() OF THIS ONE READ.
+    pub fn read_stats_percpu(&self) -> Vec<PandemoniumStats> {
+        let mut buf = self.stats_buf.borrow_mut();
+        self.syscalls.record(MapOp::Lookup, 1);
+        if !lookup_percpu_into(&self.skel.maps.stats_map, 0, &mut buf) {
+            return Vec::new();
         }
-
-        total
+        buf.stats()
     }
 
-    // WRITE TUNING KNOBS TO BPF MAP -- CALLED BY MONITOR THREAD
+    // WRITE TUNING KNOBS TO BPF MAP -- CALLED BY MONITOR THREAD.
+    // SLICE KNOBS ARE CLAMPED TO THE LOAD-TIME GUARDRAILS FIRST; AN
+    // UNCHANGED VALUE IS SKIPPED.
     pub fn write_tuning_knobs(&self, knobs: &TuningKnobs) -> Result<()> {
-        let key = 0u32.to_ne_bytes();
-        let value = unsafe {
-            std::slice::from_raw_parts(
-                knobs as *const TuningKnobs as *const u8,
-                std::mem::size_of::<TuningKnobs>(),
-            )
-        };
-        self.skel
-            .maps
-            .tuning_knobs_map
-            .update(&key, value, libbpf_rs::MapFlags::ANY)?;
+        let mut knobs = *knobs;
+        self.slice_bounds.clamp_knobs(&mut knobs);
+        self.knob_writer.write(&knobs, |k| {
+            let key = 0u32.to_ne_bytes();
+            let value = unsafe {
+                std::slice::from_raw_parts(
+                    k as *const TuningKnobs as *const u8,
+                    std::mem::size_of::<TuningKnobs>(),
+                )
+            };
+            self.syscalls.record(MapOp::Update, 1);
+            self.skel
+                .maps
+                .tuning_knobs_map
+                .update(&key, value, libbpf_rs::MapFlags::ANY)?;
+            Ok(())
+        })?;
         Ok(())
     }
 
-    // READ CURRENT TUNING KNOBS FROM BPF MAP
+    pub fn knob_write_counts(&self) -> KnobWriteCounts {
+        self.knob_writer.counts()
+    }
+
+    // READ CURRENT TUNING KNOBS: THE KnobWriter CACHE ONCE A WRITE HAS
+    // LANDED (NO SYSCALL), THE BPF MAP BEFORE THAT
     pub fn read_tuning_knobs(&self) -> TuningKnobs {
+        if let Some(knobs) = self.knob_writer.cached() {
+            return knobs;
+        }
         let key = 0u32.to_ne_bytes();
+        self.syscalls.record(MapOp::Lookup, 1);
         match self
             .skel
             .maps
             .tuning_knobs_map
             .lookup(&key, libbpf_rs::MapFlags::ANY)
         {
-            Ok(Some(v)) if v.len() >= std::mem::size_of::<TuningKnobs>() => unsafe {
-                std::ptr::read_unaligned(v.as_ptr() as *const TuningKnobs)
-            },
+            Ok(Some(v)) => TuningKnobs::from_bytes(&v).unwrap_or_default(),
             _ => TuningKnobs::default(),
         }
     }
@@ -230,57 +314,79 @@ impl<'a> Scheduler<'a> {
     // READ WAKEUP LATENCY HISTOGRAM: 3 TIERS x 12 BUCKETS
     // SUMS ACROSS ALL CPUs (PERCPU_ARRAY). RETURNS CUMULATIVE COUNTS.
     pub fn read_wake_lat_hist(&self) -> [[u64; 12]; 3] {
-        let mut result = [[0u64; 12]; 3];
-        for key_idx in 0u32..36 {
-            let key = key_idx.to_ne_bytes();
-            if let Ok(Some(percpu_vals)) = self
-                .skel
-                .maps
-                .wake_lat_hist
-                .lookup_percpu(&key, libbpf_rs::MapFlags::ANY)
-            {
-                let tier = (key_idx / 12) as usize;
-                let bucket = (key_idx % 12) as usize;
-                for cpu_val in &percpu_vals {
-                    if cpu_val.len() >= std::mem::size_of::<u64>() {
-                        let val: u64 =
-                            unsafe { std::ptr::read_unaligned(cpu_val.as_ptr() as *const u64) };
-                        result[tier][bucket] += val;
-                    }
-                }
-            }
-        }
-        result
+        let mut buf = self.wake_hist_buf.borrow_mut();
+        let calls = lookup_all_percpu_into(&self.skel.maps.wake_lat_hist, &mut buf);
+        self.syscalls.record(MapOp::Lookup, calls);
+        std::array::from_fn(|tier| std::array::from_fn(|bucket| buf.sum_u64(tier * 12 + bucket)))
     }
 
     // READ SLEEP DURATION HISTOGRAM: 4 BUCKETS
     // SUMS ACROSS ALL CPUs (PERCPU_ARRAY). RETURNS CUMULATIVE COUNTS.
     pub fn read_sleep_hist(&self) -> [u64; 4] {
-        let mut result = [0u64; 4];
-        for key_idx in 0u32..4 {
-            let key = key_idx.to_ne_bytes();
-            if let Ok(Some(percpu_vals)) = self
-                .skel
-                .maps
-                .sleep_hist
-                .lookup_percpu(&key, libbpf_rs::MapFlags::ANY)
-            {
-                for cpu_val in &percpu_vals {
-                    if cpu_val.len() >= std::mem::size_of::<u64>() {
-                        let val: u64 =
-                            unsafe { std::ptr::read_unaligned(cpu_val.as_ptr() as *const u64) };
-                        result[key_idx as usize] += val;
-                    }
-                }
-            }
+        let mut buf = self.sleep_hist_buf.borrow_mut();
+        let calls = lookup_all_percpu_into(&self.skel.maps.sleep_hist, &mut buf);
+        self.syscalls.record(MapOp::Lookup, calls);
+        std::array::from_fn(|key| buf.sum_u64(key))
+    }
+
+    // READ APPLIED LAG HISTOGRAM: 7 SCALE BUCKETS + FLOOR CLAMPS
+    // SUMS ACROSS ALL CPUs (PERCPU_ARRAY). RETURNS CUMULATIVE COUNTS.
+    pub fn read_lag_hist(&self) -> LagHist {
+        let mut buf = self.lag_hist_buf.borrow_mut();
+        let calls = lookup_all_percpu_into(&self.skel.maps.lag_hist, &mut buf);
+        self.syscalls.record(MapOp::Lookup, calls);
+        LagHist::from_keys(&std::array::from_fn(|key| buf.sum_u64(key)))
+    }
+
+    // DRAIN EVERY CPU'S event_ring: EVENTS NEWER THAN THE CURSOR GO
+    // INTO out. RETURNS HOW MANY WERE OVERWRITTEN BEFORE THIS READ.
+    pub fn read_events(&self, cursor: &mut EventCursor, out: &mut Vec<SchedEvent>) -> u64 {
+        let mut buf = self.event_buf.borrow_mut();
+        let calls = lookup_all_percpu_into(&self.skel.maps.event_ring, &mut buf);
+        self.syscalls.record(MapOp::Lookup, calls);
+        let mut lost = 0;
+        for cpu in 0..buf.nr_cpus() {
+            let slots: [SchedEvent; EVENT_RING_SLOTS] = std::array::from_fn(|key| {
+                SchedEvent::from_bytes(buf.value(key, cpu)).unwrap_or_default()
+            });
+            lost += cursor.drain(cpu, &slots, out);
+        }
+        lost
+    }
+
+    // READ SHARED DSQ DEPTH: LATEST tick() SAMPLE (GAUGE, NOT CUMULATIVE)
+    pub fn read_dsq_depth(&self) -> DsqDepth {
+        let key = 0u32.to_ne_bytes();
+        self.syscalls.record(MapOp::Lookup, 1);
+        match self
+            .skel
+            .maps
+            .dsq_depth_map
+            .lookup(&key, libbpf_rs::MapFlags::ANY)
+        {
+            Ok(Some(v)) if v.len() == std::mem::size_of::<DsqDepth>() => unsafe {
+                std::ptr::read_unaligned(v.as_ptr() as *const DsqDepth)
+            },
+            _ => DsqDepth::default(),
+        }
+    }
+
+    // EVERY CPU'S BUSY FLAG (PERCPU), AS AN IDLE BITMAP.
+    // None: LOOKUP FAILED (THE SAMPLE IS SKIPPED, NOT READ AS ALL-BUSY)
+    pub fn read_idle_bitmap(&self) -> Option<CpuBitmap> {
+        let mut buf = self.busy_buf.borrow_mut();
+        self.syscalls.record(MapOp::Lookup, 1);
+        if !lookup_percpu_into(&self.skel.maps.cpu_busy_map, 0, &mut buf) {
+            return None;
         }
-        result
+        Some(residency::idle_bitmap(buf.u64s(0)))
     }
 
     // POPULATE CACHE DOMAIN MAP FROM TOPOLOGY DATA AT STARTUP
     pub fn write_cache_domain(&self, cpu: u32, l2_group: u32) -> Result<()> {
         let key = cpu.to_ne_bytes();
         let val = l2_group.to_ne_bytes();
+        self.syscalls.record(MapOp::Update, 1);
         self.skel
             .maps
             .cache_domain
@@ -292,6 +398,7 @@ impl<'a> Scheduler<'a> {
     pub fn write_l2_sibling(&self, group_id: u32, slot: u32, cpu: u32) -> Result<()> {
         let key = (group_id * 8 + slot).to_ne_bytes();
         let val = cpu.to_ne_bytes();
+        self.syscalls.record(MapOp::Update, 1);
         self.skel
             .maps
             .l2_siblings
@@ -299,6 +406,19 @@ impl<'a> Scheduler<'a> {
         Ok(())
     }
 
+    // PER-CLASS (FAST, SLOW) IDLE % + WAKE LATENCY SINCE THE LAST CALL.
+    // None ON UNIFORM MACHINES AND ON THE FIRST (BASELINE) CALL.
+    // per_cpu IS THIS TICK'S read_stats_percpu(), SHARED WITH THE TOTAL
+    pub fn hybrid_tick(
+        &self,
+        mon: &mut HybridMonitor,
+        per_cpu: &[PandemoniumStats],
+    ) -> Option<(ClassSample, ClassSample)> {
+        let topo = self.hybrid.as_ref()?;
+        let proc_stat = std::fs::read_to_string(PROC_STAT_PATH).ok()?;
+        mon.tick(topo, &proc_stat, per_cpu)
+    }
+
     // POPULATE COMPOSITOR MAP ENTRY
     pub fn write_compositor(&self, name: &str) -> Result<()> {
         let mut key = [0u8; 16];
@@ -306,6 +426,7 @@ impl<'a> Scheduler<'a> {
         let len = bytes.len().min(15);
         key[..len].copy_from_slice(&bytes[..len]);
         let val = [1u8];
+        self.syscalls.record(MapOp::Update, 1);
         self.skel
             .maps
             .compositor_map
@@ -313,35 +434,30 @@ impl<'a> Scheduler<'a> {
         Ok(())
     }
 
-    // READ UEI EXIT INFO. RETURNS (should_restart).
-    pub fn read_exit_info(&self) -> bool {
-        let data = self.skel.maps.data_data.as_ref().unwrap();
-        let kind = data.uei.kind;
-        let exit_code = data.uei.exit_code;
-
-        if kind != SCX_EXIT_NONE {
-            let reason_bytes: &[u8] =
-                unsafe { std::slice::from_raw_parts(data.uei.reason.as_ptr() as *const u8, 128) };
-            let msg_bytes: &[u8] =
-                unsafe { std::slice::from_raw_parts(data.uei.msg.as_ptr() as *const u8, 1024) };
-
-            let reason = std::str::from_utf8(reason_bytes)
-                .unwrap_or("unknown")
-                .trim_end_matches('\0');
-            let msg = std::str::from_utf8(msg_bytes)
-                .unwrap_or("")
-                .trim_end_matches('\0');
-
-            log_warn!("BPF exit: kind={} code={}", kind, exit_code);
-            if !reason.is_empty() {
-                log_warn!("BPF exit reason: {}", reason);
-            }
-            if !msg.is_empty() {
-                log_warn!("BPF exit msg: {}", msg);
-            }
-        }
+    // GAMEMODE: EVERY THREAD OF tgid LAT_CRITICAL UNTIL clear_game_tgid
+    pub fn write_game_tgid(&self, tgid: u32) -> Result<()> {
+        let val = [1u8];
+        self.syscalls.record(MapOp::Update, 1);
+        self.skel
+            .maps
+            .game_tgid_map
+            .update(&tgid.to_ne_bytes(), &val, libbpf_rs::MapFlags::ANY)?;
+        Ok(())
+    }
 
-        (exit_code as u64 & SCX_ECODE_RST_MASK) != 0
+    pub fn clear_game_tgid(&self, tgid: u32) -> Result<()> {
+        self.syscalls.record(MapOp::Delete, 1);
+        self.skel.maps.game_tgid_map.delete(&tgid.to_ne_bytes())?;
+        Ok(())
+    }
+
+    // THE uei THE BPF SIDE RECORDED. DECODING AND LOGGING LIVE IN
+    // exitcode.rs; main.rs DECIDES RESTART AND EXIT CODE FROM IT.
+    pub fn read_exit_info(&self) -> ExitInfo {
+        let uei = &self.skel.maps.data_data.as_ref().unwrap().uei;
+        let reason: Vec<u8> = uei.reason.iter().map(|&c| c as u8).collect();
+        let msg: Vec<u8> = uei.msg.iter().map(|&c| c as u8).collect();
+        ExitInfo::from_uei(uei.kind, uei.exit_code, &reason, &msg)
     }
 
     pub fn exited(&self) -> bool {
@@ -349,29 +465,172 @@ impl<'a> Scheduler<'a> {
     }
 }
 
+// OPEN, CONFIGURE RODATA, LOAD: EVERYTHING BEFORE ATTACH THAT init() AND
+// self_test() SHARE
+fn load_skel<'a>(
+    open_object: &'a mut MaybeUninit<libbpf_rs::OpenObject>,
+    nr_cpus_override: Option<u64>,
+    has_set_slice_kfunc: bool,
+    hybrid_enabled: bool,
+    slice_bounds: SliceBounds,
+    lightweight: bool,
+    nr_reserved: u32,
+) -> Result<MainSkel<'a>> {
+    // OPEN
+    let builder = MainSkelBuilder::default();
+    let mut open_skel = builder.open(open_object).context("OPENING BPF SKELETON")?;
+
+    // CONFIGURE RODATA (BEFORE LOAD)
+    let rodata = open_skel.maps.rodata_data.as_mut().unwrap();
+
+    let possible = libbpf_rs::num_possible_cpus().context("COUNTING POSSIBLE CPUS")? as u64;
+    rodata.nr_cpu_ids = nr_cpus_override.unwrap_or(possible);
+    rodata.has_set_slice_kfunc = has_set_slice_kfunc;
+    rodata.hybrid_enabled = hybrid_enabled;
+    rodata.slice_min_ns = slice_bounds.min_ns;
+    rodata.slice_max_ns = slice_bounds.max_ns;
+    rodata.lightweight = lightweight;
+    rodata.nr_reserved = nr_reserved;
+
+    // POPULATE SCX ENUM VALUES
+    rodata.__SCX_DSQ_FLAG_BUILTIN = SCX_DSQ_FLAG_BUILTIN;
+    rodata.__SCX_DSQ_FLAG_LOCAL_ON = SCX_DSQ_FLAG_LOCAL_ON;
+    rodata.__SCX_DSQ_INVALID = SCX_DSQ_FLAG_BUILTIN;
+    rodata.__SCX_DSQ_GLOBAL = SCX_DSQ_FLAG_BUILTIN | 1;
+    rodata.__SCX_DSQ_LOCAL = SCX_DSQ_FLAG_BUILTIN | SCX_DSQ_FLAG_LOCAL_ON;
+    rodata.__SCX_DSQ_LOCAL_ON = SCX_DSQ_FLAG_BUILTIN | SCX_DSQ_FLAG_LOCAL_ON | 1;
+    rodata.__SCX_DSQ_LOCAL_CPU_MASK = 0xFFFFFFFF;
+
+    // POPULATE SCX_KICK_* ENUM VALUES
+    rodata.__SCX_KICK_IDLE = 1;
+    rodata.__SCX_KICK_PREEMPT = 2;
+    rodata.__SCX_KICK_WAIT = 4;
+
+    // LOAD (VALIDATES BPF WITH KERNEL)
+    open_skel.load().context("LOADING BPF PROGRAM (VERIFIER)")
+}
+
+// EVERY MAP RUST DECODES (abi::MIRRORS), SIZED BY THE KERNEL'S bpf_map_info
+fn map_abi(skel: &MainSkel) -> Result<Vec<MapAbi>> {
+    let m = &skel.maps;
+    let handles = [
+        ("stats_map", MapHandle::try_from(&m.stats_map)),
+        ("tuning_knobs_map", MapHandle::try_from(&m.tuning_knobs_map)),
+        ("dsq_depth_map", MapHandle::try_from(&m.dsq_depth_map)),
+        ("cpu_busy_map", MapHandle::try_from(&m.cpu_busy_map)),
+        (
+            "reserved_cpus_map",
+            MapHandle::try_from(&m.reserved_cpus_map),
+        ),
+        (
+            "task_class_observe",
+            MapHandle::try_from(&m.task_class_observe),
+        ),
+        ("task_class_init", MapHandle::try_from(&m.task_class_init)),
+        ("wake_lat_hist", MapHandle::try_from(&m.wake_lat_hist)),
+        ("sleep_hist", MapHandle::try_from(&m.sleep_hist)),
+        ("lag_hist", MapHandle::try_from(&m.lag_hist)),
+        ("event_ring", MapHandle::try_from(&m.event_ring)),
+    ];
+    handles
+        .into_iter()
+        .map(|(name, handle)| {
+            let info = handle
+                .and_then(|h| h.info())
+                .with_context(|| format!("QUERYING {}", name))?;
+            MapAbi::new(name, info.info.key_size, info.info.value_size)
+                .with_context(|| format!("{}: NO RUST MIRROR IN abi::MIRRORS", name))
+        })
+        .collect()
+}
+
+// THE ATTACH GATE: A STALE MIRROR OF ANY MAP WE DECODE STOPS init()
+fn check_map_abi(skel: &MainSkel) -> Result<()> {
+    for row in map_abi(skel)? {
+        row.check()?;
+    }
+    Ok(())
+}
+
+// `pandemonium self-test`: LOAD WITHOUT ATTACHING AND REPORT EVERY MAP. THE
+// LOADED OBJECT IS FREED ON RETURN; NOTHING IS PINNED OR WRITTEN.
+pub fn self_test() -> Result<Vec<MapAbi>> {
+    let mut open_object = MaybeUninit::uninit();
+    let skel = load_skel(
+        &mut open_object,
+        None,
+        false,
+        false,
+        SliceBounds::default(),
+        false,
+        0,
+    )?;
+    map_abi(&skel)
+}
+
+// EVERY PIN OR NONE: A FAILURE REMOVES THE PINS (AND DIRECTORY) THIS CALL
+// CREATED BEFORE RETURNING THE ERROR
+fn pin_maps(skel: &mut MainSkel, pins: &PinDir) -> Result<()> {
+    let mut guard = PinGuard::create(pins)
+        .with_context(|| format!("CREATING PIN DIR {}", pins.dir().display()))?;
+    let m = &mut skel.maps;
+    let steps = [
+        (pins.knobs(), &mut m.tuning_knobs_map),
+        (pins.map("cache_domain"), &mut m.cache_domain),
+        (pins.map("task_class_observe"), &mut m.task_class_observe),
+        (pins.map("task_class_init"), &mut m.task_class_init),
+        (pins.stats(), &mut m.stats_map),
+        (pins.map("compositor_map"), &mut m.compositor_map),
+        // pandemonium::client::Client::histogram() READS IT
+        (pins.map(container::WAKE_HIST_PIN), &mut m.wake_lat_hist),
+        (pins.map(container::CPU_BUSY_PIN), &mut m.cpu_busy_map),
+        // status READS IT
+        (
+            pins.map(container::RESERVED_CPUS_PIN),
+            &mut m.reserved_cpus_map,
+        ),
+    ];
+    for (path, map) in steps {
+        let shown = path.display().to_string();
+        guard
+            .pin(path, |p| map.pin(p))
+            .with_context(|| format!("PINNING {}", shown))?;
+    }
+    guard.commit();
+    Ok(())
+}
+
+// POPULATE FAST CPUS LIST (HYBRID), SENTINEL u32::MAX AFTER THE LAST
+fn write_fast_cpus(skel: &MainSkel, cpus: &[u32]) -> Result<()> {
+    let end = cpus.len().min(MAX_FAST_SCAN);
+    for (slot, &cpu) in cpus[..end].iter().chain([u32::MAX].iter()).enumerate() {
+        skel.maps.fast_cpus.update(
+            &(slot as u32).to_ne_bytes(),
+            &cpu.to_ne_bytes(),
+            libbpf_rs::MapFlags::ANY,
+        )?;
+    }
+    Ok(())
+}
+
 impl Drop for Scheduler<'_> {
     fn drop(&mut self) {
-        let _ = self.skel.maps.tuning_knobs_map.unpin(KNOBS_PIN);
-        let _ = self
-            .skel
-            .maps
-            .cache_domain
-            .unpin("/sys/fs/bpf/pandemonium/cache_domain");
-        let _ = self
-            .skel
-            .maps
+        let m = &mut self.skel.maps;
+        let _ = m.tuning_knobs_map.unpin(self.pins.knobs());
+        let _ = m.stats_map.unpin(self.pins.stats());
+        let _ = m.cache_domain.unpin(self.pins.map("cache_domain"));
+        let _ = m
             .task_class_observe
-            .unpin("/sys/fs/bpf/pandemonium/task_class_observe");
-        let _ = self
-            .skel
-            .maps
-            .task_class_init
-            .unpin("/sys/fs/bpf/pandemonium/task_class_init");
-        let _ = self
-            .skel
-            .maps
-            .compositor_map
-            .unpin("/sys/fs/bpf/pandemonium/compositor_map");
-        let _ = std::fs::remove_dir("/sys/fs/bpf/pandemonium");
+            .unpin(self.pins.map("task_class_observe"));
+        let _ = m.task_class_init.unpin(self.pins.map("task_class_init"));
+        let _ = m.compositor_map.unpin(self.pins.map("compositor_map"));
+        let _ = m
+            .wake_lat_hist
+            .unpin(self.pins.map(container::WAKE_HIST_PIN));
+        let _ = m.cpu_busy_map.unpin(self.pins.map(container::CPU_BUSY_PIN));
+        let _ = m
+            .reserved_cpus_map
+            .unpin(self.pins.map(container::RESERVED_CPUS_PIN));
+        let _ = std::fs::remove_dir(self.pins.dir());
     }
 }
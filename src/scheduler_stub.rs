@@ -0,0 +1,148 @@
+// PANDEMONIUM SCHEDULER STUB (--features stub-bpf)
+// SAME PUBLIC API AS scheduler.rs, NO BPF SKELETON BEHIND IT
+//
+// stub-bpf BUILDS WITHOUT vmlinux.h, bpftool OR clang SO CLIPPY AND THE
+// TEST SUITE RUN ON CI AND IN CONTAINERS. THE BINARY STILL LINKS, BUT
+// init() FAILS, SO A Scheduler NEVER EXISTS AND EVERY METHOD BELOW IS
+// UNREACHABLE (THE Infallible FIELD MAKES THAT A TYPE-LEVEL FACT).
+//
+// WORKS IN A STUB BUILD: check (INCL. --runtime), probe, dmesg, ctl,
+// procdb, blackbox, topology, EVERYTHING IN THE LIB.
+// FAILS AT init(): RUNNING THE SCHEDULER ITSELF (start, self-test, THE PANDEMONIUM
+// PHASE OF bench / bench-run, test --soak) AND ITS LIVE TELEMETRY.
+
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+use anyhow::{bail, Result};
+
+use crate::syscalls::SyscallCounts;
+use crate::tuning::{KnobWriteCounts, LagHist, SliceBounds, TuningKnobs};
+use pandemonium::abi::MapAbi;
+use pandemonium::container::PinDir;
+use pandemonium::event::EventLog;
+use pandemonium::exitcode::ExitInfo;
+use pandemonium::hotplug::CpuBitmap;
+use pandemonium::hybrid::{ClassSample, HybridMonitor, HybridTopology};
+use pandemonium::stats::{DsqDepth, PandemoniumStats};
+use pandemonium::victims::{EventCursor, SchedEvent};
+
+pub const STUB_BPF_ERROR: &str = "BUILT WITH --features stub-bpf: NO BPF PROGRAM IS COMPILED IN. \
+     SCHEDULING, BENCH/SOAK AGAINST PANDEMONIUM AND BPF TELEMETRY ARE UNAVAILABLE. \
+     REBUILD WITHOUT stub-bpf ON A sched_ext KERNEL";
+
+pub struct Scheduler<'a> {
+    never: Infallible,
+    pub log: EventLog,
+    pub hybrid: Option<HybridTopology>,
+    pub pins: PinDir,
+    pub slice_bounds: SliceBounds,
+    pub lightweight: bool,
+    pub reserved: Vec<u32>,
+    _skel: PhantomData<&'a ()>,
+}
+
+impl<'a> Scheduler<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        _open_object: &'a mut MaybeUninit<libbpf_rs::OpenObject>,
+        _nr_cpus_override: Option<u64>,
+        _has_set_slice_kfunc: bool,
+        _hybrid: Option<HybridTopology>,
+        _slice_bounds: SliceBounds,
+        _lightweight: bool,
+        _reserved: Vec<u32>,
+        _pins: PinDir,
+    ) -> Result<Self> {
+        bail!(STUB_BPF_ERROR)
+    }
+
+    pub fn take_syscalls(&self) -> SyscallCounts {
+        match self.never {}
+    }
+
+    pub fn read_stats(&self) -> PandemoniumStats {
+        match self.never {}
+    }
+
+    pub fn read_stats_percpu(&self) -> Vec<PandemoniumStats> {
+        match self.never {}
+    }
+
+    pub fn write_tuning_knobs(&self, _knobs: &TuningKnobs) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn knob_write_counts(&self) -> KnobWriteCounts {
+        match self.never {}
+    }
+
+    pub fn read_tuning_knobs(&self) -> TuningKnobs {
+        match self.never {}
+    }
+
+    pub fn read_wake_lat_hist(&self) -> [[u64; 12]; 3] {
+        match self.never {}
+    }
+
+    pub fn read_sleep_hist(&self) -> [u64; 4] {
+        match self.never {}
+    }
+
+    pub fn read_lag_hist(&self) -> LagHist {
+        match self.never {}
+    }
+
+    pub fn read_events(&self, _cursor: &mut EventCursor, _out: &mut Vec<SchedEvent>) -> u64 {
+        match self.never {}
+    }
+
+    pub fn read_dsq_depth(&self) -> DsqDepth {
+        match self.never {}
+    }
+
+    pub fn read_idle_bitmap(&self) -> Option<CpuBitmap> {
+        match self.never {}
+    }
+
+    pub fn write_cache_domain(&self, _cpu: u32, _l2_group: u32) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn write_l2_sibling(&self, _group_id: u32, _slot: u32, _cpu: u32) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn hybrid_tick(
+        &self,
+        _mon: &mut HybridMonitor,
+        _per_cpu: &[PandemoniumStats],
+    ) -> Option<(ClassSample, ClassSample)> {
+        match self.never {}
+    }
+
+    pub fn write_compositor(&self, _name: &str) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn write_game_tgid(&self, _tgid: u32) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn clear_game_tgid(&self, _tgid: u32) -> Result<()> {
+        match self.never {}
+    }
+
+    pub fn read_exit_info(&self) -> ExitInfo {
+        match self.never {}
+    }
+
+    pub fn exited(&self) -> bool {
+        match self.never {}
+    }
+}
+
+pub fn self_test() -> Result<Vec<MapAbi>> {
+    bail!(STUB_BPF_ERROR)
+}
@@ -0,0 +1,138 @@
+// PANDEMONIUM sched_ext RUNTIME STATE
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// "IS IT ACTUALLY RUNNING?" -- THE KERNEL ANSWERS UNDER /sys/kernel/sched_ext:
+// state, switch_all, enable_seq, nr_rejected, hotplug_seq, root/ops AND (6.16+)
+// root/events. `pandemonium check --runtime` READS THEM HERE, THEN ADDS A
+// HEALTH LINE FROM THE PINNED stats/knobs MAPS WHEN THE ROOT IS OURS.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::attach::parse_ops;
+use crate::stats::StatsDelta;
+use crate::tuning::{TuningKnobs, AFFINITY_OFF, AFFINITY_STRONG};
+
+pub const SCX_SYSFS_ROOT: &str = "/sys/kernel/sched_ext";
+
+// .name IN pandemonium_ops (main.bpf.c)
+pub const PANDEMONIUM_OPS: &str = "pandemonium";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScxRuntime {
+    pub state: Option<String>,
+    pub switch_all: Option<bool>,
+    pub enable_seq: Option<u64>,
+    pub nr_rejected: Option<u64>,
+    pub hotplug_seq: Option<u64>,
+    pub ops: Option<String>,        // None: NO SCHEDULER ATTACHED
+    pub events: Vec<(String, u64)>, // root/events, EMPTY ON KERNELS WITHOUT IT
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+// "SCX_EV_SELECT_CPU_FALLBACK     : 12" PER LINE
+pub fn parse_events(text: &str) -> Vec<(String, u64)> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+impl ScxRuntime {
+    // None WHEN sched_ext IS NOT BUILT IN (NO SYSFS ROOT)
+    pub fn read_from(root: &Path) -> Option<Self> {
+        if !root.is_dir() {
+            return None;
+        }
+        Some(Self {
+            state: read_trimmed(&root.join("state")),
+            switch_all: read_u64(&root.join("switch_all")).map(|v| v != 0),
+            enable_seq: read_u64(&root.join("enable_seq")),
+            nr_rejected: read_u64(&root.join("nr_rejected")),
+            hotplug_seq: read_u64(&root.join("hotplug_seq")),
+            ops: std::fs::read_to_string(root.join("root/ops"))
+                .ok()
+                .and_then(|s| parse_ops(&s)),
+            events: std::fs::read_to_string(root.join("root/events"))
+                .map(|s| parse_events(&s))
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn read() -> Option<Self> {
+        Self::read_from(Path::new(SCX_SYSFS_ROOT))
+    }
+
+    pub fn is_pandemonium(&self) -> bool {
+        self.ops.as_deref() == Some(PANDEMONIUM_OPS)
+    }
+
+    // ONE FIELD PER LINE; UNREADABLE FILES SHOW AS "?"
+    pub fn summary_lines(&self) -> Vec<String> {
+        fn show<T: ToString>(v: &Option<T>) -> String {
+            v.as_ref().map_or("?".to_string(), |v| v.to_string())
+        }
+        let ops = match &self.ops {
+            Some(name) if self.is_pandemonium() => format!("{} (PANDEMONIUM)", name),
+            Some(name) => format!("{} (NOT PANDEMONIUM)", name),
+            None => "none".to_string(),
+        };
+        let mut out = vec![
+            format!("ops:         {}", ops),
+            format!("state:       {}", show(&self.state)),
+            format!("switch_all:  {}", show(&self.switch_all)),
+            format!("enable_seq:  {}", show(&self.enable_seq)),
+            format!("nr_rejected: {}", show(&self.nr_rejected)),
+            format!("hotplug_seq: {}", show(&self.hotplug_seq)),
+        ];
+        // ZERO EVENT COUNTERS ARE NOISE IN A SUPPORT PASTE
+        out.extend(
+            self.events
+                .iter()
+                .filter(|(_, v)| *v > 0)
+                .map(|(name, v)| format!("  {:<32}{}", name, v)),
+        );
+        out
+    }
+}
+
+// 3725s -> "1h02m05s"
+pub fn format_uptime(d: Duration) -> String {
+    let s = d.as_secs();
+    match (s / 3600, (s / 60) % 60, s % 60) {
+        (0, 0, sec) => format!("{}s", sec),
+        (0, m, sec) => format!("{}m{:02}s", m, sec),
+        (h, m, sec) => format!("{}h{:02}m{:02}s", h, m, sec),
+    }
+}
+
+// ONE-SECOND SAMPLE OF THE PINNED stats_map PLUS THE LIVE KNOBS
+pub fn health_line(delta: &StatsDelta, knobs: &TuningKnobs) -> String {
+    let affinity = match knobs.affinity_mode {
+        AFFINITY_OFF => "off",
+        AFFINITY_STRONG => "strong",
+        _ => "weak",
+    };
+    format!(
+        "d/s: {} idle: {}% wake: {}us kick: H={} S={} rescue: {} slice: {}us batch: {}us sjrn: {}ms affinity: {}",
+        delta.dispatches,
+        delta.idle_pct(),
+        delta.wake_avg_us(),
+        delta.hard_kicks,
+        delta.soft_kicks,
+        delta.overflow_rescue,
+        knobs.slice_ns / 1000,
+        knobs.batch_slice_ns / 1000,
+        knobs.sojourn_thresh_ns / 1_000_000,
+        affinity,
+    )
+}
@@ -0,0 +1,165 @@
+// PANDEMONIUM LONG-RUN SOAK PLAN
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// SOME FAILURES (THE RUNNABLE TASK STALL) ONLY SHOW UP AFTER DAYS. THE QUICK
+// GATE CANNOT CATCH THEM. `pandemonium test --soak HOURS` RUNS THE SCHEDULER
+// UNDER A ROTATING SYNTHETIC WORKLOAD AND JUDGES ONE SAMPLE PER MINUTE.
+//
+// BOTH TABLES ARE DATA: A NEW SCENARIO IS ONE ROTATION ROW (A MIX OF
+// COMPILE, CPU SPIN AND PROCESS-STORM INGREDIENTS), A NEW FAILURE RULE IS
+// ONE CRITERIA ROW. cli/soak.rs ONLY EXECUTES WHAT THESE TABLES SAY.
+
+use crate::scxstate::PANDEMONIUM_OPS;
+
+// ONE ROTATION STEP. INGREDIENTS COMBINE: compile LOOPS A CLEAN RELEASE
+// BUILD, spin_pct PINS A STRESS WORKER ON THAT SHARE OF CPUS, storm_threads
+// EACH SPAWN SHORT-LIVED PROCESSES BACK TO BACK.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Phase {
+    pub name: &'static str,
+    pub minutes: u64,
+    pub compile: bool,
+    pub spin_pct: u32,
+    pub storm_threads: u32,
+}
+
+pub const ROTATION: &[Phase] = &[
+    Phase {
+        name: "idle",
+        minutes: 10,
+        compile: false,
+        spin_pct: 0,
+        storm_threads: 0,
+    },
+    Phase {
+        name: "compile-burst",
+        minutes: 20,
+        compile: true,
+        spin_pct: 0,
+        storm_threads: 0,
+    },
+    Phase {
+        name: "mixed-stress",
+        minutes: 20,
+        compile: true,
+        spin_pct: 50,
+        storm_threads: 0,
+    },
+    Phase {
+        name: "process-storm",
+        minutes: 10,
+        compile: false,
+        spin_pct: 0,
+        storm_threads: 4,
+    },
+];
+
+// THE PHASE RUNNING AT minute (0-BASED), CYCLING THROUGH ROTATION
+pub fn phase_at(minute: u64) -> &'static Phase {
+    let cycle: u64 = ROTATION.iter().map(|p| p.minutes).sum();
+    let mut at = minute % cycle;
+    for phase in ROTATION {
+        if at < phase.minutes {
+            return phase;
+        }
+        at -= phase.minutes;
+    }
+    &ROTATION[0]
+}
+
+// spin_pct OF nr_cpus, SPREAD EVENLY SO SMT SIBLINGS AND L2 GROUPS MIX
+pub fn spin_cpus(spin_pct: u32, nr_cpus: u32) -> Vec<u32> {
+    let k = (nr_cpus as u64 * spin_pct.min(100) as u64).div_ceil(100) as u32;
+    (0..k).map(|i| i * nr_cpus / k).collect()
+}
+
+// "p99: 120us" FROM AN ADAPTIVE TELEMETRY LINE (d/s: ...)
+pub fn parse_p99_us(line: &str) -> Option<u64> {
+    if !line.starts_with("d/s:") {
+        return None;
+    }
+    let rest = &line[line.find(" p99: ")? + 6..];
+    rest[..rest.find("us")?].parse().ok()
+}
+
+// ONE MINUTE OF OBSERVATION
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SoakSample {
+    pub minute: u64,
+    pub scheduler_alive: bool,
+    pub ops: Option<String>, // sched_ext root/ops; None: NOTHING ATTACHED
+    pub p99_us: Vec<u64>,    // EVERY TELEMETRY LINE OF THE MINUTE
+}
+
+impl SoakSample {
+    // MEDIAN, SO ONE SPIKY TICK IN A COMPILE BURST IS NOT A FAILURE
+    pub fn median_p99_us(&self) -> Option<u64> {
+        let mut v = self.p99_us.clone();
+        v.sort_unstable();
+        v.get(v.len() / 2).copied()
+    }
+}
+
+// THE SCHEDULER'S WAKE P99 SATURATES AT 20MS (tuning::HIST_EDGES_NS);
+// A MEDIAN MINUTE AT HALF OF THAT IS NOT SCHEDULING, IT IS STALLING
+pub const P99_SANITY_US: u64 = 10_000;
+
+pub struct Criterion {
+    pub name: &'static str,
+    pub check: fn(&SoakSample) -> Option<String>,
+}
+
+fn check_process(s: &SoakSample) -> Option<String> {
+    (!s.scheduler_alive).then(|| "SCHEDULER PROCESS EXITED".to_string())
+}
+
+fn check_attached(s: &SoakSample) -> Option<String> {
+    match s.ops.as_deref() {
+        Some(PANDEMONIUM_OPS) => None,
+        Some(other) => Some(format!("sched_ext ROOT IS {}, NOT PANDEMONIUM", other)),
+        None => Some("sched_ext DETACHED (CHECK dmesg FOR THE EXIT REASON)".to_string()),
+    }
+}
+
+fn check_telemetry(s: &SoakSample) -> Option<String> {
+    s.p99_us
+        .is_empty()
+        .then(|| "NO TELEMETRY FOR A MINUTE (MONITOR LOOP STUCK?)".to_string())
+}
+
+fn check_p99(s: &SoakSample) -> Option<String> {
+    let p99 = s.median_p99_us()?;
+    (p99 > P99_SANITY_US).then(|| {
+        format!(
+            "MEDIAN WAKE P99 {}us OVER THE {}us SANITY BOUND",
+            p99, P99_SANITY_US
+        )
+    })
+}
+
+pub const CRITERIA: &[Criterion] = &[
+    Criterion {
+        name: "process",
+        check: check_process,
+    },
+    Criterion {
+        name: "attached",
+        check: check_attached,
+    },
+    Criterion {
+        name: "telemetry",
+        check: check_telemetry,
+    },
+    Criterion {
+        name: "p99",
+        check: check_p99,
+    },
+];
+
+// "name: REASON" FOR EVERY FAILED CRITERION; EMPTY WHEN HEALTHY
+pub fn evaluate(sample: &SoakSample) -> Vec<String> {
+    CRITERIA
+        .iter()
+        .filter_map(|c| (c.check)(sample).map(|why| format!("{}: {}", c.name, why)))
+        .collect()
+}
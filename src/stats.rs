@@ -0,0 +1,460 @@
+// PANDEMONIUM STATISTICS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// THE ONE RUST MIRROR OF struct pandemonium_stats AND struct dsq_depth
+// (intf.h). scheduler.rs SUMS THE PER-CPU STATS COPIES; BOTH MONITOR LOOPS
+// (adaptive.rs, BPF-ONLY IN main.rs) TURN CONSECUTIVE SNAPSHOTS INTO A
+// StatsDelta.
+//
+// MOST FIELDS ARE MONOTONIC COUNTERS. FOUR ARE GAUGES REWRITTEN BY tick():
+// wake_lat_max, batch_sojourn_ns, burst_mode_active, longrun_mode_active.
+// READ GAUGES FROM THE CURRENT SNAPSHOT.
+//
+// PERCPU MAP VALUES ARRIVE AS ONE SLICE PER POSSIBLE CPU. PerCpuBuf IS THE
+// PREALLOCATED DESTINATION scheduler.rs READS THEM INTO EVERY TICK, SO THE
+// MONITOR NEVER ALLOCATES A Vec<Vec<u8>> PER LOOKUP.
+//
+// COUNTERS ONLY GO BACKWARDS WHEN BPF RESTARTS AND THE MAP IS ZEROED.
+// A NAIVE wrapping_sub THEN YIELDS ~2^64 FOR ONE TICK AND POISONS idle_pct
+// AND REGIME DETECTION. StatsDelta::from() FLAGS THAT TICK AS A RESET.
+
+// MATCHES struct pandemonium_stats IN BPF (intf.h)
+#[repr(C)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PandemoniumStats {
+    pub nr_dispatches: u64,          // COUNT: TASKS DISPATCHED (ALL PATHS)
+    pub nr_idle_hits: u64,           // COUNT: SELECT_CPU IDLE FAST PATH
+    pub nr_shared: u64,              // COUNT: ENQUEUE -> PER-NODE SHARED DSQ
+    pub nr_preempt: u64,             // COUNT: TICK PREEMPTIONS
+    pub wake_lat_sum: u64,           // NS: SUM OF WAKEUP->RUN LATENCY
+    pub wake_lat_max: u64,           // NS, GAUGE: MAX WAKEUP->RUN LATENCY
+    pub wake_lat_samples: u64,       // COUNT: WAKEUP LATENCY SAMPLES
+    pub nr_keep_running: u64,        // COUNT: SLICES REPLENISHED VIA keep_running()
+    pub nr_hard_kicks: u64,          // COUNT: SCX_KICK_PREEMPT ON FRESH WAKEUP
+    pub nr_soft_kicks: u64,          // COUNT: SOFT NUDGE ON RE-ENQUEUE
+    pub nr_enq_wakeup: u64,          // COUNT: ENQUEUES OF JUST-WOKEN TASKS
+    pub nr_enq_requeue: u64,         // COUNT: RE-ENQUEUES
+    pub wake_lat_idle_sum: u64,      // NS: LATENCY SUM, IDLE FAST PATH
+    pub wake_lat_idle_cnt: u64,      // COUNT: LATENCY SAMPLES, IDLE FAST PATH
+    pub wake_lat_kick_sum: u64,      // NS: LATENCY SUM, HARD-KICKED ENQUEUE
+    pub wake_lat_kick_cnt: u64,      // COUNT: LATENCY SAMPLES, HARD-KICKED ENQUEUE
+    pub nr_procdb_hits: u64,         // COUNT: PRE-LEARNED CLASSIFICATIONS APPLIED
+    pub nr_l2_hit_batch: u64,        // COUNT: L2-LOCAL PLACEMENTS, BATCH
+    pub nr_l2_miss_batch: u64,       // COUNT: L2-REMOTE PLACEMENTS, BATCH
+    pub nr_l2_hit_interactive: u64,  // COUNT: L2-LOCAL PLACEMENTS, INTERACTIVE
+    pub nr_l2_miss_interactive: u64, // COUNT: L2-REMOTE PLACEMENTS, INTERACTIVE
+    pub nr_l2_hit_lat_crit: u64,     // COUNT: L2-LOCAL PLACEMENTS, LAT_CRITICAL
+    pub nr_l2_miss_lat_crit: u64,    // COUNT: L2-REMOTE PLACEMENTS, LAT_CRITICAL
+    pub nr_reenqueue: u64,           // COUNT: TASKS RESCUED BY scx_bpf_reenqueue_local()
+    pub batch_sojourn_ns: u64,       // NS, GAUGE: CURRENT BATCH DSQ WAIT AGE
+    pub burst_mode_active: u64,      // GAUGE: CPUS IN BURST MODE (COUNTER UNDER BURST_COUNTER_TEST)
+    pub longrun_mode_active: u64,    // GAUGE: 1 WHILE LONGRUN MODE IS ACTIVE
+    pub nr_overflow_rescue: u64,     // COUNT: OVERFLOW SOJOURN RESCUE DISPATCHES
+    pub nr_tier_to_batch: u64,       // COUNT: RECLASSIFIED INTO BATCH
+    pub nr_tier_to_interactive: u64, // COUNT: RECLASSIFIED INTO INTERACTIVE
+    pub nr_tier_to_lat_crit: u64,    // COUNT: RECLASSIFIED INTO LAT_CRITICAL
+}
+
+// COMPILE-TIME ABI SAFETY: MUST MATCH STRUCT LAYOUT IN intf.h (31 x u64)
+const _: () = assert!(std::mem::size_of::<PandemoniumStats>() == 248);
+
+// MATCHES struct dsq_depth IN BPF (intf.h). A GAUGE SAMPLED BY tick().
+#[repr(C)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DsqDepth {
+    pub interactive: u64, // TASKS: INTERACTIVE OVERFLOW DSQs, ALL NODES
+    pub batch: u64,       // TASKS: BATCH OVERFLOW DSQs, ALL NODES
+}
+
+const _: () = assert!(std::mem::size_of::<DsqDepth>() == 16);
+
+impl DsqDepth {
+    pub fn total(&self) -> u64 {
+        self.interactive + self.batch
+    }
+}
+
+// A MAP'S value_size MUST EQUAL ITS RUST MIRROR EXACTLY. LARGER IS NOT
+// "COMPATIBLE": A FIELD ADDED TO AN intf.h STRUCT SHIFTS EVERY FIELD AFTER
+// IT, AND from_bytes WOULD DECODE GARBAGE INSTEAD OF FAILING. CHECKED ON THE
+// SKELETON BEFORE ATTACH AND ON PINNED MAPS BEFORE THE FIRST READ.
+pub fn check_value_size(map: &str, value_size: u32, mirror: usize) -> anyhow::Result<()> {
+    if value_size as usize != mirror {
+        anyhow::bail!(
+            "{}: BPF VALUE IS {} BYTES, RUST MIRROR IS {} (intf.h OUT OF SYNC, OR A PINNED MAP FROM ANOTHER BUILD)",
+            map,
+            value_size,
+            mirror
+        );
+    }
+    Ok(())
+}
+
+// NO COUNTER MOVES THIS FAR IN ONE TICK; A LARGER wrapping_sub IS A RESET.
+// A GENUINE u64 WRAP (PREV IN THE TOP HALF, CUR SMALL) STAYS BELOW IT.
+pub const RESET_DELTA: u64 = u64::MAX / 2;
+
+// 0 WHEN whole IS 0
+pub fn pct(part: u64, whole: u64) -> u64 {
+    (part * 100).checked_div(whole).unwrap_or(0)
+}
+
+// SUM NS OVER COUNT SAMPLES -> AVERAGE US (0 WITHOUT SAMPLES)
+fn avg_us(sum_ns: u64, count: u64) -> u64 {
+    sum_ns.checked_div(count).unwrap_or(0) / 1000
+}
+
+impl PandemoniumStats {
+    // DECODE ONE PER-CPU MAP VALUE; None UNLESS THE SIZE MATCHES EXACTLY
+    pub fn from_bytes(b: &[u8]) -> Option<Self> {
+        if b.len() != std::mem::size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { std::ptr::read_unaligned(b.as_ptr() as *const Self) })
+    }
+
+    // (NAME, VALUE) IN STRUCT ORDER, FOR DUMPS
+    pub fn fields(&self) -> [(&'static str, u64); 31] {
+        [
+            ("nr_dispatches", self.nr_dispatches),
+            ("nr_idle_hits", self.nr_idle_hits),
+            ("nr_shared", self.nr_shared),
+            ("nr_preempt", self.nr_preempt),
+            ("wake_lat_sum", self.wake_lat_sum),
+            ("wake_lat_max", self.wake_lat_max),
+            ("wake_lat_samples", self.wake_lat_samples),
+            ("nr_keep_running", self.nr_keep_running),
+            ("nr_hard_kicks", self.nr_hard_kicks),
+            ("nr_soft_kicks", self.nr_soft_kicks),
+            ("nr_enq_wakeup", self.nr_enq_wakeup),
+            ("nr_enq_requeue", self.nr_enq_requeue),
+            ("wake_lat_idle_sum", self.wake_lat_idle_sum),
+            ("wake_lat_idle_cnt", self.wake_lat_idle_cnt),
+            ("wake_lat_kick_sum", self.wake_lat_kick_sum),
+            ("wake_lat_kick_cnt", self.wake_lat_kick_cnt),
+            ("nr_procdb_hits", self.nr_procdb_hits),
+            ("nr_l2_hit_batch", self.nr_l2_hit_batch),
+            ("nr_l2_miss_batch", self.nr_l2_miss_batch),
+            ("nr_l2_hit_interactive", self.nr_l2_hit_interactive),
+            ("nr_l2_miss_interactive", self.nr_l2_miss_interactive),
+            ("nr_l2_hit_lat_crit", self.nr_l2_hit_lat_crit),
+            ("nr_l2_miss_lat_crit", self.nr_l2_miss_lat_crit),
+            ("nr_reenqueue", self.nr_reenqueue),
+            ("batch_sojourn_ns", self.batch_sojourn_ns),
+            ("burst_mode_active", self.burst_mode_active),
+            ("longrun_mode_active", self.longrun_mode_active),
+            ("nr_overflow_rescue", self.nr_overflow_rescue),
+            ("nr_tier_to_batch", self.nr_tier_to_batch),
+            ("nr_tier_to_interactive", self.nr_tier_to_interactive),
+            ("nr_tier_to_lat_crit", self.nr_tier_to_lat_crit),
+        ]
+    }
+
+    // FOLD ONE CPU'S COPY INTO A SYSTEM-WIDE TOTAL: COUNTERS AND THE BURST FLAG
+    // SUM, THE OTHER GAUGES TAKE THE MAX
+    pub fn accumulate(&mut self, cpu: &PandemoniumStats) {
+        self.nr_dispatches += cpu.nr_dispatches;
+        self.nr_idle_hits += cpu.nr_idle_hits;
+        self.nr_shared += cpu.nr_shared;
+        self.nr_preempt += cpu.nr_preempt;
+        self.wake_lat_sum += cpu.wake_lat_sum;
+        self.wake_lat_max = self.wake_lat_max.max(cpu.wake_lat_max);
+        self.wake_lat_samples += cpu.wake_lat_samples;
+        self.nr_keep_running += cpu.nr_keep_running;
+        self.nr_hard_kicks += cpu.nr_hard_kicks;
+        self.nr_soft_kicks += cpu.nr_soft_kicks;
+        self.nr_enq_wakeup += cpu.nr_enq_wakeup;
+        self.nr_enq_requeue += cpu.nr_enq_requeue;
+        self.wake_lat_idle_sum += cpu.wake_lat_idle_sum;
+        self.wake_lat_idle_cnt += cpu.wake_lat_idle_cnt;
+        self.wake_lat_kick_sum += cpu.wake_lat_kick_sum;
+        self.wake_lat_kick_cnt += cpu.wake_lat_kick_cnt;
+        self.nr_procdb_hits += cpu.nr_procdb_hits;
+        self.nr_l2_hit_batch += cpu.nr_l2_hit_batch;
+        self.nr_l2_miss_batch += cpu.nr_l2_miss_batch;
+        self.nr_l2_hit_interactive += cpu.nr_l2_hit_interactive;
+        self.nr_l2_miss_interactive += cpu.nr_l2_miss_interactive;
+        self.nr_l2_hit_lat_crit += cpu.nr_l2_hit_lat_crit;
+        self.nr_l2_miss_lat_crit += cpu.nr_l2_miss_lat_crit;
+        self.nr_reenqueue += cpu.nr_reenqueue;
+        self.batch_sojourn_ns = self.batch_sojourn_ns.max(cpu.batch_sojourn_ns);
+        self.burst_mode_active += cpu.burst_mode_active;
+        self.longrun_mode_active = self.longrun_mode_active.max(cpu.longrun_mode_active);
+        self.nr_overflow_rescue += cpu.nr_overflow_rescue;
+        self.nr_tier_to_batch += cpu.nr_tier_to_batch;
+        self.nr_tier_to_interactive += cpu.nr_tier_to_interactive;
+        self.nr_tier_to_lat_crit += cpu.nr_tier_to_lat_crit;
+    }
+
+    // SYSTEM-WIDE TOTAL OF A PER-CPU READ
+    pub fn total(per_cpu: &[PandemoniumStats]) -> PandemoniumStats {
+        let mut total = PandemoniumStats::default();
+        for cpu in per_cpu {
+            total.accumulate(cpu);
+        }
+        total
+    }
+
+    // TOTAL WITHOUT THE skip CPUS' COUNTERS (--reserved-cpus: WHAT RUNS
+    // THERE IS NOT PANDEMONIUM'S LOAD, AND THEIR IDLE HITS ARE NOT IDLE
+    // CAPACITY IT CAN USE)
+    pub fn total_except(per_cpu: &[PandemoniumStats], skip: &[u32]) -> PandemoniumStats {
+        let mut total = PandemoniumStats::default();
+        for (cpu, s) in per_cpu.iter().enumerate() {
+            if !skip.contains(&(cpu as u32)) {
+                total.accumulate(s);
+            }
+        }
+        total
+    }
+
+    // CUMULATIVE AVERAGE WAKEUP LATENCY
+    pub fn wake_avg_us(&self) -> u64 {
+        avg_us(self.wake_lat_sum, self.wake_lat_samples)
+    }
+
+    // CUMULATIVE L2 HIT RATE (%) PER TIER: 0=BATCH, 1=INTERACTIVE, 2=LAT_CRITICAL
+    pub fn l2_hit_pct(&self) -> [u64; 3] {
+        [
+            pct(
+                self.nr_l2_hit_batch,
+                self.nr_l2_hit_batch + self.nr_l2_miss_batch,
+            ),
+            pct(
+                self.nr_l2_hit_interactive,
+                self.nr_l2_hit_interactive + self.nr_l2_miss_interactive,
+            ),
+            pct(
+                self.nr_l2_hit_lat_crit,
+                self.nr_l2_hit_lat_crit + self.nr_l2_miss_lat_crit,
+            ),
+        ]
+    }
+
+    // COUNTER MOVEMENT SINCE prev, SEE StatsDelta::from()
+    pub fn delta(&self, prev: &PandemoniumStats) -> StatsDelta {
+        StatsDelta::from(prev, self)
+    }
+}
+
+// ONE TICK OF COUNTER MOVEMENT (SAME UNITS AS THE COUNTERS ABOVE).
+// l2_hit / l2_miss / tier_changes ARE INDEXED BY TIER: 0=BATCH,
+// 1=INTERACTIVE, 2=LAT_CRITICAL (tier_changes: THE TIER MOVED INTO).
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StatsDelta {
+    pub dispatches: u64,
+    pub idle_hits: u64,
+    pub shared: u64,
+    pub preempt: u64,
+    pub wake_lat_sum: u64,
+    pub wake_lat_samples: u64,
+    pub keep_running: u64,
+    pub hard_kicks: u64,
+    pub soft_kicks: u64,
+    pub enq_wakeup: u64,
+    pub enq_requeue: u64,
+    pub wake_lat_idle_sum: u64,
+    pub wake_lat_idle_cnt: u64,
+    pub wake_lat_kick_sum: u64,
+    pub wake_lat_kick_cnt: u64,
+    pub procdb_hits: u64,
+    pub l2_hit: [u64; 3],
+    pub l2_miss: [u64; 3],
+    pub reenqueue: u64,
+    pub burst_ticks: u64,
+    pub overflow_rescue: u64,
+    pub tier_changes: [u64; 3],
+    // COUNTERS WENT BACKWARDS (BPF RESTART): EVERY DELTA ABOVE IS ZERO,
+    // THE TICK CARRIES NO INFORMATION
+    pub reset: bool,
+}
+
+impl StatsDelta {
+    // WRAPPING DELTAS, OR ALL-ZERO WITH reset SET IF ANY COUNTER REGRESSED
+    pub fn from(prev: &PandemoniumStats, cur: &PandemoniumStats) -> Self {
+        let d = |c: u64, p: u64| c.wrapping_sub(p);
+        let delta = StatsDelta {
+            dispatches: d(cur.nr_dispatches, prev.nr_dispatches),
+            idle_hits: d(cur.nr_idle_hits, prev.nr_idle_hits),
+            shared: d(cur.nr_shared, prev.nr_shared),
+            preempt: d(cur.nr_preempt, prev.nr_preempt),
+            wake_lat_sum: d(cur.wake_lat_sum, prev.wake_lat_sum),
+            wake_lat_samples: d(cur.wake_lat_samples, prev.wake_lat_samples),
+            keep_running: d(cur.nr_keep_running, prev.nr_keep_running),
+            hard_kicks: d(cur.nr_hard_kicks, prev.nr_hard_kicks),
+            soft_kicks: d(cur.nr_soft_kicks, prev.nr_soft_kicks),
+            enq_wakeup: d(cur.nr_enq_wakeup, prev.nr_enq_wakeup),
+            enq_requeue: d(cur.nr_enq_requeue, prev.nr_enq_requeue),
+            wake_lat_idle_sum: d(cur.wake_lat_idle_sum, prev.wake_lat_idle_sum),
+            wake_lat_idle_cnt: d(cur.wake_lat_idle_cnt, prev.wake_lat_idle_cnt),
+            wake_lat_kick_sum: d(cur.wake_lat_kick_sum, prev.wake_lat_kick_sum),
+            wake_lat_kick_cnt: d(cur.wake_lat_kick_cnt, prev.wake_lat_kick_cnt),
+            procdb_hits: d(cur.nr_procdb_hits, prev.nr_procdb_hits),
+            l2_hit: [
+                d(cur.nr_l2_hit_batch, prev.nr_l2_hit_batch),
+                d(cur.nr_l2_hit_interactive, prev.nr_l2_hit_interactive),
+                d(cur.nr_l2_hit_lat_crit, prev.nr_l2_hit_lat_crit),
+            ],
+            l2_miss: [
+                d(cur.nr_l2_miss_batch, prev.nr_l2_miss_batch),
+                d(cur.nr_l2_miss_interactive, prev.nr_l2_miss_interactive),
+                d(cur.nr_l2_miss_lat_crit, prev.nr_l2_miss_lat_crit),
+            ],
+            reenqueue: d(cur.nr_reenqueue, prev.nr_reenqueue),
+            burst_ticks: d(cur.burst_mode_active, prev.burst_mode_active),
+            overflow_rescue: d(cur.nr_overflow_rescue, prev.nr_overflow_rescue),
+            tier_changes: [
+                d(cur.nr_tier_to_batch, prev.nr_tier_to_batch),
+                d(cur.nr_tier_to_interactive, prev.nr_tier_to_interactive),
+                d(cur.nr_tier_to_lat_crit, prev.nr_tier_to_lat_crit),
+            ],
+            reset: false,
+        };
+        if delta.counters().any(|v| v > RESET_DELTA) {
+            return StatsDelta {
+                reset: true,
+                ..Default::default()
+            };
+        }
+        delta
+    }
+
+    // MONOTONIC COUNTER DELTAS (burst_ticks TRACKS A GAUGE: NOT A RESET SIGNAL)
+    fn counters(&self) -> impl Iterator<Item = u64> {
+        [
+            self.dispatches,
+            self.idle_hits,
+            self.shared,
+            self.preempt,
+            self.wake_lat_sum,
+            self.wake_lat_samples,
+            self.keep_running,
+            self.hard_kicks,
+            self.soft_kicks,
+            self.enq_wakeup,
+            self.enq_requeue,
+            self.wake_lat_idle_sum,
+            self.wake_lat_idle_cnt,
+            self.wake_lat_kick_sum,
+            self.wake_lat_kick_cnt,
+            self.procdb_hits,
+            self.reenqueue,
+            self.overflow_rescue,
+        ]
+        .into_iter()
+        .chain(self.l2_hit)
+        .chain(self.l2_miss)
+        .chain(self.tier_changes)
+    }
+
+    // SHARE OF DISPATCHES PLACED BY THE IDLE FAST PATH (%)
+    pub fn idle_pct(&self) -> u64 {
+        pct(self.idle_hits, self.dispatches)
+    }
+
+    pub fn wake_avg_us(&self) -> u64 {
+        avg_us(self.wake_lat_sum, self.wake_lat_samples)
+    }
+
+    pub fn lat_idle_us(&self) -> u64 {
+        avg_us(self.wake_lat_idle_sum, self.wake_lat_idle_cnt)
+    }
+
+    pub fn lat_kick_us(&self) -> u64 {
+        avg_us(self.wake_lat_kick_sum, self.wake_lat_kick_cnt)
+    }
+
+    // L2 HIT RATE (%) THIS TICK, PER TIER
+    pub fn l2_hit_pct(&self) -> [u64; 3] {
+        std::array::from_fn(|t| pct(self.l2_hit[t], self.l2_hit[t] + self.l2_miss[t]))
+    }
+
+    // THE BURST GAUGE MOVED SINCE THE LAST SNAPSHOT
+    pub fn burst(&self) -> bool {
+        self.burst_ticks > 0
+    }
+
+    // ENQUEUES MINUS DISPATCHES THIS TICK. SIGNED: select_cpu() DIRECT
+    // DISPATCHES HAVE NO MATCHING ENQUEUE, SO A HEALTHY TICK RUNS NEGATIVE
+    pub fn backlog_net(&self) -> i64 {
+        let enq = self.enq_wakeup.saturating_add(self.enq_requeue);
+        let as_i64 = |v: u64| i64::try_from(v).unwrap_or(i64::MAX);
+        as_i64(enq).saturating_sub(as_i64(self.dispatches))
+    }
+}
+
+// KERNEL LAYOUT OF A PERCPU ARRAY READ: FOR EACH KEY, nr_cpus SLICES OF
+// value_size ROUNDED UP TO 8 BYTES. ONE BUFFER HOLDS EVERY KEY SO A SINGLE
+// BATCH LOOKUP FILLS IT; bpf_map_lookup_elem FILLS ONE KEY'S RANGE.
+//
+// OFFLINE CPUS CANNOT BE SKIPPED: THE KERNEL ALWAYS COPIES ALL POSSIBLE
+// CPUS, AND A CPU THAT WENT OFFLINE KEEPS ITS CUMULATIVE COUNTERS, WHICH
+// MUST STAY IN THE SUMS OR THE NEXT DELTA GOES BACKWARDS.
+pub struct PerCpuBuf {
+    data: Vec<u8>,
+    nr_keys: usize,
+    nr_cpus: usize,
+    stride: usize,
+    value_size: usize,
+}
+
+impl PerCpuBuf {
+    pub fn new(nr_keys: usize, nr_cpus: usize, value_size: usize) -> Self {
+        let stride = value_size.div_ceil(8) * 8;
+        Self {
+            data: vec![0; nr_keys * nr_cpus * stride],
+            nr_keys,
+            nr_cpus,
+            stride,
+            value_size,
+        }
+    }
+
+    pub fn nr_keys(&self) -> usize {
+        self.nr_keys
+    }
+
+    pub fn nr_cpus(&self) -> usize {
+        self.nr_cpus
+    }
+
+    // WHOLE BUFFER, KEY-MAJOR: THE BATCH LOOKUP DESTINATION
+    pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    // ONE KEY'S nr_cpus SLICES: THE SINGLE-KEY LOOKUP DESTINATION
+    pub fn key_mut(&mut self, key: usize) -> &mut [u8] {
+        let len = self.nr_cpus * self.stride;
+        &mut self.data[key * len..(key + 1) * len]
+    }
+
+    pub fn value(&self, key: usize, cpu: usize) -> &[u8] {
+        let off = (key * self.nr_cpus + cpu) * self.stride;
+        &self.data[off..off + self.value_size]
+    }
+
+    // u64-VALUED MAPS: ONE KEY'S VALUE ON EACH CPU, BY CPU ID
+    pub fn u64s(&self, key: usize) -> impl Iterator<Item = u64> + '_ {
+        let len = self.nr_cpus * self.stride;
+        self.data[key * len..(key + 1) * len]
+            .chunks_exact(self.stride)
+            .map(|b| u64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    // HISTOGRAM BUCKETS: ONE KEY SUMMED OVER EVERY CPU
+    pub fn sum_u64(&self, key: usize) -> u64 {
+        self.u64s(key).sum()
+    }
+
+    // stats_map (ONE KEY): ONE ENTRY PER POSSIBLE CPU, INDEXED BY CPU ID
+    pub fn stats(&self) -> Vec<PandemoniumStats> {
+        (0..self.nr_cpus)
+            .map(|cpu| PandemoniumStats::from_bytes(self.value(0, cpu)).unwrap_or_default())
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+}
@@ -0,0 +1,275 @@
+// PANDEMONIUM STATUS-BAR OUTPUT
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// `pandemonium status` PRINTS ONE LINE FOR A STATUS BAR AND EXITS:
+//   --waybar              {"text": ..., "alt": ..., "class": ..., "tooltip": ...}
+//   --format custom:TPL   TPL WITH {field} PLACEHOLDERS, FOR OTHER BARS
+// A SCHEDULER THAT IS NOT RUNNING IS A STATE, NOT AN ERROR: THE BAR SHOWS
+// "off" WITH CLASS "inactive" INSTEAD OF A BROKEN MODULE.
+//
+// TEMPLATE MINI-LANGUAGE: LITERAL TEXT, {field}, {{ AND }} FOR LITERAL
+// BRACES. UNKNOWN FIELDS AND UNBALANCED BRACES ARE PARSE ERRORS, SO A TYPO
+// FAILS ONCE AT THE COMMAND LINE, NOT SILENTLY ON EVERY REFRESH.
+
+use crate::benchout::Json;
+use crate::reserved::describe;
+use crate::tuning::Regime;
+
+pub const FORMAT_CUSTOM_PREFIX: &str = "custom:";
+pub const DEFAULT_TEMPLATE: &str = "{regime} P99 {p99}";
+pub const WAYBAR_TEMPLATE: &str = "P99 {p99}";
+
+const INACTIVE_TEXT: &str = "off";
+const MISSING: &str = "-";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Regime, // LIGHT / MIXED / HEAVY, BPF-ONLY, INACTIVE
+    Class,  // LOWERCASE, AS IN THE WAYBAR class
+    P99,    // "250us" BELOW 1ms, ELSE "2.0ms"
+    P99Us,
+    P99Ms,      // ONE DECIMAL
+    WakeUs,     // AVERAGE WAKEUP LATENCY
+    Samples,    // WAKEUP LATENCY SAMPLES BEHIND THE P99
+    Dispatches, // CUMULATIVE
+    Flags,      // "PINNED PAUSED TIGHT", EMPTY WHEN NONE
+    Reserved,   // --reserved-cpus AS "2-3,8", `-` WHEN NONE
+    Restarts,   // KERNEL-REQUESTED RESTARTS THIS RUN
+}
+
+// NAME -> FIELD, ALSO THE LIST IN THE UNKNOWN-FIELD ERROR
+pub const FIELDS: [(&str, Field); 11] = [
+    ("regime", Field::Regime),
+    ("class", Field::Class),
+    ("p99", Field::P99),
+    ("p99_us", Field::P99Us),
+    ("p99_ms", Field::P99Ms),
+    ("wake_us", Field::WakeUs),
+    ("samples", Field::Samples),
+    ("dispatches", Field::Dispatches),
+    ("flags", Field::Flags),
+    ("reserved", Field::Reserved),
+    ("restarts", Field::Restarts),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    Lit(String),
+    Field(Field),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+// ONE READ OF A RUNNING SCHEDULER. P99 AND WAKE ARE CUMULATIVE SINCE ATTACH
+// (THE PINNED wake_lat_hist AND stats_map).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BarStatus {
+    pub regime: Option<Regime>, // None: --no-adaptive, NO CONTROL SOCKET
+    pub pinned: bool,
+    pub paused: bool,
+    pub tightened: bool,
+    pub p99_ns: u64,
+    pub wake_avg_ns: u64,
+    pub samples: u64,
+    pub dispatches: u64,
+    pub reserved: Vec<u32>, // --reserved-cpus, ASCENDING
+    pub restarts: u32,      // KERNEL-REQUESTED RESTARTS (0 WITHOUT A SOCKET)
+    pub last_restart: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BarState {
+    Running(BarStatus),
+    Inactive(String), // WHY: NOTHING ATTACHED, ANOTHER SCHEDULER, NO PINS
+}
+
+impl Template {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut lit = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    lit.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    lit.push('}');
+                }
+                '}' => return Err(format!("UNMATCHED }} IN {:?} (USE }}}} FOR A LITERAL)", s)),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("UNCLOSED {{ IN {:?}", s)),
+                        }
+                    }
+                    let field = FIELDS
+                        .iter()
+                        .find(|(n, _)| *n == name.trim())
+                        .map(|&(_, f)| f)
+                        .ok_or_else(|| {
+                            let names: Vec<&str> = FIELDS.iter().map(|(n, _)| *n).collect();
+                            format!("UNKNOWN FIELD {{{}}} (FIELDS: {})", name, names.join(", "))
+                        })?;
+                    if !lit.is_empty() {
+                        segments.push(Segment::Lit(std::mem::take(&mut lit)));
+                    }
+                    segments.push(Segment::Field(field));
+                }
+                c => lit.push(c),
+            }
+        }
+        if !lit.is_empty() {
+            segments.push(Segment::Lit(lit));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn render(&self, state: &BarState) -> String {
+        self.segments
+            .iter()
+            .map(|seg| match seg {
+                Segment::Lit(s) => s.clone(),
+                Segment::Field(f) => field_value(state, *f),
+            })
+            .collect()
+    }
+}
+
+// --format VALUE: "custom:TEMPLATE" (THE ONLY KIND SO FAR)
+pub fn parse_format(arg: &str) -> Result<Template, String> {
+    match arg.strip_prefix(FORMAT_CUSTOM_PREFIX) {
+        Some(tpl) => Template::parse(tpl),
+        None => Err(format!(
+            "--format {:?}: EXPECTED {}TEMPLATE, E.G. custom:\"{{regime}} {{p99_us}}us\"",
+            arg, FORMAT_CUSTOM_PREFIX
+        )),
+    }
+}
+
+// 250_000 -> "250us", 2_000_000 -> "2.0ms"
+pub fn format_latency(ns: u64) -> String {
+    if ns < 1_000_000 {
+        format!("{}us", ns / 1000)
+    } else {
+        format!("{:.1}ms", ns as f64 / 1e6)
+    }
+}
+
+impl BarStatus {
+    pub fn regime_label(&self) -> &'static str {
+        self.regime.map_or("BPF-ONLY", Regime::label)
+    }
+
+    pub fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.pinned, "PINNED"),
+            (self.paused, "PAUSED"),
+            (self.tightened, "TIGHT"),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|&(_, name)| name)
+        .collect()
+    }
+}
+
+impl BarState {
+    // WAYBAR class: COLOR THE MODULE PER REGIME IN style.css
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Running(s) => match s.regime {
+                Some(Regime::Light) => "light",
+                Some(Regime::Mixed) => "mixed",
+                Some(Regime::Heavy) => "heavy",
+                None => "bpf-only",
+            },
+            Self::Inactive(_) => "inactive",
+        }
+    }
+
+    pub fn tooltip_lines(&self) -> Vec<String> {
+        match self {
+            Self::Running(s) => {
+                let mut lines = vec![format!("PANDEMONIUM {}", s.regime_label())];
+                let flags = s.flags();
+                if !flags.is_empty() {
+                    lines.push(flags.join(" "));
+                }
+                lines.push(format!(
+                    "WAKEUP P99 {} / AVG {} ({} SAMPLES SINCE ATTACH)",
+                    format_latency(s.p99_ns),
+                    format_latency(s.wake_avg_ns),
+                    s.samples
+                ));
+                lines.push(format!("DISPATCHES {}", s.dispatches));
+                if !s.reserved.is_empty() {
+                    lines.push(format!("RESERVED CPUS {}", describe(&s.reserved)));
+                }
+                if s.restarts > 0 {
+                    lines.push(format!(
+                        "KERNEL RESTARTS {} (LAST: {})",
+                        s.restarts,
+                        s.last_restart.as_deref().unwrap_or(MISSING)
+                    ));
+                }
+                lines
+            }
+            Self::Inactive(reason) => vec!["PANDEMONIUM NOT RUNNING".to_string(), reason.clone()],
+        }
+    }
+}
+
+fn field_value(state: &BarState, field: Field) -> String {
+    let s = match state {
+        BarState::Running(s) => s,
+        BarState::Inactive(_) => {
+            return match field {
+                Field::Regime => "INACTIVE".to_string(),
+                Field::Class => state.class().to_string(),
+                Field::Flags => String::new(),
+                _ => MISSING.to_string(),
+            }
+        }
+    };
+    match field {
+        Field::Regime => s.regime_label().to_string(),
+        Field::Class => state.class().to_string(),
+        Field::P99 => format_latency(s.p99_ns),
+        Field::P99Us => (s.p99_ns / 1000).to_string(),
+        Field::P99Ms => format!("{:.1}", s.p99_ns as f64 / 1e6),
+        Field::WakeUs => (s.wake_avg_ns / 1000).to_string(),
+        Field::Samples => s.samples.to_string(),
+        Field::Dispatches => s.dispatches.to_string(),
+        Field::Flags => s.flags().join(" "),
+        Field::Reserved => describe(&s.reserved),
+        Field::Restarts => s.restarts.to_string(),
+    }
+}
+
+// ONE LINE: WAYBAR READS A JSON OBJECT PER LINE
+pub fn waybar_json(state: &BarState, text: &Template) -> String {
+    let text = match state {
+        BarState::Running(_) => text.render(state),
+        BarState::Inactive(_) => INACTIVE_TEXT.to_string(),
+    };
+    Json::obj(vec![
+        ("text", Json::Str(text)),
+        ("alt", Json::str(state.class())),
+        ("class", Json::str(state.class())),
+        ("tooltip", Json::Str(state.tooltip_lines().join("\n"))),
+    ])
+    .render_line()
+}
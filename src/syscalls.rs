@@ -0,0 +1,180 @@
+// PANDEMONIUM MAP SYSCALL BUDGET
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+// SHARED BETWEEN BINARY CRATE (scheduler.rs, procdb.rs) AND LIB CRATE (tests)
+//
+// EVERY BPF MAP OPERATION THE MONITOR MAKES IS A bpf() SYSCALL. THE FIXED
+// PART OF A TICK IS A HANDFUL: STATS, HISTOGRAMS, IDLE BITMAP, DSQ DEPTH,
+// THE OCCASIONAL KNOB WRITE. PROCDB SCALES WITH PROCESS CHURN: ITS DRAIN
+// COSTS A get_next_key, A lookup AND A delete PER OBSERVATION, ITS FLUSH AN
+// update PER PREDICTION, ITS EVICTIONS A delete EACH. A SyscallTally
+// COUNTS EVERY ONE BY KIND; --verbose PRINTS THE TICK'S COUNT AS
+// [SYSCALLS]. AN OpBudget CAPS PROCDB'S SHARE OF A TICK, AND WHAT DOES NOT
+// FIT WAITS FOR THE NEXT ONE.
+//
+// MapOps IS THE SLICE OF A BPF MAP PROCDB USES. Counted WRAPS ONE AND
+// RECORDS EACH CALL, SO THE TESTS COUNT AGAINST AN IN-MEMORY MAP.
+
+use std::cell::Cell;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapOp {
+    Lookup, // bpf_map_lookup_elem, OR ONE bpf_map_lookup_batch
+    Update,
+    Delete,
+    NextKey, // ONE STEP OF A KEY WALK
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyscallCounts {
+    pub lookup: u64,
+    pub update: u64,
+    pub delete: u64,
+    pub next_key: u64,
+}
+
+impl SyscallCounts {
+    pub fn total(&self) -> u64 {
+        self.lookup + self.update + self.delete + self.next_key
+    }
+
+    pub fn add(&mut self, op: MapOp, n: u64) {
+        let c = match op {
+            MapOp::Lookup => &mut self.lookup,
+            MapOp::Update => &mut self.update,
+            MapOp::Delete => &mut self.delete,
+            MapOp::NextKey => &mut self.next_key,
+        };
+        *c += n;
+    }
+
+    pub fn merge(&mut self, other: &SyscallCounts) {
+        self.lookup += other.lookup;
+        self.update += other.update;
+        self.delete += other.delete;
+        self.next_key += other.next_key;
+    }
+
+    // [SYSCALLS] COLUMNS, total FIRST
+    pub fn fields(&self) -> [(&'static str, u64); 5] {
+        [
+            ("total", self.total()),
+            ("lookup", self.lookup),
+            ("update", self.update),
+            ("delete", self.delete),
+            ("next_key", self.next_key),
+        ]
+    }
+}
+
+// ONE OWNER, ONE THREAD (THE MONITOR): A Cell, NO ATOMICS
+#[derive(Debug, Default)]
+pub struct SyscallTally {
+    counts: Cell<SyscallCounts>,
+}
+
+impl SyscallTally {
+    pub fn record(&self, op: MapOp, n: u64) {
+        let mut c = self.counts.get();
+        c.add(op, n);
+        self.counts.set(c);
+    }
+
+    // THE COUNTS SINCE THE LAST take(): TAKEN ONCE PER TICK, ONE TICK'S WORTH
+    pub fn take(&self) -> SyscallCounts {
+        self.counts.take()
+    }
+}
+
+pub trait MapOps {
+    fn lookup(&self, key: &[u8]) -> Option<Vec<u8>>;
+    fn update(&self, key: &[u8], value: &[u8]) -> bool;
+    fn delete(&self, key: &[u8]) -> bool;
+    // THE FIRST max KEYS IN MAP ORDER. ONE get_next_key PER KEY, PLUS THE
+    // ONE THAT FINDS THE END WHEN THE MAP HOLDS FEWER THAN max.
+    fn keys(&self, max: usize) -> Vec<Vec<u8>>;
+}
+
+// THE get_next_key CALLS OF A keys(max) THAT RETURNED n KEYS
+pub fn walk_cost(n: usize, max: usize) -> u64 {
+    n as u64 + (n < max) as u64
+}
+
+pub struct Counted<'a, M: ?Sized> {
+    map: &'a M,
+    tally: &'a SyscallTally,
+}
+
+impl<'a, M: MapOps + ?Sized> Counted<'a, M> {
+    pub fn new(map: &'a M, tally: &'a SyscallTally) -> Self {
+        Self { map, tally }
+    }
+}
+
+impl<M: MapOps + ?Sized> MapOps for Counted<'_, M> {
+    fn lookup(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.tally.record(MapOp::Lookup, 1);
+        self.map.lookup(key)
+    }
+
+    fn update(&self, key: &[u8], value: &[u8]) -> bool {
+        self.tally.record(MapOp::Update, 1);
+        self.map.update(key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> bool {
+        self.tally.record(MapOp::Delete, 1);
+        self.map.delete(key)
+    }
+
+    fn keys(&self, max: usize) -> Vec<Vec<u8>> {
+        let keys = self.map.keys(max);
+        self.tally
+            .record(MapOp::NextKey, walk_cost(keys.len(), max));
+        keys
+    }
+}
+
+// PER-TICK OPERATION CAP. take() SPENDS ONLY WHAT FITS; A REFUSAL MARKS
+// THE TICK capped AND THE CALLER CARRIES THE WORK OVER.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpBudget {
+    left: u64,
+    capped: bool,
+}
+
+impl OpBudget {
+    pub fn new(ops: u64) -> Self {
+        Self {
+            left: ops,
+            capped: false,
+        }
+    }
+
+    // STARTUP AND IMPORTS: ONE-OFF WORK, NOT A TICK'S
+    pub fn unlimited() -> Self {
+        Self::new(u64::MAX)
+    }
+
+    pub fn left(&self) -> u64 {
+        self.left
+    }
+
+    // SOME WORK WAS LEFT FOR THE NEXT TICK
+    pub fn capped(&self) -> bool {
+        self.capped
+    }
+
+    pub fn take(&mut self, n: u64) -> bool {
+        if n > self.left {
+            self.capped = true;
+            return false;
+        }
+        self.left -= n;
+        true
+    }
+
+    // WORK STOPPED SHORT WITHOUT A REFUSED take() (A KEY WALK CUT AT ITS CAP)
+    pub fn cap(&mut self) {
+        self.capped = true;
+    }
+}
@@ -0,0 +1,398 @@
+// PANDEMONIUM SYSTEM HEADER
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// "WHICH BUILD, WHICH KERNEL, WHICH MACHINE?" -- THE FIRST QUESTION ON EVERY
+// BUG REPORT. ONE BLOCK, PRINTED AT `run` STARTUP, AT THE TOP OF EVERY
+// BENCH/SCALE REPORT (`pandemonium info` FOR THE PYTHON HARNESS) AND IN THE
+// SIGUSR1 DIAGNOSTIC DUMP:
+//   PANDEMONIUM v<CARGO_PKG_VERSION> (git <describe>)
+//   KERNEL:    /proc/sys/kernel/osrelease
+//   SCHED_EXT: WORST KFUNC VERDICT + SLICE/VTIME WRITE PATH (compat.rs PROBE)
+//   CPU:       /proc/cpuinfo MODEL
+//   TOPOLOGY:  ONLINE CPUS, PHYSICAL CORES, SMT STATE, NUMA NODES
+// GIT_HASH IS EMBEDDED BY build.rs; A SOURCE TREE WITHOUT .git BUILDS FINE
+// AND SAYS "unknown".
+//
+// BELOW IT, THE SAME THREE PLACES PRINT THE [CONFIG] BLOCK: ONE key=value
+// PER LINE (OR ONE JSON OBJECT) FOR A SCRIPT TO GREP OUT OF A BUG REPORT.
+// POSSIBLE/ONLINE CPUS, L2/L3 GROUPS, NUMA, GOVERNOR, THE RUN'S MODE AND
+// THE KNOBS BPF HELD RIGHT AFTER ATTACH. config_lines() IS ITS ONLY
+// RENDERER. `info` AND BENCH REPORTS HAVE NO SCHEDULER OF THEIR OWN:
+// mode, lightweight, reserved_cpus AND knob.* READ `-` THERE.
+
+use std::path::Path;
+
+use crate::benchout::Json;
+use crate::compat::{self, Compat, CompatReport};
+use crate::cpulist;
+use crate::hotplug::parse_cpu_list;
+use crate::l2topo::L2Topology;
+use crate::tuning::KNOB_NAMES;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_DESCRIBE: Option<&str> = option_env!("GIT_HASH");
+
+const CPUINFO_PATH: &str = "/proc/cpuinfo";
+const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+const SMT_ACTIVE_PATH: &str = "/sys/devices/system/cpu/smt/active";
+const NODE_ONLINE_PATH: &str = "/sys/devices/system/node/online";
+const GOVERNOR_PATH: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor";
+
+pub const CONFIG_PREFIX: &str = "[CONFIG]";
+pub const MODE_ADAPTIVE: &str = "adaptive";
+pub const MODE_BPF_ONLY: &str = "bpf-only";
+const NO_VALUE: &str = "-";
+
+// EVERY BLOCK CARRIES ALL OF THESE, THEN knob.<NAME> PER KNOB_NAMES ENTRY
+pub const CONFIG_KEYS: [&str; 12] = [
+    "kernel",
+    "cpus_possible",
+    "cpus_online",
+    "nr_cpus",
+    "smt",
+    "numa_nodes",
+    "l2_groups",
+    "l3_groups",
+    "governor",
+    "mode",
+    "lightweight",
+    "reserved_cpus",
+];
+
+// HOW THE SCHEDULER IN THIS PROCESS WAS STARTED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunMode {
+    pub mode: &'static str, // MODE_ADAPTIVE OR MODE_BPF_ONLY
+    pub lightweight: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemInfo {
+    pub version: String,
+    pub git: Option<String>,
+    pub kernel: String,
+    pub sched_ext: String,
+    pub cpu_model: Option<String>,
+    pub cpus: usize,
+    pub cores: usize,
+    pub smt: Option<bool>, // None: KERNEL WITHOUT smt/active
+    pub nodes: usize,
+}
+
+// x86 "model name", arm64 HAS NONE PER CPU BUT SOME SOCS SET "Hardware"
+pub fn parse_cpu_model(cpuinfo: &str) -> Option<String> {
+    ["model name", "Hardware", "cpu model"]
+        .iter()
+        .find_map(|key| {
+            cpuinfo.lines().find_map(|line| {
+                let (k, v) = line.split_once(':')?;
+                (k.trim() == *key).then(|| v.trim().to_string())
+            })
+        })
+        .filter(|m| !m.is_empty())
+}
+
+// ONE core_cpus_list PER ONLINE CPU; SMT SIBLINGS SHARE A LIST
+pub fn count_cores(sibling_lists: &[String]) -> usize {
+    let mut cores: Vec<Vec<u32>> = sibling_lists
+        .iter()
+        .map(|s| parse_cpu_list(s.trim()))
+        .filter(|c| !c.is_empty())
+        .collect();
+    cores.sort();
+    cores.dedup();
+    cores.len()
+}
+
+// "PASS (14/14 KFUNCS CURRENT, SLICE/VTIME VIA scx_bpf_task_set_*)"
+pub fn scx_markers(report: &CompatReport) -> String {
+    let current = report
+        .entries
+        .iter()
+        .filter(|e| e.status == Compat::Pass)
+        .count();
+    format!(
+        "{} ({}/{} KFUNCS CURRENT, SLICE/VTIME VIA {})",
+        report.worst().label(),
+        current,
+        report.entries.len(),
+        if report.has_task_set_kfuncs() {
+            "scx_bpf_task_set_*"
+        } else {
+            "p->scx"
+        }
+    )
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+}
+
+impl SystemInfo {
+    pub fn collect() -> Self {
+        let online =
+            read_trimmed(Path::new(crate::hotplug::CPU_ONLINE_PATH)).map(|s| parse_cpu_list(&s));
+        let online = online.unwrap_or_default();
+        let sibling_lists: Vec<String> = online
+            .iter()
+            .filter_map(|cpu| {
+                let topo = Path::new(CPU_SYSFS).join(format!("cpu{}/topology", cpu));
+                // core_cpus_list IS 5.4+; thread_siblings_list IS ITS OLD NAME
+                read_trimmed(&topo.join("core_cpus_list"))
+                    .or_else(|| read_trimmed(&topo.join("thread_siblings_list")))
+            })
+            .collect();
+        let sched_ext = match compat::probe_kernel() {
+            Ok(report) => scx_markers(&report),
+            Err(e) => format!("PROBE FAILED ({})", e),
+        };
+        Self {
+            version: VERSION.to_string(),
+            git: GIT_DESCRIBE.map(str::to_string),
+            kernel: compat::kernel_release(),
+            sched_ext,
+            cpu_model: std::fs::read_to_string(CPUINFO_PATH)
+                .ok()
+                .and_then(|s| parse_cpu_model(&s)),
+            cpus: online.len(),
+            cores: count_cores(&sibling_lists),
+            smt: read_trimmed(Path::new(SMT_ACTIVE_PATH)).map(|s| s == "1"),
+            // NO NODE DIRECTORY: A !CONFIG_NUMA KERNEL, ONE NODE
+            nodes: read_trimmed(Path::new(NODE_ONLINE_PATH))
+                .map(|s| parse_cpu_list(&s).len())
+                .unwrap_or(1)
+                .max(1),
+        }
+    }
+
+    pub fn header_lines(&self) -> Vec<String> {
+        vec![
+            format!(
+                "PANDEMONIUM v{} (git {})",
+                self.version,
+                self.git.as_deref().unwrap_or("unknown")
+            ),
+            format!(
+                "KERNEL:    {}",
+                if self.kernel.is_empty() {
+                    "unknown"
+                } else {
+                    &self.kernel
+                }
+            ),
+            format!("SCHED_EXT: {}", self.sched_ext),
+            format!(
+                "CPU:       {}",
+                self.cpu_model.as_deref().unwrap_or("unknown")
+            ),
+            format!(
+                "TOPOLOGY:  {} CPUS, {} CORES, SMT {}, {} NODE{}",
+                self.cpus,
+                self.cores,
+                match self.smt {
+                    Some(true) => "ON",
+                    Some(false) => "OFF",
+                    None => "N/A",
+                },
+                self.nodes,
+                if self.nodes == 1 { "" } else { "S" }
+            ),
+        ]
+    }
+
+    // report.json "system" OBJECT
+    pub fn json(&self) -> Json {
+        let opt = |v: &Option<String>| v.as_deref().map(Json::str).unwrap_or(Json::Null);
+        Json::obj(vec![
+            ("version", Json::str(&self.version)),
+            ("git", opt(&self.git)),
+            ("kernel", Json::str(&self.kernel)),
+            ("sched_ext", Json::str(&self.sched_ext)),
+            ("cpu_model", opt(&self.cpu_model)),
+            ("cpus", Json::Num(self.cpus as f64)),
+            ("cores", Json::Num(self.cores as f64)),
+            ("smt", self.smt.map(Json::Bool).unwrap_or(Json::Null)),
+            ("nodes", Json::Num(self.nodes as f64)),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunConfig {
+    pub kernel: String,
+    pub cpus_possible: usize,
+    pub cpus_online: Vec<u32>,
+    pub nr_cpus: usize, // WHAT THE SCHEDULER SCALES FOR (--nr-cpus, CPUSET OR POSSIBLE)
+    pub smt: Option<bool>,
+    pub nodes: usize,
+    pub l2_groups: Vec<Vec<u32>>,
+    pub l3_groups: Vec<Vec<u32>>,
+    pub governor: Option<String>,
+    pub mode: Option<RunMode>, // None: NO SCHEDULER IN THIS PROCESS
+    pub reserved_cpus: Option<Vec<u32>>, // --reserved-cpus; None: NO SCHEDULER
+    pub knobs: Option<[u64; 10]>, // KNOB_NAMES ORDER
+}
+
+fn cache_list_path(cpu: usize, index: u32) -> String {
+    format!(
+        "{}/cpu{}/cache/index{}/shared_cpu_list",
+        CPU_SYSFS, cpu, index
+    )
+}
+
+// "0-1;2-3": GROUPS IN FIRST-SEEN ORDER, `;` BETWEEN THEM
+fn format_groups(groups: &[Vec<u32>]) -> String {
+    if groups.is_empty() {
+        return NO_VALUE.to_string();
+    }
+    groups
+        .iter()
+        .map(|g| cpulist::format(g))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn or_dash(s: &str) -> &str {
+    if s.is_empty() {
+        NO_VALUE
+    } else {
+        s
+    }
+}
+
+pub fn config_keys() -> Vec<String> {
+    CONFIG_KEYS
+        .iter()
+        .map(|k| k.to_string())
+        .chain(KNOB_NAMES.iter().map(|n| format!("knob.{}", n)))
+        .collect()
+}
+
+impl RunConfig {
+    // nr_cpus: THE EFFECTIVE --nr-cpus OR CPUSET COUNT (None: ALL POSSIBLE)
+    pub fn collect(
+        system: &SystemInfo,
+        nr_cpus: Option<u64>,
+        mode: Option<RunMode>,
+        knobs: Option<[u64; 10]>,
+    ) -> Self {
+        let possible = crate::memlock::possible_cpus().unwrap_or(system.cpus as u64) as usize;
+        // THE GROUPING IS LEVEL-AGNOSTIC: index3 LISTS MAKE L3 GROUPS
+        let l3_lists: Vec<Option<String>> = (0..possible)
+            .map(|cpu| std::fs::read_to_string(cache_list_path(cpu, 3)).ok())
+            .collect();
+        Self {
+            kernel: system.kernel.clone(),
+            cpus_possible: possible,
+            cpus_online: crate::hotplug::read_online().unwrap_or_default(),
+            nr_cpus: nr_cpus.map_or(possible, |n| n as usize),
+            smt: system.smt,
+            nodes: system.nodes,
+            l2_groups: L2Topology::detect(possible).l2_groups,
+            l3_groups: L2Topology::from_shared_lists(&l3_lists).l2_groups,
+            governor: read_trimmed(Path::new(GOVERNOR_PATH)).filter(|g| !g.is_empty()),
+            mode,
+            reserved_cpus: None,
+            knobs,
+        }
+    }
+
+    fn pairs(&self) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = vec![
+            ("kernel", or_dash(&self.kernel).to_string()),
+            ("cpus_possible", self.cpus_possible.to_string()),
+            (
+                "cpus_online",
+                or_dash(&cpulist::format(&self.cpus_online)).to_string(),
+            ),
+            ("nr_cpus", self.nr_cpus.to_string()),
+            (
+                "smt",
+                match self.smt {
+                    Some(true) => "on",
+                    Some(false) => "off",
+                    None => NO_VALUE,
+                }
+                .to_string(),
+            ),
+            ("numa_nodes", self.nodes.to_string()),
+            ("l2_groups", format_groups(&self.l2_groups)),
+            ("l3_groups", format_groups(&self.l3_groups)),
+            (
+                "governor",
+                self.governor.as_deref().unwrap_or(NO_VALUE).to_string(),
+            ),
+            ("mode", self.mode.map_or(NO_VALUE, |m| m.mode).to_string()),
+            (
+                "lightweight",
+                self.mode
+                    .map_or(NO_VALUE, |m| if m.lightweight { "on" } else { "off" })
+                    .to_string(),
+            ),
+            (
+                "reserved_cpus",
+                self.reserved_cpus
+                    .as_deref()
+                    .map_or_else(|| NO_VALUE.to_string(), crate::reserved::describe),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        for (i, name) in KNOB_NAMES.iter().enumerate() {
+            out.push((
+                format!("knob.{}", name),
+                self.knobs
+                    .map_or_else(|| NO_VALUE.to_string(), |k| k[i].to_string()),
+            ));
+        }
+        out
+    }
+
+    // "[CONFIG] key=value", CONFIG_KEYS ORDER THEN THE KNOBS
+    pub fn config_lines(&self) -> Vec<String> {
+        self.pairs()
+            .into_iter()
+            .map(|(k, v)| format!("{} {}={}", CONFIG_PREFIX, k, v))
+            .collect()
+    }
+
+    // SAME KEYS; LISTS AS ARRAYS, knob.* AS ONE "knobs" OBJECT, `-` AS null
+    pub fn json(&self) -> Json {
+        let cpus = |c: &[u32]| Json::Arr(c.iter().map(|&c| Json::Num(c as f64)).collect());
+        let groups = |g: &[Vec<u32>]| Json::Arr(g.iter().map(|g| cpus(g)).collect());
+        let opt = |v: Option<&str>| v.map(Json::str).unwrap_or(Json::Null);
+        let kernel = (!self.kernel.is_empty()).then_some(self.kernel.as_str());
+        let knobs = match self.knobs {
+            Some(k) => Json::obj(
+                KNOB_NAMES
+                    .iter()
+                    .zip(k)
+                    .map(|(n, v)| (*n, Json::Num(v as f64)))
+                    .collect(),
+            ),
+            None => Json::Null,
+        };
+        Json::obj(vec![
+            ("kernel", opt(kernel)),
+            ("cpus_possible", Json::Num(self.cpus_possible as f64)),
+            ("cpus_online", cpus(&self.cpus_online)),
+            ("nr_cpus", Json::Num(self.nr_cpus as f64)),
+            ("smt", self.smt.map(Json::Bool).unwrap_or(Json::Null)),
+            ("numa_nodes", Json::Num(self.nodes as f64)),
+            ("l2_groups", groups(&self.l2_groups)),
+            ("l3_groups", groups(&self.l3_groups)),
+            ("governor", opt(self.governor.as_deref())),
+            ("mode", opt(self.mode.map(|m| m.mode))),
+            (
+                "lightweight",
+                self.mode.map_or(Json::Null, |m| Json::Bool(m.lightweight)),
+            ),
+            (
+                "reserved_cpus",
+                self.reserved_cpus.as_deref().map_or(Json::Null, cpus),
+            ),
+            ("knobs", knobs),
+        ])
+    }
+}
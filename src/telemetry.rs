@@ -0,0 +1,217 @@
+// PANDEMONIUM TELEMETRY LINE
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// ONE FORMAT FOR THE PER-TICK `d/s:` LINE OF BOTH MONITOR LOOPS:
+// adaptive.rs AND THE --no-adaptive BPF-ONLY LOOP IN main.rs. A
+// BENCHMARK'S BPF-ONLY AND FULL PHASES THEN PARSE WITH ONE REGEX SET.
+// EVERY COLUMN IS ALWAYS PRESENT, IN THE SAME ORDER; ONE THE BPF-ONLY LOOP
+// HAS NO VALUE FOR (P99, PROCDB, SLEEP, THE SOJOURN THRESHOLD) READS `-`.
+// ONLY THE OPT-IN COLUMNS COME AND GO, THE SAME WAY IN BOTH MODES:
+//   probe_p99   --self-probe
+//   slo         --slo-p99-us
+// THE LINE ENDS IN A TAG: [BPF ...] OR [<REGIME> ...] WITH BURST, LONGRUN,
+// PINNED, PAUSED FLAGS.
+// resid (SAMPLED IDLE RESIDENCY, residency.rs) SITS NEXT TO THE DISPATCH-
+// COUNTER idle; `-` UNTIL THE FIRST FULL TICK OF IDLE BITMAP SAMPLES.
+// rt (CAPACITY RT/DEADLINE TASKS TOOK, rtsteal.rs) FOLLOWS resid; `-` ON
+// THE FIRST TICK.
+// --lightweight (EITHER LOOP): BPF SAMPLES NO WAKEUPS, SO wake, p99,
+// lat_idle, lat_kick, obs AND sleep READ `-` AND THE TAG SAYS LITE.
+// gov (LAST) IS THE CPUFREQ GOVERNOR THIS TICK (governor.rs): ONE TOKEN,
+// a+b WHEN POLICIES DISAGREE, `-` WITHOUT cpufreq.
+
+use crate::stats::{DsqDepth, PandemoniumStats, StatsDelta};
+
+pub const NO_VALUE: &str = "-";
+pub const BPF_TAG: &str = "BPF";
+
+// COLUMNS ONLY THE ADAPTIVE LOOP FILLS
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveColumns {
+    pub p99_us: u64,
+    pub tier_p99_us: [u64; 3], // BATCH, INTERACTIVE, LAT_CRITICAL
+    pub procdb_total: usize,
+    pub procdb_confident: usize,
+    pub procdb_evicted: usize,
+    pub procdb_flushed: usize,
+    pub procdb_ingested: u64,
+    pub io_pct: u64,
+    pub sojourn_thresh_ms: u64,
+    pub regime: &'static str,
+    pub pinned: bool,
+    pub paused: bool,
+}
+
+// EVERYTHING THE STATS DELTA DOES NOT CARRY. KNOB VALUES ARE WHAT BPF
+// HOLDS NOW (read_tuning_knobs), IN BOTH MODES.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TelemetryExtras {
+    pub adaptive: Option<AdaptiveColumns>, // None: BPF-ONLY
+    pub probe_p99_us: Option<u64>,         // None: NO --self-probe
+    pub slo: Option<Option<bool>>,         // None: NO --slo-p99-us; Some(None): NO DATA
+    pub residency_pct: Option<u64>,        // None: NO IDLE BITMAP SAMPLES YET
+    pub rt_pct: Option<u64>,               // None: NO RT INTERVAL YET
+    pub slice_us: u64,
+    pub batch_us: u64,
+    pub cls_high: u64,
+    pub cls_low: u64,
+    pub qdepth: DsqDepth,
+    pub backlog: u64,
+    pub backlog_warn: bool,
+    pub lightweight: bool,
+    pub governor: Option<String>, // None: NOT READ (SAME `-` AS NO cpufreq)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TelemetryLine {
+    columns: Vec<(&'static str, String)>,
+    tag: String,
+}
+
+impl TelemetryLine {
+    pub fn names(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|(n, _)| *n).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.columns {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(name);
+            out.push_str(": ");
+            out.push_str(value);
+        }
+        out.push_str(&format!(" [{}]", self.tag));
+        out
+    }
+}
+
+fn or_dash<T>(v: Option<T>, f: impl FnOnce(T) -> String) -> String {
+    v.map_or_else(|| NO_VALUE.to_string(), f)
+}
+
+pub fn telemetry_tick(
+    delta: &StatsDelta,
+    stats: &PandemoniumStats,
+    extras: &TelemetryExtras,
+) -> TelemetryLine {
+    let a = extras.adaptive;
+    // WAKEUP-SAMPLED COLUMNS: NOTHING BEHIND THEM IN LIGHTWEIGHT MODE
+    let lite = extras.lightweight;
+    let sampled = |v: String| if lite { NO_VALUE.to_string() } else { v };
+    let sa = a.filter(|_| !lite);
+    let [l2_b, l2_i, l2_l] = delta.l2_hit_pct();
+    let [rt_b, rt_i, rt_l] = delta.tier_changes;
+    let mut columns: Vec<(&'static str, String)> = vec![
+        ("d/s", format!("{:<8}", delta.dispatches)),
+        ("idle", format!("{}%", delta.idle_pct())),
+        (
+            "resid",
+            or_dash(extras.residency_pct, |p| format!("{}%", p)),
+        ),
+        ("rt", or_dash(extras.rt_pct, |p| format!("{}%", p))),
+        ("shared", format!("{:<6}", delta.shared)),
+        ("preempt", format!("{:<4}", delta.preempt)),
+        ("keep", format!("{:<4}", delta.keep_running)),
+        (
+            "kick",
+            format!("H={:<4} S={:<4}", delta.hard_kicks, delta.soft_kicks),
+        ),
+        (
+            "enq",
+            format!("W={:<4} R={:<4}", delta.enq_wakeup, delta.enq_requeue),
+        ),
+        ("wake", sampled(format!("{}us", delta.wake_avg_us()))),
+        (
+            "p99",
+            or_dash(sa, |a| {
+                let [b, i, l] = a.tier_p99_us;
+                format!("{}us [B:{} I:{} L:{}]", a.p99_us, b, i, l)
+            }),
+        ),
+    ];
+    if let Some(us) = extras.probe_p99_us {
+        columns.push(("probe_p99", format!("{}us", us)));
+    }
+    if let Some(met) = extras.slo {
+        let v = match met {
+            None => NO_VALUE,
+            Some(true) => "ok",
+            Some(false) => "MISS",
+        };
+        columns.push(("slo", v.to_string()));
+    }
+    columns.extend([
+        ("lat_idle", sampled(format!("{}us", delta.lat_idle_us()))),
+        ("lat_kick", sampled(format!("{}us", delta.lat_kick_us()))),
+        (
+            "procdb",
+            or_dash(a, |a| format!("{}/{}", a.procdb_total, a.procdb_confident)),
+        ),
+        ("evict", or_dash(a, |a| a.procdb_evicted.to_string())),
+        ("flush", or_dash(a, |a| a.procdb_flushed.to_string())),
+        ("obs", or_dash(sa, |a| a.procdb_ingested.to_string())),
+        ("hits", delta.procdb_hits.to_string()),
+        ("sleep", or_dash(sa, |a| format!("io={}%", a.io_pct))),
+        ("slice", format!("{}us", extras.slice_us)),
+        ("batch", format!("{}us", extras.batch_us)),
+        ("reenq", delta.reenqueue.to_string()),
+        (
+            "sjrn",
+            format!(
+                "{}ms/{}",
+                stats.batch_sojourn_ns / 1_000_000,
+                or_dash(a, |a| format!("{}ms", a.sojourn_thresh_ms))
+            ),
+        ),
+        ("rescue", delta.overflow_rescue.to_string()),
+        (
+            "qdepth",
+            format!("I={} B={}", extras.qdepth.interactive, extras.qdepth.batch),
+        ),
+        (
+            "backlog",
+            format!(
+                "{}{}",
+                extras.backlog,
+                if extras.backlog_warn { "[!]" } else { "" }
+            ),
+        ),
+        ("l2", format!("B={}% I={}% L={}%", l2_b, l2_i, l2_l)),
+        ("retier", format!("B={} I={} L={}", rt_b, rt_i, rt_l)),
+        ("cls", format!("H={} L={}", extras.cls_high, extras.cls_low)),
+        ("gov", or_dash(extras.governor.as_deref(), str::to_string)),
+    ]);
+
+    let mut tag = a.map_or(BPF_TAG, |a| a.regime).to_string();
+    if delta.burst() {
+        tag.push_str(" BURST");
+    }
+    if stats.longrun_mode_active > 0 {
+        tag.push_str(" LONGRUN");
+    }
+    if lite {
+        tag.push_str(" LITE");
+    }
+    if let Some(a) = a {
+        if a.paused {
+            tag.push_str(" PAUSED");
+        } else if a.pinned {
+            tag.push_str(" PINNED");
+        }
+    }
+    TelemetryLine { columns, tag }
+}
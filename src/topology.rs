@@ -7,59 +7,23 @@
 
 use anyhow::Result;
 
+use pandemonium::l2topo::{L2Topology, MAX_L2_SIBLINGS};
+
 use crate::scheduler::Scheduler;
 
-pub struct CpuTopology {
-    pub nr_cpus: usize,
-    pub l2_domain: Vec<u32>,      // l2_domain[cpu] = group_id
-    pub l2_groups: Vec<Vec<u32>>, // l2_groups[group_id] = [cpu, ...]
-}
+// GROUPING LIVES IN pandemonium::l2topo (SHARED WITH `pandemonium topology`);
+// THIS WRAPPER OWNS THE BPF MAP WRITES
+pub struct CpuTopology(pub L2Topology);
 
 impl CpuTopology {
     pub fn detect(nr_cpus: usize) -> Result<Self> {
-        let mut l2_domain = vec![0u32; nr_cpus];
-        let mut seen_groups: Vec<Vec<u32>> = Vec::new();
-
-        for cpu in 0..nr_cpus {
-            let path = format!(
-                "/sys/devices/system/cpu/cpu{}/cache/index2/shared_cpu_list",
-                cpu
-            );
-            let content = match std::fs::read_to_string(&path) {
-                Ok(s) => s,
-                Err(_) => {
-                    // CPU MIGHT BE OFFLINE OR HAVE NO L2 INFO -- ASSIGN OWN GROUP
-                    l2_domain[cpu] = cpu as u32;
-                    continue;
-                }
-            };
-
-            let members = parse_cpu_list(content.trim());
-
-            // CHECK IF THIS GROUP ALREADY EXISTS
-            let group_id = match seen_groups.iter().position(|g| *g == members) {
-                Some(id) => id as u32,
-                None => {
-                    let id = seen_groups.len() as u32;
-                    seen_groups.push(members.clone());
-                    id
-                }
-            };
-
-            l2_domain[cpu] = group_id;
-        }
-
-        Ok(Self {
-            nr_cpus,
-            l2_domain,
-            l2_groups: seen_groups,
-        })
+        Ok(Self(L2Topology::detect(nr_cpus)))
     }
 
     // WRITE L2 DOMAIN MAP TO BPF ARRAY VIA SCHEDULER
     pub fn populate_bpf_map(&self, sched: &Scheduler) -> Result<()> {
-        for cpu in 0..self.nr_cpus {
-            sched.write_cache_domain(cpu as u32, self.l2_domain[cpu])?;
+        for cpu in 0..self.0.nr_cpus {
+            sched.write_cache_domain(cpu as u32, self.0.l2_domain[cpu])?;
         }
         Ok(())
     }
@@ -67,8 +31,7 @@ impl CpuTopology {
     // WRITE L2 SIBLINGS FLAT ARRAY TO BPF MAP
     // l2_siblings[group_id * 8 + slot] = cpu_id, SENTINEL u32::MAX MARKS END
     pub fn populate_l2_siblings_map(&self, sched: &Scheduler) -> Result<()> {
-        const MAX_L2_SIBLINGS: usize = 8;
-        for (gid, members) in self.l2_groups.iter().enumerate() {
+        for (gid, members) in self.0.l2_groups.iter().enumerate() {
             for (slot, &cpu) in members.iter().enumerate().take(MAX_L2_SIBLINGS) {
                 sched.write_l2_sibling(gid as u32, slot as u32, cpu)?;
             }
@@ -79,45 +42,36 @@ impl CpuTopology {
         Ok(())
     }
 
-    pub fn log_summary(&self) {
-        for (gid, members) in self.l2_groups.iter().enumerate() {
-            let cpus: Vec<String> = members.iter().map(|c| c.to_string()).collect();
-            log_info!("L2 GROUP {}: [{}]", gid, cpus.join(","));
+    // DETECT + WRITE BOTH MAPS. STARTUP AND CPU HOTPLUG SHARE THIS PATH;
+    // OFFLINE CPUS DROP OUT OF THE SURVIVORS' shared_cpu_list, RESERVED
+    // CPUS OUT OF EVERY GROUP.
+    pub fn apply(sched: &Scheduler, nr_cpus: usize) {
+        match Self::detect(nr_cpus) {
+            Ok(CpuTopology(topo)) => {
+                let topo = CpuTopology(topo.without(&sched.reserved));
+                topo.log_summary();
+                if let Err(e) = topo.populate_bpf_map(sched) {
+                    log_warn!("CACHE TOPOLOGY MAP WRITE FAILED: {}", e);
+                }
+                if let Err(e) = topo.populate_l2_siblings_map(sched) {
+                    log_warn!("L2 SIBLINGS MAP WRITE FAILED: {}", e);
+                }
+            }
+            Err(e) => log_warn!("CACHE TOPOLOGY DETECT FAILED: {}", e),
         }
-        log_info!(
-            "L2 GROUPS: {} across {} CPUs",
-            self.l2_groups.len(),
-            self.nr_cpus
-        );
     }
-}
 
-// PARSE KERNEL CPU LIST FORMAT: "0,6" or "0-2,6-8" or "3"
-fn parse_cpu_list(s: &str) -> Vec<u32> {
-    let mut result = Vec::new();
-    for part in s.split(',') {
-        let part = part.trim();
-        if part.is_empty() {
-            continue;
-        }
-        if let Some((start, end)) = part.split_once('-') {
-            if let (Ok(s), Ok(e)) = (start.parse::<u32>(), end.parse::<u32>()) {
-                for cpu in s..=e {
-                    result.push(cpu);
-                }
-            }
-        } else if let Ok(cpu) = part.parse::<u32>() {
-            result.push(cpu);
+    pub fn log_summary(&self) {
+        for line in self.0.summary_lines() {
+            log_info!("{}", line);
         }
     }
-    result.sort();
-    result.dedup();
-    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pandemonium::hotplug::parse_cpu_list;
 
     #[test]
     fn parse_single() {
@@ -163,7 +117,7 @@ mod tests {
             return; // NO CPUS VISIBLE (CONTAINER?)
         }
 
-        let topo = CpuTopology::detect(nr_cpus).unwrap();
+        let CpuTopology(topo) = CpuTopology::detect(nr_cpus).unwrap();
         assert_eq!(topo.nr_cpus, nr_cpus);
         assert_eq!(topo.l2_domain.len(), nr_cpus);
 
@@ -1,6 +1,13 @@
 // PANDEMONIUM TUNING TYPES
 // PURE-RUST MODULE: ZERO BPF DEPENDENCIES
 // SHARED BETWEEN BINARY CRATE (scheduler.rs, adaptive.rs) AND LIB CRATE (tests)
+//
+// SINGLE SOURCE OF TRUTH FOR REGIME PROFILES AND THE KNOB ABI. NO OTHER
+// MODULE MAY DECLARE SLICE/PREEMPT/LAG/BATCH CONSTANTS OR ITS OWN Regime:
+// THE TESTS WOULD CHECK ONE SET OF NUMBERS WHILE THE BINARY RAN ANOTHER.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
 
 // REGIME THRESHOLDS (SCHMITT TRIGGER)
 // DIRECTIONAL HYSTERESIS PREVENTS OSCILLATION AT REGIME BOUNDARIES.
@@ -31,6 +38,10 @@ const HEAVY_PREEMPT_NS: u64 = 2_000_000; // 2MS: SLIGHTLY RELAXED
 const HEAVY_LAG_SCALE: u64 = 2;
 const HEAVY_BATCH_NS: u64 = 20_000_000; // 20MS: LET BATCH RIP
 
+// REGIME-INDEPENDENT STARTING POINTS (RUST OVERWRITES BOTH AT RUNTIME)
+const DEFAULT_SOJOURN_THRESH_NS: u64 = 5_000_000; // 5MS
+const DEFAULT_BURST_SLICE_NS: u64 = 1_000_000; // 1MS
+
 // P99 CEILINGS
 
 const LIGHT_P99_CEIL_NS: u64 = 3_000_000; // 3MS
@@ -60,7 +71,7 @@ pub const AFFINITY_WEAK: u64 = 1;
 pub const AFFINITY_STRONG: u64 = 2;
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct TuningKnobs {
     pub slice_ns: u64,
     pub preempt_thresh_ns: u64,
@@ -74,19 +85,80 @@ pub struct TuningKnobs {
     pub burst_slice_ns: u64,
 }
 
+// COMPILE-TIME ABI SAFETY: 10 x u64, ONE NAME PER FIELD
+const _: () = assert!(std::mem::size_of::<TuningKnobs>() == 80);
+const _: () = assert!(KNOB_NAMES.len() * 8 == std::mem::size_of::<TuningKnobs>());
+
+impl TuningKnobs {
+    // DECODE THE tuning_knobs_map VALUE; None UNLESS THE SIZE MATCHES EXACTLY
+    pub fn from_bytes(b: &[u8]) -> Option<Self> {
+        if b.len() != std::mem::size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { std::ptr::read_unaligned(b.as_ptr() as *const Self) })
+    }
+}
+
+// MIRRORS THE BPF FALLBACKS WHEN THE MAP IS UNREADABLE (main.bpf.c):
+// THE MIXED PROFILE WITH L2 AFFINITY OFF
 impl Default for TuningKnobs {
     fn default() -> Self {
         Self {
-            slice_ns: 1_000_000,
-            preempt_thresh_ns: 1_000_000,
-            lag_scale: 4,
-            batch_slice_ns: 20_000_000,
-            cpu_bound_thresh_ns: MIXED_DEMOTION_NS,
-            lat_cri_thresh_high: DEFAULT_LAT_CRI_THRESH_HIGH,
-            lat_cri_thresh_low: DEFAULT_LAT_CRI_THRESH_LOW,
             affinity_mode: AFFINITY_OFF,
-            sojourn_thresh_ns: 5_000_000,
-            burst_slice_ns: 1_000_000,
+            ..regime_knobs(Regime::Mixed)
+        }
+    }
+}
+
+// KNOB NAMES AS EXPOSED BY THE CONTROL SOCKET (FIELD NAMES, STRUCT ORDER)
+pub const KNOB_NAMES: [&str; 10] = [
+    "slice_ns",
+    "preempt_thresh_ns",
+    "lag_scale",
+    "batch_slice_ns",
+    "cpu_bound_thresh_ns",
+    "lat_cri_thresh_high",
+    "lat_cri_thresh_low",
+    "affinity_mode",
+    "sojourn_thresh_ns",
+    "burst_slice_ns",
+];
+
+impl TuningKnobs {
+    fn field_mut(&mut self, name: &str) -> Option<&mut u64> {
+        Some(match name {
+            "slice_ns" => &mut self.slice_ns,
+            "preempt_thresh_ns" => &mut self.preempt_thresh_ns,
+            "lag_scale" => &mut self.lag_scale,
+            "batch_slice_ns" => &mut self.batch_slice_ns,
+            "cpu_bound_thresh_ns" => &mut self.cpu_bound_thresh_ns,
+            "lat_cri_thresh_high" => &mut self.lat_cri_thresh_high,
+            "lat_cri_thresh_low" => &mut self.lat_cri_thresh_low,
+            "affinity_mode" => &mut self.affinity_mode,
+            "sojourn_thresh_ns" => &mut self.sojourn_thresh_ns,
+            "burst_slice_ns" => &mut self.burst_slice_ns,
+            _ => return None,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        let mut copy = *self;
+        copy.field_mut(name).map(|v| *v)
+    }
+
+    // VALUES IN KNOB_NAMES ORDER
+    pub fn values(&self) -> [u64; 10] {
+        KNOB_NAMES.map(|n| self.get(n).unwrap_or(0))
+    }
+
+    // FALSE ON UNKNOWN NAME
+    pub fn set(&mut self, name: &str, value: u64) -> bool {
+        match self.field_mut(name) {
+            Some(v) => {
+                *v = value;
+                true
+            }
+            None => false,
         }
     }
 }
@@ -110,6 +182,16 @@ impl Regime {
         }
     }
 
+    // CASE-INSENSITIVE INVERSE OF label()
+    pub fn from_label(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "LIGHT" => Some(Self::Light),
+            "MIXED" => Some(Self::Mixed),
+            "HEAVY" => Some(Self::Heavy),
+            _ => None,
+        }
+    }
+
     pub fn p99_ceiling(self) -> u64 {
         match self {
             Self::Light => LIGHT_P99_CEIL_NS,
@@ -121,7 +203,7 @@ impl Regime {
 
 // REGIME KNOBS
 
-pub fn regime_knobs(r: Regime) -> TuningKnobs {
+pub const fn regime_knobs(r: Regime) -> TuningKnobs {
     match r {
         Regime::Light => TuningKnobs {
             slice_ns: LIGHT_SLICE_NS,
@@ -132,8 +214,8 @@ pub fn regime_knobs(r: Regime) -> TuningKnobs {
             lat_cri_thresh_high: DEFAULT_LAT_CRI_THRESH_HIGH,
             lat_cri_thresh_low: DEFAULT_LAT_CRI_THRESH_LOW,
             affinity_mode: AFFINITY_WEAK,
-            sojourn_thresh_ns: 5_000_000,
-            burst_slice_ns: 1_000_000,
+            sojourn_thresh_ns: DEFAULT_SOJOURN_THRESH_NS,
+            burst_slice_ns: DEFAULT_BURST_SLICE_NS,
         },
         Regime::Mixed => TuningKnobs {
             slice_ns: MIXED_SLICE_NS,
@@ -144,8 +226,8 @@ pub fn regime_knobs(r: Regime) -> TuningKnobs {
             lat_cri_thresh_high: DEFAULT_LAT_CRI_THRESH_HIGH,
             lat_cri_thresh_low: DEFAULT_LAT_CRI_THRESH_LOW,
             affinity_mode: AFFINITY_STRONG,
-            sojourn_thresh_ns: 5_000_000,
-            burst_slice_ns: 1_000_000,
+            sojourn_thresh_ns: DEFAULT_SOJOURN_THRESH_NS,
+            burst_slice_ns: DEFAULT_BURST_SLICE_NS,
         },
         Regime::Heavy => TuningKnobs {
             slice_ns: HEAVY_SLICE_NS,
@@ -156,8 +238,8 @@ pub fn regime_knobs(r: Regime) -> TuningKnobs {
             lat_cri_thresh_high: DEFAULT_LAT_CRI_THRESH_HIGH,
             lat_cri_thresh_low: DEFAULT_LAT_CRI_THRESH_LOW,
             affinity_mode: AFFINITY_WEAK,
-            sojourn_thresh_ns: 5_000_000,
-            burst_slice_ns: 1_000_000,
+            sojourn_thresh_ns: DEFAULT_SOJOURN_THRESH_NS,
+            burst_slice_ns: DEFAULT_BURST_SLICE_NS,
         },
     }
 }
@@ -222,6 +304,112 @@ pub fn detect_regime(current: Regime, idle_pct: u64) -> Regime {
     }
 }
 
+// IDLE PERCENTAGE FOR detect_regime(): THE DISPATCH-COUNTER SHARE ALONE,
+// OR ITS MEAN WITH SAMPLED IDLE RESIDENCY (residency.rs) WHEN THERE IS ONE.
+// DISPATCH COUNTS SEE ONLY BUSY MOMENTS; RESIDENCY SEES PARKED CPUS BUT NOT
+// WHETHER WAKEUPS FIND THEM. EACH PULLS THE OTHER HALFWAY.
+pub fn regime_idle_signal(dispatch_idle_pct: u64, residency_pct: Option<u64>) -> u64 {
+    residency_pct.map_or(dispatch_idle_pct, |r| (dispatch_idle_pct + r.min(100)) / 2)
+}
+
+// THE IDLE SIGNAL ONCE RT/DEADLINE TASKS TOOK THEIR SHARE (rtsteal.rs).
+// THOSE CLASSES RUN OUTSIDE sched_ext: THE DISPATCH COUNTERS NEVER SEE
+// THEM, SO A CPU AN AUDIO THREAD HOLDS CAN STILL READ AS IDLE. RT TIME UP
+// TO THE MEASURED BUSY SHARE IS ALREADY OUT OF THE SIGNAL (RESIDENCY SEES
+// THOSE CPUS BUSY); ONLY THE EXCESS COMES OFF, SO IDLE NEVER EXCEEDS THE
+// CAPACITY LEFT. NO READING, OR rt = 0: THE SIGNAL PASSES THROUGH.
+pub fn rt_adjusted_idle(idle_pct: u64, rt_pct: Option<u64>) -> u64 {
+    rt_pct.map_or(idle_pct, |rt| idle_pct.min(100 - rt.min(100)))
+}
+
+// PAUSE GATING
+// WHICH ADAPTIVE DECISIONS RUN THIS TICK. PAUSED FREEZES EVERY KNOB WRITE
+// THE LOOP WOULD MAKE ON ITS OWN; TELEMETRY AND PROCDB KEEP FLOWING.
+// PINNED ONLY TURNS OFF REGIME DETECTION.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TickDecisions {
+    pub detect_regime: bool,
+    pub tighten: bool,
+    pub relax: bool,
+    pub batch_tune: bool,
+}
+
+pub fn tick_decisions(paused: bool, pinned: bool) -> TickDecisions {
+    TickDecisions {
+        detect_regime: !paused && !pinned,
+        tighten: !paused,
+        relax: !paused,
+        batch_tune: !paused,
+    }
+}
+
+// --lightweight: BPF TAKES NO WAKEUP SAMPLES, SO THE P99 IS ALWAYS 0.
+// REGIME DETECTION NEEDS ONLY IDLE% AND QUEUE DEPTH AND KEEPS RUNNING;
+// THE REFLEX HAS NO SPIKE TO REACT TO AND NEVER TIGHTENS.
+impl TickDecisions {
+    pub fn lightweight(self, on: bool) -> Self {
+        Self {
+            tighten: self.tighten && !on,
+            ..self
+        }
+    }
+}
+
+// ON RESUME, TRUST THE MAP, NOT THE STATE FROM BEFORE THE PAUSE:
+// A SLICE BELOW THE REGIME BASELINE MEANS A TIGHTEN IS STILL IN EFFECT.
+pub fn resync_tightened(current: &TuningKnobs, baseline: &TuningKnobs) -> bool {
+    current.slice_ns < baseline.slice_ns
+}
+
+// SUSPEND/RESUME
+// CLOCK_MONOTONIC STOPS WHILE THE MACHINE IS SUSPENDED, CLOCK_BOOTTIME
+// KEEPS COUNTING. A TICK WHERE BOOTTIME OUTRAN MONOTONIC BY MORE THAN
+// RESUME_GAP_NS SPANNED A SUSPEND: ITS DELTAS AND HISTOGRAMS MIX PRE- AND
+// POST-RESUME STATE AND MUST NOT DRIVE DECISIONS.
+
+pub const RESUME_GAP_NS: u64 = 2_000_000_000;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct ClockSample {
+    pub mono_ns: u64,
+    pub boot_ns: u64,
+}
+
+fn clock_ns(clock: libc::clockid_t) -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe {
+        libc::clock_gettime(clock, &mut ts);
+    }
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+impl ClockSample {
+    pub fn now() -> Self {
+        Self {
+            mono_ns: clock_ns(libc::CLOCK_MONOTONIC),
+            boot_ns: clock_ns(libc::CLOCK_BOOTTIME),
+        }
+    }
+}
+
+// Some(SUSPENDED NS) WHEN THE INTERVAL prev -> cur SPANNED A SUSPEND
+pub fn suspend_gap_ns(prev: ClockSample, cur: ClockSample) -> Option<u64> {
+    let mono = cur.mono_ns.saturating_sub(prev.mono_ns);
+    let boot = cur.boot_ns.saturating_sub(prev.boot_ns);
+    let gap = boot.saturating_sub(mono);
+    (gap > RESUME_GAP_NS).then_some(gap)
+}
+
+// --duration: BOTH MONITOR LOOPS CHECK AT THE TOP OF EACH TICK, SO A RUN
+// ENDS AT MOST ONE TICK PAST ITS DEADLINE. None RUNS UNTIL CTRL+C.
+pub fn deadline_reached(deadline: Option<Instant>, now: Instant) -> bool {
+    deadline.is_some_and(|d| now >= d)
+}
+
 // STABILITY MODE
 
 pub const STABILITY_THRESHOLD: u32 = 10; // CONSECUTIVE STABLE TICKS BEFORE HIBERNATE
@@ -241,11 +429,89 @@ pub fn compute_stability_score(
 
 // TELEMETRY GATING
 
-pub fn should_print_telemetry(tick_counter: u64, stability_score: u32) -> bool {
-    if stability_score >= STABILITY_THRESHOLD {
-        tick_counter % 2 == 0
+// ONE LINE EVERY interval TICKS (--telemetry-interval, 1 = EVERY TICK).
+// STABILITY HIBERNATION DOUBLES THE INTERVAL.
+pub fn should_print_telemetry(tick_counter: u64, stability_score: u32, interval: u64) -> bool {
+    let every = if stability_score >= STABILITY_THRESHOLD {
+        interval.max(1) * 2
     } else {
-        true
+        interval.max(1)
+    };
+    tick_counter.is_multiple_of(every)
+}
+
+// IDLE STRETCH
+//
+// A FULLY IDLE MACHINE (LIGHT, STABILITY MAXED, UNDER IDLE_DISPATCH_RATE)
+// NEEDS NO 1S LOOP WITH 10 IDLE-BITMAP SAMPLES: AFTER IDLE_STRETCH_AFTER_NS
+// OF SUCH TICKS THE TICK STRETCHES TO STRETCHED_TICK, SAMPLED ONCE PER
+// STRETCHED_STEP. EACH STEP READS THE DISPATCH COUNT (THERE IS NO BPF
+// RINGBUF TO WAKE ON), AND THE FIRST STEP OVER THE RATE, OR A TICK THAT IS
+// NOT QUIET, SNAPS BACK TO 1S TICKS AT ONCE. THE QUIET TIME THEN RESTARTS.
+
+pub const IDLE_DISPATCH_RATE: u64 = 500; // PER SECOND, WHOLE MACHINE
+pub const IDLE_STRETCH_AFTER_NS: u64 = 60_000_000_000;
+pub const STRETCHED_TICK: Duration = Duration::from_secs(5);
+pub const STRETCHED_STEP: Duration = Duration::from_secs(1);
+
+// A TICK THAT COUNTS TOWARD THE STRETCH
+pub fn idle_quiet(regime: Regime, stability_score: u32, dispatches: u64, elapsed_ns: u64) -> bool {
+    regime == Regime::Light
+        && stability_score >= STABILITY_THRESHOLD
+        && elapsed_ns > 0
+        && (dispatches as u128 * 1_000_000_000) < IDLE_DISPATCH_RATE as u128 * elapsed_ns as u128
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IdleStretch {
+    quiet_ns: u64,
+    stretched: bool,
+}
+
+impl IdleStretch {
+    pub fn stretched(&self) -> bool {
+        self.stretched
+    }
+
+    // THE NEXT TICK'S SLEEP AND SAMPLE COUNT; normal WHEN NOT STRETCHED
+    pub fn window(&self, normal: (Duration, u32)) -> (Duration, u32) {
+        if self.stretched {
+            let steps = STRETCHED_TICK.as_nanos() / STRETCHED_STEP.as_nanos();
+            (STRETCHED_TICK, steps as u32)
+        } else {
+            normal
+        }
+    }
+
+    // END OF A TICK. true: THE STRETCH STARTED OR ENDED ON IT
+    pub fn tick(&mut self, quiet: bool, elapsed_ns: u64) -> bool {
+        let was = self.stretched;
+        if quiet {
+            self.quiet_ns = self.quiet_ns.saturating_add(elapsed_ns);
+            self.stretched = self.quiet_ns >= IDLE_STRETCH_AFTER_NS;
+        } else {
+            self.snap();
+        }
+        self.stretched != was
+    }
+
+    // ONE STEP OF A STRETCHED TICK. true: SNAPPED, END THE TICK NOW
+    pub fn step(&mut self, dispatches: u64, step_ns: u64) -> bool {
+        if !self.stretched {
+            return false;
+        }
+        let busy = step_ns > 0
+            && (dispatches as u128 * 1_000_000_000) >= IDLE_DISPATCH_RATE as u128 * step_ns as u128;
+        if busy {
+            self.snap();
+        }
+        busy
+    }
+
+    // ANYTHING ELSE THAT NEEDS THE 1S LOOP (A ctl REQUEST, CTRL+C)
+    pub fn snap(&mut self) {
+        self.quiet_ns = 0;
+        self.stretched = false;
     }
 }
 
@@ -285,12 +551,455 @@ pub fn compute_p99_from_histogram(counts: &[u64; HIST_BUCKETS]) -> u64 {
     HIST_EDGES_NS[HIST_BUCKETS - 2]
 }
 
+// P99 OVER ALL THREE TIERS POOLED
+pub fn pooled_p99_ns(tiers: &[[u64; HIST_BUCKETS]; 3]) -> u64 {
+    let pooled: [u64; HIST_BUCKETS] = std::array::from_fn(|b| tiers.iter().map(|t| t[b]).sum());
+    compute_p99_from_histogram(&pooled)
+}
+
+// APPLIED LAG HISTOGRAM (lag_hist IN main.bpf.c)
+// task_deadline() SCALES lag_scale BY WAKEUP FREQUENCY AND QUEUE DEPTH,
+// THEN CLAMPS VTIME TO vtime_now - 40MS * SCALE. THE KNOB IS ONE INPUT:
+// THIS IS WHAT ACTUALLY GOT APPLIED, AND HOW OFTEN THE FLOOR BIT.
+
+pub const LAG_HIST_BUCKETS: usize = 7; // LOG2: 1, 2-3, 4-7, ..., 32-63, 64
+pub const LAG_HIST_KEYS: usize = 8; // + FLOOR CLAMP COUNTER
+pub const LAG_CLAMP_KEY: usize = 7;
+
+// --lag-scale RUN-LEVEL OVERRIDE BOUNDS
+pub const MIN_LAG_SCALE: u64 = 1;
+pub const MAX_LAG_SCALE: u64 = 16;
+
+// MATCHES lag_bucket() IN main.bpf.c
+pub fn lag_bucket(scale: u64) -> usize {
+    ((63 - scale.max(1).leading_zeros()) as usize).min(LAG_HIST_BUCKETS - 1)
+}
+
+// "1", "2-3", ..., "64"
+pub fn lag_bucket_label(bucket: usize) -> String {
+    let lo = 1u64 << bucket;
+    if bucket == 0 || bucket == LAG_HIST_BUCKETS - 1 {
+        lo.to_string()
+    } else {
+        format!("{}-{}", lo, lo * 2 - 1)
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct LagHist {
+    pub counts: [u64; LAG_HIST_BUCKETS],
+    pub clamped: u64,
+}
+
+impl LagHist {
+    pub fn from_keys(keys: &[u64; LAG_HIST_KEYS]) -> Self {
+        Self {
+            counts: std::array::from_fn(|b| keys[b]),
+            clamped: keys[LAG_CLAMP_KEY],
+        }
+    }
+
+    // CUMULATIVE -> INTERVAL. A COUNTER GOING BACKWARDS MEANS BPF RESTARTED
+    // AND THE MAP WAS ZEROED: EVERYTHING IN self IS NEW.
+    pub fn delta(&self, prev: &LagHist) -> LagHist {
+        let backwards =
+            self.clamped < prev.clamped || self.counts.iter().zip(&prev.counts).any(|(c, p)| c < p);
+        if backwards {
+            return *self;
+        }
+        LagHist {
+            counts: std::array::from_fn(|b| self.counts[b] - prev.counts[b]),
+            clamped: self.clamped - prev.clamped,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // LOWER EDGE OF THE BUCKET HOLDING THE pct-TH PERCENTILE, 0 WHEN EMPTY
+    pub fn percentile(&self, pct: u64) -> u64 {
+        let total = self.total();
+        if total == 0 {
+            return 0;
+        }
+        let threshold = (total * pct).div_ceil(100).max(1);
+        let mut cumulative = 0;
+        for (b, &c) in self.counts.iter().enumerate() {
+            cumulative += c;
+            if cumulative >= threshold {
+                return 1 << b;
+            }
+        }
+        1 << (LAG_HIST_BUCKETS - 1)
+    }
+
+    pub fn max(&self) -> u64 {
+        self.counts
+            .iter()
+            .rposition(|&c| c > 0)
+            .map_or(0, |b| 1 << b)
+    }
+
+    // SHARE OF DEADLINES WHERE THE VTIME FLOOR MOVED THE TASK
+    pub fn clamp_pct(&self) -> u64 {
+        (self.clamped * 100).checked_div(self.total()).unwrap_or(0)
+    }
+}
+
 // REFLEX TIGHTEN DECISION: USES BOTH AGGREGATE AND INTERACTIVE P99.
 // TIGHTEN IF EITHER EXCEEDS CEILING (INTERACTIVE STARVATION HIDDEN IN AGGREGATE).
 pub fn should_reflex_tighten(aggregate_p99: u64, interactive_p99: u64, ceiling: u64) -> bool {
     aggregate_p99 > ceiling || interactive_p99 > ceiling
 }
 
+// SLICE GUARDRAILS
+// ONE LEGAL SLICE RANGE FOR BOTH SIDES. WRITTEN TO RODATA BEFORE LOAD
+// (task_slice() CLAMPS EVERY SLICE INTO IT), APPLIED TO EVERY KNOB WRITE,
+// AND USED BY THE REFLEX AS ITS RELAX CEILING. THE REFLEX KEEPS ITS OWN
+// 500US TIGHTEN FLOOR; A HIGHER --slice-min-us RAISES IT.
+// --slice-min-us / --slice-max-us OVERRIDE THE DEFAULTS.
+
+pub const MIN_SLICE_NS: u64 = 100_000; // 100US: DEFAULT FLOOR (task_slice())
+pub const REFLEX_FLOOR_NS: u64 = 500_000; // 500US: REFLEX TIGHTEN FLOOR
+pub const MAX_SLICE_NS: u64 = BATCH_MAX_NS; // 25MS: DEFAULT CEILING (IO-EXTENDED BATCH)
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SliceBounds {
+    pub min_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Default for SliceBounds {
+    fn default() -> Self {
+        Self {
+            min_ns: MIN_SLICE_NS,
+            max_ns: MAX_SLICE_NS,
+        }
+    }
+}
+
+impl SliceBounds {
+    pub fn from_us(min_us: u64, max_us: u64) -> Result<Self, String> {
+        let bounds = Self {
+            min_ns: min_us.saturating_mul(1000),
+            max_ns: max_us.saturating_mul(1000),
+        };
+        bounds.validate()?;
+        Ok(bounds)
+    }
+
+    // MIN < DEFAULT < MAX: THE DEFAULT SLICE (MIXED PROFILE, ALSO THE BPF
+    // FALLBACK) MUST BE LEGAL, OR THE FIRST KNOB WRITE IS ALREADY CLAMPED
+    pub fn validate(&self) -> Result<(), String> {
+        let default = TuningKnobs::default().slice_ns;
+        if self.min_ns == 0 || self.min_ns >= default || default >= self.max_ns {
+            return Err(format!(
+                "SLICE BOUNDS MUST SATISFY 0 < --slice-min-us ({}) < DEFAULT ({}) < --slice-max-us ({})",
+                self.min_ns / 1000,
+                default / 1000,
+                self.max_ns / 1000
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn clamp(&self, slice_ns: u64) -> u64 {
+        slice_ns.clamp(self.min_ns, self.max_ns)
+    }
+
+    // EVERY SLICE-VALUED KNOB. preempt_thresh_ns IS A RUNTIME, NOT A SLICE.
+    pub fn clamp_knobs(&self, knobs: &mut TuningKnobs) {
+        knobs.slice_ns = self.clamp(knobs.slice_ns);
+        knobs.batch_slice_ns = self.clamp(knobs.batch_slice_ns);
+        knobs.burst_slice_ns = self.clamp(knobs.burst_slice_ns);
+    }
+}
+
+// DIFFERENTIAL KNOB WRITES
+// EVERY tuning_knobs MAP WRITE GOES THROUGH ONE KnobWriter. A WRITE OF THE
+// VALUE ALREADY IN THE MAP IS SKIPPED (EACH WRITE IS A SYSCALL). THE
+// MONITOR THREAD IS THE ONLY WRITER (REFLEX AND CTL RUN INSIDE ITS LOOP),
+// SO PLAIN Cells ARE ENOUGH: THERE IS NO SECOND WRITER TO RACE.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KnobWrite {
+    Written,
+    Skipped,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct KnobWriteCounts {
+    pub written: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct KnobWriter {
+    last: Cell<Option<TuningKnobs>>,
+    counts: Cell<KnobWriteCounts>,
+}
+
+impl KnobWriter {
+    // store() RUNS ONLY WHEN THE VALUE DIFFERS FROM THE LAST ONE WRITTEN.
+    // A FAILED store() LEAVES THE CACHE UNTOUCHED.
+    pub fn write(
+        &self,
+        knobs: &TuningKnobs,
+        store: impl FnOnce(&TuningKnobs) -> anyhow::Result<()>,
+    ) -> anyhow::Result<KnobWrite> {
+        let mut counts = self.counts.get();
+        let result = if self.last.get() == Some(*knobs) {
+            counts.skipped += 1;
+            KnobWrite::Skipped
+        } else {
+            store(knobs)?;
+            self.last.set(Some(*knobs));
+            counts.written += 1;
+            KnobWrite::Written
+        };
+        self.counts.set(counts);
+        Ok(result)
+    }
+
+    pub fn counts(&self) -> KnobWriteCounts {
+        self.counts.get()
+    }
+
+    // WHAT THE MAP HOLDS, WITHOUT THE lookup SYSCALL. EVERY MAP WRITE GOES
+    // THROUGH HERE AND BPF WRITES THE KNOBS ONLY AT init, SO ONCE ONE WRITE
+    // HAS LANDED THE CACHE IS AUTHORITATIVE (THE SKIP ABOVE ALREADY TRUSTS
+    // IT). None BEFORE THE FIRST WRITE.
+    pub fn cached(&self) -> Option<TuningKnobs> {
+        self.last.get()
+    }
+}
+
+// REFLEX: P99 SPIKE TIGHTEN + GRADUATED RELAX
+// ONE STATE MACHINE FOR THE MONITOR LOOP AND THE TESTS. TIGHTEN STOPS AT
+// REFLEX_FLOOR_NS OR bounds.min_ns, WHICHEVER IS HIGHER, RELAX AT THE
+// BASELINE SLICE OR bounds.max_ns, WHICHEVER IS LOWER.
+//
+// OWNERSHIP: THE REFLEX NEVER READS OR WRITES THE MAP. IT KEEPS ONE
+// REQUESTED slice_ns (request()); THE MONITOR IS THE ONLY MAP WRITER AND
+// FOLDS THE REQUEST INTO EVERY WRITE WITH merge_reflex(). A RELAX STEP CAN
+// NO LONGER OVERWRITE A TIGHTEN (OR VICE VERSA) AND THE MONITOR NEVER HAS
+// TO INFER REFLEX ACTIVITY FROM WHAT IT READS BACK.
+
+pub const RELAX_STEP_NS: u64 = 500_000; // RELAX BY 500US PER TICK
+pub const RELAX_HOLD_TICKS: u32 = 2; // WAIT 2S OF GOOD P99 BEFORE STEPPING
+pub const SPIKE_TICKS: u32 = 2; // CONSECUTIVE ABOVE-CEILING TICKS TO TIGHTEN
+
+// WAKE LATENCY SLO (--slo-p99-us)
+// ONE P99 CEILING FOR EVERY REGIME INSTEAD OF p99_ceiling(). PRESSURE IS
+// HOW MANY TIMES TIGHTER THE SLO IS THAN THE REGIME'S OWN CEILING (1-3):
+// EACH STEP CUTS THE TIGHTEN DEEPER (3/4, 5/8, 1/2 OF BASELINE) AND
+// STRETCHES THE RELAX HOLD (2, 4, 6 TICKS). A LOOSER SLO IS PRESSURE 1.
+pub const SLO_MIN_US: u64 = 10; // SMALLEST HISTOGRAM EDGE
+pub const SLO_MAX_US: u64 = 20_000; // LARGEST FINITE HISTOGRAM EDGE
+pub const SLO_MAX_PRESSURE: u64 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReflexAction {
+    Tightened { slice_ns: u64 },
+    Relaxed { slice_ns: u64 },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Reflex {
+    pub spike_count: u32,
+    pub relax_counter: u32,
+    pub tightened: bool,
+    pub tighten_events: u64,
+    // GRADUATED RELAX STEPS TAKEN, WHOLE RUN
+    pub relax_steps: u64,
+    pub bounds: SliceBounds,
+    // REQUESTED SLICE; MEANINGFUL ONLY WHILE tightened
+    pub slice_ns: u64,
+    // --slo-p99-us; None: EACH REGIME'S OWN CEILING
+    pub slo_ns: Option<u64>,
+}
+
+// THE ONE PLACE THE REFLEX REQUEST MEETS THE REST OF THE KNOBS. PURE AND
+// DETERMINISTIC: SAME DESIRED KNOBS + SAME REQUEST = SAME MAP VALUE, NO
+// MATTER WHICH SIDE MOVED LAST. PREEMPT NEVER EXCEEDS THE REQUESTED SLICE.
+pub fn merge_reflex(desired: TuningKnobs, request: Option<u64>) -> TuningKnobs {
+    match request {
+        Some(slice_ns) => TuningKnobs {
+            slice_ns,
+            preempt_thresh_ns: desired.preempt_thresh_ns.min(slice_ns),
+            ..desired
+        },
+        None => desired,
+    }
+}
+
+impl Reflex {
+    // FORGET PARTIAL SPIKE AND RELAX RUNS (REGIME CHANGE, RESUME, CTL)
+    pub fn reset_counters(&mut self) {
+        self.spike_count = 0;
+        self.relax_counter = 0;
+    }
+
+    // DROP THE REQUEST (REGIME CHANGE, PIN): THE NEW BASELINE STANDS
+    pub fn release(&mut self) {
+        self.tightened = false;
+        self.reset_counters();
+    }
+
+    pub fn request(&self) -> Option<u64> {
+        self.tightened.then_some(self.slice_ns)
+    }
+
+    // THE P99 CEILING HELD IN regime: THE SLO WHEN ONE IS SET
+    pub fn ceiling(&self, regime: Regime) -> u64 {
+        self.slo_ns.unwrap_or(regime.p99_ceiling())
+    }
+
+    pub fn slo_pressure(&self, regime: Regime) -> u64 {
+        self.slo_ns.map_or(1, |slo| {
+            (regime.p99_ceiling() / slo.max(1)).clamp(1, SLO_MAX_PRESSURE)
+        })
+    }
+
+    // ON RESUME THE MONITOR HANDS OVER WHAT THE MAP HOLDS: A SLICE BELOW
+    // THE REGIME BASELINE IS A TIGHTEN STILL IN EFFECT
+    pub fn resync(&mut self, current: &TuningKnobs, baseline: &TuningKnobs) {
+        self.tightened = resync_tightened(current, baseline);
+        self.slice_ns = current.slice_ns;
+        self.reset_counters();
+    }
+
+    // ONE TICK OF BOTH HALVES, IN LOOP ORDER: TIGHTEN CHECK, THEN RELAX.
+    // THE CALLER SKIPS THE TICK ENTIRELY ON A REGIME CHANGE.
+    pub fn tick(
+        &mut self,
+        gate: &TickDecisions,
+        regime: Regime,
+        p99_ns: u64,
+        interactive_p99_ns: u64,
+        baseline: &TuningKnobs,
+    ) -> Option<ReflexAction> {
+        let mut action = None;
+        if gate.tighten {
+            action = self.tighten(regime, p99_ns, interactive_p99_ns, baseline);
+        }
+        if gate.relax {
+            action = action.or(self.relax(regime, p99_ns, baseline));
+        }
+        action
+    }
+
+    // REQUIRE SPIKE_TICKS CONSECUTIVE ABOVE-CEILING TICKS BEFORE TIGHTENING.
+    // ONLY TIGHTEN IN MIXED: LIGHT HAS NO CONTENTION (POINTLESS),
+    // HEAVY IS FULLY SATURATED (MORE PREEMPTION JUST ADDS OVERHEAD).
+    // WHILE TIGHTENED, NO FURTHER TIGHTEN UNTIL RELAX REACHES BASELINE.
+    pub fn tighten(
+        &mut self,
+        regime: Regime,
+        p99_ns: u64,
+        interactive_p99_ns: u64,
+        baseline: &TuningKnobs,
+    ) -> Option<ReflexAction> {
+        if self.tightened {
+            return None;
+        }
+        if !should_reflex_tighten(p99_ns, interactive_p99_ns, self.ceiling(regime)) {
+            self.spike_count = 0;
+            return None;
+        }
+        self.spike_count += 1;
+        if self.spike_count < SPIKE_TICKS || regime != Regime::Mixed {
+            return None;
+        }
+        let keep_eighths = 7 - self.slo_pressure(regime);
+        let slice_ns = self
+            .bounds
+            .clamp((baseline.slice_ns * keep_eighths / 8).max(REFLEX_FLOOR_NS));
+        // ALREADY AT THE FLOOR (1-CPU MIXED BASELINE): NOTHING TO TIGHTEN.
+        // MARKING IT TIGHTENED WOULD HOLD A "TIGHTEN" THAT RELAX CANNOT UNDO.
+        if slice_ns >= baseline.slice_ns {
+            self.spike_count = 0;
+            return None;
+        }
+        self.slice_ns = slice_ns;
+        self.tightened = true;
+        self.tighten_events += 1;
+        self.spike_count = 0;
+        Some(ReflexAction::Tightened { slice_ns })
+    }
+
+    // GRADUATED RELAX: STEP THE REQUEST TOWARD BASELINE
+    pub fn relax(
+        &mut self,
+        regime: Regime,
+        p99_ns: u64,
+        baseline: &TuningKnobs,
+    ) -> Option<ReflexAction> {
+        if !self.tightened {
+            return None;
+        }
+        if p99_ns > self.ceiling(regime) {
+            self.relax_counter = 0;
+            return None;
+        }
+        self.relax_counter += 1;
+        if self.relax_counter < RELAX_HOLD_TICKS * self.slo_pressure(regime) as u32 {
+            return None;
+        }
+        self.relax_counter = 0;
+        let ceiling = baseline.slice_ns.min(self.bounds.max_ns);
+        if self.slice_ns >= ceiling {
+            self.tightened = false;
+            return None;
+        }
+        let slice_ns = (self.slice_ns + RELAX_STEP_NS).min(ceiling);
+        self.slice_ns = slice_ns;
+        self.relax_steps += 1;
+        if slice_ns >= ceiling {
+            self.tightened = false;
+        }
+        Some(ReflexAction::Relaxed { slice_ns })
+    }
+}
+
+// SLO COMPLIANCE: 1-SECOND WINDOWS WHOSE POOLED P99 MET --slo-p99-us.
+// THE P99 IS A BUCKET UPPER EDGE, SO A MET WINDOW IS MET FOR CERTAIN.
+// WINDOWS WITHOUT WAKEUPS OR DISCARDED (RESET, RESUME) ARE NOT COUNTED.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SloCompliance {
+    pub met: u64,
+    pub windows: u64,
+}
+
+impl SloCompliance {
+    pub fn record(&mut self, p99_ns: u64, slo_ns: u64) -> bool {
+        let met = p99_ns <= slo_ns;
+        self.windows += 1;
+        self.met += met as u64;
+        met
+    }
+
+    // TENTHS OF A PERCENT; None BEFORE THE FIRST WINDOW
+    pub fn permille(&self) -> Option<u64> {
+        (self.met * 1000).checked_div(self.windows)
+    }
+
+    // SHUTDOWN SUMMARY: "SLO MET: 97.2% of windows (...)"
+    pub fn line(&self, slo_ns: u64) -> String {
+        let pct = self
+            .permille()
+            .map_or("-".to_string(), |m| format!("{}.{}%", m / 10, m % 10));
+        format!(
+            "SLO MET: {} of windows (p99 <= {}us, {}/{})",
+            pct,
+            slo_ns / 1000,
+            self.met,
+            self.windows
+        )
+    }
+}
+
 // SLEEP-INFORMED BATCH TUNING
 // IO-HEAVY: EXTEND BATCH SLICES (+25%) -- IO-BOUND TASKS BATCH BETWEEN FREQUENT SHORT SLEEPS
 // IDLE-HEAVY: TIGHTEN BATCH SLICES (-25%) -- SPORADIC USER INPUT NEEDS FASTER PREEMPTION
@@ -309,3 +1018,255 @@ pub fn sleep_adjust_batch_ns(base_batch_ns: u64, io_pct: u64) -> u64 {
     }
 }
 
+// SHARED DSQ DEPTH WATCH
+// RUNNABLE-TASK STALLS LOOK LIKE A SHARED DSQ BACKLOG THAT NEVER DRAINS.
+// GROWING: DEPTH ROSE FOR QDEPTH_GROWTH_TICKS STRAIGHT AND EXCEEDS ONE TASK
+//   PER CPU. DISPATCH IS FALLING BEHIND: FEED REGIME DETECTION AS SATURATED.
+// STUCK: DEPTH ABOVE QDEPTH_DEEP_PER_CPU TASKS PER CPU WHILE THE IDLE FAST
+//   PATH STILL FINDS CPUS. IDLE CPUS ARE NOT PULLING QUEUED WORK: THAT IS
+//   A DISPATCH BUG, NOT LOAD. FLAG IT LOUDLY, ONCE PER EPISODE.
+
+pub const QDEPTH_GROWTH_TICKS: u32 = 5;
+pub const QDEPTH_DEEP_PER_CPU: u64 = 4;
+pub const QDEPTH_IDLE_PCT: u64 = HEAVY_EXIT_PCT; // IDLE FAST PATH STILL HITTING
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct QueueDepthVerdict {
+    pub growing: bool,
+    pub stuck: bool,
+    pub stuck_onset: bool, // FIRST TICK OF A STUCK EPISODE
+}
+
+impl QueueDepthVerdict {
+    // IDLE PERCENTAGE SEEN BY detect_regime(): A GROWING BACKLOG OVERRIDES
+    // THE IDLE FAST PATH SHARE, WHICH LAGS WHEN WORK PILES UP BEHIND IT
+    pub fn regime_idle_pct(&self, idle_pct: u64) -> u64 {
+        if self.growing {
+            0
+        } else {
+            idle_pct
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct QueueDepthWatch {
+    prev: u64,
+    rising: u32,
+    stuck: bool,
+    max: u64,
+}
+
+impl QueueDepthWatch {
+    pub fn update(&mut self, depth: u64, idle_pct: u64, nr_cpus: u64) -> QueueDepthVerdict {
+        let nr_cpus = nr_cpus.max(1);
+        self.rising = if depth > self.prev {
+            self.rising.saturating_add(1)
+        } else {
+            0
+        };
+        self.prev = depth;
+        self.max = self.max.max(depth);
+
+        let stuck = depth > nr_cpus * QDEPTH_DEEP_PER_CPU && idle_pct >= QDEPTH_IDLE_PCT;
+        let stuck_onset = stuck && !self.stuck;
+        self.stuck = stuck;
+        QueueDepthVerdict {
+            growing: self.rising >= QDEPTH_GROWTH_TICKS && depth > nr_cpus,
+            stuck,
+            stuck_onset,
+        }
+    }
+
+    // DEEPEST SAMPLE SEEN, FOR THE SHUTDOWN SUMMARY
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+// DISPATCH BACKLOG EARLY WARNING
+// A ROLLING SUM OF ENQUEUES MINUS DISPATCHES (StatsDelta::backlog_net).
+// DIRECT DISPATCHES PULL IT NEGATIVE ON A HEALTHY SYSTEM, SO IT FLOORS AT
+// ZERO. WHEN DISPATCHES STOP AND ENQUEUES CONTINUE (THE STALL THAT ENDS IN
+// A WATCHDOG EJECT) IT CLIMBS EVERY TICK. GROWTH FOR MORE THAN
+// BACKLOG_WARN_TICKS STRAIGHT TICKS RAISES THE WARNING. A COUNTER RESET
+// STARTS THE ESTIMATE OVER: THE OLD ONE DESCRIBED A DEAD BPF PROGRAM.
+
+pub const BACKLOG_WARN_TICKS: u32 = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct BacklogVerdict {
+    pub backlog: u64,
+    pub warn: bool,
+    pub warn_onset: bool, // FIRST TICK OF A WARNING EPISODE
+}
+
+impl BacklogVerdict {
+    // TELEMETRY TAG: "[!]" WHILE THE WARNING HOLDS
+    pub fn tag(&self) -> &'static str {
+        if self.warn {
+            "[!]"
+        } else {
+            ""
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BacklogWatch {
+    backlog: u64,
+    rising: u32,
+    warn: bool,
+    max: u64,
+}
+
+impl BacklogWatch {
+    pub fn update(&mut self, net: i64, reset: bool) -> BacklogVerdict {
+        let next = if reset {
+            0
+        } else {
+            self.backlog.saturating_add_signed(net)
+        };
+        self.rising = if next > self.backlog {
+            self.rising.saturating_add(1)
+        } else {
+            0
+        };
+        self.backlog = next;
+        self.max = self.max.max(next);
+
+        let warn = self.rising > BACKLOG_WARN_TICKS;
+        let warn_onset = warn && !self.warn;
+        self.warn = warn;
+        BacklogVerdict {
+            backlog: next,
+            warn,
+            warn_onset,
+        }
+    }
+
+    // LARGEST ESTIMATE SEEN, FOR THE SHUTDOWN SUMMARY
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+// KICK-DRIVEN TIMER INTERVAL
+// SOFT KICKS ARE THE TIMER'S USEFUL WORK. NEAR ZERO FOR
+// TIMER_WIDEN_HOLD_TICKS STRAIGHT: WIDEN ONE STEP (BASELINE / 4), CAPPED AT
+// TIMER_MAX_MULT x BASELINE. LESS OVERHEAD WHEN NOTHING NEEDS NUDGING.
+// HARD-KICK LATENCY ABOVE TIMER_LAT_KICK_CEIL_US: HALVE THE DISTANCE BACK
+// TO BASELINE IMMEDIATELY. SLOW TO RELAX, FAST TO TIGHTEN, LIKE THE REFLEX.
+// NOT WIRED YET: THE BPF SIDE HAS NO TIMER (KICKS COME FROM enqueue() AND
+// tick()), SO THERE IS NO timer_interval_ns KNOB TO WRITE.
+
+pub const TIMER_WIDEN_HOLD_TICKS: u32 = 5;
+pub const TIMER_SOFT_KICK_FLOOR: u64 = 2; // PER TICK: "NEAR ZERO"
+pub const TIMER_LAT_KICK_CEIL_US: u64 = 500;
+pub const TIMER_MAX_MULT: u64 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TimerIntervalState {
+    pub quiet_ticks: u32, // CONSECUTIVE TICKS WITH SOFT KICKS <= FLOOR
+}
+
+#[allow(dead_code)] // BINARY HAS NO CALLER UNTIL THE BPF TIMER LANDS
+pub fn adjust_timer_interval(
+    current: u64,
+    baseline: u64,
+    soft_kicks: u64,
+    lat_kick_us: u64,
+    state: TimerIntervalState,
+) -> (u64, TimerIntervalState) {
+    let ceil = baseline.saturating_mul(TIMER_MAX_MULT);
+    // A REGIME CHANGE MOVES THE BASELINE: RE-CLAMP BEFORE STEPPING
+    let current = current.clamp(baseline, ceil);
+
+    if lat_kick_us > TIMER_LAT_KICK_CEIL_US {
+        let excess = current - baseline;
+        let next = if excess <= baseline / 4 {
+            baseline
+        } else {
+            baseline + excess / 2
+        };
+        return (next, TimerIntervalState::default());
+    }
+
+    if soft_kicks > TIMER_SOFT_KICK_FLOOR {
+        return (current, TimerIntervalState::default());
+    }
+
+    let quiet_ticks = state.quiet_ticks + 1;
+    if quiet_ticks < TIMER_WIDEN_HOLD_TICKS {
+        return (current, TimerIntervalState { quiet_ticks });
+    }
+    // WIDENED: THE NEXT STEP NEEDS ANOTHER FULL HOLD
+    ((current + baseline / 4).min(ceil), TimerIntervalState::default())
+}
+
+// ADAPTIVE CLASSIFIER THRESHOLD (--adaptive-classifier)
+// TIER SHARE OF THE TICK'S WAKEUPS, COUNTED FROM THE PER-TIER WAKE-LATENCY
+// HISTOGRAMS. LAT_CRITICAL ABOVE CLS_RAISE_PER_MILLE FOR CLS_HOLD_TICKS
+// STRAIGHT: TOO MANY TASKS QUALIFY, RAISE lat_cri_thresh_high ONE STEP.
+// BELOW CLS_LOWER_PER_MILLE FOR THE SAME HOLD: LOWER IT ONE STEP. BETWEEN
+// THE TWO, OR TOO FEW WAKEUPS TO JUDGE: BOTH HOLDS RESTART. THE HIGH
+// THRESHOLD STAYS IN [CLS_HIGH_MIN, CLS_HIGH_MAX] AND A STEP ABOVE THE LOW
+// ONE, SO INTERACTIVE NEVER COLLAPSES. lat_cri_thresh_low IS NOT ADAPTED.
+
+pub const CLS_HOLD_TICKS: u32 = 5;
+pub const CLS_RAISE_PER_MILLE: u64 = 100; // 10% OF WAKEUPS LAT_CRITICAL
+pub const CLS_LOWER_PER_MILLE: u64 = 5; // 0.5%
+pub const CLS_MIN_WAKEUPS: u64 = 200; // PER TICK: FEWER IS NOISE
+pub const CLS_STEP: u64 = 8;
+pub const CLS_HIGH_MIN: u64 = 16;
+pub const CLS_HIGH_MAX: u64 = 128; // HALF THE BPF LAT_CRI_CAP (255)
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ClassifierState {
+    pub hot_ticks: u32,  // CONSECUTIVE TICKS ABOVE CLS_RAISE_PER_MILLE
+    pub cold_ticks: u32, // CONSECUTIVE TICKS BELOW CLS_LOWER_PER_MILLE
+}
+
+// wakeups: [BATCH, INTERACTIVE, LAT_CRITICAL] THIS TICK. RETURNS THE NEW
+// lat_cri_thresh_high.
+pub fn adjust_classifier_thresholds(
+    high: u64,
+    low: u64,
+    wakeups: [u64; 3],
+    state: ClassifierState,
+) -> (u64, ClassifierState) {
+    let floor = CLS_HIGH_MIN.max(low + CLS_STEP).min(CLS_HIGH_MAX);
+    let high = high.clamp(floor, CLS_HIGH_MAX);
+    let total: u64 = wakeups.iter().sum();
+    if total < CLS_MIN_WAKEUPS {
+        return (high, ClassifierState::default());
+    }
+    let lat_cri_per_mille = wakeups[2] * 1000 / total;
+
+    if lat_cri_per_mille > CLS_RAISE_PER_MILLE {
+        let hot_ticks = state.hot_ticks + 1;
+        if hot_ticks < CLS_HOLD_TICKS {
+            let state = ClassifierState {
+                hot_ticks,
+                cold_ticks: 0,
+            };
+            return (high, state);
+        }
+        // STEPPED: THE NEXT STEP NEEDS ANOTHER FULL HOLD
+        let raised = (high + CLS_STEP).min(CLS_HIGH_MAX);
+        return (raised, ClassifierState::default());
+    }
+    if lat_cri_per_mille < CLS_LOWER_PER_MILLE {
+        let cold_ticks = state.cold_ticks + 1;
+        if cold_ticks < CLS_HOLD_TICKS {
+            let state = ClassifierState {
+                hot_ticks: 0,
+                cold_ticks,
+            };
+            return (high, state);
+        }
+        let lowered = high.saturating_sub(CLS_STEP).max(floor);
+        return (lowered, ClassifierState::default());
+    }
+    (high, ClassifierState::default())
+}
@@ -0,0 +1,381 @@
+// PANDEMONIUM WAKEUP + PREEMPTION VICTIMS
+// PURE-RUST MODULE: ZERO BPF DEPENDENCIES
+//
+// WHO IS WAITING, AND WHO KEEPS GETTING KICKED OFF A CPU, BY comm. BPF
+// ONLY RECORDS A TAGGED {seq, lat_ns, pid, kind, tier} IN A PER-CPU RING
+// (event_ring, intf.h): NO STRING COPY ON THE HOT PATH.
+//   EVENT_SLOW_WAKE  A WAKEUP SLOWER THAN SLOW_WAKE_NS
+//   EVENT_PREEMPT    A BATCH TASK PREEMPTED BY tick() FOR A tier TASK
+// THE MONITOR THREAD DRAINS THE RING EVERY TICK AND RESOLVES pid -> comm
+// FROM /proc/<pid>/comm ITSELF:
+//   - LAZILY: A pid SEEN BEFORE HITS THE CACHE, NOT /proc
+//   - RATE-LIMITED: AT MOST RESOLVE_PER_TICK /proc READS PER TICK; THE REST
+//     WAIT IN A BOUNDED QUEUE (FULL: THE SAMPLE IS DROPPED AND COUNTED)
+//   - BOUNDED: COMM_CACHE_CAP pids, LEAST RECENTLY USED EVICTED FIRST
+//
+// A pid THAT EXITED BEFORE ITS TURN IS ATTRIBUTED TO "<exited>". A REUSED
+// pid STILL IN THE CACHE KEEPS ITS OLD NAME UNTIL EVICTED: GOOD ENOUGH FOR
+// "WHICH PROGRAMS SUFFER", NOT A PER-THREAD AUDIT.
+//
+// PER comm: SLOW WAKEUP COUNT, WORST LATENCY, AND A LOG2 HISTOGRAM FROM
+// 2MS GIVING A P99 UPPER BOUND; PREEMPTIONS BY THE TIER THEY MADE ROOM
+// FOR. THE TOP_VICTIMS OF EACH GO IN THE SIGUSR1 DUMP AND, UNDER
+// --verbose, EVERY VERBOSE_VICTIM_TICKS TICKS. `procdb list` SHOWS
+// PREEMPTED/S FROM THE RUNNING SCHEDULER (ctl `procdb preempted`).
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::procdb::TIER_NAMES;
+
+// intf.h: EVENT_RING_SLOTS, SLOW_WAKE_NS, EVENT_*
+pub const EVENT_RING_SLOTS: usize = 32;
+pub const SLOW_WAKE_NS: u64 = 2_000_000;
+pub const EVENT_SLOW_WAKE: u16 = 0;
+pub const EVENT_PREEMPT: u16 = 1;
+
+pub const RESOLVE_PER_TICK: usize = 32;
+pub const PENDING_CAP: usize = 1024;
+pub const COMM_CACHE_CAP: usize = 512;
+pub const TOP_VICTIMS: usize = 5;
+pub const VERBOSE_VICTIM_TICKS: u64 = 30;
+pub const EXITED_COMM: &str = "<exited>";
+
+// [2,4) [4,8) ... [128,256) [256,+INF) MS
+pub const VICTIM_BUCKETS: usize = 8;
+
+// MIRROR OF struct sched_event IN intf.h
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SchedEvent {
+    pub seq: u64,
+    pub lat_ns: u64,
+    pub pid: u32,
+    pub kind: u16,
+    pub tier: u16,
+}
+
+const _: () = assert!(std::mem::size_of::<SchedEvent>() == 24);
+
+impl SchedEvent {
+    // DECODE ONE PER-CPU MAP VALUE; None UNLESS THE SIZE MATCHES EXACTLY
+    pub fn from_bytes(b: &[u8]) -> Option<Self> {
+        if b.len() != std::mem::size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { std::ptr::read_unaligned(b.as_ptr() as *const Self) })
+    }
+}
+
+// PER-CPU HIGH-WATER seq: WHAT THE LAST TICK ALREADY TOOK FROM EACH RING
+#[derive(Default)]
+pub struct EventCursor {
+    last: Vec<u64>,
+}
+
+impl EventCursor {
+    // ONE CPU'S RING SLOTS -> THE SAMPLES NEWER THAN LAST TICK (OLDEST
+    // FIRST) AND HOW MANY WERE OVERWRITTEN BEFORE WE GOT TO THEM. A seq
+    // BELOW THE HIGH-WATER MARK MEANS THE MAP WAS RECREATED: START OVER.
+    pub fn drain(&mut self, cpu: usize, slots: &[SchedEvent], out: &mut Vec<SchedEvent>) -> u64 {
+        if self.last.len() <= cpu {
+            self.last.resize(cpu + 1, 0);
+        }
+        let newest = slots.iter().map(|w| w.seq).max().unwrap_or(0);
+        if newest < self.last[cpu] {
+            self.last[cpu] = 0;
+        }
+        let last = self.last[cpu];
+        let start = out.len();
+        out.extend(slots.iter().filter(|w| w.seq > last));
+        out[start..].sort_unstable_by_key(|w| w.seq);
+        self.last[cpu] = newest;
+        (newest - last).saturating_sub((out.len() - start) as u64)
+    }
+}
+
+fn victim_bucket(lat_ns: u64) -> usize {
+    let steps = (lat_ns / SLOW_WAKE_NS).max(1);
+    ((63 - steps.leading_zeros()) as usize).min(VICTIM_BUCKETS - 1)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VictimStats {
+    pub count: u64,
+    pub worst_ns: u64,
+    pub buckets: [u64; VICTIM_BUCKETS],
+}
+
+impl VictimStats {
+    pub fn record(&mut self, lat_ns: u64) {
+        self.count += 1;
+        self.worst_ns = self.worst_ns.max(lat_ns);
+        self.buckets[victim_bucket(lat_ns)] += 1;
+    }
+
+    // UPPER EDGE OF THE BUCKET HOLDING THE 99TH PERCENTILE, CAPPED AT THE
+    // WORST SEEN (THE TOP BUCKET HAS NO EDGE)
+    pub fn p99_ns(&self) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = self.count.saturating_mul(99).div_ceil(100);
+        let mut seen = 0;
+        for (i, &c) in self.buckets.iter().enumerate() {
+            seen += c;
+            if seen >= target && i + 1 < VICTIM_BUCKETS {
+                return (SLOW_WAKE_NS << (i + 1)).min(self.worst_ns);
+            }
+        }
+        self.worst_ns
+    }
+}
+
+// PREEMPTIONS OF ONE comm, BY THE TIER THAT GOT ITS CPU (TIER_NAMES ORDER)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PreemptStats {
+    pub count: u64,
+    pub by_tier: [u64; 3],
+}
+
+impl PreemptStats {
+    pub fn record(&mut self, tier: u16) {
+        self.count += 1;
+        if let Some(n) = self.by_tier.get_mut(tier as usize) {
+            *n += 1;
+        }
+    }
+
+    pub fn per_sec(&self, secs: u64) -> f64 {
+        self.count as f64 / secs.max(1) as f64
+    }
+}
+
+// pid -> comm (None: THE pid HAD EXITED), LEAST RECENTLY USED EVICTED
+#[derive(Default)]
+pub struct CommCache {
+    entries: HashMap<u32, (Option<String>, u64)>,
+    clock: u64,
+}
+
+impl CommCache {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, pid: u32) -> bool {
+        self.entries.contains_key(&pid)
+    }
+
+    // Some(None): CACHED AS EXITED. None: NOT CACHED.
+    pub fn get(&mut self, pid: u32) -> Option<Option<&str>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(&pid).map(|(comm, used)| {
+            *used = clock;
+            comm.as_deref()
+        })
+    }
+
+    pub fn insert(&mut self, pid: u32, comm: Option<String>) {
+        if self.entries.len() >= COMM_CACHE_CAP && !self.entries.contains_key(&pid) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(&p, _)| p);
+            if let Some(p) = oldest {
+                self.entries.remove(&p);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(pid, (comm, self.clock));
+    }
+}
+
+#[derive(Default)]
+pub struct VictimTable {
+    cache: CommCache,
+    pending: VecDeque<SchedEvent>,
+    per_comm: HashMap<String, VictimStats>,
+    preempted: HashMap<String, PreemptStats>,
+    pub samples: u64,  // EVENT_SLOW_WAKE
+    pub preempts: u64, // EVENT_PREEMPT
+    pub lost: u64,     // RING OVERRUNS + FULL PENDING QUEUE
+    pub reads: u64,    // /proc/<pid>/comm READS, WHOLE RUN
+}
+
+impl VictimTable {
+    pub fn cache(&self) -> &CommCache {
+        &self.cache
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn note_lost(&mut self, n: u64) {
+        self.lost += n;
+    }
+
+    // CACHED pids ARE ATTRIBUTED NOW; THE REST WAIT FOR resolve().
+    // AN UNKNOWN kind (A NEWER BPF OBJECT) IS SKIPPED.
+    pub fn ingest(&mut self, events: &[SchedEvent]) {
+        for e in events {
+            match e.kind {
+                EVENT_SLOW_WAKE => self.samples += 1,
+                EVENT_PREEMPT => self.preempts += 1,
+                _ => continue,
+            }
+            if let Some(comm) = self.cache.get(e.pid) {
+                let comm = comm.unwrap_or(EXITED_COMM).to_string();
+                self.attribute(comm, e);
+            } else if self.pending.len() < PENDING_CAP {
+                self.pending.push_back(*e);
+            } else {
+                self.lost += 1;
+            }
+        }
+    }
+
+    // ONE TICK'S WORTH OF pid -> comm RESOLUTION: AT MOST budget CALLS TO
+    // read_comm. QUEUED EVENTS WHOSE pid RESOLVED EARLIER COST NOTHING.
+    pub fn resolve(&mut self, budget: usize, mut read_comm: impl FnMut(u32) -> Option<String>) {
+        let mut reads = 0;
+        while let Some(&e) = self.pending.front() {
+            let comm = match self.cache.get(e.pid) {
+                Some(comm) => comm.map(str::to_string),
+                None => {
+                    if reads == budget {
+                        break;
+                    }
+                    reads += 1;
+                    let comm = read_comm(e.pid);
+                    self.cache.insert(e.pid, comm.clone());
+                    comm
+                }
+            };
+            self.pending.pop_front();
+            self.attribute(comm.unwrap_or_else(|| EXITED_COMM.to_string()), &e);
+        }
+        self.reads += reads as u64;
+    }
+
+    fn attribute(&mut self, comm: String, e: &SchedEvent) {
+        if e.kind == EVENT_PREEMPT {
+            self.preempted.entry(comm).or_default().record(e.tier);
+        } else {
+            self.per_comm.entry(comm).or_default().record(e.lat_ns);
+        }
+    }
+
+    pub fn get(&self, comm: &str) -> Option<&VictimStats> {
+        self.per_comm.get(comm)
+    }
+
+    pub fn get_preempted(&self, comm: &str) -> Option<&PreemptStats> {
+        self.preempted.get(comm)
+    }
+
+    // WORST FIRST: P99, THEN WORST SINGLE WAKEUP, THEN COUNT, THEN NAME
+    pub fn top(&self, k: usize) -> Vec<(&str, &VictimStats)> {
+        let mut all: Vec<(&str, &VictimStats)> = self
+            .per_comm
+            .iter()
+            .map(|(comm, s)| (comm.as_str(), s))
+            .collect();
+        all.sort_by(|a, b| {
+            b.1.p99_ns()
+                .cmp(&a.1.p99_ns())
+                .then(b.1.worst_ns.cmp(&a.1.worst_ns))
+                .then(b.1.count.cmp(&a.1.count))
+                .then(a.0.cmp(b.0))
+        });
+        all.truncate(k);
+        all
+    }
+
+    // MOST PREEMPTED FIRST, THEN NAME
+    pub fn top_preempted(&self, k: usize) -> Vec<(&str, &PreemptStats)> {
+        let mut all: Vec<(&str, &PreemptStats)> = self
+            .preempted
+            .iter()
+            .map(|(comm, s)| (comm.as_str(), s))
+            .collect();
+        all.sort_by(|a, b| b.1.count.cmp(&a.1.count).then(a.0.cmp(b.0)));
+        all.truncate(k);
+        all
+    }
+
+    // EVERY PREEMPTED comm: PREEMPTIONS PER SECOND OVER secs OF RUN
+    pub fn preempted_rates(&self, secs: u64) -> Vec<(String, f64)> {
+        self.top_preempted(usize::MAX)
+            .into_iter()
+            .map(|(comm, s)| (comm.to_string(), s.per_sec(secs)))
+            .collect()
+    }
+
+    // "[VICTIMS] samples=.. lost=.. pending=.." THEN ONE LINE PER TOP comm
+    pub fn format_lines(&self, k: usize) -> Vec<String> {
+        let mut lines = vec![format!(
+            "[VICTIMS] >{}ms samples={} lost={} pending={} comms={}",
+            SLOW_WAKE_NS / 1_000_000,
+            self.samples,
+            self.lost,
+            self.pending.len(),
+            self.per_comm.len()
+        )];
+        for (i, (comm, s)) in self.top(k).iter().enumerate() {
+            lines.push(format!(
+                "[VICTIMS] {}. {:<16} n={} worst={}us p99<={}us",
+                i + 1,
+                comm,
+                s.count,
+                s.worst_ns / 1000,
+                s.p99_ns() / 1000
+            ));
+        }
+        lines
+    }
+
+    // "[PREEMPTED] events=.. comms=.." THEN ONE LINE PER TOP comm, SPLIT
+    // BY THE TIER THAT TOOK THE CPU
+    pub fn preempted_lines(&self, k: usize, secs: u64) -> Vec<String> {
+        let mut lines = vec![format!(
+            "[PREEMPTED] events={} comms={}",
+            self.preempts,
+            self.preempted.len()
+        )];
+        for (i, (comm, s)) in self.top_preempted(k).iter().enumerate() {
+            let by: Vec<String> = TIER_NAMES
+                .iter()
+                .zip(s.by_tier)
+                .map(|(t, n)| format!("{}:{}", t, n))
+                .collect();
+            lines.push(format!(
+                "[PREEMPTED] {}. {:<16} n={} rate={:.2}/s by={}",
+                i + 1,
+                comm,
+                s.count,
+                s.per_sec(secs),
+                by.join(",")
+            ));
+        }
+        lines
+    }
+}
+
+// EVERY 30TH TICK (TICK 29, 59, ...), LIKE diag::should_dump_stats
+pub fn should_dump_victims(tick: u64) -> bool {
+    (tick + 1).is_multiple_of(VERBOSE_VICTIM_TICKS)
+}
+
+// /proc/<pid>/comm WITHOUT THE NEWLINE; None ONCE THE TASK IS GONE
+pub fn read_comm(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|s| s.trim_end().to_string())
+}
@@ -0,0 +1,80 @@
+// PANDEMONIUM MAP ABI SELF-TEST TESTS
+// PASS/FAIL ROWS, THE ATTACH GATE'S ERRORS, AND MIRRORS AGAINST main.bpf.c AND memlock::MAPS
+
+use pandemonium::abi::{failures, summary, MapAbi, MIRRORS};
+use pandemonium::memlock::MAPS;
+
+// WHAT A MATCHING KERNEL REPORTS
+fn matching() -> Vec<MapAbi> {
+    MIRRORS
+        .iter()
+        .map(|&(map, key, value)| MapAbi::new(map, key as u32, value as u32).unwrap())
+        .collect()
+}
+
+#[test]
+fn matching_sizes_pass_every_map() {
+    let rows = matching();
+    assert_eq!(rows.len(), MIRRORS.len());
+    assert!(rows.iter().all(|r| r.pass() && r.check().is_ok()));
+    assert_eq!(failures(&rows), 0);
+    assert!(summary(&rows).starts_with("SELF-TEST PASSED: 11 MAPS"));
+    let line = rows[0].line();
+    assert!(line.starts_with("PASS  stats_map "), "{}", line);
+    // NOT A MAP RUST DECODES
+    assert!(MapAbi::new("compositor_map", 16, 1).is_none());
+}
+
+#[test]
+fn a_grown_value_or_key_fails_its_row() {
+    let mut rows = matching();
+    let knobs = rows
+        .iter_mut()
+        .find(|r| r.map == "tuning_knobs_map")
+        .unwrap();
+    knobs.value_size += 8;
+    let line = knobs.line();
+    assert!(line.starts_with("FAIL  tuning_knobs_map"), "{}", line);
+    assert!(line.ends_with(&format!(
+        "value {}/{}",
+        knobs.value_size, knobs.value_mirror
+    )));
+    let err = knobs.check().unwrap_err().to_string();
+    assert!(err.starts_with("tuning_knobs_map: BPF VALUE IS"), "{}", err);
+
+    let observe = rows
+        .iter_mut()
+        .find(|r| r.map == "task_class_observe")
+        .unwrap();
+    observe.key_size = 32;
+    assert!(!observe.pass());
+    let err = observe.check().unwrap_err().to_string();
+    assert!(
+        err.starts_with("task_class_observe: BPF KEY IS 32 BYTES, RUST MIRROR IS 16"),
+        "{}",
+        err
+    );
+
+    assert_eq!(failures(&rows), 2);
+    assert!(summary(&rows).starts_with("SELF-TEST FAILED: 2 OF 11 MAPS"));
+}
+
+// EVERY MIRROR NAMES A DECLARED MAP, AND memlock's HAND-KEPT SIZES AGREE
+#[test]
+fn mirrors_match_bpf_program_and_memlock() {
+    let bpf = include_str!("../src/bpf/main.bpf.c");
+    for &(map, key, value) in &MIRRORS {
+        assert!(
+            bpf.contains(&format!("}} {} SEC(\".maps\");", map)),
+            "{} NOT DECLARED IN main.bpf.c",
+            map
+        );
+        let spec = MAPS.iter().find(|m| m.name == map).unwrap();
+        assert_eq!(
+            (spec.key_size, spec.value_size),
+            (key as u64, value as u64),
+            "{}",
+            map
+        );
+    }
+}
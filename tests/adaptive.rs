@@ -4,14 +4,23 @@
 // ALL TESTS USE PURE-RUST TYPES FROM pandemonium::tuning.
 // ZERO BPF DEPENDENCIES. RUN OFFLINE.
 
+use std::time::{Duration, Instant};
+
 use pandemonium::tuning::{
-    compute_p99_from_histogram, compute_stability_score, detect_regime, regime_knobs,
-    should_print_telemetry, should_reflex_tighten, sleep_adjust_batch_ns, Regime, TuningKnobs,
-    AFFINITY_OFF, AFFINITY_STRONG, AFFINITY_WEAK, BATCH_MAX_NS,
-    DEFAULT_LAT_CRI_THRESH_HIGH, DEFAULT_LAT_CRI_THRESH_LOW,
-    HEAVY_DEMOTION_NS, HEAVY_ENTER_PCT, HEAVY_EXIT_PCT,
-    HIST_BUCKETS, LIGHT_DEMOTION_NS, LIGHT_ENTER_PCT, LIGHT_EXIT_PCT, MIXED_DEMOTION_NS,
-    STABILITY_THRESHOLD,
+    adjust_classifier_thresholds, adjust_timer_interval, compute_p99_from_histogram,
+    compute_stability_score, deadline_reached, detect_regime, idle_quiet, merge_reflex,
+    pooled_p99_ns, regime_knobs, resync_tightened, scaled_regime_knobs, should_print_telemetry,
+    should_reflex_tighten, sleep_adjust_batch_ns, suspend_gap_ns, tick_decisions, BacklogWatch,
+    ClassifierState, ClockSample, IdleStretch, KnobWrite, KnobWriteCounts, KnobWriter,
+    QueueDepthWatch, Reflex, ReflexAction, Regime, SliceBounds, SloCompliance, TimerIntervalState,
+    TuningKnobs, AFFINITY_OFF, AFFINITY_STRONG, AFFINITY_WEAK, BACKLOG_WARN_TICKS, BATCH_MAX_NS,
+    CLS_HIGH_MAX, CLS_HIGH_MIN, CLS_HOLD_TICKS, CLS_MIN_WAKEUPS, CLS_STEP,
+    DEFAULT_LAT_CRI_THRESH_HIGH, DEFAULT_LAT_CRI_THRESH_LOW, HEAVY_DEMOTION_NS, HEAVY_ENTER_PCT,
+    HEAVY_EXIT_PCT, HIST_BUCKETS, IDLE_DISPATCH_RATE, IDLE_STRETCH_AFTER_NS, KNOB_NAMES,
+    LIGHT_DEMOTION_NS, LIGHT_ENTER_PCT, LIGHT_EXIT_PCT, MAX_SLICE_NS, MIN_SLICE_NS,
+    MIXED_DEMOTION_NS, QDEPTH_GROWTH_TICKS, REFLEX_FLOOR_NS, RELAX_HOLD_TICKS, RESUME_GAP_NS,
+    SLO_MAX_PRESSURE, SPIKE_TICKS, STABILITY_THRESHOLD, STRETCHED_STEP, STRETCHED_TICK,
+    TIMER_LAT_KICK_CEIL_US, TIMER_MAX_MULT, TIMER_WIDEN_HOLD_TICKS,
 };
 
 // REGIME DETECTION (SCHMITT TRIGGER)
@@ -119,9 +128,19 @@ fn demotion_threshold_in_knobs() {
 // TUNING KNOBS ABI
 
 #[test]
-fn tuning_knobs_size_is_8_u64() {
+fn tuning_knobs_size_is_10_u64() {
     // MUST MATCH struct tuning_knobs IN intf.h (10 x u64 = 80 BYTES)
     assert_eq!(std::mem::size_of::<TuningKnobs>(), 80);
+    assert_eq!(KNOB_NAMES.len(), 10);
+}
+
+#[test]
+fn tuning_knobs_decode_requires_exact_size() {
+    // A GROWN tuning_knobs STRUCT MUST FAIL TO DECODE, NOT DECODE SHIFTED
+    let bytes = [0u8; 88];
+    assert!(TuningKnobs::from_bytes(&bytes[..80]).is_some());
+    assert!(TuningKnobs::from_bytes(&bytes[..72]).is_none());
+    assert!(TuningKnobs::from_bytes(&bytes).is_none());
 }
 
 #[test]
@@ -137,6 +156,49 @@ fn tuning_knobs_default() {
     assert_eq!(k.affinity_mode, AFFINITY_OFF);
 }
 
+#[test]
+fn tuning_knobs_default_is_mixed_without_affinity() {
+    let mixed = regime_knobs(Regime::Mixed);
+    assert_eq!(
+        TuningKnobs::default(),
+        TuningKnobs {
+            affinity_mode: AFFINITY_OFF,
+            ..mixed
+        }
+    );
+}
+
+// SINGLE SOURCE OF TRUTH: REGIME PROFILES ARE DECLARED ONLY IN tuning.rs
+
+#[test]
+fn regime_profiles_declared_once() {
+    let others = [
+        ("adaptive.rs", include_str!("../src/adaptive.rs")),
+        ("scheduler.rs", include_str!("../src/scheduler.rs")),
+        ("main.rs", include_str!("../src/main.rs")),
+        ("ctl.rs", include_str!("../src/ctl.rs")),
+    ];
+    for (file, src) in others {
+        for line in src.lines().map(str::trim_start) {
+            assert!(
+                !line.contains("enum Regime") && !line.contains("fn regime_knobs"),
+                "{} REDECLARES THE REGIME TYPE/PROFILES: {}",
+                file,
+                line
+            );
+            for prefix in ["LIGHT_", "MIXED_", "HEAVY_"] {
+                assert!(
+                    !line.starts_with(&format!("const {}", prefix))
+                        && !line.starts_with(&format!("pub const {}", prefix)),
+                    "{} REDECLARES A REGIME CONSTANT: {}",
+                    file,
+                    line
+                );
+            }
+        }
+    }
+}
+
 // STABILITY MODE
 
 #[test]
@@ -170,21 +232,200 @@ fn stability_score_resets_on_p99_above_half_ceiling() {
     assert_eq!(score, 0);
 }
 
+// IDLE STRETCH
+
+const SEC_NS: u64 = 1_000_000_000;
+
+#[test]
+fn idle_quiet_needs_light_max_stability_and_a_low_rate() {
+    let max = STABILITY_THRESHOLD;
+    assert!(idle_quiet(
+        Regime::Light,
+        max,
+        IDLE_DISPATCH_RATE - 1,
+        SEC_NS
+    ));
+    // AT THE RATE, NOT UNDER IT
+    assert!(!idle_quiet(Regime::Light, max, IDLE_DISPATCH_RATE, SEC_NS));
+    // THE RATE IS PER SECOND OF ELAPSED TIME: A 5S TICK MAY DISPATCH MORE
+    assert!(idle_quiet(
+        Regime::Light,
+        max,
+        4 * IDLE_DISPATCH_RATE,
+        5 * SEC_NS
+    ));
+    assert!(!idle_quiet(Regime::Light, max - 1, 0, SEC_NS));
+    assert!(!idle_quiet(Regime::Mixed, max, 0, SEC_NS));
+    assert!(!idle_quiet(Regime::Heavy, max, 0, SEC_NS));
+    // NO ELAPSED TIME: NO EVIDENCE OF IDLENESS
+    assert!(!idle_quiet(Regime::Light, max, 0, 0));
+}
+
+#[test]
+fn stretch_after_a_quiet_minute_and_window() {
+    let normal = (Duration::from_secs(1), 10);
+    let mut st = IdleStretch::default();
+    assert_eq!(st.window(normal), normal);
+    let ticks = IDLE_STRETCH_AFTER_NS / SEC_NS;
+    for _ in 1..ticks {
+        assert!(!st.tick(true, SEC_NS));
+    }
+    assert!(!st.stretched());
+    // THE 60TH QUIET SECOND STRETCHES
+    assert!(st.tick(true, SEC_NS));
+    assert!(st.stretched());
+    let steps = (STRETCHED_TICK.as_secs() / STRETCHED_STEP.as_secs()) as u32;
+    assert_eq!(st.window(normal), (STRETCHED_TICK, steps));
+    assert_eq!(st.window(normal), (Duration::from_secs(5), 5));
+    // STAYING QUIET: NO CHANGE TO REPORT
+    assert!(!st.tick(true, 5 * SEC_NS));
+    assert!(st.stretched());
+
+    // ONE LOUD TICK BEFORE THE MINUTE RESTARTS THE COUNT
+    let mut st = IdleStretch::default();
+    st.tick(true, 59 * SEC_NS);
+    assert!(!st.tick(false, SEC_NS));
+    st.tick(true, 59 * SEC_NS);
+    assert!(!st.stretched());
+}
+
+#[test]
+fn stretched_tick_snaps_back_on_activity() {
+    let mut st = IdleStretch::default();
+    st.tick(true, IDLE_STRETCH_AFTER_NS);
+    assert!(st.stretched());
+
+    // QUIET STEPS KEEP THE STRETCH
+    assert!(!st.step(IDLE_DISPATCH_RATE / 2, SEC_NS));
+    assert!(!st.step(0, SEC_NS));
+    // NO INTERVAL: NO SIGNAL EITHER WAY
+    assert!(!st.step(1_000_000, 0));
+    assert!(st.stretched());
+
+    // THE FIRST BUSY STEP SNAPS, AND THE QUIET MINUTE STARTS OVER
+    assert!(st.step(IDLE_DISPATCH_RATE, SEC_NS));
+    assert!(!st.stretched());
+    assert_eq!(
+        st.window((Duration::from_secs(1), 10)).0,
+        Duration::from_secs(1)
+    );
+    st.tick(true, IDLE_STRETCH_AFTER_NS - 1);
+    assert!(!st.stretched());
+    // NOT STRETCHED: step() NEVER SNAPS
+    assert!(!st.step(u64::MAX, SEC_NS));
+
+    // A TICK THAT IS NOT QUIET SNAPS TOO, AND REPORTS THE CHANGE
+    let mut st = IdleStretch::default();
+    st.tick(true, IDLE_STRETCH_AFTER_NS);
+    assert!(st.tick(false, 5 * SEC_NS));
+    assert!(!st.stretched());
+
+    // A ctl REQUEST OR CTRL+C: snap() DIRECTLY
+    let mut st = IdleStretch::default();
+    st.tick(true, IDLE_STRETCH_AFTER_NS);
+    st.snap();
+    assert_eq!(st, IdleStretch::default());
+}
+
+// PAUSE GATING
+
+#[test]
+fn pause_skips_every_adaptive_decision() {
+    let d = tick_decisions(true, false);
+    assert!(!d.detect_regime);
+    assert!(!d.tighten);
+    assert!(!d.relax);
+    assert!(!d.batch_tune);
+}
+
+#[test]
+fn pin_only_skips_regime_detection() {
+    let d = tick_decisions(false, true);
+    assert!(!d.detect_regime);
+    assert!(d.tighten && d.relax && d.batch_tune);
+
+    let d = tick_decisions(false, false);
+    assert!(d.detect_regime && d.tighten && d.relax && d.batch_tune);
+
+    // PAUSE WINS OVER PIN
+    assert_eq!(tick_decisions(true, true), tick_decisions(true, false));
+}
+
+#[test]
+fn lightweight_keeps_detection_without_the_reflex() {
+    // NO WAKEUP SAMPLES: REGIME DETECTION RUNS ON STATS, NOTHING TIGHTENS
+    let d = tick_decisions(false, false).lightweight(true);
+    assert!(d.detect_regime && d.relax && d.batch_tune);
+    assert!(!d.tighten);
+    assert_eq!(
+        tick_decisions(false, true).lightweight(false),
+        tick_decisions(false, true)
+    );
+    assert_eq!(
+        tick_decisions(true, false).lightweight(true),
+        tick_decisions(true, false)
+    );
+
+    // EVEN A SPIKE THAT WOULD TIGHTEN (A STALE HISTOGRAM) DOES NOT
+    let baseline = scaled_regime_knobs(Regime::Mixed, 8);
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let mut reflex = Reflex::default();
+    for _ in 0..6 {
+        assert_eq!(
+            reflex.tick(&d, Regime::Mixed, spike, spike, &baseline),
+            None
+        );
+    }
+    assert!(!reflex.tightened);
+}
+
+#[test]
+fn resume_resyncs_tightened_from_map() {
+    let baseline = scaled_regime_knobs(Regime::Mixed, 8);
+    let tight = TuningKnobs {
+        slice_ns: baseline.slice_ns * 3 / 4,
+        ..baseline
+    };
+    assert!(resync_tightened(&tight, &baseline));
+    assert!(!resync_tightened(&baseline, &baseline));
+
+    // MANUALLY WIDENED DURING THE PAUSE: NOT A TIGHTEN
+    let wide = TuningKnobs {
+        slice_ns: baseline.slice_ns * 2,
+        ..baseline
+    };
+    assert!(!resync_tightened(&wide, &baseline));
+}
+
 // TELEMETRY GATING
 
 #[test]
 fn should_print_telemetry_always_when_unstable() {
     for tick in 0..10 {
-        assert!(should_print_telemetry(tick, STABILITY_THRESHOLD - 1));
+        assert!(should_print_telemetry(tick, STABILITY_THRESHOLD - 1, 1));
     }
 }
 
 #[test]
 fn should_print_telemetry_alternates_when_stable() {
-    assert!(should_print_telemetry(0, STABILITY_THRESHOLD));
-    assert!(!should_print_telemetry(1, STABILITY_THRESHOLD));
-    assert!(should_print_telemetry(2, STABILITY_THRESHOLD));
-    assert!(!should_print_telemetry(3, STABILITY_THRESHOLD));
+    assert!(should_print_telemetry(0, STABILITY_THRESHOLD, 1));
+    assert!(!should_print_telemetry(1, STABILITY_THRESHOLD, 1));
+    assert!(should_print_telemetry(2, STABILITY_THRESHOLD, 1));
+    assert!(!should_print_telemetry(3, STABILITY_THRESHOLD, 1));
+}
+
+#[test]
+fn should_print_telemetry_honors_interval() {
+    // --telemetry-interval 5: EVERY 5TH TICK, EVERY 10TH WHILE HIBERNATING
+    let printed = |score| {
+        (0..20)
+            .filter(|&t| should_print_telemetry(t, score, 5))
+            .collect::<Vec<u64>>()
+    };
+    assert_eq!(printed(0), vec![0, 5, 10, 15]);
+    assert_eq!(printed(STABILITY_THRESHOLD), vec![0, 10]);
+    // 0 IS TREATED AS 1, NEVER A DIVIDE BY ZERO
+    assert!(should_print_telemetry(3, 0, 0));
 }
 
 // PER-TIER P99
@@ -219,6 +460,19 @@ fn per_tier_p99_reset_clears_all() {
     assert_eq!(compute_p99_from_histogram(&empty), 0);
 }
 
+#[test]
+fn pooled_p99_spans_tiers() {
+    // 99 FAST LAT_CRITICAL WAKEUPS HIDE ONE SLOW BATCH WAKEUP; 98 DO NOT
+    let mut tiers = [[0u64; HIST_BUCKETS]; 3];
+    tiers[0][8] = 1; // 5MS BUCKET
+    tiers[2][1] = 99; // 25US BUCKET
+    assert_eq!(pooled_p99_ns(&tiers), 25_000);
+    tiers[2][1] = 98;
+    tiers[1][8] = 1;
+    assert_eq!(pooled_p99_ns(&tiers), 5_000_000);
+    assert_eq!(pooled_p99_ns(&[[0; HIST_BUCKETS]; 3]), 0);
+}
+
 #[test]
 fn reflex_tightens_on_interactive_p99() {
     let ceiling = Regime::Mixed.p99_ceiling(); // 5MS
@@ -292,3 +546,922 @@ fn sleep_adjust_caps_at_max() {
     assert_eq!(result, BATCH_MAX_NS);
 }
 
+// SHARED DSQ QUEUE DEPTH
+
+fn feed(
+    w: &mut QueueDepthWatch,
+    depths: &[u64],
+    idle_pct: u64,
+    nr_cpus: u64,
+) -> Vec<(bool, bool, bool)> {
+    depths
+        .iter()
+        .map(|&d| {
+            let v = w.update(d, idle_pct, nr_cpus);
+            (v.growing, v.stuck, v.stuck_onset)
+        })
+        .collect()
+}
+
+#[test]
+fn qdepth_growth_after_sustained_rise() {
+    // 8 CPUS, DEPTH CLIMBING PAST ONE TASK PER CPU, NO IDLE HITS
+    let mut w = QueueDepthWatch::default();
+    let v = feed(&mut w, &[4, 8, 12, 16, 20, 24], 0, 8);
+    let growing: Vec<bool> = v.iter().map(|t| t.0).collect();
+    assert_eq!(QDEPTH_GROWTH_TICKS, 5);
+    assert_eq!(growing, [false, false, false, false, true, true]);
+}
+
+#[test]
+fn qdepth_flat_tick_resets_growth() {
+    let mut w = QueueDepthWatch::default();
+    feed(&mut w, &[10, 20, 30, 40], 0, 8);
+    // PLATEAU: THE RUN RESTARTS
+    assert!(!w.update(40, 0, 8).growing);
+    let v = feed(&mut w, &[41, 42, 43, 44], 0, 8);
+    assert!(v.iter().all(|t| !t.0));
+    assert!(w.update(45, 0, 8).growing);
+}
+
+#[test]
+fn qdepth_small_rising_queue_not_growing() {
+    // RISING EVERY TICK BUT NEVER ABOVE ONE TASK PER CPU
+    let mut w = QueueDepthWatch::default();
+    let v = feed(&mut w, &[1, 2, 3, 4, 5, 6, 7, 8], 0, 8);
+    assert!(v.iter().all(|t| !t.0));
+}
+
+#[test]
+fn qdepth_deep_with_idle_cpus_is_stuck() {
+    // 4 CPUS: DEEP = MORE THAN 16 QUEUED
+    let mut w = QueueDepthWatch::default();
+    let v = feed(&mut w, &[17, 30, 30, 2, 40], 50, 4);
+    let stuck: Vec<bool> = v.iter().map(|t| t.1).collect();
+    let onset: Vec<bool> = v.iter().map(|t| t.2).collect();
+    assert_eq!(stuck, [true, true, true, false, true]);
+    // ONE LOUD FLAG PER EPISODE
+    assert_eq!(onset, [true, false, false, false, true]);
+}
+
+#[test]
+fn qdepth_deep_without_idle_is_load() {
+    // SATURATED MACHINE: DEEP QUEUES ARE EXPECTED, NOT A DISPATCH BUG
+    let mut w = QueueDepthWatch::default();
+    let v = feed(&mut w, &[100, 200, 300], 5, 4);
+    assert!(v.iter().all(|t| !t.1 && !t.2));
+}
+
+#[test]
+fn qdepth_growth_feeds_regime_as_saturated() {
+    let mut w = QueueDepthWatch::default();
+    let mut last = Default::default();
+    for d in [20, 40, 60, 80, 100] {
+        last = w.update(d, 40, 8);
+    }
+    assert!(last.growing);
+    // 40% IDLE WOULD BE MIXED; A GROWING BACKLOG READS AS HEAVY
+    assert_eq!(last.regime_idle_pct(40), 0);
+    assert_eq!(
+        detect_regime(Regime::Mixed, last.regime_idle_pct(40)),
+        Regime::Heavy
+    );
+    assert_eq!(w.update(100, 40, 8).regime_idle_pct(40), 40);
+}
+
+#[test]
+fn qdepth_tracks_max() {
+    let mut w = QueueDepthWatch::default();
+    feed(&mut w, &[3, 90, 7, 0], 0, 8);
+    assert_eq!(w.max(), 90);
+}
+
+// DISPATCH BACKLOG EARLY WARNING
+
+fn feed_backlog(w: &mut BacklogWatch, nets: &[i64]) -> Vec<(u64, bool, bool)> {
+    nets.iter()
+        .map(|&n| {
+            let v = w.update(n, false);
+            (v.backlog, v.warn, v.warn_onset)
+        })
+        .collect()
+}
+
+#[test]
+fn backlog_warns_after_sustained_growth() {
+    // DISPATCHES STOPPED, ENQUEUES CONTINUE: +100 EVERY TICK
+    let mut w = BacklogWatch::default();
+    let v = feed_backlog(&mut w, &[100; 7]);
+    assert_eq!(BACKLOG_WARN_TICKS, 5);
+    let warn: Vec<bool> = v.iter().map(|t| t.1).collect();
+    let onset: Vec<bool> = v.iter().map(|t| t.2).collect();
+    assert_eq!(warn, [false, false, false, false, false, true, true]);
+    assert_eq!(onset, [false, false, false, false, false, true, false]);
+    assert_eq!(v[6].0, 700);
+}
+
+#[test]
+fn backlog_floors_at_zero_on_direct_dispatch() {
+    // HEALTHY: DIRECT DISPATCHES OUTNUMBER ENQUEUES, NO NEGATIVE CREDIT BANKED
+    let mut w = BacklogWatch::default();
+    let v = feed_backlog(&mut w, &[-5_000, -5_000, 40]);
+    assert_eq!(v[1].0, 0);
+    assert_eq!(v[2].0, 40);
+    // A DRAINING TICK ENDS THE RUN
+    let v = feed_backlog(&mut w, &[10, 10, 10, 10, -1, 10, 10, 10, 10, 10]);
+    assert!(v.iter().all(|t| !t.1));
+}
+
+#[test]
+fn backlog_reset_starts_over() {
+    let mut w = BacklogWatch::default();
+    feed_backlog(&mut w, &[50; 6]);
+    assert!(w.update(50, false).warn);
+    // BPF RESTARTED: THE OLD ESTIMATE IS GONE, THE WARNING CLEARS
+    let v = w.update(50, true);
+    assert_eq!((v.backlog, v.warn), (0, false));
+    assert_eq!(w.max(), 350);
+    // A NEW EPISODE FLAGS AGAIN
+    let v = feed_backlog(&mut w, &[50; 6]);
+    assert!(v[5].2);
+}
+
+#[test]
+fn backlog_saturates_instead_of_wrapping() {
+    let mut w = BacklogWatch::default();
+    assert_eq!(w.update(i64::MAX, false).backlog, i64::MAX as u64);
+    assert_eq!(w.update(i64::MAX, false).backlog, u64::MAX - 1);
+    assert_eq!(w.update(i64::MAX, false).backlog, u64::MAX);
+    assert_eq!(w.update(i64::MIN, false).backlog, u64::MAX - (1 << 63));
+}
+
+// KICK-DRIVEN TIMER INTERVAL
+
+const TIMER_BASE: u64 = 1_000_000;
+
+// RUN A SEQUENCE OF (soft_kicks, lat_kick_us) TICKS, RETURN EACH INTERVAL
+fn run_timer(start: u64, ticks: &[(u64, u64)]) -> Vec<u64> {
+    let mut cur = start;
+    let mut st = TimerIntervalState::default();
+    ticks
+        .iter()
+        .map(|&(soft, lat)| {
+            (cur, st) = adjust_timer_interval(cur, TIMER_BASE, soft, lat, st);
+            cur
+        })
+        .collect()
+}
+
+#[test]
+fn timer_widens_after_quiet_hold() {
+    let out = run_timer(TIMER_BASE, &[(0, 10); 5]);
+    assert_eq!(TIMER_WIDEN_HOLD_TICKS, 5);
+    assert_eq!(out[..4], [TIMER_BASE; 4]);
+    assert_eq!(out[4], 1_250_000);
+}
+
+#[test]
+fn timer_busy_tick_resets_hold() {
+    // 4 QUIET, 1 BUSY, 4 QUIET: NEVER A FULL HOLD
+    let mut ticks = vec![(0, 10); 4];
+    ticks.push((500, 10));
+    ticks.extend([(1, 10); 4]);
+    let out = run_timer(TIMER_BASE, &ticks);
+    assert!(out.iter().all(|&v| v == TIMER_BASE));
+}
+
+#[test]
+fn timer_widening_capped() {
+    let out = run_timer(TIMER_BASE, &[(0, 0); 200]);
+    assert_eq!(*out.last().unwrap(), TIMER_BASE * TIMER_MAX_MULT);
+    assert!(out.iter().all(|&v| v <= TIMER_BASE * TIMER_MAX_MULT));
+}
+
+#[test]
+fn timer_tightens_immediately_on_kick_latency() {
+    // 4x BASELINE: EACH SLOW HARD-KICK TICK HALVES THE EXCESS, THEN SNAPS
+    let slow = TIMER_LAT_KICK_CEIL_US + 1;
+    let out = run_timer(TIMER_BASE * 4, &[(0, slow); 5]);
+    assert_eq!(
+        out,
+        [2_500_000, 1_750_000, 1_375_000, 1_187_500, TIMER_BASE]
+    );
+}
+
+#[test]
+fn timer_tighten_restarts_hold() {
+    let mut ticks = vec![(0, 0); 4];
+    ticks.push((0, TIMER_LAT_KICK_CEIL_US + 1));
+    ticks.extend([(0, 0); 4]);
+    let out = run_timer(TIMER_BASE, &ticks);
+    assert!(out.iter().all(|&v| v == TIMER_BASE));
+}
+
+#[test]
+fn timer_reclamps_on_baseline_change() {
+    // REGIME MOVED THE BASELINE ABOVE THE CURRENT INTERVAL
+    let (v, _) = adjust_timer_interval(500_000, TIMER_BASE, 100, 0, Default::default());
+    assert_eq!(v, TIMER_BASE);
+    let (v, _) = adjust_timer_interval(9_000_000, TIMER_BASE, 100, 0, Default::default());
+    assert_eq!(v, TIMER_BASE * TIMER_MAX_MULT);
+}
+
+// ADAPTIVE CLASSIFIER THRESHOLD (LAT_CRITICAL SHARE OF WAKEUPS)
+
+const CLS_HOT: [u64; 3] = [600, 200, 200]; // 20% LAT_CRITICAL
+const CLS_COLD: [u64; 3] = [700, 299, 1]; // 0.1%
+const CLS_CALM: [u64; 3] = [700, 250, 50]; // 5%
+
+// RUN A SEQUENCE OF PER-TIER WAKEUP TICKS, RETURN EACH HIGH THRESHOLD
+fn run_classifier(start: u64, ticks: &[[u64; 3]]) -> Vec<u64> {
+    let mut high = start;
+    let mut st = ClassifierState::default();
+    ticks
+        .iter()
+        .map(|&w| {
+            (high, st) = adjust_classifier_thresholds(high, DEFAULT_LAT_CRI_THRESH_LOW, w, st);
+            high
+        })
+        .collect()
+}
+
+#[test]
+fn classifier_raises_after_hot_hold() {
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[CLS_HOT; 5]);
+    assert_eq!(CLS_HOLD_TICKS, 5);
+    assert_eq!(out[..4], [DEFAULT_LAT_CRI_THRESH_HIGH; 4]);
+    assert_eq!(out[4], DEFAULT_LAT_CRI_THRESH_HIGH + CLS_STEP);
+    // THE NEXT STEP NEEDS ANOTHER FULL HOLD
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[CLS_HOT; 10]);
+    assert_eq!(out[8], DEFAULT_LAT_CRI_THRESH_HIGH + CLS_STEP);
+    assert_eq!(out[9], DEFAULT_LAT_CRI_THRESH_HIGH + 2 * CLS_STEP);
+}
+
+#[test]
+fn classifier_lowers_after_cold_hold() {
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[CLS_COLD; 5]);
+    assert_eq!(out[3], DEFAULT_LAT_CRI_THRESH_HIGH);
+    assert_eq!(out[4], DEFAULT_LAT_CRI_THRESH_HIGH - CLS_STEP);
+}
+
+#[test]
+fn classifier_in_band_or_flipping_resets_hold() {
+    // 4 HOT, 1 CALM, 4 HOT, THEN 4 COLD, 1 HOT, 4 COLD: NEVER A FULL HOLD
+    let mut ticks = vec![CLS_HOT; 4];
+    ticks.push(CLS_CALM);
+    ticks.extend([CLS_HOT; 4]);
+    ticks.extend([CLS_COLD; 4]);
+    ticks.push(CLS_HOT);
+    ticks.extend([CLS_COLD; 4]);
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &ticks);
+    assert!(out.iter().all(|&v| v == DEFAULT_LAT_CRI_THRESH_HIGH));
+}
+
+#[test]
+fn classifier_ignores_quiet_ticks() {
+    // ALL LAT_CRITICAL, BUT TOO FEW WAKEUPS TO MEAN ANYTHING
+    let sparse = [0, 0, CLS_MIN_WAKEUPS - 1];
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[sparse; 20]);
+    assert!(out.iter().all(|&v| v == DEFAULT_LAT_CRI_THRESH_HIGH));
+    // A QUIET TICK BREAKS A HOLD TOO
+    let mut ticks = vec![CLS_HOT; 4];
+    ticks.push([0; 3]);
+    ticks.extend([CLS_HOT; 4]);
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &ticks);
+    assert!(out.iter().all(|&v| v == DEFAULT_LAT_CRI_THRESH_HIGH));
+}
+
+#[test]
+fn classifier_stays_within_bounds() {
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[CLS_HOT; 200]);
+    assert_eq!(*out.last().unwrap(), CLS_HIGH_MAX);
+    let out = run_classifier(DEFAULT_LAT_CRI_THRESH_HIGH, &[CLS_COLD; 200]);
+    assert_eq!(*out.last().unwrap(), CLS_HIGH_MIN);
+
+    // A RAISED LOW THRESHOLD LIFTS THE FLOOR: HIGH STAYS A STEP ABOVE IT
+    let (high, _) = adjust_classifier_thresholds(16, 40, [0; 3], ClassifierState::default());
+    assert_eq!(high, 40 + CLS_STEP);
+    let (high, _) = adjust_classifier_thresholds(900, 8, [0; 3], ClassifierState::default());
+    assert_eq!(high, CLS_HIGH_MAX);
+}
+
+// --duration DEADLINE
+
+#[test]
+fn deadline_ends_the_run_once_reached() {
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(3);
+    assert!(!deadline_reached(None, start + Duration::from_secs(86_400)));
+    assert!(!deadline_reached(Some(deadline), start));
+    assert!(!deadline_reached(
+        Some(deadline),
+        deadline - Duration::from_millis(1)
+    ));
+    assert!(deadline_reached(Some(deadline), deadline));
+    assert!(deadline_reached(
+        Some(deadline),
+        deadline + Duration::from_secs(1)
+    ));
+}
+
+// SUSPEND/RESUME DETECTION (MONOTONIC VS BOOTTIME)
+
+fn clocks(mono_s: f64, boot_s: f64) -> ClockSample {
+    ClockSample {
+        mono_ns: (mono_s * 1e9) as u64,
+        boot_ns: (boot_s * 1e9) as u64,
+    }
+}
+
+#[test]
+fn normal_tick_is_not_a_resume() {
+    // BOTH CLOCKS ADVANCE ~1S (SLEEP OVERSHOOT UNDER LOAD INCLUDED)
+    let prev = clocks(100.0, 130.0);
+    assert_eq!(suspend_gap_ns(prev, clocks(101.0, 131.0)), None);
+    assert_eq!(suspend_gap_ns(prev, clocks(104.0, 134.0)), None);
+}
+
+#[test]
+fn boottime_jump_is_a_resume() {
+    // 1S OF MONOTONIC, 1H OF BOOTTIME: SUSPENDED ~3599S
+    let prev = clocks(100.0, 130.0);
+    let gap = suspend_gap_ns(prev, clocks(101.0, 3730.0)).unwrap();
+    assert_eq!(gap, 3_599_000_000_000);
+}
+
+#[test]
+fn resume_gap_threshold_is_strict() {
+    let prev = ClockSample::default();
+    let at = |gap: u64| ClockSample {
+        mono_ns: 1_000_000_000,
+        boot_ns: 1_000_000_000 + gap,
+    };
+    assert_eq!(suspend_gap_ns(prev, at(RESUME_GAP_NS)), None);
+    assert_eq!(
+        suspend_gap_ns(prev, at(RESUME_GAP_NS + 1)),
+        Some(RESUME_GAP_NS + 1)
+    );
+}
+
+#[test]
+fn live_clocks_back_to_back_no_resume() {
+    // LIVE CLOCKS: NO SUSPEND BETWEEN TWO BACK-TO-BACK READS
+    let a = ClockSample::now();
+    let b = ClockSample::now();
+    assert!(b.mono_ns >= a.mono_ns);
+    assert!(b.boot_ns >= a.boot_ns);
+    assert_eq!(suspend_gap_ns(a, b), None);
+}
+
+// REGIME STATE MACHINE PROPERTIES
+// RANDOM idle_pct SEQUENCES THROUGH detect_regime, THE 2-TICK HOLD AND THE
+// REAL tuning::Reflex. THE HOLD AND PAUSE/RESUME BOOKKEEPING LIVE INLINE IN
+// monitor_loop, SO THE HARNESS REPLICATES THEM LINE FOR LINE.
+
+// XORSHIFT64: DETERMINISTIC, NO DEPENDENCY; A FAILING SEED REPRODUCES
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+// THE tuning_knobs MAP; ONLY THE SIMULATED MONITOR WRITES IT
+struct MapSim {
+    knobs: TuningKnobs,
+}
+
+#[derive(Clone, Copy)]
+enum IdleGen {
+    Uniform,
+    RandomWalk,
+    // HUG THE SCHMITT THRESHOLDS, WHERE THRASHING LIVES
+    Boundary,
+}
+
+fn next_idle(rng: &mut Rng, gen: IdleGen, prev: u64) -> u64 {
+    match gen {
+        IdleGen::Uniform => rng.below(101),
+        IdleGen::RandomWalk => (prev + rng.below(21)).saturating_sub(10).min(100),
+        IdleGen::Boundary => {
+            let edges = [
+                HEAVY_ENTER_PCT,
+                HEAVY_EXIT_PCT,
+                LIGHT_EXIT_PCT,
+                LIGHT_ENTER_PCT,
+            ];
+            let edge = edges[rng.below(edges.len() as u64) as usize];
+            (edge + rng.below(5)).saturating_sub(2)
+        }
+    }
+}
+
+fn check_regime_properties(seed: u64, gen: IdleGen, nr_cpus: u64, ticks: u64) {
+    let mut rng = Rng(seed);
+    let mut regime = Regime::Mixed;
+    let mut pending_regime = regime;
+    let mut regime_hold: u32 = 0;
+    let mut paused = false;
+    let mut reflex = Reflex::default();
+    let mut map = MapSim {
+        knobs: scaled_regime_knobs(regime, nr_cpus),
+    };
+    let mut last_change: Option<u64> = None;
+    let mut idle = 50;
+
+    for tick in 0..ticks {
+        let ctx = format!("SEED {} NR_CPUS {} TICK {}", seed, nr_cpus, tick);
+        idle = next_idle(&mut rng, gen, idle);
+        let ceiling = regime.p99_ceiling();
+        let (p99, tp99_i) = match rng.below(4) {
+            0 => (ceiling + 1 + rng.below(ceiling), 0),
+            1 => (ceiling / 2, ceiling + 1),
+            _ => (rng.below(ceiling + 1), rng.below(ceiling + 1)),
+        };
+
+        // OCCASIONAL CTL PAUSE/RESUME (monitor_loop CtlCommand::Resume)
+        if rng.below(50) == 0 {
+            paused = !paused;
+            if !paused {
+                let baseline = scaled_regime_knobs(regime, nr_cpus);
+                reflex.resync(&map.knobs, &baseline);
+                pending_regime = regime;
+                regime_hold = 0;
+            }
+        }
+
+        let gate = tick_decisions(paused, false);
+        let detected = if gate.detect_regime {
+            detect_regime(regime, idle)
+        } else {
+            regime
+        };
+        let mut regime_changed_this_tick = false;
+        if detected != regime {
+            if detected == pending_regime {
+                regime_hold += 1;
+            } else {
+                pending_regime = detected;
+                regime_hold = 1;
+            }
+            if regime_hold >= 2 {
+                assert!(
+                    !(regime == Regime::Light && detected == Regime::Heavy),
+                    "LIGHT -> HEAVY DIRECTLY: {}",
+                    ctx
+                );
+                if let Some(prev) = last_change {
+                    assert!(tick - prev >= 2, "TWO TRANSITIONS WITHIN 2 TICKS: {}", ctx);
+                }
+                last_change = Some(tick);
+                regime = detected;
+                map.knobs = scaled_regime_knobs(regime, nr_cpus);
+                regime_changed_this_tick = true;
+                reflex.release();
+            }
+        } else {
+            pending_regime = regime;
+            regime_hold = 0;
+        }
+
+        let baseline = scaled_regime_knobs(regime, nr_cpus);
+        if !regime_changed_this_tick {
+            reflex.tick(&gate, regime, p99, tp99_i, &baseline);
+        }
+        // THE MONITOR'S PER-TICK WRITE (monitor_loop, batch tuning left out)
+        if gate.batch_tune {
+            map.knobs = merge_reflex(baseline, reflex.request());
+        }
+
+        // KNOBS: ONLY SLICE + PREEMPT MOVE, WITHIN [FLOOR, BASELINE]
+        let k = map.knobs;
+        assert!(k.slice_ns >= REFLEX_FLOOR_NS, "SLICE BELOW FLOOR: {}", ctx);
+        assert!(
+            k.slice_ns <= baseline.slice_ns,
+            "SLICE ABOVE BASELINE: {}",
+            ctx
+        );
+        assert!(
+            k.preempt_thresh_ns <= baseline.preempt_thresh_ns,
+            "PREEMPT ABOVE BASELINE: {}",
+            ctx
+        );
+        assert_eq!(
+            TuningKnobs {
+                slice_ns: baseline.slice_ns,
+                preempt_thresh_ns: baseline.preempt_thresh_ns,
+                ..k
+            },
+            baseline,
+            "NON-REFLEX KNOB DRIFTED: {}",
+            ctx
+        );
+        if reflex.tightened {
+            assert!(
+                k.slice_ns < baseline.slice_ns,
+                "TIGHTENED AT BASELINE SLICE: {}",
+                ctx
+            );
+        } else {
+            assert_eq!(
+                k.slice_ns, baseline.slice_ns,
+                "UNTIGHTENED BELOW BASELINE: {}",
+                ctx
+            );
+        }
+    }
+}
+
+#[test]
+fn regime_properties_uniform_idle() {
+    for seed in 1..=200 {
+        for nr_cpus in [1, 2, 4, 64] {
+            check_regime_properties(seed, IdleGen::Uniform, nr_cpus, 500);
+        }
+    }
+}
+
+#[test]
+fn regime_properties_random_walk_idle() {
+    for seed in 1..=200 {
+        for nr_cpus in [1, 2, 4, 64] {
+            check_regime_properties(seed, IdleGen::RandomWalk, nr_cpus, 500);
+        }
+    }
+}
+
+#[test]
+fn regime_properties_threshold_hugging_idle() {
+    for seed in 1..=200 {
+        for nr_cpus in [1, 2, 4, 64] {
+            check_regime_properties(seed, IdleGen::Boundary, nr_cpus, 500);
+        }
+    }
+}
+
+#[test]
+fn tighten_noop_when_baseline_at_floor() {
+    // 1 CPU: MIXED BASELINE SLICE IS ALREADY REFLEX_FLOOR_NS
+    let baseline = scaled_regime_knobs(Regime::Mixed, 1);
+    assert_eq!(baseline.slice_ns, REFLEX_FLOOR_NS);
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let mut reflex = Reflex::default();
+    let gate = tick_decisions(false, false);
+    for _ in 0..6 {
+        let action = reflex.tick(&gate, Regime::Mixed, spike, spike, &baseline);
+        assert_eq!(action, None);
+    }
+    assert!(!reflex.tightened);
+    assert_eq!(reflex.tighten_events, 0);
+    assert_eq!(reflex.request(), None);
+}
+
+// SLICE GUARDRAILS
+
+#[test]
+fn slice_bounds_require_min_below_default_below_max() {
+    let default_us = TuningKnobs::default().slice_ns / 1000;
+    let b = SliceBounds::from_us(MIN_SLICE_NS / 1000, MAX_SLICE_NS / 1000).unwrap();
+    assert_eq!(b, SliceBounds::default());
+    assert!(SliceBounds::from_us(200, 8000).is_ok());
+    assert!(SliceBounds::from_us(0, 8000).is_err());
+    assert!(SliceBounds::from_us(default_us, 8000).is_err());
+    assert!(SliceBounds::from_us(200, default_us).is_err());
+    assert!(SliceBounds::from_us(8000, 200).is_err());
+}
+
+#[test]
+fn slice_bounds_clamp_every_slice_knob() {
+    let b = SliceBounds::from_us(800, 10_000).unwrap();
+    let mut k = scaled_regime_knobs(Regime::Mixed, 1);
+    k.burst_slice_ns = 100_000;
+    b.clamp_knobs(&mut k);
+    assert_eq!(k.slice_ns, 800_000);
+    assert_eq!(k.batch_slice_ns, 5_000_000);
+    assert_eq!(k.burst_slice_ns, 800_000);
+    // NOT A SLICE: LEFT ALONE
+    assert_eq!(k.preempt_thresh_ns, 500_000);
+
+    let mut k = regime_knobs(Regime::Heavy);
+    b.clamp_knobs(&mut k);
+    assert_eq!(k.slice_ns, 4_000_000);
+    assert_eq!(k.batch_slice_ns, 10_000_000);
+}
+
+#[test]
+fn reflex_floor_and_ceiling_follow_bounds() {
+    let bounds = SliceBounds::from_us(900, 1800).unwrap();
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let gate = tick_decisions(false, false);
+
+    // FLOOR: 1MS * 3/4 = 750US, RAISED TO THE CONFIGURED 900US
+    let mixed = regime_knobs(Regime::Mixed);
+    let mut reflex = Reflex {
+        bounds,
+        ..Reflex::default()
+    };
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, spike, 0, &mixed);
+    }
+    assert_eq!(reflex.request(), Some(900_000));
+
+    // DEFAULT 100US FLOOR: THE REFLEX STILL STOPS AT REFLEX_FLOOR_NS
+    assert_eq!(SliceBounds::default().min_ns, MIN_SLICE_NS);
+    let narrow = TuningKnobs {
+        slice_ns: 600_000,
+        ..mixed
+    };
+    let mut reflex = Reflex::default();
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, spike, 0, &narrow);
+    }
+    assert_eq!(reflex.request(), Some(REFLEX_FLOOR_NS));
+
+    // CEILING: 2MS * 3/4 = 1.5MS; RELAX STOPS AT 1.8MS, NOT THE 2MS BASELINE
+    let wide = TuningKnobs {
+        slice_ns: 2_000_000,
+        ..mixed
+    };
+    let mut reflex = Reflex {
+        bounds,
+        ..Reflex::default()
+    };
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, spike, 0, &wide);
+    }
+    assert_eq!(reflex.request(), Some(1_500_000));
+    let last = (0..10)
+        .filter_map(|_| reflex.tick(&gate, Regime::Mixed, 0, 0, &wide))
+        .last();
+    assert_eq!(
+        last,
+        Some(ReflexAction::Relaxed {
+            slice_ns: 1_800_000
+        })
+    );
+    assert_eq!(reflex.request(), None);
+    assert_eq!((reflex.tighten_events, reflex.relax_steps), (1, 1));
+}
+
+// WAKE LATENCY SLO (--slo-p99-us)
+
+fn slo_reflex(slo_us: u64) -> Reflex {
+    Reflex {
+        slo_ns: Some(slo_us * 1000),
+        ..Reflex::default()
+    }
+}
+
+#[test]
+fn slo_replaces_every_regime_ceiling() {
+    let plain = Reflex::default();
+    let slo = slo_reflex(1500);
+    for r in [Regime::Light, Regime::Mixed, Regime::Heavy] {
+        assert_eq!(plain.ceiling(r), r.p99_ceiling());
+        assert_eq!(plain.slo_pressure(r), 1);
+        assert_eq!(slo.ceiling(r), 1_500_000);
+    }
+    // 3MS / 1.5MS = 2; MIXED 5MS AND HEAVY 10MS CAP AT SLO_MAX_PRESSURE
+    assert_eq!(slo.slo_pressure(Regime::Light), 2);
+    assert_eq!(slo.slo_pressure(Regime::Mixed), SLO_MAX_PRESSURE);
+    assert_eq!(slo.slo_pressure(Regime::Heavy), SLO_MAX_PRESSURE);
+    // LOOSER THAN THE REGIME'S OWN CEILING: NO EXTRA PRESSURE
+    assert_eq!(slo_reflex(8000).slo_pressure(Regime::Mixed), 1);
+}
+
+#[test]
+fn slo_tightens_below_the_regime_ceiling() {
+    let gate = tick_decisions(false, false);
+    let mixed = regime_knobs(Regime::Mixed);
+    // 2MS P99 IS INSIDE MIXED'S 5MS CEILING BUT MISSES A 1.5MS SLO
+    let mut plain = Reflex::default();
+    let mut slo = slo_reflex(1500);
+    for _ in 0..SPIKE_TICKS {
+        plain.tick(&gate, Regime::Mixed, 2_000_000, 0, &mixed);
+        slo.tick(&gate, Regime::Mixed, 2_000_000, 0, &mixed);
+    }
+    assert_eq!(plain.request(), None);
+    assert!(slo.request().is_some());
+}
+
+#[test]
+fn slo_pressure_deepens_the_tighten() {
+    let gate = tick_decisions(false, false);
+    let mixed = regime_knobs(Regime::Mixed);
+    let bounds = SliceBounds::from_us(100, 25_000).unwrap();
+    // PRESSURE 1, 2, 3: 3/4, 5/8, 1/2 OF THE 1MS MIXED BASELINE
+    for (slo_us, slice_ns) in [(5000, 750_000), (2500, 625_000), (1500, 500_000)] {
+        let mut reflex = Reflex {
+            bounds,
+            ..slo_reflex(slo_us)
+        };
+        for _ in 0..SPIKE_TICKS {
+            reflex.tick(&gate, Regime::Mixed, 20_000_000, 0, &mixed);
+        }
+        assert_eq!(reflex.request(), Some(slice_ns), "SLO {}us", slo_us);
+    }
+}
+
+#[test]
+fn slo_pressure_slows_the_relax() {
+    let gate = tick_decisions(false, false);
+    let mixed = regime_knobs(Regime::Mixed);
+    let mut reflex = slo_reflex(1500);
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, 2_000_000, 0, &mixed);
+    }
+    // GOOD TICKS UNTIL THE FIRST RELAX STEP: THE HOLD x PRESSURE
+    let hold = (1..=20)
+        .find(|_| {
+            reflex
+                .tick(&gate, Regime::Mixed, 1_000_000, 0, &mixed)
+                .is_some()
+        })
+        .unwrap();
+    assert_eq!(hold, RELAX_HOLD_TICKS * SLO_MAX_PRESSURE as u32);
+}
+
+#[test]
+fn slo_compliance_counts_windows() {
+    let mut c = SloCompliance::default();
+    assert_eq!(c.permille(), None);
+    assert_eq!(
+        c.line(1_500_000),
+        "SLO MET: - of windows (p99 <= 1500us, 0/0)"
+    );
+    assert!(c.record(1_000_000, 1_500_000));
+    assert!(c.record(1_500_000, 1_500_000));
+    assert!(!c.record(2_000_000, 1_500_000));
+    assert_eq!((c.met, c.windows), (2, 3));
+    assert_eq!(c.permille(), Some(666));
+    assert_eq!(
+        c.line(1_500_000),
+        "SLO MET: 66.6% of windows (p99 <= 1500us, 2/3)"
+    );
+    for _ in 0..33 {
+        c.record(0, 1_500_000);
+    }
+    assert_eq!(
+        c.line(1_500_000),
+        "SLO MET: 97.2% of windows (p99 <= 1500us, 35/36)"
+    );
+}
+
+// REFLEX -> MONITOR MERGE
+
+#[test]
+fn merge_reflex_request_sets_slice_and_caps_preempt() {
+    let mixed = regime_knobs(Regime::Mixed);
+    assert_eq!(merge_reflex(mixed, None), mixed);
+
+    let m = merge_reflex(mixed, Some(750_000));
+    assert_eq!(m.slice_ns, 750_000);
+    assert_eq!(m.preempt_thresh_ns, 750_000);
+    assert_eq!(
+        TuningKnobs {
+            slice_ns: mixed.slice_ns,
+            preempt_thresh_ns: mixed.preempt_thresh_ns,
+            ..m
+        },
+        mixed
+    );
+
+    // A PREEMPT THRESHOLD ALREADY BELOW THE REQUEST STAYS PUT
+    let low = TuningKnobs {
+        preempt_thresh_ns: 500_000,
+        ..mixed
+    };
+    assert_eq!(merge_reflex(low, Some(750_000)).preempt_thresh_ns, 500_000);
+
+    // IDEMPOTENT: RE-MERGING THE MAP VALUE CHANGES NOTHING
+    assert_eq!(merge_reflex(m, Some(750_000)), m);
+}
+
+// THE OLD RACE: A BATCH-TUNE READ-MODIFY-WRITE WITH A STALE READ UNDID A
+// TIGHTEN, AND A RELAX STEP CLOBBERED BATCH TUNING. THE MERGE REBUILDS
+// EACH WRITE FROM (DESIRED, REQUEST), SO BOTH SURVIVE IN EITHER ORDER.
+#[test]
+fn merge_reflex_keeps_tighten_and_batch_tuning_together() {
+    let baseline = regime_knobs(Regime::Mixed);
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let gate = tick_decisions(false, false);
+    let mut reflex = Reflex::default();
+    let tuned = TuningKnobs {
+        batch_slice_ns: sleep_adjust_batch_ns(baseline.batch_slice_ns, 80),
+        ..baseline
+    };
+
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, spike, 0, &baseline);
+    }
+    let map = merge_reflex(tuned, reflex.request());
+    assert_eq!(map.slice_ns, 750_000);
+    assert_eq!(map.batch_slice_ns, tuned.batch_slice_ns);
+
+    // RELAX STEP, THEN A BATCH CHANGE ON THE SAME TICK
+    while reflex.tick(&gate, Regime::Mixed, 0, 0, &baseline).is_none() {}
+    let untuned = TuningKnobs {
+        batch_slice_ns: baseline.batch_slice_ns,
+        ..tuned
+    };
+    let map = merge_reflex(untuned, reflex.request());
+    assert_eq!(map.slice_ns, baseline.slice_ns);
+    assert_eq!(map, baseline);
+
+    // REGIME CHANGE MID-TIGHTEN: THE REQUEST IS DROPPED WITH THE OLD BASELINE
+    for _ in 0..SPIKE_TICKS {
+        reflex.tick(&gate, Regime::Mixed, spike, 0, &baseline);
+    }
+    assert!(reflex.request().is_some());
+    reflex.release();
+    let heavy = regime_knobs(Regime::Heavy);
+    assert_eq!(merge_reflex(heavy, reflex.request()), heavy);
+}
+
+// RESUME HANDS THE REFLEX WHAT THE MAP HOLDS, NOT ITS PRE-PAUSE STATE
+#[test]
+fn reflex_resync_adopts_map_slice() {
+    let baseline = regime_knobs(Regime::Mixed);
+    let mut reflex = Reflex::default();
+    let tight = TuningKnobs {
+        slice_ns: 600_000,
+        ..baseline
+    };
+    reflex.resync(&tight, &baseline);
+    assert_eq!(reflex.request(), Some(600_000));
+    assert_eq!(merge_reflex(baseline, reflex.request()).slice_ns, 600_000);
+    reflex.resync(&baseline, &baseline);
+    assert_eq!(reflex.request(), None);
+}
+
+// DIFFERENTIAL KNOB WRITES
+
+#[test]
+fn knob_writer_skips_unchanged_values() {
+    let w = KnobWriter::default();
+    let mut stores = 0;
+    let k = TuningKnobs::default();
+    for _ in 0..3 {
+        w.write(&k, |_| {
+            stores += 1;
+            Ok(())
+        })
+        .unwrap();
+    }
+    let changed = TuningKnobs {
+        slice_ns: 750_000,
+        ..k
+    };
+    let r = w.write(&changed, |_| {
+        stores += 1;
+        Ok(())
+    });
+    assert_eq!(r.unwrap(), KnobWrite::Written);
+    assert_eq!(stores, 2);
+    assert_eq!(
+        w.counts(),
+        KnobWriteCounts {
+            written: 2,
+            skipped: 2
+        }
+    );
+}
+
+#[test]
+fn knob_writer_cache_replaces_the_read_back_once_written() {
+    let w = KnobWriter::default();
+    // NOTHING WRITTEN YET: THE MAP STILL HOLDS BPF'S init DEFAULTS
+    assert_eq!(w.cached(), None);
+
+    let k = TuningKnobs {
+        slice_ns: 750_000,
+        affinity_mode: AFFINITY_STRONG,
+        ..TuningKnobs::default()
+    };
+    w.write(&k, |_| Ok(())).unwrap();
+    assert_eq!(w.cached(), Some(k));
+
+    let next = TuningKnobs {
+        batch_slice_ns: 10_000_000,
+        ..k
+    };
+    w.write(&next, |_| Ok(())).unwrap();
+    assert_eq!(w.cached(), Some(next));
+
+    // A SKIP OR A FAILED STORE LEAVES IT AS IT WAS
+    assert_eq!(w.write(&next, |_| Ok(())).unwrap(), KnobWrite::Skipped);
+    let failed = w.write(&k, |_| anyhow::bail!("EPERM"));
+    assert!(failed.is_err());
+    assert_eq!(w.cached(), Some(next));
+    assert_eq!(w.counts().written, 2);
+    // THE FAILED VALUE IS STILL WRITTEN ON THE NEXT TRY, NOT SKIPPED
+    assert_eq!(w.write(&k, |_| Ok(())).unwrap(), KnobWrite::Written);
+}
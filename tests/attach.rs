@@ -0,0 +1,117 @@
+// PANDEMONIUM ATTACH PRE-CHECK TESTS
+// OPS FILE PARSING, MANAGER DETECTION, BOUNDED TAKEOVER WAIT
+
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use pandemonium::attach::{
+    busy_message, find_manager_in, managed_message, parse_ops, wait_for_detach,
+};
+
+fn fake_proc(name: &str, procs: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pandemonium-attach-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&root);
+    for (pid, comm) in procs {
+        let dir = root.join(pid);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("comm"), format!("{}\n", comm)).unwrap();
+    }
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+#[test]
+fn parse_ops_empty_is_none() {
+    assert_eq!(parse_ops(""), None);
+    assert_eq!(parse_ops("\n"), None);
+    assert_eq!(parse_ops("  \n"), None);
+}
+
+#[test]
+fn parse_ops_names_scheduler() {
+    assert_eq!(parse_ops("lavd_1.0.12\n").as_deref(), Some("lavd_1.0.12"));
+    assert_eq!(parse_ops("pandemonium").as_deref(), Some("pandemonium"));
+}
+
+#[test]
+fn manager_found_by_comm() {
+    let root = fake_proc("found", &[("1", "systemd"), ("4242", "scx_loader")]);
+    assert_eq!(find_manager_in(&root), Some("scx_loader"));
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn manager_ignores_other_processes() {
+    // NON-PID ENTRIES (self, sys) ARE SKIPPED EVEN IF THEY MATCH
+    let root = fake_proc(
+        "absent",
+        &[("1", "systemd"), ("77", "scx_lavd"), ("self", "scx_loader")],
+    );
+    assert_eq!(find_manager_in(&root), None);
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn manager_missing_proc_root() {
+    let root = std::env::temp_dir().join("pandemonium-attach-does-not-exist");
+    assert_eq!(find_manager_in(&root), None);
+}
+
+#[test]
+fn messages_name_the_scheduler() {
+    let busy = busy_message("lavd_1.0.12");
+    assert!(busy.contains("'lavd_1.0.12'"));
+    assert!(busy.contains("--takeover"));
+
+    let managed = managed_message("lavd_1.0.12", "scx_loader");
+    assert!(managed.contains("'lavd_1.0.12'"));
+    assert!(managed.contains("scx_loader"));
+    assert!(managed.contains("scxctl stop"));
+}
+
+#[test]
+fn wait_returns_once_detached() {
+    let polls = Cell::new(0);
+    let mut reports = Vec::new();
+    let r = wait_for_detach(
+        Duration::from_secs(5),
+        || {
+            polls.set(polls.get() + 1);
+            if polls.get() < 3 {
+                Some("lavd".to_string())
+            } else {
+                None
+            }
+        },
+        |ops, _| reports.push(ops.to_string()),
+    );
+    assert!(r.is_ok());
+    assert_eq!(polls.get(), 3);
+    // FIRST POLL ALWAYS REPORTS, SO THE USER SEES WHAT IS BEING WAITED ON
+    assert_eq!(reports, vec!["lavd".to_string()]);
+}
+
+#[test]
+fn wait_already_free_is_immediate() {
+    let mut reported = false;
+    let r = wait_for_detach(Duration::from_secs(5), || None, |_, _| reported = true);
+    assert!(r.is_ok());
+    assert!(!reported);
+}
+
+#[test]
+fn wait_is_bounded() {
+    let r = wait_for_detach(
+        Duration::from_millis(250),
+        || Some("lavd".to_string()),
+        |_, _| {},
+    );
+    let err = r.unwrap_err().to_string();
+    assert!(err.contains("'lavd'"));
+    assert!(err.contains("DID NOT DETACH"));
+}
@@ -0,0 +1,169 @@
+// PANDEMONIUM BENCH RE-ANALYSIS TESTS
+// SAMPLE FILES READ BACK, ARBITRARY PERCENTILES, ASCII CDF ROWS,
+// MANN-WHITNEY U AGAINST HAND-COMPUTED VALUES, CORRUPT SAMPLE FILES
+
+use std::path::PathBuf;
+
+use pandemonium::benchanalyze::{
+    all_pairs, cdf, check_percentiles, compare_line, erfc, find, load, mann_whitney,
+    percentile_table, report, Phase, DEFAULT_PERCENTILES,
+};
+use pandemonium::benchout::{write_samples, BenchLayout};
+use pandemonium::probe::summarize_probe;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pandemonium-benchanalyze-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// n SAMPLES AT fast us, THEN tail SAMPLES AT slow us, ONE PER LINE
+fn bimodal(fast: u32, n: usize, slow: u32, tail: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..n {
+        text.push_str(&format!("{}\n", fast));
+    }
+    for _ in 0..tail {
+        text.push_str(&format!("{}\n", slow));
+    }
+    text
+}
+
+#[test]
+fn percentiles_pool_threads_and_match_the_report() {
+    // TWO THREADS, ONE AS A HISTOGRAM BUCKET
+    let text = "# probe\n0 10\n0 30\n1 20\nhist 1 40 2\n";
+    let phase = Phase::from_text("eevdf", text).unwrap();
+    assert_eq!(phase.samples, [10.0, 20.0, 30.0, 40.0, 40.0]);
+    let summary = summarize_probe(text).unwrap();
+    assert_eq!(phase.percentile(50.0), summary.pooled.median);
+    assert_eq!(phase.percentile(99.0), summary.pooled.p99);
+    assert_eq!(phase.percentile(20.0), 20.0);
+
+    let table = percentile_table(&[phase], &[50.0, 99.9]);
+    assert!(table[0].ends_with("P50     P99.9"), "{:?}", table[0]);
+    assert!(table[1].starts_with("eevdf"), "{:?}", table[1]);
+    assert!(table[1].ends_with("30us      40us"), "{:?}", table[1]);
+
+    assert!(check_percentiles(&DEFAULT_PERCENTILES).is_ok());
+    assert!(check_percentiles(&[100.0]).is_ok());
+    assert!(check_percentiles(&[]).is_err());
+    assert!(check_percentiles(&[0.0]).is_err());
+    assert!(check_percentiles(&[50.0, 100.5]).is_err());
+}
+
+#[test]
+fn cdf_shows_a_bimodal_phase_as_a_flat_stretch() {
+    // 90% AT 50us, 10% AT 5ms: THE P99 ALONE SAYS "5ms"
+    let phase = Phase::from_text("pandemonium", &bimodal(50, 900, 5000, 100)).unwrap();
+    let rows = cdf(&phase, 10);
+    assert_eq!(rows[0], "pandemonium (1000 samples)");
+    // 1, 2, ..., 8192us: THE LAST EDGE COVERS THE WORST SAMPLE
+    assert_eq!(rows.len(), 1 + 14);
+    assert_eq!(rows[6], "  <=       32us |          |   0.0%");
+    assert_eq!(rows[7], "  <=       64us |######### |  90.0%");
+    for row in &rows[8..14] {
+        assert!(row.ends_with("|######### |  90.0%"), "{}", row);
+    }
+    assert_eq!(rows[14], "  <=     8192us |##########| 100.0%");
+
+    let empty = Phase::from_text("eevdf", "").unwrap();
+    assert_eq!(cdf(&empty, 10), ["eevdf: NO SAMPLES"]);
+}
+
+#[test]
+fn mann_whitney_matches_hand_computed_values() {
+    // NO OVERLAP: U = 0, z = -4.5 / sqrt(5.25), TWO-SIDED p = 0.0495
+    let t = mann_whitney(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]).unwrap();
+    assert_eq!(t.u, 0.0);
+    assert!((t.z + 1.9640).abs() < 1e-4, "{:?}", t);
+    assert!((t.p - 0.0495).abs() < 1e-4, "{:?}", t);
+    assert_eq!(t.a_over_b, 0.0);
+    assert!(t.significant());
+
+    // TIES SHARE THEIR AVERAGE RANK: a = [1, 2], b = [2, 3] -> U = 0.5
+    let t = mann_whitney(&[1.0, 2.0], &[2.0, 3.0]).unwrap();
+    assert_eq!(t.u, 0.5);
+    assert!(!t.significant());
+
+    // THE SAME DISTRIBUTION: NO SHIFT, NOTHING TO REPORT. ALL TIES: NO VARIANCE
+    let same = [5.0, 7.0, 9.0, 11.0];
+    let t = mann_whitney(&same, &same).unwrap();
+    assert_eq!((t.z, t.a_over_b), (0.0, 0.5));
+    assert!((t.p - 1.0).abs() < 1e-6);
+    let t = mann_whitney(&[4.0; 5], &[4.0; 7]).unwrap();
+    assert_eq!((t.z, t.p), (0.0, 1.0));
+    assert!(mann_whitney(&[], &[1.0]).is_none());
+
+    assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+    assert!((erfc(1.0) - 0.157_299_2).abs() < 1e-6);
+    assert!((erfc(-1.0) - 1.842_700_8).abs() < 1e-6);
+}
+
+#[test]
+fn bench_directory_reads_back_and_compares_any_two_phases() {
+    let dir = scratch("dir");
+    let out = BenchLayout::new(dir.clone());
+    write_samples(
+        &out.probe_samples("EEVDF (DEFAULT)"),
+        &bimodal(80, 200, 400, 20),
+    )
+    .unwrap();
+    write_samples(&out.probe_samples("PANDEMONIUM"), &bimodal(40, 200, 70, 20)).unwrap();
+    // AN OLDER, UNCOMPRESSED PHASE FILE
+    std::fs::write(dir.join("probe-lavd.txt"), bimodal(60, 220, 60, 0)).unwrap();
+
+    let phases = load(&dir).unwrap();
+    let names: Vec<&str> = phases.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["eevdf", "lavd", "pandemonium"]);
+    assert!(phases.iter().all(|p| p.samples.len() == 220));
+    assert_eq!(all_pairs(&phases).len(), 3);
+
+    // REPORT NAMES FIND THEIR FILES
+    let eevdf = find(&phases, "EEVDF (DEFAULT)").unwrap();
+    let pand = find(&phases, "pandemonium").unwrap();
+    let line = compare_line(eevdf, pand);
+    assert!(
+        line.starts_with("eevdf VS pandemonium: U=48400 "),
+        "{}",
+        line
+    );
+    assert!(line.contains("p<0.0001"), "{}", line);
+    assert!(
+        line.ends_with("P(eevdf>pandemonium)=1.000 SIGNIFICANT (p < 0.05)"),
+        "{}",
+        line
+    );
+    let err = find(&phases, "bogus").unwrap_err().to_string();
+    assert_eq!(err, "NO PHASE 'bogus' (HAVE: eevdf, lavd, pandemonium)");
+
+    // TABLE, A CDF PER PHASE, THEN THE ONE REQUESTED COMPARISON
+    let lines = report(&phases, &[50.0, 99.0], &[(eevdf, pand)]);
+    assert!(lines[0].starts_with("PHASE"));
+    assert_eq!(lines.iter().filter(|l| l.contains(" samples)")).count(), 3);
+    assert_eq!(lines.last().unwrap(), &line);
+
+    let empty = scratch("empty");
+    assert!(load(&empty).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&empty);
+}
+
+#[test]
+fn corrupt_sample_file_names_the_file() {
+    let dir = scratch("corrupt");
+    let path = dir.join("probe-eevdf.txt");
+    std::fs::write(&path, "12\nNaN\n").unwrap();
+    let err = load(&dir).unwrap_err().to_string();
+    assert_eq!(
+        err,
+        format!("{}: PROBE LINE 2: NON-FINITE SAMPLE NaN", path.display())
+    );
+    assert!(Phase::from_text("eevdf", "hist 0 4 18446744073709551615\n").is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,189 @@
+// PANDEMONIUM BENCH OUTPUT DIRECTORY TESTS
+// FILE LAYOUT, PHASE SLUGS, bench-latest LINK, --keep-logs PRUNING,
+// report.json RENDERING
+
+use std::path::{Path, PathBuf};
+
+use pandemonium::benchout::{
+    bench_dirs, phase_slug, point_latest, prune_bench_dirs, read_samples, sample_files,
+    samples_file, write_samples, BenchLayout, Json, LATEST_LINK,
+};
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pandemonium-benchout-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn names(dirs: &[PathBuf]) -> Vec<String> {
+    dirs.iter()
+        .map(|d| d.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn layout_puts_every_artifact_in_one_directory() {
+    let out = BenchLayout::stamped(Path::new("/tmp/pandemonium"), "20260101-120000");
+    assert_eq!(
+        out.dir(),
+        Path::new("/tmp/pandemonium/bench-20260101-120000")
+    );
+    let file = |p: PathBuf| {
+        assert_eq!(p.parent(), Some(out.dir()));
+        p.file_name().unwrap().to_string_lossy().into_owned()
+    };
+    assert_eq!(file(out.build_log()), "build.log");
+    assert_eq!(file(out.report_txt()), "report.txt");
+    assert_eq!(file(out.report_json()), "report.json");
+    assert_eq!(file(out.sched_log("PANDEMONIUM")), "sched-pandemonium.log");
+    assert_eq!(
+        file(out.probe_samples("EEVDF (DEFAULT)")),
+        "probe-eevdf.txt.gz"
+    );
+
+    // --out-dir IS TAKEN VERBATIM
+    let custom = BenchLayout::new(PathBuf::from("/srv/results/run1"));
+    assert_eq!(
+        custom.report_json(),
+        Path::new("/srv/results/run1/report.json")
+    );
+}
+
+#[test]
+fn phase_slugs_are_file_name_safe() {
+    assert_eq!(phase_slug("EEVDF (DEFAULT)"), "eevdf");
+    assert_eq!(phase_slug("PANDEMONIUM"), "pandemonium");
+    assert_eq!(phase_slug("  (x) "), "x");
+    assert_eq!(phase_slug("()"), "phase");
+}
+
+#[test]
+fn latest_link_follows_the_newest_run() {
+    let log = scratch("latest");
+    let first = BenchLayout::stamped(&log, "20260101-000000");
+    let second = BenchLayout::stamped(&log, "20260102-000000");
+    std::fs::create_dir_all(first.dir()).unwrap();
+    std::fs::create_dir_all(second.dir()).unwrap();
+
+    point_latest(&log, first.dir()).unwrap();
+    point_latest(&log, second.dir()).unwrap();
+    let link = log.join(LATEST_LINK);
+    assert_eq!(std::fs::read_link(&link).unwrap(), second.dir());
+    // THE LINK RESOLVES TO A DIRECTORY BUT IS NEVER LISTED AS A RUN
+    assert_eq!(
+        names(&bench_dirs(&log)),
+        ["bench-20260101-000000", "bench-20260102-000000"]
+    );
+}
+
+#[test]
+fn prune_keeps_the_newest_and_ignores_everything_else() {
+    let log = scratch("prune");
+    for stamp in ["20260103-000000", "20260101-000000", "20260102-000000"] {
+        let out = BenchLayout::stamped(&log, stamp);
+        std::fs::create_dir_all(out.dir()).unwrap();
+        std::fs::write(out.report_txt(), "x\n").unwrap();
+    }
+    // run.rs DIRECTORIES AND A STRAY FILE SHARE THE LOG DIRECTORY
+    std::fs::create_dir_all(log.join("run-20260101-000000")).unwrap();
+    std::fs::write(log.join("bench-notes.txt"), "").unwrap();
+
+    let removed = prune_bench_dirs(&log, 2);
+    assert_eq!(names(&removed), ["bench-20260101-000000"]);
+    assert_eq!(
+        names(&bench_dirs(&log)),
+        ["bench-20260102-000000", "bench-20260103-000000"]
+    );
+    assert!(log.join("run-20260101-000000").is_dir());
+    assert!(log.join("bench-notes.txt").is_file());
+
+    // UNDER THE LIMIT: NOTHING GOES; MISSING LOG DIR: NOTHING TO DO
+    assert!(prune_bench_dirs(&log, 20).is_empty());
+    assert!(prune_bench_dirs(&log.join("missing"), 1).is_empty());
+}
+
+#[test]
+fn samples_round_trip_gzipped_and_older_plain_files_still_read() {
+    let dir = scratch("samples");
+    let out = BenchLayout::new(dir.clone());
+    let text = "# probe\n0 120\n1 95\nhist 0 64 3\n";
+    write_samples(&out.probe_samples("EEVDF (DEFAULT)"), text).unwrap();
+    assert_eq!(samples_file("EEVDF (DEFAULT)"), "probe-eevdf.txt.gz");
+    // COMPRESSED ON DISK, THE SAME TEXT BACK
+    let raw = std::fs::read(out.probe_samples("EEVDF (DEFAULT)")).unwrap();
+    assert_eq!(&raw[..2], &[0x1f, 0x8b]);
+    assert_eq!(
+        read_samples(&out.probe_samples("EEVDF (DEFAULT)")).unwrap(),
+        text
+    );
+
+    // AN OLDER RUN: PLAIN probe-<phase>.txt. A PHASE WITH BOTH READS THE .gz
+    std::fs::write(dir.join("probe-pandemonium.txt"), "5\n").unwrap();
+    std::fs::write(dir.join("probe-eevdf.txt"), "stale\n").unwrap();
+    std::fs::write(dir.join("sched-pandemonium.log"), "").unwrap();
+    let files = sample_files(&dir).unwrap();
+    let phases: Vec<&str> = files.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(phases, ["eevdf", "pandemonium"]);
+    assert_eq!(files[0].1, out.probe_samples("EEVDF"));
+    assert_eq!(read_samples(&files[1].1).unwrap(), "5\n");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn json_renders_nested_and_ordered() {
+    let json = Json::obj(vec![
+        ("mode", Json::str("io")),
+        ("sched_args", Json::Arr(vec![])),
+        (
+            "eevdf",
+            Json::obj(vec![
+                ("samples", Json::Num(1200.0)),
+                ("p99_us", Json::Num(85.5)),
+            ]),
+        ),
+        ("ok", Json::Bool(true)),
+        ("missing", Json::Null),
+    ]);
+    assert_eq!(
+        json.render(),
+        concat!(
+            "{\n",
+            "  \"mode\": \"io\",\n",
+            "  \"sched_args\": [],\n",
+            "  \"eevdf\": {\n",
+            "    \"samples\": 1200,\n",
+            "    \"p99_us\": 85.5\n",
+            "  },\n",
+            "  \"ok\": true,\n",
+            "  \"missing\": null\n",
+            "}\n"
+        )
+    );
+    // SAME VALUE, ONE LINE (pandemonium status --waybar)
+    assert_eq!(
+        json.render_line(),
+        concat!(
+            "{\"mode\": \"io\", \"sched_args\": [], ",
+            "\"eevdf\": {\"samples\": 1200, \"p99_us\": 85.5}, ",
+            "\"ok\": true, \"missing\": null}\n"
+        )
+    );
+}
+
+#[test]
+fn json_escapes_strings_and_drops_non_finite_numbers() {
+    let s = Json::str("cargo build \"--release\"\n\tC:\\x\u{1}");
+    assert_eq!(
+        s.render(),
+        "\"cargo build \\\"--release\\\"\\n\\tC:\\\\x\\u0001\"\n"
+    );
+    assert_eq!(Json::Num(f64::NAN).render(), "null\n");
+    assert_eq!(Json::Num(f64::INFINITY).render(), "null\n");
+    assert_eq!(Json::Num(-3.0).render(), "-3\n");
+    assert_eq!(Json::Arr(vec![Json::Num(1.0)]).render(), "[\n  1\n]\n");
+}
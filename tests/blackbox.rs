@@ -0,0 +1,193 @@
+// PANDEMONIUM BLACK BOX TESTS
+// SLOT ENCODING, TORN-WRITE DETECTION, RING WRAPAROUND, UNCLEAN PRESERVATION
+
+use std::path::PathBuf;
+
+use pandemonium::blackbox::{
+    decode, decode_slot, encode_header, encode_slot, preserve_if_unclean, BlackBox, Header, Record,
+    FILE_SIZE, HEADER_SIZE, KIND_KNOBS, KIND_SNAPSHOT, SLOTS, SLOT_SIZE,
+};
+use pandemonium::event::{EventLog, TickCounters};
+
+fn tmp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pandemonium-bb-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn knobs_record(ts_ns: u64, tag: u64) -> Record {
+    Record::knobs(ts_ns, [tag; 10], 1)
+}
+
+#[test]
+fn slot_round_trip() {
+    let mut log = EventLog::new();
+    log.snapshot(TickCounters {
+        dispatches: 1000,
+        idle_hits: 600,
+        shared: 50,
+        preempt: 7,
+        keep_run: 3,
+        wake_avg_us: 42,
+        hard_kicks: 5,
+        soft_kicks: 9,
+        lat_idle_us: 11,
+        lat_kick_us: 13,
+    });
+    let mut r = Record::snapshot(log.latest().unwrap(), 250, 2, 800_000);
+    r.seq = 7;
+    let back = decode_slot(&encode_slot(&r)).unwrap();
+    assert_eq!(back, r);
+    assert_eq!(back.kind, KIND_SNAPSHOT);
+    assert_eq!(back.data[0], 1000);
+    assert_eq!(back.data[10], 250);
+    assert_eq!(back.data[11], 2);
+    assert_eq!(back.data[12], 800_000);
+
+    let mut k = knobs_record(5, 3);
+    k.seq = 8;
+    let back = decode_slot(&encode_slot(&k)).unwrap();
+    assert_eq!(back.kind, KIND_KNOBS);
+    assert_eq!(back.data[10], 1);
+}
+
+#[test]
+fn torn_slot_detected() {
+    let mut r = knobs_record(99, 4);
+    r.seq = 1;
+    let mut slot = encode_slot(&r);
+    slot[60] ^= 0xff;
+    assert_eq!(decode_slot(&slot), None);
+
+    // A FILE WITH ONE TORN SLOT COUNTS IT AND KEEPS THE REST
+    let mut file = encode_header(&Header {
+        clean: false,
+        pid: 1,
+        start_s: 0,
+        next_seq: 3,
+    })
+    .to_vec();
+    file.extend_from_slice(&slot);
+    let mut good = knobs_record(100, 5);
+    good.seq = 2;
+    file.extend_from_slice(&encode_slot(&good));
+    let dump = decode(&file).unwrap();
+    assert_eq!(dump.torn, 1);
+    assert_eq!(dump.records, vec![good]);
+}
+
+#[test]
+fn empty_slots_skipped() {
+    assert_eq!(decode_slot(&[0u8; SLOT_SIZE]), None);
+    assert_eq!(decode_slot(&[0u8; 16]), None);
+    let mut file = vec![0u8; FILE_SIZE];
+    file[..HEADER_SIZE].copy_from_slice(&encode_header(&Header {
+        clean: true,
+        pid: 1,
+        start_s: 0,
+        next_seq: 1,
+    }));
+    let dump = decode(&file).unwrap();
+    assert!(dump.records.is_empty());
+    assert_eq!(dump.torn, 0);
+}
+
+#[test]
+fn bad_files_rejected() {
+    assert!(decode(&[]).is_err());
+    assert!(decode(&[0u8; 10]).is_err());
+    let mut file = encode_header(&Header {
+        clean: true,
+        pid: 1,
+        start_s: 0,
+        next_seq: 1,
+    });
+    file[0] = b'X';
+    assert!(decode(&file).is_err());
+}
+
+#[test]
+fn ring_wraps_in_order() {
+    let path = tmp_path("wrap.bin");
+    let total = SLOTS as u64 + 37;
+    {
+        let mut bb = BlackBox::create(&path).unwrap();
+        for i in 0..total {
+            bb.record(knobs_record(i * 1000, i));
+        }
+    }
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes.len(), FILE_SIZE);
+    let dump = decode(&bytes).unwrap();
+    assert!(!dump.header.clean);
+    assert_eq!(dump.header.pid, std::process::id());
+    assert_eq!(dump.header.next_seq, total + 1);
+    assert_eq!(dump.records.len(), SLOTS);
+    assert_eq!(dump.torn, 0);
+    // OLDEST SURVIVOR IS THE FIRST NOT OVERWRITTEN; NEWEST IS LAST
+    assert_eq!(dump.records[0].data[0], total - SLOTS as u64);
+    assert_eq!(dump.records[SLOTS - 1].data[0], total - 1);
+    assert!(dump.records.windows(2).all(|w| w[0].seq + 1 == w[1].seq));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn short_file_keeps_complete_slots() {
+    let path = tmp_path("short.bin");
+    {
+        let mut bb = BlackBox::create(&path).unwrap();
+        for i in 0..5 {
+            bb.record(knobs_record(i, i));
+        }
+    }
+    let bytes = std::fs::read(&path).unwrap();
+    // CUT MID-WAY THROUGH THE FOURTH SLOT
+    let cut = HEADER_SIZE + 3 * SLOT_SIZE + SLOT_SIZE / 2;
+    let dump = decode(&bytes[..cut]).unwrap();
+    assert_eq!(dump.records.len(), 3);
+    assert_eq!(dump.torn, 1);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unclean_run_preserved_clean_run_not() {
+    let path = tmp_path("live.bin");
+    let dest = tmp_path("unclean.bin");
+    let _ = std::fs::remove_file(&dest);
+
+    // NO FILE YET: NOTHING TO DO
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(preserve_if_unclean(&path, &dest).unwrap(), None);
+
+    // UNCLEAN WITH RECORDS: MOVED ASIDE
+    {
+        let mut bb = BlackBox::create(&path).unwrap();
+        bb.record(knobs_record(1, 1));
+    }
+    let h = preserve_if_unclean(&path, &dest).unwrap().unwrap();
+    assert_eq!(h.pid, std::process::id());
+    assert!(!path.exists());
+    assert_eq!(
+        decode(&std::fs::read(&dest).unwrap())
+            .unwrap()
+            .records
+            .len(),
+        1
+    );
+
+    // CLEAN SHUTDOWN: LEFT IN PLACE
+    {
+        let mut bb = BlackBox::create(&path).unwrap();
+        bb.record(knobs_record(1, 1));
+        bb.mark_clean();
+    }
+    assert_eq!(preserve_if_unclean(&path, &dest).unwrap(), None);
+    assert!(path.exists());
+
+    // UNCLEAN BUT EMPTY (DIED BEFORE THE FIRST TICK): NOTHING WORTH KEEPING
+    drop(BlackBox::create(&path).unwrap());
+    assert_eq!(preserve_if_unclean(&path, &dest).unwrap(), None);
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&dest).unwrap();
+}
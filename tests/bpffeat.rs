@@ -0,0 +1,165 @@
+// PANDEMONIUM BPF FEATURE REPORT TESTS
+// clang/bpftool VERSION PARSING, MINIMUM-VS-DETECTED ROW STATUSES, PROBE
+// OUTCOMES, AND THE FEATURE TABLE AGREEING WITH WHAT main.bpf.c USES
+
+use pandemonium::bpffeat::{
+    btf_row, feature_row, parse_bpftool_version, parse_clang_version, tool_row, Feature, Probe,
+    Version, FEATURES, LIBBPF_RS_VERSION, MIN_CLANG, MIN_LIBBPF,
+};
+use pandemonium::compat::Compat;
+
+#[test]
+fn versions_parse_and_order() {
+    assert_eq!(Version::parse("18.1.8"), Some(Version::new(18, 1, 8)));
+    assert_eq!(Version::parse("v7.4.0"), Some(Version::new(7, 4, 0)));
+    assert_eq!(
+        Version::parse("14.0.0-1ubuntu1.1"),
+        Some(Version::new(14, 0, 0))
+    );
+    assert_eq!(Version::parse("1.4"), Some(Version::new(1, 4, 0)));
+    assert_eq!(Version::parse("19"), Some(Version::new(19, 0, 0)));
+    assert_eq!(Version::parse("unknown"), None);
+    assert_eq!(Version::parse(""), None);
+
+    assert!(Version::new(17, 9, 9) < MIN_CLANG);
+    assert!(Version::new(18, 0, 0) >= MIN_CLANG);
+    assert!(Version::new(1, 10, 0) > Version::new(1, 9, 0));
+    assert_eq!(Version::new(1, 4, 0).to_string(), "1.4.0");
+    assert_eq!(
+        Version::parse(LIBBPF_RS_VERSION),
+        Some(Version::new(0, 26, 1))
+    );
+}
+
+#[test]
+fn clang_version_output_variants() {
+    let cases = [
+        (
+            "clang version 18.1.8\nTarget: x86_64-pc-linux-gnu\nThread model: posix\n",
+            Some(Version::new(18, 1, 8)),
+        ),
+        (
+            "Ubuntu clang version 14.0.0-1ubuntu1.1\nTarget: x86_64-pc-linux-gnu\n",
+            Some(Version::new(14, 0, 0)),
+        ),
+        (
+            "Apple clang version 15.0.0 (clang-1500.3.9.4)\n",
+            Some(Version::new(15, 0, 0)),
+        ),
+        (
+            "Debian clang version 19.1.7 (3)\nTarget: aarch64-unknown-linux-gnu\n",
+            Some(Version::new(19, 1, 7)),
+        ),
+        ("bash: clang: command not found\n", None),
+        ("", None),
+    ];
+    for (output, want) in cases {
+        assert_eq!(parse_clang_version(output), want, "{:?}", output);
+    }
+}
+
+#[test]
+fn bpftool_version_output_variants() {
+    assert_eq!(
+        parse_bpftool_version("bpftool v7.4.0\nusing libbpf v1.4\nfeatures: llvm, skeletons\n"),
+        Some(Version::new(7, 4, 0))
+    );
+    assert_eq!(
+        parse_bpftool_version("/usr/sbin/bpftool v5.15.0\nfeatures: libbfd\n"),
+        Some(Version::new(5, 15, 0))
+    );
+    // THE libbpf LINE IS NOT THE TOOL'S OWN VERSION
+    assert_eq!(parse_bpftool_version("using libbpf v1.4\n"), None);
+    assert_eq!(parse_bpftool_version(""), None);
+}
+
+#[test]
+fn tool_rows_compare_against_the_minimum() {
+    let ok = tool_row("clang", Some(MIN_CLANG), Some(Version::new(18, 1, 8)), true);
+    assert_eq!(ok.status, Compat::Pass);
+    assert_eq!(ok.minimum, "18.0.0");
+    assert_eq!(ok.detected, "18.1.8");
+
+    let old = tool_row("clang", Some(MIN_CLANG), Some(Version::new(14, 0, 0)), true);
+    assert_eq!(old.status, Compat::Fail);
+    let missing = tool_row("clang", Some(MIN_CLANG), None, true);
+    assert_eq!(missing.status, Compat::Fail);
+    assert_eq!(missing.detected, "missing");
+
+    // bpftool: NOT REQUIRED, ANY VERSION
+    let bpftool = tool_row("bpftool", None, None, false);
+    assert_eq!(bpftool.status, Compat::Warn);
+    assert_eq!(bpftool.minimum, "any");
+    assert_eq!(
+        tool_row("bpftool", None, Some(Version::new(5, 15, 0)), false).status,
+        Compat::Pass
+    );
+
+    assert_eq!(
+        tool_row(
+            "libbpf",
+            Some(MIN_LIBBPF),
+            Some(Version::new(0, 8, 0)),
+            true
+        )
+        .status,
+        Compat::Fail
+    );
+}
+
+#[test]
+fn btf_row_requires_vmlinux() {
+    let present = btf_row(Some(5120));
+    assert_eq!(present.status, Compat::Pass);
+    assert_eq!(present.detected, "5120 KB");
+    let absent = btf_row(None);
+    assert_eq!(absent.status, Compat::Fail);
+    assert_eq!(absent.detected, "missing");
+}
+
+#[test]
+fn probe_outcomes_map_to_statuses() {
+    assert_eq!(Probe::from_libbpf(1), Probe::Supported);
+    assert_eq!(Probe::from_libbpf(0), Probe::Unsupported);
+    assert_eq!(Probe::from_libbpf(-1), Probe::Failed(-1));
+
+    let used = FEATURES
+        .iter()
+        .find(|r| r.feature == Feature::TaskStorage)
+        .unwrap();
+    let unused = FEATURES
+        .iter()
+        .find(|r| r.feature == Feature::Ringbuf)
+        .unwrap();
+
+    assert_eq!(feature_row(used, &Probe::Supported).status, Compat::Pass);
+    assert_eq!(feature_row(used, &Probe::Unsupported).status, Compat::Fail);
+    assert_eq!(
+        feature_row(unused, &Probe::Unsupported).status,
+        Compat::Warn
+    );
+    // A PROBE THAT COULD NOT RUN NEVER FAILS THE CHECK
+    for probe in [Probe::Failed(-1), Probe::Skipped] {
+        assert_eq!(feature_row(used, &probe).status, Compat::Warn);
+    }
+    let skipped = feature_row(used, &Probe::Skipped);
+    assert_eq!(skipped.detected, "skipped (not root)");
+    assert_eq!(skipped.minimum, "kernel 5.11");
+}
+
+// A FEATURE MARKED used MUST APPEAR IN main.bpf.c AND AN UNUSED ONE MUST
+// NOT: OTHERWISE A MISSING FEATURE FAILS (OR PASSES) check WRONGLY
+#[test]
+fn used_flags_match_the_bpf_program() {
+    let src = include_str!("../src/bpf/main.bpf.c");
+    for req in FEATURES {
+        let marker = match req.feature {
+            Feature::StructOps => "BPF_STRUCT_OPS(",
+            Feature::TaskStorage => "BPF_MAP_TYPE_TASK_STORAGE",
+            Feature::Ringbuf => "BPF_MAP_TYPE_RINGBUF",
+            Feature::Timer => "bpf_timer_init",
+            Feature::Iterators => "SEC(\"iter",
+        };
+        assert_eq!(src.contains(marker), req.used, "{}", req.name);
+    }
+}
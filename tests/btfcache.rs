@@ -0,0 +1,182 @@
+// PANDEMONIUM vmlinux.h CACHE STAMP TESTS
+// FNV HASH, STAMP ROUND TRIP, CACHE VERDICTS, REGENERATION REASONS,
+// HEADER SOURCE SELECTION (stub / override / host),
+// sched_ext SENTINEL SCAN AGAINST FIXTURE HEADER SNIPPETS
+
+use pandemonium::btfcache::{
+    check_cache, fnv1a64, missing_scx_message, missing_scx_types, CacheStamp, CacheVerdict,
+    HeaderSource, ALLOW_MISSING_SCX_ENV, MIN_HEADER_BYTES, SCX_SENTINEL_TYPES,
+};
+
+const BIG: Option<u64> = Some(4 << 20);
+
+fn stamp(release: &str, btf: &[u8]) -> CacheStamp {
+    CacheStamp::new(release, Some(btf))
+}
+
+#[test]
+fn fnv1a64_known_values() {
+    assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_ne!(fnv1a64(b"btf\x00"), fnv1a64(b"btf\x01"));
+}
+
+#[test]
+fn stamp_render_parse_round_trip() {
+    // /proc/sys/kernel/osrelease ENDS IN A NEWLINE
+    let s = stamp("6.14.2-arch1-1\n", b"BTF");
+    assert_eq!(s.release, "6.14.2-arch1-1");
+    assert_eq!(CacheStamp::parse(&s.render()), Some(s));
+
+    let no_btf = CacheStamp::new("6.14.2-arch1-1", None);
+    assert!(!no_btf.render().contains("btf_fnv1a"));
+    assert_eq!(CacheStamp::parse(&no_btf.render()), Some(no_btf));
+
+    assert_eq!(CacheStamp::parse("btf_fnv1a=00000000000000ff\n"), None);
+    assert_eq!(CacheStamp::parse(""), None);
+}
+
+#[test]
+fn missing_or_truncated_header_regenerates_silently() {
+    let now = stamp("6.14.2", b"BTF");
+    let text = now.render();
+    assert_eq!(check_cache(None, Some(&text), &now), CacheVerdict::Missing);
+    assert_eq!(
+        check_cache(Some(MIN_HEADER_BYTES), Some(&text), &now),
+        CacheVerdict::Missing
+    );
+    assert_eq!(CacheVerdict::Missing.reason(), None);
+    assert!(check_cache(Some(MIN_HEADER_BYTES + 1), Some(&text), &now).is_fresh());
+}
+
+#[test]
+fn unstamped_header_is_replaced() {
+    let now = stamp("6.14.2", b"BTF");
+    let v = check_cache(BIG, None, &now);
+    assert_eq!(v, CacheVerdict::Unstamped);
+    assert!(!v.is_fresh());
+    assert!(v.reason().is_some());
+    assert_eq!(
+        check_cache(BIG, Some("garbage"), &now),
+        CacheVerdict::Unstamped
+    );
+}
+
+#[test]
+fn kernel_change_names_both_releases() {
+    let was = stamp("6.13.7", b"BTF");
+    let now = stamp("6.14.2", b"BTF");
+    let v = check_cache(BIG, Some(&was.render()), &now);
+    assert_eq!(
+        v,
+        CacheVerdict::KernelChanged {
+            was: "6.13.7".to_string(),
+            now: "6.14.2".to_string(),
+        }
+    );
+    assert_eq!(
+        v.reason().as_deref(),
+        Some("kernel changed from 6.13.7 to 6.14.2")
+    );
+}
+
+#[test]
+fn btf_change_under_same_release_regenerates() {
+    let was = stamp("6.14.2", b"BTF-OLD");
+    let now = stamp("6.14.2", b"BTF-NEW");
+    let v = check_cache(BIG, Some(&was.render()), &now);
+    assert_eq!(v, CacheVerdict::BtfChanged);
+    assert!(v.reason().is_some());
+}
+
+#[test]
+fn unreadable_btf_falls_back_to_release_only() {
+    let was = stamp("6.14.2", b"BTF");
+    let now = CacheStamp::new("6.14.2", None);
+    assert!(check_cache(BIG, Some(&was.render()), &now).is_fresh());
+
+    let was_no_btf = CacheStamp::new("6.14.2", None);
+    let now_btf = stamp("6.14.2", b"BTF");
+    assert!(check_cache(BIG, Some(&was_no_btf.render()), &now_btf).is_fresh());
+}
+
+#[test]
+fn header_source_precedence() {
+    let path = Some("/srv/vmlinux-6.14.h");
+    // stub-bpf WINS OVER EVERYTHING: NOTHING IS READ
+    assert_eq!(HeaderSource::select(true, path), HeaderSource::Stub);
+    // AN EXPLICIT PATH BEATS THE HOST CACHE
+    assert_eq!(
+        HeaderSource::select(false, path),
+        HeaderSource::Override("/srv/vmlinux-6.14.h".to_string())
+    );
+    assert_eq!(HeaderSource::select(false, None), HeaderSource::HostCache);
+    // `PANDEMONIUM_VMLINUX_H= cargo build` MEANS UNSET, NOT THE EMPTY PATH
+    assert_eq!(
+        HeaderSource::select(false, Some("")),
+        HeaderSource::HostCache
+    );
+}
+
+#[test]
+fn only_host_cache_needs_host_btf() {
+    assert!(HeaderSource::HostCache.needs_host_btf());
+    for src in [HeaderSource::Stub, HeaderSource::Override("x".to_string())] {
+        assert!(!src.needs_host_btf());
+    }
+}
+
+// bpftool `format c` SHAPE: DEFINITIONS AT COLUMN 0, FIELDS TAB-INDENTED
+const SCX_HEADER: &str = "\
+struct scx_dispatch_q {
+\traw_spinlock_t lock;
+\tu32 nr;
+};
+
+struct sched_ext_ops {
+\ts32 (*select_cpu)(struct task_struct *, s32, u64);
+\tchar name[128];
+};
+
+struct bpf_iter_scx_dsq {
+\tu64 __opaque[6];
+};
+";
+
+const PLAIN_HEADER: &str = "\
+struct task_struct;
+
+struct rq {
+\traw_spinlock_t __lock;
+\tstruct sched_ext_entity *scx;
+};
+";
+
+#[test]
+fn scx_header_has_every_sentinel() {
+    assert!(missing_scx_types(SCX_HEADER).is_empty());
+}
+
+#[test]
+fn non_scx_header_misses_every_sentinel() {
+    assert_eq!(missing_scx_types(PLAIN_HEADER), SCX_SENTINEL_TYPES.to_vec());
+    assert_eq!(missing_scx_types(""), SCX_SENTINEL_TYPES.to_vec());
+}
+
+#[test]
+fn forward_declarations_and_uses_are_not_definitions() {
+    // AN OLDER sched_ext KERNEL WITHOUT THE DSQ ITERATOR, PLUS A STRAY
+    // FORWARD DECLARATION AND A FIELD THAT MERELY MENTIONS THE TYPE
+    let partial = SCX_HEADER.replace("struct bpf_iter_scx_dsq {", "struct bpf_iter_scx_dsq;")
+        + "struct foo {\n\tstruct bpf_iter_scx_dsq {\n};\n";
+    assert_eq!(missing_scx_types(&partial), vec!["bpf_iter_scx_dsq"]);
+}
+
+#[test]
+fn missing_scx_message_is_actionable() {
+    let msg = missing_scx_message("kernel 6.6.30-lts", &["sched_ext_ops", "scx_dispatch_q"]);
+    assert!(msg.contains("kernel 6.6.30-lts"));
+    assert!(msg.contains("sched_ext_ops, scx_dispatch_q"));
+    assert!(msg.contains("CONFIG_SCHED_CLASS_EXT"));
+    assert!(msg.contains(&format!("{}=1", ALLOW_MISSING_SCX_ENV)));
+}
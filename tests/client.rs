@@ -0,0 +1,253 @@
+// PANDEMONIUM CLIENT API TESTS
+// stats/knobs/histogram/reserved_cpus AGAINST A FAKE PIN DIRECTORY (DirMaps), regime()
+// AGAINST A FAKE CONTROL SOCKET, IDLE CPUS FROM /proc/stat SNAPSHOTS
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+
+use pandemonium::client::{
+    idle_cpus_between, Client, DirMaps, RegimeStatus, WakeLatHist, IDLE_CPU_PCT,
+};
+use pandemonium::ctl::{self, KnobOverrides};
+use pandemonium::hotplug::cpu_bitmap;
+use pandemonium::restart::RestartSummary;
+use pandemonium::stats::PandemoniumStats;
+use pandemonium::tuning::{Regime, SliceBounds, TuningKnobs, HIST_BUCKETS, HIST_EDGES_NS};
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pandemonium-client-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn stats_bytes(s: &PandemoniumStats) -> Vec<u8> {
+    s.fields()
+        .iter()
+        .flat_map(|(_, v)| v.to_ne_bytes())
+        .collect()
+}
+
+fn u64_bytes(values: &[u64]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+fn offline(dir: &Path) -> Client<DirMaps> {
+    Client::with_maps(DirMaps::new(dir), dir.join("ctl.sock"))
+}
+
+#[test]
+fn stats_are_read_per_cpu_and_summed() {
+    let dir = scratch("stats");
+    let cpu0 = PandemoniumStats {
+        nr_dispatches: 100,
+        nr_idle_hits: 40,
+        wake_lat_max: 900,
+        ..Default::default()
+    };
+    let cpu1 = PandemoniumStats {
+        nr_dispatches: 50,
+        nr_idle_hits: 10,
+        wake_lat_max: 3000,
+        ..Default::default()
+    };
+    std::fs::write(dir.join("stats_map.cpu0"), stats_bytes(&cpu0)).unwrap();
+    std::fs::write(dir.join("stats_map.cpu1"), stats_bytes(&cpu1)).unwrap();
+
+    let client = offline(&dir);
+    assert_eq!(client.stats_percpu().unwrap(), [cpu0, cpu1]);
+    let total = client.stats().unwrap();
+    assert_eq!(total.nr_dispatches, 150);
+    assert_eq!(total.nr_idle_hits, 50);
+    // GAUGES TAKE THE MAX, AS IN THE MONITOR LOOP
+    assert_eq!(total.wake_lat_max, 3000);
+}
+
+#[test]
+fn knobs_round_trip_and_layout_drift_is_an_error() {
+    let dir = scratch("knobs");
+    let knobs = TuningKnobs {
+        slice_ns: 2_000_000,
+        ..Default::default()
+    };
+    std::fs::write(dir.join("tuning_knobs"), u64_bytes(&knobs.values())).unwrap();
+    assert_eq!(offline(&dir).knobs().unwrap(), knobs);
+
+    // A DUMP FROM A BUILD WITH AN EXTRA FIELD: REFUSED, NOT MISDECODED
+    let mut grown = knobs.values().to_vec();
+    grown.push(7);
+    std::fs::write(dir.join("tuning_knobs"), u64_bytes(&grown)).unwrap();
+    let err = format!("{:#}", offline(&dir).knobs().unwrap_err());
+    assert!(err.contains("88 BYTES"), "{}", err);
+}
+
+#[test]
+fn reserved_cpus_decode_the_pinned_bitmap() {
+    let dir = scratch("reserved");
+    std::fs::write(dir.join("reserved_cpus"), u64_bytes(&cpu_bitmap(&[]))).unwrap();
+    assert_eq!(offline(&dir).reserved_cpus().unwrap(), Vec::<u32>::new());
+    std::fs::write(
+        dir.join("reserved_cpus"),
+        u64_bytes(&cpu_bitmap(&[2, 3, 64, 1023])),
+    )
+    .unwrap();
+    assert_eq!(offline(&dir).reserved_cpus().unwrap(), vec![2, 3, 64, 1023]);
+    // HALF A BITMAP: REFUSED
+    std::fs::write(dir.join("reserved_cpus"), u64_bytes(&[0; 8])).unwrap();
+    assert!(offline(&dir).reserved_cpus().is_err());
+}
+
+#[test]
+fn missing_pins_name_the_path() {
+    let dir = scratch("missing");
+    let client = offline(&dir);
+    let err = format!("{:#}", client.knobs().unwrap_err());
+    assert!(err.contains("tuning_knobs"), "{}", err);
+    let err = format!("{:#}", client.stats().unwrap_err());
+    assert!(err.contains("stats_map.cpu0"), "{}", err);
+}
+
+#[test]
+fn histogram_sums_cpus_per_tier() {
+    let dir = scratch("hist");
+    // KEY = TIER * 12 + BUCKET. CPU0: 99 BATCH WAKEUPS IN BUCKET 0, ONE IN
+    // BUCKET 5. CPU1: 10 LAT_CRITICAL WAKEUPS IN BUCKET 1.
+    let mut cpu0 = vec![0u64; 3 * HIST_BUCKETS];
+    cpu0[0] = 99;
+    cpu0[5] = 1;
+    let mut cpu1 = vec![0u64; 3 * HIST_BUCKETS];
+    cpu1[2 * HIST_BUCKETS + 1] = 10;
+    std::fs::write(dir.join("wake_lat_hist.cpu0"), u64_bytes(&cpu0)).unwrap();
+    std::fs::write(dir.join("wake_lat_hist.cpu1"), u64_bytes(&cpu1)).unwrap();
+
+    let hist = offline(&dir).histogram().unwrap();
+    assert_eq!(hist.samples(), 110);
+    assert_eq!(hist.counts[0][0], 99);
+    assert_eq!(hist.counts[2][1], 10);
+    assert_eq!(hist.counts[1], [0; HIST_BUCKETS]);
+    assert_eq!(hist.tier_p99_ns(2), Some(HIST_EDGES_NS[1]));
+    assert_eq!(hist.tier_p99_ns(3), None);
+    assert_eq!(hist.p99_ns(), HIST_EDGES_NS[1]);
+    assert_eq!(WakeLatHist::default().samples(), 0);
+}
+
+#[test]
+fn regime_over_a_fake_control_socket() {
+    let dir = scratch("socket");
+    let path = dir.join("ctl.sock");
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = std::thread::spawn(move || {
+        let mut overrides = KnobOverrides::default();
+        overrides.set("slice_ns", 1_000_000);
+        let replies = [
+            ctl::format_regime(
+                Regime::Heavy,
+                true,
+                false,
+                true,
+                &overrides,
+                &SliceBounds::from_us(200, 8000).unwrap(),
+                &RestartSummary {
+                    count: 2,
+                    last_reason: Some("cpu 3 going offline".to_string()),
+                },
+            ),
+            ctl::err("ADAPTIVE LAYER DISABLED"),
+        ];
+        for reply in replies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            stream.read_to_string(&mut line).unwrap();
+            assert_eq!(line, "get regime\n");
+            stream.write_all(reply.as_bytes()).unwrap();
+        }
+    });
+
+    let client = offline(&dir);
+    let status = client.regime().unwrap();
+    assert_eq!(status.regime, Regime::Heavy);
+    assert!(status.pinned && !status.paused && status.tightened);
+    assert_eq!(status.held, ["slice_ns"]);
+    assert_eq!(
+        (status.slice_min_ns, status.slice_max_ns),
+        (200_000, 8_000_000)
+    );
+    assert_eq!(status.restarts, 2);
+    assert_eq!(status.last_restart.as_deref(), Some("cpu 3 going offline"));
+
+    let err = client.regime().unwrap_err().to_string();
+    assert_eq!(err, "ADAPTIVE LAYER DISABLED");
+    server.join().unwrap();
+
+    // NOTHING LISTENING: THE HINT NAMES THE SOCKET AND THE GROUP OPTION
+    std::fs::remove_file(&path).unwrap();
+    let err = client.regime().unwrap_err().to_string();
+    assert!(
+        err.contains("ctl.sock") && err.contains("--pin-group"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn regime_response_fields_are_required() {
+    let kv = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    };
+    let full = [
+        ("regime", "LIGHT"),
+        ("pinned", "false"),
+        ("paused", "true"),
+        ("tightened", "false"),
+        ("held", "-"),
+        ("slice_min_ns", "100000"),
+        ("slice_max_ns", "5000000"),
+    ];
+    let status = RegimeStatus::from_response(&kv(&full)).unwrap();
+    assert_eq!(status.regime, Regime::Light);
+    assert!(status.paused);
+    assert!(status.held.is_empty());
+    // NO restarts/last_restart (AN OLDER SCHEDULER): NONE, NOT AN ERROR
+    assert_eq!((status.restarts, status.last_restart), (0, None));
+    let mut restarted = full.to_vec();
+    restarted.push(("restarts", "1"));
+    restarted.push(("last_restart", "-"));
+    let status = RegimeStatus::from_response(&kv(&restarted)).unwrap();
+    assert_eq!((status.restarts, status.last_restart), (1, None));
+    restarted[7] = ("restarts", "many");
+    assert!(RegimeStatus::from_response(&kv(&restarted)).is_err());
+
+    let err = RegimeStatus::from_response(&kv(&full[..4])).unwrap_err();
+    assert!(err.contains("held"), "{}", err);
+    let mut bad = full;
+    bad[0] = ("regime", "TURBO");
+    assert!(RegimeStatus::from_response(&kv(&bad)).is_err());
+}
+
+#[test]
+fn idle_cpus_from_proc_stat_windows() {
+    // user nice system idle iowait irq softirq steal
+    let before = "cpu  0 0 0 0 0 0 0 0\n\
+                  cpu0 100 0 0 100 0 0 0 0\n\
+                  cpu1 100 0 0 100 0 0 0 0\n\
+                  cpu2 100 0 0 100 0 0 0 0\n";
+    // cpu0: 95 IDLE OF 100. cpu1: 50 OF 100. cpu2: 85 IDLE + 10 IOWAIT.
+    // cpu3 CAME ONLINE MID-WINDOW: NO BASELINE, NOT REPORTED.
+    let after = "cpu  0 0 0 0 0 0 0 0\n\
+                 cpu0 105 0 0 195 0 0 0 0\n\
+                 cpu1 150 0 0 150 0 0 0 0\n\
+                 cpu2 105 0 0 185 10 0 0 0\n\
+                 cpu3 10 0 0 10 0 0 0 0\n";
+    assert_eq!(idle_cpus_between(before, after, IDLE_CPU_PCT), [0, 2]);
+    assert_eq!(idle_cpus_between(before, after, 50), [0, 1, 2]);
+    // NO TIME PASSED: NOTHING IS KNOWN TO BE IDLE
+    assert!(idle_cpus_between(before, before, 0).is_empty());
+}
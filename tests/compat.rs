@@ -0,0 +1,147 @@
+// PANDEMONIUM COMPAT TESTS
+// BTF STRING-TABLE PARSING AND KFUNC MATRIX EVALUATION ON SYNTHETIC BLOBS
+
+use pandemonium::compat::{
+    btf_strings, evaluate, has_symbol, Compat, CompatReport, KfuncReq, KFUNCS,
+};
+
+// BUILD A MINIMAL BTF BLOB: 24-BYTE HEADER, EMPTY TYPE SECTION, STRINGS
+fn btf_blob(names: &[&str], little_endian: bool) -> Vec<u8> {
+    let mut strs = vec![0u8];
+    for n in names {
+        strs.extend_from_slice(n.as_bytes());
+        strs.push(0);
+    }
+    let u32b = |v: u32| {
+        if little_endian {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+    let mut out = Vec::new();
+    if little_endian {
+        out.extend_from_slice(&0xeb9fu16.to_le_bytes());
+    } else {
+        out.extend_from_slice(&0xeb9fu16.to_be_bytes());
+    }
+    out.push(1); // VERSION
+    out.push(0); // FLAGS
+    out.extend_from_slice(&u32b(24)); // HDR_LEN
+    out.extend_from_slice(&u32b(0)); // TYPE_OFF
+    out.extend_from_slice(&u32b(0)); // TYPE_LEN
+    out.extend_from_slice(&u32b(0)); // STR_OFF
+    out.extend_from_slice(&u32b(strs.len() as u32)); // STR_LEN
+    out.extend_from_slice(&strs);
+    out
+}
+
+const REQS: &[KfuncReq] = &[
+    KfuncReq {
+        name: "scx_bpf_dsq_insert_vtime",
+        legacy: Some("scx_bpf_dispatch_vtime"),
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_kick_cpu",
+        legacy: None,
+        required: true,
+    },
+    KfuncReq {
+        name: "scx_bpf_task_set_slice",
+        legacy: None,
+        required: false,
+    },
+];
+
+#[test]
+fn strings_little_endian() {
+    let blob = btf_blob(&["scx_bpf_kick_cpu"], true);
+    let strtab = btf_strings(&blob).unwrap();
+    assert!(has_symbol(strtab, "scx_bpf_kick_cpu"));
+}
+
+#[test]
+fn strings_big_endian() {
+    let blob = btf_blob(&["scx_bpf_kick_cpu"], false);
+    let strtab = btf_strings(&blob).unwrap();
+    assert!(has_symbol(strtab, "scx_bpf_kick_cpu"));
+}
+
+#[test]
+fn symbol_match_is_exact() {
+    let blob = btf_blob(&["scx_bpf_dsq_insert_vtime"], true);
+    let strtab = btf_strings(&blob).unwrap();
+    assert!(!has_symbol(strtab, "scx_bpf_dsq_insert"));
+    assert!(!has_symbol(strtab, "dsq_insert_vtime"));
+}
+
+#[test]
+fn bad_magic_rejected() {
+    let mut blob = btf_blob(&["x"], true);
+    blob[0] = 0;
+    assert!(btf_strings(&blob).is_err());
+}
+
+#[test]
+fn truncated_rejected() {
+    let blob = btf_blob(&["scx_bpf_kick_cpu"], true);
+    assert!(btf_strings(&blob[..10]).is_err());
+    assert!(btf_strings(&blob[..blob.len() - 4]).is_err());
+}
+
+#[test]
+fn all_current_names_pass() {
+    let blob = btf_blob(
+        &[
+            "scx_bpf_dsq_insert_vtime",
+            "scx_bpf_kick_cpu",
+            "scx_bpf_task_set_slice",
+        ],
+        true,
+    );
+    let st = evaluate(btf_strings(&blob).unwrap(), REQS);
+    assert!(st.iter().all(|e| e.status == Compat::Pass));
+}
+
+#[test]
+fn legacy_name_warns() {
+    let blob = btf_blob(&["scx_bpf_dispatch_vtime", "scx_bpf_kick_cpu"], true);
+    let st = evaluate(btf_strings(&blob).unwrap(), REQS);
+    assert_eq!(st[0].status, Compat::Warn);
+    assert_eq!(st[0].found_as, Some("scx_bpf_dispatch_vtime"));
+    assert_eq!(st[1].status, Compat::Pass);
+    // OPTIONAL SYMBOL MISSING: WARN, NOT FAIL
+    assert_eq!(st[2].status, Compat::Warn);
+    assert_eq!(st[2].found_as, None);
+}
+
+#[test]
+fn required_missing_fails() {
+    let blob = btf_blob(&["scx_bpf_dsq_insert_vtime"], true);
+    let st = evaluate(btf_strings(&blob).unwrap(), REQS);
+    assert_eq!(st[1].status, Compat::Fail);
+    assert_eq!(st[1].found_as, None);
+}
+
+fn report_for(names: &[&str]) -> CompatReport {
+    let blob = btf_blob(names, true);
+    CompatReport {
+        kernel: String::new(),
+        entries: evaluate(btf_strings(&blob).unwrap(), KFUNCS),
+    }
+}
+
+#[test]
+fn set_kfuncs_need_both() {
+    let both = report_for(&["scx_bpf_task_set_slice", "scx_bpf_task_set_dsq_vtime"]);
+    assert!(both.has_task_set_kfuncs());
+
+    let slice_only = report_for(&["scx_bpf_task_set_slice"]);
+    assert!(!slice_only.has_task_set_kfuncs());
+
+    // NO SETTERS AND NO REQUIRED KFUNCS: LEGACY PATH, REFUSED AT STARTUP
+    let legacy = report_for(&[]);
+    assert!(!legacy.has_task_set_kfuncs());
+    assert_eq!(legacy.worst(), Compat::Fail);
+}
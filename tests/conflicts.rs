@@ -0,0 +1,75 @@
+// PANDEMONIUM CONFLICTING DAEMON TESTS
+// comm MATCHING AGAINST THE TABLE, ONE FINDING PER DAEMON, AND A /proc SCAN
+// OF A SYNTHETIC TREE (NON-PID ENTRIES, VANISHED PROCESSES)
+
+use pandemonium::conflicts::{detect, match_comm, scan, COMM_LEN, CONFLICTS};
+
+#[test]
+fn table_comms_fit_the_kernel_limit() {
+    for c in CONFLICTS {
+        assert!(c.comm.len() <= COMM_LEN, "{} is cut by the kernel", c.comm);
+        assert!(!c.note.is_empty(), "{}", c.name);
+    }
+    // system76-scheduler IS MATCHED UNDER ITS 15-BYTE comm
+    assert_eq!(
+        match_comm("system76-schedu\n").map(|c| c.name),
+        Some("system76-scheduler")
+    );
+}
+
+#[test]
+fn comm_matching_is_exact() {
+    assert_eq!(
+        match_comm("ananicy-cpp\n").map(|c| c.name),
+        Some("ananicy-cpp")
+    );
+    assert_eq!(match_comm("ananicy").map(|c| c.name), Some("ananicy"));
+    assert_eq!(match_comm("gamemoded\n").map(|c| c.name), Some("gamemode"));
+    assert!(match_comm("gamemoderun").is_none());
+    assert!(match_comm("ananicy-cpp-x").is_none());
+    assert!(match_comm("").is_none());
+}
+
+#[test]
+fn one_finding_per_daemon_in_table_order() {
+    let found = detect([
+        (900, "gamemoded\n"),
+        (1, "systemd\n"),
+        (512, "ananicy-cpp\n"),
+        (300, "gamemoded\n"),
+        (77, "bash\n"),
+    ]);
+    let names: Vec<&str> = found.iter().map(|f| f.name).collect();
+    assert_eq!(names, vec!["ananicy-cpp", "gamemode"]);
+    // THE LOWEST PID NAMES THE DAEMON
+    assert_eq!(found[1].pid, 300);
+    let msg = found[0].message();
+    assert!(msg.starts_with("CONFLICTING DAEMON: ananicy-cpp (PID 512)"));
+    assert!(msg.contains("--ignore-conflicts"));
+
+    assert!(detect([(1, "systemd\n")]).is_empty());
+}
+
+#[test]
+fn proc_scan_reads_numeric_dirs_only() {
+    let root = std::env::temp_dir().join(format!("pandemonium-conflicts-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for (dir, comm) in [
+        ("42", Some("system76-schedu\n")),
+        ("43", Some("zsh\n")),
+        ("self", Some("ananicy-cpp\n")),
+        ("44", None), // EXITED MID-SCAN: NO comm LEFT
+    ] {
+        let d = root.join(dir);
+        std::fs::create_dir_all(&d).unwrap();
+        if let Some(comm) = comm {
+            std::fs::write(d.join("comm"), comm).unwrap();
+        }
+    }
+    let found = scan(&root);
+    assert_eq!(found.len(), 1);
+    assert_eq!((found[0].pid, found[0].name), (42, "system76-scheduler"));
+
+    assert!(scan(&root.join("missing")).is_empty());
+    std::fs::remove_dir_all(&root).unwrap();
+}
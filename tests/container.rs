@@ -0,0 +1,286 @@
+// PANDEMONIUM CONTAINER AWARENESS TESTS
+// RUNTIME DETECTION FROM FIXTURE SIGNALS, /proc/self/mounts PARSING,
+// bpffs CHECK FOR THE PIN DIRECTORY, HINTS, --pin-dir, --pin-group,
+// PIN ROLLBACK ON A FAILED Scheduler::init() PIN STEP
+
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use pandemonium::container::{
+    bpffs_hint, mount_for, on_bpffs, parse_mounts, resolve_group, sched_ext_hint, ContainerSignals,
+    MountEntry, PinDir, PinGuard, BPFFS_ROOT, DEFAULT_PIN_DIR,
+};
+
+fn cgroup(text: &str) -> ContainerSignals {
+    ContainerSignals {
+        cgroup: text.to_string(),
+        ..Default::default()
+    }
+}
+
+// HOST: CGROUP V2, systemd USER SLICE
+const HOST_CGROUP: &str = "0::/user.slice/user-1000.slice/session-2.scope\n";
+
+const HOST_MOUNTS: &str = "\
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+bpf /sys/fs/bpf bpf rw,nosuid,nodev,noexec,relatime,mode=700 0 0
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+";
+
+// DOCKER WITHOUT -v /sys/fs/bpf: /sys IS A READ-ONLY sysfs, NO bpffs
+const DOCKER_MOUNTS: &str = "\
+overlay / overlay rw,relatime,lowerdir=/var/lib/docker/overlay2/l/X 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+sysfs /sys sysfs ro,nosuid,nodev,noexec,relatime 0 0
+";
+
+#[test]
+fn host_is_not_a_container() {
+    assert_eq!(cgroup(HOST_CGROUP).runtime(), None);
+    assert_eq!(ContainerSignals::default().runtime(), None);
+}
+
+#[test]
+fn marker_files_name_the_runtime() {
+    let docker = ContainerSignals {
+        dockerenv: true,
+        cgroup: HOST_CGROUP.to_string(),
+        ..Default::default()
+    };
+    assert_eq!(docker.runtime().as_deref(), Some("docker"));
+    let podman = ContainerSignals {
+        containerenv: true,
+        ..Default::default()
+    };
+    assert_eq!(podman.runtime().as_deref(), Some("podman"));
+}
+
+#[test]
+fn pid1_container_variable() {
+    let nspawn = ContainerSignals {
+        pid1_environ: Some("PATH=/usr/bin\0container=systemd-nspawn\0TERM=xterm\0".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(nspawn.runtime().as_deref(), Some("systemd-nspawn"));
+    // EMPTY VALUE OR A VARIABLE THAT MERELY ENDS IN container= IS NOT ONE
+    let empty = ContainerSignals {
+        pid1_environ: Some("container=\0MY_container=lxc\0".to_string()),
+        cgroup: HOST_CGROUP.to_string(),
+        ..Default::default()
+    };
+    assert_eq!(empty.runtime(), None);
+}
+
+#[test]
+fn cgroup_v1_paths_name_the_runtime() {
+    let docker = "12:pids:/docker/3f1c2a\n11:memory:/docker/3f1c2a\n0::/docker/3f1c2a\n";
+    assert_eq!(cgroup(docker).runtime().as_deref(), Some("docker"));
+    let k8s = "0::/kubepods/besteffort/pod1234/abcd\n";
+    assert_eq!(cgroup(k8s).runtime().as_deref(), Some("kubernetes"));
+    let lxc = "0::/lxc.payload.web/init.scope\n";
+    assert_eq!(cgroup(lxc).runtime().as_deref(), Some("lxc"));
+    // THE CONTROLLER FIELD IS NOT THE PATH
+    assert_eq!(cgroup("3:kubepods:/\n").runtime(), None);
+}
+
+#[test]
+fn root_only_cgroup_view_is_a_namespace() {
+    assert_eq!(
+        cgroup("0::/\n").runtime().as_deref(),
+        Some("cgroup-namespace")
+    );
+    // V1 HIERARCHIES ALL AT "/" STILL LOOK LIKE A HOST
+    assert_eq!(cgroup("1:cpu:/\n0::/\n").runtime(), None);
+}
+
+#[test]
+fn mounts_parse_with_octal_escapes() {
+    let m = parse_mounts("tmpfs /mnt/my\\040pins tmpfs rw 0 0\nshort line\n");
+    assert_eq!(
+        m,
+        vec![MountEntry {
+            point: PathBuf::from("/mnt/my pins"),
+            fstype: "tmpfs".to_string(),
+        }]
+    );
+    // A BACKSLASH NOT FOLLOWED BY THREE OCTAL DIGITS IS KEPT
+    let m = parse_mounts("x /a\\9b ext4 rw 0 0\n");
+    assert_eq!(m[0].point, PathBuf::from("/a\\9b"));
+}
+
+#[test]
+fn mount_for_picks_the_deepest_and_latest() {
+    let mounts = parse_mounts(HOST_MOUNTS);
+    let pin = Path::new(DEFAULT_PIN_DIR);
+    assert_eq!(mount_for(&mounts, pin).unwrap().fstype, "bpf");
+    assert_eq!(
+        mount_for(&mounts, Path::new("/home/u")).unwrap().fstype,
+        "ext4"
+    );
+    // /sys/fs/bpfx SHARES A STRING PREFIX WITH /sys/fs/bpf, NOT A COMPONENT ONE
+    assert_eq!(
+        mount_for(&mounts, Path::new("/sys/fs/bpfx"))
+            .unwrap()
+            .fstype,
+        "sysfs"
+    );
+
+    let stacked = format!("{}tmpfs /sys/fs/bpf tmpfs rw 0 0\n", HOST_MOUNTS);
+    let mounts = parse_mounts(&stacked);
+    assert_eq!(mount_for(&mounts, pin).unwrap().fstype, "tmpfs");
+    assert_eq!(mount_for(&[], pin), None);
+}
+
+#[test]
+fn pin_dir_must_be_on_bpffs() {
+    let pin = Path::new(DEFAULT_PIN_DIR);
+    assert!(on_bpffs(&parse_mounts(HOST_MOUNTS), pin));
+    assert!(!on_bpffs(&parse_mounts(DOCKER_MOUNTS), pin));
+    // A PLAIN DIRECTORY (OR TMPFS) AT /sys/fs/bpf ACCEPTS mkdir BUT NOT PINS
+    let tmpfs = format!("{}tmpfs /sys/fs/bpf tmpfs rw 0 0\n", DOCKER_MOUNTS);
+    assert!(!on_bpffs(&parse_mounts(&tmpfs), pin));
+    // A SECOND bpffs MOUNTED ELSEWHERE WORKS WITH --pin-dir
+    let extra = format!("{}bpf /run/bpf bpf rw 0 0\n", DOCKER_MOUNTS);
+    assert!(on_bpffs(
+        &parse_mounts(&extra),
+        Path::new("/run/bpf/pandemonium")
+    ));
+}
+
+#[test]
+fn hints_mention_the_fix() {
+    let host = bpffs_hint(Path::new(DEFAULT_PIN_DIR), None);
+    assert!(host.contains(DEFAULT_PIN_DIR));
+    assert!(host.contains(&format!("mount -t bpf bpf {}", BPFFS_ROOT)));
+    assert!(!host.contains("--pin-dir"));
+
+    let docker = bpffs_hint(Path::new(DEFAULT_PIN_DIR), Some("docker"));
+    assert!(docker.contains("IN docker"));
+    assert!(docker.contains(&format!("-v {}:{}", BPFFS_ROOT, BPFFS_ROOT)));
+    assert!(docker.contains("--pin-dir"));
+
+    assert!(sched_ext_hint(None).contains("CONFIG_SCHED_CLASS_EXT"));
+    assert!(sched_ext_hint(Some("podman")).contains("--privileged"));
+}
+
+#[test]
+fn pin_dir_paths() {
+    let def = PinDir::default();
+    assert_eq!(def.dir(), Path::new(DEFAULT_PIN_DIR));
+    assert_eq!(
+        def.knobs(),
+        PathBuf::from("/sys/fs/bpf/pandemonium/tuning_knobs")
+    );
+    assert_eq!(
+        def.stats(),
+        PathBuf::from("/sys/fs/bpf/pandemonium/stats_map")
+    );
+
+    let custom = PinDir::new(Path::new("/run/bpf/pd")).unwrap();
+    assert_eq!(
+        custom.map("task_class_init"),
+        PathBuf::from("/run/bpf/pd/task_class_init")
+    );
+
+    let err = PinDir::new(Path::new("bpf/pd")).unwrap_err();
+    assert!(err.contains("bpf/pd"));
+    assert!(err.contains("ABSOLUTE"));
+}
+
+#[test]
+fn pin_group_names_and_gids() {
+    assert_eq!(resolve_group("0"), Ok(0));
+    assert_eq!(resolve_group("4242"), Ok(4242));
+    let err = resolve_group("no-such-pandemonium-group").unwrap_err();
+    assert!(err.contains("no-such-pandemonium-group"));
+    assert!(resolve_group("a\0b").is_err());
+}
+
+#[test]
+fn share_opens_the_pins_to_the_group() {
+    let root = std::env::temp_dir().join(format!("pandemonium-share-{}", std::process::id()));
+    let dir = root.join("pins");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("stats_map"), b"").unwrap();
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).unwrap();
+    let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+
+    // NO GROUP: share() TOUCHES NOTHING
+    let pins = PinDir::new(&dir).unwrap();
+    assert_eq!(pins.group(), None);
+    pins.share().unwrap();
+    assert_eq!(mode(&dir), 0o700);
+
+    // OUR OWN GROUP: chown NEEDS NO PRIVILEGE
+    let gid = std::fs::metadata(&dir).unwrap().gid();
+    let pins = pins.with_group(gid);
+    assert_eq!(pins.group(), Some(gid));
+    pins.share().unwrap();
+    assert_eq!(mode(&dir), 0o750);
+    assert_eq!(mode(&dir.join("stats_map")), 0o660);
+
+    // A 0700 PARENT KEEPS THE GROUP OUT
+    std::fs::set_permissions(&root, std::fs::Permissions::from_mode(0o700)).unwrap();
+    assert_eq!(pins.blocked_ancestor(), Some(root.clone()));
+    std::fs::set_permissions(&root, std::fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(pins.blocked_ancestor(), None);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+// A FAKE "MAP PIN": ANY FILE AT THE PATH
+fn fake_pin(path: &Path) -> Result<(), String> {
+    std::fs::write(path, b"map").map_err(|e| e.to_string())
+}
+
+#[test]
+fn failed_pin_step_rolls_back_what_it_created() {
+    let root = std::env::temp_dir().join(format!("pandemonium-rollback-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    let pins = PinDir::new(&root.join("pins")).unwrap();
+
+    let mut guard = PinGuard::create(&pins).unwrap();
+    assert!(pins.dir().is_dir());
+    guard.pin(pins.knobs(), fake_pin).unwrap();
+    guard.pin(pins.stats(), fake_pin).unwrap();
+    let err = guard
+        .pin(pins.map("cache_domain"), |_| Err("EPERM".to_string()))
+        .unwrap_err();
+    assert_eq!(err, "EPERM");
+    assert_eq!(guard.pinned(), [pins.knobs(), pins.stats()]);
+    // THE ? IN pin_maps() DROPS THE GUARD HERE
+    drop(guard);
+    assert!(!pins.knobs().exists());
+    assert!(!pins.stats().exists());
+    assert!(!pins.dir().exists(), "DIRECTORY THIS RUN CREATED IS GONE");
+    assert!(root.is_dir());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn committed_pins_stay_and_a_preexisting_dir_is_kept() {
+    let root = std::env::temp_dir().join(format!("pandemonium-commit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let pins = PinDir::new(&root).unwrap();
+    std::fs::create_dir_all(pins.dir()).unwrap();
+    // A PREVIOUS RUN'S STALE PIN IS REPLACED, NOT APPENDED TO
+    std::fs::write(pins.knobs(), b"stale").unwrap();
+    std::fs::write(pins.map("unrelated"), b"keep").unwrap();
+
+    let mut guard = PinGuard::create(&pins).unwrap();
+    guard.pin(pins.knobs(), fake_pin).unwrap();
+    guard.commit();
+    assert_eq!(std::fs::read(pins.knobs()).unwrap(), b"map");
+
+    // ROLLBACK IN A DIRECTORY IT DID NOT CREATE: ONLY ITS OWN PINS GO
+    let mut guard = PinGuard::create(&pins).unwrap();
+    guard.pin(pins.stats(), fake_pin).unwrap();
+    drop(guard);
+    assert!(!pins.stats().exists());
+    assert!(pins.knobs().exists());
+    assert!(pins.map("unrelated").exists());
+    assert!(pins.dir().is_dir());
+    std::fs::remove_dir_all(&root).unwrap();
+}
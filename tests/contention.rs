@@ -6,7 +6,8 @@
 
 use pandemonium::tuning::{
     compute_p99_from_histogram, compute_stability_score, detect_regime, regime_knobs,
-    should_reflex_tighten, sleep_adjust_batch_ns, Regime, TuningKnobs,
+    should_reflex_tighten, sleep_adjust_batch_ns, tick_decisions, merge_reflex, Reflex,
+    ReflexAction, Regime, TuningKnobs, REFLEX_FLOOR_NS, RELAX_HOLD_TICKS, RELAX_STEP_NS,
     AFFINITY_STRONG, AFFINITY_WEAK,
     BATCH_MAX_NS, HIST_BUCKETS, HIST_EDGES_NS,
 };
@@ -133,11 +134,7 @@ fn sojourn_ewma_stability_identical_targets() {
 }
 
 // GRADUATED RELAX STATE MACHINE
-// SIMULATE THE EXACT LOGIC FROM adaptive.rs
-
-const MIN_SLICE_NS: u64 = 500_000;
-const RELAX_STEP_NS: u64 = 500_000;
-const RELAX_HOLD_TICKS: u32 = 2;
+// SIMULATE THE EXACT LOGIC OF tuning::Reflex (DRIVEN DIRECTLY BELOW)
 
 struct RelaxSim {
     tightened: bool,
@@ -296,7 +293,7 @@ impl TightenSim {
         if should_reflex_tighten(p99_ns, interactive_p99_ns, ceiling) {
             self.spike_count += 1;
             if self.spike_count >= 2 && regime == Regime::Mixed {
-                self.slice_ns = (self.slice_ns * 3 / 4).max(MIN_SLICE_NS);
+                self.slice_ns = (self.slice_ns * 3 / 4).max(REFLEX_FLOOR_NS);
                 self.tightened = true;
                 self.tighten_events += 1;
                 self.spike_count = 0;
@@ -378,6 +375,137 @@ fn tighten_fires_on_interactive_p99_alone() {
     assert!(sim.tightened);
 }
 
+// REFLEX STATE MACHINE: SCRIPTED P99 AGAINST AN IN-MEMORY KNOBS MAP
+// DRIVES THE SAME tuning::Reflex THE MONITOR LOOP RUNS
+
+struct FakeKnobs {
+    knobs: TuningKnobs,
+    writes: Vec<TuningKnobs>,
+}
+
+impl FakeKnobs {
+    fn new(regime: Regime) -> Self {
+        Self {
+            knobs: regime_knobs(regime),
+            writes: Vec::new(),
+        }
+    }
+}
+
+// THE MONITOR'S WRITE: UNCHANGED VALUES NEVER REACH THE MAP (KnobWriter)
+impl FakeKnobs {
+    fn write(&mut self, knobs: TuningKnobs) {
+        if knobs != self.knobs {
+            self.knobs = knobs;
+            self.writes.push(knobs);
+        }
+    }
+}
+
+// ONE TICK PER SCRIPT ENTRY (AGGREGATE P99, NS); RETURNS EVERY TICK'S ACTION.
+// THE REFLEX ONLY UPDATES ITS REQUEST; THE MONITOR MERGES AND WRITES.
+fn run_script(
+    reflex: &mut Reflex,
+    sink: &mut FakeKnobs,
+    regime: Regime,
+    script: &[u64],
+) -> Vec<Option<ReflexAction>> {
+    let gate = tick_decisions(false, false);
+    let baseline = regime_knobs(regime);
+    script
+        .iter()
+        .map(|&p99| {
+            let action = reflex.tick(&gate, regime, p99, 0, &baseline);
+            sink.write(merge_reflex(baseline, reflex.request()));
+            action
+        })
+        .collect()
+}
+
+#[test]
+fn reflex_filters_isolated_spikes() {
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let calm = Regime::Mixed.p99_ceiling() / 2;
+    let mut reflex = Reflex::default();
+    let mut sink = FakeKnobs::new(Regime::Mixed);
+
+    // ALTERNATING SPIKES NEVER REACH SPIKE_TICKS IN A ROW
+    let actions = run_script(&mut reflex, &mut sink, Regime::Mixed, &[spike, calm, spike, calm, spike]);
+    assert!(actions.iter().all(|a| a.is_none()));
+    assert!(sink.writes.is_empty());
+    assert_eq!(reflex.spike_count, 1);
+
+    // THE NEXT SPIKE MAKES TWO IN A ROW
+    let actions = run_script(&mut reflex, &mut sink, Regime::Mixed, &[spike]);
+    assert_eq!(actions, vec![Some(ReflexAction::Tightened { slice_ns: 750_000 })]);
+    assert_eq!(reflex.tighten_events, 1);
+    assert_eq!(sink.knobs.slice_ns, 750_000);
+    assert_eq!(sink.knobs.preempt_thresh_ns, 750_000);
+    assert_eq!(
+        sink.knobs.batch_slice_ns,
+        regime_knobs(Regime::Mixed).batch_slice_ns
+    );
+}
+
+#[test]
+fn reflex_tightens_only_in_mixed() {
+    for regime in [Regime::Light, Regime::Heavy] {
+        let spike = regime.p99_ceiling() + 1_000_000;
+        let mut reflex = Reflex::default();
+        let mut sink = FakeKnobs::new(regime);
+        run_script(&mut reflex, &mut sink, regime, &[spike; 10]);
+        assert!(sink.writes.is_empty(), "WROTE KNOBS IN {:?}", regime);
+        assert_eq!(reflex.tighten_events, 0);
+        assert!(!reflex.tightened);
+    }
+}
+
+#[test]
+fn reflex_cooldown_until_relaxed_to_baseline() {
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let calm = Regime::Mixed.p99_ceiling() / 2;
+    let mut reflex = Reflex::default();
+    let mut sink = FakeKnobs::new(Regime::Mixed);
+
+    // SUSTAINED SPIKES: ONE TIGHTEN, THEN HELD (NO CASCADE WHILE TIGHTENED)
+    run_script(&mut reflex, &mut sink, Regime::Mixed, &[spike; 10]);
+    assert_eq!(reflex.tighten_events, 1);
+    assert_eq!(sink.writes.len(), 1);
+    assert_eq!(sink.knobs.slice_ns, 750_000);
+
+    // RELAX_HOLD_TICKS OF CALM STEP BACK TO BASELINE AND END THE COOLDOWN
+    let actions = run_script(&mut reflex, &mut sink, Regime::Mixed, &[calm; RELAX_HOLD_TICKS as usize]);
+    assert_eq!(
+        actions.last().copied().flatten(),
+        Some(ReflexAction::Relaxed { slice_ns: 1_000_000 })
+    );
+    assert!(!reflex.tightened);
+    assert_eq!(sink.knobs, regime_knobs(Regime::Mixed));
+
+    // A NEW SPIKE RUN TIGHTENS AGAIN
+    run_script(&mut reflex, &mut sink, Regime::Mixed, &[spike, spike]);
+    assert_eq!(reflex.tighten_events, 2);
+}
+
+#[test]
+fn reflex_paused_gate_freezes_knobs() {
+    let spike = Regime::Mixed.p99_ceiling() + 1;
+    let gate = tick_decisions(true, false);
+    let baseline = regime_knobs(Regime::Mixed);
+    let mut reflex = Reflex::default();
+    let mut sink = FakeKnobs::new(Regime::Mixed);
+    for _ in 0..5 {
+        let action = reflex.tick(&gate, Regime::Mixed, spike, spike, &baseline);
+        assert_eq!(action, None);
+        // PAUSED: THE MONITOR SKIPS ITS WRITE TOO
+        if gate.batch_tune {
+            sink.write(merge_reflex(baseline, reflex.request()));
+        }
+    }
+    assert!(sink.writes.is_empty());
+    assert_eq!(reflex.spike_count, 0);
+}
+
 // LONGRUN OVERRIDE LOGIC
 
 #[test]
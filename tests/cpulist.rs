@@ -0,0 +1,111 @@
+// PANDEMONIUM CPU LIST TESTS
+// STRICT cpulist PARSING (SINGLES, RANGES, HOLES, WHITESPACE, TRAILING
+// COMMAS, MALFORMED TOKENS, BOUNDS) AND THE ROUND TRIP THROUGH format
+
+use pandemonium::cpulist::{format, parse, CpuListError, MAX_CPU};
+
+#[test]
+fn singles_ranges_and_holes_parse_sorted_and_deduped() {
+    assert_eq!(parse("3"), Ok(vec![3]));
+    assert_eq!(parse("0,6"), Ok(vec![0, 6]));
+    assert_eq!(parse("0-2,6-8"), Ok(vec![0, 1, 2, 6, 7, 8]));
+    assert_eq!(parse("0-3,8,10-11"), Ok(vec![0, 1, 2, 3, 8, 10, 11]));
+    assert_eq!(parse("5-5"), Ok(vec![5]));
+    assert_eq!(parse("9,0,4-5"), Ok(vec![0, 4, 5, 9]));
+    assert_eq!(parse("2-4,3,4-6"), Ok(vec![2, 3, 4, 5, 6]));
+    assert_eq!(parse("0"), Ok(vec![0]));
+    assert_eq!(parse(&MAX_CPU.to_string()), Ok(vec![MAX_CPU]));
+}
+
+#[test]
+fn whitespace_around_entries_and_dashes_is_allowed() {
+    // SYSFS FILES END IN A NEWLINE
+    assert_eq!(parse("0-3\n"), Ok(vec![0, 1, 2, 3]));
+    assert_eq!(parse(" 2 , 8 - 9 "), Ok(vec![2, 8, 9]));
+    assert_eq!(parse("\t1,\t3\n"), Ok(vec![1, 3]));
+    // WHITESPACE INSIDE A NUMBER IS NOT
+    assert_eq!(parse("1 2"), Err(CpuListError::BadToken("1 2".to_string())));
+}
+
+#[test]
+fn empty_lists_and_empty_entries_are_errors() {
+    assert_eq!(parse(""), Err(CpuListError::Empty));
+    assert_eq!(parse("  \n"), Err(CpuListError::Empty));
+    for list in ["2,", "2, ", ",2", "2,,3", ",", "0-3,\n"] {
+        assert_eq!(parse(list), Err(CpuListError::EmptyToken), "{:?}", list);
+    }
+}
+
+#[test]
+fn malformed_tokens_fail_the_whole_list() {
+    for token in [
+        "x", "2a", "+2", "-2", "2-", "-", "1-2-3", "0x4", "2.5", "2;3", "1-x",
+    ] {
+        assert_eq!(
+            parse(token),
+            Err(CpuListError::BadToken(token.to_string())),
+            "{}",
+            token
+        );
+    }
+    // THE OFFENDING ENTRY IS NAMED, NOT THE LIST
+    assert_eq!(
+        parse("0-3,x,8"),
+        Err(CpuListError::BadToken("x".to_string()))
+    );
+    assert_eq!(
+        parse("3-1"),
+        Err(CpuListError::Reversed { start: 3, end: 1 })
+    );
+    assert_eq!(
+        parse("0,5-3"),
+        Err(CpuListError::Reversed { start: 5, end: 3 })
+    );
+}
+
+#[test]
+fn cpus_past_the_kernel_limit_are_errors() {
+    let past = MAX_CPU as u64 + 1;
+    assert_eq!(parse(&past.to_string()), Err(CpuListError::TooLarge(past)));
+    assert_eq!(
+        parse("0-4000000000"),
+        Err(CpuListError::TooLarge(4_000_000_000))
+    );
+    // PAST u64: NOT A NUMBER WORTH ECHOING AS ONE
+    assert_eq!(
+        parse("99999999999999999999999"),
+        Err(CpuListError::BadToken(
+            "99999999999999999999999".to_string()
+        ))
+    );
+}
+
+#[test]
+fn errors_say_what_to_write() {
+    assert!(CpuListError::Reversed { start: 3, end: 1 }
+        .message()
+        .contains("WRITE 1-3"));
+    assert!(CpuListError::BadToken("x".to_string())
+        .message()
+        .contains("\"x\""));
+    assert!(CpuListError::TooLarge(9000)
+        .message()
+        .contains(&MAX_CPU.to_string()));
+    assert!(CpuListError::EmptyToken
+        .message()
+        .contains("TRAILING COMMA"));
+}
+
+#[test]
+fn format_writes_sorted_runs_and_round_trips() {
+    assert_eq!(format(&[0, 1, 2, 3]), "0-3");
+    assert_eq!(format(&[11, 8, 0, 1, 10, 1]), "0-1,8,10-11");
+    assert_eq!(format(&[5]), "5");
+    assert_eq!(format(&[]), "");
+    for list in ["0-3", "0-1,3", "0,2,4-7", "1,3,5,7", "0-3,8-11"] {
+        assert_eq!(format(&parse(list).unwrap()), list);
+    }
+    // ANY SET SURVIVES format -> parse
+    let holes: Vec<u32> = (0..64).filter(|c| c % 3 != 1).collect();
+    assert_eq!(parse(&format(&holes)), Ok(holes));
+}
@@ -0,0 +1,260 @@
+// PANDEMONIUM CONTROL PROTOCOL TESTS
+// COMMAND PARSING, KNOB VALIDATION, HELD KNOBS, RESPONSE ROUND TRIPS,
+// PROFILES, dispatch() THROUGH A FAKE MONITOR LOOP
+
+use std::sync::mpsc;
+
+use pandemonium::ctl::{
+    dispatch, format_knobs, format_regime, ok_kv, parse_command, parse_response, validate_knob,
+    CtlCommand, CtlRequest, KnobOverrides, Profile,
+};
+use pandemonium::gamemode::NO_PID;
+use pandemonium::restart::RestartSummary;
+use pandemonium::tuning::{
+    scaled_regime_knobs, Regime, SliceBounds, TuningKnobs, KNOB_NAMES, MAX_SLICE_NS, MIN_SLICE_NS,
+};
+
+#[test]
+fn parse_all_commands() {
+    assert_eq!(parse_command("get knobs\n"), Ok(CtlCommand::GetKnobs));
+    assert_eq!(parse_command("  get   regime "), Ok(CtlCommand::GetRegime));
+    assert_eq!(
+        parse_command("set slice_ns 750000"),
+        Ok(CtlCommand::SetKnob {
+            name: "slice_ns",
+            value: 750_000
+        })
+    );
+    assert_eq!(
+        parse_command("unset slice_ns"),
+        Ok(CtlCommand::UnsetKnob { name: "slice_ns" })
+    );
+    assert_eq!(
+        parse_command("pin regime heavy"),
+        Ok(CtlCommand::Pin(Regime::Heavy))
+    );
+    assert_eq!(
+        parse_command("pin regime LIGHT"),
+        Ok(CtlCommand::Pin(Regime::Light))
+    );
+    assert_eq!(parse_command("unpin"), Ok(CtlCommand::Unpin));
+    assert_eq!(parse_command("pause"), Ok(CtlCommand::Pause));
+    assert_eq!(parse_command("resume"), Ok(CtlCommand::Resume));
+    assert_eq!(parse_command("stats"), Ok(CtlCommand::Stats));
+    assert_eq!(parse_command("procdb export"), Ok(CtlCommand::ProcdbExport));
+    assert_eq!(
+        parse_command("procdb preempted"),
+        Ok(CtlCommand::ProcdbPreempted)
+    );
+    assert_eq!(
+        parse_command("procdb import"),
+        Ok(CtlCommand::ProcdbImport { replace: false })
+    );
+    assert_eq!(
+        parse_command("procdb import replace"),
+        Ok(CtlCommand::ProcdbImport { replace: true })
+    );
+    assert_eq!(
+        parse_command("boost 1234"),
+        Ok(CtlCommand::Boost { pid: 1234 })
+    );
+    assert_eq!(
+        parse_command("gamemode start 4242"),
+        Ok(CtlCommand::GameStart { pid: 4242 })
+    );
+    assert_eq!(
+        parse_command("gamemode end 4242"),
+        Ok(CtlCommand::GameEnd { pid: 4242 })
+    );
+    // NO PID: THE GAMEMODE-WIDE SESSION
+    assert_eq!(
+        parse_command("gamemode start"),
+        Ok(CtlCommand::GameStart { pid: NO_PID })
+    );
+    assert_eq!(
+        parse_command("gamemode end"),
+        Ok(CtlCommand::GameEnd { pid: NO_PID })
+    );
+}
+
+#[test]
+fn profiles_are_pins() {
+    assert_eq!(
+        parse_command("profile power-saver"),
+        Ok(CtlCommand::Pin(Regime::Heavy))
+    );
+    assert_eq!(
+        parse_command("profile performance"),
+        Ok(CtlCommand::Pin(Regime::Mixed))
+    );
+    assert_eq!(parse_command("profile balanced"), Ok(CtlCommand::Unpin));
+    for p in Profile::ALL {
+        assert_eq!(Profile::from_name(p.name()), Some(p));
+    }
+    let err = parse_command("profile turbo").unwrap_err();
+    assert!(
+        err.contains("power-saver, balanced, performance"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn parse_rejects_bad_input() {
+    assert!(parse_command("").is_err());
+    assert!(parse_command("get").is_err());
+    assert!(parse_command("set slice_ns").is_err());
+    assert!(parse_command("set nope_ns 1000").is_err());
+    assert!(parse_command("set slice_ns -5").is_err());
+    assert!(parse_command("set slice_ns 1.5").is_err());
+    assert!(parse_command("pin regime turbo").is_err());
+    assert!(parse_command("reboot").is_err());
+    assert!(parse_command("procdb import overwrite").is_err());
+    assert!(parse_command("boost 0").is_err());
+    assert!(parse_command("boost -1").is_err());
+    assert!(parse_command("boost self").is_err());
+    assert!(parse_command("profile").is_err());
+    assert!(parse_command("gamemode").is_err());
+    assert!(parse_command("gamemode start 0").is_err());
+    assert!(parse_command("gamemode stop 1").is_err());
+    assert!(parse_command("gamemode end 1 2").is_err());
+}
+
+#[test]
+fn knob_ranges() {
+    assert!(validate_knob("slice_ns", 0).is_err());
+    assert!(validate_knob("slice_ns", 2_000_000_000).is_err());
+    assert!(validate_knob("slice_ns", 750_000).is_ok());
+    assert!(validate_knob("affinity_mode", 2).is_ok());
+    assert!(validate_knob("affinity_mode", 3).is_err());
+    assert!(validate_knob("lag_scale", 0).is_ok());
+}
+
+#[test]
+fn every_knob_name_gets_and_sets() {
+    let mut k = TuningKnobs::default();
+    for (i, name) in KNOB_NAMES.iter().enumerate() {
+        assert!(k.set(name, 1000 + i as u64), "{}", name);
+    }
+    for (i, name) in KNOB_NAMES.iter().enumerate() {
+        assert_eq!(k.get(name), Some(1000 + i as u64), "{}", name);
+    }
+    assert!(!k.set("bogus", 1));
+    assert_eq!(k.get("bogus"), None);
+}
+
+#[test]
+fn held_knobs_survive_regime_writes() {
+    let mut held = KnobOverrides::default();
+    held.set("slice_ns", 750_000);
+    held.set("slice_ns", 800_000);
+
+    // A REGIME CHANGE WRITES THE PROFILE; THE HELD VALUE IS RE-APPLIED
+    let mut knobs = scaled_regime_knobs(Regime::Heavy, 8);
+    held.apply(&mut knobs);
+    assert_eq!(knobs.slice_ns, 800_000);
+    assert_eq!(
+        knobs.batch_slice_ns,
+        scaled_regime_knobs(Regime::Heavy, 8).batch_slice_ns
+    );
+    assert_eq!(held.names(), vec!["slice_ns"]);
+    assert_eq!(held.get("slice_ns"), Some(800_000));
+    assert_eq!(held.get("lag_scale"), None);
+
+    assert!(held.unset("slice_ns"));
+    assert!(!held.unset("slice_ns"));
+    let mut knobs = scaled_regime_knobs(Regime::Heavy, 8);
+    held.apply(&mut knobs);
+    assert_eq!(knobs, scaled_regime_knobs(Regime::Heavy, 8));
+}
+
+#[test]
+fn knobs_response_round_trip() {
+    let k = TuningKnobs {
+        slice_ns: 750_000,
+        ..TuningKnobs::default()
+    };
+    let kv = parse_response(&format_knobs(&k)).unwrap();
+    assert_eq!(kv.len(), KNOB_NAMES.len());
+    assert_eq!(kv["slice_ns"], "750000");
+    assert_eq!(kv["lag_scale"], k.lag_scale.to_string());
+}
+
+#[test]
+fn regime_response_round_trip() {
+    let mut held = KnobOverrides::default();
+    let bounds = SliceBounds::default();
+    let kv = parse_response(&format_regime(
+        Regime::Mixed,
+        false,
+        false,
+        true,
+        &held,
+        &bounds,
+        &RestartSummary::default(),
+    ))
+    .unwrap();
+    assert_eq!(kv["regime"], "MIXED");
+    assert_eq!(kv["pinned"], "false");
+    assert_eq!(kv["paused"], "false");
+    assert_eq!(kv["tightened"], "true");
+    assert_eq!(kv["held"], "-");
+    assert_eq!(kv["slice_min_ns"], MIN_SLICE_NS.to_string());
+    assert_eq!(kv["slice_max_ns"], MAX_SLICE_NS.to_string());
+    assert_eq!(kv["restarts"], "0");
+    assert_eq!(kv["last_restart"], "-");
+
+    held.set("slice_ns", 1);
+    held.set("lag_scale", 2);
+    let bounds = SliceBounds::from_us(200, 8000).unwrap();
+    let kv = parse_response(&format_regime(
+        Regime::Heavy,
+        true,
+        true,
+        false,
+        &held,
+        &bounds,
+        &RestartSummary {
+            count: 3,
+            last_reason: Some("cpu 3 going offline".to_string()),
+        },
+    ))
+    .unwrap();
+    assert_eq!(kv["pinned"], "true");
+    assert_eq!(kv["paused"], "true");
+    assert_eq!(kv["held"], "slice_ns,lag_scale");
+    assert_eq!(kv["slice_min_ns"], "200000");
+    assert_eq!(kv["slice_max_ns"], "8000000");
+    assert_eq!(kv["restarts"], "3");
+    assert_eq!(kv["last_restart"], "cpu 3 going offline");
+}
+
+#[test]
+fn error_responses() {
+    assert_eq!(
+        parse_response("ERR UNKNOWN KNOB x\n"),
+        Err("UNKNOWN KNOB x".to_string())
+    );
+    assert!(parse_response("").is_err());
+    assert!(parse_response("garbage\n").is_err());
+}
+
+#[test]
+fn dispatch_answers_or_reports_a_dead_loop() {
+    let (tx, rx) = mpsc::channel::<CtlRequest>();
+    let monitor = std::thread::spawn(move || {
+        let req = rx.recv().unwrap();
+        assert_eq!(req.cmd, CtlCommand::Boost { pid: 42 });
+        req.reply.send(ok_kv(&[("pid", "42".to_string())])).unwrap();
+    });
+    let resp = dispatch(&tx, CtlCommand::Boost { pid: 42 });
+    assert_eq!(parse_response(&resp).unwrap()["pid"], "42");
+    monitor.join().unwrap();
+
+    // RECEIVER GONE: THE SCHEDULER IS EXITING
+    let resp = dispatch(&tx, CtlCommand::Stats);
+    assert_eq!(
+        parse_response(&resp),
+        Err("SCHEDULER IS SHUTTING DOWN".to_string())
+    );
+}
@@ -0,0 +1,102 @@
+// PANDEMONIUM D-BUS INTERFACE TESTS (cargo test --features dbus)
+// METHODS THROUGH A FAKE MONITOR LOOP, ERROR MAPPING, PropertiesChanged,
+// NAME RELEASE. NEEDS A SESSION BUS: SKIPPED WITHOUT DBUS_SESSION_BUS_ADDRESS.
+
+#![cfg(feature = "dbus")]
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use pandemonium::ctl::{self, CtlCommand, CtlRequest, LiveStatus};
+use pandemonium::dbus::{serve, BUS_NAME, INTERFACE, OBJECT_PATH};
+use pandemonium::tuning::Regime;
+use zbus::blocking::{fdo::DBusProxy, Connection, Proxy};
+
+// ANSWERS LIKE adaptive::monitor_loop, FORWARDS EVERY COMMAND TO THE TEST
+fn fake_monitor_loop() -> (mpsc::Sender<CtlRequest>, mpsc::Receiver<CtlCommand>) {
+    let (tx, rx) = mpsc::channel::<CtlRequest>();
+    let (seen_tx, seen_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for req in rx {
+            let resp = match req.cmd {
+                CtlCommand::Boost { pid: 999_999 } => ctl::err("NO SUCH PID 999999"),
+                _ => ctl::ok_kv(&[]),
+            };
+            let _ = seen_tx.send(req.cmd);
+            let _ = req.reply.send(resp);
+        }
+    });
+    (tx, seen_rx)
+}
+
+fn error_name(e: zbus::Error) -> String {
+    match e {
+        zbus::Error::MethodError(name, _, _) => name.to_string(),
+        other => panic!("NOT A METHOD ERROR: {}", other),
+    }
+}
+
+// ONE TEST: THE WELL-KNOWN NAME CAN ONLY BE OWNED ONCE PER BUS
+#[test]
+fn scheduler1_on_the_session_bus() {
+    if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+        eprintln!("SKIPPED: NO SESSION BUS (DBUS_SESSION_BUS_ADDRESS UNSET)");
+        return;
+    }
+    let (ctl_tx, seen) = fake_monitor_loop();
+    let live = serve(true, ctl_tx).expect("serve on the session bus");
+
+    let conn = Connection::session().unwrap();
+    let proxy = Proxy::new(&conn, BUS_NAME, OBJECT_PATH, INTERFACE).unwrap();
+
+    // METHODS ARE CONTROL-SOCKET COMMANDS
+    proxy
+        .call::<_, _, ()>("SetProfile", &("performance",))
+        .unwrap();
+    assert_eq!(seen.recv().unwrap(), CtlCommand::Pin(Regime::Mixed));
+    proxy
+        .call::<_, _, ()>("SetProfile", &("balanced",))
+        .unwrap();
+    assert_eq!(seen.recv().unwrap(), CtlCommand::Unpin);
+    proxy.call::<_, _, ()>("PinRegime", &("heavy",)).unwrap();
+    assert_eq!(seen.recv().unwrap(), CtlCommand::Pin(Regime::Heavy));
+    proxy.call::<_, _, ()>("Boost", &(4242u32,)).unwrap();
+    assert_eq!(seen.recv().unwrap(), CtlCommand::Boost { pid: 4242 });
+
+    // REJECTED BY THE PARSER: NEVER REACHES THE LOOP
+    let e = proxy
+        .call::<_, _, ()>("SetProfile", &("turbo",))
+        .unwrap_err();
+    assert_eq!(error_name(e), "org.freedesktop.DBus.Error.InvalidArgs");
+    let e = proxy.call::<_, _, ()>("Boost", &(0u32,)).unwrap_err();
+    assert_eq!(error_name(e), "org.freedesktop.DBus.Error.InvalidArgs");
+    assert!(seen.try_recv().is_err());
+
+    // ERR FROM THE LOOP
+    let e = proxy.call::<_, _, ()>("Boost", &(999_999u32,)).unwrap_err();
+    assert_eq!(error_name(e), "org.freedesktop.DBus.Error.Failed");
+
+    // NOTHING FROM THE LOOP YET
+    assert_eq!(proxy.get_property::<String>("Regime").unwrap(), "");
+    assert_eq!(proxy.get_property::<u64>("P99Us").unwrap(), 0);
+
+    let changes = proxy.receive_property_changed::<u64>("P99Us");
+    live.send(LiveStatus {
+        regime: Regime::Heavy,
+        p99_us: 850,
+    })
+    .unwrap();
+    let p99 = changes.map(|c| c.get().unwrap()).find(|&v| v != 0).unwrap();
+    assert_eq!(p99, 850);
+    // THE CACHE FOLLOWS THE SAME SIGNAL
+    assert_eq!(proxy.get_property::<String>("Regime").unwrap(), "HEAVY");
+
+    // DROPPING THE SENDER (SCHEDULER EXIT) RELEASES THE NAME
+    drop(live);
+    let bus = DBusProxy::new(&conn).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while bus.name_has_owner(BUS_NAME.try_into().unwrap()).unwrap() {
+        assert!(Instant::now() < deadline, "{} STILL OWNED", BUS_NAME);
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
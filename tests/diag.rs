@@ -0,0 +1,241 @@
+// PANDEMONIUM DIAGNOSTIC SNAPSHOT TESTS
+// REGIME HISTORY BOUNDS, HISTOGRAM FORMATTING, PROCDB TOP-N ORDERING,
+// --verbose SECTION CADENCE, PREDICTION CHANGE + FLAP WITHHOLD REPORTING,
+// APPLIED LAG HISTOGRAM
+
+use std::collections::HashMap;
+
+use pandemonium::diag::{
+    comm_str, diag_path, format_hist, format_kv_line, format_lag_hist, format_lag_summary,
+    format_profile, prediction_changes, should_dump_stats, top_profiles, withheld_predictions,
+    RegimeChange, RegimeHistory, REGIME_HISTORY_CAP,
+};
+use pandemonium::procdb::{FlapTracker, TaskProfile};
+use pandemonium::tuning::{
+    lag_bucket, lag_bucket_label, LagHist, Regime, HIST_BUCKETS, LAG_CLAMP_KEY, LAG_HIST_BUCKETS,
+    LAG_HIST_KEYS,
+};
+
+fn comm(name: &str) -> [u8; 16] {
+    let mut c = [0u8; 16];
+    c[..name.len()].copy_from_slice(name.as_bytes());
+    c
+}
+
+#[test]
+fn regime_history_is_bounded() {
+    let mut h = RegimeHistory::default();
+    assert!(h.is_empty());
+    for tick in 0..(REGIME_HISTORY_CAP as u64 + 10) {
+        h.push(RegimeChange {
+            tick,
+            from: Regime::Mixed,
+            to: Regime::Heavy,
+            idle_pct: 5,
+            cause: "detect",
+        });
+    }
+    assert_eq!(h.len(), REGIME_HISTORY_CAP);
+
+    // OLDEST DROPPED: FIRST LINE IS TICK 10
+    let lines = h.lines();
+    assert!(lines[0].starts_with("tick 10 "), "{}", lines[0]);
+    assert!(lines[0].contains("MIXED -> HEAVY"));
+    assert!(lines[0].ends_with("idle=5% (detect)"));
+}
+
+#[test]
+fn hist_lists_every_bucket_with_p99() {
+    let mut counts = [0u64; HIST_BUCKETS];
+    counts[0] = 990;
+    counts[7] = 10; // 2MS BUCKET
+    let text = format_hist(1, &counts);
+    let mut lines = text.lines();
+    assert_eq!(lines.next().unwrap(), "INTERACTIVE (samples=1000 p99=10us)");
+    assert_eq!(lines.clone().count(), HIST_BUCKETS);
+    assert!(text.contains("<= 2000us   10"));
+    assert!(text.contains("<= +inf"));
+}
+
+#[test]
+fn top_profiles_most_observed_first() {
+    let mut db = HashMap::new();
+    for (name, obs) in [("make", 5), ("cc1", 50), ("ld", 50), ("kwin", 20)] {
+        db.insert(
+            comm(name),
+            TaskProfile {
+                observations: obs,
+                ..Default::default()
+            },
+        );
+    }
+    let top = top_profiles(&db, 3);
+    let names: Vec<String> = top.iter().map(|(c, _)| comm_str(c)).collect();
+    // TIE ON 50 BROKEN BY comm BYTES
+    assert_eq!(names, vec!["cc1", "ld", "kwin"]);
+}
+
+#[test]
+fn profile_line_names_tier() {
+    let p = TaskProfile {
+        tier_votes: [0, 0, 9],
+        avg_runtime_ns: 120_000,
+        observations: 9,
+        ..Default::default()
+    };
+    let line = format_profile(&comm("pipewire"), &p);
+    assert!(line.starts_with("pipewire "));
+    assert!(line.contains("tier=LAT_CRITICAL"));
+    assert!(line.contains("avg_rt=120us"));
+    assert!(line.ends_with(" flap=0%"));
+
+    let p = TaskProfile {
+        flap: FlapTracker {
+            flaps: 6,
+            pairs: 8,
+            withheld: true,
+            ..Default::default()
+        },
+        ..p
+    };
+    assert!(format_profile(&comm("pipewire"), &p).ends_with(" flap=75% WITHHELD"));
+}
+
+#[test]
+fn comm_and_path() {
+    assert_eq!(comm_str(&comm("")), "");
+    assert_eq!(comm_str(&[b'x'; 16]), "x".repeat(16));
+    assert_eq!(
+        diag_path("20261016-120000"),
+        "/tmp/pandemonium/diag-20261016-120000.txt"
+    );
+}
+
+#[test]
+fn stats_dump_every_tenth_tick() {
+    let ticks: Vec<u64> = (0..35).filter(|t| should_dump_stats(*t)).collect();
+    assert_eq!(ticks, vec![9, 19, 29]);
+}
+
+#[test]
+fn kv_line_is_single_tagged_line() {
+    let line = format_kv_line("STATS", &[("nr_dispatches", 42), ("nr_shared", 0)]);
+    assert_eq!(line, "[STATS] nr_dispatches=42 nr_shared=0");
+    assert_eq!(format_kv_line("KNOB-CHANGE", &[]), "[KNOB-CHANGE]");
+    // THE PYTHON HARNESS PARSES [KNOBS] LINES; VERBOSE TAGS MUST NOT COLLIDE
+    assert!(!line.contains("[KNOBS]"));
+}
+
+#[test]
+fn prediction_changes_report_only_news() {
+    let mut last = HashMap::new();
+    let first = prediction_changes(&mut last, &[(comm("kwin"), 2), (comm("make"), 0)]);
+    assert_eq!(first, vec!["kwin=LAT_CRITICAL", "make=BATCH"]);
+
+    // RUNTIME-ONLY REWRITE: NOTHING TO SAY
+    let same = prediction_changes(&mut last, &[(comm("kwin"), 2)]);
+    assert!(same.is_empty());
+
+    // RE-TIERED + NEW; make, ABSENT FROM THIS DELTA, IS STILL KNOWN
+    let moved = prediction_changes(&mut last, &[(comm("kwin"), 1), (comm("cc1"), 0)]);
+    assert_eq!(moved, vec!["cc1=BATCH", "kwin=INTERACTIVE"]);
+    assert!(prediction_changes(&mut last, &[(comm("make"), 0)]).is_empty());
+
+    // EVICTED BY THE CALLER: ITS RETURN IS NEWS AGAIN
+    last.remove(&comm("make"));
+    let back = prediction_changes(&mut last, &[(comm("make"), 0)]);
+    assert_eq!(back, vec!["make=BATCH"]);
+
+    // WITHHELD FOR FLAPPING: REPORTED, THEN FORGOTTEN LIKE AN EVICTION
+    let flapping = withheld_predictions(&mut last, &[(comm("make"), 71), (comm("cc1"), 50)]);
+    assert_eq!(flapping, vec!["cc1=50%", "make=71%"]);
+    assert!(!last.contains_key(&comm("make")));
+    assert!(last.contains_key(&comm("kwin")));
+    let back = prediction_changes(&mut last, &[(comm("make"), 0)]);
+    assert_eq!(back, vec!["make=BATCH"]);
+}
+
+// APPLIED LAG HISTOGRAM
+
+// MIRRORS lag_bucket() IN main.bpf.c: task_deadline() CLAMPS TO 1..=64
+#[test]
+fn lag_buckets_match_bpf() {
+    let expect = [
+        (0, 0),
+        (1, 0),
+        (2, 1),
+        (3, 1),
+        (4, 2),
+        (7, 2),
+        (8, 3),
+        (16, 4),
+        (31, 4),
+        (32, 5),
+        (63, 5),
+        (64, 6),
+        (1000, 6),
+    ];
+    for (scale, bucket) in expect {
+        assert_eq!(lag_bucket(scale), bucket, "scale {}", scale);
+    }
+    assert_eq!(LAG_HIST_KEYS, LAG_HIST_BUCKETS + 1);
+    assert_eq!(LAG_CLAMP_KEY, LAG_HIST_BUCKETS);
+    let labels: Vec<String> = (0..LAG_HIST_BUCKETS).map(lag_bucket_label).collect();
+    assert_eq!(labels, ["1", "2-3", "4-7", "8-15", "16-31", "32-63", "64"]);
+}
+
+#[test]
+fn lag_hist_delta_and_percentiles() {
+    let prev = LagHist::from_keys(&[10, 0, 100, 0, 0, 0, 0, 5]);
+    let cur = LagHist::from_keys(&[20, 0, 180, 0, 10, 0, 0, 6]);
+    let d = cur.delta(&prev);
+    assert_eq!(d.counts, [10, 0, 80, 0, 10, 0, 0]);
+    assert_eq!(d.clamped, 1);
+    assert_eq!(d.total(), 100);
+    assert_eq!(d.percentile(50), 4);
+    assert_eq!(d.percentile(90), 4);
+    assert_eq!(d.percentile(99), 16);
+    assert_eq!(d.percentile(5), 1);
+    assert_eq!(d.max(), 16);
+    assert_eq!(d.clamp_pct(), 1);
+
+    // BPF RESTART ZEROED THE MAP: THE NEW COUNTS ARE THE INTERVAL
+    let restarted = LagHist::from_keys(&[3, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(restarted.delta(&cur), restarted);
+
+    let empty = LagHist::default();
+    assert_eq!(empty.percentile(50), 0);
+    assert_eq!(empty.max(), 0);
+    assert_eq!(empty.clamp_pct(), 0);
+}
+
+#[test]
+fn lag_summary_line() {
+    let h = LagHist::from_keys(&[0, 50, 50, 0, 0, 0, 0, 25]);
+    assert_eq!(
+        format_lag_summary(4, &h),
+        "[LAG] knob=4 deadlines=100 p50=x2 p90=x4 max=x4 clamp=25%"
+    );
+    assert_eq!(
+        format_lag_summary(6, &LagHist::default()),
+        "[LAG] knob=6 deadlines=0 p50=x0 p90=x0 max=x0 clamp=0%"
+    );
+}
+
+#[test]
+fn lag_hist_dump_lists_every_bucket() {
+    let h = LagHist::from_keys(&[7, 0, 3, 0, 0, 0, 1, 2]);
+    let out = format_lag_hist(&h);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 1 + LAG_HIST_BUCKETS);
+    assert_eq!(lines[0], "deadlines=11 floor_clamps=2 (18%)");
+    assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(), ["x1", "7"]);
+    assert_eq!(
+        lines[3].split_whitespace().collect::<Vec<_>>(),
+        ["x4-7", "3"]
+    );
+    assert_eq!(
+        lines[7].split_whitespace().collect::<Vec<_>>(),
+        ["x64", "1"]
+    );
+}
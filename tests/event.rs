@@ -1,25 +1,44 @@
 // PANDEMONIUM EVENT LOG TESTS
-// UNIT TESTS FOR THE PRE-ALLOCATED RING BUFFER
+// UNIT TESTS FOR THE PRE-ALLOCATED RING BUFFER, DIAG TAIL TABLE,
+// WHOLE-RUN WAKEUP PATH BUDGET, SUMMARY MATH OVER KNOWN TICKS AND THE
+// CONSOLIDATED SHUTDOWN BLOCK
 
-use pandemonium::event::{EventLog, MAX_SNAPSHOTS};
+use pandemonium::event::{
+    AdaptiveTally, EventLog, RunTotals, TickCounters, WakeHeat, WakePathBudget, HEAT_MINUTES,
+    MAX_SNAPSHOTS, WAKE_PATHS,
+};
+use pandemonium::stats::StatsDelta;
+use pandemonium::tuning::{HIST_BUCKETS, HIST_EDGES_NS};
+
+const SEC: u64 = 1_000_000_000;
+
+fn dispatches(n: u64) -> TickCounters {
+    TickCounters {
+        dispatches: n,
+        ..Default::default()
+    }
+}
 
 #[test]
 fn snapshot_records() {
     let mut log = EventLog::new();
     assert_eq!(log.len(), 0);
 
-    log.snapshot(100, 90, 10, 5, 30, 65, 20, 10, 40, 50);
+    let tick = TickCounters {
+        dispatches: 100,
+        idle_hits: 90,
+        shared: 10,
+        preempt: 5,
+        keep_run: 30,
+        wake_avg_us: 65,
+        hard_kicks: 20,
+        soft_kicks: 10,
+        lat_idle_us: 40,
+        lat_kick_us: 50,
+    };
+    log.snapshot(tick);
     assert_eq!(log.len(), 1);
-    assert_eq!(log.get(0).dispatches, 100);
-    assert_eq!(log.get(0).idle_hits, 90);
-    assert_eq!(log.get(0).shared, 10);
-    assert_eq!(log.get(0).preempt, 5);
-    assert_eq!(log.get(0).keep_run, 30);
-    assert_eq!(log.get(0).wake_avg_us, 65);
-    assert_eq!(log.get(0).hard_kicks, 20);
-    assert_eq!(log.get(0).soft_kicks, 10);
-    assert_eq!(log.get(0).lat_idle_us, 40);
-    assert_eq!(log.get(0).lat_kick_us, 50);
+    assert_eq!(log.get(0).tick, tick);
     assert!(log.get(0).ts_ns > 0);
 }
 
@@ -29,19 +48,22 @@ fn ring_buffer_wraps() {
 
     // FILL TO CAPACITY
     for i in 0..MAX_SNAPSHOTS {
-        log.snapshot(i as u64, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+        log.snapshot(dispatches(i as u64));
     }
     assert_eq!(log.len(), MAX_SNAPSHOTS);
     assert_eq!(log.head(), 0); // WRAPPED BACK TO START
 
     // WRITE ONE MORE -- OVERWRITES OLDEST
-    log.snapshot(9999, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+    log.snapshot(dispatches(9999));
     assert_eq!(log.len(), MAX_SNAPSHOTS);
     assert_eq!(log.head(), 1);
-    assert_eq!(log.get(0).dispatches, 9999);
+    assert_eq!(log.get(0).tick.dispatches, 9999);
 
     // CHRONOLOGICAL ITERATION STARTS FROM OLDEST (INDEX 1)
-    let ordered: Vec<u64> = log.iter_chronological().map(|s| s.dispatches).collect();
+    let ordered: Vec<u64> = log
+        .iter_chronological()
+        .map(|s| s.tick.dispatches)
+        .collect();
     assert_eq!(ordered[0], 1); // OLDEST SURVIVING ENTRY
     assert_eq!(*ordered.last().unwrap(), 9999); // NEWEST
     assert_eq!(ordered.len(), MAX_SNAPSHOTS);
@@ -50,20 +72,379 @@ fn ring_buffer_wraps() {
 #[test]
 fn summary_no_panic_empty() {
     let log = EventLog::new();
-    log.summary(); // SHOULD NOT PANIC WITH 0 SNAPSHOTS
+    log.summary(&RunTotals::default()); // SHOULD NOT PANIC WITH 0 SNAPSHOTS
 }
 
 #[test]
 fn summary_no_panic_one() {
     let mut log = EventLog::new();
-    log.snapshot(100, 50, 50, 10, 20, 70, 0, 0, 0, 0);
-    log.summary(); // SHOULD NOT PANIC WITH 1 SNAPSHOT
+    log.snapshot(dispatches(100));
+    assert_eq!(log.run_summary(), None);
+    log.summary(&RunTotals::default()); // SHOULD NOT PANIC WITH 1 SNAPSHOT
 }
 
 #[test]
 fn dump_no_panic() {
     let mut log = EventLog::new();
-    log.snapshot(100, 50, 50, 5, 25, 70, 0, 0, 0, 0);
-    log.snapshot(200, 150, 50, 10, 40, 150, 0, 0, 0, 0);
+    log.snapshot(dispatches(100));
+    log.snapshot(dispatches(200));
     log.dump(); // SHOULD NOT PANIC
 }
+
+#[test]
+fn tail_table_keeps_newest() {
+    let mut log = EventLog::new();
+    assert!(!log.wrapped());
+    assert_eq!(log.tail_table(3).lines().count(), 1); // HEADER ONLY
+
+    for i in 0..10u64 {
+        log.snapshot(dispatches(1000 + i));
+    }
+    let table = log.tail_table(3);
+    let rows: Vec<&str> = table.lines().skip(1).collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].contains(" 1007 "));
+    assert!(rows[2].starts_with("0.0 ")); // NEWEST HAS AGE 0
+    assert!(rows[2].contains(" 1009 "));
+}
+
+#[test]
+fn tick_counters_take_each_field_from_its_own_counter() {
+    // EVERY INPUT DISTINCT: A SWAPPED FIELD SHOWS UP AS A WRONG VALUE
+    let d = StatsDelta {
+        dispatches: 1000,
+        idle_hits: 600,
+        shared: 300,
+        preempt: 7,
+        keep_running: 11,
+        hard_kicks: 13,
+        soft_kicks: 17,
+        wake_lat_samples: 100,
+        wake_lat_sum: 100 * 42_000,
+        wake_lat_idle_cnt: 60,
+        wake_lat_idle_sum: 60 * 19_000,
+        wake_lat_kick_cnt: 20,
+        wake_lat_kick_sum: 20 * 88_000,
+        ..Default::default()
+    };
+    assert_eq!(
+        TickCounters::from_delta(&d),
+        TickCounters {
+            dispatches: 1000,
+            idle_hits: 600,
+            shared: 300,
+            preempt: 7,
+            keep_run: 11,
+            wake_avg_us: 42,
+            hard_kicks: 13,
+            soft_kicks: 17,
+            lat_idle_us: 19,
+            lat_kick_us: 88,
+        }
+    );
+}
+
+#[test]
+fn summary_math_over_known_ticks() {
+    let mut log = EventLog::new();
+    let tick = |d, idle, kicks| TickCounters {
+        dispatches: d,
+        idle_hits: idle,
+        shared: d - idle,
+        preempt: 1,
+        keep_run: 2,
+        wake_avg_us: 500, // AN AVERAGE: NEVER SUMMED
+        hard_kicks: kicks,
+        soft_kicks: 2 * kicks,
+        ..Default::default()
+    };
+    log.record(10 * SEC, tick(1000, 400, 5));
+    log.record(11 * SEC, tick(3000, 900, 10));
+    log.record(14 * SEC, tick(2000, 700, 0));
+
+    let sum = log.run_summary().unwrap();
+    let t = sum.totals;
+    assert_eq!(t.dispatches, 6000);
+    assert_eq!(t.idle_hits, 2000);
+    assert_eq!(t.shared, 4000);
+    assert_eq!(t.preempt, 3);
+    assert_eq!(t.keep_run, 6);
+    assert_eq!((t.hard_kicks, t.soft_kicks), (15, 30));
+    assert_eq!(t.wake_avg_us, 0);
+    assert_eq!(sum.peak_dispatches, 3000);
+    assert_eq!(sum.samples, 3);
+    assert_eq!(sum.elapsed_s, 4.0);
+    assert_eq!(sum.avg_dispatch_per_s(), Some(1500.0));
+    assert!((sum.idle_hit_pct() - 33.333).abs() < 0.01);
+
+    let lines = sum.lines();
+    assert_eq!(lines[0], "PANDEMONIUM SUMMARY");
+    for want in [
+        "  TOTAL DISPATCHES:  6000",
+        "  TOTAL IDLE HITS:   2000",
+        "  PEAK DISPATCH/S:   3000",
+        "  AVG DISPATCH/S:    1500",
+        "  IDLE HIT RATE:     33.3%",
+        "  ELAPSED:           4.0s",
+        "  SAMPLES:           3",
+    ] {
+        assert!(lines.iter().any(|l| l == want), "{:?} IN {:?}", want, lines);
+    }
+}
+
+#[test]
+fn summary_without_elapsed_time_has_no_rates() {
+    let mut log = EventLog::new();
+    log.record(5 * SEC, dispatches(100));
+    log.record(5 * SEC, dispatches(0));
+    let sum = log.run_summary().unwrap();
+    assert_eq!(sum.avg_dispatch_per_s(), None);
+    assert_eq!(sum.idle_hit_pct(), 0.0);
+    assert!(!sum.lines().iter().any(|l| l.contains("AVG DISPATCH/S")));
+}
+
+#[test]
+fn dump_and_tail_table_share_columns() {
+    let mut log = EventLog::new();
+    log.record(
+        SEC,
+        TickCounters {
+            dispatches: 1234,
+            idle_hits: 56,
+            hard_kicks: 7,
+            soft_kicks: 8,
+            lat_idle_us: 9,
+            lat_kick_us: 10,
+            ..Default::default()
+        },
+    );
+    let table = log.tail_table(1);
+    let mut lines = table.lines();
+    let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    let row: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    assert_eq!(header.len(), row.len());
+    let col = |name: &str| row[header.iter().position(|h| *h == name).unwrap()];
+    assert_eq!(col("DISPATCH/S"), "1234");
+    assert_eq!(col("IDLE/S"), "56");
+    assert_eq!(col("KICK_H"), "7");
+    assert_eq!(col("KICK_S"), "8");
+    assert_eq!(col("LAT_IDLE"), "9");
+    assert_eq!(col("LAT_KICK"), "10");
+}
+
+#[test]
+fn shutdown_block_with_fixed_inputs() {
+    let mut log = EventLog::new();
+    log.record(0, dispatches(1000));
+    log.record(2 * SEC, dispatches(3000));
+    // 990 WAKEUPS IN THE 2ND BUCKET, 10 IN THE 5TH: P99 IS THE 2ND EDGE
+    let mut wake_hist = [[0u64; HIST_BUCKETS]; 3];
+    wake_hist[1][1] = 990;
+    wake_hist[2][4] = 10;
+    let totals = RunTotals {
+        hard_kicks: 120,
+        soft_kicks: 45,
+        wake_hist,
+        lag_deadlines: 200,
+        lag_clamps: 20,
+        lightweight: false,
+    };
+    let p99_us = HIST_EDGES_NS[1] / 1000;
+
+    // BPF-ONLY: NO REFLEX OR REGIME LINES
+    let lines = log.summary_lines(&totals);
+    assert_eq!(lines[0], "PANDEMONIUM SUMMARY");
+    let block: Vec<&str> = lines
+        .iter()
+        .map(|l| l.as_str())
+        .skip_while(|l| !l.contains("WAKE P99"))
+        .collect();
+    assert_eq!(
+        block,
+        vec![
+            format!("  WAKE P99 (RUN):    {}us", p99_us).as_str(),
+            "  TOTAL KICKS:       H=120 S=45",
+            "  LAG CLAMPS:        20 (10% OF DEADLINES)",
+        ]
+    );
+
+    log.adaptive = Some(AdaptiveTally {
+        regime_ticks: [30, 60, 10],
+        tighten_events: 4,
+        relax_steps: 11,
+    });
+    let lines = log.summary_lines(&totals);
+    assert_eq!(
+        &lines[lines.len() - 2..],
+        [
+            "  REFLEX:            TIGHTEN=4 RELAX_STEPS=11",
+            "  REGIME TIME:       LIGHT=30.0% MIXED=60.0% HEAVY=10.0%",
+        ]
+    );
+
+    // LIGHTWEIGHT, OR NO WAKEUP SAMPLED: NO P99 TO CLAIM
+    for t in [
+        RunTotals {
+            lightweight: true,
+            ..totals
+        },
+        RunTotals {
+            wake_hist: [[0; HIST_BUCKETS]; 3],
+            ..totals
+        },
+    ] {
+        assert_eq!(t.lines(None)[0], "  WAKE P99 (RUN):    -");
+    }
+    // NO TICKS YET: ALL ZERO, NOT NaN
+    assert_eq!(AdaptiveTally::default().regime_pct(), [0.0; 3]);
+    // UNDER TWO SNAPSHOTS: NO BLOCK AT ALL
+    assert!(EventLog::new().summary_lines(&totals).is_empty());
+}
+
+// ONE TICK: (COUNT, AVG_US) FOR IDLE, HARD KICK, SOFT KICK
+fn path_tick(paths: [(u64, u64); 3]) -> StatsDelta {
+    let [(ic, ia), (kc, ka), (sc, sa)] = paths;
+    StatsDelta {
+        wake_lat_samples: ic + kc + sc,
+        wake_lat_sum: (ic * ia + kc * ka + sc * sa) * 1000,
+        wake_lat_idle_cnt: ic,
+        wake_lat_idle_sum: ic * ia * 1000,
+        wake_lat_kick_cnt: kc,
+        wake_lat_kick_sum: kc * ka * 1000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn path_budget_accumulates_per_path() {
+    let mut b = WakePathBudget::default();
+    b.record(&path_tick([(900, 4), (50, 80), (50, 30)]));
+    b.record(&path_tick([(100, 6), (50, 400), (0, 0)]));
+    let [idle, kick, soft] = b.paths;
+    assert_eq!((idle.count, kick.count, soft.count), (1000, 100, 50));
+    assert_eq!(b.total(), 1150);
+    // (900*4 + 100*6) / 1000 = 4.2US
+    assert_eq!(idle.avg_us(), 4);
+    assert_eq!(kick.avg_us(), 240);
+    assert_eq!(soft.avg_us(), 30);
+    // WORST 1-SECOND AVERAGE, NOT THE RUN AVERAGE
+    assert_eq!(kick.worst_ns, 400_000);
+    // HALF THE KICKED WAKEUPS SAT IN THE 400US SECOND: P99 IS ITS BUCKET
+    assert_eq!(kick.p99_us(), 500);
+    assert_eq!(idle.p99_us(), 10);
+}
+
+#[test]
+fn path_budget_skips_reset_and_empty_paths() {
+    let mut b = WakePathBudget::default();
+    b.record(&StatsDelta {
+        reset: true,
+        ..path_tick([(10, 5000), (10, 5000), (10, 5000)])
+    });
+    assert_eq!(b, WakePathBudget::default());
+    assert!(b.table().is_empty());
+
+    // NO KICKS THIS TICK: THE KICK PATH HISTOGRAM STAYS EMPTY
+    b.record(&path_tick([(10, 5), (0, 0), (0, 0)]));
+    assert_eq!(b.paths[1].hist.iter().sum::<u64>(), 0);
+    assert_eq!(b.paths[1].worst_ns, 0);
+    // SOFT KICK IS THE REMAINDER: NEVER NEGATIVE ON A SKEWED SNAPSHOT
+    b.record(&StatsDelta {
+        wake_lat_samples: 5,
+        wake_lat_idle_cnt: 10,
+        ..Default::default()
+    });
+    assert_eq!(b.paths[2].count, 0);
+}
+
+#[test]
+fn path_budget_table() {
+    let mut b = WakePathBudget::default();
+    b.record(&path_tick([(750, 3), (200, 120), (50, 40)]));
+    let table = b.table();
+    assert_eq!(table.len(), 1 + WAKE_PATHS.len());
+    assert!(table[0].contains("SHARE") && table[0].contains("WORST_US"));
+    let cols: Vec<&str> = table[2].split_whitespace().collect();
+    assert_eq!(cols, ["hard_kick", "200", "20.0%", "120", "250", "120"]);
+    assert!(table[1].contains(" 75.0% "));
+    assert!(table[3].starts_with("  soft_kick "));
+}
+
+#[test]
+fn path_budget_outlives_the_ring() {
+    let mut log = EventLog::new();
+    for _ in 0..MAX_SNAPSHOTS + 10 {
+        log.snapshot(TickCounters::default());
+        log.paths.record(&path_tick([(1, 5), (0, 0), (0, 0)]));
+    }
+    assert!(log.wrapped());
+    assert_eq!(log.paths.total(), MAX_SNAPSHOTS as u64 + 10);
+    log.summary(&RunTotals::default()); // SHOULD NOT PANIC WITH THE PATH TABLE
+}
+
+// ONE TICK WITH n WAKEUPS IN bucket, SPLIT ACROSS TWO TIERS
+fn heat_tick(bucket: usize, n: u64) -> [[u64; HIST_BUCKETS]; 3] {
+    let mut delta = [[0u64; HIST_BUCKETS]; 3];
+    delta[0][bucket] = n / 2;
+    delta[2][bucket] = n - n / 2;
+    delta
+}
+
+#[test]
+fn wake_heat_folds_ticks_into_minutes() {
+    let mut heat = WakeHeat::default();
+    assert!(heat.is_empty());
+    let t0 = 500 * SEC;
+    heat.add(t0, &heat_tick(1, 10));
+    heat.add(t0 + 30 * SEC, &heat_tick(1, 5));
+    heat.add(t0 + 59 * SEC, &heat_tick(4, 3));
+    // MINUTE 1 HAD NO TICK (SUSPENDED): MINUTE 2 FOLLOWS MINUTE 0
+    heat.add(t0 + 150 * SEC, &heat_tick(0, 1));
+    let rows = heat.rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[0].0, rows[0].1[1], rows[0].1[4]), (0, 15, 3));
+    assert_eq!((rows[1].0, rows[1].1[0]), (2, 1));
+    let lines = heat.lines();
+    assert!(lines[0].trim_start().starts_with("MIN"), "{:?}", lines);
+    assert!(lines[2].starts_with("     2 "), "{:?}", lines);
+
+    // PAST HEAT_MINUTES THE OLDEST DROP, AND THE MAP SAYS SO
+    let mut heat = WakeHeat::default();
+    for m in 0..HEAT_MINUTES as u64 + 2 {
+        heat.add(m * 60 * SEC, &heat_tick(2, 4));
+    }
+    let rows = heat.rows();
+    assert_eq!(rows.len(), HEAT_MINUTES);
+    assert_eq!(rows[0].0, 2);
+    assert_eq!(
+        heat.lines().last().unwrap(),
+        &format!("  (2 EARLIER MINUTES DROPPED, LAST {} KEPT)", HEAT_MINUTES)
+    );
+    // THE EVENT LOG STARTS WITH AN EMPTY MAP
+    assert!(EventLog::new().heat.is_empty());
+}
+
+#[test]
+fn run_cdf_follows_the_p99_line() {
+    let mut wake_hist = [[0u64; HIST_BUCKETS]; 3];
+    wake_hist[1][1] = 990;
+    wake_hist[2][4] = 10;
+    let totals = RunTotals {
+        wake_hist,
+        ..RunTotals::default()
+    };
+    let lines = totals.cdf_lines();
+    assert_eq!(lines.len(), 1 + HIST_BUCKETS);
+    assert_eq!(lines[0], "  WAKE LATENCY CDF (RUN, ALL TIERS):");
+    assert!(lines[2].starts_with("  <=  25us |"), "{}", lines[2]);
+    assert!(lines[2].ends_with("|  99.0%"), "{}", lines[2]);
+    assert!(lines[5].ends_with("| 100.0%"), "{}", lines[5]);
+
+    // NO P99, NO CDF
+    let light = RunTotals {
+        lightweight: true,
+        ..totals
+    };
+    assert!(light.cdf_lines().is_empty());
+    assert!(RunTotals::default().cdf_lines().is_empty());
+}
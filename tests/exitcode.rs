@@ -0,0 +1,172 @@
+// PANDEMONIUM EXIT CODE TESTS
+// uei DECODING (RESTART, CLEAN DETACH, ERROR, STALL) FROM SYNTHETIC BYTE
+// ARRAYS, THE CODE EACH OUTCOME EXITS WITH, STATUS TAGS SURVIVING anyhow CONTEXT
+
+use anyhow::Context;
+
+use pandemonium::exitcode::{
+    kind_name, status_of, uei_str, BpfExit, ExitInfo, ExitStatus, SCX_ECODE_ACT_RESTART,
+    SCX_ECODE_RSN_HOTPLUG, SCX_EXIT_DONE, SCX_EXIT_ERROR, SCX_EXIT_ERROR_BPF, SCX_EXIT_ERROR_STALL,
+    SCX_EXIT_NONE, SCX_EXIT_SYSRQ, SCX_EXIT_UNREG, SCX_EXIT_UNREG_BPF, SCX_EXIT_UNREG_KERN,
+    UEI_MSG_LEN, UEI_REASON_LEN,
+};
+
+#[test]
+fn uei_kinds_decode_to_outcomes() {
+    assert_eq!(BpfExit::from_uei(SCX_EXIT_NONE, 0), BpfExit::Attached);
+    for kind in [
+        SCX_EXIT_DONE,
+        SCX_EXIT_UNREG,
+        SCX_EXIT_UNREG_BPF,
+        SCX_EXIT_UNREG_KERN,
+        SCX_EXIT_SYSRQ,
+    ] {
+        assert_eq!(
+            BpfExit::from_uei(kind, 0),
+            BpfExit::Unregistered { kind },
+            "{}",
+            kind_name(kind)
+        );
+    }
+    for kind in [SCX_EXIT_ERROR, SCX_EXIT_ERROR_BPF, SCX_EXIT_ERROR_STALL] {
+        assert_eq!(
+            BpfExit::from_uei(kind, 7),
+            BpfExit::Error { kind, code: 7 },
+            "{}",
+            kind_name(kind)
+        );
+    }
+    assert_eq!(kind_name(SCX_EXIT_ERROR_STALL), "ERROR_STALL");
+    assert_eq!(kind_name(12345), "UNKNOWN");
+}
+
+#[test]
+fn restart_bit_wins_over_the_kind() {
+    let rst = SCX_ECODE_ACT_RESTART as i64;
+    for kind in [SCX_EXIT_NONE, SCX_EXIT_UNREG_BPF, SCX_EXIT_ERROR_BPF] {
+        let exit = BpfExit::from_uei(kind, rst | 3);
+        assert_eq!(
+            exit,
+            BpfExit::Restart {
+                kind,
+                code: rst | 3
+            }
+        );
+        assert!(exit.should_restart());
+    }
+    assert!(!BpfExit::from_uei(SCX_EXIT_ERROR_STALL, 0).should_restart());
+    assert!(!BpfExit::Attached.should_restart());
+}
+
+#[test]
+fn only_a_kernel_error_is_a_failed_run() {
+    assert_eq!(BpfExit::Attached.status(), ExitStatus::Clean);
+    assert_eq!(
+        BpfExit::Unregistered {
+            kind: SCX_EXIT_SYSRQ
+        }
+        .status(),
+        ExitStatus::Clean
+    );
+    // CTRL+C DURING A RESTART REQUEST: THE RESTART NEVER HAPPENS, CLEAN
+    assert_eq!(
+        BpfExit::from_uei(SCX_EXIT_UNREG_BPF, SCX_ECODE_ACT_RESTART as i64).status(),
+        ExitStatus::Clean
+    );
+    assert_eq!(
+        BpfExit::from_uei(SCX_EXIT_ERROR_STALL, 0).status(),
+        ExitStatus::BpfError
+    );
+}
+
+#[test]
+fn codes_are_fixed_and_round_trip() {
+    let codes: Vec<u8> = ExitStatus::ALL.iter().map(|s| s.code()).collect();
+    assert_eq!(codes, vec![0, 1, 2, 3, 4]);
+    for status in ExitStatus::ALL {
+        assert_eq!(ExitStatus::from_code(status.code().into()), Some(status));
+    }
+    assert_eq!(ExitStatus::from_code(130), None);
+    assert_eq!(ExitStatus::from_code(-1), None);
+}
+
+#[test]
+fn status_tags_survive_context_and_keep_the_message() {
+    let tagged = ExitStatus::PreflightFailed.wrap(anyhow::anyhow!("ANOTHER SCHEDULER"));
+    assert_eq!(status_of(&tagged), ExitStatus::PreflightFailed);
+    assert_eq!(tagged.to_string(), "ANOTHER SCHEDULER");
+
+    let inner: anyhow::Result<()> = Err(anyhow::anyhow!("EPERM")).context("ATTACH");
+    let attach = ExitStatus::AttachFailed.wrap(inner.unwrap_err());
+    assert_eq!(attach.to_string(), "ATTACH: EPERM");
+    let outer: anyhow::Result<()> = Err(attach).context("RESTART 2");
+    assert_eq!(status_of(&outer.unwrap_err()), ExitStatus::AttachFailed);
+
+    assert_eq!(status_of(&anyhow::anyhow!("PLAIN")), ExitStatus::Failed);
+}
+
+// uei.reason / uei.msg AS BPF LEAVES THEM: NUL-TERMINATED, GARBAGE AFTER
+fn uei_field<const N: usize>(text: &[u8], tail: &[u8]) -> [u8; N] {
+    let mut field = [0u8; N];
+    field[..text.len()].copy_from_slice(text);
+    let after = (text.len() + 1).min(N);
+    let n = tail.len().min(N - after);
+    field[after..after + n].copy_from_slice(&tail[..n]);
+    field
+}
+
+#[test]
+fn uei_strings_stop_at_the_first_nul() {
+    let reason: [u8; UEI_REASON_LEN] = uei_field(b"runnable task stall", b"\xff\xfe junk");
+    let msg: [u8; UEI_MSG_LEN] = uei_field(b"kworker/3:1[123] failed to run for 30.5s\n", b"old");
+    let info = ExitInfo::from_uei(SCX_EXIT_ERROR_STALL, 0, &reason, &msg);
+    assert_eq!(info.reason, "runnable task stall");
+    assert_eq!(info.msg, "kworker/3:1[123] failed to run for 30.5s");
+    assert!(!info.wants_restart);
+    assert_eq!(info.outcome(), BpfExit::from_uei(SCX_EXIT_ERROR_STALL, 0));
+    assert_eq!(info.status(), ExitStatus::BpfError);
+
+    assert_eq!(uei_str(&[0u8; UEI_REASON_LEN]), "");
+    assert_eq!(uei_str(b""), "");
+}
+
+#[test]
+fn unterminated_and_non_utf8_fields_are_kept_lossily() {
+    // NO NUL ANYWHERE: THE WHOLE ARRAY, NOT A PANIC OR AN OVERRUN
+    let full = [b'x'; UEI_REASON_LEN];
+    assert_eq!(uei_str(&full), "x".repeat(UEI_REASON_LEN));
+    // INVALID UTF-8 BEFORE THE NUL: REPLACED, THE REST SURVIVES
+    let reason: [u8; UEI_REASON_LEN] = uei_field(b"bad \xc3\x28 byte", b"");
+    let info = ExitInfo::from_uei(SCX_EXIT_ERROR_BPF, 0, &reason, &[]);
+    assert_eq!(info.reason, "bad \u{fffd}( byte");
+    assert_eq!(info.msg, "");
+}
+
+#[test]
+fn exit_code_bits_are_decoded_not_dropped() {
+    let code = (SCX_ECODE_ACT_RESTART | SCX_ECODE_RSN_HOTPLUG | 3) as i64;
+    let info = ExitInfo::from_uei(SCX_EXIT_UNREG_KERN, code, b"cpu hotplug\0", &[]);
+    assert!(info.wants_restart);
+    assert_eq!(
+        info.describe_code(),
+        "code=0x1000100000003 (RESTART, HOTPLUG, USER 3)"
+    );
+    assert_eq!(
+        info.log_lines(),
+        vec![
+            "BPF exit: kind=66 (UNREG_KERN) code=0x1000100000003 (RESTART, HOTPLUG, USER 3)",
+            "BPF exit reason: cpu hotplug",
+        ]
+    );
+    // THE OLD 1 << 16 "RESTART" BIT IS A USER CODE, NOT A RESTART
+    let user = ExitInfo::from_uei(SCX_EXIT_UNREG_BPF, 1 << 16, &[], &[]);
+    assert!(!user.wants_restart);
+    assert_eq!(user.describe_code(), "code=0x10000 (USER 65536)");
+    assert_eq!(
+        ExitInfo::from_uei(SCX_EXIT_ERROR, 0, &[], &[]).describe_code(),
+        "code=0"
+    );
+    // STILL ATTACHED (OR DETACHED BY US): NOTHING TO LOG
+    assert!(ExitInfo::default().log_lines().is_empty());
+    assert_eq!(ExitInfo::default().status(), ExitStatus::Clean);
+}
@@ -0,0 +1,164 @@
+// PANDEMONIUM GAMEMODE SESSION TESTS
+// NESTING (OVERLAPPING GAMES), PRIOR STATE CAPTURE + RESTORE, GAME KNOBS,
+// EXITED-GAME REAPING, Tgid PARSING, gamemode.ini SNIPPET
+
+use pandemonium::ctl::KnobOverrides;
+use pandemonium::gamemode::{
+    game_knobs, gamemode_snippet, parse_tgid, GameEnd, GameSessions, GameStart, PriorState,
+    GAME_KNOBS, NO_PID,
+};
+use pandemonium::tuning::{scaled_regime_knobs, Regime, SliceBounds, TuningKnobs};
+
+fn prior(pinned: Option<Regime>) -> PriorState {
+    PriorState::capture(pinned, &KnobOverrides::default())
+}
+
+#[test]
+fn overlapping_games_nest() {
+    let mut games = GameSessions::default();
+    assert!(games.tgids().is_empty());
+    assert_eq!(games.start(100, prior(None)), GameStart::First);
+    // THE SECOND GAME'S SNAPSHOT IS THE GAME STATE ITSELF: NEVER KEPT
+    assert_eq!(
+        games.start(200, prior(Some(Regime::Mixed))),
+        GameStart::Nested { active: 2 }
+    );
+    assert_eq!(games.tgids(), [100, 200]);
+
+    // FIRST GAME ENDS WHILE THE SECOND RUNS: GAME STATE STAYS
+    assert_eq!(games.end(100), GameEnd::Remaining { active: 1 });
+    assert!(!games.tgids().is_empty());
+    // LAST END HANDS BACK WHAT THE FIRST START REPLACED
+    assert_eq!(games.end(200), GameEnd::Last(prior(None)));
+    assert!(games.tgids().is_empty());
+    assert_eq!(games.end(200), GameEnd::NotActive);
+
+    // A FRESH SESSION CAPTURES AGAIN
+    assert_eq!(
+        games.start(300, prior(Some(Regime::Heavy))),
+        GameStart::First
+    );
+    assert_eq!(games.end(300), GameEnd::Last(prior(Some(Regime::Heavy))));
+}
+
+#[test]
+fn duplicate_start_and_unknown_end_change_nothing() {
+    let mut games = GameSessions::default();
+    games.start(100, prior(Some(Regime::Light)));
+    assert_eq!(
+        games.start(100, prior(None)),
+        GameStart::Duplicate { active: 1 }
+    );
+    assert_eq!(games.end(999), GameEnd::NotActive);
+    assert_eq!(games.tgids(), [100]);
+    assert_eq!(games.end(100), GameEnd::Last(prior(Some(Regime::Light))));
+
+    // GAMEMODE-WIDE SESSION (NO PID) NESTS LIKE ANY GAME
+    games.start(NO_PID, prior(None));
+    assert_eq!(
+        games.start(NO_PID, prior(None)),
+        GameStart::Duplicate { active: 1 }
+    );
+    assert_eq!(
+        games.start(42, prior(None)),
+        GameStart::Nested { active: 2 }
+    );
+    assert_eq!(games.end(NO_PID), GameEnd::Remaining { active: 1 });
+}
+
+#[test]
+fn prior_held_knobs_come_back() {
+    let mut held = KnobOverrides::default();
+    held.set("slice_ns", 900_000);
+    held.set("lag_scale", 6);
+    let saved = PriorState::capture(Some(Regime::Heavy), &held);
+    assert_eq!(
+        saved.held,
+        [("slice_ns", Some(900_000)), ("preempt_thresh_ns", None)]
+    );
+
+    // THE GAME HOLDS BOTH ITS KNOBS ...
+    for (name, value) in [("slice_ns", 600_000), ("preempt_thresh_ns", 400_000)] {
+        held.set(name, value);
+    }
+    // ... AND THE LAST END PUTS THE OLD HOLD BACK, RELEASES THE NEW ONE,
+    // LEAVES UNRELATED HELD KNOBS ALONE
+    saved.restore(&mut held);
+    assert_eq!(held.get("slice_ns"), Some(900_000));
+    assert_eq!(held.get("preempt_thresh_ns"), None);
+    assert_eq!(held.get("lag_scale"), Some(6));
+    assert_eq!(saved.pinned, Some(Regime::Heavy));
+}
+
+#[test]
+fn game_knobs_tighten_within_bounds() {
+    let bounds = SliceBounds::default();
+    for nr_cpus in [1, 4, 16, 64] {
+        let mixed = scaled_regime_knobs(Regime::Mixed, nr_cpus);
+        let knobs = game_knobs(&mixed, &bounds);
+        assert_eq!(knobs.map(|(n, _)| n), GAME_KNOBS);
+        let (slice, preempt) = (knobs[0].1, knobs[1].1);
+        assert_eq!(
+            slice,
+            bounds.clamp(mixed.slice_ns * 3 / 4),
+            "{} CPUS",
+            nr_cpus
+        );
+        assert!(slice <= mixed.slice_ns);
+        assert!(preempt <= slice);
+    }
+    // NARROW --slice-min-us: THE FLOOR WINS
+    let narrow = SliceBounds {
+        min_ns: 2_000_000,
+        max_ns: 8_000_000,
+    };
+    let mixed = TuningKnobs {
+        slice_ns: 1_000_000,
+        preempt_thresh_ns: 5_000_000,
+        ..Default::default()
+    };
+    assert_eq!(
+        game_knobs(&mixed, &narrow),
+        [("slice_ns", 2_000_000), ("preempt_thresh_ns", 2_000_000)]
+    );
+}
+
+#[test]
+fn exited_games_are_reaped_but_not_the_gamemode_session() {
+    let mut games = GameSessions::default();
+    for tgid in [NO_PID, 100, 200, 300] {
+        games.start(tgid, prior(None));
+    }
+    assert_eq!(games.exited(|t| t == 200), [100, 300]);
+    assert!(games.exited(|_| true).is_empty());
+}
+
+#[test]
+fn tgid_from_proc_status() {
+    let status = "Name:\tgame-worker\nUmask:\t0022\nState:\tS (sleeping)\nTgid:\t4242\nNgid:\t0\nPid:\t4250\n";
+    assert_eq!(parse_tgid(status), Some(4242));
+    assert_eq!(parse_tgid("Name:\tx\nPid:\t1\n"), None);
+    assert_eq!(parse_tgid("Tgid:\tnope\n"), None);
+    // LIVE: THIS TEST PROCESS IS ITS OWN TGID
+    let own = std::fs::read_to_string("/proc/self/status").unwrap();
+    assert_eq!(parse_tgid(&own), Some(std::process::id()));
+}
+
+#[test]
+fn snippet_is_a_gamemode_custom_section() {
+    let ini = gamemode_snippet("/usr/local/bin/pandemonium", None);
+    let lines: Vec<&str> = ini.lines().filter(|l| !l.starts_with(';')).collect();
+    assert_eq!(
+        lines,
+        [
+            "[custom]",
+            "start=/usr/local/bin/pandemonium gamemode start",
+            "end=/usr/local/bin/pandemonium gamemode end",
+        ]
+    );
+    assert!(ini.contains("--pin-group"));
+
+    let ini = gamemode_snippet("pandemonium", Some("/run/pd.sock"));
+    assert!(ini.contains("start=pandemonium gamemode --socket /run/pd.sock start\n"));
+    assert!(ini.contains("end=pandemonium gamemode --socket /run/pd.sock end\n"));
+}
@@ -5,6 +5,340 @@
 // RUN: sudo cargo test --test gate --release -- --ignored --test-threads=1
 //
 // LAYER 2: LOAD, CLASSIFY, UNLOAD (BPF END-TO-END)
+// CONTROL SOCKET: SET/GET ROUND TRIPS AGAINST A LIVE SCHEDULER
+// CPU HOTPLUG: OFFLINE + ONLINE A CPU UNDER A LIVE SCHEDULER
+// --duration: SELF-TERMINATING RUNS EXIT 0 WITH THE SUMMARY
+// EXIT CODES: USAGE 1, PREFLIGHT 4, CTRL+C 0
+// FORCE QUIT: A WEDGED SHUTDOWN EXITS 130 ON THE SECOND CTRL+C
+//   (--features gate-hooks)
+// LAYER 6: PROCESS STORM (PROCDB CHURN, ENQUEUE/ENABLE HOT PATH)
+// LAYER 7: LEAK CHECK (PINS, PROCESSES, THREADS ACROSS START/STOP CYCLES)
+
+const TEST_CTL_SOCKET: &str = "/tmp/pandemonium/test-ctl.sock";
+
+fn ctl(cmd: &str) -> Result<std::collections::BTreeMap<String, String>, String> {
+    use std::io::{Read, Write};
+    let mut s = std::os::unix::net::UnixStream::connect(TEST_CTL_SOCKET)
+        .map_err(|e| format!("CONNECT: {}", e))?;
+    s.set_read_timeout(Some(Duration::from_secs(5))).ok();
+    s.write_all(format!("{}\n", cmd).as_bytes()).unwrap();
+    s.shutdown(std::net::Shutdown::Write).ok();
+    let mut resp = String::new();
+    s.read_to_string(&mut resp).unwrap();
+    pandemonium::ctl::parse_response(&resp)
+}
+
+#[test]
+#[ignore]
+fn control_socket_round_trip() {
+    fs::create_dir_all(LOG_DIR).ok();
+    let mut child = start_pandemonium(&["--ctl-socket", TEST_CTL_SOCKET]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    thread::sleep(Duration::from_secs(2));
+
+    let knobs = ctl("get knobs").expect("get knobs");
+    assert!(knobs.contains_key("slice_ns"));
+
+    // SET IS VISIBLE IMMEDIATELY AND HELD ACROSS TICKS
+    let set = ctl("set slice_ns 750000").expect("set slice_ns");
+    assert_eq!(set["slice_ns"], "750000");
+    thread::sleep(Duration::from_secs(3));
+    assert_eq!(ctl("get knobs").unwrap()["slice_ns"], "750000");
+
+    // PIN, THEN CONFIRM THE REGIME STICKS
+    let pin = ctl("pin regime heavy").expect("pin");
+    assert_eq!(pin["regime"], "HEAVY");
+    assert_eq!(pin["pinned"], "true");
+    thread::sleep(Duration::from_secs(3));
+    let regime = ctl("get regime").unwrap();
+    assert_eq!(regime["regime"], "HEAVY");
+    assert_eq!(regime["held"], "slice_ns");
+    assert_eq!(ctl("get knobs").unwrap()["slice_ns"], "750000");
+
+    assert_eq!(ctl("unpin").unwrap()["pinned"], "false");
+
+    // PAUSE FREEZES KNOBS; RESUME RE-SYNCS FROM THE MAP
+    assert_eq!(ctl("pause").unwrap()["paused"], "true");
+    let frozen = ctl("get knobs").unwrap();
+    thread::sleep(Duration::from_secs(3));
+    assert_eq!(ctl("get knobs").unwrap(), frozen);
+    assert_eq!(ctl("resume").unwrap()["paused"], "false");
+    assert!(ctl("unset slice_ns").is_ok());
+    assert!(
+        ctl("stats").unwrap()["nr_dispatches"]
+            .parse::<u64>()
+            .unwrap()
+            > 0
+    );
+
+    // INVALID INPUT GETS ERR, NOT A HANG
+    assert!(ctl("set slice_ns 0").is_err());
+    assert!(ctl("frobnicate").is_err());
+
+    stop_pandemonium(&mut child);
+    assert!(
+        !std::path::Path::new(TEST_CTL_SOCKET).exists(),
+        "SOCKET NOT REMOVED ON SHUTDOWN"
+    );
+}
+
+// CPU HOTPLUG: OFFLINE A CPU UNDER THE RUNNING SCHEDULER, THEN BRING IT BACK
+
+// HIGHEST ONLINE CPU THAT CAN BE OFFLINED (CPU0 USUALLY CANNOT)
+fn hotplug_victim() -> Option<u32> {
+    let online = pandemonium::hotplug::read_online()?;
+    if online.len() < 2 {
+        return None;
+    }
+    online.into_iter().rev().find(|&c| {
+        c != 0 && std::path::Path::new(&format!("/sys/devices/system/cpu/cpu{}/online", c)).exists()
+    })
+}
+
+fn set_cpu_online(cpu: u32, online: bool) -> bool {
+    let path = format!("/sys/devices/system/cpu/cpu{}/online", cpu);
+    fs::write(path, if online { "1" } else { "0" }).is_ok()
+}
+
+#[test]
+#[ignore]
+fn cpu_hotplug_while_running() {
+    let Some(cpu) = hotplug_victim() else {
+        eprintln!("HOTPLUG: SKIP (NO HOTPLUGGABLE CPU)");
+        return;
+    };
+
+    let mut child = start_pandemonium(&[]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    thread::sleep(Duration::from_secs(2));
+
+    let offlined = set_cpu_online(cpu, false);
+    thread::sleep(Duration::from_secs(3));
+    let alive_offline = is_scx_active();
+    // ALWAYS RESTORE BEFORE ASSERTING
+    set_cpu_online(cpu, true);
+    thread::sleep(Duration::from_secs(3));
+    let alive_online = is_scx_active();
+
+    let output = stop_pandemonium(&mut child);
+    assert!(offlined, "COULD NOT OFFLINE CPU {}", cpu);
+    assert!(alive_offline, "SCHEDULER DETACHED AFTER OFFLINING CPU {}", cpu);
+    assert!(alive_online, "SCHEDULER DETACHED AFTER ONLINING CPU {}", cpu);
+
+    let transitions: Vec<&str> = output
+        .lines()
+        .filter(|l| l.contains("CPU HOTPLUG:"))
+        .collect();
+    assert!(
+        transitions.len() >= 2,
+        "EXPECTED OFFLINE + ONLINE TRANSITIONS, GOT:\n{}\nOUTPUT:\n{}",
+        transitions.join("\n"),
+        &output[..output.len().min(2000)]
+    );
+    assert!(transitions[0].contains(&format!("OFFLINE [{}]", cpu)));
+    assert!(transitions[1].contains(&format!("ONLINE [{}]", cpu)));
+}
+
+#[test]
+#[ignore]
+fn verbose_sections() {
+    let mut child = start_pandemonium(&["--verbose"]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    // FIRST CUMULATIVE DUMP LANDS ON TICK 10
+    thread::sleep(Duration::from_secs(12));
+    let output = stop_pandemonium(&mut child);
+
+    let head = &output[..output.len().min(2000)];
+    assert!(output.contains("d/s:"), "NO TELEMETRY LINE:\n{}", head);
+    assert!(output.contains("[STATS] "), "NO [STATS] SECTION:\n{}", head);
+    assert!(
+        output.contains("nr_dispatches="),
+        "[STATS] MISSING COUNTERS:\n{}",
+        head
+    );
+    // THE FIRST TICK ALWAYS REPORTS THE STARTING KNOBS
+    assert!(
+        output.contains("[KNOB-CHANGE] "),
+        "NO [KNOB-CHANGE] SECTION:\n{}",
+        head
+    );
+}
+
+// --lightweight SMOKE: LOADS, SCHEDULES, TAGS ITS TELEMETRY LITE, AND
+// THE ADAPTIVE LOOP RUNS WITHOUT A P99
+#[test]
+#[ignore]
+fn lightweight_smoke() {
+    let mut child = start_pandemonium(&["--lightweight", "--verbose"]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    thread::sleep(Duration::from_secs(4));
+    let output = stop_pandemonium(&mut child);
+
+    let head = &output[..output.len().min(2000)];
+    assert!(
+        output.contains("[CONFIG] lightweight=on"),
+        "NO lightweight=on IN [CONFIG]:\n{}",
+        head
+    );
+    let ticks: Vec<&str> = output.lines().filter(|l| l.starts_with("d/s:")).collect();
+    assert!(!ticks.is_empty(), "NO TELEMETRY LINE:\n{}", head);
+    let dispatch_re = Regex::new(r"^d/s:\s+[1-9]").unwrap();
+    assert!(
+        ticks.iter().any(|l| dispatch_re.is_match(l)),
+        "NO DISPATCHES UNDER --lightweight:\n{}",
+        head
+    );
+    for line in &ticks {
+        assert!(line.contains(" LITE]"), "UNTAGGED TICK: {}", line);
+        assert!(
+            line.contains(" p99: - "),
+            "P99 UNDER --lightweight: {}",
+            line
+        );
+    }
+}
+
+// --duration: THE SCHEDULER ENDS ITS OWN RUN, SUMMARY INCLUDED, EXIT 0
+#[test]
+#[ignore]
+fn duration_exits_cleanly() {
+    let secs = 3;
+    let mut child = start_pandemonium(&["--duration", &secs.to_string()]);
+    let started = Instant::now();
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+
+    // DRAIN STDOUT ON ITS OWN THREAD SO try_wait CAN KEEP THE CLOCK
+    let stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || std::io::read_to_string(stdout).unwrap_or_default());
+    let limit = Duration::from_secs(secs + 2) + started.elapsed();
+    let status = loop {
+        if let Some(status) = child.try_wait().expect("try_wait") {
+            break Some(status);
+        }
+        if started.elapsed() > limit {
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let Some(status) = status else {
+        stop_pandemonium(&mut child);
+        panic!("STILL RUNNING {}S AFTER A {}S --duration", secs + 2, secs);
+    };
+    let output = reader.join().unwrap();
+    wait_for_deactivation();
+
+    let head = &output[..output.len().min(2000)];
+    assert!(status.success(), "EXIT STATUS {}:\n{}", status, head);
+    assert!(
+        output.contains("DURATION ELAPSED"),
+        "NO DURATION LOG:\n{}",
+        head
+    );
+    assert!(
+        output.contains("PANDEMONIUM SUMMARY"),
+        "NO SUMMARY:\n{}",
+        head
+    );
+}
+
+// EXIT CODES FOR INDUCED FAILURES (exitcode.rs). A KERNEL ERROR EXIT (2)
+// NEEDS A STALLED BPF PROGRAM; tests/exitcode.rs COVERS ITS DECODING.
+fn exit_code_of(args: &[&str]) -> (Option<i32>, String) {
+    let out = Command::new(binary_path())
+        .args(args)
+        .output()
+        .expect("FAILED TO RUN PANDEMONIUM");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    (out.status.code(), text)
+}
+
+#[test]
+#[ignore]
+fn exit_codes_for_induced_failures() {
+    // BAD COMMAND LINE: 1, NOT CLAP'S 2
+    let (code, out) = exit_code_of(&["--no-such-flag"]);
+    assert_eq!(code, Some(1), "USAGE ERROR:\n{}", out);
+
+    // PREFLIGHT: A CPUSET FILE THAT DOES NOT EXIST
+    let (code, out) = exit_code_of(&["--scaling-cpuset", "/nonexistent/cpuset.cpus"]);
+    assert_eq!(code, Some(4), "BAD CPUSET:\n{}", out);
+
+    // PREFLIGHT: A SECOND INSTANCE WHILE THE FIRST IS ATTACHED
+    let mut child = start_pandemonium(&[]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    let (code, out) = exit_code_of(&[]);
+    let first_alive = is_scx_active();
+
+    // CTRL+C: 0
+    stop_pandemonium(&mut child);
+    let first = child.wait().expect("wait");
+    assert_eq!(code, Some(4), "SECOND INSTANCE:\n{}", out);
+    assert!(first_alive, "SECOND INSTANCE DETACHED THE FIRST");
+    assert_eq!(first.code(), Some(0), "CTRL+C EXIT STATUS {}", first);
+}
+
+// FORCE QUIT (interrupt.rs). --wedge-shutdown HANGS THE MAIN THREAD AFTER
+// THE MONITOR STOPS, AS A WEDGED BPF READ WOULD: THE FIRST CTRL+C CANNOT
+// FINISH, THE SECOND MUST DETACH, UNPIN AND EXIT 130 ON ITS OWN. THE FLAG
+// ONLY EXISTS WITH --features gate-hooks, WHICH ALSO BUILDS THE BINARY.
+#[cfg(feature = "gate-hooks")]
+#[test]
+#[ignore]
+fn second_ctrlc_forces_exit_when_wedged() {
+    let mut child = start_pandemonium(&["--wedge-shutdown"]);
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    thread::sleep(Duration::from_secs(2));
+    let sigint = |child: &std::process::Child| unsafe {
+        libc::killpg(child.id() as i32, libc::SIGINT);
+    };
+
+    sigint(&child);
+    thread::sleep(Duration::from_secs(3));
+    let wedged = child.try_wait().expect("try_wait").is_none();
+    let attached_while_wedged = is_scx_active();
+
+    sigint(&child);
+    let deadline = Instant::now() + Duration::from_secs(15);
+    let status = loop {
+        if let Some(status) = child.try_wait().expect("try_wait") {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            child.kill().ok();
+            child.wait().ok();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let output = child
+        .stdout
+        .take()
+        .map(|p| std::io::read_to_string(p).unwrap_or_default())
+        .unwrap_or_default();
+    let detached = wait_for_deactivation();
+
+    assert!(wedged, "EXITED ON THE FIRST CTRL+C:\n{}", output);
+    assert!(attached_while_wedged, "DETACHED WHILE WEDGED");
+    let status = status.expect("STILL RUNNING 15S AFTER THE SECOND CTRL+C");
+    assert_eq!(status.code(), Some(130), "FORCED EXIT STATUS {}", status);
+    assert!(
+        output.contains("FORCING EXIT"),
+        "NO FORCING EXIT LINE:\n{}",
+        output
+    );
+    assert!(!output.contains("PANDEMONIUM SUMMARY"), "SUMMARY PRINTED");
+    assert!(detached, "STILL ATTACHED AFTER THE FORCED EXIT");
+    assert!(
+        !std::path::Path::new(DEFAULT_PIN_DIR).exists(),
+        "{} LEFT BEHIND",
+        DEFAULT_PIN_DIR
+    );
+}
+
 // LAYER 3: LATENCY GATE (CYCLICTEST)
 // LAYER 4: INTERACTIVE RESPONSIVENESS (WAKEUP LATENCY)
 
@@ -14,6 +348,8 @@ use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use pandemonium::container::DEFAULT_PIN_DIR;
+use pandemonium::leak::{leaks, Leak, Snapshot};
 use regex::Regex;
 
 const LOG_DIR: &str = "/tmp/pandemonium";
@@ -34,6 +370,13 @@ fn is_scx_active() -> bool {
         .unwrap_or(false)
 }
 
+// NAME THE ATTACHED SCHEDULER (AND ITS MANAGER) INSTEAD OF "ALREADY ACTIVE"
+fn require_scx_free() {
+    if let Err(e) = pandemonium::attach::ensure_free(false, |_, _| {}) {
+        panic!("{}", e);
+    }
+}
+
 fn wait_for_activation() -> bool {
     let deadline = Instant::now() + ACTIVATION_TIMEOUT;
     while Instant::now() < deadline {
@@ -65,7 +408,7 @@ fn start_pandemonium(extra_args: &[&str]) -> std::process::Child {
         "BINARY NOT FOUND AT {}. BUILD FIRST.",
         bin
     );
-    assert!(!is_scx_active(), "SCHED_EXT ALREADY ACTIVE");
+    require_scx_free();
 
     Command::new(&bin)
         .args(extra_args)
@@ -114,6 +457,19 @@ fn which(name: &str) -> bool {
         .unwrap_or(false)
 }
 
+// KERNEL LOG LINES (REQUIRES ROOT OR dmesg_restrict=0)
+fn dmesg_lines() -> Vec<String> {
+    Command::new("dmesg")
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|l| l.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn save_report(stamp: &str, results: &[(String, Option<bool>, String)], verdict: &str) {
     fs::create_dir_all(LOG_DIR).ok();
     let path = format!("{}/test-{}.log", LOG_DIR, stamp);
@@ -158,7 +514,9 @@ fn timestamp() -> String {
 fn layer2_load_classify_unload() {
     let bin = binary_path();
     assert!(std::path::Path::new(&bin).exists(), "BINARY NOT FOUND");
-    assert!(!is_scx_active(), "SCHED_EXT ALREADY ACTIVE");
+    require_scx_free();
+
+    let dmesg_before = dmesg_lines().len();
 
     // START PANDEMONIUM WITH BUILD_MODE TO TEST classify_weight()
     let mut child = start_pandemonium(&["--build-mode"]);
@@ -213,6 +571,23 @@ fn layer2_load_classify_unload() {
 
     // VERIFY UNLOADED
     assert!(!is_scx_active(), "SCHED_EXT STILL ACTIVE AFTER STOP");
+
+    // ON KERNELS WITH scx_bpf_task_set_*, NO DIRECT-WRITE DEPRECATION SPAM
+    let kfuncs = pandemonium::compat::probe_kernel()
+        .map(|r| r.has_task_set_kfuncs())
+        .unwrap_or(false);
+    if kfuncs {
+        let deprecated: Vec<String> = dmesg_lines()
+            .into_iter()
+            .skip(dmesg_before)
+            .filter(|l| l.contains("deprecated") && l.contains("p->scx."))
+            .collect();
+        assert!(
+            deprecated.is_empty(),
+            "DEPRECATION WARNINGS IN DMESG:\n{}",
+            deprecated.join("\n")
+        );
+    }
 }
 
 // LAYER 3: LATENCY GATE (CYCLICTEST)
@@ -225,7 +600,7 @@ fn layer3_latency_gate() {
         return;
     }
 
-    assert!(!is_scx_active(), "SCHED_EXT ALREADY ACTIVE");
+    require_scx_free();
 
     let mut child = start_pandemonium(&[]);
     assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
@@ -295,7 +670,7 @@ fn layer3_latency_gate() {
 #[test]
 #[ignore]
 fn layer4_interactive_responsiveness() {
-    assert!(!is_scx_active(), "SCHED_EXT ALREADY ACTIVE");
+    require_scx_free();
 
     let mut child = start_pandemonium(&[]);
     assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
@@ -337,7 +712,7 @@ fn layer4_interactive_responsiveness() {
 #[test]
 #[ignore]
 fn layer5_contention_latency() {
-    assert!(!is_scx_active(), "SCHED_EXT ALREADY ACTIVE");
+    require_scx_free();
 
     let mut child = start_pandemonium(&[]);
     assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
@@ -413,6 +788,227 @@ fn layer5_contention_latency() {
     );
 }
 
+// LAYER 6: PROCESS STORM (make -j STYLE CHURN)
+// THOUSANDS OF SHORT-LIVED PROCESSES PER SECOND HAMMER enable/enqueue/disable
+// AND, UNDER DISTINCT comm NAMES, PROCDB CREATION + CAP EVICTION
+
+const STORM_SECS: u64 = 30;
+const STORM_COMMS: u32 = 4096; // DISTINCT comm NAMES, WELL OVER procdb::MAX_PROFILES
+const STORM_P99_LIMIT_US: f64 = 5000.0;
+
+// FORK ONE CHILD THAT RENAMES ITSELF, RUNS PAST EWMA MATURITY (8 STOPS) SO
+// BPF PUBLISHES ITS PROFILE, THEN EXITS. THE CHILD ONLY MAKES
+// ASYNC-SIGNAL-SAFE CALLS: THE TEST PROCESS IS MULTITHREADED.
+fn storm_spawn(comm: &[u8; 16]) {
+    unsafe {
+        let pid = libc::fork();
+        if pid == 0 {
+            libc::prctl(libc::PR_SET_NAME, comm.as_ptr() as libc::c_ulong, 0, 0, 0);
+            let ts = libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 20_000,
+            };
+            for _ in 0..12 {
+                libc::nanosleep(&ts, std::ptr::null_mut());
+            }
+            libc::_exit(0);
+        }
+        if pid > 0 {
+            libc::waitpid(pid, std::ptr::null_mut(), 0);
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn layer6_process_storm() {
+    use std::io::BufRead;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    require_scx_free();
+
+    let mut child = start_pandemonium(&["--verbose"]);
+    // DRAIN --verbose OUTPUT AS IT ARRIVES: A FULL PIPE WOULD STALL THE MONITOR
+    let stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || {
+        std::io::BufReader::new(stdout)
+            .lines()
+            .map_while(|l| l.ok())
+            .collect::<Vec<String>>()
+    });
+    assert!(wait_for_activation(), "DID NOT ACTIVATE WITHIN 10S");
+    thread::sleep(Duration::from_secs(2));
+
+    let ncpu = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let running = Arc::new(AtomicBool::new(true));
+    let next = Arc::new(AtomicU32::new(0));
+    let spawned = Arc::new(AtomicU32::new(0));
+    let mut storm_threads = Vec::new();
+    for _ in 0..ncpu.min(8) {
+        let (flag, next, spawned) = (running.clone(), next.clone(), spawned.clone());
+        storm_threads.push(thread::spawn(move || {
+            while flag.load(Ordering::Relaxed) {
+                // NAME BUILT BEFORE fork: NO ALLOCATION IN THE CHILD
+                let n = next.fetch_add(1, Ordering::Relaxed) % STORM_COMMS;
+                let name = format!("storm-{:04}", n);
+                let mut comm = [0u8; 16];
+                comm[..name.len()].copy_from_slice(name.as_bytes());
+                storm_spawn(&comm);
+                spawned.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+    }
+
+    // INTERACTIVE PROBE FOR THE WHOLE STORM: 10MS SLEEP/WAKE,
+    // sched_ext OWNERSHIP CHECKED ONCE PER SECOND
+    let target = Duration::from_millis(10);
+    let mut overshoots_us: Vec<f64> = Vec::with_capacity(STORM_SECS as usize * 100);
+    let mut detached: Option<String> = None;
+    let start = Instant::now();
+    let mut next_check = start;
+    while start.elapsed() < Duration::from_secs(STORM_SECS) {
+        let t0 = Instant::now();
+        thread::sleep(target);
+        let overshoot = t0.elapsed().saturating_sub(target);
+        overshoots_us.push(overshoot.as_nanos() as f64 / 1000.0);
+        if Instant::now() >= next_check {
+            next_check += Duration::from_secs(1);
+            let ops = pandemonium::scxstate::ScxRuntime::read().and_then(|rt| rt.ops);
+            if ops.as_deref() != Some(pandemonium::scxstate::PANDEMONIUM_OPS) {
+                detached = Some(format!(
+                    "{:?} AFTER {:.0}S",
+                    ops,
+                    start.elapsed().as_secs_f64()
+                ));
+                break;
+            }
+        }
+    }
+
+    running.store(false, Ordering::Relaxed);
+    for t in storm_threads {
+        t.join().ok();
+    }
+    let spawned = spawned.load(Ordering::Relaxed);
+    let elapsed = start.elapsed().as_secs_f64();
+
+    stop_pandemonium(&mut child);
+    let lines = reader.join().unwrap_or_default();
+
+    assert!(
+        detached.is_none(),
+        "sched_ext LOST DURING STORM: ROOT OPS {}",
+        detached.unwrap()
+    );
+
+    // PROCDB SUMMARY FROM EVERY TELEMETRY LINE: "procdb: TOTAL/CONFIDENT evict: N"
+    let re = Regex::new(r"procdb: (\d+)/\d+ evict: (\d+)").unwrap();
+    let mut totals = Vec::new();
+    let mut evicted = 0u64;
+    for line in lines.iter().filter(|l| l.starts_with("d/s:")) {
+        if let Some(c) = re.captures(line) {
+            totals.push(c[1].parse::<usize>().unwrap());
+            evicted += c[2].parse::<u64>().unwrap();
+        }
+    }
+
+    overshoots_us.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let median = overshoots_us[overshoots_us.len() / 2];
+    let p99 = overshoots_us[(overshoots_us.len() as f64 * 0.99) as usize];
+    let max_total = totals.iter().copied().max().unwrap_or(0);
+
+    eprintln!(
+        "LAYER 6: PROCESS STORM ({:.0} procs/s, med={:.0}us p99={:.0}us, procdb max={} evict={})",
+        spawned as f64 / elapsed,
+        median,
+        p99,
+        max_total,
+        evicted
+    );
+
+    assert!(
+        !totals.is_empty(),
+        "NO PROCDB TELEMETRY IN --verbose OUTPUT"
+    );
+    assert!(
+        max_total > totals[0],
+        "PROCDB CREATED NO PROFILES DURING THE STORM (TOTAL STAYED AT {})",
+        totals[0]
+    );
+    assert!(
+        max_total <= pandemonium::procdb::MAX_PROFILES,
+        "PROCDB OVER CAP: {} PROFILES (MAX_PROFILES: {})",
+        max_total,
+        pandemonium::procdb::MAX_PROFILES
+    );
+    assert!(
+        evicted > 0,
+        "PROCDB NEVER EVICTED UNDER {} DISTINCT comm NAMES",
+        STORM_COMMS
+    );
+    assert!(
+        p99 <= STORM_P99_LIMIT_US,
+        "STORM PROBE P99 TOO HIGH: {:.0}us (LIMIT: {:.0}us)",
+        p99,
+        STORM_P99_LIMIT_US
+    );
+}
+
+// LAYER 7: LEAK CHECK
+// THREE FULL START/STOP CYCLES (10S RUN, SIGINT). AFTER EACH, THE PIN DIR,
+// THE pandemonium PROCESSES AND THIS PROCESS'S THREAD COUNT MATCH THE
+// SNAPSHOT TAKEN BEFORE THE FIRST, SO A LEAK THAT ACCUMULATES ACROSS
+// CYCLES FAILS TOO (src/leak.rs)
+
+const LEAK_CYCLES: u32 = 3;
+const LEAK_RUN_SECS: u64 = 10;
+
+#[test]
+#[ignore]
+fn layer7_leak_check() {
+    let pin_dir = std::path::Path::new(DEFAULT_PIN_DIR);
+    let observer = std::process::id();
+    let snapshot = || Snapshot::take(pin_dir, std::path::Path::new("/proc"), observer);
+
+    let baseline = snapshot();
+    assert!(
+        !baseline.pin_dir,
+        "{} EXISTS BEFORE THE FIRST START: {:?}",
+        DEFAULT_PIN_DIR, baseline.pins
+    );
+    for cycle in 1..=LEAK_CYCLES {
+        let mut child = start_pandemonium(&[]);
+        assert!(
+            wait_for_activation(),
+            "CYCLE {}: DID NOT ACTIVATE WITHIN 10S",
+            cycle
+        );
+        thread::sleep(Duration::from_secs(LEAK_RUN_SECS));
+        // THE CHECK CAN SEE WHAT IT LOOKS FOR: PINS AND THE PROCESS, LIVE
+        let running = snapshot();
+        assert!(
+            !running.pins.is_empty(),
+            "CYCLE {}: NO PINS UNDER {} WHILE RUNNING",
+            cycle,
+            DEFAULT_PIN_DIR
+        );
+        assert!(
+            running.processes.iter().any(|(pid, _)| *pid == child.id()),
+            "CYCLE {}: SCHEDULER PROCESS NOT SEEN",
+            cycle
+        );
+
+        stop_pandemonium(&mut child);
+        let found = leaks(&baseline, &snapshot());
+        let messages: Vec<String> = found.iter().map(Leak::message).collect();
+        assert!(found.is_empty(), "CYCLE {}: {}", cycle, messages.join("; "));
+        eprintln!("LEAK CHECK: CYCLE {}/{} CLEAN", cycle, LEAK_CYCLES);
+    }
+}
+
 // FULL TEST GATE (RUN ALL LAYERS, PRODUCE REPORT)
 
 #[test]
@@ -545,6 +1141,60 @@ fn full_gate() {
         }
     }
 
+    // LAYER 6: PROCESS STORM
+    if !any_fail {
+        let l6 = std::panic::catch_unwind(|| {
+            layer6_process_storm();
+        });
+        let (l6_pass, l6_detail) = match l6 {
+            Ok(()) => (true, String::new()),
+            Err(e) => {
+                let msg = if let Some(s) = e.downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    "UNKNOWN ERROR".to_string()
+                };
+                let short = if msg.len() > 100 { &msg[..100] } else { &msg };
+                (false, short.to_string())
+            }
+        };
+        let status = if l6_pass { "PASS" } else { "FAIL" };
+        eprintln!("LAYER 6: PROCESS STORM ... {}", status);
+        results.push((
+            "LAYER 6: PROCESS STORM".to_string(),
+            Some(l6_pass),
+            l6_detail,
+        ));
+        if !l6_pass {
+            any_fail = true;
+        }
+    }
+
+    // LAYER 7: LEAK CHECK
+    if !any_fail {
+        let l7 = std::panic::catch_unwind(|| {
+            layer7_leak_check();
+        });
+        let (l7_pass, l7_detail) = match l7 {
+            Ok(()) => (true, String::new()),
+            Err(e) => {
+                let msg = if let Some(s) = e.downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    "UNKNOWN ERROR".to_string()
+                };
+                let short = if msg.len() > 100 { &msg[..100] } else { &msg };
+                (false, short.to_string())
+            }
+        };
+        let status = if l7_pass { "PASS" } else { "FAIL" };
+        eprintln!("LAYER 7: LEAK CHECK ... {}", status);
+        results.push(("LAYER 7: LEAK CHECK".to_string(), Some(l7_pass), l7_detail));
+        if !l7_pass {
+            any_fail = true;
+        }
+    }
+
     let verdict = if any_fail { "FAIL" } else { "PASS" };
     eprintln!("{}", "=".repeat(60));
     eprintln!("VERDICT: {}", verdict);
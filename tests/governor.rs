@@ -0,0 +1,149 @@
+// PANDEMONIUM CPUFREQ GOVERNOR WATCH TESTS
+// STATE FROM INJECTED sysfs STRINGS (UNIFORM, MIXED, NO cpufreq), CHANGE
+// DETECTION, THE sysfs READ OF A SYNTHETIC TREE, AND EVENT LOG MARKS
+
+use pandemonium::event::{EventLog, RunTotals, TickCounters, MAX_GOVERNOR_MARKS};
+use pandemonium::governor::{read, GovernorState, GovernorWatch, NO_GOVERNOR};
+
+const SEC: u64 = 1_000_000_000;
+
+fn state(cpu0: &str, policies: &[(u32, &str)]) -> GovernorState {
+    GovernorState::from_strings(Some(cpu0), policies)
+}
+
+#[test]
+fn labels_uniform_mixed_and_missing() {
+    let uniform = state("powersave\n", &[(0, "powersave\n"), (4, "powersave\n")]);
+    assert_eq!(uniform.label(), "powersave");
+    assert!(!uniform.mixed());
+    assert_eq!(uniform.detail(), None);
+
+    // cpu0's GOVERNOR LEADS; POLICIES ARE ORDERED BY NUMBER, NOT INPUT
+    let mixed = state("powersave\n", &[(4, "performance\n"), (0, "powersave\n")]);
+    assert_eq!(mixed.label(), "powersave+performance");
+    assert!(mixed.mixed());
+    assert_eq!(
+        mixed.detail().as_deref(),
+        Some("policy0=powersave policy4=performance")
+    );
+
+    let none = GovernorState::from_strings(None, &[]);
+    assert_eq!(none.label(), NO_GOVERNOR);
+    // AN EMPTY FILE IS NO GOVERNOR, NOT AN EMPTY ONE
+    assert_eq!(state("\n", &[(0, "")]), none);
+    // NO cpu0 cpufreq, POLICIES STILL READ
+    assert_eq!(
+        GovernorState::from_strings(None, &[(0, "schedutil\n")]).label(),
+        "schedutil"
+    );
+}
+
+#[test]
+fn watch_reports_each_change_once() {
+    let mut watch = GovernorWatch::new(state("powersave\n", &[(0, "powersave\n")]));
+    assert_eq!(watch.label(), "powersave");
+    assert_eq!(watch.observe(state("powersave", &[(0, "powersave")])), None);
+
+    let change = watch
+        .observe(state("performance\n", &[(0, "performance\n")]))
+        .unwrap();
+    assert_eq!(
+        (change.from.as_str(), change.to.as_str()),
+        ("powersave", "performance")
+    );
+    assert_eq!(change.detail, None);
+    let msg = change.message();
+    assert!(msg.starts_with("CPU GOVERNOR CHANGED: powersave -> performance"));
+    assert!(msg.contains("NOT COMPARABLE"));
+    assert_eq!(watch.label(), "performance");
+    assert_eq!(
+        watch.observe(state("performance", &[(0, "performance")])),
+        None
+    );
+}
+
+#[test]
+fn one_policy_moving_is_a_change() {
+    let two = |a: &str, b: &str| state(a, &[(0, a), (4, b)]);
+    let mut watch = GovernorWatch::new(two("performance", "performance"));
+
+    let split = watch.observe(two("performance", "powersave")).unwrap();
+    assert_eq!(split.to, "performance+powersave");
+    assert_eq!(
+        split.detail.as_deref(),
+        Some("policy0=performance policy4=powersave")
+    );
+    assert!(split
+        .message()
+        .contains("(policy0=performance policy4=powersave)"));
+
+    // STILL MIXED, SAME LABEL, DIFFERENT POLICY: REPORTED
+    let flip = watch.observe(two("powersave", "performance")).unwrap();
+    assert_eq!(flip.from, "performance+powersave");
+    assert_eq!(flip.to, "powersave+performance");
+
+    // cpufreq GOING AWAY (DRIVER UNLOADED) IS A CHANGE TOO
+    let gone = watch.observe(GovernorState::default()).unwrap();
+    assert_eq!(gone.to, NO_GOVERNOR);
+}
+
+#[test]
+fn sysfs_read_of_a_synthetic_tree() {
+    let root = std::env::temp_dir().join(format!("pandemonium-governor-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for (dir, gov) in [
+        ("cpu0/cpufreq", Some("schedutil\n")),
+        ("cpufreq/policy0", Some("schedutil\n")),
+        ("cpufreq/policy8", Some("performance\n")),
+        ("cpufreq/policy4", None), // OFFLINE: NO scaling_governor
+        ("cpufreq/boost", None),
+    ] {
+        let d = root.join(dir);
+        std::fs::create_dir_all(&d).unwrap();
+        if let Some(gov) = gov {
+            std::fs::write(d.join("scaling_governor"), gov).unwrap();
+        }
+    }
+    let s = read(&root);
+    assert_eq!(s.cpu0.as_deref(), Some("schedutil"));
+    assert_eq!(
+        s.policies,
+        vec![(0, "schedutil".to_string()), (8, "performance".to_string())]
+    );
+    assert_eq!(s.label(), "schedutil+performance");
+
+    assert_eq!(read(&root.join("missing")).label(), NO_GOVERNOR);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn event_log_marks_changes() {
+    let mut log = EventLog::new();
+    log.record(10 * SEC, TickCounters::default());
+    log.record(14 * SEC, TickCounters::default());
+    assert!(log.governor_lines(10 * SEC).is_empty());
+
+    let mut watch = GovernorWatch::new(state("powersave", &[]));
+    let change = watch.observe(state("performance", &[])).unwrap();
+    log.mark_governor(12 * SEC + SEC / 2, &change);
+    assert_eq!(log.governor_changes(), 1);
+    assert_eq!(
+        log.governor_lines(10 * SEC),
+        vec!["GOVERNOR @ 2.5s: powersave -> performance"]
+    );
+    let summary = log.summary_lines(&RunTotals::default());
+    assert!(summary
+        .iter()
+        .any(|l| l == "  GOVERNOR CHANGES:  1 (STARTED AS powersave)"));
+
+    // BOUNDED: PAST THE CAP ONLY THE COUNT GROWS
+    for i in 0..MAX_GOVERNOR_MARKS as u64 + 5 {
+        log.mark_governor(14 * SEC + i, &change);
+    }
+    assert_eq!(log.governor_marks().len(), MAX_GOVERNOR_MARKS);
+    assert_eq!(log.governor_changes(), MAX_GOVERNOR_MARKS as u64 + 6);
+    assert_eq!(
+        log.governor_lines(10 * SEC).last().unwrap(),
+        "(+6 LATER GOVERNOR CHANGES NOT KEPT)"
+    );
+}
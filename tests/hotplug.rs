@@ -0,0 +1,194 @@
+// PANDEMONIUM CPU HOTPLUG TESTS
+// ONLINE-SET DIFFING, CPU-COUNT RESCALING, KNOB RECOMPUTE ON TRANSITION,
+// --nr-cpus CLAMPING, CGROUP CPUSET MASKS WITH HOLES AND THE SCALING COUNT
+
+use pandemonium::cpulist;
+use pandemonium::hotplug::{
+    parse_cpu_list, read_cpu_list, sanitize_nr_cpus, scaling_cpus, CpuScaling, CpuTransition,
+    HotplugWatch, NrCpusWarning,
+};
+use pandemonium::tuning::{scaled_regime_knobs, Regime};
+
+#[test]
+fn online_list_parses() {
+    assert_eq!(parse_cpu_list("0-3\n".trim()), vec![0, 1, 2, 3]);
+    assert_eq!(parse_cpu_list("0-1,3"), vec![0, 1, 3]);
+    assert_eq!(parse_cpu_list("3,0-1"), vec![0, 1, 3]);
+}
+
+#[test]
+fn unparseable_sysfs_list_is_no_cpus() {
+    assert_eq!(parse_cpu_list(""), Vec::<u32>::new());
+    assert_eq!(parse_cpu_list("0-3,x"), Vec::<u32>::new());
+}
+
+#[test]
+fn watch_ignores_unchanged_set() {
+    let mut w = HotplugWatch::new(vec![0, 1, 2, 3]);
+    assert_eq!(w.update(vec![0, 1, 2, 3]), None);
+    assert_eq!(w.online(), &[0, 1, 2, 3]);
+}
+
+#[test]
+fn watch_ignores_empty_read() {
+    // A TRANSIENT EMPTY READ MUST NEVER SCALE TO ZERO CPUS
+    let mut w = HotplugWatch::new(vec![0, 1]);
+    assert_eq!(w.update(Vec::new()), None);
+    assert_eq!(w.online(), &[0, 1]);
+}
+
+#[test]
+fn watch_reports_offline() {
+    let mut w = HotplugWatch::new(vec![0, 1, 2, 3]);
+    let t = w.update(vec![0, 1, 3]).unwrap();
+    assert_eq!(
+        t,
+        CpuTransition {
+            from: 4,
+            to: 3,
+            offlined: vec![2],
+            onlined: vec![],
+        }
+    );
+    assert_eq!(t.describe(), "4 -> 3 CPUS (OFFLINE [2])");
+    assert_eq!(w.online(), &[0, 1, 3]);
+}
+
+#[test]
+fn watch_reports_online_and_swap() {
+    let mut w = HotplugWatch::new(vec![0, 1, 3]);
+    let t = w.update(vec![0, 1, 2, 3]).unwrap();
+    assert_eq!(t.onlined, vec![2]);
+    assert!(t.offlined.is_empty());
+
+    // SAME COUNT, DIFFERENT CPUS: STILL A TRANSITION (TOPOLOGY CHANGED)
+    let t = w.update(vec![0, 1, 2, 4]).unwrap();
+    assert_eq!((t.from, t.to), (4, 4));
+    assert_eq!(t.describe(), "4 -> 4 CPUS (OFFLINE [3] ONLINE [4])");
+}
+
+#[test]
+fn scaling_sojourn_bounds() {
+    let s2 = CpuScaling::new(2);
+    assert_eq!(s2.sojourn_floor_ns, 2_000_000);
+    assert_eq!(s2.sojourn_ceil_ns, 4_000_000);
+    let s4 = CpuScaling::new(4);
+    assert_eq!(s4.sojourn_floor_ns, 4_000_000);
+    assert_eq!(s4.sojourn_ceil_ns, 8_000_000);
+    let s12 = CpuScaling::new(12);
+    assert_eq!(s12.sojourn_floor_ns, 6_000_000);
+    assert_eq!(s12.sojourn_ceil_ns, 12_000_000);
+}
+
+#[test]
+fn scaling_never_zero() {
+    assert_eq!(CpuScaling::new(0), CpuScaling::new(1));
+}
+
+#[test]
+fn sojourn_threshold_clamped_on_shrink() {
+    // 12C -> 2C: AN EWMA'D 10MS THRESHOLD MUST FALL INTO THE 2C RANGE
+    let after = CpuScaling::new(2);
+    assert_eq!(after.clamp_sojourn(10_000_000), 4_000_000);
+    // 2C -> 12C: A 2MS THRESHOLD RISES TO THE NEW FLOOR
+    let after = CpuScaling::new(12);
+    assert_eq!(after.clamp_sojourn(2_000_000), 6_000_000);
+    assert_eq!(after.clamp_sojourn(8_000_000), 8_000_000);
+}
+
+#[test]
+fn knobs_rescale_on_transition() {
+    // LIGHT SLICE CAPS AT nr_cpus * 500US: OFFLINING CPUS MUST SHRINK IT
+    let mut w = HotplugWatch::new(vec![0, 1, 2, 3]);
+    let before = scaled_regime_knobs(Regime::Light, w.online().len() as u64);
+    let t = w.update(vec![0]).unwrap();
+    let scaling = CpuScaling::new(t.to as u64);
+    let after = scaled_regime_knobs(Regime::Light, scaling.nr_cpus);
+    assert!(after.slice_ns < before.slice_ns);
+    assert_eq!(after, scaled_regime_knobs(Regime::Light, 1));
+}
+
+#[test]
+fn nr_cpus_within_possible_passes_through() {
+    assert_eq!(sanitize_nr_cpus(1, 16), (1, None));
+    assert_eq!(sanitize_nr_cpus(8, 16), (8, None));
+    assert_eq!(sanitize_nr_cpus(16, 16), (16, None));
+    // NEVER 0, EVEN IF THE CALLER LET IT THROUGH
+    assert_eq!(sanitize_nr_cpus(0, 16), (1, None));
+}
+
+#[test]
+fn nr_cpus_above_possible_is_clamped_with_a_warning() {
+    let (effective, warning) = sanitize_nr_cpus(512, 16);
+    assert_eq!(effective, 16);
+    let warning = warning.unwrap();
+    assert_eq!(
+        warning,
+        NrCpusWarning {
+            requested: 512,
+            possible: 16,
+        }
+    );
+    assert!(warning.message().contains("512"));
+    assert!(warning.message().contains("CLAMPED TO 16"));
+    // UNREADABLE POSSIBLE COUNT (0): CLAMP TO 1, NOT 0
+    assert_eq!(sanitize_nr_cpus(4, 0).0, 1);
+}
+
+#[test]
+fn cpuset_masks_with_holes_parse() {
+    // systemd AllowedCPUs=0-3,8-11 AND A DOCKER --cpuset-cpus WITH SINGLES
+    assert_eq!(
+        parse_cpu_list("0-3,8-11\n".trim()),
+        vec![0, 1, 2, 3, 8, 9, 10, 11]
+    );
+    assert_eq!(parse_cpu_list("1,3,5,7"), vec![1, 3, 5, 7]);
+    assert_eq!(parse_cpu_list("0,2-3,6,9-10"), vec![0, 2, 3, 6, 9, 10]);
+    assert_eq!(
+        cpulist::format(&parse_cpu_list("0,2-3,6,9-10")),
+        "0,2-3,6,9-10"
+    );
+
+    let dir = std::env::temp_dir().join(format!("pandemonium-cpuset-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = |name: &str, body: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, body).unwrap();
+        path
+    };
+    assert_eq!(
+        read_cpu_list(&file("effective", "4-5,12\n")),
+        Some(vec![4, 5, 12])
+    );
+    // AN EMPTY cpuset.cpus.effective (NO CPUSET CONTROLLER) SCALES TO NOTHING
+    assert_eq!(read_cpu_list(&file("empty", "\n")), None);
+    assert_eq!(read_cpu_list(&dir.join("missing")), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scaling_counts_online_cpus_inside_the_cpuset() {
+    let online: Vec<u32> = (0..16).collect();
+    assert_eq!(scaling_cpus(&online, None), 16);
+    assert_eq!(scaling_cpus(&online, Some(&parse_cpu_list("0-3,8-11"))), 8);
+    // CPUSET CPUS THAT ARE OFFLINE DO NOT COUNT
+    let holes: Vec<u32> = online.iter().copied().filter(|c| c % 2 == 0).collect();
+    assert_eq!(scaling_cpus(&holes, Some(&parse_cpu_list("0-3"))), 2);
+    // NOTHING ONLINE INSIDE IT: THE CPUSET IS IGNORED, NEVER SCALE TO ZERO
+    assert_eq!(scaling_cpus(&[0, 1], Some(&[8, 9])), 2);
+}
+
+#[test]
+fn watch_rescales_within_the_cpuset() {
+    let mut w = HotplugWatch::new((0..16).collect()).with_cpuset(Some(vec![0, 1, 2, 3]));
+    assert_eq!(w.scaling_cpus(), 4);
+    assert_eq!(w.online().len(), 16);
+    // OFFLINING A CPU OUTSIDE THE CPUSET: A TRANSITION, SAME SCALING
+    let t = w.update((0..15).collect()).unwrap();
+    assert_eq!(t.to, 15);
+    assert_eq!(w.scaling_cpus(), 4);
+    w.update(vec![0, 1, 4, 5]).unwrap();
+    assert_eq!(w.scaling_cpus(), 2);
+    assert_eq!(CpuScaling::new(w.scaling_cpus()), CpuScaling::new(2));
+    assert_eq!(HotplugWatch::new(vec![0, 1, 2]).scaling_cpus(), 3);
+}
@@ -0,0 +1,123 @@
+// PANDEMONIUM HYBRID CORE TESTS
+// FAST/SLOW CLASSIFICATION FROM SYNTHETIC cpu_capacity / max_freq, FAST LIST,
+// /proc/stat PARSING, PER-CLASS IDLE + WAKE LATENCY DELTAS
+
+use pandemonium::hybrid::{
+    format_line, parse_proc_stat, ClassSample, HybridMonitor, HybridTopology,
+};
+use pandemonium::stats::PandemoniumStats;
+
+fn caps(v: &[u64]) -> Vec<Option<u64>> {
+    v.iter().map(|&c| Some(c)).collect()
+}
+
+#[test]
+fn alder_lake_splits_p_and_e() {
+    // 4 P-CORE THREADS AT 1024, 4 E-CORES AT ~600
+    let h = HybridTopology::classify(&caps(&[1024, 1024, 1024, 1024, 609, 609, 609, 609])).unwrap();
+    assert_eq!(h.fast_cpu_list(), vec![0, 1, 2, 3]);
+    assert_eq!(h.counts(), (4, 4));
+    assert!(h.is_fast(0));
+    assert!(!h.is_fast(7));
+    assert!(!h.is_fast(64));
+}
+
+#[test]
+fn uniform_machine_is_not_hybrid() {
+    assert_eq!(HybridTopology::classify(&caps(&[1024; 16])), None);
+    // NOTHING READABLE (VM, CONTAINER): NOT HYBRID
+    assert_eq!(HybridTopology::classify(&[None, None]), None);
+    assert_eq!(HybridTopology::classify(&[]), None);
+}
+
+#[test]
+fn max_freq_fallback_classifies() {
+    // NO cpu_capacity: kHz VALUES, E-CORES INTERLEAVED
+    let h = HybridTopology::classify(&caps(&[5_000_000, 3_800_000, 5_000_000, 3_800_000])).unwrap();
+    assert_eq!(h.fast_cpu_list(), vec![0, 2]);
+}
+
+#[test]
+fn three_cluster_midpoint() {
+    // big.LITTLE 1+3+4: MIDPOINT OF 160..1024 IS 592, MID CORES (870) ARE FAST
+    let h = HybridTopology::classify(&caps(&[160, 160, 160, 160, 870, 870, 870, 1024])).unwrap();
+    assert_eq!(h.fast_cpu_list(), vec![4, 5, 6, 7]);
+}
+
+#[test]
+fn unknown_cpu_counts_as_fast() {
+    let h = HybridTopology::classify(&[Some(1024), None, Some(512)]).unwrap();
+    assert_eq!(h.fast_cpu_list(), vec![0, 1]);
+}
+
+const STAT_T0: &str = "\
+cpu  400 0 100 1500 0 0 0 0 0 0
+cpu0 100 0 50 850 0 0 0 0 0 0
+cpu1 300 0 50 650 0 0 0 0 0 0
+intr 12345
+ctxt 999
+";
+
+const STAT_T1: &str = "\
+cpu  600 0 200 2200 0 0 0 0 0 0
+cpu0 150 0 50 1100 0 0 0 0 0 0
+cpu1 450 0 150 750 50 0 0 0 0 0
+intr 12400
+";
+
+#[test]
+fn proc_stat_per_cpu_only() {
+    let rows = parse_proc_stat(STAT_T0);
+    // AGGREGATE "cpu" LINE AND NON-CPU LINES SKIPPED
+    assert_eq!(rows, vec![(0, 850, 1000), (1, 650, 1000)]);
+    // IOWAIT COUNTS AS IDLE
+    assert_eq!(parse_proc_stat(STAT_T1)[1], (1, 800, 1400));
+}
+
+#[test]
+fn monitor_splits_idle_and_latency_by_class() {
+    let h = HybridTopology::classify(&caps(&[1024, 512])).unwrap();
+    let mut mon = HybridMonitor::default();
+    let lat = |sum: u64, n: u64| PandemoniumStats {
+        wake_lat_sum: sum,
+        wake_lat_samples: n,
+        ..Default::default()
+    };
+
+    // FIRST TICK IS THE BASELINE
+    assert_eq!(mon.tick(&h, STAT_T0, &[lat(0, 0), lat(0, 0)]), None);
+
+    let (fast, slow) = mon
+        .tick(&h, STAT_T1, &[lat(40_000, 10), lat(900_000, 3)])
+        .unwrap();
+    // CPU0: 250 IDLE OF 300; CPU1: 150 IDLE OF 400
+    assert_eq!(
+        fast,
+        ClassSample {
+            idle_pct: 83,
+            wake_us: 4
+        }
+    );
+    assert_eq!(
+        slow,
+        ClassSample {
+            idle_pct: 37,
+            wake_us: 300
+        }
+    );
+    assert_eq!(
+        format_line(&fast, &slow),
+        "[HYBRID] fast: idle=83% wake=4us slow: idle=37% wake=300us"
+    );
+}
+
+#[test]
+fn monitor_quiet_tick_is_zero_not_nan() {
+    let h = HybridTopology::classify(&caps(&[1024, 512])).unwrap();
+    let mut mon = HybridMonitor::default();
+    let per_cpu = vec![PandemoniumStats::default(); 2];
+    mon.tick(&h, STAT_T0, &per_cpu);
+    let (fast, slow) = mon.tick(&h, STAT_T0, &per_cpu).unwrap();
+    assert_eq!(fast, ClassSample::default());
+    assert_eq!(slow, ClassSample::default());
+}
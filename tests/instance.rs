@@ -0,0 +1,165 @@
+// PANDEMONIUM SINGLE-INSTANCE LOCK TESTS
+// TWO PROCESSES (fork) CONTENDING FOR ONE LOCK FILE, --force AGAINST A LIVE
+// AND A DEAD OWNER, STALE PID FILES, PID PARSING
+
+use std::path::{Path, PathBuf};
+
+use pandemonium::instance::{acquire, parse_pid, pid_alive, LockError};
+
+fn lock_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "pandemonium-lock-{}-{}.pid",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+// CHILD TAKES THE LOCK, SIGNALS ready, THEN HOLDS IT UNTIL release CLOSES
+fn fork_holder(path: &Path) -> (libc::pid_t, libc::c_int) {
+    let mut ready = [0; 2];
+    let mut release = [0; 2];
+    unsafe {
+        assert_eq!(libc::pipe(ready.as_mut_ptr()), 0);
+        assert_eq!(libc::pipe(release.as_mut_ptr()), 0);
+    }
+    let child = unsafe { libc::fork() };
+    assert!(child >= 0, "fork failed");
+    if child == 0 {
+        let code = match acquire(path, std::process::id(), false) {
+            Ok(_lock) => {
+                let mut b = [1u8];
+                unsafe {
+                    libc::close(release[1]);
+                    libc::write(ready[1], b.as_ptr().cast(), 1);
+                    // BLOCKS UNTIL THE PARENT CLOSES ITS END
+                    libc::read(release[0], b.as_mut_ptr().cast(), 1);
+                }
+                0
+            }
+            Err(_) => 1,
+        };
+        unsafe { libc::_exit(code) };
+    }
+    unsafe {
+        libc::close(ready[1]);
+        libc::close(release[0]);
+        let mut b = [0u8];
+        assert_eq!(libc::read(ready[0], b.as_mut_ptr().cast(), 1), 1);
+        libc::close(ready[0]);
+    }
+    (child, release[1])
+}
+
+fn reap(child: libc::pid_t, release: libc::c_int) -> i32 {
+    let mut status = 0;
+    unsafe {
+        libc::close(release);
+        assert_eq!(libc::waitpid(child, &mut status, 0), child);
+    }
+    libc::WEXITSTATUS(status)
+}
+
+#[test]
+fn second_process_is_refused_until_the_first_exits() {
+    let path = lock_path("two");
+    let (child, release) = fork_holder(&path);
+
+    let err = acquire(&path, std::process::id(), false).unwrap_err();
+    assert_eq!(
+        err,
+        LockError::Held {
+            pid: Some(child as u32)
+        }
+    );
+    assert!(err
+        .message(&path)
+        .contains(&format!("ALREADY RUNNING AS PID {}", child)));
+
+    // --force DOES NOT STEAL FROM A LIVE OWNER
+    assert_eq!(
+        acquire(&path, std::process::id(), true).unwrap_err(),
+        LockError::StillAlive { pid: child as u32 }
+    );
+
+    // OWNER EXITS: THE KERNEL DROPS ITS LOCK, NO --force NEEDED
+    assert_eq!(reap(child, release), 0);
+    let lock = acquire(&path, std::process::id(), false).unwrap();
+    assert_eq!(
+        parse_pid(&std::fs::read_to_string(lock.path()).unwrap()),
+        Some(std::process::id())
+    );
+    drop(lock);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn parent_holding_the_lock_refuses_a_forked_child() {
+    let path = lock_path("parent");
+    let lock = acquire(&path, std::process::id(), false).unwrap();
+    let parent = std::process::id();
+
+    let child = unsafe { libc::fork() };
+    assert!(child >= 0, "fork failed");
+    if child == 0 {
+        // A FRESH OPEN IS A NEW OPEN FILE DESCRIPTION: THE INHERITED FD
+        // DOES NOT LET THE CHILD IN
+        let refused = matches!(
+            acquire(&path, 1, false),
+            Err(LockError::Held { pid: Some(p) }) if p == parent
+        );
+        unsafe { libc::_exit(if refused { 0 } else { 1 }) };
+    }
+    let mut status = 0;
+    unsafe {
+        assert_eq!(libc::waitpid(child, &mut status, 0), child);
+    }
+    assert_eq!(libc::WEXITSTATUS(status), 0);
+    drop(lock);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn force_takes_over_from_a_dead_owner() {
+    // A LEAKED FD KEEPS THE LOCK HELD BUT THE RECORDED PID IS GONE
+    let path = lock_path("dead");
+    let leaked = acquire(&path, 999_999_999, false).unwrap();
+    assert_eq!(
+        acquire(&path, 4242, false).unwrap_err(),
+        LockError::Held {
+            pid: Some(999_999_999)
+        }
+    );
+    let lock = acquire(&path, 4242, true).unwrap();
+    assert_eq!(std::fs::read_to_string(lock.path()).unwrap(), "4242\n");
+    drop(leaked);
+    // THE NEW LOCK IS ITS OWN: DROPPING THE OLD ONE CHANGES NOTHING
+    assert_eq!(
+        acquire(&path, 1, false).unwrap_err(),
+        LockError::Held { pid: Some(4242) }
+    );
+    drop(lock);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn stale_pid_file_without_a_lock_is_ignored() {
+    let path = lock_path("stale");
+    std::fs::write(&path, "999999999\n").unwrap();
+    let lock = acquire(&path, 4242, false).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "4242\n");
+    drop(lock);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn pid_parsing_and_liveness() {
+    assert_eq!(parse_pid("1234\n"), Some(1234));
+    assert_eq!(parse_pid(" 77 "), Some(77));
+    assert_eq!(parse_pid(""), None);
+    assert_eq!(parse_pid("0\n"), None);
+    assert_eq!(parse_pid("pandemonium"), None);
+    assert!(pid_alive(std::process::id()));
+    assert!(!pid_alive(u32::MAX));
+}
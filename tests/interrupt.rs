@@ -0,0 +1,43 @@
+// PANDEMONIUM CTRL+C ESCALATION TESTS
+// PRESS COUNTING (SHUTDOWN, FORCE, REPEATS), RESET ON RESTART, AND THE
+// HANDLER'S PIN REMOVAL OVER A SYNTHETIC PIN DIR
+
+use pandemonium::interrupt::{remove_pins, Interrupts, Press, FORCE_CLEANUP, FORCE_EXIT_CODE};
+
+#[test]
+fn second_press_forces_and_later_ones_repeat() {
+    let sigints = Interrupts::new();
+    assert!(!sigints.forced());
+    assert_eq!(sigints.press(), Press::Shutdown);
+    assert!(!sigints.forced());
+    assert_eq!(sigints.press(), Press::Force);
+    assert!(sigints.forced());
+    // MASHING CTRL+C: NO SECOND FORCED EXIT, NO WRAP BACK TO Shutdown
+    for _ in 0..300 {
+        assert_eq!(sigints.press(), Press::Repeat);
+    }
+    assert!(sigints.forced());
+
+    // A KERNEL-REQUESTED RESTART: THE NEXT RUN STARTS FROM ZERO
+    sigints.reset();
+    assert!(!sigints.forced());
+    assert_eq!(sigints.press(), Press::Shutdown);
+
+    assert_eq!(FORCE_EXIT_CODE, 128 + 2);
+    assert!(FORCE_CLEANUP.as_secs() >= 1);
+}
+
+#[test]
+fn forced_cleanup_unlinks_every_pin_and_the_dir() {
+    let dir = std::env::temp_dir().join(format!("pandemonium-interrupt-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for pin in ["tuning_knobs", "stats_map", "wake_lat_hist"] {
+        std::fs::write(dir.join(pin), "").unwrap();
+    }
+    assert_eq!(remove_pins(&dir), 3);
+    assert!(!dir.exists());
+
+    // ALREADY GONE (THE MAIN THREAD GOT THERE FIRST, OR NOTHING WAS PINNED)
+    assert_eq!(remove_pins(&dir), 0);
+}
@@ -0,0 +1,67 @@
+// PANDEMONIUM IO BENCH TESTS
+// PROFILE VALIDATION, WORKER REPORT ROUND TRIP, IOPS AGGREGATION
+
+use pandemonium::iobench::{
+    parse_io_report, total_iops, IoKind, IoProfile, IoReport, IO_ALIGN, IO_FILE_BYTES,
+};
+
+#[test]
+fn profile_requires_aligned_size_and_depth() {
+    assert!(IoProfile::default().validate().is_ok());
+    let p = |size, depth| IoProfile { size, depth };
+    assert!(p(0, 1).validate().is_err());
+    assert!(p(IO_ALIGN + 512, 1)
+        .validate()
+        .unwrap_err()
+        .contains("4096"));
+    assert!(p(IO_ALIGN * 16, 1).validate().is_ok());
+    assert!(p(IO_ALIGN, 0)
+        .validate()
+        .unwrap_err()
+        .contains("--io-depth"));
+    assert!(p(IO_FILE_BYTES + IO_ALIGN, 1).validate().is_err());
+    assert_eq!(p(IO_ALIGN, 1).slots(), IO_FILE_BYTES / IO_ALIGN);
+}
+
+#[test]
+fn kind_names_round_trip() {
+    for k in IoKind::ALL {
+        assert_eq!(IoKind::parse(k.name()), Some(k));
+    }
+    assert_eq!(IoKind::parse("write"), None);
+}
+
+#[test]
+fn report_line_round_trips() {
+    let r = IoReport {
+        kind: IoKind::Fsync,
+        ops: 1234,
+        elapsed_us: 30_000_000,
+    };
+    assert_eq!(r.line(), "io fsync ops=1234 elapsed_us=30000000");
+    assert_eq!(parse_io_report(&r.line()), Some(r));
+    // PROBE SAMPLES, HEADERS AND TRUNCATED LINES ARE NOT REPORTS
+    assert_eq!(parse_io_report("1234"), None);
+    assert_eq!(parse_io_report("# probe period_us=10000"), None);
+    assert_eq!(parse_io_report("io read ops=5"), None);
+    assert_eq!(parse_io_report("io scan ops=5 elapsed_us=10"), None);
+}
+
+#[test]
+fn iops_add_across_concurrent_workers() {
+    let r = |kind, ops, elapsed_us| IoReport {
+        kind,
+        ops,
+        elapsed_us,
+    };
+    let reports = [
+        r(IoKind::Read, 3000, 1_000_000),
+        r(IoKind::Read, 1500, 500_000),
+        r(IoKind::Fsync, 200, 2_000_000),
+        // KILLED BEFORE ITS FIRST CLOCK READ: CONTRIBUTES NOTHING
+        r(IoKind::Fsync, 0, 0),
+    ];
+    assert_eq!(total_iops(&reports, IoKind::Read), 6000.0);
+    assert_eq!(total_iops(&reports, IoKind::Fsync), 100.0);
+    assert_eq!(total_iops(&[], IoKind::Read), 0.0);
+}
@@ -0,0 +1,70 @@
+// PANDEMONIUM KMSG TESTS
+// /dev/kmsg RECORD PARSING, FILTERING, HINTS
+
+use pandemonium::kmsg::{format_relative, hint_for, is_scx_related, parse_record};
+
+#[test]
+fn parse_basic_record() {
+    let r = parse_record("6,1234,5678901,-;sched_ext: BPF scheduler \"pandemonium\" enabled\n")
+        .unwrap();
+    assert_eq!(r.level, 6);
+    assert_eq!(r.facility, 0);
+    assert_eq!(r.seq, 1234);
+    assert_eq!(r.ts_us, 5678901);
+    assert_eq!(r.msg, "sched_ext: BPF scheduler \"pandemonium\" enabled");
+    assert!(!r.is_error());
+}
+
+#[test]
+fn parse_priority_split() {
+    // FACILITY 1 (USER), LEVEL 3 (ERR): (1 << 3) | 3 = 11
+    let r = parse_record("11,7,100,-;oops").unwrap();
+    assert_eq!(r.facility, 1);
+    assert_eq!(r.level, 3);
+    assert!(r.is_error());
+}
+
+#[test]
+fn parse_drops_continuation_lines() {
+    let raw =
+        "3,42,999,-,caller=T1;sched_ext: runnable task stall\n SUBSYSTEM=cpu\n DEVICE=+cpu:0\n";
+    let r = parse_record(raw).unwrap();
+    assert_eq!(r.msg, "sched_ext: runnable task stall");
+}
+
+#[test]
+fn parse_message_with_semicolons() {
+    let r = parse_record("4,1,1,-;a; b; c").unwrap();
+    assert_eq!(r.msg, "a; b; c");
+}
+
+#[test]
+fn parse_rejects_garbage() {
+    assert!(parse_record("").is_none());
+    assert!(parse_record("no header here").is_none());
+    assert!(parse_record("x,1,1,-;msg").is_none());
+    assert!(parse_record("6,1;msg").is_none());
+}
+
+#[test]
+fn scx_filter() {
+    assert!(is_scx_related("sched_ext: BPF scheduler disabled"));
+    assert!(is_scx_related("PANDEMONIUM: exit"));
+    assert!(is_scx_related("scx_bpf_dsq_insert_vtime called"));
+    assert!(!is_scx_related("usb 1-1: new high-speed USB device"));
+}
+
+#[test]
+fn hints_for_known_failures() {
+    assert!(hint_for("sched_ext: writing directly to p->scx.slice is deprecated").is_some());
+    assert!(hint_for("sched_ext: pandemonium: runnable task stall (kworker/0:1[12])").is_some());
+    assert!(hint_for("sched_ext: BPF scheduler \"pandemonium\" enabled").is_none());
+}
+
+#[test]
+fn relative_timestamps() {
+    assert_eq!(format_relative(1_500_000, 1_000_000), "[+0.500000]");
+    assert_eq!(format_relative(13_000_042, 1_000_000), "[+12.000042]");
+    // OUT-OF-ORDER BASE NEVER UNDERFLOWS
+    assert_eq!(format_relative(5, 10), "[+0.000000]");
+}
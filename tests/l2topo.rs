@@ -0,0 +1,118 @@
+// PANDEMONIUM L2 TOPOLOGY TESTS
+// GROUPING FROM SYNTHETIC shared_cpu_list, PREFERRED NEIGHBORS, PER-GROUP L2 RATES,
+// RESERVED CPUS DROPPED FROM THEIR GROUPS
+
+use pandemonium::l2topo::{L2Topology, MAX_L2_SIBLINGS};
+use pandemonium::stats::PandemoniumStats;
+
+fn lists(raw: &[Option<&str>]) -> Vec<Option<String>> {
+    raw.iter().map(|l| l.map(|s| format!("{}\n", s))).collect()
+}
+
+// 8 CPUS, SMT PAIRS SHARING L2: (0,4) (1,5) (2,6) (3,7)
+fn smt_pairs() -> L2Topology {
+    L2Topology::from_shared_lists(&lists(&[
+        Some("0,4"),
+        Some("1,5"),
+        Some("2,6"),
+        Some("3,7"),
+        Some("0,4"),
+        Some("1,5"),
+        Some("2,6"),
+        Some("3,7"),
+    ]))
+}
+
+#[test]
+fn groups_in_first_seen_order() {
+    let t = smt_pairs();
+    assert_eq!(t.nr_cpus, 8);
+    assert_eq!(
+        t.l2_groups,
+        vec![vec![0, 4], vec![1, 5], vec![2, 6], vec![3, 7]]
+    );
+    assert_eq!(t.l2_domain, vec![0, 1, 2, 3, 0, 1, 2, 3]);
+}
+
+#[test]
+fn neighbors_exclude_self() {
+    let t = smt_pairs();
+    assert_eq!(t.preferred_neighbors(0), vec![4]);
+    assert_eq!(t.preferred_neighbors(7), vec![3]);
+    assert!(t.preferred_neighbors(99).is_empty());
+}
+
+#[test]
+fn neighbors_capped_at_bpf_slots() {
+    // E-CORE CLUSTER STYLE: 12 CPUS ON ONE L2, BPF SEES THE FIRST 8 SLOTS
+    let shared = "0-11";
+    let t = L2Topology::from_shared_lists(&lists(&[Some(shared); 12]));
+    assert_eq!(t.l2_groups.len(), 1);
+    assert_eq!(t.preferred_neighbors(0), vec![1, 2, 3, 4, 5, 6, 7]);
+    // A CPU PAST THE SLOT LIMIT STILL SEES THE FULL 8 VISIBLE SLOTS
+    assert_eq!(t.preferred_neighbors(10).len(), MAX_L2_SIBLINGS);
+}
+
+#[test]
+fn unreadable_cpu_has_no_group() {
+    // OFFLINE CPU 1: OWN ID IN THE DOMAIN MAP, NO NEIGHBORS
+    let t = L2Topology::from_shared_lists(&lists(&[Some("0,2"), None, Some("0,2")]));
+    assert_eq!(t.l2_groups, vec![vec![0, 2]]);
+    assert_eq!(t.l2_domain[1], 1);
+    assert!(t.preferred_neighbors(1).is_empty());
+    let table = t.neighbor_table();
+    assert_eq!(table.len(), 4);
+    assert!(table[2].starts_with("1      -"), "{}", table[2]);
+}
+
+#[test]
+fn reserved_cpus_leave_their_groups() {
+    let t = smt_pairs().without(&[1, 4]);
+    assert_eq!(t.l2_groups, vec![vec![0], vec![5], vec![2, 6], vec![3, 7]]);
+    // NO SIBLING STEALS FOR A RESERVED CPU, NONE STEALS ONTO IT
+    assert_eq!(t.preferred_neighbors(0), Vec::<u32>::new());
+    assert_eq!(t.preferred_neighbors(4), Vec::<u32>::new());
+    assert_eq!(t.preferred_neighbors(2), vec![6]);
+    assert_eq!(t.l2_domain, vec![0, 1, 2, 3, 4, 1, 2, 3]);
+    assert_eq!(smt_pairs().without(&[]), smt_pairs());
+}
+
+#[test]
+fn summary_matches_startup_log() {
+    let t = smt_pairs();
+    let lines = t.summary_lines();
+    assert_eq!(lines[0], "L2 GROUP 0: [0,4]");
+    assert_eq!(lines.last().unwrap(), "L2 GROUPS: 4 across 8 CPUs");
+}
+
+#[test]
+fn group_l2_counts_sum_members() {
+    let t = smt_pairs();
+    let mut per_cpu = vec![PandemoniumStats::default(); 8];
+    per_cpu[0].nr_l2_hit_batch = 30;
+    per_cpu[4].nr_l2_hit_interactive = 60;
+    per_cpu[4].nr_l2_miss_lat_crit = 10;
+    per_cpu[3].nr_l2_miss_batch = 5;
+    let counts = t.group_l2_counts(&per_cpu);
+    assert_eq!(counts, vec![(90, 10), (0, 0), (0, 0), (0, 5)]);
+    let table = t.group_l2_table(&per_cpu);
+    assert!(table[1].ends_with(" 90"), "{}", table[1]);
+    assert!(table[4].ends_with(" 0"), "{}", table[4]);
+}
+
+#[test]
+fn group_l2_counts_short_percpu_slice() {
+    // FEWER PER-CPU VALUES THAN CPUS: MISSING ONES COUNT AS ZERO
+    let t = smt_pairs();
+    let per_cpu = vec![
+        PandemoniumStats {
+            nr_l2_hit_batch: 1,
+            ..Default::default()
+        };
+        2
+    ];
+    assert_eq!(
+        t.group_l2_counts(&per_cpu),
+        vec![(1, 0), (1, 0), (0, 0), (0, 0)]
+    );
+}
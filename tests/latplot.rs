@@ -0,0 +1,89 @@
+// PANDEMONIUM WAKEUP LATENCY PLOT TESTS
+// CDF AND HEATMAP ROWS SNAPSHOTTED FROM FIXED HISTOGRAMS,
+// BUCKET LABELS, TIER POOLING, LOG-SCALE INTENSITY
+
+use pandemonium::latplot::{bucket_label, cdf_lines, heat_level, heatmap_lines, pool, HEAT_RAMP};
+use pandemonium::tuning::HIST_BUCKETS;
+
+fn hist(cells: &[(usize, u64)]) -> [u64; HIST_BUCKETS] {
+    let mut counts = [0u64; HIST_BUCKETS];
+    for &(b, c) in cells {
+        counts[b] = c;
+    }
+    counts
+}
+
+#[test]
+fn labels_and_pooling() {
+    assert_eq!(bucket_label(0), "10us");
+    assert_eq!(bucket_label(4), "250us");
+    assert_eq!(bucket_label(6), "1ms");
+    assert_eq!(bucket_label(10), "20ms");
+    assert_eq!(bucket_label(HIST_BUCKETS - 1), "+inf");
+
+    let tiers = [hist(&[(0, 1)]), hist(&[(0, 2), (3, 5)]), hist(&[(11, 7)])];
+    assert_eq!(pool(&tiers), hist(&[(0, 3), (3, 5), (11, 7)]));
+}
+
+#[test]
+fn cdf_snapshot() {
+    // HALF AT 25us, 30% AT 50us, THE LAST 20% AT 250us
+    let rows = cdf_lines(&hist(&[(1, 50), (2, 30), (4, 20)]), 10);
+    assert_eq!(
+        rows,
+        [
+            "  <=  10us |          |   0.0%",
+            "  <=  25us |#####     |  50.0%",
+            "  <=  50us |########  |  80.0%",
+            "  <= 100us |########  |  80.0%",
+            "  <= 250us |##########| 100.0%",
+            "  <= 500us |##########| 100.0%",
+            "  <=   1ms |##########| 100.0%",
+            "  <=   2ms |##########| 100.0%",
+            "  <=   5ms |##########| 100.0%",
+            "  <=  10ms |##########| 100.0%",
+            "  <=  20ms |##########| 100.0%",
+            "  <=  +inf |##########| 100.0%",
+        ]
+    );
+    assert_eq!(cdf_lines(&[0; HIST_BUCKETS], 10), ["  NO WAKEUP SAMPLES"]);
+}
+
+#[test]
+fn heat_level_is_log_scaled() {
+    let top = HEAT_RAMP.len() - 1;
+    assert_eq!(heat_level(0, 1000), 0);
+    assert_eq!(heat_level(1, 1000), 1);
+    assert_eq!(heat_level(10, 1000), 3);
+    assert_eq!(heat_level(100, 1000), 6);
+    assert_eq!(heat_level(1000, 1000), top);
+    // A SINGLE WAKEUP IN THE WHOLE MAP IS THE BUSIEST CELL
+    assert_eq!(heat_level(1, 1), top);
+}
+
+#[test]
+fn heatmap_snapshot() {
+    // MINUTE 1 HAD NO TICK: NO ROW FOR IT
+    let rows = [
+        (0, hist(&[(0, 1), (1, 1000)])),
+        (2, hist(&[(2, 10), (11, 100)])),
+    ];
+    let lines = heatmap_lines(&rows);
+    assert_eq!(
+        lines,
+        [
+            "   MIN  10us  25us  50us 100us 250us 500us   1ms   2ms   5ms  10ms  20ms  +inf"
+                .to_string(),
+            "     0 ..... @@@@@".to_string(),
+            format!("     2{}-----{}*****", " ".repeat(13), " ".repeat(49)),
+            "  SCALE: ' .:-=+*#%@' (LOG, '@' = 1000 WAKEUPS)".to_string(),
+        ]
+    );
+    // EVERY ROW LINES UP UNDER THE HEADER
+    assert_eq!(lines[2].len(), lines[0].len());
+    assert_eq!(heatmap_lines(&[]), ["  NO WAKEUP SAMPLES"]);
+    assert_eq!(
+        heatmap_lines(&[(0, [0; HIST_BUCKETS])]),
+        ["  NO WAKEUP SAMPLES"]
+    );
+}
@@ -0,0 +1,133 @@
+// PANDEMONIUM LEAK SNAPSHOT TESTS
+// SNAPSHOTS OF A SYNTHETIC PIN DIR + /proc (THE OBSERVER EXCLUDED, ONLY
+// pandemonium comms), AND THE BEFORE/AFTER DIFF THE GATE AND SOAK USE
+
+use std::path::{Path, PathBuf};
+
+use pandemonium::leak::{leaks, Leak, Snapshot, PROCESS_COMM};
+
+const OBSERVER: u32 = 500;
+
+struct Tree {
+    root: PathBuf,
+}
+
+impl Tree {
+    fn new(name: &str) -> Self {
+        let root =
+            std::env::temp_dir().join(format!("pandemonium-leak-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("proc")).unwrap();
+        Self { root }
+    }
+
+    fn pins(&self) -> PathBuf {
+        self.root.join("bpf/pandemonium")
+    }
+
+    fn pin(&self, name: &str) {
+        std::fs::create_dir_all(self.pins()).unwrap();
+        std::fs::write(self.pins().join(name), "").unwrap();
+    }
+
+    fn process(&self, pid: u32, comm: &str, threads: u32) {
+        let d = self.root.join(format!("proc/{}", pid));
+        for tid in 0..threads {
+            std::fs::create_dir_all(d.join(format!("task/{}", pid + tid))).unwrap();
+        }
+        std::fs::write(d.join("comm"), format!("{}\n", comm)).unwrap();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot::take(&self.pins(), &self.root.join("proc"), OBSERVER)
+    }
+}
+
+impl Drop for Tree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn snapshot_reads_pins_processes_and_threads() {
+    let t = Tree::new("snap");
+    let clean = t.snapshot();
+    assert_eq!(clean, Snapshot::default());
+
+    t.pin("tuning_knobs");
+    t.pin("stats_map");
+    // THE OBSERVER IS A pandemonium PROCESS TOO (THE SOAK RUNNER): NOT REPORTED
+    t.process(OBSERVER, PROCESS_COMM, 3);
+    t.process(900, PROCESS_COMM, 5);
+    t.process(901, "bash", 1);
+    t.process(902, "pandemonium-gui", 1);
+    std::fs::create_dir_all(t.root.join("proc/self")).unwrap();
+
+    let s = t.snapshot();
+    assert!(s.pin_dir);
+    assert_eq!(s.pins, vec!["stats_map", "tuning_knobs"]);
+    assert_eq!(s.processes, vec![(900, PROCESS_COMM.to_string())]);
+    assert_eq!(s.threads, 3);
+}
+
+#[test]
+fn diff_names_each_leak() {
+    let before = Snapshot {
+        threads: 4,
+        ..Snapshot::default()
+    };
+    let after = Snapshot {
+        pin_dir: true,
+        pins: vec!["stats_map".to_string()],
+        processes: vec![(900, PROCESS_COMM.to_string())],
+        threads: 6,
+    };
+    let found = leaks(&before, &after);
+    assert_eq!(
+        found,
+        vec![
+            Leak::PinDir,
+            Leak::Pin("stats_map".to_string()),
+            Leak::Process(900, PROCESS_COMM.to_string()),
+            Leak::Threads {
+                before: 4,
+                after: 6
+            },
+        ]
+    );
+    let messages: Vec<String> = found.iter().map(Leak::message).collect();
+    assert_eq!(messages[0], "PIN DIR NOT REMOVED");
+    assert_eq!(messages[1], "PIN LEFT BEHIND: stats_map");
+    assert_eq!(messages[2], "PROCESS STILL RUNNING: pandemonium (PID 900)");
+    assert_eq!(messages[3], "THREADS: 4 BEFORE, 6 AFTER");
+
+    // BACK TO BASELINE, OR FEWER THREADS: CLEAN
+    assert!(leaks(&before, &before).is_empty());
+    let fewer = Snapshot {
+        threads: 2,
+        ..Snapshot::default()
+    };
+    assert!(leaks(&before, &fewer).is_empty());
+}
+
+#[test]
+fn state_present_at_baseline_is_not_the_cycle_s_leak() {
+    let t = Tree::new("baseline");
+    t.pin("stats_map");
+    t.process(700, PROCESS_COMM, 1);
+    let before = t.snapshot();
+
+    // A CYCLE THAT ADDS A PIN AND A PROCESS, THEN CLEANS ONLY ITS PROCESS
+    t.pin("task_class_init");
+    t.process(800, PROCESS_COMM, 1);
+    std::fs::remove_dir_all(t.root.join("proc/800")).unwrap();
+    assert_eq!(
+        leaks(&before, &t.snapshot()),
+        vec![Leak::Pin("task_class_init".to_string())]
+    );
+
+    // NOTHING UNDER A MISSING ROOT: AN EMPTY SNAPSHOT, NOT AN ERROR
+    let missing = Snapshot::take(Path::new("/nonexistent/pins"), Path::new("/nonexistent"), 1);
+    assert_eq!(missing, Snapshot::default());
+}
@@ -0,0 +1,107 @@
+// PANDEMONIUM MEMLOCK PREFLIGHT TESTS
+// MAP FOOTPRINT ESTIMATES, PER-CPU SCALING, TABLE FORMAT, ERROR MATCHING
+
+use pandemonium::memlock::{
+    format_table, is_memlock_error, is_short, limit_from_raw, map_bytes, total_bytes, MapKind,
+    MapSpec, MAPS,
+};
+
+const PAGE: u64 = 4096;
+
+fn spec(kind: MapKind, key: u64, value: u64, entries: u64) -> MapSpec {
+    MapSpec {
+        name: "t",
+        kind,
+        key_size: key,
+        value_size: value,
+        max_entries: entries,
+    }
+}
+
+#[test]
+fn array_rounds_to_pages() {
+    // 1 x 80B STILL COSTS A PAGE
+    assert_eq!(map_bytes(&spec(MapKind::Array, 4, 80, 1), 8), PAGE);
+    // 1024 x 4B -> 8B ALIGNED = 8KB
+    assert_eq!(map_bytes(&spec(MapKind::Array, 4, 4, 1024), 8), 2 * PAGE);
+}
+
+#[test]
+fn percpu_scales_with_cpus() {
+    let m = spec(MapKind::PercpuArray, 4, 8, 36);
+    // 36 x 8B x 64 CPUS = 18KB -> 5 PAGES
+    assert_eq!(map_bytes(&m, 64), 5 * PAGE);
+    assert!(map_bytes(&m, 256) > map_bytes(&m, 64));
+    // ZERO CPUS NEVER ESTIMATES ZERO
+    assert_eq!(map_bytes(&m, 0), map_bytes(&m, 1));
+}
+
+#[test]
+fn hash_counts_elements_and_buckets() {
+    // 512 x (48 + 16 + 40) + 512 x 16 = 61440 -> 15 PAGES
+    let m = spec(MapKind::Hash, 16, 40, 512);
+    assert_eq!(map_bytes(&m, 4), 15 * PAGE);
+    // BUCKETS ROUND UP TO A POWER OF TWO
+    let m = spec(MapKind::Hash, 16, 1, 33);
+    assert_eq!(map_bytes(&m, 4), PAGE);
+    assert_eq!(
+        map_bytes(&spec(MapKind::LruHash, 16, 40, 512), 4),
+        map_bytes(&spec(MapKind::Hash, 16, 40, 512), 4)
+    );
+}
+
+#[test]
+fn task_storage_is_free_up_front() {
+    assert_eq!(map_bytes(&spec(MapKind::TaskStorage, 4, 0, 0), 128), 0);
+}
+
+#[test]
+fn declared_maps_total() {
+    let small = total_bytes(MAPS, 4);
+    let big = total_bytes(MAPS, 1024);
+    assert!(small > 0);
+    assert!(big > small);
+    assert_eq!(small % PAGE, 0);
+    assert!(MAPS.iter().any(|m| m.name == "stats_map"));
+}
+
+#[test]
+fn table_has_every_map_and_total() {
+    let lines = format_table(MAPS, 16);
+    assert_eq!(lines.len(), MAPS.len() + 2);
+    assert!(lines[0].contains("LOCKED_KB"));
+    for m in MAPS {
+        assert!(
+            lines.iter().any(|l| l.contains(m.name)),
+            "{} missing",
+            m.name
+        );
+    }
+    let last = lines.last().unwrap();
+    assert!(last.contains("TOTAL (16 possible CPUs)"));
+    assert!(last
+        .trim_end()
+        .ends_with(&(total_bytes(MAPS, 16) / 1024).to_string()));
+    assert!(lines
+        .iter()
+        .any(|l| l.contains("task_ctx_stor") && l.contains("on demand")));
+}
+
+#[test]
+fn short_limit() {
+    assert!(!is_short(None, u64::MAX));
+    assert!(is_short(Some(64 * 1024), 128 * 1024));
+    assert!(!is_short(Some(128 * 1024), 128 * 1024));
+    assert_eq!(limit_from_raw(libc::RLIM_INFINITY), None);
+    assert_eq!(limit_from_raw(8 << 20), Some(8 << 20));
+}
+
+#[test]
+fn memlock_error_matching() {
+    assert!(is_memlock_error("failed to create map: -ENOMEM"));
+    assert!(is_memlock_error(
+        "map 'stats_map': Operation not permitted (os error 1)"
+    ));
+    assert!(is_memlock_error("Cannot allocate memory (os error 12)"));
+    assert!(!is_memlock_error("Invalid argument (os error 22)"));
+}
@@ -11,7 +11,9 @@ Usage:
 """
 
 import argparse
+import json
 import os
+import random
 import threading
 import traceback
 import re
@@ -20,19 +22,20 @@ import shutil
 import subprocess
 import sys
 import time
+from dataclasses import dataclass
 from datetime import datetime
 from pathlib import Path
 
 sys.path.insert(0, str(Path(__file__).parent.parent.resolve()))
 from pandemonium_common import (
     SCRIPT_DIR, TARGET_DIR, LOG_DIR, ARCHIVE_DIR, BINARY, SOURCE_PATTERNS,
-    get_version, get_git_info,
+    get_version, get_git_info, get_system_header,
     log_info, log_warn, log_error, run_cmd,
     has_root_owned_files, clean_root_files, check_sources_changed, build,
     SCX_OPS, is_scx_active, scx_scheduler_name,
     wait_for_activation, wait_for_deactivation, wait_for_no_scheduler,
     set_cpu_online, restrict_cpus, restore_all_cpus, CpuGuard,
-    get_possible_cpus, get_online_cpus, compute_core_counts,
+    get_possible_cpus, get_online_cpus, compute_core_counts, sanitize_nr_cpus,
     mean_stdev, percentile,
     find_trace_pipe,
 )
@@ -42,6 +45,53 @@ from pandemonium_common import (
 
 DEFAULT_EXTERNALS = ["scx_bpfland"]
 
+# PROBE PROFILE: FORWARDED TO EVERY `pandemonium probe` SPAWN.
+# SET FROM --probe-period-us / --probe-spin-us IN main().
+PROBE_ARGS: list[str] = []
+
+
+def probe_cmd(binary) -> list[str]:
+    """argv for `pandemonium probe` with the selected probe profile."""
+    return [str(binary), "probe", *PROBE_ARGS]
+
+
+# HARNESS-LIFETIME DEATH PIPE. WE HOLD THE WRITE END UNTIL WE EXIT (OR
+# GET KILLED); EVERY PROBE POLLS THE READ END AND EXITS ON HUP, SO A
+# DEAD HARNESS NEVER LEAVES PROBES RUNNING.
+_DEATH_PIPE: tuple[int, int] | None = None
+
+
+def spawn_probe(binary, audio: bool = False) -> subprocess.Popen:
+    """Start `pandemonium probe` bound to this process's lifetime.
+
+    audio=True adds the emulated 48kHz/64-frame audio callback; its
+    "xrun ..." line is read back with parse_audio_output().
+    """
+    global _DEATH_PIPE
+    if _DEATH_PIPE is None:
+        _DEATH_PIPE = os.pipe()
+    death_read = _DEATH_PIPE[0]
+    extra = ["--audio"] if audio else []
+    return subprocess.Popen(
+        [*probe_cmd(binary), *extra, "--death-pipe-fd", str(death_read)],
+        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
+        pass_fds=(death_read,),
+    )
+
+
+def add_probe_args(p: argparse.ArgumentParser) -> None:
+    p.add_argument("--probe-period-us", type=int, default=None,
+                   help="Probe wakeup period in us (default: 10000; "
+                        "1000 = audio callback, 16667 = 60fps frame)")
+    p.add_argument("--probe-spin-us", type=int, default=None,
+                   help="Probe busy-work per period in us (default: 0)")
+    p.add_argument("--probe-threads", type=int, default=None,
+                   help="Concurrent probe threads (default: 1); "
+                        "reports worst-thread p99 separately")
+    p.add_argument("--probe-output", choices=["raw", "hist"], default=None,
+                   help="Probe output format (default: raw; hist cuts "
+                        "pipe volume at short periods)")
+
 
 # DMESG MONITORING
 
@@ -366,6 +416,34 @@ class SchedulerProcess:
         self.cleanup()
 
 
+def preflight_failures(report: dict) -> list[str]:
+    """FAIL entries of a `pandemonium check --json` report, one line each
+    with its remedy. A report that is not one (no entries) is a failure."""
+    entries = report.get("entries")
+    if not isinstance(entries, list):
+        return ["check --json: no entries in the report"]
+    out = []
+    for e in entries:
+        if e.get("status") != "FAIL":
+            continue
+        line = f"{e.get('section')}: {e.get('name')}: {e.get('detail')}"
+        if e.get("remedy"):
+            line += f" -> {e['remedy']}"
+        out.append(line)
+    return out
+
+
+def run_preflight(binary) -> dict:
+    """`sudo pandemonium check --json` (root: the BPF feature probes run).
+    Exits 1 on any FAIL, so the JSON is read regardless of the status."""
+    r = subprocess.run(["sudo", str(binary), "check", "--json"],
+                       capture_output=True, text=True)
+    try:
+        return json.loads(r.stdout)
+    except json.JSONDecodeError:
+        return {"error": (r.stderr or r.stdout).strip()[:400]}
+
+
 def start_scheduler(cmd: list[str], name: str) -> SchedulerProcess | None:
     """Spawn a scheduler subprocess in its own process group.
     Stdout and stderr go to files to avoid pipe buffer overflow.
@@ -489,23 +567,91 @@ def timed_run(cmd: str, clean_cmd: str | None = None) -> float | None:
     return elapsed
 
 
-def parse_probe_output(stdout_text: str) -> dict:
-    """Parse probe stdout (one overshoot_us per line) into latency stats."""
-    values = []
+def parse_probe_samples(stdout_text: str) -> dict[int, list[float]]:
+    """Group probe samples by thread id.
+
+    Single-thread probes print "<overshoot_us>" (thread 0); --threads N
+    prints "<tid> <overshoot_us>"; --output hist prints
+    "hist <tid> <bucket_lo_us> <count>", expanded to <count> samples at
+    the bucket's lower edge. "# probe ..." headers are skipped.
+    """
+    threads: dict[int, list[float]] = {}
     for line in stdout_text.splitlines():
         line = line.strip()
-        if line and line.lstrip("-").isdigit():
-            values.append(float(line))
+        if not line or line.startswith("#"):
+            continue
+        fields = line.split()
+        count = "1"
+        if len(fields) == 1:
+            tid, val = "0", fields[0]
+        elif len(fields) == 2:
+            tid, val = fields
+        elif len(fields) == 4 and fields[0] == "hist":
+            _, tid, val, count = fields
+        else:
+            continue
+        if tid.isdigit() and val.lstrip("-").isdigit() and count.isdigit():
+            threads.setdefault(int(tid), []).extend([float(val)] * int(count))
+    return threads
+
+
+def summarize_probe_samples(threads: dict[int, list[float]]) -> dict:
+    """Pooled stats plus per-thread p99 and the worst thread's p99."""
+    values = [v for vals in threads.values() for v in vals]
     if not values:
-        return {"samples": 0, "median_us": 0, "p99_us": 0, "worst_us": 0}
+        return {"samples": 0, "median_us": 0, "p99_us": 0, "worst_us": 0,
+                "threads": 0, "thread_p99_us": {},
+                "worst_thread": 0, "worst_thread_p99_us": 0}
+    thread_p99 = {tid: int(percentile(vals, 99))
+                  for tid, vals in sorted(threads.items())}
+    worst_thread = max(thread_p99, key=thread_p99.get)
     return {
         "samples": len(values),
         "median_us": int(percentile(values, 50)),
         "p99_us": int(percentile(values, 99)),
         "worst_us": int(max(values)),
+        "threads": len(threads),
+        "thread_p99_us": thread_p99,
+        "worst_thread": worst_thread,
+        "worst_thread_p99_us": thread_p99[worst_thread],
     }
 
 
+def parse_probe_output(stdout_text: str) -> dict:
+    """Parse probe stdout into pooled + per-thread latency stats."""
+    return summarize_probe_samples(parse_probe_samples(stdout_text))
+
+
+def parse_audio_output(stdout_text: str) -> dict:
+    """Sum "xrun cycles=N xruns=M elapsed_us=US" lines from `probe --audio`.
+
+    Counts and elapsed time add, so pooled iterations keep a correct rate.
+    Empty dict when the probe ran without --audio.
+    """
+    totals = {"cycles": 0, "xruns": 0, "elapsed_us": 0}
+    found = False
+    for line in stdout_text.splitlines():
+        fields = line.split()
+        if len(fields) != 4 or fields[0] != "xrun":
+            continue
+        try:
+            vals = dict(f.split("=", 1) for f in fields[1:])
+            parsed = {k: int(vals[k]) for k in totals}
+        except (ValueError, KeyError):
+            continue
+        for k, v in parsed.items():
+            totals[k] += v
+        found = True
+    return totals if found else {}
+
+
+def xruns_per_min(audio: dict) -> float:
+    """Audio-callback deadline misses per minute of probe time."""
+    if not audio.get("elapsed_us"):
+        return 0.0
+    return audio["xruns"] * 60_000_000 / audio["elapsed_us"]
+
+
 def measure_latency(binary: Path, n_cpus: int, iterations: int = 1,
                     duration_secs: int = 15, warmup_secs: int = 3) -> dict:
     """Spawn pinned stress workers on all cores + unpinned probe.
@@ -535,10 +681,7 @@ def measure_latency(binary: Path, n_cpus: int, iterations: int = 1,
 
     # Warmup probe (discard output, let scheduler classify workload)
     log_info(f"Warmup: {warmup_secs}s")
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(warmup_secs)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -547,14 +690,13 @@ def measure_latency(binary: Path, n_cpus: int, iterations: int = 1,
         warmup.kill()
         warmup.wait()
 
-    # Measurement iterations (pool all samples)
-    all_values: list[float] = []
+    # Measurement iterations (pool all samples, keep per-thread streams).
+    # The audio callback rides along: xruns/min is a standard latency column.
+    all_threads: dict[int, list[float]] = {}
+    audio: dict = {}
     for i in range(iterations):
         log_info(f"Latency iteration {i + 1}/{iterations}: {duration_secs}s")
-        probe = subprocess.Popen(
-            [str(binary), "probe"],
-            stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-        )
+        probe = spawn_probe(binary, audio=True)
         time.sleep(duration_secs)
         probe.send_signal(signal.SIGINT)
         try:
@@ -563,10 +705,12 @@ def measure_latency(binary: Path, n_cpus: int, iterations: int = 1,
             probe.kill()
             stdout, _ = probe.communicate()
 
-        for line in stdout.decode(errors="replace").splitlines():
-            line = line.strip()
-            if line and line.lstrip("-").isdigit():
-                all_values.append(float(line))
+        text = stdout.decode(errors="replace")
+        parsed = parse_probe_samples(text)
+        for tid, vals in parsed.items():
+            all_threads.setdefault(tid, []).extend(vals)
+        for k, v in parse_audio_output(text).items():
+            audio[k] = audio.get(k, 0) + v
 
     # Stop stress workers
     for w in workers:
@@ -578,20 +722,24 @@ def measure_latency(binary: Path, n_cpus: int, iterations: int = 1,
             w.kill()
             w.wait()
 
-    if not all_values:
-        result = {"samples": 0, "median_us": 0, "p99_us": 0, "worst_us": 0}
-    else:
-        result = {
-            "samples": len(all_values),
-            "median_us": int(percentile(all_values, 50)),
-            "p99_us": int(percentile(all_values, 99)),
-            "worst_us": int(max(all_values)),
-        }
+    result = summarize_probe_samples(all_threads)
+    if audio:
+        result["audio_cycles"] = audio["cycles"]
+        result["xruns"] = audio["xruns"]
+        result["xruns_per_min"] = round(xruns_per_min(audio), 1)
 
     log_info(f"Latency: {result['samples']} samples, "
              f"median={result['median_us']}us, "
              f"p99={result['p99_us']}us, "
              f"worst={result['worst_us']}us")
+    if result["threads"] > 1:
+        log_info(f"Latency: worst-thread p99="
+                 f"{result['worst_thread_p99_us']}us "
+                 f"(thread {result['worst_thread']} of {result['threads']})")
+    if audio:
+        log_info(f"Latency: audio xruns={result['xruns']} of "
+                 f"{result['audio_cycles']} cycles "
+                 f"({result['xruns_per_min']}/min)")
     return result
 
 
@@ -650,10 +798,7 @@ def measure_burst(binary: Path, n_cpus: int, burst_size: int,
 
     # Warmup (discard output)
     log_info(f"Warmup: {warmup_secs}s")
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(warmup_secs)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -664,10 +809,7 @@ def measure_burst(binary: Path, n_cpus: int, burst_size: int,
 
     # Baseline measurement
     log_info(f"Baseline: {baseline_secs}s")
-    baseline_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    baseline_probe = spawn_probe(binary)
     time.sleep(baseline_secs)
     baseline_probe.send_signal(signal.SIGINT)
     try:
@@ -681,10 +823,7 @@ def measure_burst(binary: Path, n_cpus: int, burst_size: int,
              f"p99={baseline['p99_us']}us")
 
     # Burst measurement: start probe, fire burst, measure during + after
-    burst_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    burst_probe = spawn_probe(binary)
     time.sleep(0.5)
 
     log_info(f"Firing burst: {burst_size} processes")
@@ -725,10 +864,7 @@ def measure_burst(binary: Path, n_cpus: int, burst_size: int,
     # ARE STILL IN THE INTERACTIVE DSQ. interactive_run MAY BE IN AN
     # ARBITRARY STATE. THIS MEASURES THE SETTLING BEHAVIOR.
     log_info("Recovery: 5s")
-    recovery_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    recovery_probe = spawn_probe(binary)
     time.sleep(5)
     recovery_probe.send_signal(signal.SIGINT)
     try:
@@ -827,10 +963,7 @@ def measure_longrun(binary: Path, n_cpus: int,
 
     # Warmup
     log_info(f"Warmup: {warmup_secs}s")
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(warmup_secs)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -840,10 +973,7 @@ def measure_longrun(binary: Path, n_cpus: int,
         warmup.wait()
 
     # Start latency probe + long-runners simultaneously
-    probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    probe = spawn_probe(binary)
     longrunners = spawn_longrunners(longrun_count, longrun_secs)
     log_info(f"Running: {longrun_count} long-runners + probe for {longrun_secs}s")
 
@@ -947,10 +1077,7 @@ def measure_mixed(binary: Path, n_cpus: int,
 
     # Warmup
     log_info(f"Warmup: {warmup_secs}s")
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(warmup_secs)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -960,10 +1087,7 @@ def measure_mixed(binary: Path, n_cpus: int,
         warmup.wait()
 
     # Start long-runners + probe simultaneously
-    probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    probe = spawn_probe(binary)
     longrunners = spawn_longrunners(longrun_count, longrun_secs)
     log_info(f"Running: {longrun_count} long-runners + probe")
 
@@ -1366,8 +1490,8 @@ def measure_launch(binary: Path, n_cpus: int,
 def parse_tick_lines(stdout_text: str) -> list[dict]:
     """Parse d/s: tick lines from scheduler stdout.
 
-    Handles both BPF-only format (ends with [BPF]) and adaptive format
-    (ends with [Light/Mixed/Heavy]).
+    Both loops print the same columns (src/telemetry.rs). A BPF-only line
+    ends with [BPF] and has "-" where only the adaptive loop has a value.
     """
     ticks = []
     for line in stdout_text.splitlines():
@@ -1383,6 +1507,13 @@ def parse_tick_lines(stdout_text: str) -> list[dict]:
         m = re.search(r"idle:\s*(\d+)%", line)
         if m:
             tick["idle_pct"] = int(m.group(1))
+        m = re.search(r"resid:\s*(\d+)%", line)
+        if m:
+            tick["residency_pct"] = int(m.group(1))
+        # CAPACITY RT/DEADLINE TASKS TOOK ("-" ON THE FIRST TICK)
+        m = re.search(r" rt:\s*(\d+)%", line)
+        if m:
+            tick["rt_pct"] = int(m.group(1))
         m = re.search(r"shared:\s*(\d+)", line)
         if m:
             tick["shared"] = int(m.group(1))
@@ -1409,48 +1540,82 @@ def parse_tick_lines(stdout_text: str) -> list[dict]:
         m = re.search(r"lat_kick:\s*(\d+)us", line)
         if m:
             tick["lat_kick_us"] = int(m.group(1))
+        m = re.search(r"qdepth:\s*I=(\d+)\s*B=(\d+)", line)
+        if m:
+            tick["qdepth_interactive"] = int(m.group(1))
+            tick["qdepth_batch"] = int(m.group(2))
+        m = re.search(r"backlog:\s*(\d+)(\[!\])?", line)
+        if m:
+            tick["backlog"] = int(m.group(1))
+            tick["backlog_warn"] = m.group(2) is not None
         m = re.search(r"l2:\s*B=(\d+)%\s*I=(\d+)%\s*L=(\d+)%", line)
         if m:
             tick["l2_pct_batch"] = int(m.group(1))
             tick["l2_pct_interactive"] = int(m.group(2))
             tick["l2_pct_latcrit"] = int(m.group(3))
+        m = re.search(r"retier:\s*B=(\d+)\s*I=(\d+)\s*L=(\d+)", line)
+        if m:
+            tick["retier_batch"] = int(m.group(1))
+            tick["retier_interactive"] = int(m.group(2))
+            tick["retier_latcrit"] = int(m.group(3))
+        m = re.search(r"cls:\s*H=(\d+)\s*L=(\d+)", line)
+        if m:
+            tick["cls_high"] = int(m.group(1))
+            tick["cls_low"] = int(m.group(2))
+        m = re.search(r"slo:\s*(ok|MISS)", line)
+        if m:
+            tick["slo_met"] = m.group(1) == "ok"
+
+        m = re.search(r"hits:\s*(\d+)", line)
+        if m:
+            tick["procdb_hits"] = int(m.group(1))
+        m = re.search(r"slice:\s*(\d+)us", line)
+        if m:
+            tick["slice_us"] = int(m.group(1))
+        m = re.search(r"batch:\s*(\d+)us", line)
+        if m:
+            tick["batch_us"] = int(m.group(1))
+        # CPUFREQ GOVERNOR: "-" WITHOUT cpufreq, a+b WHEN POLICIES DISAGREE
+        m = re.search(r" gov:\s*([^\s\[-]\S*)", line)
+        if m:
+            tick["governor"] = m.group(1)
+
+        # ADAPTIVE-ONLY COLUMNS READ "-" IN A BPF-ONLY LINE: NO MATCH, NO KEY
+        m = re.search(r" p99:\s*(\d+)us \[B:(\d+)\s*I:(\d+)\s*L:(\d+)\]", line)
+        if m:
+            tick["p99_us"] = int(m.group(1))
+            tick["tier_p99_batch"] = int(m.group(2))
+            tick["tier_p99_interactive"] = int(m.group(3))
+            tick["tier_p99_latcrit"] = int(m.group(4))
+        m = re.search(r"procdb:\s*(\d+)/(\d+)", line)
+        if m:
+            tick["procdb_total"] = int(m.group(1))
+            tick["procdb_confident"] = int(m.group(2))
+        m = re.search(r"evict:\s*(\d+)", line)
+        if m:
+            tick["procdb_evict"] = int(m.group(1))
+        m = re.search(r"flush:\s*(\d+)", line)
+        if m:
+            tick["procdb_flush"] = int(m.group(1))
+        m = re.search(r"obs:\s*(\d+)", line)
+        if m:
+            tick["procdb_ingested"] = int(m.group(1))
+        m = re.search(r"sleep:\s*io=(\d+)%", line)
+        if m:
+            tick["io_pct"] = int(m.group(1))
 
         # REGIME + FLAGS: [BPF], [BPF BURST], [BPF LONGRUN],
-        # [BPF BURST LONGRUN], [MIXED], [MIXED BURST], [HEAVY LONGRUN], etc.
+        # [BPF BURST LONGRUN], [MIXED], [MIXED BURST], [HEAVY LONGRUN PINNED],
+        # [MIXED LITE] (--lightweight: wake/p99/lat/obs/sleep READ "-"), etc.
         regime_match = re.search(
-            r'\[(BPF|Light|Mixed|Heavy|LIGHT|MIXED|HEAVY)((?:\s+(?:BURST|LONGRUN))*)\]', line)
+            r'\[(BPF|Light|Mixed|Heavy|LIGHT|MIXED|HEAVY)((?:\s+(?:BURST|LONGRUN|LITE|PINNED|PAUSED))*)\]',
+            line)
         if regime_match:
             tick["regime"] = regime_match.group(1)
             flags = regime_match.group(2).upper()
             tick["burst_active"] = "BURST" in flags
             tick["longrun_active"] = "LONGRUN" in flags
-
-            if tick["regime"] == "BPF":
-                m = re.search(r"procdb:\s*(\d+)\s", line)
-                if m:
-                    tick["procdb_hits"] = int(m.group(1))
-            else:
-                m = re.search(r"p99:\s*(\d+)us", line)
-                if m:
-                    tick["p99_us"] = int(m.group(1))
-                m = re.search(r"p99:.*?\[B:(\d+)\s*I:(\d+)\s*L:(\d+)\]", line)
-                if m:
-                    tick["tier_p99_batch"] = int(m.group(1))
-                    tick["tier_p99_interactive"] = int(m.group(2))
-                    tick["tier_p99_latcrit"] = int(m.group(3))
-                m = re.search(r"procdb:\s*(\d+)/(\d+)", line)
-                if m:
-                    tick["procdb_total"] = int(m.group(1))
-                    tick["procdb_confident"] = int(m.group(2))
-                m = re.search(r"sleep:\s*io=(\d+)%", line)
-                if m:
-                    tick["io_pct"] = int(m.group(1))
-                m = re.search(r"slice:\s*(\d+)us", line)
-                if m:
-                    tick["slice_us"] = int(m.group(1))
-                m = re.search(r"batch:\s*(\d+)us", line)
-                if m:
-                    tick["batch_us"] = int(m.group(1))
+            tick["lightweight"] = "LITE" in flags
         if tick:
             ticks.append(tick)
 
@@ -1538,6 +1703,14 @@ def aggregate_ticks(ticks: list[dict]) -> dict:
         for r in regimes:
             agg["regime_counts"][r] = agg["regime_counts"].get(r, 0) + 1
 
+    # Governor distribution: ticks per governor, to segment a run that
+    # saw power-profiles-daemon (or a user) switch mid-phase
+    governors = [t["governor"] for t in ticks if t.get("governor")]
+    if governors:
+        agg["governor_counts"] = {}
+        for g in governors:
+            agg["governor_counts"][g] = agg["governor_counts"].get(g, 0) + 1
+
     return agg
 
 
@@ -1594,6 +1767,10 @@ def write_prometheus(data: dict, stamp: str) -> Path:
                 gauge("pandemonium_bench_latency_worst_us",
                       "Worst-case wakeup latency",
                       lat["worst_us"], labels)
+                if "xruns_per_min" in lat:
+                    gauge("pandemonium_bench_latency_xruns_per_min",
+                          "Emulated audio callback deadline misses per minute",
+                          lat["xruns_per_min"], labels)
 
             # Throughput metrics
             tp = sched_data.get("throughput", {})
@@ -2015,9 +2192,35 @@ def prom_sys_append_probe(path: Path, lat: dict, label_str: str):
 
 # REPORT
 
+TAINT_MARK = "*"
+TAINT_FOOTNOTE = (f"{TAINT_MARK} TAINTED: THE SCHEDULER CRASHED DURING THIS PHASE "
+                  f"(LATER MEASUREMENTS SKIPPED, EXCLUDED FROM VS EEVDF)")
+
+
+def is_tainted(sched_data: dict) -> bool:
+    """A phase whose scheduler crashed mid-measurement (run_scale_phase)."""
+    return bool(sched_data.get("tainted"))
+
+
+def _row_name(sched_name: str, sched_data: dict) -> str:
+    return sched_name + (TAINT_MARK if is_tainted(sched_data) else "")
+
+
+def tainted_pandemonium(data: dict) -> list[str]:
+    """'<N>C <NAME>' for every tainted PANDEMONIUM phase: fails the run."""
+    return [f"{cores}C {name}"
+            for cores, schedulers in sorted(data.get("results", {}).items(),
+                                            key=lambda kv: int(kv[0]))
+            for name, sched_data in schedulers.items()
+            if "PANDEMONIUM" in name and is_tainted(sched_data)]
+
+
 def format_report(data: dict) -> str:
     """Format benchmark results into a human-readable report."""
-    lines = []
+    # SYSTEM HEADER FIRST (ABSENT IN ARCHIVES FROM OLDER RUNS)
+    lines = list(data.get("system", []))
+    if lines:
+        lines.append("")
     if data.get("deadline_only"):
         mode = "DEADLINE ONLY"
     elif data.get("ipc_only"):
@@ -2037,14 +2240,21 @@ def format_report(data: dict) -> str:
     if not data.get("burst_only"):
         lines.append(f"ITERATIONS:  {data.get('iterations', '?')}")
     lines.append(f"MAX CPUS:    {data.get('max_cpus', '?')}")
+    if data.get("phase_seed") is not None:
+        lines.append(f"PHASE ORDER: SHUFFLED (SEED {data['phase_seed']})")
+    else:
+        lines.append("PHASE ORDER: FIXED")
     lines.append("")
 
     results = data.get("results", {})
     sorted_cores = sorted(results.keys(), key=int)
+    phase_order = data.get("phase_order", {})
 
     for cores_str in sorted_cores:
         schedulers = results[cores_str]
         lines.append(f"[{cores_str} CORES]")
+        if cores_str in phase_order:
+            lines.append(f"RUN ORDER: {' -> '.join(phase_order[cores_str])}")
 
         # Throughput table
         lines.append(f"{'SCHEDULER':<28} {'MEAN':>10} {'STDEV':>10} "
@@ -2054,8 +2264,13 @@ def format_report(data: dict) -> str:
             if "mean_s" not in tp:
                 continue
             delta = tp.get("vs_eevdf_pct")
-            delta_str = f"{delta:+.1f}%" if delta is not None else "(baseline)"
-            lines.append(f"{sched_name:<28} {tp['mean_s']:>9.2f}s "
+            if is_tainted(sched_data):
+                delta_str = "N/A"
+            elif delta is not None:
+                delta_str = f"{delta:+.1f}%"
+            else:
+                delta_str = "(baseline)"
+            lines.append(f"{_row_name(sched_name, sched_data):<28} {tp['mean_s']:>9.2f}s "
                         f"{tp.get('stdev_s', 0):>9.2f}s {delta_str:>12}")
 
         # Latency table
@@ -2064,15 +2279,17 @@ def format_report(data: dict) -> str:
         if has_latency:
             lines.append("")
             lines.append(f"{'SCHEDULER':<28} {'SAMPLES':>8} {'MEDIAN':>10} "
-                        f"{'P99':>10} {'WORST':>10}")
+                        f"{'P99':>10} {'WORST':>10} {'XRUNS/MIN':>10}")
             for sched_name, sched_data in schedulers.items():
                 lat = sched_data.get("latency", {})
                 if lat.get("samples", 0) == 0:
                     continue
+                xr = (f"{lat['xruns_per_min']:.1f}"
+                      if "xruns_per_min" in lat else "--")
                 lines.append(
-                    f"{sched_name:<28} {lat['samples']:>8} "
+                    f"{_row_name(sched_name, sched_data):<28} {lat['samples']:>8} "
                     f"{lat['median_us']:>9}us {lat['p99_us']:>9}us "
-                    f"{lat['worst_us']:>9}us")
+                    f"{lat['worst_us']:>9}us {xr:>10}")
 
         # Burst table
         has_burst = any(
@@ -2101,7 +2318,7 @@ def format_report(data: dict) -> str:
                         if recovery.get("samples", 0) > 0 else "--")
                 samples = (str(burst.get("samples", 0))
                            if burst.get("samples", 0) > 0 else "--")
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{bp99:>10} {brp99:>10} "
                              f"{worst:>10} {rp99:>10} {samples:>8}")
 
@@ -2127,7 +2344,7 @@ def format_report(data: dict) -> str:
                 work_tot = str(lr.get("work_total", 0))
                 work_min = str(lr.get("work_min", 0))
                 work_max = str(lr.get("work_max", 0))
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{lp99:>10} {lworst:>10} "
                              f"{work_tot:>10} {work_min:>10} {work_max:>10}")
 
@@ -2153,7 +2370,7 @@ def format_report(data: dict) -> str:
                 work_tot = str(mx.get("work_total", 0))
                 work_min = str(mx.get("work_min", 0))
                 work_max = str(mx.get("work_max", 0))
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{mp99:>10} {mworst:>10} "
                              f"{work_tot:>10} {work_min:>10} {work_max:>10}")
 
@@ -2176,7 +2393,7 @@ def format_report(data: dict) -> str:
                 ratio = f"{dl.get('miss_ratio', 0):.1%}"
                 jp99 = f"{dl['jitter_p99_us']}us"
                 jworst = f"{dl['jitter_worst_us']}us"
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{missed:>8} {total:>8} "
                              f"{ratio:>8} {jp99:>10} {jworst:>10}")
 
@@ -2198,7 +2415,7 @@ def format_report(data: dict) -> str:
                 median = f"{ipc['rtt_median_us']}us"
                 p99 = f"{ipc['rtt_p99_us']}us"
                 worst = f"{ipc['rtt_worst_us']}us"
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{pairs:>8} {median:>10} "
                              f"{p99:>10} {worst:>10}")
 
@@ -2220,10 +2437,14 @@ def format_report(data: dict) -> str:
                 mean = f"{lnch['launch_mean_us']}us"
                 p99 = f"{lnch['launch_p99_us']}us"
                 worst = f"{lnch['launch_worst_us']}us"
-                lines.append(f"{sched_name:<28} {status:>8} "
+                lines.append(f"{_row_name(sched_name, sched_data):<28} {status:>8} "
                              f"{count:>8} {mean:>10} "
                              f"{p99:>10} {worst:>10}")
 
+        if any(is_tainted(d) for d in schedulers.values()):
+            lines.append("")
+            lines.append(TAINT_FOOTNOTE)
+
         lines.append("")
 
     # Summary matrix: throughput delta vs EEVDF
@@ -2245,9 +2466,11 @@ def format_report(data: dict) -> str:
                 continue
             row = f"{sched:<28}"
             for c in sorted_cores:
-                tp = results.get(c, {}).get(sched, {}).get("throughput", {})
-                delta = tp.get("vs_eevdf_pct")
-                if delta is not None:
+                sd = results.get(c, {}).get(sched, {})
+                delta = sd.get("throughput", {}).get("vs_eevdf_pct")
+                if is_tainted(sd):
+                    row += f" {'N/A':>8}"
+                elif delta is not None:
                     row += f" {delta:>+7.1f}%"
                 else:
                     row += f" {'--':>8}"
@@ -2264,10 +2487,31 @@ def format_report(data: dict) -> str:
         for sched in all_schedulers:
             row = f"{sched:<28}"
             for c in sorted_cores:
-                lat = results.get(c, {}).get(sched, {}).get("latency", {})
+                sd = results.get(c, {}).get(sched, {})
+                lat = sd.get("latency", {})
                 p99 = lat.get("p99_us")
                 if p99 is not None and lat.get("samples", 0) > 0:
-                    row += f" {p99:>8}"
+                    tag = TAINT_MARK if is_tainted(sd) else ""
+                    row += f" {str(p99) + tag:>8}"
+                else:
+                    row += f" {'--':>8}"
+            lines.append(row)
+
+        lines.append("")
+
+        lines.append("AUDIO XRUNS/MIN")
+        header = f"{'SCHEDULER':<28}"
+        for c in sorted_cores:
+            header += f" {c + 'C':>8}"
+        lines.append(header)
+
+        for sched in all_schedulers:
+            row = f"{sched:<28}"
+            for c in sorted_cores:
+                lat = results.get(c, {}).get(sched, {}).get("latency", {})
+                xr = lat.get("xruns_per_min")
+                if xr is not None:
+                    row += f" {xr:>8.1f}"
                 else:
                     row += f" {'--':>8}"
             lines.append(row)
@@ -2534,6 +2778,10 @@ def format_report(data: dict) -> str:
 
             lines.append("")
 
+    if any(is_tainted(d) for c in sorted_cores for d in results[c].values()):
+        lines.append(TAINT_FOOTNOTE)
+        lines.append("")
+
     return "\n".join(lines)
 
 
@@ -2618,10 +2866,7 @@ def measure_burst_starvation(binary: Path, n_cpus: int,
         workers.append(p)
 
     # WARMUP: LET SCHEDULER CLASSIFY WORKLOAD
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(3)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -2632,10 +2877,7 @@ def measure_burst_starvation(binary: Path, n_cpus: int,
 
     # BASELINE PROBE (5S)
     log_info("[burst-starvation] Baseline: 5s")
-    baseline_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    baseline_probe = spawn_probe(binary)
     time.sleep(5)
     baseline_probe.send_signal(signal.SIGINT)
     try:
@@ -2648,10 +2890,7 @@ def measure_burst_starvation(binary: Path, n_cpus: int,
              f"p99={baseline['p99_us']}us")
 
     # START PROBE FOR BURST MEASUREMENT
-    burst_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    burst_probe = spawn_probe(binary)
     time.sleep(0.5)
 
     # DETONATE: FIRE TIMED BURST
@@ -2681,10 +2920,7 @@ def measure_burst_starvation(binary: Path, n_cpus: int,
 
     # RECOVERY PROBE (5S)
     log_info("[burst-starvation] Recovery: 5s")
-    recovery_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    recovery_probe = spawn_probe(binary)
     time.sleep(5)
     recovery_probe.send_signal(signal.SIGINT)
     try:
@@ -2772,10 +3008,7 @@ def measure_work_stealing(binary: Path, n_cpus: int) -> dict:
         )
         sym_workers.append(p)
 
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(2)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -2784,10 +3017,7 @@ def measure_work_stealing(binary: Path, n_cpus: int) -> dict:
         warmup.kill()
         warmup.wait()
 
-    sym_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    sym_probe = spawn_probe(binary)
     time.sleep(10)
     sym_probe.send_signal(signal.SIGINT)
     try:
@@ -2821,10 +3051,7 @@ def measure_work_stealing(binary: Path, n_cpus: int) -> dict:
         )
         asym_workers.append(p)
 
-    warmup = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    warmup = spawn_probe(binary)
     time.sleep(2)
     warmup.send_signal(signal.SIGINT)
     try:
@@ -2833,10 +3060,7 @@ def measure_work_stealing(binary: Path, n_cpus: int) -> dict:
         warmup.kill()
         warmup.wait()
 
-    asym_probe = subprocess.Popen(
-        [str(binary), "probe"],
-        stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-    )
+    asym_probe = spawn_probe(binary)
     time.sleep(10)
     asym_probe.send_signal(signal.SIGINT)
     try:
@@ -3165,25 +3389,192 @@ def cmd_bench_pcpu(args) -> int:
 
 # BENCH-SCALE COMMAND
 
-def entries_for_cores(
-    base_entries: list[tuple[str, list[str] | None]],
-    n: int,
-) -> list[tuple[str, list[str] | None]]:
-    """Adjust scheduler commands for a specific core count.
+@dataclass(frozen=True)
+class ScalePhase:
+    """One scheduler run at each core count. A new phase (e.g. shadow
+    mode) is one more entry in the list built by cmd_bench_scale."""
+    name: str
+    cmd: list[str] | None          # None: EEVDF, no scheduler process
+    settle_secs: float = 5.0
+    nr_cpus_flag: bool = False     # PANDEMONIUM variants get --nr-cpus N
+
+    @property
+    def needs_scx(self) -> bool:
+        """Scheduler phases must keep sched_ext active throughout; EEVDF
+        must start with it inactive."""
+        return self.cmd is not None
+
+    def command(self, n: int) -> list[str] | None:
+        """Scheduler command for n cores. External schedulers see the
+        online CPUs via the kernel."""
+        if self.cmd is None:
+            return None
+        if self.nr_cpus_flag:
+            return self.cmd + ["--nr-cpus", str(n)]
+        return list(self.cmd)
+
+
+# MEASUREMENTS AFTER LATENCY + THROUGHPUT, IN ORDER:
+# (RESULT KEY, ONLY-MODE FLAG ON args, CRASH LABEL, MEASURE(n))
+SCALE_MEASUREMENTS = [
+    ("burst", "burst", "burst",
+     lambda n: measure_burst(BINARY, n, max(8, n * 4))),
+    ("longrun", "longrun", "long-run",
+     lambda n: measure_longrun(BINARY, n, longrun_count=max(4, n // 2))),
+    ("mixed", "mixed", "mixed test",
+     lambda n: measure_mixed(BINARY, n, longrun_count=max(4, n // 2),
+                             burst_size=max(8, n * 4))),
+    ("deadline", "deadline", "deadline test",
+     lambda n: measure_deadline(BINARY, n)),
+    ("ipc", "ipc", "IPC test",
+     lambda n: measure_ipc(BINARY, n)),
+    ("launch", "launch", "launch test",
+     lambda n: measure_launch(BINARY, n)),
+]
+
 
-    PANDEMONIUM variants get --nr-cpus N.
-    External schedulers see the online CPUs via kernel.
-    EEVDF is None (no scheduler process).
-    """
-    adjusted = []
-    for name, cmd in base_entries:
-        if cmd is None:
-            adjusted.append((name, None))
-        elif "PANDEMONIUM" in name:
-            adjusted.append((name, cmd + ["--nr-cpus", str(n)]))
-        else:
-            adjusted.append((name, list(cmd)))
-    return adjusted
+@dataclass
+class PhaseResult:
+    """What one ScalePhase produced at one core count."""
+    result: dict
+    tp_mean: float | None = None   # unrounded, for VS EEVDF
+    crashed: bool = False
+
+
+def _phase_down(phase: ScalePhase, guard: SchedulerProcess | None) -> str | None:
+    """Why a scheduler phase is no longer measuring its scheduler, or None."""
+    if guard is None:
+        return None
+    if guard.proc.poll() is not None:
+        return f"exit {guard.proc.returncode}"
+    if phase.needs_scx and not is_scx_active():
+        return "sched_ext deactivated (kernel ejected it)"
+    return None
+
+
+def _crash_drain(phase: ScalePhase, guard: SchedulerProcess, why: str,
+                 during: str):
+    """Same crash report for every phase: reason, stderr, stdout tail."""
+    log_error(f"{phase.name} CRASHED during {during} ({why})")
+    for line in guard.read_stderr().strip().splitlines()[-20:]:
+        log_error(f"  stderr: {line}")
+    for line in guard.drain_stdout().strip().splitlines()[-10:]:
+        log_error(f"  stdout: {line}")
+
+
+def run_scale_phase(phase: ScalePhase, n: int, args, workload_cmd: str,
+                    clean_cmd: str | None) -> PhaseResult | None:
+    """Start the phase's scheduler, run the selected measurements, stop it.
+    A crash ends the phase: whatever runs after it would measure EEVDF.
+    Returns None if the phase could not start."""
+    log_info(f"Scheduler: {phase.name}")
+
+    sched_result: dict = {
+        "throughput": {},
+        "latency": {},
+        "telemetry": {},
+    }
+    out = PhaseResult(sched_result)
+
+    # Start scheduler (EEVDF = no-op, but nothing else may be attached)
+    guard = None
+    sched_cmd = phase.command(n)
+    if sched_cmd is None:
+        if is_scx_active() and not wait_for_no_scheduler(timeout=15):
+            log_error(f"{phase.name}: sched_ext still active "
+                      f"({scx_scheduler_name()}) -- skipping")
+            print()
+            return None
+    else:
+        guard = start_and_wait(sched_cmd, phase.name,
+                               settle_secs=phase.settle_secs)
+        if guard is None:
+            print()
+            return None
+
+    run_full = not any(getattr(args, flag)
+                       for _, flag, _, _ in SCALE_MEASUREMENTS)
+
+    def crashed(during: str) -> bool:
+        why = _phase_down(phase, guard)
+        if why is None:
+            return False
+        _crash_drain(phase, guard, why, during)
+        out.crashed = True
+        sched_result["tainted"] = True
+        return True
+
+    if run_full:
+        # Latency measurement
+        sched_result["latency"] = measure_latency(BINARY, n,
+                                                  iterations=args.iterations)
+        if crashed("latency"):
+            sched_result["latency"]["survived"] = False
+
+    if run_full and not out.crashed:
+        # Throughput measurement
+        times = []
+        for i in range(args.iterations):
+            log_info(f"Throughput iteration {i + 1}/{args.iterations}")
+            t = timed_run(workload_cmd, clean_cmd)
+            if crashed("throughput"):
+                break
+            if t is None:
+                log_warn(f"Workload failed under {phase.name}")
+                break
+            times.append(t)
+
+        if times and not out.crashed:
+            out.tp_mean, std = mean_stdev(times)
+            sched_result["throughput"] = {
+                "times": [round(t, 2) for t in times],
+                "mean_s": round(out.tp_mean, 2),
+                "stdev_s": round(std, 2),
+            }
+
+    for key, flag, label, measure in SCALE_MEASUREMENTS:
+        if out.crashed:
+            break
+        if not (run_full or getattr(args, flag)):
+            continue
+        result = measure(n)
+        if crashed(label):
+            result["survived"] = False
+        sched_result[key] = result
+
+    # Stop scheduler, capture telemetry
+    stdout = stop_and_wait(guard)
+    if stdout and "PANDEMONIUM" in phase.name:
+        ticks = parse_tick_lines(stdout)
+        knobs = parse_knobs_line(stdout)
+
+        # BURST ACTIVATION VERIFICATION
+        if "burst" in sched_result:
+            burst_ticks = [t for t in ticks if t.get("burst_active")]
+            if burst_ticks:
+                log_info(f"Burst verification: CUSUM activated in "
+                         f"{len(burst_ticks)}/{len(ticks)} ticks")
+            else:
+                log_warn(f"Burst verification: CUSUM NEVER ACTIVATED "
+                         f"(burst test may be ineffective at {n} cores)")
+            sched_result["burst"]["cusum_activated"] = len(burst_ticks) > 0
+            sched_result["burst"]["cusum_ticks"] = len(burst_ticks)
+
+        # LONGRUN ACTIVATION VERIFICATION
+        longrun_ticks = [t for t in ticks if t.get("longrun_active")]
+        if longrun_ticks:
+            log_info(f"Longrun verification: detected in "
+                     f"{len(longrun_ticks)}/{len(ticks)} ticks")
+        sched_result["longrun_ticks"] = len(longrun_ticks)
+
+        sched_result["telemetry"] = {
+            "tick_count": len(ticks),
+            "tick_aggregate": aggregate_ticks(ticks),
+            "knobs": knobs,
+        }
+
+    print()
+    return out
 
 
 def cmd_bench_scale(args) -> int:
@@ -3224,30 +3615,43 @@ def cmd_bench_scale(args) -> int:
     restore_all_cpus(possible)
     time.sleep(0.5)
 
-    # Pre-flight: verify PANDEMONIUM can load BPF and activate
-    log_info("Pre-flight: verifying PANDEMONIUM can activate...")
-    preflight = start_and_wait([str(BINARY)], "PANDEMONIUM")
-    if preflight is None:
+    # Pre-flight: the same structured report as `pandemonium check`, then
+    # one real activation (the verifier is the one check it cannot run)
+    log_info("Pre-flight: pandemonium check --json...")
+    report = run_preflight(BINARY)
+    failures = preflight_failures(report)
+    if failures:
+        log_error("Pre-flight FAILED:")
+        for line in failures:
+            log_error(f"  {line}")
+        if report.get("error"):
+            log_error(f"  {report['error']}")
+        return 1
+    activation = start_and_wait([str(BINARY)], "PANDEMONIUM")
+    if activation is None:
         log_error("Pre-flight FAILED -- PANDEMONIUM cannot activate")
         log_error("Fix the error above before running bench-scale")
         dmesg.save()
         return 1
-    stop_and_wait(preflight)
+    stop_and_wait(activation)
     log_info("Pre-flight PASSED")
     print()
 
-    # Build entry list: EEVDF + PANDEMONIUM (BPF) + PANDEMONIUM (ADAPTIVE) + externals
-    base_entries: list[tuple[str, list[str] | None]] = [
-        ("EEVDF", None),
-        ("PANDEMONIUM (BPF)", [str(BINARY), "--verbose", "--no-adaptive"]),
-        ("PANDEMONIUM (ADAPTIVE)", [str(BINARY), "--verbose"]),
+    # Phase list: EEVDF + PANDEMONIUM (BPF) + PANDEMONIUM (ADAPTIVE) + externals
+    phases: list[ScalePhase] = [
+        ScalePhase("EEVDF", None),
+        ScalePhase("PANDEMONIUM (BPF)",
+                   [str(BINARY), "--verbose", "--no-adaptive"],
+                   nr_cpus_flag=True),
+        ScalePhase("PANDEMONIUM (ADAPTIVE)", [str(BINARY), "--verbose"],
+                   settle_secs=10.0, nr_cpus_flag=True),
     ]
 
     for name in args.schedulers:
         path = find_scheduler(name)
         if path:
             log_info(f"Found: {name} ({path})")
-            base_entries.append((name, [name]))
+            phases.append(ScalePhase(name, [name]))
         else:
             log_warn(f"SKIPPING {name} (not installed)")
 
@@ -3257,20 +3661,41 @@ def cmd_bench_scale(args) -> int:
     if not args.cmd:
         clean_cmd = f"cargo clean --target-dir {TARGET_DIR}"
 
-    # Core counts
-    max_cpus = get_online_cpus()
+    # Core counts: EACH ONE IS A --nr-cpus, VALIDATED BEFORE ANY PHASE RUNS.
+    # THE HARNESS ONLY OFFLINES CPUS, SO THE ONLINE COUNT IS THE CEILING.
+    max_cpus = min(get_online_cpus(), get_possible_cpus())
     if args.core_counts:
-        core_counts = [int(c.strip()) for c in args.core_counts.split(",")]
-        core_counts = [c for c in core_counts if 2 <= c <= max_cpus]
+        core_counts = []
+        for c in args.core_counts.split(","):
+            try:
+                n, warning = sanitize_nr_cpus(int(c.strip()), max_cpus)
+            except ValueError as e:
+                log_error(f"--core-counts: {e}")
+                return 1
+            if warning:
+                log_warn(warning)
+            if n >= 2:
+                core_counts.append(n)
         if max_cpus not in core_counts:
             core_counts.append(max_cpus)
-        core_counts.sort()
+        core_counts = sorted(set(core_counts))
     else:
         core_counts = compute_core_counts(max_cpus)
 
+    # Phase order: fixed, or shuffled per core count from one logged seed
+    rng = None
+    phase_seed = None
+    if args.shuffle_phases or args.phase_seed is not None:
+        phase_seed = (args.phase_seed if args.phase_seed is not None
+                      else random.SystemRandom().randrange(2 ** 32))
+        rng = random.Random(phase_seed)
+
     print()
-    log_info(f"Schedulers: {', '.join(name for name, _ in base_entries)}")
+    log_info(f"Schedulers: {', '.join(p.name for p in phases)}")
     log_info(f"Core counts: {core_counts}")
+    if rng is not None:
+        log_info(f"Phase order: SHUFFLED per core count "
+                 f"(seed {phase_seed}, replay with --phase-seed {phase_seed})")
     if args.deadline:
         log_info("Mode: DEADLINE ONLY (periodic frame jitter)")
     elif args.ipc:
@@ -3298,6 +3723,8 @@ def cmd_bench_scale(args) -> int:
         "git_commit": git["commit"],
         "git_dirty": git["dirty"],
         "timestamp": stamp,
+        # ALL CPUS STILL ONLINE: THE HEADER DESCRIBES THE MACHINE
+        "system": get_system_header(),
         "iterations": args.iterations,
         "burst_only": args.burst,
         "longrun_only": args.longrun,
@@ -3306,11 +3733,11 @@ def cmd_bench_scale(args) -> int:
         "ipc_only": args.ipc,
         "launch_only": args.launch,
         "max_cpus": max_cpus,
+        "phase_seed": phase_seed,
+        "phase_order": {},
         "results": {},
     }
 
-    eevdf_mean = {}  # {cores_str: mean_s} for vs_eevdf calculation
-
     with CpuGuard(max_cpus):
         restore_all_cpus(max_cpus)
         time.sleep(0.5)
@@ -3334,158 +3761,34 @@ def cmd_bench_scale(args) -> int:
             log_info(f"Online: {online} CPUs")
             print()
 
-            entries = entries_for_cores(base_entries, n)
-
-            for sched_name, sched_cmd in entries:
-                log_info(f"Scheduler: {sched_name}")
-
-                sched_result: dict = {
-                    "throughput": {},
-                    "latency": {},
-                    "telemetry": {},
-                }
-
-                # Start scheduler (EEVDF = no-op)
-                guard = None
-                if sched_cmd is not None:
-                    settle = 10.0 if "ADAPTIVE" in sched_name else 5.0
-                    guard = start_and_wait(sched_cmd, sched_name,
-                                          settle_secs=settle)
-                    if guard is None:
-                        print()
-                        continue
+            order = list(phases)
+            if rng is not None:
+                rng.shuffle(order)
+            data["phase_order"][cores_str] = [p.name for p in order]
+            log_info(f"Run order: {' -> '.join(p.name for p in order)}")
+            print()
 
-                any_single = (args.burst or args.longrun or args.mixed
-                              or args.deadline or args.ipc or args.launch)
-                run_full = not any_single
-
-                if run_full:
-                    # Latency measurement
-                    latency = measure_latency(BINARY, n,
-                                              iterations=args.iterations)
-                    sched_result["latency"] = latency
-
-                    # Throughput measurement
-                    times = []
-                    for i in range(args.iterations):
-                        log_info(f"Throughput iteration {i + 1}/{args.iterations}")
-                        t = timed_run(workload_cmd, clean_cmd)
-                        if t is None:
-                            log_warn(f"Workload failed under {sched_name}")
-                            break
-                        times.append(t)
-
-                    if times:
-                        m, std = mean_stdev(times)
-                        tp = {"times": [round(t, 2) for t in times],
-                              "mean_s": round(m, 2),
-                              "stdev_s": round(std, 2)}
-                        if sched_name == "EEVDF":
-                            eevdf_mean[cores_str] = m
-                        elif (cores_str in eevdf_mean
-                              and eevdf_mean[cores_str] > 0):
-                            delta = ((m - eevdf_mean[cores_str])
-                                     / eevdf_mean[cores_str]) * 100.0
-                            tp["vs_eevdf_pct"] = round(delta, 1)
-                        sched_result["throughput"] = tp
-
-                if run_full or args.burst:
-                    # Burst measurement (app launch under full load)
-                    burst_size = n * 4
-                    if burst_size < 8:
-                        burst_size = 8
-                    burst_result = measure_burst(BINARY, n, burst_size)
-                    if guard is not None and guard.proc.poll() is not None:
-                        burst_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during burst "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["burst"] = burst_result
-
-                if run_full or args.longrun:
-                    # Long-running process test
-                    longrun_count = max(4, n // 2)
-                    longrun_result = measure_longrun(BINARY, n,
-                                                     longrun_count=longrun_count)
-                    if guard is not None and guard.proc.poll() is not None:
-                        longrun_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during long-run "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["longrun"] = longrun_result
-
-                if run_full or args.mixed:
-                    # Mixed workload test (burst + longrun combined)
-                    mixed_burst_size = n * 4
-                    if mixed_burst_size < 8:
-                        mixed_burst_size = 8
-                    mixed_result = measure_mixed(BINARY, n,
-                                                 longrun_count=max(4, n // 2),
-                                                 burst_size=mixed_burst_size)
-                    if guard is not None and guard.proc.poll() is not None:
-                        mixed_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during mixed test "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["mixed"] = mixed_result
-
-                if run_full or args.deadline:
-                    # Periodic deadline (frame scheduling jitter)
-                    deadline_result = measure_deadline(BINARY, n)
-                    if guard is not None and guard.proc.poll() is not None:
-                        deadline_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during deadline test "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["deadline"] = deadline_result
-
-                if run_full or args.ipc:
-                    # IPC round-trip (pipe ping-pong)
-                    ipc_result = measure_ipc(BINARY, n)
-                    if guard is not None and guard.proc.poll() is not None:
-                        ipc_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during IPC test "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["ipc"] = ipc_result
-
-                if run_full or args.launch:
-                    # Application launch under load
-                    launch_result = measure_launch(BINARY, n)
-                    if guard is not None and guard.proc.poll() is not None:
-                        launch_result["survived"] = False
-                        log_error(f"{sched_name} CRASHED during launch test "
-                                  f"(exit {guard.proc.returncode})")
-                    sched_result["launch"] = launch_result
-
-                # Stop scheduler, capture telemetry
-                stdout = stop_and_wait(guard)
-                if stdout and "PANDEMONIUM" in sched_name:
-                    ticks = parse_tick_lines(stdout)
-                    knobs = parse_knobs_line(stdout)
-
-                    # BURST ACTIVATION VERIFICATION
-                    if "burst" in sched_result:
-                        burst_ticks = [t for t in ticks if t.get("burst_active")]
-                        if burst_ticks:
-                            log_info(f"Burst verification: CUSUM activated in "
-                                     f"{len(burst_ticks)}/{len(ticks)} ticks")
-                        else:
-                            log_warn(f"Burst verification: CUSUM NEVER ACTIVATED "
-                                     f"(burst test may be ineffective at {n} cores)")
-                        sched_result["burst"]["cusum_activated"] = len(burst_ticks) > 0
-                        sched_result["burst"]["cusum_ticks"] = len(burst_ticks)
-
-                    # LONGRUN ACTIVATION VERIFICATION
-                    longrun_ticks = [t for t in ticks if t.get("longrun_active")]
-                    if longrun_ticks:
-                        log_info(f"Longrun verification: detected in "
-                                 f"{len(longrun_ticks)}/{len(ticks)} ticks")
-                    sched_result["longrun_ticks"] = len(longrun_ticks)
-
-                    sched_result["telemetry"] = {
-                        "tick_count": len(ticks),
-                        "tick_aggregate": aggregate_ticks(ticks),
-                        "knobs": knobs,
-                    }
-
-                data["results"][cores_str][sched_name] = sched_result
-                print()
+            results: dict[str, dict] = {}
+            tp_means: dict[str, float] = {}
+            for phase in order:
+                ran = run_scale_phase(phase, n, args, workload_cmd, clean_cmd)
+                if ran is None:
+                    continue
+                results[phase.name] = ran.result
+                if ran.tp_mean is not None:
+                    tp_means[phase.name] = ran.tp_mean
+
+            # VS EEVDF ONCE EVERY PHASE HAS RUN: SHUFFLED, EEVDF MAY BE LAST
+            base = tp_means.get("EEVDF", 0.0)
+            for name, m in tp_means.items():
+                if name != "EEVDF" and base > 0 and not is_tainted(results[name]):
+                    results[name]["throughput"]["vs_eevdf_pct"] = round(
+                        (m - base) / base * 100.0, 1)
+
+            # REPORT IN PHASE-LIST ORDER WHATEVER THE RUN ORDER WAS
+            data["results"][cores_str] = {
+                p.name: results[p.name] for p in phases if p.name in results
+            }
 
             # Restore CPUs for next round
             if n < max_cpus:
@@ -3538,6 +3841,11 @@ def cmd_bench_scale(args) -> int:
 
     if not data["results"]:
         return 1
+    # CI: A TAINTED PANDEMONIUM PHASE IS A FAILURE EVEN IF THE REPORT WAS WRITTEN
+    tainted = tainted_pandemonium(data)
+    if tainted:
+        log_error(f"TAINTED PANDEMONIUM PHASES: {', '.join(tainted)}")
+        return 1
     return 0
 
 
@@ -3629,10 +3937,7 @@ def cmd_bench_sys(args) -> int:
                 stop_and_wait(guard)
             return 1
         log_info("Starting latency probe (unpinned)")
-        probe_proc = subprocess.Popen(
-            [str(BINARY), "probe"],
-            stdout=subprocess.PIPE, stderr=subprocess.DEVNULL,
-        )
+        probe_proc = spawn_probe(BINARY)
 
     # Create .prom file immediately with header
     version = get_version()
@@ -5661,6 +5966,13 @@ def main() -> int:
                             "test under load")
     bench.add_argument("--trace", action="store_true",
                        help="Enable bpf_printk trace capture during benchmark")
+    bench.add_argument("--shuffle-phases", action="store_true",
+                       help="Randomize scheduler phase order at each core "
+                            "count (seed logged and in the report)")
+    bench.add_argument("--phase-seed", type=int, default=None,
+                       help="Replay a shuffled phase order from its seed "
+                            "(implies --shuffle-phases)")
+    add_probe_args(bench)
 
     trace_bench = sub.add_parser("bench-trace",
                                   help="Crash-detection stress test with trace capture")
@@ -5693,6 +6005,7 @@ def main() -> int:
     sys_bench.add_argument("--compositor", action="append",
                            help="Additional compositor process names "
                                 "(PANDEMONIUM modes only)")
+    add_probe_args(sys_bench)
 
     pcpu_bench = sub.add_parser("bench-pcpu",
                                 help="Per-CPU DSQ visibility stress test (v5.4.8)")
@@ -5701,6 +6014,7 @@ def main() -> int:
     pcpu_bench.add_argument("--core-counts", type=str, default=None,
                             help="Comma-separated core counts "
                                  "(default: auto 2,4,8,...,max)")
+    add_probe_args(pcpu_bench)
 
     cs2_bench = sub.add_parser("bench-cs2",
                                 help="Automated game workload diagnosis")
@@ -5725,6 +6039,15 @@ def main() -> int:
         parser.print_help()
         return 0
 
+    if getattr(args, "probe_period_us", None) is not None:
+        PROBE_ARGS.extend(["--period-us", str(args.probe_period_us)])
+    if getattr(args, "probe_spin_us", None) is not None:
+        PROBE_ARGS.extend(["--spin-us", str(args.probe_spin_us)])
+    if getattr(args, "probe_threads", None) is not None:
+        PROBE_ARGS.extend(["--threads", str(args.probe_threads)])
+    if getattr(args, "probe_output", None) is not None:
+        PROBE_ARGS.extend(["--output", args.probe_output])
+
     if hasattr(args, "schedulers") and isinstance(args.schedulers, str):
         args.schedulers = [s.strip() for s in args.schedulers.split(",")
                            if s.strip()]
@@ -0,0 +1,110 @@
+// PANDEMONIUM PINNED MAP OPEN TESTS
+// BOUNDED RETRY WITH DOUBLING BACKOFF, ERROR CONTEXT ON A MISSING PIN,
+// procdb INIT RETRY CADENCE IN THE MONITOR LOOP
+
+use std::path::Path;
+use std::time::Duration;
+
+use pandemonium::pinmap::{open_pinned_with_retry, retry_with_backoff, TickRetry};
+use pandemonium::procdb::INIT_RETRY_TICKS;
+
+const MS: Duration = Duration::from_millis(1);
+
+#[test]
+fn pin_that_appears_late_is_opened() {
+    // THIRD TRY FINDS IT: TWO WAITS, THE SECOND TWICE THE FIRST
+    let mut sleeps = Vec::new();
+    let mut tries = 0;
+    let got = retry_with_backoff(
+        5,
+        10 * MS,
+        |d| sleeps.push(d),
+        || {
+            tries += 1;
+            if tries < 3 {
+                Err("ENOENT")
+            } else {
+                Ok(tries)
+            }
+        },
+    );
+    assert_eq!(got, Ok(3));
+    assert_eq!(sleeps, [10 * MS, 20 * MS]);
+}
+
+#[test]
+fn attempts_are_bounded_and_the_last_error_returns() {
+    let mut sleeps = Vec::new();
+    let mut tries = 0;
+    let got: Result<(), u32> = retry_with_backoff(
+        4,
+        5 * MS,
+        |d| sleeps.push(d),
+        || {
+            tries += 1;
+            Err(tries)
+        },
+    );
+    assert_eq!(got, Err(4));
+    // NO WAIT AFTER THE LAST TRY
+    assert_eq!(sleeps, [5 * MS, 10 * MS, 20 * MS]);
+
+    // ZERO ATTEMPTS STILL TRIES ONCE, WITHOUT WAITING
+    let mut sleeps = Vec::new();
+    let got: Result<(), &str> = retry_with_backoff(0, MS, |d| sleeps.push(d), || Err("x"));
+    assert_eq!(got, Err("x"));
+    assert!(sleeps.is_empty());
+}
+
+#[test]
+fn missing_pin_error_names_the_path_and_attempts() {
+    let path = Path::new("/nonexistent/pandemonium/task_class_init");
+    let err = open_pinned_with_retry(path, 3, MS).unwrap_err();
+    let shown = format!("{:#}", err);
+    assert!(
+        shown.starts_with(
+            "OPENING PINNED MAP /nonexistent/pandemonium/task_class_init (3 ATTEMPTS): "
+        ),
+        "{}",
+        shown
+    );
+    let err = open_pinned_with_retry(path, 0, MS).unwrap_err();
+    assert!(err.to_string().ends_with("(1 ATTEMPT)"), "{}", err);
+}
+
+#[test]
+fn procdb_init_is_retried_every_10_ticks() {
+    // THE MONITOR LOOP'S SHAPE: STARTUP TRY AT TICK 0, THE PINS APPEAR AT
+    // TICK 35, ONE TRY PER DUE TICK UNTIL ONE SUCCEEDS
+    let mut retry = TickRetry::every(INIT_RETRY_TICKS);
+    assert!(retry.due(0));
+    let mut tried_at = Vec::new();
+    let mut opened_at = None;
+    for tick in 0..100 {
+        if opened_at.is_some() || !retry.due(tick) {
+            continue;
+        }
+        tried_at.push(tick);
+        if tick >= 35 {
+            opened_at = Some(tick);
+        } else {
+            retry.failed(tick);
+        }
+    }
+    assert_eq!(tried_at, [0, 10, 20, 30, 40]);
+    assert_eq!(opened_at, Some(40));
+    assert_eq!(retry.failures(), 4);
+}
+
+#[test]
+fn tick_retry_counts_from_the_failing_tick() {
+    let mut retry = TickRetry::every(3);
+    retry.failed(7);
+    assert!(!retry.due(9));
+    assert!(retry.due(10));
+    // ZERO INTERVAL WOULD RETRY IN THE SAME TICK: AT LEAST ONE
+    let mut retry = TickRetry::every(0);
+    retry.failed(5);
+    assert!(!retry.due(5));
+    assert!(retry.due(6));
+}
@@ -0,0 +1,281 @@
+// PANDEMONIUM PREFLIGHT TESTS
+// REPORT CLASSIFICATION (FAIL FAILS check, ONLY A hard FAIL BLOCKS A START,
+// WARN NEVER FAILS), TEXT + JSON RENDERING, AND EACH ENTRY BUILDER
+
+use pandemonium::bpffeat::{self, Feature, Probe, Version, FEATURES, MIN_CLANG};
+use pandemonium::compat::{Compat, CompatReport, KfuncStatus};
+use pandemonium::conflicts::detect;
+use pandemonium::memlock::{MapKind, MapSpec};
+use pandemonium::preflight::{
+    bpf_entry, config_entry, conflict_entries, kernel_entry, kfunc_entries, memlock_entries,
+    sched_ext_entry, tool_entry, Entry, PreflightReport, Status, SEC_BPF, SEC_SCX, SEC_TOOLS,
+};
+
+fn report(entries: Vec<Entry>) -> PreflightReport {
+    PreflightReport {
+        entries,
+        task_set_kfuncs: true,
+    }
+}
+
+#[test]
+fn classification_rules() {
+    let empty = report(Vec::new());
+    assert_eq!(empty.worst(), Status::Ok);
+    assert!(empty.ok());
+    assert_eq!(empty.blocking_message(), None);
+
+    // WARN: check PASSES, NOTHING BLOCKS
+    let warn = report(vec![
+        Entry::new(SEC_TOOLS, "cargo", Status::Ok, "found"),
+        Entry::new(SEC_BPF, "ringbuf map", Status::Warn, "no (unused)").hard(),
+    ]);
+    assert_eq!(warn.worst(), Status::Warn);
+    assert!(warn.ok());
+    assert!(warn.blocking().is_empty());
+    assert_eq!(warn.warnings().len(), 1);
+
+    // SOFT FAIL: check FAILS, THE SCHEDULER STILL STARTS
+    let soft = report(vec![tool_entry("clang", false)]);
+    assert!(!soft.ok());
+    assert!(soft.blocking().is_empty());
+    assert_eq!(soft.blocking_message(), None);
+
+    // HARD FAIL: BOTH
+    let hard = report(vec![
+        tool_entry("clang", false),
+        sched_ext_entry(None, None),
+    ]);
+    assert!(!hard.ok());
+    assert_eq!(hard.blocking().len(), 1);
+    assert_eq!(hard.blocking()[0].section, SEC_SCX);
+}
+
+#[test]
+fn blocking_message_names_each_requirement_and_remedy() {
+    let r = report(vec![
+        Entry::new(SEC_BPF, "kernel BTF", Status::Fail, "missing")
+            .remedy("boot a kernel with CONFIG_DEBUG_INFO_BTF=y")
+            .hard(),
+        Entry::new(SEC_BPF, "libbpf", Status::Fail, "0.8.0").hard(),
+        Entry::new(SEC_TOOLS, "clang", Status::Fail, "missing"),
+    ]);
+    let msg = r.blocking_message().unwrap();
+    assert!(msg.starts_with("PREFLIGHT FAILED: "));
+    assert!(msg.contains("kernel BTF: missing (boot a kernel with CONFIG_DEBUG_INFO_BTF=y)"));
+    assert!(msg.contains("; libbpf: 0.8.0."));
+    assert!(!msg.contains("clang"));
+    assert!(msg.contains("pandemonium check"));
+}
+
+#[test]
+fn render_groups_sections_and_shows_remedies_only_when_not_ok() {
+    let r = report(vec![
+        tool_entry("cargo", true),
+        tool_entry("clang", false),
+        sched_ext_entry(Some("pandemonium_1.0\n"), None),
+    ]);
+    let lines = r.render_lines();
+    let text: Vec<&str> = lines.iter().map(|(_, l)| l.as_str()).collect();
+    assert_eq!(text[0], "Tools:");
+    assert!(text[1].contains("cargo") && text[1].contains("OK") && text[1].ends_with("found"));
+    assert!(text[2].contains("clang") && text[2].contains("FAIL"));
+    assert!(text[3].trim_start().starts_with("-> install clang"));
+    assert_eq!(text[4], "sched_ext:");
+    assert!(text[5].ends_with("available (pandemonium_1.0 active)"));
+    assert_eq!(lines.last().unwrap().0, Status::Fail);
+    assert_eq!(text.last().unwrap(), &"Some checks failed");
+    // ONE HEADER PER SECTION, REMEDY ONLY UNDER clang
+    assert_eq!(text.iter().filter(|l| l.ends_with(':')).count(), 2);
+    assert_eq!(text.iter().filter(|l| l.contains("->")).count(), 1);
+    // EACH LINE LOGS AT ITS ENTRY'S STATUS
+    assert_eq!(lines[2].0, Status::Fail);
+    assert_eq!(lines[3].0, Status::Fail);
+
+    let ok = report(vec![tool_entry("cargo", true)]).render_lines();
+    assert_eq!(
+        ok.last().unwrap(),
+        &(Status::Ok, "All checks passed".to_string())
+    );
+}
+
+#[test]
+fn json_carries_every_entry_field() {
+    let r = report(vec![tool_entry("cargo", true), sched_ext_entry(None, None)]);
+    let json = r.json().render();
+    assert!(json.starts_with("{\n  \"ok\": false,\n  \"status\": \"FAIL\","));
+    assert!(json.contains("\"section\": \"Tools\""));
+    assert!(json.contains("\"name\": \"cargo\""));
+    assert!(json.contains("\"remedy\": null"));
+    assert!(json.contains("\"hard\": true"));
+    assert!(json.contains("\"detail\": \"not available (sysfs path missing)\""));
+    assert_eq!(json.matches("\"section\"").count(), 2);
+}
+
+#[test]
+fn tool_and_kernel_entries() {
+    assert_eq!(tool_entry("cargo", true).status, Status::Ok);
+    let rustc = tool_entry("rustc", false);
+    assert_eq!(rustc.status, Status::Fail);
+    assert!(!rustc.hard);
+    assert_eq!(
+        rustc.remedy.as_deref(),
+        Some("install Rust: https://rustup.rs")
+    );
+
+    assert_eq!(kernel_entry("6.12.1-arch1-1\n").status, Status::Ok);
+    assert_eq!(kernel_entry("7.0.0").status, Status::Ok);
+    assert_eq!(kernel_entry("6.13-rc1").status, Status::Ok);
+    let old = kernel_entry("6.6.30-lts");
+    assert_eq!(old.status, Status::Fail);
+    assert!(old.detail.starts_with("6.6 is too old"));
+    // A BACKPORTED sched_ext MAY STILL LOAD: ONLY check FAILS ON IT
+    assert!(!old.hard);
+    assert_eq!(kernel_entry("garbage").status, Status::Warn);
+}
+
+#[test]
+fn kfunc_entries_block_only_on_required_symbols() {
+    let kfuncs = CompatReport {
+        kernel: "6.12.0".to_string(),
+        entries: vec![
+            KfuncStatus {
+                name: "scx_bpf_dsq_insert",
+                found_as: Some("scx_bpf_dispatch"),
+                status: Compat::Warn,
+            },
+            KfuncStatus {
+                name: "scx_bpf_create_dsq",
+                found_as: None,
+                status: Compat::Fail,
+            },
+            KfuncStatus {
+                name: "scx_bpf_cpu_node",
+                found_as: None,
+                status: Compat::Warn,
+            },
+        ],
+    };
+    let entries = kfunc_entries(&kfuncs);
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].detail, "legacy scx_bpf_dispatch");
+    assert!(entries[1].blocks_start());
+    assert_eq!(entries[2].detail, "optional, missing");
+    assert!(!entries[2].blocks_start());
+    // NO SETTERS: LEGACY PATH, A WARNING
+    assert_eq!(entries[3].name, "slice/vtime path");
+    assert_eq!(entries[3].status, Status::Warn);
+}
+
+#[test]
+fn bpf_entries_mark_hard_requirements_with_remedies() {
+    let feature = |f: Feature| FEATURES.iter().find(|r| r.feature == f).unwrap();
+
+    let storage = bpf_entry(&bpffeat::feature_row(
+        feature(Feature::TaskStorage),
+        &Probe::Unsupported,
+    ));
+    assert!(storage.blocks_start());
+    assert_eq!(storage.remedy.as_deref(), Some("boot kernel 5.11+"));
+
+    let ringbuf = bpf_entry(&bpffeat::feature_row(
+        feature(Feature::Ringbuf),
+        &Probe::Unsupported,
+    ));
+    assert_eq!(ringbuf.status, Status::Warn);
+    assert!(!ringbuf.blocks_start());
+
+    let skipped = bpf_entry(&bpffeat::feature_row(
+        feature(Feature::StructOps),
+        &Probe::Skipped,
+    ));
+    assert_eq!(skipped.status, Status::Warn);
+    assert!(skipped.remedy.unwrap().starts_with("rerun as root"));
+
+    let clang = bpf_entry(&bpffeat::tool_row(
+        "clang",
+        Some(MIN_CLANG),
+        Some(Version::new(14, 0, 0)),
+        true,
+    ));
+    assert_eq!(clang.status, Status::Fail);
+    assert!(!clang.hard);
+    assert_eq!(clang.detail, "14.0.0 (minimum 18.0.0)");
+
+    assert!(bpf_entry(&bpffeat::btf_row(None)).blocks_start());
+    let ok = bpf_entry(&bpffeat::btf_row(Some(5120)));
+    assert_eq!(ok.status, Status::Ok);
+    assert_eq!(ok.remedy, None);
+}
+
+#[test]
+fn config_and_sched_ext_entries() {
+    assert_eq!(config_entry(None).status, Status::Warn);
+    assert_eq!(
+        config_entry(Some("CONFIG_FOO=y\nCONFIG_SCHED_CLASS_EXT=y\n")).status,
+        Status::Ok
+    );
+    let missing = config_entry(Some("# CONFIG_SCHED_CLASS_EXT is not set\n"));
+    assert_eq!(missing.status, Status::Fail);
+    assert!(!missing.hard);
+
+    assert!(sched_ext_entry(None, Some("docker")).blocks_start());
+    assert_eq!(
+        sched_ext_entry(Some("\n"), None).detail,
+        "available (no scheduler active)"
+    );
+}
+
+#[test]
+fn memlock_entries_warn_only_when_short() {
+    let maps = [
+        MapSpec {
+            name: "stats_map",
+            kind: MapKind::PercpuArray,
+            key_size: 4,
+            value_size: 512,
+            max_entries: 1,
+        },
+        MapSpec {
+            name: "task_ctx_stor",
+            kind: MapKind::TaskStorage,
+            key_size: 4,
+            value_size: 0,
+            max_entries: 0,
+        },
+    ];
+    let unlimited = memlock_entries(&maps, 8, None);
+    assert_eq!(unlimited.len(), 3);
+    assert!(unlimited[1].detail.ends_with("on demand"));
+    assert_eq!(unlimited[2].name, "RLIMIT_MEMLOCK");
+    assert_eq!(unlimited[2].status, Status::Ok);
+
+    let short = memlock_entries(&maps, 8, Some(1024));
+    assert_eq!(short[2].status, Status::Warn);
+    assert!(short[2].remedy.is_some());
+    assert!(!short[2].blocks_start());
+    assert_eq!(
+        memlock_entries(&maps, 8, Some(64 << 20))[2].status,
+        Status::Ok
+    );
+}
+
+#[test]
+fn conflicting_daemons_warn_with_a_remedy() {
+    let none = conflict_entries(&[]);
+    assert_eq!(none.len(), 1);
+    assert_eq!(none[0].status, Status::Ok);
+
+    let found = detect([(512, "ananicy-cpp\n"), (900, "gamemoded\n")]);
+    let entries = conflict_entries(&found);
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.status == Status::Warn));
+    assert!(entries.iter().all(|e| !e.blocks_start()));
+    assert!(entries[0].detail.starts_with("pid 512: renices"));
+    assert!(entries[1]
+        .remedy
+        .as_deref()
+        .unwrap()
+        .contains("--ignore-conflicts"));
+    assert!(report(entries).ok());
+}
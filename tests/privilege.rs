@@ -0,0 +1,179 @@
+// PANDEMONIUM PRIVILEGE ESCALATION TESTS
+// ROOT / $PANDEMONIUM_SUDO / sudo > doas > run0 SELECTION AGAINST FAKE
+// $PATH DIRECTORIES, EXECUTABLE LOOKUP, argv + env(1) WRAPPING
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use pandemonium::privilege::{find_program, Escalation, ESCALATORS, SUDO_ENV};
+
+// A DIRECTORY OF EMPTY FILES; mode 0o755 UNLESS THE NAME ENDS IN ".noexec"
+fn fake_bin(name: &str, programs: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "pandemonium-privilege-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for prog in programs {
+        let (file, mode) = match prog.strip_suffix(".noexec") {
+            Some(p) => (p, 0o644),
+            None => (*prog, 0o755),
+        };
+        let path = dir.join(file);
+        std::fs::write(&path, "").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+    dir
+}
+
+fn path_of(dirs: &[&PathBuf]) -> String {
+    dirs.iter()
+        .map(|d| d.display().to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn tool(words: &[&str]) -> Escalation {
+    Escalation::Tool(words.iter().map(|w| w.to_string()).collect())
+}
+
+fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn root_runs_directly_whatever_is_installed() {
+    let bin = fake_bin("root", &["sudo"]);
+    let path = path_of(&[&bin]);
+    assert_eq!(
+        Escalation::resolve(0, None, Some(&path)),
+        Ok(Escalation::Root)
+    );
+    // EVEN A BROKEN OVERRIDE: ROOT NEVER LOOKS AT IT
+    assert_eq!(
+        Escalation::resolve(0, Some("nope"), None),
+        Ok(Escalation::Root)
+    );
+}
+
+#[test]
+fn sudo_then_doas_then_run0() {
+    assert_eq!(ESCALATORS, ["sudo", "doas", "run0"]);
+    let all = fake_bin("all", &["run0", "doas", "sudo"]);
+    let path = path_of(&[&all]);
+    assert_eq!(
+        Escalation::resolve(1000, None, Some(&path)),
+        Ok(tool(&["sudo"]))
+    );
+
+    let no_sudo = fake_bin("no-sudo", &["run0", "doas"]);
+    let path = path_of(&[&no_sudo]);
+    assert_eq!(
+        Escalation::resolve(1000, None, Some(&path)),
+        Ok(tool(&["doas"]))
+    );
+
+    let systemd = fake_bin("systemd", &["run0"]);
+    let path = path_of(&[&systemd]);
+    assert_eq!(
+        Escalation::resolve(1000, None, Some(&path)),
+        Ok(tool(&["run0"]))
+    );
+}
+
+#[test]
+fn order_is_by_tool_not_by_path_entry() {
+    // doas EARLIER IN $PATH THAN sudo: sudo STILL WINS
+    let first = fake_bin("first", &["doas"]);
+    let second = fake_bin("second", &["sudo"]);
+    let path = path_of(&[&first, &second]);
+    assert_eq!(
+        Escalation::resolve(1000, None, Some(&path)),
+        Ok(tool(&["sudo"]))
+    );
+}
+
+#[test]
+fn non_executable_files_are_skipped() {
+    let bin = fake_bin("noexec", &["sudo.noexec", "doas"]);
+    let path = path_of(&[&bin]);
+    assert_eq!(
+        Escalation::resolve(1000, None, Some(&path)),
+        Ok(tool(&["doas"]))
+    );
+    assert_eq!(find_program("sudo", &path), None);
+    assert_eq!(find_program("doas", &path), Some(bin.join("doas")));
+}
+
+#[test]
+fn override_wins_and_keeps_its_args() {
+    let bin = fake_bin("override", &["sudo", "doas"]);
+    let path = path_of(&[&bin]);
+    assert_eq!(
+        Escalation::resolve(1000, Some("doas -n"), Some(&path)),
+        Ok(tool(&["doas", "-n"]))
+    );
+    // AN ABSOLUTE PATH NEEDS NO $PATH
+    let abs = bin.join("doas").display().to_string();
+    assert_eq!(
+        Escalation::resolve(1000, Some(&abs), None),
+        Ok(tool(&[&abs]))
+    );
+    // EMPTY / BLANK MEANS UNSET
+    assert_eq!(
+        Escalation::resolve(1000, Some("  "), Some(&path)),
+        Ok(tool(&["sudo"]))
+    );
+}
+
+#[test]
+fn missing_override_is_an_error_not_a_fallback() {
+    let bin = fake_bin("bad-override", &["sudo"]);
+    let path = path_of(&[&bin]);
+    let err = Escalation::resolve(1000, Some("pkexec"), Some(&path)).unwrap_err();
+    assert!(err.contains(SUDO_ENV));
+    assert!(err.contains("pkexec"));
+}
+
+#[test]
+fn nothing_found_names_the_way_out() {
+    let empty = fake_bin("empty", &[]);
+    let path = path_of(&[&empty]);
+    for p in [Some(path.as_str()), Some(""), None] {
+        let err = Escalation::resolve(1000, None, p).unwrap_err();
+        assert!(err.contains("sudo, doas, run0"));
+        assert!(err.contains(SUDO_ENV));
+    }
+}
+
+#[test]
+fn wrap_prepends_tool_and_env() {
+    let cmd = argv(&["cargo", "test", "--release"]);
+    assert_eq!(Escalation::Root.wrap(&[], &cmd), cmd);
+    assert_eq!(
+        tool(&["doas", "-n"]).wrap(&[], &cmd),
+        argv(&["doas", "-n", "cargo", "test", "--release"])
+    );
+    let env = [("CARGO_TARGET_DIR", "/tmp/pandemonium-build")];
+    assert_eq!(
+        tool(&["run0"]).wrap(&env, &cmd),
+        argv(&[
+            "run0",
+            "env",
+            "CARGO_TARGET_DIR=/tmp/pandemonium-build",
+            "cargo",
+            "test",
+            "--release"
+        ])
+    );
+    // ROOT USES env(1) TOO: ONE argv SHAPE FOR EVERY CASE
+    assert_eq!(Escalation::Root.wrap(&env, &cmd)[0], "env");
+}
+
+#[test]
+fn describe_for_logs() {
+    assert_eq!(Escalation::Root.describe(), "root");
+    assert_eq!(tool(&["sudo", "-n"]).describe(), "sudo -n");
+}
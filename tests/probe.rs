@@ -0,0 +1,390 @@
+// PANDEMONIUM PROBE OUTPUT TESTS
+// SINGLE/MULTI-THREAD PARSING, HEADER SKIP, NON-FINITE + OVERSIZED hist
+// REJECTION, POOLED + PER-THREAD PERCENTILES, AUDIO CALLBACK
+// DEADLINES + XRUN ACCOUNTING, DEATH-PIPE ORPHAN EXIT,
+// SELF-PROBE SHARED HISTOGRAM + WINDOWS + BPF COMPARISON
+
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use pandemonium::probe::{
+    audio_deadline_ns, hist_bucket, hist_bucket_lo, next_audio_cycle, parse_audio_line,
+    parse_audio_output, parse_probe_output, percentile, summarize_probe, AudioStats, LatencyHist,
+    ProbeWindow, SelfProbeSummary, SharedLatencyHist, HIST_BUCKETS, MAX_PROBE_SAMPLES,
+};
+
+#[test]
+fn single_thread_bare_lines() {
+    let parsed = parse_probe_output("12\n7\n300\n").unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[&0], vec![12.0, 7.0, 300.0]);
+}
+
+#[test]
+fn header_and_garbage_skipped() {
+    let text = "# probe period_us=1000 spin_us=200 threads=1\n\n5\nnot-a-number\n1 2 3\n6\n";
+    let parsed = parse_probe_output(text).unwrap();
+    assert_eq!(parsed[&0], vec![5.0, 6.0]);
+}
+
+#[test]
+fn multi_thread_prefixed() {
+    let text = "0 10\n1 20\n0 11\n2 30\n1 21\n";
+    let parsed = parse_probe_output(text).unwrap();
+    assert_eq!(parsed.len(), 3);
+    assert_eq!(parsed[&0], vec![10.0, 11.0]);
+    assert_eq!(parsed[&1], vec![20.0, 21.0]);
+    assert_eq!(parsed[&2], vec![30.0]);
+}
+
+#[test]
+fn pooled_and_per_thread_summary() {
+    // THREAD 0: 100 FAST SAMPLES. THREAD 1: 99 FAST + 1 BAD TAIL.
+    let mut text = String::new();
+    for _ in 0..100 {
+        text.push_str("0 5\n");
+    }
+    for _ in 0..99 {
+        text.push_str("1 5\n");
+    }
+    text.push_str("1 900\n");
+
+    let s = summarize_probe(&text).unwrap();
+    assert_eq!(s.pooled.samples, 200);
+    assert_eq!(s.pooled.median, 5.0);
+    assert_eq!(s.pooled.worst, 900.0);
+    assert_eq!(s.per_thread.len(), 2);
+    assert_eq!(s.per_thread[0].1.p99, 5.0);
+    assert_eq!(s.per_thread[1].1.p99, 900.0);
+
+    // POOLED P99 HIDES THE TAIL; WORST-THREAD P99 EXPOSES IT
+    assert_eq!(s.pooled.p99, 5.0);
+    assert_eq!(s.worst_thread_p99(), Some((1, 900.0)));
+}
+
+#[test]
+fn empty_output() {
+    let s = summarize_probe("").unwrap();
+    assert_eq!(s.pooled.samples, 0);
+    assert_eq!(s.pooled.p99, 0.0);
+    assert!(s.worst_thread_p99().is_none());
+}
+
+#[test]
+fn non_finite_samples_are_errors() {
+    for text in ["5\nNaN\n", "0 5\n1 inf\n", "hist 0 -inf 3\n"] {
+        let err = parse_probe_output(text).unwrap_err().to_string();
+        assert!(err.contains("NON-FINITE SAMPLE"), "{}", err);
+    }
+    assert!(summarize_probe("7\nNaN\n").is_err());
+}
+
+#[test]
+fn hist_counts_are_capped() {
+    let one = format!("hist 0 4 {}\n", MAX_PROBE_SAMPLES + 1);
+    let err = parse_probe_output(&one).unwrap_err().to_string();
+    assert_eq!(
+        err,
+        format!("PROBE LINE 1: MORE THAN {} SAMPLES", MAX_PROBE_SAMPLES)
+    );
+
+    // THE CAP IS ON THE TOTAL, NOT PER BUCKET; usize::MAX DOESN'T WRAP IT
+    let split = format!("hist 0 4 {}\nhist 1 9 {}\n", MAX_PROBE_SAMPLES, usize::MAX);
+    let err = parse_probe_output(&split).unwrap_err().to_string();
+    assert!(err.starts_with("PROBE LINE 2:"), "{}", err);
+}
+
+#[test]
+fn percentile_clamps() {
+    let v = [1.0, 2.0, 3.0, 4.0];
+    assert_eq!(percentile(&v, 0.0), 1.0);
+    assert_eq!(percentile(&v, 50.0), 3.0);
+    assert_eq!(percentile(&v, 100.0), 4.0);
+    assert_eq!(percentile(&[], 99.0), 0.0);
+}
+
+#[test]
+fn hist_bucket_edges() {
+    assert_eq!(hist_bucket(0), 0);
+    assert_eq!(hist_bucket(99), 99);
+    assert_eq!(hist_bucket(100), 100);
+    assert_eq!(hist_bucket(109), 100);
+    assert_eq!(hist_bucket(110), 101);
+    assert_eq!(hist_bucket(99_999), HIST_BUCKETS - 2);
+    assert_eq!(hist_bucket(100_000), HIST_BUCKETS - 1);
+    assert_eq!(hist_bucket(u64::MAX), HIST_BUCKETS - 1);
+
+    // LOWER EDGE ROUND-TRIPS FOR EVERY BUCKET, EDGES STRICTLY INCREASE
+    for i in 0..HIST_BUCKETS {
+        assert_eq!(hist_bucket(hist_bucket_lo(i)), i);
+        if i > 0 {
+            assert!(hist_bucket_lo(i) > hist_bucket_lo(i - 1));
+        }
+    }
+}
+
+#[test]
+fn hist_accumulates() {
+    let mut h = LatencyHist::default();
+    for us in [3, 3, 3, 150, 155, 2_500, 250_000] {
+        h.record(us);
+    }
+    assert_eq!(h.total(), 7);
+    assert_eq!(
+        h.lines(2),
+        vec![
+            "hist 2 3 3",
+            "hist 2 150 2",
+            "hist 2 2500 1",
+            "hist 2 100000 1",
+        ]
+    );
+}
+
+#[test]
+fn hist_output_percentiles() {
+    // 98 FAST, 2 SLOW ON THREAD 0; 100 MEDIUM ON THREAD 1
+    let text = "# probe period_us=1000 spin_us=0 threads=2\n\
+                hist 0 4 98\n\
+                hist 0 2500 2\n\
+                hist 1 150 100\n";
+    let parsed = parse_probe_output(text).unwrap();
+    assert_eq!(parsed[&0].len(), 100);
+    assert_eq!(parsed[&1].len(), 100);
+
+    let s = summarize_probe(text).unwrap();
+    assert_eq!(s.pooled.samples, 200);
+    assert_eq!(s.per_thread[0].1.median, 4.0);
+    assert_eq!(s.per_thread[0].1.p99, 2500.0);
+    assert_eq!(s.per_thread[1].1.p99, 150.0);
+    assert_eq!(s.worst_thread_p99(), Some((0, 2500.0)));
+}
+
+#[test]
+fn hist_since_and_percentile() {
+    let mut early = LatencyHist::default();
+    early.record(5);
+    let mut late = early.clone();
+    for _ in 0..98 {
+        late.record(40);
+    }
+    late.record(1_500);
+    late.record(1_500);
+    let d = late.since(&early);
+    assert_eq!(d.total(), 100);
+    assert_eq!(d.percentile_us(50), 40);
+    assert_eq!(d.percentile_us(99), 1_500);
+    // EMPTY: 0, NOT THE OVERFLOW EDGE
+    assert_eq!(LatencyHist::default().percentile_us(99), 0);
+}
+
+#[test]
+fn shared_hist_counts_every_thread() {
+    let h = SharedLatencyHist::default();
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let h = &h;
+            s.spawn(move || {
+                for _ in 0..1_000 {
+                    h.record(t * 10);
+                }
+            });
+        }
+    });
+    let snap = h.snapshot();
+    assert_eq!(snap.total(), 4_000);
+    assert_eq!(snap.counts[hist_bucket(30)], 1_000);
+}
+
+#[test]
+fn probe_window_since_last_and_since_start() {
+    let h = SharedLatencyHist::default();
+    // SAMPLES FROM BEFORE THE LOOP STARTED (A PREVIOUS SCHEDULER INSTANCE)
+    h.record(9_000);
+    let mut w = ProbeWindow::new(&h);
+    for _ in 0..100 {
+        h.record(20);
+    }
+    assert_eq!(w.p99_since_last(&h), 20);
+    h.record(700);
+    assert_eq!(w.p99_since_last(&h), 700);
+    // NOTHING NEW: AN EMPTY WINDOW
+    assert_eq!(w.p99_since_last(&h), 0);
+    let all = w.since_start(&h);
+    assert_eq!(all.total(), 101);
+    assert_eq!(all.percentile_us(99), 20);
+}
+
+#[test]
+fn self_probe_summary_compares_bpf() {
+    let mut probe = LatencyHist::default();
+    for _ in 0..99 {
+        probe.record(60);
+    }
+    probe.record(4_200);
+    let s = SelfProbeSummary::new(&probe, 12, 250);
+    assert_eq!((s.samples, s.probe_p50_us, s.probe_p99_us), (100, 60, 60));
+    assert_eq!(s.p99_gap_us(), -190);
+    assert_eq!(
+        s.line(),
+        "[SELF-PROBE] samples=100 probe_p50_us=60 probe_p99_us=60 bpf_wake_avg_us=12 bpf_p99_us=250 p99_gap_us=-190"
+    );
+    let s = SelfProbeSummary::new(&probe, 12, 10);
+    assert_eq!(s.p99_gap_us(), 50);
+}
+
+#[test]
+fn hist_mode_end_to_end() {
+    let child = Command::new(env!("CARGO_BIN_EXE_pandemonium"))
+        .args(["probe", "--period-us", "1000", "--threads", "2"])
+        .args(["--output", "hist", "--header"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("FAILED TO START PROBE");
+    std::thread::sleep(Duration::from_millis(300));
+    unsafe {
+        libc::kill(child.id() as i32, libc::SIGTERM);
+    }
+    let out = child.wait_with_output().unwrap();
+    let text = String::from_utf8_lossy(&out.stdout);
+
+    assert!(text.lines().next().unwrap().starts_with("# probe"));
+    assert!(text.lines().skip(1).all(|l| l.starts_with("hist ")));
+    let s = summarize_probe(&text).unwrap();
+    assert_eq!(s.per_thread.len(), 2);
+    assert!(s.pooled.samples > 10);
+}
+
+// 48KHZ / 64 FRAMES = 1333333.3NS. THE GRID IS EXACT AT EVERY CYCLE:
+// NO PER-PERIOD ROUNDING THAT WOULD DRIFT THE DEADLINES (AND HIDE MISSES)
+#[test]
+fn audio_grid_does_not_drift() {
+    let start = 1_000_000_000;
+    assert_eq!(audio_deadline_ns(start, 0), start);
+    assert_eq!(audio_deadline_ns(start, 1), start + 1_333_333);
+    assert_eq!(audio_deadline_ns(start, 3), start + 4_000_000);
+    // ONE HOUR OF CALLBACKS LANDS EXACTLY ON THE HOUR
+    assert_eq!(
+        audio_deadline_ns(start, 2_700_000),
+        start + 3_600_000_000_000
+    );
+}
+
+#[test]
+fn audio_resync_skips_to_next_grid_point() {
+    let start = 5_000;
+    // ON TIME: STILL BEFORE GRID POINT 1
+    assert_eq!(next_audio_cycle(start, start + 1_000_000), 1);
+    // EXACTLY ON A GRID POINT: THAT DEADLINE HAS PASSED
+    assert_eq!(next_audio_cycle(start, audio_deadline_ns(start, 2)), 3);
+    // STALLED ~10 PERIODS: RESUME AT THE FIRST FUTURE POINT, NOT 10 LATE ONES
+    let done = audio_deadline_ns(start, 10) + 500_000;
+    let next = next_audio_cycle(start, done);
+    assert_eq!(next, 11);
+    assert!(audio_deadline_ns(start, next) > done);
+    assert!(audio_deadline_ns(start, next - 1) <= done);
+    assert_eq!(next_audio_cycle(start, start - 1), 0);
+}
+
+#[test]
+fn audio_line_roundtrip_and_rate() {
+    let stats = AudioStats {
+        cycles: 45_000,
+        xruns: 6,
+        elapsed_us: 60_000_000,
+    };
+    assert_eq!(
+        stats.line(),
+        "xrun cycles=45000 xruns=6 elapsed_us=60000000"
+    );
+    assert_eq!(parse_audio_line(&stats.line()), Some(stats));
+    assert_eq!(stats.xruns_per_min(), 6.0);
+    assert_eq!(AudioStats::default().xruns_per_min(), 0.0);
+    assert_eq!(parse_audio_line("xrun cycles=1 xruns=x elapsed_us=1"), None);
+    assert_eq!(parse_audio_line("hist 0 1 2"), None);
+}
+
+// THE XRUN LINE RIDES IN THE PROBE STREAM: LATENCY PARSING IGNORES IT,
+// ITERATIONS POOL BY SUMMING COUNTS AND TIME
+#[test]
+fn audio_output_pools_and_stays_out_of_latency() {
+    let text = "# probe period_us=10000 spin_us=0 threads=1 audio=1\n\
+                12\n\
+                7\n\
+                xrun cycles=100 xruns=1 elapsed_us=30000000\n\
+                xrun cycles=100 xruns=3 elapsed_us=30000000\n";
+    let s = summarize_probe(text).unwrap();
+    assert_eq!(s.pooled.samples, 2);
+    let audio = parse_audio_output(text).unwrap();
+    assert_eq!(audio.cycles, 200);
+    assert_eq!(audio.xruns, 4);
+    assert_eq!(audio.xruns_per_min(), 4.0);
+    assert_eq!(parse_audio_output("12\n7\n"), None);
+}
+
+#[test]
+fn audio_mode_end_to_end() {
+    let child = Command::new(env!("CARGO_BIN_EXE_pandemonium"))
+        .args(["probe", "--audio", "--output", "hist"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("FAILED TO START PROBE");
+    std::thread::sleep(Duration::from_millis(500));
+    unsafe {
+        libc::kill(child.id() as i32, libc::SIGTERM);
+    }
+    let out = child.wait_with_output().unwrap();
+    let text = String::from_utf8_lossy(&out.stdout);
+
+    let audio = parse_audio_output(&text).expect("NO XRUN LINE");
+    // ~375 GRID POINTS IN 500MS; MISSED ONES ARE SKIPPED, NOT REPLAYED
+    assert!(audio.cycles > 50, "ONLY {} AUDIO CYCLES", audio.cycles);
+    assert!(audio.cycles < 400);
+    assert!(audio.xruns <= audio.cycles);
+    assert!(summarize_probe(&text).unwrap().pooled.samples > 0);
+}
+
+// PARENT DEATH == WRITE END CLOSED. THE PROBE MUST NOTICE AND EXIT.
+#[test]
+fn probe_exits_when_death_pipe_closes() {
+    let mut fds = [0i32; 2];
+    assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), 0) }, 0);
+    let (death_read, death_write) = (fds[0], fds[1]);
+
+    let mut child = unsafe {
+        Command::new(env!("CARGO_BIN_EXE_pandemonium"))
+            .args(["probe", "--death-pipe-fd", &death_read.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .pre_exec(move || {
+                libc::close(death_write);
+                Ok(())
+            })
+            .spawn()
+            .expect("FAILED TO START PROBE")
+    };
+    unsafe {
+        libc::close(death_read);
+    }
+
+    // PROBE IS ALIVE WHILE WE HOLD THE WRITE END
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(child.try_wait().unwrap().is_none(), "PROBE EXITED EARLY");
+
+    unsafe {
+        libc::close(death_write);
+    }
+    let t0 = Instant::now();
+    while t0.elapsed() < Duration::from_secs(1) {
+        if child.try_wait().unwrap().is_some() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    child.kill().ok();
+    child.wait().ok();
+    panic!("PROBE STILL RUNNING 1S AFTER DEATH PIPE CLOSED");
+}
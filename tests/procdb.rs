@@ -1,17 +1,16 @@
 use std::collections::HashMap;
 
 use pandemonium::procdb::{
-    ProcessDb, TaskClassEntry, TaskProfile, MAX_PROFILES, MIN_CONFIDENCE, MIN_OBSERVATIONS,
-    STALE_TICKS,
+    builtin_seeds, comm_key, decode_export, encode_export, flush_dirty, forced_comm, list_rows,
+    parse_force_file, parse_seed_file, retention_score, withhold_flapping, FlapTracker, Force,
+    ProcessDb, Seed, TaskClassEntry, TaskProfile, BUILTIN_SEEDS, FLAP_MIN_PAIRS, FLAP_WINDOW,
+    FLAP_WITHHOLD_PCT, FLUSH_RUNTIME_DELTA_PCT, MAX_PROFILES, MIN_CONFIDENCE, MIN_OBSERVATIONS,
+    PRIORITY_VOTE_WEIGHT, RETAIN_W_CONFIDENCE, SCHED_FIFO, SCHED_RR, SEED_VOTE_WEIGHT, STALE_TICKS,
+    UCLAMP_LAT_CRIT_MIN,
 };
 
 fn offline_db() -> ProcessDb {
-    ProcessDb {
-        observe: None,
-        init: None,
-        profiles: HashMap::new(),
-        tick: 0,
-    }
+    ProcessDb::detached(HashMap::new())
 }
 
 #[test]
@@ -74,7 +73,7 @@ fn profile_confidence_zero_votes() {
 
 #[test]
 fn task_class_entry_layout() {
-    // VERIFY RUST STRUCT MATCHES BPF: 1 + 7 + 8 + 8 + 8 + 8 = 40 BYTES
+    // VERIFY RUST STRUCT MATCHES BPF: 1 + 1 + 2 + 4 + 8 + 8 + 8 + 8 = 40 BYTES
     assert_eq!(std::mem::size_of::<TaskClassEntry>(), 40);
 }
 
@@ -217,7 +216,12 @@ fn save_load_round_trip() {
             wakeup_freq: 5,
             csw_rate: 10,
             observations: 10,
+            priority_observations: 0,
             last_seen_tick: 50,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
         },
     );
     db.profiles.insert(
@@ -229,7 +233,12 @@ fn save_load_round_trip() {
             wakeup_freq: 40,
             csw_rate: 200,
             observations: 8,
+            priority_observations: 0,
             last_seen_tick: 50,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
         },
     );
     db.save(&path).unwrap();
@@ -360,12 +369,7 @@ fn loaded_profiles_age_out() {
 
     // LOAD INTO FRESH DB -- PROFILES GET LAST_SEEN_TICK=0
     let loaded = ProcessDb::load_from_disk(&path).unwrap();
-    let mut db2 = ProcessDb {
-        observe: None,
-        init: None,
-        profiles: loaded,
-        tick: 0,
-    };
+    let mut db2 = ProcessDb::detached(loaded);
 
     // TICK 61 TIMES -- PROFILE SHOULD BE EVICTED
     for _ in 0..=STALE_TICKS {
@@ -505,6 +509,103 @@ fn eviction_deterministic() {
     }
 }
 
+// RETENTION SCORE + CONFIGURABLE LIMITS
+
+fn one_shot(last_seen_tick: u64) -> TaskProfile {
+    TaskProfile {
+        tier_votes: [0, 1, 0],
+        avg_runtime_ns: 100000,
+        observations: 1,
+        last_seen_tick,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn retention_score_terms() {
+    // FRESH ONE-SHOT: RECENCY ONLY PLUS A SLIVER OF OBSERVATIONS
+    let fresh = retention_score(&one_shot(100), 100, STALE_TICKS);
+    assert!(fresh > 1.0 && fresh < 1.2, "GOT {}", fresh);
+
+    // CONFIDENT, SATURATED, JUST SEEN: EVERY TERM MAXED
+    let p = TaskProfile {
+        tier_votes: [0, 0, 80],
+        avg_runtime_ns: 100000,
+        observations: 80,
+        last_seen_tick: 100,
+        ..Default::default()
+    };
+    let max = retention_score(&p, 100, STALE_TICKS);
+    assert!(
+        (max - (RETAIN_W_CONFIDENCE + 2.0)).abs() < 1e-9,
+        "GOT {}",
+        max
+    );
+
+    // RECENCY DECAYS LINEARLY TO ZERO AT stale_ticks
+    let half = retention_score(&p, 130, STALE_TICKS);
+    assert!((max - half - 0.5).abs() < 1e-9, "GOT {}", half);
+    let gone = retention_score(&p, 100 + STALE_TICKS * 2, STALE_TICKS);
+    assert!((max - gone - 1.0).abs() < 1e-9, "GOT {}", gone);
+}
+
+#[test]
+fn confident_profile_outlives_one_shots() {
+    // CAP OF 3: ONE OLD CONFIDENT PROFILE + THREE FRESH ONE-SHOTS
+    let mut db = offline_db();
+    db.limits.max_profiles = 3;
+    db.tick = 1000;
+    let keeper = make_comm(b"firefox");
+    db.profiles.insert(
+        keeper,
+        TaskProfile {
+            tier_votes: [0, 40, 0],
+            avg_runtime_ns: 100000,
+            runtime_dev_ns: 10000,
+            observations: 40,
+            last_seen_tick: 960,
+            ..Default::default()
+        },
+    );
+    for name in [&b"Web Content"[..], b"Isolated Web Co", b"RDD Process"] {
+        db.profiles.insert(make_comm(name), one_shot(1000));
+    }
+    assert_eq!(db.tick(), 1);
+    assert!(db.profiles.contains_key(&keeper));
+    assert_eq!(db.profiles.len(), 3);
+    assert_eq!(db.evictions, 1);
+}
+
+#[test]
+fn configurable_stale_ticks() {
+    let mut db = offline_db();
+    db.limits.stale_ticks = 600;
+    db.tick = 1000;
+    let comm = make_comm(b"slack");
+    db.profiles
+        .insert(comm, confident_profile(1000 - STALE_TICKS - 5));
+    // PAST THE DEFAULT WINDOW, INSIDE THE CONFIGURED ONE
+    assert_eq!(db.tick(), 0);
+    assert!(db.profiles.contains_key(&comm));
+    db.tick = 1000 + 600;
+    assert_eq!(db.tick(), 1);
+    assert!(db.profiles.is_empty());
+}
+
+#[test]
+fn evictions_counted_per_tick_and_cumulative() {
+    let mut db = offline_db();
+    db.limits.max_profiles = 2;
+    db.tick = 100;
+    for i in 0..5u8 {
+        db.profiles.insert(make_comm(&[b'a' + i]), one_shot(100));
+    }
+    assert_eq!(db.tick(), 3);
+    assert_eq!(db.tick(), 0);
+    assert_eq!(db.evictions, 3);
+    assert_eq!(db.profiles.len(), 2);
+}
+
 // BEHAVIORAL CONFIDENCE (V4.0 PHASE 2)
 
 #[test]
@@ -589,7 +690,12 @@ fn richer_observation_roundtrip() {
             wakeup_freq: 45,
             csw_rate: 180,
             observations: 8,
+            priority_observations: 0,
             last_seen_tick: 100,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
         },
     );
     db.save(&path).unwrap();
@@ -604,3 +710,777 @@ fn richer_observation_roundtrip() {
 
     let _ = std::fs::remove_file(&path);
 }
+
+// PRIORITY PRIOR (SCHED_FIFO/RR, uclamp_min)
+
+fn observation(tier: u8, policy: u8, uclamp_min: u32) -> TaskClassEntry {
+    TaskClassEntry {
+        tier,
+        policy,
+        pinned: 0,
+        _pad: 0,
+        uclamp_min,
+        avg_runtime: 200_000,
+        runtime_dev: 180_000, // 90% DEV: BEHAVIORALLY UNSTABLE
+        wakeup_freq: 50,
+        csw_rate: 100,
+    }
+}
+
+#[test]
+fn priority_hint_sources() {
+    assert!(!observation(1, 0, 0).priority_hint());
+    assert!(observation(1, SCHED_FIFO, 0).priority_hint());
+    assert!(observation(1, SCHED_RR, 0).priority_hint());
+    assert!(!observation(1, 0, UCLAMP_LAT_CRIT_MIN - 1).priority_hint());
+    assert!(observation(1, 0, UCLAMP_LAT_CRIT_MIN).priority_hint());
+}
+
+#[test]
+fn priority_vote_is_weighted_lat_critical() {
+    let mut db = offline_db();
+    let comm = make_comm(b"pipewire");
+    // BPF SAW IT AS BATCH, BUT uclamp_min SAYS LATENCY-SENSITIVE
+    db.merge_observation(comm, &observation(0, 0, 1024));
+    db.merge_observation(comm, &observation(0, 0, 0));
+    db.merge_observation(comm, &observation(1, 0, 0));
+    let p = &db.profiles[&comm];
+    assert_eq!(p.tier_votes, [1, 1, PRIORITY_VOTE_WEIGHT]);
+    assert_eq!(p.observations, 3);
+    assert_eq!(p.priority_observations, 1);
+    assert_eq!(p.dominant_tier(), 2);
+    // 4 / (1 + 1 + 4)
+    assert!((p.confidence() - 4.0 / 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn priority_profile_flushes_before_min_observations() {
+    let mut db = offline_db();
+    let comm = make_comm(b"game.exe");
+    db.merge_observation(comm, &observation(1, SCHED_RR, 0));
+    let p = &db.profiles[&comm];
+    assert!(p.observations < MIN_OBSERVATIONS);
+    assert_eq!(p.behavioral_confidence(), 0.0);
+    assert_eq!(p.priority_confidence(), 1.0);
+    assert!(p.predictable());
+    assert_eq!(db.summary(), (1, 1));
+}
+
+#[test]
+fn plain_profile_waits_for_min_observations() {
+    let mut db = offline_db();
+    let comm = make_comm(b"cc1");
+    db.merge_observation(comm, &observation(2, 0, 0));
+    let p = &db.profiles[&comm];
+    assert_eq!(p.tier_votes, [0, 0, 1]);
+    assert_eq!(p.priority_confidence(), 0.0);
+    assert!(!p.predictable());
+}
+
+#[test]
+fn priority_fast_path_needs_lat_critical_majority() {
+    // ONE HINT OUTVOTED BY BATCH BEHAVIOR: NO FAST PATH
+    let p = TaskProfile {
+        tier_votes: [PRIORITY_VOTE_WEIGHT + 3, 0, PRIORITY_VOTE_WEIGHT],
+        observations: 2,
+        priority_observations: 1,
+        ..Default::default()
+    };
+    assert_eq!(p.dominant_tier(), 0);
+    assert_eq!(p.priority_confidence(), 0.0);
+    assert!(!p.predictable());
+
+    // LAT_CRITICAL MAJORITY BELOW MIN_CONFIDENCE: NO FAST PATH EITHER
+    let p = TaskProfile {
+        tier_votes: [3, 0, PRIORITY_VOTE_WEIGHT],
+        observations: 2,
+        priority_observations: 1,
+        ..Default::default()
+    };
+    assert!(p.priority_confidence() < MIN_CONFIDENCE);
+    assert!(!p.predictable());
+}
+
+// EXPORT / IMPORT TESTS
+
+fn exported_db() -> ProcessDb {
+    let mut db = offline_db();
+    db.profiles.insert(
+        make_comm(b"rustc"),
+        TaskProfile {
+            tier_votes: [9, 1, 0],
+            avg_runtime_ns: 4_000_000,
+            runtime_dev_ns: 800_000,
+            wakeup_freq: 3,
+            csw_rate: 7,
+            observations: 10,
+            priority_observations: 0,
+            last_seen_tick: 40,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
+        },
+    );
+    db.profiles.insert(
+        make_comm(b"pipewire"),
+        TaskProfile {
+            tier_votes: [0, 1, 2 * PRIORITY_VOTE_WEIGHT],
+            avg_runtime_ns: 30_000,
+            runtime_dev_ns: 3_000,
+            wakeup_freq: 500,
+            csw_rate: 900,
+            observations: 3,
+            priority_observations: 2,
+            last_seen_tick: 40,
+            seeded: false,
+            flushed: None,
+            flap: FlapTracker::default(),
+            forced: None,
+        },
+    );
+    db
+}
+
+#[test]
+fn export_round_trip_keeps_full_votes() {
+    let db = exported_db();
+    let bytes = encode_export(&db.profiles);
+    assert_eq!(bytes, encode_export(&db.profiles)); // DETERMINISTIC
+
+    let decoded = decode_export(&bytes).unwrap();
+    assert_eq!(decoded.len(), 2);
+    let rustc = &decoded[&make_comm(b"rustc")];
+    assert_eq!(rustc.tier_votes, [9, 1, 0]); // NOT COLLAPSED LIKE save()
+    assert_eq!(rustc.avg_runtime_ns, 4_000_000);
+    assert_eq!(rustc.runtime_dev_ns, 800_000);
+    assert_eq!(rustc.wakeup_freq, 3);
+    assert_eq!(rustc.csw_rate, 7);
+    assert_eq!(rustc.observations, 10);
+    assert_eq!(rustc.last_seen_tick, 0);
+    let pw = &decoded[&make_comm(b"pipewire")];
+    assert_eq!(pw.priority_observations, 2);
+    assert!(pw.predictable());
+
+    // THROUGH A FILE, INTO AN EMPTY DATABASE
+    let path = tmp_path("export_round_trip.pdex");
+    assert_eq!(db.export_to(&path).unwrap(), 2);
+    let mut other = offline_db();
+    other.tick = 100;
+    assert_eq!(other.import_from(&path, false).unwrap(), (2, 0));
+    assert_eq!(other.profiles[&make_comm(b"rustc")].last_seen_tick, 100);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn import_merges_votes_and_ewma_runtimes() {
+    let mut db = offline_db();
+    db.tick = 7;
+    db.profiles.insert(
+        make_comm(b"rustc"),
+        TaskProfile {
+            tier_votes: [3, 0, 0],
+            avg_runtime_ns: 2_000_000,
+            runtime_dev_ns: 400_000,
+            observations: 3,
+            ..Default::default()
+        },
+    );
+    let (added, merged) = db.import(exported_db().profiles, false);
+    assert_eq!((added, merged), (1, 1));
+
+    let rustc = &db.profiles[&make_comm(b"rustc")];
+    assert_eq!(rustc.tier_votes, [12, 1, 0]);
+    assert_eq!(rustc.observations, 13);
+    // 7/8 LOCAL + 1/8 IMPORTED
+    assert_eq!(rustc.avg_runtime_ns, (2_000_000 * 7 + 4_000_000) / 8);
+    assert_eq!(rustc.runtime_dev_ns, (400_000 * 7 + 800_000) / 8);
+    assert_eq!(rustc.last_seen_tick, 7);
+
+    // NO LOCAL OBSERVATIONS: IMPORTED RUNTIMES TAKEN AS-IS
+    let mut fresh = TaskProfile::default();
+    fresh.merge_from(&exported_db().profiles[&make_comm(b"rustc")]);
+    assert_eq!(fresh.avg_runtime_ns, 4_000_000);
+}
+
+#[test]
+fn import_replace_drops_local_profiles() {
+    let mut db = offline_db();
+    db.profiles
+        .insert(make_comm(b"local-only"), confident_profile(0));
+    db.profiles
+        .insert(make_comm(b"rustc"), confident_profile(0));
+    let (added, merged) = db.import(exported_db().profiles, true);
+    assert_eq!((added, merged), (2, 0));
+    assert_eq!(db.profiles.len(), 2);
+    assert!(!db.profiles.contains_key(&make_comm(b"local-only")));
+    assert_eq!(db.profiles[&make_comm(b"rustc")].tier_votes, [9, 1, 0]);
+}
+
+#[test]
+fn import_rejects_corrupted_exports() {
+    let good = encode_export(&exported_db().profiles);
+    assert!(decode_export(&good).is_ok());
+
+    // ONE FLIPPED BIT IN A RUNTIME: CHECKSUM MISMATCH
+    let mut flipped = good.clone();
+    flipped[12 + 40] ^= 0x01;
+    let err = decode_export(&flipped).unwrap_err().to_string();
+    assert!(err.contains("CHECKSUM"), "{}", err);
+
+    // TRUNCATED, BAD MAGIC, FUTURE VERSION, CACHE FILE INSTEAD OF EXPORT
+    assert!(decode_export(&good[..good.len() - 1]).is_err());
+    assert!(decode_export(&good[..8]).is_err());
+    let mut magic = good.clone();
+    magic[0] = b'X';
+    assert!(decode_export(&magic).is_err());
+    let mut version = good.clone();
+    version[4] = 99;
+    assert!(decode_export(&version)
+        .unwrap_err()
+        .to_string()
+        .contains("VERSION"));
+    let cache = tmp_path("not_an_export.bin");
+    exported_db().save(&cache).unwrap();
+    assert!(decode_export(&std::fs::read(&cache).unwrap()).is_err());
+    let _ = std::fs::remove_file(&cache);
+
+    // A REJECTED FILE LEAVES THE DATABASE UNTOUCHED
+    let path = tmp_path("corrupt_import.pdex");
+    std::fs::write(&path, &flipped).unwrap();
+    let mut db = offline_db();
+    db.profiles
+        .insert(make_comm(b"rustc"), confident_profile(0));
+    assert!(db.import_from(&path, true).is_err());
+    assert_eq!(db.profiles.len(), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+// SEED TESTS
+
+#[test]
+fn builtin_seeds_predict_before_any_observation() {
+    let seeds = builtin_seeds();
+    assert_eq!(seeds.len(), BUILTIN_SEEDS.len());
+    let mut db = offline_db();
+    assert_eq!(db.seed(&seeds), seeds.len());
+
+    let pw = &db.profiles[&comm_key("pipewire")];
+    assert!(pw.seed_only());
+    assert_eq!(pw.tier_votes, [0, 0, SEED_VOTE_WEIGHT]);
+    assert!(pw.predictable());
+    assert_eq!(db.profiles[&comm_key("rustc")].dominant_tier(), 0);
+
+    // NOT COUNTED AS LEARNED: NEITHER SAVED NOR EXPORTED
+    let path = tmp_path("seed_only_save.bin");
+    db.save(&path).unwrap();
+    assert!(ProcessDb::load_from_disk(&path).unwrap().is_empty());
+    let _ = std::fs::remove_file(&path);
+    assert!(decode_export(&encode_export(&db.profiles))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn seed_never_overrides_known_profile() {
+    let mut db = offline_db();
+    db.profiles.insert(comm_key("rustc"), confident_profile(0));
+    db.profiles.get_mut(&comm_key("rustc")).unwrap().tier_votes = [0, 5, 0];
+    let seeds = builtin_seeds();
+    assert_eq!(db.seed(&seeds), seeds.len() - 1);
+    let rustc = &db.profiles[&comm_key("rustc")];
+    assert!(!rustc.seeded);
+    assert_eq!(rustc.tier_votes, [0, 5, 0]);
+}
+
+#[test]
+fn contrary_observations_override_seed() {
+    let mut db = offline_db();
+    db.seed(&builtin_seeds());
+    let comm = comm_key("ffmpeg"); // SEEDED BATCH
+    let interactive = TaskClassEntry {
+        runtime_dev: 0, // STABLE: THE BEHAVIORAL PATH CAN REACH MIN_CONFIDENCE
+        ..observation(1, 0, 0)
+    };
+
+    // FIRST REAL OBSERVATION REPLACES THE SEEDED RUNTIME, SEED TIER HOLDS
+    db.merge_observation(comm, &interactive);
+    let p = &db.profiles[&comm];
+    assert_eq!(p.avg_runtime_ns, 200_000);
+    assert_eq!(p.dominant_tier(), 0);
+    assert!(p.predictable());
+
+    // SECOND: 2 v 2, NO PREDICTION
+    db.merge_observation(comm, &interactive);
+    assert!(!db.profiles[&comm].predictable());
+
+    // THIRD: OBSERVED TIER WINS AND THE BEHAVIORAL PATH TAKES OVER
+    db.merge_observation(comm, &interactive);
+    let p = &db.profiles[&comm];
+    assert_eq!(p.dominant_tier(), 1);
+    assert_eq!(p.seed_confidence(), 0.0);
+    assert!(p.predictable());
+}
+
+#[test]
+fn seed_only_profiles_skip_stale_eviction() {
+    let mut db = offline_db();
+    db.limits.stale_ticks = 5;
+    db.seed(&builtin_seeds());
+    db.profiles
+        .insert(make_comm(b"learned"), confident_profile(0));
+    for _ in 0..10 {
+        db.tick();
+    }
+    assert!(db.profiles.contains_key(&comm_key("pipewire")));
+    assert!(!db.profiles.contains_key(&make_comm(b"learned")));
+
+    // UNDER CAP PRESSURE THEY GO FIRST
+    db.limits.max_profiles = 1;
+    db.profiles
+        .insert(make_comm(b"learned"), confident_profile(db.tick));
+    db.tick();
+    assert_eq!(db.profiles.len(), 1);
+    assert!(db.profiles.contains_key(&make_comm(b"learned")));
+}
+
+#[test]
+fn seed_file_parsing() {
+    let seeds = parse_seed_file(
+        "# comm  tier  avg_runtime_us\n\
+         \n\
+         my-daw        LAT_CRITICAL  40   # AUDIO ENGINE\n\
+         blender       batch         20000\n",
+    )
+    .unwrap();
+    assert_eq!(
+        seeds,
+        vec![
+            Seed {
+                comm: comm_key("my-daw"),
+                tier: 2,
+                avg_runtime_ns: 40_000,
+            },
+            Seed {
+                comm: comm_key("blender"),
+                tier: 0,
+                avg_runtime_ns: 20_000_000,
+            },
+        ]
+    );
+    assert!(parse_seed_file("").unwrap().is_empty());
+
+    for bad in [
+        "pipewire lat_critical",
+        "pipewire realtime 50",
+        "pipewire lat_critical fast",
+        "a-very-long-process-name batch 10",
+    ] {
+        let err = parse_seed_file(bad).unwrap_err().to_string();
+        assert!(err.starts_with("SEED LINE 1"), "{}: {}", bad, err);
+    }
+}
+
+// FLUSH TESTS
+
+#[test]
+fn stable_profile_is_written_once() {
+    let mut db = offline_db();
+    db.profiles.insert(make_comm(b"kwin"), confident_profile(0));
+    let mut writes = 0;
+    for _ in 0..10 {
+        let written = flush_dirty(&mut db.profiles, |_, _| {
+            writes += 1;
+            true
+        });
+        // SAME EWMA INPUT EVERY TICK: NOTHING MOVES
+        let p = db.profiles.get_mut(&make_comm(b"kwin")).unwrap();
+        p.tier_votes[0] += 1;
+        p.observations += 1;
+        assert!(written.len() <= 1);
+    }
+    assert_eq!(writes, 1);
+    assert_eq!(db.profiles[&make_comm(b"kwin")].flushed, Some((0, 100_000)));
+}
+
+#[test]
+fn runtime_drift_and_retier_mark_dirty() {
+    let mut p = confident_profile(0);
+    assert!(p.dirty()); // NEVER FLUSHED
+    p.flushed = Some((0, 100_000));
+    assert!(!p.dirty());
+
+    // WITHIN FLUSH_RUNTIME_DELTA_PCT OF THE FLUSHED VALUE, EITHER WAY
+    p.avg_runtime_ns = 100_000 + 100_000 * FLUSH_RUNTIME_DELTA_PCT / 100;
+    assert!(!p.dirty());
+    p.avg_runtime_ns = 100_000 - 100_000 * FLUSH_RUNTIME_DELTA_PCT / 100;
+    assert!(!p.dirty());
+    p.avg_runtime_ns = 111_000;
+    assert!(p.dirty());
+    p.avg_runtime_ns = 89_000;
+    assert!(p.dirty());
+
+    // RE-TIERED AT THE SAME RUNTIME
+    p.avg_runtime_ns = 100_000;
+    p.tier_votes = [0, 5, 0];
+    assert!(p.dirty());
+
+    // NOT PREDICTABLE: NOTHING TO WRITE
+    p.tier_votes = [2, 2, 1];
+    assert!(!p.dirty());
+}
+
+#[test]
+fn failed_write_retries_next_flush() {
+    let mut db = offline_db();
+    db.profiles
+        .insert(make_comm(b"rustc"), confident_profile(0));
+    assert!(flush_dirty(&mut db.profiles, |_, _| false).is_empty());
+    assert_eq!(db.profiles[&make_comm(b"rustc")].flushed, None);
+
+    let written = flush_dirty(&mut db.profiles, |comm, entry| {
+        assert_eq!(comm, &make_comm(b"rustc"));
+        assert_eq!(entry.tier, 0);
+        assert_eq!(entry.avg_runtime, 100_000);
+        true
+    });
+    assert_eq!(written, vec![(make_comm(b"rustc"), 0)]);
+    assert!(flush_dirty(&mut db.profiles, |_, _| true).is_empty());
+}
+
+// FLAP TESTS
+
+#[test]
+fn flap_tracker_counts_disagreeing_pairs() {
+    let mut f = FlapTracker::default();
+    // THE FIRST OBSERVATION HAS NOTHING TO DISAGREE WITH
+    assert!(!f.observe(0));
+    assert_eq!((f.flaps, f.pairs), (0, 0));
+    assert!(!f.observe(0));
+    assert!(f.observe(1));
+    assert_eq!((f.flaps, f.pairs), (1, 2));
+
+    // NO VERDICT BEFORE FLAP_MIN_PAIRS, HOWEVER BAD IT LOOKS
+    let mut f = FlapTracker::default();
+    f.observe(0);
+    for i in 1..FLAP_MIN_PAIRS {
+        f.observe((i % 2) as u8);
+    }
+    assert_eq!(f.flaps, FLAP_MIN_PAIRS - 1);
+    assert_eq!(f.rate_pct(), 0);
+    assert!(!f.flapping());
+    f.observe(0);
+    assert_eq!(f.rate_pct(), 100);
+    assert!(f.flapping());
+}
+
+#[test]
+fn flap_window_halves_old_history() {
+    let mut f = FlapTracker::default();
+    f.observe(0);
+    for i in 1..=FLAP_WINDOW {
+        f.observe((i % 2) as u8);
+    }
+    assert_eq!((f.flaps, f.pairs), (FLAP_WINDOW, FLAP_WINDOW));
+    assert!(f.observe(1));
+    assert_eq!(
+        (f.flaps, f.pairs),
+        (FLAP_WINDOW / 2 + 1, FLAP_WINDOW / 2 + 1)
+    );
+    assert!(!f.observe(1));
+    assert_eq!(
+        (f.flaps, f.pairs),
+        (FLAP_WINDOW / 2 + 1, FLAP_WINDOW / 2 + 2)
+    );
+}
+
+#[test]
+fn flap_hysteresis() {
+    // 3 OF 6 PAIRS DISAGREE: EXACTLY FLAP_WITHHOLD_PCT
+    let mut f = FlapTracker {
+        flaps: 3,
+        pairs: 6,
+        ..Default::default()
+    };
+    assert_eq!(f.rate_pct(), FLAP_WITHHOLD_PCT);
+    assert!(f.flapping());
+    // BETWEEN HALF AND THE THRESHOLD: ONLY A WITHHELD PROFILE STAYS OUT
+    f.flaps = 2;
+    assert!(!f.flapping());
+    f.withheld = true;
+    assert!(f.flapping());
+    f.flaps = 1;
+    assert!(!f.flapping());
+}
+
+#[test]
+fn merged_observations_feed_the_flap_tracker() {
+    let mut db = offline_db();
+    let comm = make_comm(b"flappy");
+    db.merge_observation(comm, &observation(0, 0, 0));
+    db.merge_observation(comm, &observation(1, 0, 0));
+    db.merge_observation(comm, &observation(1, 0, 0));
+    // A PRIORITY HINT VOTES LAT_CRITICAL, SO IT FLAPS FROM INTERACTIVE
+    db.merge_observation(comm, &observation(1, SCHED_FIFO, 0));
+    let f = db.profiles[&comm].flap;
+    assert_eq!((f.flaps, f.pairs), (2, 3));
+    assert_eq!(f.last_tier, Some(2));
+}
+
+fn steady(tier: u8) -> TaskClassEntry {
+    TaskClassEntry {
+        runtime_dev: 10_000,
+        ..observation(tier, 0, 0)
+    }
+}
+
+#[test]
+fn flapping_prediction_is_withheld_until_it_settles() {
+    let mut db = offline_db();
+    let comm = make_comm(b"flappy");
+    db.profiles.insert(
+        comm,
+        TaskProfile {
+            tier_votes: [20, 0, 0],
+            avg_runtime_ns: 200_000,
+            runtime_dev_ns: 10_000,
+            observations: 20,
+            flushed: Some((0, 200_000)),
+            ..Default::default()
+        },
+    );
+
+    // BATCH <-> INTERACTIVE: 7 PAIRS, ALL DISAGREE. THE VOTES STILL
+    // PREDICT BATCH CONFIDENTLY; ONLY THE FLAP RATE CATCHES IT.
+    for tier in [1, 0, 1, 0, 1, 0, 1, 0] {
+        db.merge_observation(comm, &steady(tier));
+    }
+    assert!(db.profiles[&comm].predictable());
+    assert!(!db.profiles[&comm].dirty());
+
+    let mut removed = Vec::new();
+    let withheld = withhold_flapping(&mut db.profiles, |c| {
+        removed.push(*c);
+        true
+    });
+    assert_eq!(withheld, vec![(comm, 100)]);
+    assert_eq!(removed, vec![comm]);
+    assert!(db.profiles[&comm].flap.withheld);
+    assert_eq!(db.profiles[&comm].flushed, None);
+    assert!(!db.profiles[&comm].dirty());
+    // ALREADY WITHHELD: NOT REPORTED AGAIN, NOTHING WRITTEN
+    assert!(withhold_flapping(&mut db.profiles, |_| true).is_empty());
+    assert!(flush_dirty(&mut db.profiles, |_, _| true).is_empty());
+
+    // STEADY BATCH: 7 FLAPS IN 28 PAIRS IS STILL 25%, 29 PAIRS RELEASES
+    for _ in 0..21 {
+        db.merge_observation(comm, &steady(0));
+    }
+    withhold_flapping(&mut db.profiles, |_| true);
+    assert!(db.profiles[&comm].flap.withheld);
+    db.merge_observation(comm, &steady(0));
+    assert!(withhold_flapping(&mut db.profiles, |_| true).is_empty());
+    assert!(!db.profiles[&comm].flap.withheld);
+    let written = flush_dirty(&mut db.profiles, |_, _| true);
+    assert_eq!(written, vec![(comm, 0)]);
+}
+
+#[test]
+fn failed_withhold_retries_next_flush() {
+    let mut db = offline_db();
+    let comm = make_comm(b"flappy");
+    db.profiles.insert(
+        comm,
+        TaskProfile {
+            flushed: Some((1, 100_000)),
+            flap: FlapTracker {
+                flaps: 6,
+                pairs: 6,
+                ..Default::default()
+            },
+            ..confident_profile(0)
+        },
+    );
+    assert!(withhold_flapping(&mut db.profiles, |_| false).is_empty());
+    assert!(!db.profiles[&comm].flap.withheld);
+    assert_eq!(db.profiles[&comm].flushed, Some((1, 100_000)));
+    assert_eq!(
+        withhold_flapping(&mut db.profiles, |_| true),
+        vec![(comm, 100)]
+    );
+
+    // NEVER FLUSHED: NOTHING TO REMOVE, WITHHELD ALL THE SAME
+    let other = make_comm(b"fresh");
+    db.profiles.insert(
+        other,
+        TaskProfile {
+            flap: FlapTracker {
+                flaps: 6,
+                pairs: 6,
+                ..Default::default()
+            },
+            ..confident_profile(0)
+        },
+    );
+    let withheld = withhold_flapping(&mut db.profiles, |_| panic!("NOTHING FLUSHED"));
+    assert_eq!(withheld, vec![(other, 100)]);
+}
+
+// FORCED comms (--force-batch-comm / --force-latcri-comm / --force-file)
+
+fn force(db: &mut ProcessDb, name: &[u8], tier: u8) -> [u8; 16] {
+    let comm = make_comm(name);
+    db.force(&[Force { comm, tier }]);
+    comm
+}
+
+#[test]
+fn forced_tier_beats_ingest_and_flushes_pinned() {
+    let mut db = offline_db();
+    let comm = force(&mut db, b"make", 0);
+    db.tick = 7;
+    for _ in 0..10 {
+        db.merge_observation(comm, &observation(2, SCHED_FIFO, 1024));
+    }
+    let p = &db.profiles[&comm];
+    assert_eq!(p.tier_votes, [0, 0, 0]);
+    assert_eq!(p.observations, 0);
+    assert_eq!(p.last_seen_tick, 7);
+    assert!(p.predictable());
+
+    let mut entries = Vec::new();
+    let written = flush_dirty(&mut db.profiles, |c, e| {
+        entries.push((*c, *e));
+        true
+    });
+    assert_eq!(written, vec![(comm, 0)]);
+    assert_eq!(entries[0].1.pinned, 1);
+    // FLUSHED ONCE, NEVER AGAIN
+    assert!(flush_dirty(&mut db.profiles, |_, _| true).is_empty());
+}
+
+#[test]
+fn forcing_a_learned_profile_rewrites_its_prediction() {
+    let mut db = offline_db();
+    let comm = make_comm(b"steam");
+    db.profiles.insert(comm, confident_profile(0));
+    let written = flush_dirty(&mut db.profiles, |_, e| e.pinned == 0);
+    assert_eq!(written, vec![(comm, 0)]);
+
+    force(&mut db, b"steam", 2);
+    let p = &db.profiles[&comm];
+    assert_eq!(p.dominant_tier(), 0);
+    assert_eq!(p.predicted_tier(), 2);
+    assert!(p.dirty());
+    let written = flush_dirty(&mut db.profiles, |_, e| e.pinned == 1);
+    assert_eq!(written, vec![(comm, 2)]);
+    // THE PIN NEVER FLAPS, EVEN WITH A FLAPPING HISTORY ON RECORD
+    db.profiles.get_mut(&comm).unwrap().flap = FlapTracker {
+        flaps: 6,
+        pairs: 6,
+        ..Default::default()
+    };
+    assert!(withhold_flapping(&mut db.profiles, |_| panic!("FORCED")).is_empty());
+    assert_eq!(db.profiles[&comm].flushed.map(|f| f.0), Some(2));
+}
+
+#[test]
+fn forced_profiles_survive_eviction() {
+    let mut db = offline_db();
+    db.limits.stale_ticks = 5;
+    let comm = force(&mut db, b"jackd", 2);
+    for _ in 0..10 {
+        db.tick();
+    }
+    assert!(db.profiles.contains_key(&comm));
+    assert_eq!(db.evictions, 0);
+
+    // CAP PRESSURE: EVERY UNFORCED PROFILE GOES BEFORE THE PIN
+    db.limits.max_profiles = 1;
+    for name in [b"a".as_slice(), b"b"] {
+        db.profiles
+            .insert(make_comm(name), confident_profile(db.tick));
+    }
+    db.tick();
+    assert_eq!(db.profiles.keys().collect::<Vec<_>>(), [&comm]);
+    assert_eq!(db.evictions, 2);
+}
+
+#[test]
+fn forced_profiles_survive_import_replace_and_stay_home() {
+    let mut db = offline_db();
+    let comm = force(&mut db, b"obs", 2);
+    db.profiles
+        .insert(make_comm(b"learned"), confident_profile(0));
+    // UNOBSERVED PIN: NOT EXPORTED
+    let exported = decode_export(&encode_export(&db.profiles)).unwrap();
+    assert_eq!(
+        exported.keys().collect::<Vec<_>>(),
+        [&make_comm(b"learned")]
+    );
+
+    let mut incoming = HashMap::new();
+    incoming.insert(comm, confident_profile(0));
+    assert_eq!(db.import(incoming, true), (0, 1));
+    assert_eq!(db.profiles.len(), 1);
+    assert_eq!(db.profiles[&comm].forced, Some(2));
+}
+
+#[test]
+fn force_file_and_flag_parsing() {
+    let forces = parse_force_file(
+        "# comm  tier\n\
+         \n\
+         rsync      batch         # BACKUPS\n\
+         my-synth   LAT_CRITICAL\n",
+    )
+    .unwrap();
+    assert_eq!(
+        forces,
+        vec![
+            Force {
+                comm: comm_key("rsync"),
+                tier: 0,
+            },
+            Force {
+                comm: comm_key("my-synth"),
+                tier: 2,
+            },
+        ]
+    );
+    assert!(parse_force_file("rsync\n").is_err());
+    assert!(parse_force_file("rsync batch 100\n").is_err());
+    assert!(parse_force_file("rsync turbo\n").is_err());
+    assert!(parse_force_file("a-very-long-comm-name batch\n").is_err());
+
+    assert_eq!(forced_comm("rsync", 0).unwrap(), forces[0]);
+    assert!(forced_comm("", 0).is_err());
+    assert!(forced_comm("sixteen-bytes-xx", 2).is_err());
+}
+
+#[test]
+fn list_flags_forced_entries() {
+    let entry = |tier, pinned| TaskClassEntry {
+        tier,
+        pinned,
+        ..observation(tier, 0, 0)
+    };
+    let entries = [
+        (make_comm(b"rsync"), entry(0, 1)),
+        (make_comm(b"firefox"), entry(1, 0)),
+    ];
+    let rows = list_rows(&entries, None);
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with("COMM") && rows[0].contains("PREEMPTED/S"));
+    assert!(rows[1].starts_with("firefox") && !rows[1].ends_with("FORCED"));
+    assert!(rows[2].starts_with("rsync") && rows[2].ends_with("FORCED"));
+    assert!(rows[2].contains("batch"));
+    // NOT RUNNING: NO RATE TO SHOW
+    assert!(rows[1].ends_with(" -"));
+
+    // RUNNING: THE SCHEDULER'S RATE, 0 FOR A comm IT NEVER PREEMPTED
+    let rates: HashMap<String, f64> = [("rsync".to_string(), 12.5)].into_iter().collect();
+    let rows = list_rows(&entries, Some(&rates));
+    assert!(rows[1].ends_with(" 0.00"));
+    assert!(rows[2].contains(" 12.50  FORCED"));
+}
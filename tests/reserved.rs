@@ -0,0 +1,111 @@
+// PANDEMONIUM RESERVED CPU TESTS
+// --reserved-cpus PARSING (SINGLES, RANGES, MIXED, MALFORMED), VALIDATION
+// AGAINST THE ONLINE SET, RESERVED CPUS LEFT OUT OF THE STATS TOTAL
+
+use pandemonium::reserved::{
+    describe, parse_reserved_cpus, unreserved, validate, ReservedCpusError, MAX_RESERVED_CPU,
+};
+use pandemonium::stats::PandemoniumStats;
+
+#[test]
+fn singles_and_ranges_parse_sorted_and_deduped() {
+    assert_eq!(parse_reserved_cpus("2,3"), Ok(vec![2, 3]));
+    assert_eq!(parse_reserved_cpus("8-11"), Ok(vec![8, 9, 10, 11]));
+    assert_eq!(parse_reserved_cpus("7"), Ok(vec![7]));
+    assert_eq!(parse_reserved_cpus("5-5"), Ok(vec![5]));
+    assert_eq!(parse_reserved_cpus("9,0,4-5"), Ok(vec![0, 4, 5, 9]));
+    assert_eq!(parse_reserved_cpus("2-4,3,4-6"), Ok(vec![2, 3, 4, 5, 6]));
+    // SPACES AROUND ENTRIES AND AROUND THE DASH
+    assert_eq!(parse_reserved_cpus(" 2 , 8 - 9 "), Ok(vec![2, 8, 9]));
+    assert_eq!(
+        parse_reserved_cpus(&MAX_RESERVED_CPU.to_string()),
+        Ok(vec![MAX_RESERVED_CPU])
+    );
+}
+
+#[test]
+fn malformed_lists_are_errors() {
+    let bad = |s: &str| parse_reserved_cpus(s).unwrap_err();
+    assert_eq!(bad(""), ReservedCpusError::Empty);
+    assert_eq!(bad("  "), ReservedCpusError::Empty);
+    assert_eq!(bad("2,,3"), ReservedCpusError::EmptyToken);
+    assert_eq!(bad("2,"), ReservedCpusError::EmptyToken);
+    assert_eq!(bad(",2"), ReservedCpusError::EmptyToken);
+    for token in [
+        "x", "2a", "+2", "-2", "2-", "-", "1-2-3", "0x4", "2.5", "2;3",
+    ] {
+        assert_eq!(
+            bad(token),
+            ReservedCpusError::BadToken(token.to_string()),
+            "{}",
+            token
+        );
+    }
+    assert_eq!(bad("5-3"), ReservedCpusError::Reversed { start: 5, end: 3 });
+    assert_eq!(bad("1024"), ReservedCpusError::TooLarge(1024));
+    assert_eq!(bad("1000-1100"), ReservedCpusError::TooLarge(1100));
+    // PAST u64: NOT A NUMBER WORTH ECHOING AS ONE
+    assert_eq!(
+        bad("99999999999999999999999"),
+        ReservedCpusError::BadToken("99999999999999999999999".to_string())
+    );
+    // ONE BAD ENTRY FAILS THE WHOLE LIST
+    assert_eq!(bad("2,x,3"), ReservedCpusError::BadToken("x".to_string()));
+}
+
+#[test]
+fn errors_name_the_flag_and_the_fix() {
+    let msg = |s: &str| parse_reserved_cpus(s).unwrap_err().message();
+    assert!(
+        msg("5-3").starts_with("--reserved-cpus: "),
+        "{}",
+        msg("5-3")
+    );
+    assert!(msg("5-3").contains("WRITE 3-5"), "{}", msg("5-3"));
+    assert!(msg("x").contains("\"x\""), "{}", msg("x"));
+    assert!(msg("4096").contains("1023"), "{}", msg("4096"));
+    assert_eq!(
+        ReservedCpusError::Offline(vec![12, 13, 15]).message(),
+        "--reserved-cpus: CPUS NOT ONLINE: 12-13,15"
+    );
+}
+
+#[test]
+fn validation_against_the_online_set() {
+    let online: Vec<u32> = (0..8).filter(|&c| c != 5).collect();
+    assert_eq!(validate(&[2, 3], &online), Ok(()));
+    assert_eq!(
+        validate(&[4, 5, 9], &online),
+        Err(ReservedCpusError::Offline(vec![5, 9]))
+    );
+    // SOMETHING MUST BE LEFT TO SCHEDULE ON
+    assert_eq!(
+        validate(&online, &online),
+        Err(ReservedCpusError::AllOnline)
+    );
+    assert_eq!(validate(&[0, 1, 2, 3, 4, 6], &online), Ok(()));
+
+    assert_eq!(unreserved(&online, &[2, 3]), vec![0, 1, 4, 6, 7]);
+    assert_eq!(unreserved(&[3, 1, 2], &[]), vec![3, 1, 2]);
+    assert_eq!(describe(&[2, 3, 8]), "2-3,8");
+    assert_eq!(describe(&[]), "-");
+}
+
+#[test]
+fn reserved_cpus_leave_the_stats_total() {
+    let cpu = |dispatches, idle| PandemoniumStats {
+        nr_dispatches: dispatches,
+        nr_idle_hits: idle,
+        ..Default::default()
+    };
+    // CPU 1 RESERVED: A PINNED THREAD WAKING ON AN OTHERWISE IDLE CPU
+    let per_cpu = [cpu(100, 10), cpu(500, 500), cpu(100, 30)];
+    let all = PandemoniumStats::total(&per_cpu);
+    let kept = PandemoniumStats::total_except(&per_cpu, &[1]);
+    assert_eq!(all.nr_dispatches, 700);
+    assert_eq!(kept.nr_dispatches, 200);
+    assert_eq!(kept.nr_idle_hits, 40);
+    assert_eq!(PandemoniumStats::total_except(&per_cpu, &[]), all);
+    // PAST THE PER-CPU READ: NOTHING TO SKIP
+    assert_eq!(PandemoniumStats::total_except(&per_cpu, &[64]), all);
+}
@@ -0,0 +1,150 @@
+// PANDEMONIUM IDLE RESIDENCY TESTS
+// TIME-WEIGHTED PER-CPU RESIDENCY FROM INJECTED IDLE BITMAPS, CARRY-OVER
+// BETWEEN TICKS, SKIPPED READS, THE REGIME SIGNAL IT FEEDS
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use pandemonium::hotplug::{bitmap_has, cpu_bitmap as bitmap};
+use pandemonium::residency::{idle_bitmap, IdleResidency};
+use pandemonium::tuning::{detect_regime, regime_idle_signal, Regime};
+
+const MS: u64 = 1_000_000;
+
+#[test]
+fn bits_map_to_cpus() {
+    let b = bitmap(&[0, 63, 64, 1023]);
+    for cpu in [0, 63, 64, 1023] {
+        assert!(bitmap_has(&b, cpu), "{}", cpu);
+    }
+    assert!(!bitmap_has(&b, 1));
+    assert!(!bitmap_has(&b, 65));
+    // PAST THE BITMAP: NOT IDLE, NOT A PANIC; NOT SET EITHER
+    assert!(!bitmap_has(&b, 4096));
+    assert_eq!(bitmap(&[4096]), bitmap(&[]));
+}
+
+// cpu_busy_map: ONE FLAG PER POSSIBLE CPU, 0 = IN THE IDLE LOOP
+#[test]
+fn busy_flags_become_an_idle_bitmap() {
+    assert_eq!(idle_bitmap([0, 1, 1, 0]), bitmap(&[0, 3]));
+    let mut busy = vec![1u64; 70];
+    busy[65] = 0;
+    assert_eq!(idle_bitmap(busy), bitmap(&[65]));
+    // POSSIBLE CPUS PAST THE BITMAP ARE DROPPED, NOT A PANIC
+    assert_eq!(idle_bitmap(vec![1; 2048]), bitmap(&[]));
+    assert_eq!(
+        idle_bitmap(vec![0; 2048]),
+        bitmap(&(0..1024).collect::<Vec<_>>())
+    );
+}
+
+#[test]
+fn residency_weights_by_time_not_samples() {
+    // CPU 0 PARKED ALL SECOND; CPU 1 IDLE 900MS OF IT ACROSS ONE LONG
+    // GAP, BUSY FOR NINE SHORT ONES
+    let mut r = IdleResidency::default();
+    r.sample(0, &bitmap(&[0, 1]));
+    r.sample(900 * MS, &bitmap(&[0]));
+    for i in 1..=9 {
+        r.sample((900 + 10 * i) * MS, &bitmap(&[0]));
+    }
+    let tick = r.take(&[0, 1, 2, 3]).unwrap();
+    assert_eq!(tick.per_cpu, vec![(0, 100), (1, 90), (2, 0), (3, 0)]);
+    assert_eq!(tick.idle_pct, 47);
+    assert_eq!(tick.samples, 11);
+}
+
+#[test]
+fn last_sample_carries_into_the_next_tick() {
+    let mut r = IdleResidency::default();
+    r.sample(0, &bitmap(&[2]));
+    // NOTHING COVERED YET: ONE SAMPLE IS A POINT, NOT AN INTERVAL
+    assert_eq!(r.take(&[2]), None);
+    r.sample(500 * MS, &bitmap(&[]));
+    assert_eq!(r.take(&[2]).unwrap().per_cpu, vec![(2, 100)]);
+    // THE NEXT WINDOW STARTS FROM THE HELD (BUSY) SAMPLE, NOT FROM ZERO
+    r.sample(1000 * MS, &bitmap(&[2]));
+    assert_eq!(r.take(&[2]).unwrap().per_cpu, vec![(2, 0)]);
+    // A CLOCK STEPPING BACK CREDITS NOTHING
+    r.sample(900 * MS, &bitmap(&[2]));
+    assert_eq!(r.take(&[2]), None);
+}
+
+#[test]
+fn sample_window_sleeps_in_steps_and_skips_failed_reads() {
+    let mut r = IdleResidency::default();
+    let mut sleeps = Vec::new();
+    let mut reads = 0;
+    let clock = Cell::new(0u64);
+    r.sample_window(
+        Duration::from_secs(1),
+        10,
+        |d| {
+            sleeps.push(d);
+            clock.set(clock.get() + d.as_nanos() as u64);
+        },
+        || clock.get(),
+        || {
+            reads += 1;
+            // THE 5TH READ FAILS: THE 4TH SAMPLE HOLDS FOR 200MS
+            match reads {
+                5 => None,
+                n if n <= 4 => Some(bitmap(&[0])),
+                _ => Some(bitmap(&[])),
+            }
+        },
+    );
+    assert_eq!(sleeps, vec![Duration::from_millis(100); 10]);
+    let tick = r.take(&[0]).unwrap();
+    assert_eq!(tick.samples, 9);
+    // IDLE FROM THE 1ST READ (100MS) UNTIL THE 6TH (600MS): 500 OF 900MS COVERED
+    assert_eq!(tick.per_cpu, vec![(0, 55)]);
+    assert_eq!(tick.format_line(), "[RESID] idle=55% samples=9 cpu0=55");
+}
+
+#[test]
+fn sample_window_until_ends_after_the_stopping_step() {
+    let mut r = IdleResidency::default();
+    let mut sleeps = Vec::new();
+    let clock = Cell::new(0u64);
+    let mut checks = 0;
+    // THE STRETCHED TICK: 5S IN 1S STEPS, ACTIVITY SEEN AT THE 3RD STEP
+    let taken = r.sample_window_until(
+        Duration::from_secs(5),
+        5,
+        |d| {
+            sleeps.push(d);
+            clock.set(clock.get() + d.as_nanos() as u64);
+        },
+        || clock.get(),
+        || Some(bitmap(&[0])),
+        || {
+            checks += 1;
+            checks == 3
+        },
+    );
+    assert_eq!(taken, 3);
+    assert_eq!(sleeps, vec![Duration::from_secs(1); 3]);
+    // THE STOPPING STEP'S SAMPLE STILL COUNTS
+    assert_eq!(r.take(&[0]).unwrap().samples, 3);
+
+    // NEVER STOPPED: THE WHOLE WINDOW
+    let mut r = IdleResidency::default();
+    let taken = r.sample_window_until(Duration::from_secs(1), 10, |_| {}, || 0, || None, || false);
+    assert_eq!(taken, 10);
+}
+
+#[test]
+fn residency_pulls_the_regime_signal_halfway() {
+    assert_eq!(regime_idle_signal(40, None), 40);
+    assert_eq!(regime_idle_signal(40, Some(80)), 60);
+    assert_eq!(regime_idle_signal(40, Some(250)), 70);
+    // "40% IDLE" FROM DISPATCH COUNTERS WITH THE MACHINE MOSTLY PARKED:
+    // MIXED ALONE, LIGHT ONCE RESIDENCY IS IN
+    assert_eq!(detect_regime(Regime::Mixed, 40), Regime::Mixed);
+    assert_eq!(
+        detect_regime(Regime::Mixed, regime_idle_signal(40, Some(90))),
+        Regime::Light
+    );
+}
@@ -0,0 +1,110 @@
+// PANDEMONIUM RESTART STORM TESTS
+// THE BACKOFF CURVE AND GIVE-UP POINT OF A POLICY, THE SLIDING WINDOW, AND
+// THE COUNT + LAST REASON get regime REPORTS
+
+use std::time::Duration;
+
+use pandemonium::restart::{RestartDecision, RestartGuard, RestartPolicy};
+
+const SEC: u64 = 1_000_000_000;
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn policy_backoff_curve() {
+    let p = RestartPolicy::default();
+    // THE FREE ONES: ONLY THE STRUCT_OPS CLEANUP WAIT
+    for n in 1..=p.free {
+        assert_eq!(p.delay(n), Some(p.base_delay), "{}", n);
+    }
+    // THEN DOUBLING, CAPPED AT max_delay
+    assert_eq!(p.delay(4), Some(secs(4)));
+    assert_eq!(p.delay(5), Some(secs(8)));
+    assert_eq!(p.delay(6), Some(secs(16)));
+    assert_eq!(p.delay(7), Some(secs(30)));
+    assert_eq!(p.delay(8), Some(secs(30)));
+    // ONE PAST give_up: STOP
+    assert_eq!(p.delay(p.give_up + 1), None);
+
+    // THE WHOLE BACKOFF FITS IN THE WINDOW: A PERSISTENT STORM REACHES give_up
+    let total: Duration = (1..=p.give_up).filter_map(|n| p.delay(n)).sum();
+    assert!(total < p.window, "{:?}", total);
+
+    // A HUGE give_up DOES NOT OVERFLOW THE SHIFT
+    let wide = RestartPolicy {
+        give_up: u32::MAX,
+        ..p
+    };
+    assert_eq!(wide.delay(1000), Some(p.max_delay));
+}
+
+#[test]
+fn guard_gives_up_in_a_storm_and_forgives_a_quiet_spell() {
+    let p = RestartPolicy::default();
+    let mut g = RestartGuard::new(p);
+    let mut now = 0;
+    for n in 1..=p.give_up {
+        let d = g.record(now, "hotplug");
+        assert_eq!(d, RestartDecision::Wait(p.delay(n).unwrap()));
+        assert_eq!(g.storming(), n > p.free);
+        now += 10 * SEC;
+    }
+    let d = g.record(now, "hotplug");
+    assert_eq!(
+        d,
+        RestartDecision::GiveUp {
+            in_window: p.give_up + 1
+        }
+    );
+    assert_eq!(
+        g.storm_message(d),
+        "RESTART STORM: 9 KERNEL RESTARTS IN 600s, GIVING UP"
+    );
+
+    // SPREAD OUT: THE WINDOW SLIDES, EVERY RESTART IS A FREE ONE
+    let mut g = RestartGuard::new(p);
+    for i in 0..50u64 {
+        let d = g.record(i * 300 * SEC, "hotplug");
+        assert_eq!(d, RestartDecision::Wait(p.base_delay), "{}", i);
+        assert!(g.in_window() <= 2);
+    }
+    assert_eq!(g.summary().count, 50);
+
+    // A BURST, A QUIET WINDOW, A BURST: THE SECOND STARTS FROM FREE AGAIN
+    let mut g = RestartGuard::new(p);
+    for i in 0..5 {
+        g.record(i * SEC, "a");
+    }
+    assert!(g.storming());
+    let d = g.record(5 * SEC + p.window.as_nanos() as u64, "b");
+    assert_eq!(d, RestartDecision::Wait(p.base_delay));
+    assert_eq!(g.in_window(), 1);
+    assert!(!g.storming());
+}
+
+#[test]
+fn summary_keeps_the_count_and_latest_reason() {
+    let mut g = RestartGuard::new(RestartPolicy::default());
+    assert_eq!(g.summary().count, 0);
+    assert_eq!(g.summary().last_reason, None);
+
+    g.record(0, "cpu 3 going offline");
+    g.record(SEC, "another\nscheduler attached");
+    assert_eq!(g.summary().count, 2);
+    // ONE key=value LINE: NEWLINES FLATTENED
+    assert_eq!(
+        g.summary().last_reason.as_deref(),
+        Some("another scheduler attached")
+    );
+
+    let mut g = RestartGuard::new(RestartPolicy::default());
+    for i in 0..5 {
+        g.record(i * SEC, "hotplug");
+    }
+    assert_eq!(
+        g.storm_message(RestartDecision::Wait(secs(8))),
+        "RESTART STORM: 5 KERNEL RESTARTS IN 600s, WAITING 8s BEFORE THE NEXT ATTACH"
+    );
+}
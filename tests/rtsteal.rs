@@ -0,0 +1,129 @@
+// PANDEMONIUM RT/DEADLINE CAPACITY STEAL TESTS
+// stat POLICY + schedstat RUNTIME PARSING, THE STOLEN SHARE, THE WATCH OVER
+// A SYNTHETIC /proc TREE, AND THE REGIME IDLE ADJUSTMENT AT ITS BOUNDARIES
+
+use pandemonium::rtsteal::{
+    is_rt_policy, parse_policy, parse_runtime_ns, stolen_pct, RtWatch, RESCAN_TICKS,
+    SCHED_DEADLINE, SCHED_FIFO,
+};
+use pandemonium::tuning::{detect_regime, rt_adjusted_idle, Regime};
+
+const SEC: u64 = 1_000_000_000;
+
+// /proc/<pid>/task/<tid>/stat WITH policy AS FIELD 41
+fn stat(comm: &str, policy: u32) -> String {
+    let mut fields = vec!["0".to_string(); 50];
+    fields[0] = "S".to_string();
+    fields[38] = policy.to_string();
+    format!("4242 ({}) {}\n", comm, fields.join(" "))
+}
+
+#[test]
+fn policy_and_runtime_parsing() {
+    assert_eq!(parse_policy(&stat("pipewire", SCHED_FIFO)), Some(1));
+    // comm WITH SPACES AND A ')' OF ITS OWN
+    assert_eq!(parse_policy(&stat("data-loop) 0", SCHED_DEADLINE)), Some(6));
+    assert_eq!(parse_policy(&stat("bash", 0)), Some(0));
+    assert_eq!(parse_policy("4242 (trunc) S 1 2"), None);
+    assert_eq!(parse_policy(""), None);
+
+    assert!(is_rt_policy(1) && is_rt_policy(2) && is_rt_policy(6));
+    // SCHED_OTHER, SCHED_BATCH, SCHED_IDLE, SCHED_EXT
+    for p in [0, 3, 5, 7] {
+        assert!(!is_rt_policy(p), "{}", p);
+    }
+
+    assert_eq!(parse_runtime_ns("123456789 4000 12\n"), Some(123_456_789));
+    assert_eq!(parse_runtime_ns(""), None);
+}
+
+#[test]
+fn stolen_share_of_capacity() {
+    // HALF A CPU OF 4 FOR ONE SECOND: 12%
+    assert_eq!(stolen_pct(SEC / 2, SEC, 4), 12);
+    assert_eq!(stolen_pct(0, SEC, 4), 0);
+    // MORE THAN THE CAPACITY (CLOCK SKEW, A LATE TICK): CAPPED
+    assert_eq!(stolen_pct(10 * SEC, SEC, 4), 100);
+    // NO INTERVAL OR NO CPUS: NOTHING STOLEN, NO DIVISION BY ZERO
+    assert_eq!(stolen_pct(SEC, 0, 4), 0);
+    assert_eq!(stolen_pct(SEC, SEC, 0), 0);
+}
+
+#[test]
+fn watch_follows_rt_threads_in_a_synthetic_proc() {
+    let root = std::env::temp_dir().join(format!("pandemonium-rtsteal-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let thread = |pid: u32, tid: u32, policy: u32, run_ns: u64| {
+        let d = root.join(format!("{}/task/{}", pid, tid));
+        std::fs::create_dir_all(&d).unwrap();
+        std::fs::write(d.join("stat"), stat("t", policy)).unwrap();
+        std::fs::write(d.join("schedstat"), format!("{} 0 0\n", run_ns)).unwrap();
+    };
+    // AN AUDIO SERVER WITH ONE FIFO THREAD, A NORMAL PROCESS, A DL TASK
+    thread(100, 100, 0, 5 * SEC);
+    thread(100, 101, SCHED_FIFO, SEC);
+    thread(200, 200, 0, 9 * SEC);
+    thread(300, 300, SCHED_DEADLINE, 0);
+    std::fs::create_dir_all(root.join("self")).unwrap();
+
+    let mut w = RtWatch::new(&root);
+    assert_eq!(w.tick(0, 2), None);
+    assert_eq!(w.threads(), 2);
+
+    // 0.5S OF FIFO + 0.3S OF DEADLINE OVER 2 CPUS x 1S
+    thread(100, 101, SCHED_FIFO, SEC + SEC / 2);
+    thread(300, 300, SCHED_DEADLINE, SEC * 3 / 10);
+    // THE NORMAL THREAD'S RUNTIME NEVER COUNTS
+    thread(200, 200, 0, 20 * SEC);
+    assert_eq!(w.tick(SEC, 2), Some(40));
+
+    // A THREAD THAT EXITS DROPS OUT; NOTHING RAN
+    std::fs::remove_dir_all(root.join("300")).unwrap();
+    assert_eq!(w.tick(2 * SEC, 2), Some(0));
+    assert_eq!(w.threads(), 1);
+
+    // A THREAD TURNING RT IS PICKED UP AT THE NEXT RESCAN, COUNTED FROM THERE
+    thread(200, 200, SCHED_FIFO, 20 * SEC);
+    let mut now = 2 * SEC;
+    for _ in 3..RESCAN_TICKS {
+        now += SEC;
+        assert_eq!(w.tick(now, 2), Some(0));
+    }
+    assert_eq!(w.threads(), 1);
+    now += SEC;
+    assert_eq!(w.tick(now, 2), Some(0));
+    assert_eq!(w.threads(), 2);
+    thread(200, 200, SCHED_FIFO, 21 * SEC);
+    now += SEC;
+    assert_eq!(w.tick(now, 2), Some(50));
+
+    std::fs::remove_dir_all(&root).unwrap();
+    // NO /proc AT ALL: NO THREADS, NOTHING STOLEN
+    let mut empty = RtWatch::new(&root);
+    assert_eq!(empty.tick(0, 2), None);
+    assert_eq!(empty.tick(SEC, 2), Some(0));
+}
+
+#[test]
+fn rt_share_comes_off_the_idle_signal_at_the_boundaries() {
+    // NO READING, OR rt = 0: UNCHANGED
+    assert_eq!(rt_adjusted_idle(60, None), 60);
+    assert_eq!(rt_adjusted_idle(60, Some(0)), 60);
+    // rt WITHIN THE MEASURED BUSY SHARE (40%): ALREADY OUT OF THE SIGNAL
+    assert_eq!(rt_adjusted_idle(60, Some(40)), 60);
+    assert_eq!(rt_adjusted_idle(60, Some(25)), 60);
+    // rt > MEASURED BUSY: IDLE CAPPED AT THE CAPACITY LEFT
+    assert_eq!(rt_adjusted_idle(60, Some(70)), 30);
+    assert_eq!(rt_adjusted_idle(100, Some(100)), 0);
+    // AN OUT-OF-RANGE SHARE IS CLAMPED, NOT AN UNDERFLOW
+    assert_eq!(rt_adjusted_idle(60, Some(250)), 0);
+    assert_eq!(rt_adjusted_idle(0, Some(90)), 0);
+
+    // AN AUDIO THREAD HOLDING 60% OF THE MACHINE THE DISPATCH COUNTERS
+    // CANNOT SEE: NOT LIGHT
+    assert_eq!(detect_regime(Regime::Mixed, 80), Regime::Light);
+    assert_eq!(
+        detect_regime(Regime::Mixed, rt_adjusted_idle(80, Some(60))),
+        Regime::Mixed
+    );
+}
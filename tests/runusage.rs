@@ -0,0 +1,107 @@
+// PANDEMONIUM PER-RUN RESOURCE USAGE TESTS
+// rusage CONVERSION, FIELD-WISE MEANS, ROW + PHASE SUMMARY FORMATTING
+
+use pandemonium::runusage::{
+    format_usage_row, mean_usage, pct_delta, usage_header, usage_summary_lines, RunUsage,
+};
+
+fn usage(wall_s: f64, user_s: f64, sys_s: f64, rss_kb: u64, vcsw: u64, ivcsw: u64) -> RunUsage {
+    RunUsage {
+        wall_s,
+        user_s,
+        sys_s,
+        max_rss_kb: rss_kb,
+        vcsw,
+        ivcsw,
+    }
+}
+
+#[test]
+fn from_rusage_converts_timevals_and_counts() {
+    let mut ru: libc::rusage = unsafe { std::mem::zeroed() };
+    ru.ru_utime.tv_sec = 80;
+    ru.ru_utime.tv_usec = 250_000;
+    ru.ru_stime.tv_sec = 5;
+    ru.ru_stime.tv_usec = 500;
+    ru.ru_maxrss = 524_288;
+    ru.ru_nvcsw = 12_345;
+    ru.ru_nivcsw = 678;
+    let u = RunUsage::from_rusage(12.5, &ru);
+    assert_eq!(u.wall_s, 12.5);
+    assert!((u.user_s - 80.25).abs() < 1e-9);
+    assert!((u.sys_s - 5.0005).abs() < 1e-9);
+    assert_eq!(u.max_rss_kb, 524_288);
+    assert_eq!((u.vcsw, u.ivcsw), (12_345, 678));
+}
+
+#[test]
+fn wait4_on_a_real_child() {
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let mut status = 0;
+    let mut ru: libc::rusage = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::wait4(child.id() as i32, &mut status, 0, &mut ru) };
+    assert_eq!(rc, child.id() as i32);
+    assert!(libc::WIFEXITED(status));
+    // wait4 ALREADY REAPED IT: std SEES ECHILD
+    assert!(child.wait().is_err());
+    // EVERY PROCESS MAPS SOMETHING
+    assert!(RunUsage::from_rusage(0.0, &ru).max_rss_kb > 0);
+}
+
+#[test]
+fn mean_is_field_wise() {
+    let runs = [
+        usage(10.0, 40.0, 4.0, 1000, 100, 10),
+        usage(12.0, 44.0, 6.0, 2000, 200, 21),
+    ];
+    let m = mean_usage(&runs);
+    assert_eq!(m.wall_s, 11.0);
+    assert_eq!(m.user_s, 42.0);
+    assert_eq!(m.sys_s, 5.0);
+    assert_eq!(m.max_rss_kb, 1500);
+    assert_eq!(m.vcsw, 150);
+    // 15.5 ROUNDS AWAY FROM ZERO
+    assert_eq!(m.ivcsw, 16);
+    assert_eq!(mean_usage(&[]), RunUsage::default());
+}
+
+#[test]
+fn pct_delta_handles_zero_base() {
+    assert_eq!(pct_delta(100.0, 150.0), 50.0);
+    assert_eq!(pct_delta(100.0, 80.0), -20.0);
+    assert_eq!(pct_delta(0.0, 42.0), 0.0);
+}
+
+#[test]
+fn rows_line_up_with_the_header() {
+    let header = usage_header();
+    let row = format_usage_row("1", &usage(12.34, 80.12, 5.01, 524_288, 12_345, 6_789));
+    assert_eq!(row.len(), header.len());
+    assert!(row.contains("12.34s"));
+    assert!(row.contains("512.0MB"));
+    assert!(row.trim_end().ends_with("6789"));
+    for col in ["WALL", "USER", "SYS", "MAX RSS", "VOL CSW", "INVOL CSW"] {
+        assert!(header.contains(col));
+    }
+}
+
+#[test]
+fn summary_has_one_row_per_metric_with_delta() {
+    let eevdf = usage(10.0, 40.0, 4.0, 1024, 1000, 200);
+    let pand = usage(10.5, 40.0, 5.0, 1024, 900, 300);
+    let lines = usage_summary_lines(&eevdf, &pand);
+    assert_eq!(lines.len(), 7);
+    assert!(lines[0].starts_with("PHASE SUMMARY"));
+    let row = |name: &str| {
+        lines
+            .iter()
+            .find(|l| l.trim_start().starts_with(name))
+            .unwrap()
+    };
+    assert!(row("WALL").ends_with("+5.0%"));
+    assert!(row("USER CPU").ends_with("+0.0%"));
+    assert!(row("SYS CPU").ends_with("+25.0%"));
+    assert!(row("MAX RSS").contains("1.0MB"));
+    assert!(row("VOL CSW").ends_with("-10.0%"));
+    assert!(row("INVOL CSW").ends_with("+50.0%"));
+}
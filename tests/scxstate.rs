@@ -0,0 +1,140 @@
+// PANDEMONIUM sched_ext RUNTIME STATE TESTS
+// SYNTHETIC /sys/kernel/sched_ext TREES, EVENT PARSING, UPTIME + HEALTH FORMATTING
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use pandemonium::scxstate::{format_uptime, health_line, parse_events, ScxRuntime};
+use pandemonium::stats::StatsDelta;
+use pandemonium::tuning::{regime_knobs, Regime};
+
+fn fake_sysfs(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "pandemonium-scxstate-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("root")).unwrap();
+    for (file, contents) in files {
+        std::fs::write(root.join(file), contents).unwrap();
+    }
+    root
+}
+
+#[test]
+fn reads_attached_pandemonium() {
+    let root = fake_sysfs(
+        "ours",
+        &[
+            ("state", "enabled\n"),
+            ("switch_all", "1\n"),
+            ("enable_seq", "7\n"),
+            ("nr_rejected", "0\n"),
+            ("hotplug_seq", "2\n"),
+            ("root/ops", "pandemonium\n"),
+        ],
+    );
+    let rt = ScxRuntime::read_from(&root).unwrap();
+    assert_eq!(rt.state.as_deref(), Some("enabled"));
+    assert_eq!(rt.switch_all, Some(true));
+    assert_eq!(rt.enable_seq, Some(7));
+    assert_eq!(rt.nr_rejected, Some(0));
+    assert_eq!(rt.hotplug_seq, Some(2));
+    assert!(rt.is_pandemonium());
+    assert!(rt.events.is_empty());
+    let lines = rt.summary_lines();
+    assert_eq!(lines[0], "ops:         pandemonium (PANDEMONIUM)");
+    assert_eq!(lines.len(), 6);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn other_scheduler_is_not_pandemonium() {
+    let root = fake_sysfs(
+        "lavd",
+        &[("state", "enabled\n"), ("root/ops", "lavd_1.0.12\n")],
+    );
+    let rt = ScxRuntime::read_from(&root).unwrap();
+    assert!(!rt.is_pandemonium());
+    assert_eq!(
+        rt.summary_lines()[0],
+        "ops:         lavd_1.0.12 (NOT PANDEMONIUM)"
+    );
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn missing_files_show_unknown() {
+    // EMPTY ops: NOTHING ATTACHED; UNREADABLE COUNTERS PRINT "?"
+    let root = fake_sysfs("idle", &[("state", "disabled\n"), ("root/ops", "\n")]);
+    let rt = ScxRuntime::read_from(&root).unwrap();
+    assert_eq!(rt.ops, None);
+    assert_eq!(rt.enable_seq, None);
+    let lines = rt.summary_lines();
+    assert_eq!(lines[0], "ops:         none");
+    assert_eq!(lines[3], "enable_seq:  ?");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn no_sysfs_root_is_none() {
+    let root = std::env::temp_dir().join("pandemonium-scxstate-does-not-exist");
+    assert_eq!(ScxRuntime::read_from(&root), None);
+}
+
+#[test]
+fn events_parsed_and_zeroes_hidden() {
+    let text = "SCX_EV_SELECT_CPU_FALLBACK     : 12\n\
+                SCX_EV_DISPATCH_LOCAL_DSQ_OFFLINE: 0\n\
+                SCX_EV_DISPATCH_KEEP_LAST      : 3\n\
+                garbage line\n";
+    let events = parse_events(text);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], ("SCX_EV_SELECT_CPU_FALLBACK".to_string(), 12));
+
+    let root = fake_sysfs(
+        "events",
+        &[("root/ops", "pandemonium\n"), ("root/events", text)],
+    );
+    let rt = ScxRuntime::read_from(&root).unwrap();
+    let lines = rt.summary_lines();
+    assert_eq!(lines.len(), 8);
+    assert!(lines[6].contains("SCX_EV_SELECT_CPU_FALLBACK"));
+    assert!(lines[7].ends_with("3"));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn uptime_formatting() {
+    assert_eq!(format_uptime(Duration::from_secs(0)), "0s");
+    assert_eq!(format_uptime(Duration::from_secs(59)), "59s");
+    assert_eq!(format_uptime(Duration::from_secs(61)), "1m01s");
+    assert_eq!(format_uptime(Duration::from_secs(3725)), "1h02m05s");
+    assert_eq!(format_uptime(Duration::from_secs(90_000)), "25h00m00s");
+}
+
+#[test]
+fn health_line_fields() {
+    let delta = StatsDelta {
+        dispatches: 1000,
+        idle_hits: 250,
+        wake_lat_sum: 40_000,
+        wake_lat_samples: 10,
+        hard_kicks: 5,
+        soft_kicks: 7,
+        ..Default::default()
+    };
+    let knobs = regime_knobs(Regime::Mixed);
+    let line = health_line(&delta, &knobs);
+    assert!(
+        line.starts_with("d/s: 1000 idle: 25% wake: 4us kick: H=5 S=7"),
+        "{}",
+        line
+    );
+    assert!(
+        line.contains(&format!("slice: {}us", knobs.slice_ns / 1000)),
+        "{}",
+        line
+    );
+}
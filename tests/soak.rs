@@ -0,0 +1,104 @@
+// PANDEMONIUM SOAK PLAN TESTS
+// ROTATION SCHEDULE, CPU SPREAD, TELEMETRY PARSING, FAILURE CRITERIA
+
+use pandemonium::soak::{
+    evaluate, parse_p99_us, phase_at, spin_cpus, SoakSample, CRITERIA, P99_SANITY_US, ROTATION,
+};
+
+fn healthy(minute: u64) -> SoakSample {
+    SoakSample {
+        minute,
+        scheduler_alive: true,
+        ops: Some("pandemonium".to_string()),
+        p99_us: vec![50, 120, 80],
+    }
+}
+
+#[test]
+fn rotation_cycles_through_every_phase() {
+    let cycle: u64 = ROTATION.iter().map(|p| p.minutes).sum();
+    assert_eq!(phase_at(0).name, ROTATION[0].name);
+    assert_eq!(phase_at(ROTATION[0].minutes - 1).name, ROTATION[0].name);
+    assert_eq!(phase_at(ROTATION[0].minutes).name, ROTATION[1].name);
+    assert_eq!(phase_at(cycle - 1).name, ROTATION[ROTATION.len() - 1].name);
+    assert_eq!(phase_at(cycle).name, ROTATION[0].name);
+    // EVERY PHASE ACTUALLY RUNS
+    for p in ROTATION {
+        assert!(p.minutes > 0, "{} NEVER RUNS", p.name);
+        assert!((0..cycle).any(|m| phase_at(m).name == p.name));
+    }
+}
+
+#[test]
+fn spin_cpus_spread_evenly() {
+    assert_eq!(spin_cpus(0, 8), Vec::<u32>::new());
+    assert_eq!(spin_cpus(50, 8), vec![0, 2, 4, 6]);
+    assert_eq!(spin_cpus(100, 4), vec![0, 1, 2, 3]);
+    // ROUNDS UP: 50% OF 3 CPUS IS 2 SPINNERS, NEVER A DUPLICATE CPU
+    assert_eq!(spin_cpus(50, 3), vec![0, 1]);
+    assert_eq!(spin_cpus(150, 2), vec![0, 1]);
+}
+
+#[test]
+fn p99_parsed_from_adaptive_telemetry() {
+    let line = "d/s: 251000   idle: 5% shared: 230000 preempt: 12   keep: 0    kick: H=8000 S=22000 enq: W=8000 R=22000 wake: 4us p99: 120us [B:250 I:100 L:50] lat_idle: 3us";
+    assert_eq!(parse_p99_us(line), Some(120));
+    assert_eq!(parse_p99_us("[HYBRID] fast: idle=10% wake=4us"), None);
+    assert_eq!(parse_p99_us("d/s: 100 idle: 5% [BPF]"), None);
+}
+
+#[test]
+fn healthy_minute_passes() {
+    assert!(evaluate(&healthy(3)).is_empty());
+    assert_eq!(healthy(3).median_p99_us(), Some(80));
+}
+
+#[test]
+fn detach_and_exit_fail_with_named_criteria() {
+    let detached = SoakSample {
+        ops: None,
+        ..healthy(7)
+    };
+    let f = evaluate(&detached);
+    assert_eq!(f.len(), 1);
+    assert!(f[0].starts_with("attached: sched_ext DETACHED"), "{}", f[0]);
+
+    let other = SoakSample {
+        ops: Some("lavd_1.0.12".to_string()),
+        ..healthy(7)
+    };
+    assert!(evaluate(&other)[0].contains("lavd_1.0.12"));
+
+    let dead = SoakSample {
+        scheduler_alive: false,
+        ops: None,
+        p99_us: Vec::new(),
+        ..healthy(7)
+    };
+    // ONE LINE PER FAILED CRITERION, IN CRITERIA ORDER
+    let failed = evaluate(&dead);
+    let names: Vec<&str> = failed
+        .iter()
+        .map(|f| f.split(':').next().unwrap())
+        .collect();
+    assert_eq!(names, vec!["process", "attached", "telemetry"]);
+    assert_eq!(CRITERIA[0].name, "process");
+}
+
+#[test]
+fn p99_bound_uses_the_median() {
+    // ONE SATURATED TICK IN A COMPILE BURST IS NOT A FAILURE
+    let spiky = SoakSample {
+        p99_us: vec![100, 20_000, 150],
+        ..healthy(30)
+    };
+    assert!(evaluate(&spiky).is_empty());
+
+    let stalled = SoakSample {
+        p99_us: vec![20_000, P99_SANITY_US + 1, 20_000],
+        ..healthy(30)
+    };
+    let f = evaluate(&stalled);
+    assert_eq!(f.len(), 1);
+    assert!(f[0].starts_with("p99: MEDIAN WAKE P99 20000us"), "{}", f[0]);
+}
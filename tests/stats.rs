@@ -0,0 +1,378 @@
+// PANDEMONIUM STATISTICS TESTS
+// ABI LAYOUT + MAP SIZE CHECKS, PER-CPU ACCUMULATION, PERCPU READ BUFFERS, TICK DELTAS, RESET DETECTION, DERIVED RATES
+
+use std::time::{Duration, Instant};
+
+use pandemonium::stats::{
+    check_value_size, pct, DsqDepth, PandemoniumStats, PerCpuBuf, StatsDelta,
+};
+
+fn snapshot(dispatches: u64, idle: u64) -> PandemoniumStats {
+    PandemoniumStats {
+        nr_dispatches: dispatches,
+        nr_idle_hits: idle,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn layout_matches_intf_h() {
+    // struct pandemonium_stats: 31 x u64
+    assert_eq!(std::mem::size_of::<PandemoniumStats>(), 248);
+    assert_eq!(std::mem::align_of::<PandemoniumStats>(), 8);
+    assert_eq!(PandemoniumStats::default().fields().len(), 31);
+}
+
+#[test]
+fn dsq_depth_layout_matches_intf_h() {
+    // struct dsq_depth: 2 x u64
+    assert_eq!(std::mem::size_of::<DsqDepth>(), 16);
+    let d = DsqDepth {
+        interactive: 7,
+        batch: 12,
+    };
+    assert_eq!(d.total(), 19);
+}
+
+#[test]
+fn decode_requires_exact_size() {
+    // A BPF-SIDE FIELD ADDITION MUST FAIL LOUDLY, NOT DECODE SHIFTED FIELDS
+    let bytes = [0u8; 256];
+    assert!(PandemoniumStats::from_bytes(&bytes[..248]).is_some());
+    assert!(PandemoniumStats::from_bytes(&bytes[..240]).is_none());
+    assert!(PandemoniumStats::from_bytes(&bytes).is_none());
+
+    assert!(check_value_size("stats_map", 248, std::mem::size_of::<PandemoniumStats>()).is_ok());
+    let err = check_value_size("stats_map", 256, 248)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("stats_map: BPF VALUE IS 256 BYTES, RUST MIRROR IS 248"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn fields_follow_struct_order() {
+    // fields() MUST WALK THE STRUCT IN MEMORY ORDER: WRITE A DISTINCT VALUE
+    // INTO EACH u64 SLOT AND READ THEM BACK BY NAME
+    let mut raw = [0u64; 31];
+    for (i, v) in raw.iter_mut().enumerate() {
+        *v = i as u64 + 1;
+    }
+    let s: PandemoniumStats = unsafe { std::mem::transmute(raw) };
+    for (i, (name, v)) in s.fields().iter().enumerate() {
+        assert_eq!(*v, i as u64 + 1, "{} OUT OF ORDER", name);
+    }
+    assert_eq!(s.fields()[0].0, "nr_dispatches");
+    assert_eq!(s.fields()[27].0, "nr_overflow_rescue");
+    assert_eq!(s.fields()[30].0, "nr_tier_to_lat_crit");
+}
+
+#[test]
+fn accumulate_sums_counters_and_maxes_gauges() {
+    let mut total = PandemoniumStats::default();
+    total.accumulate(&PandemoniumStats {
+        nr_dispatches: 10,
+        wake_lat_max: 5_000,
+        batch_sojourn_ns: 2_000_000,
+        longrun_mode_active: 1,
+        burst_mode_active: 3,
+        ..Default::default()
+    });
+    total.accumulate(&PandemoniumStats {
+        nr_dispatches: 7,
+        wake_lat_max: 9_000,
+        batch_sojourn_ns: 1_000_000,
+        longrun_mode_active: 0,
+        burst_mode_active: 2,
+        ..Default::default()
+    });
+    assert_eq!(total.nr_dispatches, 17);
+    assert_eq!(total.burst_mode_active, 5);
+    assert_eq!(total.wake_lat_max, 9_000);
+    assert_eq!(total.batch_sojourn_ns, 2_000_000);
+    assert_eq!(total.longrun_mode_active, 1);
+}
+
+// WRITE ONE CPU'S stats_map SLICE THE WAY THE KERNEL LAYS IT OUT
+fn put_stats(buf: &mut PerCpuBuf, cpu: usize, s: &PandemoniumStats) {
+    let size = std::mem::size_of::<PandemoniumStats>();
+    let bytes = unsafe { std::slice::from_raw_parts(s as *const _ as *const u8, size) };
+    buf.key_mut(0)[cpu * size..(cpu + 1) * size].copy_from_slice(bytes);
+}
+
+#[test]
+fn percpu_buf_decodes_kernel_layout() {
+    // u64 HISTOGRAM: KEY-MAJOR, ONE 8-BYTE SLICE PER CPU
+    let mut hist = PerCpuBuf::new(4, 3, 8);
+    assert_eq!(hist.as_mut_bytes().len(), 4 * 3 * 8);
+    for key in 0..4 {
+        for cpu in 0..3 {
+            let v = (key * 10 + cpu) as u64;
+            hist.key_mut(key)[cpu * 8..cpu * 8 + 8].copy_from_slice(&v.to_ne_bytes());
+        }
+    }
+    assert_eq!(hist.sum_u64(0), 1 + 2);
+    assert_eq!(hist.sum_u64(3), 30 + 31 + 32);
+    hist.clear();
+    assert_eq!(hist.sum_u64(3), 0);
+
+    // ODD VALUE SIZES ROUND UP TO 8 PER CPU
+    assert_eq!(PerCpuBuf::new(2, 4, 12).as_mut_bytes().len(), 2 * 4 * 16);
+
+    let mut stats = PerCpuBuf::new(1, 2, std::mem::size_of::<PandemoniumStats>());
+    put_stats(&mut stats, 1, &snapshot(40, 10));
+    let per_cpu = stats.stats();
+    assert_eq!(per_cpu.len(), 2);
+    assert_eq!(per_cpu[0], PandemoniumStats::default());
+    assert_eq!(per_cpu[1].nr_dispatches, 40);
+    assert_eq!(PandemoniumStats::total(&per_cpu).nr_idle_hits, 10);
+}
+
+#[test]
+fn percpu_decode_within_budget() {
+    // THE MONITOR'S PER-TICK WORK AFTER THE SYSCALLS: DECODE AND SUM stats_map
+    // PLUS BOTH HISTOGRAMS ON A 256-POSSIBLE-CPU MACHINE. 5MS IS 0.5% OF THE
+    // 1S TICK AND LOOSE ENOUGH FOR A DEBUG BUILD ON A LOADED CI RUNNER.
+    const NR_CPUS: usize = 256;
+    const ITERS: u32 = 200;
+    let mut stats = PerCpuBuf::new(1, NR_CPUS, std::mem::size_of::<PandemoniumStats>());
+    for cpu in 0..NR_CPUS {
+        put_stats(&mut stats, cpu, &snapshot(cpu as u64, 1));
+    }
+    let wake = PerCpuBuf::new(36, NR_CPUS, 8);
+    let sleep = PerCpuBuf::new(4, NR_CPUS, 8);
+
+    let t0 = Instant::now();
+    let mut sink = 0u64;
+    for _ in 0..ITERS {
+        let per_cpu = stats.stats();
+        sink += PandemoniumStats::total(&per_cpu).nr_dispatches;
+        sink += (0..36).map(|k| wake.sum_u64(k)).sum::<u64>();
+        sink += (0..4).map(|k| sleep.sum_u64(k)).sum::<u64>();
+    }
+    let per_iter = t0.elapsed() / ITERS;
+    assert_eq!(sink, ITERS as u64 * (NR_CPUS * (NR_CPUS - 1) / 2) as u64);
+    assert!(
+        per_iter < Duration::from_millis(5),
+        "PERCPU DECODE TOOK {:?} PER TICK",
+        per_iter
+    );
+}
+
+#[test]
+fn delta_subtracts_counters() {
+    let prev = PandemoniumStats {
+        nr_dispatches: 1_000,
+        nr_idle_hits: 400,
+        nr_l2_hit_interactive: 10,
+        nr_l2_miss_interactive: 10,
+        burst_mode_active: 4,
+        nr_tier_to_batch: 70,
+        nr_tier_to_interactive: 90,
+        ..Default::default()
+    };
+    let cur = PandemoniumStats {
+        nr_dispatches: 1_500,
+        nr_idle_hits: 650,
+        nr_l2_hit_interactive: 40,
+        nr_l2_miss_interactive: 20,
+        burst_mode_active: 4,
+        nr_tier_to_batch: 82,
+        nr_tier_to_interactive: 101,
+        nr_tier_to_lat_crit: 1,
+        ..Default::default()
+    };
+    let d = cur.delta(&prev);
+    assert_eq!(d.dispatches, 500);
+    assert_eq!(d.idle_hits, 250);
+    assert_eq!(d.idle_pct(), 50);
+    assert_eq!(d.l2_hit, [0, 30, 0]);
+    assert_eq!(d.l2_miss, [0, 10, 0]);
+    assert_eq!(d.l2_hit_pct(), [0, 75, 0]);
+    // BATCH<->INTERACTIVE FLAPPING: BOTH DIRECTIONS MOVE TOGETHER
+    assert_eq!(d.tier_changes, [12, 11, 1]);
+    assert!(!d.burst());
+}
+
+#[test]
+fn delta_of_identical_snapshots_is_zero() {
+    let s = snapshot(123, 45);
+    assert_eq!(s.delta(&s), StatsDelta::default());
+}
+
+#[test]
+fn delta_wraps_at_u64_max() {
+    let prev = snapshot(u64::MAX - 9, 0);
+    let cur = snapshot(5, 0);
+    assert_eq!(cur.delta(&prev).dispatches, 15);
+}
+
+#[test]
+fn latency_averages_in_us() {
+    let d = StatsDelta {
+        wake_lat_sum: 30_000,
+        wake_lat_samples: 3,
+        wake_lat_idle_sum: 8_000,
+        wake_lat_idle_cnt: 4,
+        ..Default::default()
+    };
+    assert_eq!(d.wake_avg_us(), 10);
+    assert_eq!(d.lat_idle_us(), 2);
+    // NO SAMPLES: 0, NOT A DIVIDE-BY-ZERO
+    assert_eq!(d.lat_kick_us(), 0);
+    assert_eq!(StatsDelta::default().idle_pct(), 0);
+    // CUMULATIVE, STRAIGHT FROM THE COUNTERS
+    let s = PandemoniumStats {
+        wake_lat_sum: 90_000,
+        wake_lat_samples: 9,
+        ..Default::default()
+    };
+    assert_eq!(s.wake_avg_us(), 10);
+}
+
+#[test]
+fn cumulative_l2_hit_rate() {
+    let s = PandemoniumStats {
+        nr_l2_hit_batch: 3,
+        nr_l2_miss_batch: 1,
+        nr_l2_hit_lat_crit: 9,
+        nr_l2_miss_lat_crit: 1,
+        ..Default::default()
+    };
+    assert_eq!(s.l2_hit_pct(), [75, 0, 90]);
+    assert_eq!(pct(1, 3), 33);
+    assert_eq!(pct(5, 0), 0);
+}
+
+#[test]
+fn backlog_net_is_signed() {
+    let d = StatsDelta {
+        enq_wakeup: 300,
+        enq_requeue: 200,
+        dispatches: 450,
+        ..Default::default()
+    };
+    assert_eq!(d.backlog_net(), 50);
+    // DIRECT DISPATCHES WITHOUT AN ENQUEUE: NEGATIVE, NOT A ~2^64 WRAP
+    let d = StatsDelta {
+        enq_wakeup: 10,
+        dispatches: 1_000,
+        ..Default::default()
+    };
+    assert_eq!(d.backlog_net(), -990);
+}
+
+#[test]
+fn backlog_net_across_counter_wrap() {
+    let prev = PandemoniumStats {
+        nr_dispatches: u64::MAX - 9,
+        nr_enq_wakeup: u64::MAX - 99,
+        nr_enq_requeue: 7,
+        ..Default::default()
+    };
+    let cur = PandemoniumStats {
+        nr_dispatches: 10,
+        nr_enq_wakeup: 100,
+        nr_enq_requeue: 17,
+        ..Default::default()
+    };
+    let d = cur.delta(&prev);
+    assert!(!d.reset);
+    // 200 WAKE + 10 REQUEUE ENQUEUES AGAINST 20 DISPATCHES
+    assert_eq!(d.backlog_net(), 190);
+}
+
+#[test]
+fn backlog_net_zero_on_reset() {
+    let prev = PandemoniumStats {
+        nr_dispatches: 5_000_000,
+        nr_enq_wakeup: 9_000_000,
+        ..Default::default()
+    };
+    let d = snapshot(1_200, 0).delta(&prev);
+    assert!(d.reset);
+    assert_eq!(d.backlog_net(), 0);
+}
+
+// RESET DETECTION: NORMAL, WRAPPED AND RESTARTED SEQUENCES
+
+#[test]
+fn normal_sequence_is_not_a_reset() {
+    let ticks = [snapshot(0, 0), snapshot(900, 300), snapshot(2_000, 1_000)];
+    for w in ticks.windows(2) {
+        let d = StatsDelta::from(&w[0], &w[1]);
+        assert!(!d.reset);
+        assert!(d.dispatches > 0);
+    }
+}
+
+#[test]
+fn wrapped_counter_is_not_a_reset() {
+    let prev = snapshot(u64::MAX - 99, u64::MAX - 9);
+    let cur = snapshot(100, 10);
+    let d = StatsDelta::from(&prev, &cur);
+    assert!(!d.reset);
+    assert_eq!(d.dispatches, 200);
+    assert_eq!(d.idle_hits, 20);
+}
+
+#[test]
+fn restart_clamps_to_zero_and_flags() {
+    // BPF RELOADED: COUNTERS FALL BACK TOWARDS ZERO
+    let prev = snapshot(5_000_000, 4_000_000);
+    let cur = snapshot(1_200, 1_000);
+    let d = StatsDelta::from(&prev, &cur);
+    assert!(d.reset);
+    assert_eq!(
+        d,
+        StatsDelta {
+            reset: true,
+            ..Default::default()
+        }
+    );
+    // idle_pct CANNOT BE POISONED BY A ~2^64 DELTA
+    assert_eq!(d.idle_pct(), 0);
+
+    // THE FOLLOWING TICK IS MEASURED FROM THE NEW BASELINE
+    let next = StatsDelta::from(&cur, &snapshot(2_200, 1_500));
+    assert!(!next.reset);
+    assert_eq!(next.dispatches, 1_000);
+    assert_eq!(next.idle_pct(), 50);
+}
+
+#[test]
+fn any_regressed_counter_is_a_reset() {
+    let prev = PandemoniumStats {
+        nr_dispatches: 10,
+        nr_l2_miss_lat_crit: 50,
+        ..Default::default()
+    };
+    let cur = PandemoniumStats {
+        nr_dispatches: 20,
+        nr_l2_miss_lat_crit: 3,
+        ..Default::default()
+    };
+    assert!(StatsDelta::from(&prev, &cur).reset);
+}
+
+#[test]
+fn gauges_moving_down_is_not_a_reset() {
+    let prev = PandemoniumStats {
+        nr_dispatches: 10,
+        burst_mode_active: 4,
+        batch_sojourn_ns: 9_000_000,
+        wake_lat_max: 50_000,
+        longrun_mode_active: 1,
+        ..Default::default()
+    };
+    let cur = PandemoniumStats {
+        nr_dispatches: 20,
+        ..Default::default()
+    };
+    let d = StatsDelta::from(&prev, &cur);
+    assert!(!d.reset);
+    assert_eq!(d.dispatches, 10);
+}
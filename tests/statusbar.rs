@@ -0,0 +1,144 @@
+// PANDEMONIUM STATUS-BAR OUTPUT TESTS
+// TEMPLATE PARSING (FIELDS, ESCAPES, ERRORS), --format VALUES, RENDERING
+// RUNNING AND INACTIVE, WAYBAR JSON LINE
+
+use pandemonium::statusbar::{
+    format_latency, parse_format, waybar_json, BarState, BarStatus, Field, Segment, Template,
+    DEFAULT_TEMPLATE, WAYBAR_TEMPLATE,
+};
+use pandemonium::tuning::Regime;
+
+fn running() -> BarState {
+    BarState::Running(BarStatus {
+        regime: Some(Regime::Mixed),
+        pinned: false,
+        paused: true,
+        tightened: false,
+        p99_ns: 250_000,
+        wake_avg_ns: 8_400,
+        samples: 120_000,
+        dispatches: 9_876_543,
+        reserved: vec![2, 3, 8],
+        restarts: 0,
+        last_restart: None,
+    })
+}
+
+fn render(tpl: &str, state: &BarState) -> String {
+    Template::parse(tpl).unwrap().render(state)
+}
+
+#[test]
+fn template_splits_literals_and_fields() {
+    let tpl = Template::parse("{regime} {p99_us}us").unwrap();
+    assert_eq!(
+        tpl.segments(),
+        [
+            Segment::Field(Field::Regime),
+            Segment::Lit(" ".to_string()),
+            Segment::Field(Field::P99Us),
+            Segment::Lit("us".to_string()),
+        ]
+    );
+    // WHITESPACE INSIDE BRACES IS TOLERATED; EMPTY TEMPLATE IS EMPTY OUTPUT
+    assert_eq!(
+        Template::parse("{ class }").unwrap().segments(),
+        [Segment::Field(Field::Class)]
+    );
+    assert!(Template::parse("").unwrap().segments().is_empty());
+}
+
+#[test]
+fn doubled_braces_are_literals() {
+    assert_eq!(render("{{{regime}}}", &running()), "{MIXED}");
+    assert_eq!(render("}}{{", &running()), "}{");
+    assert_eq!(
+        Template::parse("a{{b").unwrap().segments(),
+        [Segment::Lit("a{b".to_string())]
+    );
+}
+
+#[test]
+fn template_errors_name_the_problem() {
+    let err = Template::parse("{regim} P99").unwrap_err();
+    assert!(err.contains("UNKNOWN FIELD {regim}"), "{}", err);
+    assert!(err.contains("p99_us"), "{}", err);
+    assert!(Template::parse("{}").unwrap_err().contains("UNKNOWN FIELD"));
+    assert!(Template::parse("P99 {p99")
+        .unwrap_err()
+        .contains("UNCLOSED"));
+    assert!(Template::parse("P99 p99}")
+        .unwrap_err()
+        .contains("UNMATCHED"));
+}
+
+#[test]
+fn format_values_need_the_custom_prefix() {
+    let tpl = parse_format("custom:{regime} {p99_us}us").unwrap();
+    assert_eq!(tpl.render(&running()), "MIXED 250us");
+    assert_eq!(parse_format("custom:").unwrap().render(&running()), "");
+    let err = parse_format("{regime}").unwrap_err();
+    assert!(err.contains("custom:"), "{}", err);
+    assert!(parse_format("custom:{nope}").is_err());
+}
+
+#[test]
+fn every_field_renders_running_and_inactive() {
+    let tpl = "{regime}|{class}|{p99}|{p99_us}|{p99_ms}|{wake_us}|{samples}|{dispatches}|{flags}|{reserved}|{restarts}";
+    assert_eq!(
+        render(tpl, &running()),
+        "MIXED|mixed|250us|250|0.2|8|120000|9876543|PAUSED|2-3,8|0"
+    );
+    let off = BarState::Inactive("NO sched_ext SCHEDULER ATTACHED".to_string());
+    assert_eq!(render(tpl, &off), "INACTIVE|inactive|-|-|-|-|-|-||-|-");
+
+    // --no-adaptive: NO CONTROL SOCKET, NO REGIME
+    let bpf_only = BarState::Running(BarStatus {
+        p99_ns: 2_000_000,
+        ..Default::default()
+    });
+    assert_eq!(render(DEFAULT_TEMPLATE, &bpf_only), "BPF-ONLY P99 2.0ms");
+    // NOTHING RESERVED
+    assert_eq!(render("{reserved}", &bpf_only), "-");
+    assert_eq!(bpf_only.class(), "bpf-only");
+}
+
+#[test]
+fn latency_switches_units_at_one_millisecond() {
+    assert_eq!(format_latency(10_000), "10us");
+    assert_eq!(format_latency(999_999), "999us");
+    assert_eq!(format_latency(1_000_000), "1.0ms");
+    assert_eq!(format_latency(20_000_000), "20.0ms");
+}
+
+#[test]
+fn waybar_line_running_and_inactive() {
+    let text = Template::parse(WAYBAR_TEMPLATE).unwrap();
+    assert_eq!(
+        waybar_json(&running(), &text),
+        concat!(
+            "{\"text\": \"P99 250us\", \"alt\": \"mixed\", \"class\": \"mixed\", ",
+            "\"tooltip\": \"PANDEMONIUM MIXED\\nPAUSED\\n",
+            "WAKEUP P99 250us / AVG 8us (120000 SAMPLES SINCE ATTACH)\\n",
+            "DISPATCHES 9876543\\nRESERVED CPUS 2-3,8\"}\n"
+        )
+    );
+
+    // KERNEL RESTARTS: ONE MORE TOOLTIP LINE, NAMING THE LATEST REASON
+    let BarState::Running(status) = running() else {
+        unreachable!()
+    };
+    let restarted = BarState::Running(BarStatus {
+        restarts: 2,
+        last_restart: Some("cpu 3 going offline".to_string()),
+        ..status
+    });
+    assert!(waybar_json(&restarted, &text)
+        .contains("RESERVED CPUS 2-3,8\\nKERNEL RESTARTS 2 (LAST: cpu 3 going offline)\"}"));
+
+    let off = BarState::Inactive("scx_lavd IS ATTACHED".to_string());
+    let line = waybar_json(&off, &text);
+    assert!(line.starts_with("{\"text\": \"off\", \"alt\": \"inactive\", \"class\": \"inactive\""));
+    assert!(line.contains("PANDEMONIUM NOT RUNNING\\nscx_lavd IS ATTACHED"));
+    assert_eq!(line.lines().count(), 1);
+}
@@ -0,0 +1,283 @@
+// PANDEMONIUM MAP SYSCALL BUDGET TESTS
+// COUNTING THROUGH Counted, THE PER-TICK OpBudget, AND PROCDB'S CAPPED
+// DRAIN, FLUSH AND EVICTION DELETES AGAINST AN IN-MEMORY MAP LAYER
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use pandemonium::procdb::{drain_observations, ProcessDb, TaskClassEntry, TaskProfile};
+use pandemonium::procdb::{MIN_OBSERVATIONS, OBSERVE_OPS, STALE_TICKS};
+use pandemonium::syscalls::{
+    walk_cost, Counted, MapOp, MapOps, OpBudget, SyscallCounts, SyscallTally,
+};
+
+// A BPF HASH MAP STAND-IN: KEYS IN ORDER, SHARED WITH THE ProcessDb THAT OWNS A CLONE
+#[derive(Clone, Default)]
+struct FakeMap(Rc<RefCell<BTreeMap<Vec<u8>, Vec<u8>>>>);
+
+impl FakeMap {
+    fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.0.borrow().contains_key(key)
+    }
+}
+
+impl MapOps for FakeMap {
+    fn lookup(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.0.borrow().get(key).cloned()
+    }
+
+    fn update(&self, key: &[u8], value: &[u8]) -> bool {
+        self.0.borrow_mut().insert(key.to_vec(), value.to_vec());
+        true
+    }
+
+    fn delete(&self, key: &[u8]) -> bool {
+        self.0.borrow_mut().remove(key).is_some()
+    }
+
+    fn keys(&self, max: usize) -> Vec<Vec<u8>> {
+        self.0.borrow().keys().take(max).cloned().collect()
+    }
+}
+
+fn comm(i: usize) -> [u8; 16] {
+    let mut c = [0u8; 16];
+    let name = format!("task{}", i);
+    c[..name.len()].copy_from_slice(name.as_bytes());
+    c
+}
+
+fn entry_bytes(tier: u8) -> Vec<u8> {
+    let e = TaskClassEntry {
+        tier,
+        policy: 0,
+        pinned: 0,
+        _pad: 0,
+        uclamp_min: 0,
+        avg_runtime: 100_000,
+        runtime_dev: 0,
+        wakeup_freq: 0,
+        csw_rate: 0,
+    };
+    unsafe {
+        std::slice::from_raw_parts(
+            &e as *const TaskClassEntry as *const u8,
+            std::mem::size_of::<TaskClassEntry>(),
+        )
+    }
+    .to_vec()
+}
+
+fn observations(n: usize) -> FakeMap {
+    let map = FakeMap::default();
+    for i in 0..n {
+        map.update(&comm(i), &entry_bytes(0));
+    }
+    map
+}
+
+fn confident() -> TaskProfile {
+    TaskProfile {
+        tier_votes: [5, 0, 0],
+        avg_runtime_ns: 100_000,
+        observations: MIN_OBSERVATIONS,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn counted_map_records_each_call_by_kind() {
+    let map = observations(3);
+    let tally = SyscallTally::default();
+    let c = Counted::new(&map, &tally);
+
+    assert!(c.lookup(&comm(0)).is_some());
+    assert!(c.lookup(b"missing").is_none());
+    c.update(&comm(9), &entry_bytes(1));
+    c.delete(&comm(9));
+    // THREE KEYS, WALK OF 10: THE FOURTH get_next_key FINDS THE END
+    assert_eq!(c.keys(10).len(), 3);
+    // CUT AT max: NO END-OF-MAP CALL
+    assert_eq!(c.keys(2).len(), 2);
+
+    let counts = tally.take();
+    assert_eq!(
+        counts,
+        SyscallCounts {
+            lookup: 2,
+            update: 1,
+            delete: 1,
+            next_key: 6,
+        }
+    );
+    assert_eq!(counts.total(), 10);
+    assert_eq!(counts.fields()[0], ("total", 10));
+    // take() STARTS THE NEXT TICK AT ZERO
+    assert_eq!(tally.take(), SyscallCounts::default());
+
+    assert_eq!(walk_cost(0, 0), 0);
+    assert_eq!(walk_cost(0, 5), 1);
+    assert_eq!(walk_cost(5, 5), 5);
+
+    let mut sum = counts;
+    let mut more = SyscallCounts::default();
+    more.add(MapOp::Lookup, 3);
+    more.add(MapOp::NextKey, 1);
+    sum.merge(&more);
+    assert_eq!((sum.lookup, sum.next_key, sum.total()), (5, 7, 14));
+}
+
+#[test]
+fn op_budget_spends_only_what_fits() {
+    let mut b = OpBudget::new(5);
+    assert!(b.take(3));
+    assert!(!b.capped());
+    // REFUSED: NOTHING SPENT, THE TICK IS MARKED
+    assert!(!b.take(3));
+    assert_eq!(b.left(), 2);
+    assert!(b.capped());
+    assert!(b.take(2));
+    assert!(!b.take(1));
+
+    let mut b = OpBudget::new(1);
+    b.cap();
+    assert!(b.capped());
+    assert!(OpBudget::unlimited().take(u64::MAX));
+}
+
+#[test]
+fn drain_stays_within_the_budget_and_leaves_the_rest() {
+    let map = observations(100);
+    let tally = SyscallTally::default();
+    let counted = Counted::new(&map, &tally);
+
+    let mut budget = OpBudget::new(64);
+    let got = drain_observations(&counted, &mut budget);
+    // (64 - 1) / 3: THE LAST BUDGET UNIT PAYS THE LOOK-AHEAD STEP
+    assert_eq!(got.len(), 21);
+    assert!(budget.capped());
+    assert_eq!(map.len(), 79);
+    let spent = tally.take();
+    assert!(spent.total() <= 64, "{:?}", spent);
+    assert_eq!(spent.total(), 64 - budget.left());
+    assert_eq!((spent.lookup, spent.delete, spent.next_key), (21, 21, 22));
+    // DRAINED IN KEY ORDER, DECODED BACK TO THEIR comm
+    assert_eq!(got[0].0, comm(0));
+
+    // THE NEXT TICKS CATCH UP; THE LAST ONE FINDS THE END AND IS NOT capped
+    let mut ticks = 1;
+    loop {
+        let mut budget = OpBudget::new(64);
+        drain_observations(&counted, &mut budget);
+        ticks += 1;
+        if !budget.capped() {
+            break;
+        }
+    }
+    assert_eq!(map.len(), 0);
+    assert_eq!(ticks, 5);
+
+    // A BUDGET TOO SMALL FOR ONE OBSERVATION TOUCHES NOTHING BUT THE WALK
+    let map = observations(2);
+    let mut budget = OpBudget::new(OBSERVE_OPS);
+    assert!(drain_observations(&Counted::new(&map, &tally), &mut budget).is_empty());
+    assert!(budget.capped());
+    assert_eq!(map.len(), 2);
+    let mut budget = OpBudget::new(0);
+    assert!(drain_observations(&map, &mut budget).is_empty());
+    assert!(budget.capped());
+}
+
+#[test]
+fn procdb_tick_caps_flush_and_carries_dirty_profiles() {
+    let observe = observations(10);
+    let init = FakeMap::default();
+    let mut db = ProcessDb::detached(HashMap::new());
+    db.observe = Some(Box::new(observe.clone()));
+    db.init = Some(Box::new(init.clone()));
+    for i in 100..130 {
+        db.profiles.insert(comm(i), confident());
+    }
+
+    let mut budget = OpBudget::new(50);
+    db.ingest(&mut budget);
+    // 10 OBSERVATIONS: 31 OF 50 (10 x 3 + THE END OF THE WALK)
+    assert_eq!(db.ingested, 10);
+    assert_eq!(observe.len(), 0);
+    assert_eq!(budget.left(), 19);
+    assert!(!budget.capped());
+    // THE 10 NEW PROFILES ARE ONE OBSERVATION OLD: NOTHING TO FLUSH YET
+    let flush = db.flush_within(&mut budget);
+    assert_eq!(flush.written.len(), 19);
+    assert!(budget.capped());
+    assert_eq!(init.len(), 19);
+    assert_eq!(db.backlog(), 11);
+    assert_eq!(db.syscalls.take().total(), 50);
+
+    let flush = db.flush_within(&mut OpBudget::new(50));
+    assert_eq!(flush.written.len(), 11);
+    assert_eq!(db.backlog(), 0);
+    assert_eq!(init.len(), 30);
+}
+
+#[test]
+fn eviction_deletes_wait_for_budget_and_go_before_writes() {
+    let init = FakeMap::default();
+    let mut db = ProcessDb::detached(HashMap::new());
+    db.init = Some(Box::new(init.clone()));
+    for i in 0..6 {
+        db.profiles.insert(comm(i), confident());
+    }
+    db.flush_predictions();
+    assert_eq!(init.len(), 6);
+
+    // STALE: EVICTED FROM profiles AT ONCE, FROM THE INIT MAP ON A LATER FLUSH
+    for _ in 0..=STALE_TICKS {
+        db.tick();
+    }
+    assert!(db.profiles.is_empty());
+    assert_eq!(init.len(), 6);
+    assert_eq!(db.backlog(), 6);
+    db.syscalls.take();
+
+    // THE SAME comm IS BACK: ITS NEW PREDICTION WAITS BEHIND ITS OLD DELETE
+    db.profiles.insert(
+        comm(0),
+        TaskProfile {
+            last_seen_tick: db.tick,
+            ..confident()
+        },
+    );
+    let mut budget = OpBudget::new(4);
+    let flush = db.flush_within(&mut budget);
+    assert!(flush.written.is_empty());
+    assert_eq!(init.len(), 2);
+    assert_eq!(db.backlog(), 3);
+    let mut budget = OpBudget::new(4);
+    let flush = db.flush_within(&mut budget);
+    assert_eq!(flush.written.len(), 1);
+    assert!(init.contains(&comm(0)));
+    assert_eq!(init.len(), 1);
+    assert_eq!(db.backlog(), 0);
+    assert_eq!(
+        db.syscalls.take(),
+        SyscallCounts {
+            delete: 6,
+            update: 1,
+            ..SyscallCounts::default()
+        }
+    );
+
+    // DETACHED (NO INIT MAP): NOTHING TO DELETE, NO BACKLOG
+    let mut db = ProcessDb::detached(HashMap::new());
+    db.profiles.insert(comm(0), confident());
+    for _ in 0..=STALE_TICKS {
+        db.tick();
+    }
+    assert_eq!(db.backlog(), 0);
+}
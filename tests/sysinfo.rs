@@ -0,0 +1,313 @@
+// PANDEMONIUM SYSTEM HEADER TESTS
+// CPU MODEL PARSING, CORE COUNTING FROM SIBLING LISTS, sched_ext MARKERS,
+// HEADER AND report.json RENDERING, THE [CONFIG] BLOCK ON A SYNTHETIC TOPOLOGY
+
+use pandemonium::benchout::Json;
+use pandemonium::compat::{Compat, CompatReport, KfuncStatus};
+use pandemonium::l2topo::L2Topology;
+use pandemonium::sysinfo::{
+    config_keys, count_cores, parse_cpu_model, scx_markers, RunConfig, RunMode, SystemInfo,
+    CONFIG_KEYS, CONFIG_PREFIX, MODE_ADAPTIVE,
+};
+use pandemonium::tuning::{TuningKnobs, KNOB_NAMES};
+
+fn info() -> SystemInfo {
+    SystemInfo {
+        version: "1.2.3".to_string(),
+        git: Some("v1.2.3-4-gabc1234".to_string()),
+        kernel: "6.12.1-arch1-1".to_string(),
+        sched_ext: "PASS (2/2 KFUNCS CURRENT, SLICE/VTIME VIA scx_bpf_task_set_*)".to_string(),
+        cpu_model: Some("AMD Ryzen 9 7950X 16-Core Processor".to_string()),
+        cpus: 32,
+        cores: 16,
+        smt: Some(true),
+        nodes: 1,
+    }
+}
+
+// 2 NODES x 4 CORES x 2-WAY SMT, L2 PER CORE, L3 PER NODE; CPU 15 OFFLINE
+fn synthetic_config() -> RunConfig {
+    let l2: Vec<Option<String>> = (0..16u32)
+        .map(|cpu| (cpu != 15).then(|| format!("{},{}", cpu & !1, cpu | 1)))
+        .collect();
+    let l3: Vec<Option<String>> = (0..16u32)
+        .map(|cpu| (cpu != 15).then(|| if cpu < 8 { "0-7" } else { "8-15" }.to_string()))
+        .collect();
+    RunConfig {
+        kernel: "6.12.1-arch1-1".to_string(),
+        cpus_possible: 16,
+        cpus_online: (0..15).collect(),
+        nr_cpus: 16,
+        smt: Some(true),
+        nodes: 2,
+        l2_groups: L2Topology::from_shared_lists(&l2).l2_groups,
+        l3_groups: L2Topology::from_shared_lists(&l3).l2_groups,
+        governor: Some("schedutil".to_string()),
+        mode: Some(RunMode {
+            mode: MODE_ADAPTIVE,
+            lightweight: false,
+        }),
+        reserved_cpus: Some(vec![2, 3, 8]),
+        knobs: Some(TuningKnobs::default().values()),
+    }
+}
+
+fn config_value(lines: &[String], key: &str) -> Option<String> {
+    lines.iter().find_map(|l| {
+        let (k, v) = l.strip_prefix(CONFIG_PREFIX)?.trim().split_once('=')?;
+        (k == key).then(|| v.to_string())
+    })
+}
+
+fn status(name: &'static str, status: Compat) -> KfuncStatus {
+    KfuncStatus {
+        name,
+        found_as: (status != Compat::Fail).then_some(name),
+        status,
+    }
+}
+
+#[test]
+fn cpu_model_from_x86_and_arm_cpuinfo() {
+    let x86 = "processor\t: 0\nvendor_id\t: AuthenticAMD\n\
+               model name\t: AMD Ryzen 9 7950X 16-Core Processor\n\
+               processor\t: 1\nmodel name\t: AMD Ryzen 9 7950X 16-Core Processor\n";
+    assert_eq!(
+        parse_cpu_model(x86).as_deref(),
+        Some("AMD Ryzen 9 7950X 16-Core Processor")
+    );
+    let arm = "processor\t: 0\nBogoMIPS\t: 108.00\nCPU part\t: 0xd0b\n\
+               Hardware\t: BCM2835\n";
+    assert_eq!(parse_cpu_model(arm).as_deref(), Some("BCM2835"));
+    assert_eq!(parse_cpu_model("processor\t: 0\nCPU part\t: 0xd0b\n"), None);
+    assert_eq!(parse_cpu_model("model name\t:\n"), None);
+}
+
+#[test]
+fn cores_are_distinct_sibling_lists() {
+    let lists = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    // 2-WAY SMT, ONE LIST PER ONLINE CPU, BOTH SPELLINGS OF THE SAME PAIR
+    assert_eq!(count_cores(&lists(&["0,4", "1,5", "0,4", "1,5\n"])), 2);
+    assert_eq!(count_cores(&lists(&["0-1", "0,1", "2-3", "2-3"])), 2);
+    // NO SMT: EVERY CPU IS ITS OWN CORE; UNREADABLE LISTS DON'T COUNT
+    assert_eq!(count_cores(&lists(&["0", "1", "2", ""])), 3);
+    assert_eq!(count_cores(&[]), 0);
+}
+
+#[test]
+fn scx_markers_name_verdict_and_write_path() {
+    let modern = CompatReport {
+        kernel: "6.16.0".to_string(),
+        entries: vec![
+            status("scx_bpf_dsq_insert", Compat::Pass),
+            status("scx_bpf_task_set_slice", Compat::Pass),
+            status("scx_bpf_task_set_dsq_vtime", Compat::Pass),
+        ],
+    };
+    assert_eq!(
+        scx_markers(&modern),
+        "PASS (3/3 KFUNCS CURRENT, SLICE/VTIME VIA scx_bpf_task_set_*)"
+    );
+
+    let legacy = CompatReport {
+        kernel: "6.12.0".to_string(),
+        entries: vec![
+            status("scx_bpf_dsq_insert", Compat::Warn),
+            status("scx_bpf_task_set_slice", Compat::Fail),
+            status("scx_bpf_task_set_dsq_vtime", Compat::Fail),
+        ],
+    };
+    assert_eq!(
+        scx_markers(&legacy),
+        "FAIL (0/3 KFUNCS CURRENT, SLICE/VTIME VIA p->scx)"
+    );
+}
+
+#[test]
+fn header_block_lines() {
+    assert_eq!(
+        info().header_lines(),
+        [
+            "PANDEMONIUM v1.2.3 (git v1.2.3-4-gabc1234)",
+            "KERNEL:    6.12.1-arch1-1",
+            "SCHED_EXT: PASS (2/2 KFUNCS CURRENT, SLICE/VTIME VIA scx_bpf_task_set_*)",
+            "CPU:       AMD Ryzen 9 7950X 16-Core Processor",
+            "TOPOLOGY:  32 CPUS, 16 CORES, SMT ON, 1 NODE",
+        ]
+    );
+
+    // NO .git AT BUILD TIME, NO MODEL, NO smt/active, MULTI-NODE
+    let bare = SystemInfo {
+        git: None,
+        kernel: String::new(),
+        cpu_model: None,
+        smt: None,
+        nodes: 2,
+        ..info()
+    };
+    let lines = bare.header_lines();
+    assert_eq!(lines[0], "PANDEMONIUM v1.2.3 (git unknown)");
+    assert_eq!(lines[1], "KERNEL:    unknown");
+    assert_eq!(lines[3], "CPU:       unknown");
+    assert_eq!(lines[4], "TOPOLOGY:  32 CPUS, 16 CORES, SMT N/A, 2 NODES");
+}
+
+#[test]
+fn json_keeps_missing_fields_as_null() {
+    let bare = SystemInfo {
+        git: None,
+        smt: None,
+        ..info()
+    };
+    let Json::Obj(fields) = bare.json() else {
+        panic!("system json is not an object");
+    };
+    let get = |k: &str| fields.iter().find(|(n, _)| n == k).map(|(_, v)| v.clone());
+    assert_eq!(get("version"), Some(Json::str("1.2.3")));
+    assert_eq!(get("git"), Some(Json::Null));
+    assert_eq!(get("smt"), Some(Json::Null));
+    assert_eq!(get("cores"), Some(Json::Num(16.0)));
+    assert_eq!(fields[0].0, "version");
+}
+
+#[test]
+fn collect_reads_this_machine() {
+    // WHATEVER THE SANDBOX EXPOSES, collect() NEVER PANICS AND THE
+    // VERSION IS THE CRATE'S
+    let live = SystemInfo::collect();
+    assert_eq!(live.version, env!("CARGO_PKG_VERSION"));
+    assert!(live.nodes >= 1);
+    assert!(live.cores <= live.cpus);
+    assert_eq!(live.header_lines().len(), 5);
+}
+
+#[test]
+fn config_block_has_every_key() {
+    let cfg = synthetic_config();
+    let lines = cfg.config_lines();
+    let keys = config_keys();
+    assert_eq!(keys.len(), CONFIG_KEYS.len() + KNOB_NAMES.len());
+    assert_eq!(lines.len(), keys.len());
+    for (line, key) in lines.iter().zip(&keys) {
+        assert!(
+            line.starts_with(&format!("{} {}=", CONFIG_PREFIX, key)),
+            "{} (WANTED {})",
+            line,
+            key
+        );
+    }
+
+    let get = |k: &str| config_value(&lines, k);
+    assert_eq!(get("cpus_possible").as_deref(), Some("16"));
+    assert_eq!(get("cpus_online").as_deref(), Some("0-14"));
+    assert_eq!(get("smt").as_deref(), Some("on"));
+    assert_eq!(get("numa_nodes").as_deref(), Some("2"));
+    assert_eq!(
+        get("l2_groups").as_deref(),
+        Some("0-1;2-3;4-5;6-7;8-9;10-11;12-13;14-15")
+    );
+    assert_eq!(get("l3_groups").as_deref(), Some("0-7;8-15"));
+    assert_eq!(get("mode").as_deref(), Some("adaptive"));
+    assert_eq!(get("lightweight").as_deref(), Some("off"));
+    assert_eq!(get("reserved_cpus").as_deref(), Some("2-3,8"));
+    assert_eq!(
+        get("knob.slice_ns"),
+        Some(TuningKnobs::default().slice_ns.to_string())
+    );
+}
+
+#[test]
+fn config_without_a_scheduler_keeps_every_key() {
+    // `info` AND BENCH REPORTS: NO MODE, NO KNOBS, NOTHING READABLE
+    let bare = RunConfig {
+        kernel: String::new(),
+        cpus_online: Vec::new(),
+        smt: None,
+        l2_groups: Vec::new(),
+        l3_groups: Vec::new(),
+        governor: None,
+        mode: None,
+        reserved_cpus: None,
+        knobs: None,
+        ..synthetic_config()
+    };
+    let lines = bare.config_lines();
+    for key in config_keys() {
+        assert!(config_value(&lines, &key).is_some(), "MISSING {}", key);
+    }
+    for key in [
+        "kernel",
+        "cpus_online",
+        "smt",
+        "l2_groups",
+        "governor",
+        "mode",
+        "lightweight",
+        "reserved_cpus",
+    ] {
+        assert_eq!(config_value(&lines, key).as_deref(), Some("-"), "{}", key);
+    }
+    for name in KNOB_NAMES {
+        assert_eq!(
+            config_value(&lines, &format!("knob.{}", name)).as_deref(),
+            Some("-")
+        );
+    }
+}
+
+#[test]
+fn config_json_has_the_same_keys() {
+    let Json::Obj(fields) = synthetic_config().json() else {
+        panic!("config json is not an object");
+    };
+    let names: Vec<&str> = fields.iter().map(|(n, _)| n.as_str()).collect();
+    let mut want: Vec<&str> = CONFIG_KEYS.to_vec();
+    want.push("knobs");
+    assert_eq!(names, want);
+    let get = |k: &str| fields.iter().find(|(n, _)| n == k).map(|(_, v)| v.clone());
+    assert_eq!(get("numa_nodes"), Some(Json::Num(2.0)));
+    let Some(Json::Arr(l3)) = get("l3_groups") else {
+        panic!("l3_groups is not an array");
+    };
+    assert_eq!(l3.len(), 2);
+    let Some(Json::Obj(knobs)) = get("knobs") else {
+        panic!("knobs is not an object");
+    };
+    assert_eq!(
+        knobs.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(),
+        KNOB_NAMES
+    );
+
+    assert_eq!(
+        get("reserved_cpus"),
+        Some(Json::Arr(vec![
+            Json::Num(2.0),
+            Json::Num(3.0),
+            Json::Num(8.0)
+        ]))
+    );
+
+    let bare = RunConfig {
+        mode: None,
+        reserved_cpus: None,
+        knobs: None,
+        ..synthetic_config()
+    };
+    let Json::Obj(fields) = bare.json() else {
+        panic!("config json is not an object");
+    };
+    let get = |k: &str| fields.iter().find(|(n, _)| n == k).map(|(_, v)| v.clone());
+    assert_eq!(get("mode"), Some(Json::Null));
+    assert_eq!(get("lightweight"), Some(Json::Null));
+    assert_eq!(get("reserved_cpus"), Some(Json::Null));
+    assert_eq!(get("knobs"), Some(Json::Null));
+}
+
+#[test]
+fn config_collect_reads_this_machine() {
+    let live = SystemInfo::collect();
+    let cfg = RunConfig::collect(&live, Some(2), None, None);
+    assert_eq!(cfg.nr_cpus, 2);
+    assert_eq!(cfg.nodes, live.nodes);
+    assert_eq!(cfg.config_lines().len(), config_keys().len());
+}
@@ -0,0 +1,224 @@
+// PANDEMONIUM TELEMETRY LINE TESTS
+// ONE COLUMN SET FOR THE BPF-ONLY AND ADAPTIVE LOOPS (ADAPTIVE-ONLY VALUES
+// AS `-`), OPT-IN COLUMNS, TAG FLAGS, EXISTING PARSERS STILL MATCH,
+// --lightweight DASHES AND THE LITE TAG, resid NEXT TO idle, rt AFTER IT,
+// gov LAST
+
+use pandemonium::soak::parse_p99_us;
+use pandemonium::stats::{DsqDepth, PandemoniumStats, StatsDelta};
+use pandemonium::telemetry::{telemetry_tick, AdaptiveColumns, TelemetryExtras, NO_VALUE};
+
+fn tick() -> (StatsDelta, PandemoniumStats) {
+    let prev = PandemoniumStats::default();
+    let cur = PandemoniumStats {
+        nr_dispatches: 1000,
+        nr_idle_hits: 250,
+        nr_shared: 700,
+        nr_procdb_hits: 12,
+        batch_sojourn_ns: 3_000_000,
+        ..Default::default()
+    };
+    (cur.delta(&prev), cur)
+}
+
+fn bpf_extras() -> TelemetryExtras {
+    TelemetryExtras {
+        slice_us: 1000,
+        batch_us: 20000,
+        cls_high: 3,
+        cls_low: 1,
+        qdepth: DsqDepth {
+            interactive: 2,
+            batch: 9,
+        },
+        backlog: 40,
+        residency_pct: Some(52),
+        rt_pct: Some(3),
+        ..Default::default()
+    }
+}
+
+fn adaptive_extras() -> TelemetryExtras {
+    TelemetryExtras {
+        adaptive: Some(AdaptiveColumns {
+            p99_us: 85,
+            tier_p99_us: [400, 60, 20],
+            procdb_total: 42,
+            procdb_confident: 5,
+            procdb_flushed: 1,
+            procdb_ingested: 1830,
+            io_pct: 87,
+            sojourn_thresh_ms: 5,
+            regime: "MIXED",
+            ..Default::default()
+        }),
+        ..bpf_extras()
+    }
+}
+
+const ADAPTIVE_ONLY: [&str; 7] = ["p99", "procdb", "evict", "flush", "obs", "sleep", "sjrn"];
+
+#[test]
+fn both_modes_print_the_same_columns() {
+    let (delta, stats) = tick();
+    let bpf = telemetry_tick(&delta, &stats, &bpf_extras());
+    let full = telemetry_tick(&delta, &stats, &adaptive_extras());
+    assert_eq!(bpf.names(), full.names());
+    assert_eq!(bpf.names().first(), Some(&"d/s"));
+    assert_eq!(bpf.names().last(), Some(&"gov"));
+
+    // ADAPTIVE-ONLY VALUES READ `-` IN BPF-ONLY MODE; SOJOURN KEEPS ITS
+    // MEASURED HALF, ONLY THE THRESHOLD IS ADAPTIVE
+    for name in ADAPTIVE_ONLY {
+        let v = bpf.get(name).unwrap();
+        assert!(v == NO_VALUE || v.ends_with("/-"), "{} = {}", name, v);
+        assert!(!full.get(name).unwrap().contains(NO_VALUE), "{}", name);
+    }
+    assert_eq!(bpf.get("sjrn"), Some("3ms/-"));
+    assert_eq!(full.get("sjrn"), Some("3ms/5ms"));
+
+    // EVERYTHING ELSE IS THE SAME MEASUREMENT IN BOTH
+    for name in bpf.names() {
+        if !ADAPTIVE_ONLY.contains(&name) {
+            assert_eq!(bpf.get(name), full.get(name), "{}", name);
+        }
+    }
+    assert_eq!(bpf.get("hits"), Some("12"));
+    assert_eq!(bpf.get("idle"), Some("25%"));
+    assert_eq!(bpf.get("resid"), Some("52%"));
+    assert_eq!(bpf.get("rt"), Some("3%"));
+    assert_eq!(bpf.get("qdepth"), Some("I=2 B=9"));
+}
+
+#[test]
+fn opt_in_columns_appear_the_same_way_in_both_modes() {
+    let (delta, stats) = tick();
+    let with = |mut e: TelemetryExtras| {
+        e.probe_p99_us = Some(30);
+        e.slo = Some(None);
+        telemetry_tick(&delta, &stats, &e)
+    };
+    let bpf = with(bpf_extras());
+    let full = with(adaptive_extras());
+    assert_eq!(bpf.names(), full.names());
+    let names = bpf.names();
+    let at = |n: &str| names.iter().position(|c| *c == n).unwrap();
+    assert_eq!(at("probe_p99"), at("p99") + 1);
+    assert_eq!(at("slo"), at("probe_p99") + 1);
+    assert_eq!(bpf.get("probe_p99"), Some("30us"));
+    assert_eq!(full.get("slo"), Some("-"));
+
+    let mut e = adaptive_extras();
+    e.slo = Some(Some(false));
+    assert_eq!(telemetry_tick(&delta, &stats, &e).get("slo"), Some("MISS"));
+    let plain = telemetry_tick(&delta, &stats, &bpf_extras());
+    assert_eq!(plain.get("probe_p99"), None);
+    assert_eq!(plain.get("slo"), None);
+}
+
+#[test]
+fn tag_carries_mode_and_flags() {
+    let (mut delta, mut stats) = tick();
+    assert_eq!(telemetry_tick(&delta, &stats, &bpf_extras()).tag(), "BPF");
+    delta.burst_ticks = 1;
+    stats.longrun_mode_active = 1;
+    assert_eq!(
+        telemetry_tick(&delta, &stats, &bpf_extras()).tag(),
+        "BPF BURST LONGRUN"
+    );
+
+    let mut e = adaptive_extras();
+    let a = e.adaptive.as_mut().unwrap();
+    a.pinned = true;
+    assert_eq!(
+        telemetry_tick(&delta, &stats, &e).tag(),
+        "MIXED BURST LONGRUN PINNED"
+    );
+    // PAUSED WINS OVER PINNED
+    e.adaptive.as_mut().unwrap().paused = true;
+    assert!(telemetry_tick(&delta, &stats, &e)
+        .render()
+        .ends_with(" [MIXED BURST LONGRUN PAUSED]"));
+}
+
+#[test]
+fn rendered_line_keeps_the_parsed_shape() {
+    let (delta, stats) = tick();
+    let full = telemetry_tick(&delta, &stats, &adaptive_extras()).render();
+    assert!(
+        full.starts_with("d/s: 1000     idle: 25% resid: 52% rt: 3% shared: 700    "),
+        "{}",
+        full
+    );
+    assert!(
+        full.contains(" p99: 85us [B:400 I:60 L:20] lat_idle: "),
+        "{}",
+        full
+    );
+    // tests/gate.rs: "procdb: TOTAL/CONFIDENT evict: N"
+    assert!(
+        full.contains(" procdb: 42/5 evict: 0 flush: 1 obs: 1830 hits: 12 "),
+        "{}",
+        full
+    );
+    assert!(
+        full.contains(" sleep: io=87% slice: 1000us batch: 20000us "),
+        "{}",
+        full
+    );
+    assert_eq!(parse_p99_us(&full), Some(85));
+
+    let mut e = bpf_extras();
+    e.backlog_warn = true;
+    e.residency_pct = None;
+    let bpf = telemetry_tick(&delta, &stats, &e).render();
+    assert!(bpf.contains(" p99: - lat_idle: "), "{}", bpf);
+    assert!(bpf.contains(" backlog: 40[!] "), "{}", bpf);
+    // NO IDLE BITMAP SAMPLES YET
+    assert!(
+        bpf.contains(" idle: 25% resid: - rt: 3% shared: "),
+        "{}",
+        bpf
+    );
+    e.rt_pct = None;
+    let first = telemetry_tick(&delta, &stats, &e).render();
+    assert!(first.contains(" resid: - rt: - shared: "), "{}", first);
+    // NO GOVERNOR READ: `-`, STILL ONE TOKEN BEFORE THE TAG
+    assert!(bpf.ends_with(" cls: H=3 L=1 gov: - [BPF]"), "{}", bpf);
+    e.governor = Some("powersave+performance".to_string());
+    let gov = telemetry_tick(&delta, &stats, &e).render();
+    assert!(
+        gov.ends_with(" gov: powersave+performance [BPF]"),
+        "{}",
+        gov
+    );
+    // THE SOAK CHECK NEVER READS A BPF-ONLY LINE AS A P99
+    assert_eq!(parse_p99_us(&bpf), None);
+}
+
+#[test]
+fn lightweight_dashes_wakeup_sampled_columns() {
+    let (delta, stats) = tick();
+    let lite = |mut e: TelemetryExtras| {
+        e.lightweight = true;
+        telemetry_tick(&delta, &stats, &e)
+    };
+    let full = lite(adaptive_extras());
+    let bpf = lite(bpf_extras());
+    // SAME COLUMNS, ONLY THE VALUES GO
+    assert_eq!(
+        full.names(),
+        telemetry_tick(&delta, &stats, &adaptive_extras()).names()
+    );
+    for name in ["wake", "p99", "lat_idle", "lat_kick", "obs", "sleep"] {
+        assert_eq!(full.get(name), Some(NO_VALUE), "{}", name);
+        assert_eq!(bpf.get(name), Some(NO_VALUE), "{}", name);
+    }
+    // COARSE COUNTERS AND PROCDB TABLE STATE STAY
+    assert_eq!(full.get("hits"), Some("12"));
+    assert_eq!(full.get("procdb"), Some("42/5"));
+    assert_eq!(full.get("sjrn"), Some("3ms/5ms"));
+    assert_eq!(full.tag(), "MIXED LITE");
+    assert_eq!(bpf.tag(), "BPF LITE");
+    assert_eq!(parse_p99_us(&full.render()), None);
+}
@@ -0,0 +1,194 @@
+#!/usr/bin/env python3
+"""
+bench-scale report rendering against synthetic results.
+
+Tainted phases (scheduler crashed mid-measurement): marked rows, footnote,
+N/A in VS EEVDF, and the PANDEMONIUM-only exit check. The system header
+(`pandemonium info`) opens the report when the run recorded one. Core
+counts go through the same --nr-cpus validation as the scheduler. The
+pre-flight reads `pandemonium check --json` and fails on its FAIL entries.
+Tick telemetry carries the cpufreq governor so a phase can be segmented.
+
+Usage:
+    python3 tests/test_scale_report.py
+"""
+
+import importlib.util
+import unittest
+from pathlib import Path
+
+_SPEC = importlib.util.spec_from_file_location(
+    "pandemonium_tests", Path(__file__).parent / "pandemonium-tests.py")
+pt = importlib.util.module_from_spec(_SPEC)
+_SPEC.loader.exec_module(pt)
+
+
+def phase(mean_s, vs_eevdf=None, p99=100, tainted=False) -> dict:
+    tp = {"times": [mean_s], "mean_s": mean_s, "stdev_s": 0.0}
+    if vs_eevdf is not None:
+        tp["vs_eevdf_pct"] = vs_eevdf
+    result = {
+        "throughput": tp,
+        "latency": {"samples": 500, "median_us": 50, "p99_us": p99,
+                    "worst_us": p99 * 2},
+        "telemetry": {},
+    }
+    if tainted:
+        result["tainted"] = True
+    return result
+
+
+def data(results: dict) -> dict:
+    return {"version": "test", "iterations": 1, "max_cpus": 8,
+            "results": results}
+
+
+def row(report: str, prefix: str) -> str:
+    return next(line for line in report.splitlines()
+                if line.startswith(prefix))
+
+
+def rows(report: str, prefix: str) -> list[str]:
+    return [line for line in report.splitlines() if line.startswith(prefix)]
+
+
+CLEAN = {
+    "EEVDF": phase(10.0),
+    "PANDEMONIUM (ADAPTIVE)": phase(9.0, vs_eevdf=-10.0, p99=80),
+    "scx_bpfland": phase(11.0, vs_eevdf=10.0, p99=120),
+}
+
+
+class TaintedReportTest(unittest.TestCase):
+
+    def test_clean_run_has_no_marks(self):
+        report = pt.format_report(data({"4": CLEAN, "8": CLEAN}))
+        self.assertNotIn(pt.TAINT_FOOTNOTE, report)
+        self.assertNotIn("N/A", report)
+        self.assertTrue(row(report, "PANDEMONIUM (ADAPTIVE) ")
+                        .rstrip().endswith("-10.0%"))
+        self.assertEqual(pt.tainted_pandemonium(data({"4": CLEAN})), [])
+
+    def test_tainted_row_is_marked_and_excluded(self):
+        crashed = dict(CLEAN)
+        crashed["scx_bpfland"] = phase(11.0, vs_eevdf=10.0, p99=120,
+                                       tainted=True)
+        report = pt.format_report(data({"4": CLEAN, "8": crashed}))
+
+        # PER-CORE TABLES: NAME MARKED, VS EEVDF N/A, ONE FOOTNOTE PER SECTION
+        marked = rows(report, "scx_bpfland*")
+        self.assertEqual(len(marked), 2)  # THROUGHPUT + LATENCY TABLES
+        self.assertTrue(marked[0].rstrip().endswith("N/A"))
+        # SECTION FOOTNOTE FOR 8C + SUMMARY FOOTNOTE
+        self.assertEqual(report.count(pt.TAINT_FOOTNOTE), 2)
+
+        # SUMMARY: 4C KEEPS ITS DELTA, 8C IS N/A
+        summary = report.split("THROUGHPUT VS EEVDF")[1]
+        bpfland = row(summary.split("\n", 1)[1], "scx_bpfland")
+        self.assertEqual(bpfland.split()[1:], ["+10.0%", "N/A"])
+        lat = row(report.split("LATENCY P99 (us)")[1].split("\n", 1)[1],
+                  "scx_bpfland")
+        self.assertEqual(lat.split()[1:], ["120", "120*"])
+
+        # AN EXTERNAL SCHEDULER CRASHING DOES NOT FAIL THE RUN
+        self.assertEqual(pt.tainted_pandemonium(data({"8": crashed})), [])
+
+    def test_tainted_pandemonium_fails_the_run(self):
+        crashed = dict(CLEAN)
+        crashed["PANDEMONIUM (ADAPTIVE)"] = phase(9.0, vs_eevdf=-10.0,
+                                                  tainted=True)
+        results = {"8": crashed, "4": crashed, "2": CLEAN}
+        self.assertEqual(pt.tainted_pandemonium(data(results)),
+                         ["4C PANDEMONIUM (ADAPTIVE)",
+                          "8C PANDEMONIUM (ADAPTIVE)"])
+        report = pt.format_report(data(results))
+        self.assertTrue(row(report, "PANDEMONIUM (ADAPTIVE)*")
+                        .rstrip().endswith("N/A"))
+
+    def test_single_core_count_still_footnoted(self):
+        crashed = dict(CLEAN)
+        crashed["scx_bpfland"] = phase(11.0, tainted=True)
+        report = pt.format_report(data({"4": crashed}))
+        # NO SUMMARY MATRICES WITH ONE CORE COUNT: SECTION + TRAILING NOTE
+        self.assertNotIn("THROUGHPUT VS EEVDF", report)
+        self.assertIn(pt.TAINT_FOOTNOTE, report)
+
+
+class SystemHeaderTest(unittest.TestCase):
+
+    def test_header_opens_the_report(self):
+        d = data({"4": CLEAN})
+        d["system"] = ["PANDEMONIUM v1.0.0 (git v1.0.0-3-gabc1234)",
+                       "KERNEL:    6.12.0"]
+        lines = pt.format_report(d).splitlines()
+        self.assertEqual(lines[:3], d["system"] + [""])
+
+    def test_archives_without_header_still_render(self):
+        report = pt.format_report(data({"4": CLEAN}))
+        self.assertTrue(report.startswith("PANDEMONIUM BENCH-SCALE"))
+
+
+class NrCpusTest(unittest.TestCase):
+
+    def test_zero_is_rejected(self):
+        with self.assertRaises(ValueError):
+            pt.sanitize_nr_cpus(0, 16)
+
+    def test_above_possible_is_clamped_with_a_warning(self):
+        self.assertEqual(pt.sanitize_nr_cpus(8, 16), (8, None))
+        self.assertEqual(pt.sanitize_nr_cpus(16, 16), (16, None))
+        n, warning = pt.sanitize_nr_cpus(512, 16)
+        self.assertEqual(n, 16)
+        self.assertIn("512", warning)
+
+
+
+class PreflightReportTest(unittest.TestCase):
+    REPORT = {
+        "ok": False,
+        "status": "FAIL",
+        "entries": [
+            {"section": "Tools", "name": "cargo", "status": "OK",
+             "detail": "found", "remedy": None, "hard": False},
+            {"section": "sched_ext", "name": "sysfs", "status": "FAIL",
+             "detail": "not available", "remedy": "mount the host's /sys",
+             "hard": True},
+            {"section": "BPF map memory", "name": "RLIMIT_MEMLOCK",
+             "status": "WARN", "detail": "64 KB < 1024 KB",
+             "remedy": "raised at startup", "hard": False},
+        ],
+    }
+
+    def test_only_fail_entries_are_failures(self):
+        failures = pt.preflight_failures(self.REPORT)
+        self.assertEqual(failures, [
+            "sched_ext: sysfs: not available -> mount the host's /sys"])
+
+    def test_clean_report_passes(self):
+        clean = {"ok": True, "status": "OK",
+                 "entries": self.REPORT["entries"][:1]}
+        self.assertEqual(pt.preflight_failures(clean), [])
+
+    def test_unparsable_output_fails(self):
+        self.assertEqual(len(pt.preflight_failures({"error": "sudo: no"})), 1)
+
+
+class GovernorTickTest(unittest.TestCase):
+    LINE = ("d/s: 1000     idle: 25% resid: 52% shared: 700    "
+            "cls: H=3 L=1 gov: {} [BPF]")
+
+    def test_ticks_are_counted_per_governor(self):
+        out = "\n".join(self.LINE.format(g) for g in
+                        ["powersave", "powersave", "performance+powersave",
+                         "-"])
+        ticks = pt.parse_tick_lines(out)
+        self.assertEqual(len(ticks), 4)
+        self.assertEqual(ticks[2]["governor"], "performance+powersave")
+        # NO cpufreq: NO KEY, NOT A "-" GOVERNOR
+        self.assertNotIn("governor", ticks[3])
+        self.assertEqual(pt.aggregate_ticks(ticks)["governor_counts"],
+                         {"powersave": 2, "performance+powersave": 1})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,339 @@
+// PANDEMONIUM WAKEUP + PREEMPTION VICTIM TESTS
+// EVENT RING DRAINING, THE pid -> comm CACHE (LAZY, RATE-LIMITED, LRU
+// EVICTION), PER-comm WORST / P99, PREEMPTION COUNTS + RATES, TOP-K ORDER
+
+use std::cell::Cell;
+
+use pandemonium::victims::{
+    should_dump_victims, CommCache, EventCursor, PreemptStats, SchedEvent, VictimStats,
+    VictimTable, COMM_CACHE_CAP, EVENT_PREEMPT, EVENT_RING_SLOTS, EVENT_SLOW_WAKE, EXITED_COMM,
+    PENDING_CAP, SLOW_WAKE_NS,
+};
+
+const MS: u64 = 1_000_000;
+
+fn wake(seq: u64, pid: u32, lat_ns: u64) -> SchedEvent {
+    SchedEvent {
+        seq,
+        lat_ns,
+        pid,
+        kind: EVENT_SLOW_WAKE,
+        tier: 0,
+    }
+}
+
+// pid KICKED OFF ITS CPU FOR A tier TASK
+fn preempt(seq: u64, pid: u32, tier: u16) -> SchedEvent {
+    SchedEvent {
+        seq,
+        lat_ns: 0,
+        pid,
+        kind: EVENT_PREEMPT,
+        tier,
+    }
+}
+
+// RING AS BPF LEAVES IT AFTER seqS first..=last: SLOT seq % SLOTS
+fn ring(first: u64, last: u64) -> [SchedEvent; EVENT_RING_SLOTS] {
+    let mut slots = [SchedEvent::default(); EVENT_RING_SLOTS];
+    for seq in first..=last {
+        slots[seq as usize % EVENT_RING_SLOTS] = wake(seq, seq as u32, 3 * MS);
+    }
+    slots
+}
+
+fn comm_of(pid: u32) -> Option<String> {
+    Some(format!("task{}", pid))
+}
+
+#[test]
+fn sched_event_mirrors_intf_h() {
+    let mut bytes = [0u8; 24];
+    bytes[..8].copy_from_slice(&7u64.to_ne_bytes());
+    bytes[8..16].copy_from_slice(&(5 * MS).to_ne_bytes());
+    bytes[16..20].copy_from_slice(&42u32.to_ne_bytes());
+    assert_eq!(SchedEvent::from_bytes(&bytes), Some(wake(7, 42, 5 * MS)));
+    assert_eq!(SchedEvent::from_bytes(&bytes[..16]), None);
+    // kind + tier: THE LAST 4 BYTES
+    bytes[8..16].copy_from_slice(&0u64.to_ne_bytes());
+    bytes[20..22].copy_from_slice(&EVENT_PREEMPT.to_ne_bytes());
+    bytes[22..24].copy_from_slice(&2u16.to_ne_bytes());
+    assert_eq!(SchedEvent::from_bytes(&bytes), Some(preempt(7, 42, 2)));
+    assert_eq!(SLOW_WAKE_NS, 2 * MS);
+}
+
+#[test]
+fn cursor_takes_only_new_samples_and_counts_overruns() {
+    let mut cursor = EventCursor::default();
+    let mut out = Vec::new();
+    assert_eq!(cursor.drain(0, &ring(1, 5), &mut out), 0);
+    assert_eq!(
+        out.iter().map(|w| w.seq).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+
+    // NOTHING NEW: NOTHING TAKEN
+    out.clear();
+    assert_eq!(cursor.drain(0, &ring(1, 5), &mut out), 0);
+    assert!(out.is_empty());
+
+    // 80 MORE INTO A 32-SLOT RING: THE LAST 32, OLDEST FIRST, 48 LOST
+    out.clear();
+    assert_eq!(cursor.drain(0, &ring(6, 85), &mut out), 48);
+    assert_eq!(out.len(), EVENT_RING_SLOTS);
+    assert_eq!(out[0].seq, 54);
+    assert_eq!(out[EVENT_RING_SLOTS - 1].seq, 85);
+
+    // CPUS ARE TRACKED APART
+    out.clear();
+    assert_eq!(cursor.drain(3, &ring(1, 2), &mut out), 0);
+    assert_eq!(out.len(), 2);
+}
+
+#[test]
+fn cursor_starts_over_when_the_map_is_recreated() {
+    let mut cursor = EventCursor::default();
+    let mut out = Vec::new();
+    cursor.drain(0, &ring(1, 80), &mut out);
+    out.clear();
+    assert_eq!(cursor.drain(0, &ring(1, 3), &mut out), 0);
+    assert_eq!(out.len(), 3);
+}
+
+#[test]
+fn cached_pids_cost_no_proc_read() {
+    let reads = Cell::new(0);
+    let counting = |pid: u32| {
+        reads.set(reads.get() + 1);
+        comm_of(pid)
+    };
+    let mut t = VictimTable::default();
+    t.ingest(&[
+        wake(1, 10, 3 * MS),
+        wake(2, 10, 5 * MS),
+        wake(3, 11, 4 * MS),
+    ]);
+    assert_eq!(t.pending(), 3);
+    t.resolve(32, counting);
+    assert_eq!(reads.get(), 2);
+    assert_eq!(t.pending(), 0);
+    assert_eq!(t.get("task10").unwrap().count, 2);
+
+    // SEEN BEFORE: ATTRIBUTED AT INGEST, NEVER QUEUED
+    t.ingest(&[wake(4, 10, 9 * MS)]);
+    assert_eq!(t.pending(), 0);
+    assert_eq!(t.get("task10").unwrap().count, 3);
+    assert_eq!(t.get("task10").unwrap().worst_ns, 9 * MS);
+    assert_eq!(t.reads, 2);
+    assert_eq!(t.samples, 4);
+}
+
+#[test]
+fn resolution_is_rate_limited_per_tick() {
+    let mut t = VictimTable::default();
+    let wakes: Vec<SchedEvent> = (0..10)
+        .map(|i| wake(i + 1, 100 + i as u32, 3 * MS))
+        .collect();
+    t.ingest(&wakes);
+    t.resolve(4, comm_of);
+    assert_eq!(t.reads, 4);
+    assert_eq!(t.pending(), 6);
+    t.resolve(4, comm_of);
+    t.resolve(4, comm_of);
+    assert_eq!(t.reads, 10);
+    assert_eq!(t.pending(), 0);
+    assert_eq!(t.top(100).len(), 10);
+}
+
+#[test]
+fn a_full_queue_drops_and_counts() {
+    let mut t = VictimTable::default();
+    let wakes: Vec<SchedEvent> = (0..PENDING_CAP as u64 + 5)
+        .map(|i| wake(i + 1, i as u32 + 1, 3 * MS))
+        .collect();
+    t.ingest(&wakes);
+    assert_eq!(t.pending(), PENDING_CAP);
+    assert_eq!(t.lost, 5);
+    t.note_lost(2);
+    assert_eq!(t.lost, 7);
+}
+
+#[test]
+fn exited_pids_are_attributed_and_cached() {
+    let mut t = VictimTable::default();
+    t.ingest(&[wake(1, 77, 3 * MS)]);
+    t.resolve(32, |_| None);
+    assert_eq!(t.get(EXITED_COMM).unwrap().count, 1);
+    // THE NEGATIVE ANSWER IS CACHED TOO
+    t.ingest(&[wake(2, 77, 3 * MS)]);
+    assert_eq!(t.pending(), 0);
+    assert_eq!(t.get(EXITED_COMM).unwrap().count, 2);
+}
+
+#[test]
+fn cache_evicts_least_recently_used() {
+    let mut c = CommCache::default();
+    for pid in 0..COMM_CACHE_CAP as u32 {
+        c.insert(pid, comm_of(pid));
+    }
+    assert_eq!(c.len(), COMM_CACHE_CAP);
+    // TOUCH pid 0: pid 1 IS NOW THE OLDEST
+    assert_eq!(c.get(0), Some(Some("task0")));
+    c.insert(9999, comm_of(9999));
+    assert_eq!(c.len(), COMM_CACHE_CAP);
+    assert!(c.contains(0));
+    assert!(!c.contains(1));
+    assert!(c.contains(9999));
+    // RE-INSERTING A CACHED pid EVICTS NOTHING
+    c.insert(2, comm_of(2));
+    assert_eq!(c.len(), COMM_CACHE_CAP);
+    assert!(c.contains(3));
+    assert_eq!(c.get(1), None);
+}
+
+#[test]
+fn p99_is_a_bucket_edge_capped_at_the_worst() {
+    let mut s = VictimStats::default();
+    assert_eq!(s.p99_ns(), 0);
+    s.record(3 * MS);
+    assert_eq!(s.p99_ns(), 3 * MS);
+    for _ in 0..99 {
+        s.record(5 * MS);
+    }
+    // 100 SAMPLES: THE 99TH IS IN [4,8)MS
+    assert_eq!(s.p99_ns(), 5 * MS);
+    s.record(6 * MS);
+    assert_eq!(s.p99_ns(), 6 * MS);
+    for _ in 0..5 {
+        s.record(20 * MS);
+    }
+    assert_eq!(s.p99_ns(), 20 * MS);
+    assert_eq!(s.worst_ns, 20 * MS);
+    // PAST THE TOP EDGE: THE WORST ITSELF
+    s.record(900 * MS);
+    for _ in 0..200 {
+        s.record(800 * MS);
+    }
+    assert_eq!(s.p99_ns(), 900 * MS);
+}
+
+#[test]
+fn top_k_orders_by_p99_then_worst() {
+    let mut t = VictimTable::default();
+    t.ingest(&[
+        wake(1, 1, 3 * MS),
+        wake(2, 2, 50 * MS),
+        wake(3, 3, 10 * MS),
+        wake(4, 4, 50 * MS),
+        wake(5, 4, 50 * MS),
+        wake(6, 5, 60 * MS),
+        wake(7, 6, 2 * MS + 1),
+    ]);
+    t.resolve(32, comm_of);
+    let names: Vec<&str> = t.top(3).iter().map(|(c, _)| *c).collect();
+    // task5 WORST P99; task2 / task4 TIE ON P99 + WORST, task4 HAS MORE
+    assert_eq!(names, vec!["task5", "task4", "task2"]);
+    assert_eq!(t.top(100).len(), 6);
+
+    let lines = t.format_lines(2);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        "[VICTIMS] >2ms samples=7 lost=0 pending=0 comms=6"
+    );
+    assert!(lines[1].starts_with("[VICTIMS] 1. task5"));
+    assert!(lines[1].contains("n=1 worst=60000us p99<=60000us"));
+}
+
+#[test]
+fn verbose_dump_every_thirty_ticks() {
+    assert!(!should_dump_victims(0));
+    assert!(should_dump_victims(29));
+    assert!(!should_dump_victims(30));
+    assert!(should_dump_victims(59));
+}
+
+#[test]
+fn preemptions_count_per_comm_by_tier() {
+    let mut t = VictimTable::default();
+    t.ingest(&[
+        preempt(1, 10, 2),
+        preempt(2, 10, 1),
+        preempt(3, 10, 2),
+        preempt(4, 11, 0),
+        wake(5, 10, 3 * MS),
+    ]);
+    t.resolve(32, comm_of);
+    assert_eq!(t.preempts, 4);
+    assert_eq!(t.samples, 1);
+    // THE SAME pid CAN BE A WAKEUP VICTIM TOO: SEPARATE TABLES, ONE READ
+    assert_eq!(t.reads, 2);
+    assert_eq!(
+        t.get_preempted("task10"),
+        Some(&PreemptStats {
+            count: 3,
+            by_tier: [0, 1, 2]
+        })
+    );
+    assert_eq!(t.get("task10").unwrap().count, 1);
+    assert_eq!(t.get_preempted("task11").unwrap().by_tier, [1, 0, 0]);
+
+    // CACHED NOW: ATTRIBUTED AT INGEST
+    t.ingest(&[preempt(6, 11, 0)]);
+    assert_eq!(t.pending(), 0);
+    assert_eq!(t.get_preempted("task11").unwrap().count, 2);
+}
+
+#[test]
+fn unknown_event_kinds_are_skipped() {
+    let mut t = VictimTable::default();
+    let mut future = preempt(1, 10, 1);
+    future.kind = 9;
+    // AN OUT-OF-RANGE TIER STILL COUNTS, UNDER NO TIER
+    t.ingest(&[future, preempt(2, 12, 7)]);
+    assert_eq!(t.pending(), 1);
+    t.resolve(32, comm_of);
+    assert_eq!(t.preempts, 1);
+    assert!(t.get_preempted("task10").is_none());
+    assert_eq!(
+        t.get_preempted("task12"),
+        Some(&PreemptStats {
+            count: 1,
+            by_tier: [0, 0, 0]
+        })
+    );
+}
+
+#[test]
+fn top_preempted_and_rates() {
+    let mut t = VictimTable::default();
+    let mut seq = 0;
+    let mut events = Vec::new();
+    for (pid, n) in [(1, 30), (2, 90), (3, 30), (4, 5)] {
+        for _ in 0..n {
+            seq += 1;
+            events.push(preempt(seq, pid, 1));
+        }
+    }
+    t.ingest(&events);
+    t.resolve(32, comm_of);
+    let names: Vec<&str> = t.top_preempted(3).iter().map(|(c, _)| *c).collect();
+    // TIES BY NAME
+    assert_eq!(names, vec!["task2", "task1", "task3"]);
+
+    let rates = t.preempted_rates(30);
+    assert_eq!(rates.len(), 4);
+    assert_eq!(rates[0], ("task2".to_string(), 3.0));
+    assert_eq!(rates[3], ("task4".to_string(), 5.0 / 30.0));
+    // NO RUN TIME YET: NOT A DIVISION BY ZERO
+    assert_eq!(PreemptStats::default().per_sec(0), 0.0);
+
+    let lines = t.preempted_lines(1, 30);
+    assert_eq!(
+        lines,
+        vec![
+            "[PREEMPTED] events=155 comms=4",
+            "[PREEMPTED] 1. task2            n=90 rate=3.00/s by=batch:0,interactive:90,lat_critical:0",
+        ]
+    );
+}